@@ -1,4 +1,4 @@
-use cornucopia::{conn::cornucopia_conn, CodegenSettings};
+use cornucopia::{conn::cornucopia_conn, CodegenSettings, TargetSet};
 use criterion::Criterion;
 
 fn bench(c: &mut Criterion) {
@@ -13,11 +13,11 @@ fn bench(c: &mut Criterion) {
                 client,
                 "../test_codegen/queries",
                 None,
-                CodegenSettings {
-                    gen_sync: true,
-                    gen_async: false,
-                    derive_ser: true,
-                },
+                CodegenSettings::builder()
+                    .sync(true)
+                    .async_(false)
+                    .serialize(TargetSet::ROWS | TargetSet::TYPES)
+                    .build(),
             )
             .unwrap()
         })
@@ -28,11 +28,11 @@ fn bench(c: &mut Criterion) {
                 client,
                 "../test_codegen/queries",
                 None,
-                CodegenSettings {
-                    gen_sync: true,
-                    gen_async: false,
-                    derive_ser: true,
-                },
+                CodegenSettings::builder()
+                    .sync(true)
+                    .async_(false)
+                    .serialize(TargetSet::ROWS | TargetSet::TYPES)
+                    .build(),
             )
             .unwrap()
         })
@@ -19,7 +19,7 @@ pub mod queries {
             pub name: T1,
             pub hair_color: Option<T2>,
         }
-        #[derive(Debug, Clone, PartialEq)]
+        #[derive(Debug, Clone, PartialEq, Eq, Hash, PartialOrd, Ord)]
         pub struct User {
             pub id: i32,
             pub name: String,
@@ -45,7 +45,7 @@ pub mod queries {
                 }
             }
         }
-        #[derive(Debug, Clone, PartialEq)]
+        #[derive(Debug, Clone, PartialEq, Eq, Hash, PartialOrd, Ord)]
         pub struct Post {
             pub id: i32,
             pub user_id: i32,
@@ -75,7 +75,7 @@ pub mod queries {
                 }
             }
         }
-        #[derive(Debug, Clone, PartialEq)]
+        #[derive(Debug, Clone, PartialEq, Eq, Hash, PartialOrd, Ord)]
         pub struct Comment {
             pub id: i32,
             pub post_id: i32,
@@ -95,7 +95,7 @@ pub mod queries {
                 }
             }
         }
-        #[derive(Debug, Clone, PartialEq)]
+        #[derive(Debug, Clone, PartialEq, Eq, Hash, PartialOrd, Ord)]
         pub struct SelectComplex {
             pub myuser_id: i32,
             pub name: String,
@@ -139,215 +139,300 @@ pub mod queries {
         }
         pub mod sync {
             use postgres::{fallible_iterator::FallibleIterator, GenericClient};
-            pub struct UserQuery<'a, C: GenericClient, T, const N: usize> {
+            pub struct UserQuery<'a, C: GenericClient, T, const N: usize, E = postgres::Error> {
                 client: &'a mut C,
                 params: [&'a (dyn postgres_types::ToSql + Sync); N],
                 stmt: &'a mut cornucopia_sync::private::Stmt,
-                extractor: fn(&postgres::Row) -> super::UserBorrowed,
+                extractor: fn(&postgres::Row) -> Result<super::UserBorrowed, postgres::Error>,
                 mapper: fn(super::UserBorrowed) -> T,
+                error: fn(postgres::Error) -> E,
             }
-            impl<'a, C, T: 'a, const N: usize> UserQuery<'a, C, T, N>
+            impl<'a, C, T: 'a, const N: usize, E: 'a> UserQuery<'a, C, T, N, E>
             where
                 C: GenericClient,
             {
                 pub fn map<R>(
                     self,
                     mapper: fn(super::UserBorrowed) -> R,
-                ) -> UserQuery<'a, C, R, N> {
+                ) -> UserQuery<'a, C, R, N, E> {
                     UserQuery {
                         client: self.client,
                         params: self.params,
                         stmt: self.stmt,
                         extractor: self.extractor,
                         mapper,
+                        error: self.error,
                     }
                 }
-                pub fn one(self) -> Result<T, postgres::Error> {
-                    let stmt = self.stmt.prepare(self.client)?;
-                    let row = self.client.query_one(stmt, &self.params)?;
-                    Ok((self.mapper)((self.extractor)(&row)))
+                pub fn one(self) -> Result<T, E> {
+                    let row = self
+                        .stmt
+                        .query_one(self.client, &self.params)
+                        .map_err(self.error)?;
+                    Ok((self.mapper)((self.extractor)(&row).map_err(self.error)?))
                 }
-                pub fn all(self) -> Result<Vec<T>, postgres::Error> {
-                    self.iter()?.collect()
+                pub fn opt(self) -> Result<Option<T>, E> {
+                    let row = self
+                        .stmt
+                        .query_opt(self.client, &self.params)
+                        .map_err(self.error)?;
+                    row.map(|row| Ok((self.mapper)((self.extractor)(&row).map_err(self.error)?)))
+                        .transpose()
                 }
-                pub fn opt(self) -> Result<Option<T>, postgres::Error> {
-                    let stmt = self.stmt.prepare(self.client)?;
-                    Ok(self
-                        .client
-                        .query_opt(stmt, &self.params)?
-                        .map(|row| (self.mapper)((self.extractor)(&row))))
+                pub fn all(self) -> Result<Vec<T>, E> {
+                    self.iter()?.collect()
                 }
-                pub fn iter(
-                    self,
-                ) -> Result<impl Iterator<Item = Result<T, postgres::Error>> + 'a, postgres::Error>
-                {
-                    let stmt = self.stmt.prepare(self.client)?;
+                pub fn iter(self) -> Result<impl Iterator<Item = Result<T, E>> + 'a, E> {
                     let it = self
-                        .client
-                        .query_raw(stmt, cornucopia_sync::private::slice_iter(&self.params))?
+                        .stmt
+                        .query_raw(self.client, &self.params)
+                        .map_err(self.error)?
                         .iterator()
-                        .map(move |res| res.map(|row| (self.mapper)((self.extractor)(&row))));
+                        .map(move |res| {
+                            res.and_then(|row| (self.extractor)(&row).map(self.mapper))
+                                .map_err(self.error)
+                        });
                     Ok(it)
                 }
+                #[doc = " Declares a server-side cursor over the rows, fetching them in chunks instead of"]
+                #[doc = " buffering them all. Cursors only live inside a transaction."]
+                pub fn cursor(self) -> Result<cornucopia_sync::Cursor<'a, C, T, E>, E>
+                where
+                    C: cornucopia_sync::InTransaction,
+                {
+                    let (extractor, mapper) = (self.extractor, self.mapper);
+                    self.stmt.cursor(
+                        self.client,
+                        &self.params,
+                        move |row| extractor(row).map(mapper),
+                        self.error,
+                    )
+                }
             }
-            pub struct PostQuery<'a, C: GenericClient, T, const N: usize> {
+            pub struct PostQuery<'a, C: GenericClient, T, const N: usize, E = postgres::Error> {
                 client: &'a mut C,
                 params: [&'a (dyn postgres_types::ToSql + Sync); N],
                 stmt: &'a mut cornucopia_sync::private::Stmt,
-                extractor: fn(&postgres::Row) -> super::PostBorrowed,
+                extractor: fn(&postgres::Row) -> Result<super::PostBorrowed, postgres::Error>,
                 mapper: fn(super::PostBorrowed) -> T,
+                error: fn(postgres::Error) -> E,
             }
-            impl<'a, C, T: 'a, const N: usize> PostQuery<'a, C, T, N>
+            impl<'a, C, T: 'a, const N: usize, E: 'a> PostQuery<'a, C, T, N, E>
             where
                 C: GenericClient,
             {
                 pub fn map<R>(
                     self,
                     mapper: fn(super::PostBorrowed) -> R,
-                ) -> PostQuery<'a, C, R, N> {
+                ) -> PostQuery<'a, C, R, N, E> {
                     PostQuery {
                         client: self.client,
                         params: self.params,
                         stmt: self.stmt,
                         extractor: self.extractor,
                         mapper,
+                        error: self.error,
                     }
                 }
-                pub fn one(self) -> Result<T, postgres::Error> {
-                    let stmt = self.stmt.prepare(self.client)?;
-                    let row = self.client.query_one(stmt, &self.params)?;
-                    Ok((self.mapper)((self.extractor)(&row)))
+                pub fn one(self) -> Result<T, E> {
+                    let row = self
+                        .stmt
+                        .query_one(self.client, &self.params)
+                        .map_err(self.error)?;
+                    Ok((self.mapper)((self.extractor)(&row).map_err(self.error)?))
                 }
-                pub fn all(self) -> Result<Vec<T>, postgres::Error> {
-                    self.iter()?.collect()
+                pub fn opt(self) -> Result<Option<T>, E> {
+                    let row = self
+                        .stmt
+                        .query_opt(self.client, &self.params)
+                        .map_err(self.error)?;
+                    row.map(|row| Ok((self.mapper)((self.extractor)(&row).map_err(self.error)?)))
+                        .transpose()
                 }
-                pub fn opt(self) -> Result<Option<T>, postgres::Error> {
-                    let stmt = self.stmt.prepare(self.client)?;
-                    Ok(self
-                        .client
-                        .query_opt(stmt, &self.params)?
-                        .map(|row| (self.mapper)((self.extractor)(&row))))
+                pub fn all(self) -> Result<Vec<T>, E> {
+                    self.iter()?.collect()
                 }
-                pub fn iter(
-                    self,
-                ) -> Result<impl Iterator<Item = Result<T, postgres::Error>> + 'a, postgres::Error>
-                {
-                    let stmt = self.stmt.prepare(self.client)?;
+                pub fn iter(self) -> Result<impl Iterator<Item = Result<T, E>> + 'a, E> {
                     let it = self
-                        .client
-                        .query_raw(stmt, cornucopia_sync::private::slice_iter(&self.params))?
+                        .stmt
+                        .query_raw(self.client, &self.params)
+                        .map_err(self.error)?
                         .iterator()
-                        .map(move |res| res.map(|row| (self.mapper)((self.extractor)(&row))));
+                        .map(move |res| {
+                            res.and_then(|row| (self.extractor)(&row).map(self.mapper))
+                                .map_err(self.error)
+                        });
                     Ok(it)
                 }
+                #[doc = " Declares a server-side cursor over the rows, fetching them in chunks instead of"]
+                #[doc = " buffering them all. Cursors only live inside a transaction."]
+                pub fn cursor(self) -> Result<cornucopia_sync::Cursor<'a, C, T, E>, E>
+                where
+                    C: cornucopia_sync::InTransaction,
+                {
+                    let (extractor, mapper) = (self.extractor, self.mapper);
+                    self.stmt.cursor(
+                        self.client,
+                        &self.params,
+                        move |row| extractor(row).map(mapper),
+                        self.error,
+                    )
+                }
             }
-            pub struct CommentQuery<'a, C: GenericClient, T, const N: usize> {
+            pub struct CommentQuery<'a, C: GenericClient, T, const N: usize, E = postgres::Error> {
                 client: &'a mut C,
                 params: [&'a (dyn postgres_types::ToSql + Sync); N],
                 stmt: &'a mut cornucopia_sync::private::Stmt,
-                extractor: fn(&postgres::Row) -> super::CommentBorrowed,
+                extractor: fn(&postgres::Row) -> Result<super::CommentBorrowed, postgres::Error>,
                 mapper: fn(super::CommentBorrowed) -> T,
+                error: fn(postgres::Error) -> E,
             }
-            impl<'a, C, T: 'a, const N: usize> CommentQuery<'a, C, T, N>
+            impl<'a, C, T: 'a, const N: usize, E: 'a> CommentQuery<'a, C, T, N, E>
             where
                 C: GenericClient,
             {
                 pub fn map<R>(
                     self,
                     mapper: fn(super::CommentBorrowed) -> R,
-                ) -> CommentQuery<'a, C, R, N> {
+                ) -> CommentQuery<'a, C, R, N, E> {
                     CommentQuery {
                         client: self.client,
                         params: self.params,
                         stmt: self.stmt,
                         extractor: self.extractor,
                         mapper,
+                        error: self.error,
                     }
                 }
-                pub fn one(self) -> Result<T, postgres::Error> {
-                    let stmt = self.stmt.prepare(self.client)?;
-                    let row = self.client.query_one(stmt, &self.params)?;
-                    Ok((self.mapper)((self.extractor)(&row)))
+                pub fn one(self) -> Result<T, E> {
+                    let row = self
+                        .stmt
+                        .query_one(self.client, &self.params)
+                        .map_err(self.error)?;
+                    Ok((self.mapper)((self.extractor)(&row).map_err(self.error)?))
                 }
-                pub fn all(self) -> Result<Vec<T>, postgres::Error> {
-                    self.iter()?.collect()
+                pub fn opt(self) -> Result<Option<T>, E> {
+                    let row = self
+                        .stmt
+                        .query_opt(self.client, &self.params)
+                        .map_err(self.error)?;
+                    row.map(|row| Ok((self.mapper)((self.extractor)(&row).map_err(self.error)?)))
+                        .transpose()
                 }
-                pub fn opt(self) -> Result<Option<T>, postgres::Error> {
-                    let stmt = self.stmt.prepare(self.client)?;
-                    Ok(self
-                        .client
-                        .query_opt(stmt, &self.params)?
-                        .map(|row| (self.mapper)((self.extractor)(&row))))
+                pub fn all(self) -> Result<Vec<T>, E> {
+                    self.iter()?.collect()
                 }
-                pub fn iter(
-                    self,
-                ) -> Result<impl Iterator<Item = Result<T, postgres::Error>> + 'a, postgres::Error>
-                {
-                    let stmt = self.stmt.prepare(self.client)?;
+                pub fn iter(self) -> Result<impl Iterator<Item = Result<T, E>> + 'a, E> {
                     let it = self
-                        .client
-                        .query_raw(stmt, cornucopia_sync::private::slice_iter(&self.params))?
+                        .stmt
+                        .query_raw(self.client, &self.params)
+                        .map_err(self.error)?
                         .iterator()
-                        .map(move |res| res.map(|row| (self.mapper)((self.extractor)(&row))));
+                        .map(move |res| {
+                            res.and_then(|row| (self.extractor)(&row).map(self.mapper))
+                                .map_err(self.error)
+                        });
                     Ok(it)
                 }
+                #[doc = " Declares a server-side cursor over the rows, fetching them in chunks instead of"]
+                #[doc = " buffering them all. Cursors only live inside a transaction."]
+                pub fn cursor(self) -> Result<cornucopia_sync::Cursor<'a, C, T, E>, E>
+                where
+                    C: cornucopia_sync::InTransaction,
+                {
+                    let (extractor, mapper) = (self.extractor, self.mapper);
+                    self.stmt.cursor(
+                        self.client,
+                        &self.params,
+                        move |row| extractor(row).map(mapper),
+                        self.error,
+                    )
+                }
             }
-            pub struct SelectComplexQuery<'a, C: GenericClient, T, const N: usize> {
+            pub struct SelectComplexQuery<
+                'a,
+                C: GenericClient,
+                T,
+                const N: usize,
+                E = postgres::Error,
+            > {
                 client: &'a mut C,
                 params: [&'a (dyn postgres_types::ToSql + Sync); N],
                 stmt: &'a mut cornucopia_sync::private::Stmt,
-                extractor: fn(&postgres::Row) -> super::SelectComplexBorrowed,
+                extractor:
+                    fn(&postgres::Row) -> Result<super::SelectComplexBorrowed, postgres::Error>,
                 mapper: fn(super::SelectComplexBorrowed) -> T,
+                error: fn(postgres::Error) -> E,
             }
-            impl<'a, C, T: 'a, const N: usize> SelectComplexQuery<'a, C, T, N>
+            impl<'a, C, T: 'a, const N: usize, E: 'a> SelectComplexQuery<'a, C, T, N, E>
             where
                 C: GenericClient,
             {
                 pub fn map<R>(
                     self,
                     mapper: fn(super::SelectComplexBorrowed) -> R,
-                ) -> SelectComplexQuery<'a, C, R, N> {
+                ) -> SelectComplexQuery<'a, C, R, N, E> {
                     SelectComplexQuery {
                         client: self.client,
                         params: self.params,
                         stmt: self.stmt,
                         extractor: self.extractor,
                         mapper,
+                        error: self.error,
                     }
                 }
-                pub fn one(self) -> Result<T, postgres::Error> {
-                    let stmt = self.stmt.prepare(self.client)?;
-                    let row = self.client.query_one(stmt, &self.params)?;
-                    Ok((self.mapper)((self.extractor)(&row)))
+                pub fn one(self) -> Result<T, E> {
+                    let row = self
+                        .stmt
+                        .query_one(self.client, &self.params)
+                        .map_err(self.error)?;
+                    Ok((self.mapper)((self.extractor)(&row).map_err(self.error)?))
                 }
-                pub fn all(self) -> Result<Vec<T>, postgres::Error> {
-                    self.iter()?.collect()
+                pub fn opt(self) -> Result<Option<T>, E> {
+                    let row = self
+                        .stmt
+                        .query_opt(self.client, &self.params)
+                        .map_err(self.error)?;
+                    row.map(|row| Ok((self.mapper)((self.extractor)(&row).map_err(self.error)?)))
+                        .transpose()
                 }
-                pub fn opt(self) -> Result<Option<T>, postgres::Error> {
-                    let stmt = self.stmt.prepare(self.client)?;
-                    Ok(self
-                        .client
-                        .query_opt(stmt, &self.params)?
-                        .map(|row| (self.mapper)((self.extractor)(&row))))
+                pub fn all(self) -> Result<Vec<T>, E> {
+                    self.iter()?.collect()
                 }
-                pub fn iter(
-                    self,
-                ) -> Result<impl Iterator<Item = Result<T, postgres::Error>> + 'a, postgres::Error>
-                {
-                    let stmt = self.stmt.prepare(self.client)?;
+                pub fn iter(self) -> Result<impl Iterator<Item = Result<T, E>> + 'a, E> {
                     let it = self
-                        .client
-                        .query_raw(stmt, cornucopia_sync::private::slice_iter(&self.params))?
+                        .stmt
+                        .query_raw(self.client, &self.params)
+                        .map_err(self.error)?
                         .iterator()
-                        .map(move |res| res.map(|row| (self.mapper)((self.extractor)(&row))));
+                        .map(move |res| {
+                            res.and_then(|row| (self.extractor)(&row).map(self.mapper))
+                                .map_err(self.error)
+                        });
                     Ok(it)
                 }
+                #[doc = " Declares a server-side cursor over the rows, fetching them in chunks instead of"]
+                #[doc = " buffering them all. Cursors only live inside a transaction."]
+                pub fn cursor(self) -> Result<cornucopia_sync::Cursor<'a, C, T, E>, E>
+                where
+                    C: cornucopia_sync::InTransaction,
+                {
+                    let (extractor, mapper) = (self.extractor, self.mapper);
+                    self.stmt.cursor(
+                        self.client,
+                        &self.params,
+                        move |row| extractor(row).map(mapper),
+                        self.error,
+                    )
+                }
             }
             pub fn users() -> UsersStmt {
                 UsersStmt(cornucopia_sync::private::Stmt::new("SELECT * FROM users"))
             }
             pub struct UsersStmt(cornucopia_sync::private::Stmt);
             impl UsersStmt {
+                #[doc = " Latency budget in milliseconds declared by the `budget` option of the query"]
+                pub const BUDGET_MS: Option<u64> = None;
                 pub fn bind<'a, C: GenericClient>(
                     &'a mut self,
                     client: &'a mut C,
@@ -356,12 +441,15 @@ pub mod queries {
                         client,
                         params: [],
                         stmt: &mut self.0,
-                        extractor: |row| super::UserBorrowed {
-                            id: row.get(0),
-                            name: row.get(1),
-                            hair_color: row.get(2),
+                        extractor: |row| {
+                            Ok(super::UserBorrowed {
+                                id: row.try_get(0)?,
+                                name: row.try_get(1)?,
+                                hair_color: row.try_get(2)?,
+                            })
                         },
                         mapper: |it| <super::User>::from(it),
+                        error: From::from,
                     }
                 }
             }
@@ -372,6 +460,8 @@ pub mod queries {
             }
             pub struct InsertUserStmt(cornucopia_sync::private::Stmt);
             impl InsertUserStmt {
+                #[doc = " Latency budget in milliseconds declared by the `budget` option of the query"]
+                pub const BUDGET_MS: Option<u64> = None;
                 pub fn bind<
                     'a,
                     C: GenericClient,
@@ -383,8 +473,7 @@ pub mod queries {
                     name: &'a T1,
                     hair_color: &'a Option<T2>,
                 ) -> Result<u64, postgres::Error> {
-                    let stmt = self.0.prepare(client)?;
-                    client.execute(stmt, &[name, hair_color])
+                    self.0.execute(client, &[name, hair_color])
                 }
             }
             impl<
@@ -413,6 +502,8 @@ pub mod queries {
             }
             pub struct PostsStmt(cornucopia_sync::private::Stmt);
             impl PostsStmt {
+                #[doc = " Latency budget in milliseconds declared by the `budget` option of the query"]
+                pub const BUDGET_MS: Option<u64> = None;
                 pub fn bind<'a, C: GenericClient>(
                     &'a mut self,
                     client: &'a mut C,
@@ -421,13 +512,16 @@ pub mod queries {
                         client,
                         params: [],
                         stmt: &mut self.0,
-                        extractor: |row| super::PostBorrowed {
-                            id: row.get(0),
-                            user_id: row.get(1),
-                            title: row.get(2),
-                            body: row.get(3),
+                        extractor: |row| {
+                            Ok(super::PostBorrowed {
+                                id: row.try_get(0)?,
+                                user_id: row.try_get(1)?,
+                                title: row.try_get(2)?,
+                                body: row.try_get(3)?,
+                            })
                         },
                         mapper: |it| <super::Post>::from(it),
+                        error: From::from,
                     }
                 }
             }
@@ -438,6 +532,8 @@ pub mod queries {
             }
             pub struct PostByUserIdsStmt(cornucopia_sync::private::Stmt);
             impl PostByUserIdsStmt {
+                #[doc = " Latency budget in milliseconds declared by the `budget` option of the query"]
+                pub const BUDGET_MS: Option<u64> = None;
                 pub fn bind<'a, C: GenericClient, T1: cornucopia_sync::ArraySql<Item = i32>>(
                     &'a mut self,
                     client: &'a mut C,
@@ -447,13 +543,16 @@ pub mod queries {
                         client,
                         params: [ids],
                         stmt: &mut self.0,
-                        extractor: |row| super::PostBorrowed {
-                            id: row.get(0),
-                            user_id: row.get(1),
-                            title: row.get(2),
-                            body: row.get(3),
+                        extractor: |row| {
+                            Ok(super::PostBorrowed {
+                                id: row.try_get(0)?,
+                                user_id: row.try_get(1)?,
+                                title: row.try_get(2)?,
+                                body: row.try_get(3)?,
+                            })
                         },
                         mapper: |it| <super::Post>::from(it),
+                        error: From::from,
                     }
                 }
             }
@@ -464,6 +563,8 @@ pub mod queries {
             }
             pub struct CommentsStmt(cornucopia_sync::private::Stmt);
             impl CommentsStmt {
+                #[doc = " Latency budget in milliseconds declared by the `budget` option of the query"]
+                pub const BUDGET_MS: Option<u64> = None;
                 pub fn bind<'a, C: GenericClient>(
                     &'a mut self,
                     client: &'a mut C,
@@ -472,12 +573,15 @@ pub mod queries {
                         client,
                         params: [],
                         stmt: &mut self.0,
-                        extractor: |row| super::CommentBorrowed {
-                            id: row.get(0),
-                            post_id: row.get(1),
-                            text: row.get(2),
+                        extractor: |row| {
+                            Ok(super::CommentBorrowed {
+                                id: row.try_get(0)?,
+                                post_id: row.try_get(1)?,
+                                text: row.try_get(2)?,
+                            })
                         },
                         mapper: |it| <super::Comment>::from(it),
+                        error: From::from,
                     }
                 }
             }
@@ -488,6 +592,8 @@ pub mod queries {
             }
             pub struct CommentsByPostIdStmt(cornucopia_sync::private::Stmt);
             impl CommentsByPostIdStmt {
+                #[doc = " Latency budget in milliseconds declared by the `budget` option of the query"]
+                pub const BUDGET_MS: Option<u64> = None;
                 pub fn bind<'a, C: GenericClient, T1: cornucopia_sync::ArraySql<Item = i32>>(
                     &'a mut self,
                     client: &'a mut C,
@@ -497,20 +603,25 @@ pub mod queries {
                         client,
                         params: [ids],
                         stmt: &mut self.0,
-                        extractor: |row| super::CommentBorrowed {
-                            id: row.get(0),
-                            post_id: row.get(1),
-                            text: row.get(2),
+                        extractor: |row| {
+                            Ok(super::CommentBorrowed {
+                                id: row.try_get(0)?,
+                                post_id: row.try_get(1)?,
+                                text: row.try_get(2)?,
+                            })
                         },
                         mapper: |it| <super::Comment>::from(it),
+                        error: From::from,
                     }
                 }
             }
             pub fn select_complex() -> SelectComplexStmt {
-                SelectComplexStmt(cornucopia_sync :: private :: Stmt :: new("SELECT u.id as myuser_id, u.name, u.hair_color, p.id as post_id, p.user_id, p.title, p.body FROM users as u LEFT JOIN posts as p on u.id = p.user_id"))
+                SelectComplexStmt(cornucopia_sync::private::Stmt::new("SELECT u.id as myuser_id, u.name, u.hair_color, p.id as post_id, p.user_id, p.title, p.body FROM users as u LEFT JOIN posts as p on u.id = p.user_id"))
             }
             pub struct SelectComplexStmt(cornucopia_sync::private::Stmt);
             impl SelectComplexStmt {
+                #[doc = " Latency budget in milliseconds declared by the `budget` option of the query"]
+                pub const BUDGET_MS: Option<u64> = None;
                 pub fn bind<'a, C: GenericClient>(
                     &'a mut self,
                     client: &'a mut C,
@@ -519,16 +630,19 @@ pub mod queries {
                         client,
                         params: [],
                         stmt: &mut self.0,
-                        extractor: |row| super::SelectComplexBorrowed {
-                            myuser_id: row.get(0),
-                            name: row.get(1),
-                            hair_color: row.get(2),
-                            post_id: row.get(3),
-                            user_id: row.get(4),
-                            title: row.get(5),
-                            body: row.get(6),
+                        extractor: |row| {
+                            Ok(super::SelectComplexBorrowed {
+                                myuser_id: row.try_get(0)?,
+                                name: row.try_get(1)?,
+                                hair_color: row.try_get(2)?,
+                                post_id: row.try_get(3)?,
+                                user_id: row.try_get(4)?,
+                                title: row.try_get(5)?,
+                                body: row.try_get(6)?,
+                            })
                         },
                         mapper: |it| <super::SelectComplex>::from(it),
+                        error: From::from,
                     }
                 }
             }
@@ -537,231 +651,341 @@ pub mod queries {
             use cornucopia_async::GenericClient;
             use futures;
             use futures::{StreamExt, TryStreamExt};
-            pub struct UserQuery<'a, C: GenericClient, T, const N: usize> {
+            pub struct UserQuery<'a, C: GenericClient, T, const N: usize, E = tokio_postgres::Error> {
                 client: &'a C,
                 params: [&'a (dyn postgres_types::ToSql + Sync); N],
                 stmt: &'a mut cornucopia_async::private::Stmt,
-                extractor: fn(&tokio_postgres::Row) -> super::UserBorrowed,
+                extractor:
+                    fn(&tokio_postgres::Row) -> Result<super::UserBorrowed, tokio_postgres::Error>,
                 mapper: fn(super::UserBorrowed) -> T,
+                error: fn(tokio_postgres::Error) -> E,
             }
-            impl<'a, C, T: 'a, const N: usize> UserQuery<'a, C, T, N>
+            impl<'a, C, T: 'a, const N: usize, E: 'a> UserQuery<'a, C, T, N, E>
             where
                 C: GenericClient,
             {
                 pub fn map<R>(
                     self,
                     mapper: fn(super::UserBorrowed) -> R,
-                ) -> UserQuery<'a, C, R, N> {
+                ) -> UserQuery<'a, C, R, N, E> {
                     UserQuery {
                         client: self.client,
                         params: self.params,
                         stmt: self.stmt,
                         extractor: self.extractor,
                         mapper,
+                        error: self.error,
                     }
                 }
-                pub async fn one(self) -> Result<T, tokio_postgres::Error> {
-                    let stmt = self.stmt.prepare(self.client).await?;
-                    let row = self.client.query_one(stmt, &self.params).await?;
-                    Ok((self.mapper)((self.extractor)(&row)))
+                pub async fn one(self) -> Result<T, E> {
+                    let row = self
+                        .stmt
+                        .query_one(self.client, &self.params)
+                        .await
+                        .map_err(self.error)?;
+                    Ok((self.mapper)((self.extractor)(&row).map_err(self.error)?))
                 }
-                pub async fn all(self) -> Result<Vec<T>, tokio_postgres::Error> {
-                    self.iter().await?.try_collect().await
+                pub async fn opt(self) -> Result<Option<T>, E> {
+                    let row = self
+                        .stmt
+                        .query_opt(self.client, &self.params)
+                        .await
+                        .map_err(self.error)?;
+                    row.map(|row| Ok((self.mapper)((self.extractor)(&row).map_err(self.error)?)))
+                        .transpose()
                 }
-                pub async fn opt(self) -> Result<Option<T>, tokio_postgres::Error> {
-                    let stmt = self.stmt.prepare(self.client).await?;
-                    Ok(self
-                        .client
-                        .query_opt(stmt, &self.params)
-                        .await?
-                        .map(|row| (self.mapper)((self.extractor)(&row))))
+                pub async fn all(self) -> Result<Vec<T>, E> {
+                    self.iter().await?.try_collect().await
                 }
                 pub async fn iter(
                     self,
-                ) -> Result<
-                    impl futures::Stream<Item = Result<T, tokio_postgres::Error>> + 'a,
-                    tokio_postgres::Error,
-                > {
-                    let stmt = self.stmt.prepare(self.client).await?;
+                ) -> Result<impl futures::Stream<Item = Result<T, E>> + 'a, E> {
                     let it = self
-                        .client
-                        .query_raw(stmt, cornucopia_async::private::slice_iter(&self.params))
-                        .await?
-                        .map(move |res| res.map(|row| (self.mapper)((self.extractor)(&row))))
+                        .stmt
+                        .query_raw(self.client, &self.params)
+                        .await
+                        .map_err(self.error)?
+                        .map(move |res| {
+                            res.and_then(|row| (self.extractor)(&row).map(self.mapper))
+                                .map_err(self.error)
+                        })
                         .into_stream();
                     Ok(it)
                 }
+                #[doc = " Declares a server-side cursor over the rows, fetching them in chunks instead of"]
+                #[doc = " buffering them all. Cursors only live inside a transaction."]
+                pub async fn cursor(self) -> Result<cornucopia_async::Cursor<'a, C, T, E>, E>
+                where
+                    C: cornucopia_async::InTransaction,
+                {
+                    let (extractor, mapper) = (self.extractor, self.mapper);
+                    self.stmt
+                        .cursor(
+                            self.client,
+                            &self.params,
+                            move |row| extractor(row).map(mapper),
+                            self.error,
+                        )
+                        .await
+                }
             }
-            pub struct PostQuery<'a, C: GenericClient, T, const N: usize> {
+            pub struct PostQuery<'a, C: GenericClient, T, const N: usize, E = tokio_postgres::Error> {
                 client: &'a C,
                 params: [&'a (dyn postgres_types::ToSql + Sync); N],
                 stmt: &'a mut cornucopia_async::private::Stmt,
-                extractor: fn(&tokio_postgres::Row) -> super::PostBorrowed,
+                extractor:
+                    fn(&tokio_postgres::Row) -> Result<super::PostBorrowed, tokio_postgres::Error>,
                 mapper: fn(super::PostBorrowed) -> T,
+                error: fn(tokio_postgres::Error) -> E,
             }
-            impl<'a, C, T: 'a, const N: usize> PostQuery<'a, C, T, N>
+            impl<'a, C, T: 'a, const N: usize, E: 'a> PostQuery<'a, C, T, N, E>
             where
                 C: GenericClient,
             {
                 pub fn map<R>(
                     self,
                     mapper: fn(super::PostBorrowed) -> R,
-                ) -> PostQuery<'a, C, R, N> {
+                ) -> PostQuery<'a, C, R, N, E> {
                     PostQuery {
                         client: self.client,
                         params: self.params,
                         stmt: self.stmt,
                         extractor: self.extractor,
                         mapper,
+                        error: self.error,
                     }
                 }
-                pub async fn one(self) -> Result<T, tokio_postgres::Error> {
-                    let stmt = self.stmt.prepare(self.client).await?;
-                    let row = self.client.query_one(stmt, &self.params).await?;
-                    Ok((self.mapper)((self.extractor)(&row)))
+                pub async fn one(self) -> Result<T, E> {
+                    let row = self
+                        .stmt
+                        .query_one(self.client, &self.params)
+                        .await
+                        .map_err(self.error)?;
+                    Ok((self.mapper)((self.extractor)(&row).map_err(self.error)?))
                 }
-                pub async fn all(self) -> Result<Vec<T>, tokio_postgres::Error> {
-                    self.iter().await?.try_collect().await
+                pub async fn opt(self) -> Result<Option<T>, E> {
+                    let row = self
+                        .stmt
+                        .query_opt(self.client, &self.params)
+                        .await
+                        .map_err(self.error)?;
+                    row.map(|row| Ok((self.mapper)((self.extractor)(&row).map_err(self.error)?)))
+                        .transpose()
                 }
-                pub async fn opt(self) -> Result<Option<T>, tokio_postgres::Error> {
-                    let stmt = self.stmt.prepare(self.client).await?;
-                    Ok(self
-                        .client
-                        .query_opt(stmt, &self.params)
-                        .await?
-                        .map(|row| (self.mapper)((self.extractor)(&row))))
+                pub async fn all(self) -> Result<Vec<T>, E> {
+                    self.iter().await?.try_collect().await
                 }
                 pub async fn iter(
                     self,
-                ) -> Result<
-                    impl futures::Stream<Item = Result<T, tokio_postgres::Error>> + 'a,
-                    tokio_postgres::Error,
-                > {
-                    let stmt = self.stmt.prepare(self.client).await?;
+                ) -> Result<impl futures::Stream<Item = Result<T, E>> + 'a, E> {
                     let it = self
-                        .client
-                        .query_raw(stmt, cornucopia_async::private::slice_iter(&self.params))
-                        .await?
-                        .map(move |res| res.map(|row| (self.mapper)((self.extractor)(&row))))
+                        .stmt
+                        .query_raw(self.client, &self.params)
+                        .await
+                        .map_err(self.error)?
+                        .map(move |res| {
+                            res.and_then(|row| (self.extractor)(&row).map(self.mapper))
+                                .map_err(self.error)
+                        })
                         .into_stream();
                     Ok(it)
                 }
+                #[doc = " Declares a server-side cursor over the rows, fetching them in chunks instead of"]
+                #[doc = " buffering them all. Cursors only live inside a transaction."]
+                pub async fn cursor(self) -> Result<cornucopia_async::Cursor<'a, C, T, E>, E>
+                where
+                    C: cornucopia_async::InTransaction,
+                {
+                    let (extractor, mapper) = (self.extractor, self.mapper);
+                    self.stmt
+                        .cursor(
+                            self.client,
+                            &self.params,
+                            move |row| extractor(row).map(mapper),
+                            self.error,
+                        )
+                        .await
+                }
             }
-            pub struct CommentQuery<'a, C: GenericClient, T, const N: usize> {
+            pub struct CommentQuery<
+                'a,
+                C: GenericClient,
+                T,
+                const N: usize,
+                E = tokio_postgres::Error,
+            > {
                 client: &'a C,
                 params: [&'a (dyn postgres_types::ToSql + Sync); N],
                 stmt: &'a mut cornucopia_async::private::Stmt,
-                extractor: fn(&tokio_postgres::Row) -> super::CommentBorrowed,
+                extractor: fn(
+                    &tokio_postgres::Row,
+                )
+                    -> Result<super::CommentBorrowed, tokio_postgres::Error>,
                 mapper: fn(super::CommentBorrowed) -> T,
+                error: fn(tokio_postgres::Error) -> E,
             }
-            impl<'a, C, T: 'a, const N: usize> CommentQuery<'a, C, T, N>
+            impl<'a, C, T: 'a, const N: usize, E: 'a> CommentQuery<'a, C, T, N, E>
             where
                 C: GenericClient,
             {
                 pub fn map<R>(
                     self,
                     mapper: fn(super::CommentBorrowed) -> R,
-                ) -> CommentQuery<'a, C, R, N> {
+                ) -> CommentQuery<'a, C, R, N, E> {
                     CommentQuery {
                         client: self.client,
                         params: self.params,
                         stmt: self.stmt,
                         extractor: self.extractor,
                         mapper,
+                        error: self.error,
                     }
                 }
-                pub async fn one(self) -> Result<T, tokio_postgres::Error> {
-                    let stmt = self.stmt.prepare(self.client).await?;
-                    let row = self.client.query_one(stmt, &self.params).await?;
-                    Ok((self.mapper)((self.extractor)(&row)))
+                pub async fn one(self) -> Result<T, E> {
+                    let row = self
+                        .stmt
+                        .query_one(self.client, &self.params)
+                        .await
+                        .map_err(self.error)?;
+                    Ok((self.mapper)((self.extractor)(&row).map_err(self.error)?))
                 }
-                pub async fn all(self) -> Result<Vec<T>, tokio_postgres::Error> {
-                    self.iter().await?.try_collect().await
+                pub async fn opt(self) -> Result<Option<T>, E> {
+                    let row = self
+                        .stmt
+                        .query_opt(self.client, &self.params)
+                        .await
+                        .map_err(self.error)?;
+                    row.map(|row| Ok((self.mapper)((self.extractor)(&row).map_err(self.error)?)))
+                        .transpose()
                 }
-                pub async fn opt(self) -> Result<Option<T>, tokio_postgres::Error> {
-                    let stmt = self.stmt.prepare(self.client).await?;
-                    Ok(self
-                        .client
-                        .query_opt(stmt, &self.params)
-                        .await?
-                        .map(|row| (self.mapper)((self.extractor)(&row))))
+                pub async fn all(self) -> Result<Vec<T>, E> {
+                    self.iter().await?.try_collect().await
                 }
                 pub async fn iter(
                     self,
-                ) -> Result<
-                    impl futures::Stream<Item = Result<T, tokio_postgres::Error>> + 'a,
-                    tokio_postgres::Error,
-                > {
-                    let stmt = self.stmt.prepare(self.client).await?;
+                ) -> Result<impl futures::Stream<Item = Result<T, E>> + 'a, E> {
                     let it = self
-                        .client
-                        .query_raw(stmt, cornucopia_async::private::slice_iter(&self.params))
-                        .await?
-                        .map(move |res| res.map(|row| (self.mapper)((self.extractor)(&row))))
+                        .stmt
+                        .query_raw(self.client, &self.params)
+                        .await
+                        .map_err(self.error)?
+                        .map(move |res| {
+                            res.and_then(|row| (self.extractor)(&row).map(self.mapper))
+                                .map_err(self.error)
+                        })
                         .into_stream();
                     Ok(it)
                 }
+                #[doc = " Declares a server-side cursor over the rows, fetching them in chunks instead of"]
+                #[doc = " buffering them all. Cursors only live inside a transaction."]
+                pub async fn cursor(self) -> Result<cornucopia_async::Cursor<'a, C, T, E>, E>
+                where
+                    C: cornucopia_async::InTransaction,
+                {
+                    let (extractor, mapper) = (self.extractor, self.mapper);
+                    self.stmt
+                        .cursor(
+                            self.client,
+                            &self.params,
+                            move |row| extractor(row).map(mapper),
+                            self.error,
+                        )
+                        .await
+                }
             }
-            pub struct SelectComplexQuery<'a, C: GenericClient, T, const N: usize> {
+            pub struct SelectComplexQuery<
+                'a,
+                C: GenericClient,
+                T,
+                const N: usize,
+                E = tokio_postgres::Error,
+            > {
                 client: &'a C,
                 params: [&'a (dyn postgres_types::ToSql + Sync); N],
                 stmt: &'a mut cornucopia_async::private::Stmt,
-                extractor: fn(&tokio_postgres::Row) -> super::SelectComplexBorrowed,
+                extractor: fn(
+                    &tokio_postgres::Row,
+                )
+                    -> Result<super::SelectComplexBorrowed, tokio_postgres::Error>,
                 mapper: fn(super::SelectComplexBorrowed) -> T,
+                error: fn(tokio_postgres::Error) -> E,
             }
-            impl<'a, C, T: 'a, const N: usize> SelectComplexQuery<'a, C, T, N>
+            impl<'a, C, T: 'a, const N: usize, E: 'a> SelectComplexQuery<'a, C, T, N, E>
             where
                 C: GenericClient,
             {
                 pub fn map<R>(
                     self,
                     mapper: fn(super::SelectComplexBorrowed) -> R,
-                ) -> SelectComplexQuery<'a, C, R, N> {
+                ) -> SelectComplexQuery<'a, C, R, N, E> {
                     SelectComplexQuery {
                         client: self.client,
                         params: self.params,
                         stmt: self.stmt,
                         extractor: self.extractor,
                         mapper,
+                        error: self.error,
                     }
                 }
-                pub async fn one(self) -> Result<T, tokio_postgres::Error> {
-                    let stmt = self.stmt.prepare(self.client).await?;
-                    let row = self.client.query_one(stmt, &self.params).await?;
-                    Ok((self.mapper)((self.extractor)(&row)))
+                pub async fn one(self) -> Result<T, E> {
+                    let row = self
+                        .stmt
+                        .query_one(self.client, &self.params)
+                        .await
+                        .map_err(self.error)?;
+                    Ok((self.mapper)((self.extractor)(&row).map_err(self.error)?))
                 }
-                pub async fn all(self) -> Result<Vec<T>, tokio_postgres::Error> {
-                    self.iter().await?.try_collect().await
+                pub async fn opt(self) -> Result<Option<T>, E> {
+                    let row = self
+                        .stmt
+                        .query_opt(self.client, &self.params)
+                        .await
+                        .map_err(self.error)?;
+                    row.map(|row| Ok((self.mapper)((self.extractor)(&row).map_err(self.error)?)))
+                        .transpose()
                 }
-                pub async fn opt(self) -> Result<Option<T>, tokio_postgres::Error> {
-                    let stmt = self.stmt.prepare(self.client).await?;
-                    Ok(self
-                        .client
-                        .query_opt(stmt, &self.params)
-                        .await?
-                        .map(|row| (self.mapper)((self.extractor)(&row))))
+                pub async fn all(self) -> Result<Vec<T>, E> {
+                    self.iter().await?.try_collect().await
                 }
                 pub async fn iter(
                     self,
-                ) -> Result<
-                    impl futures::Stream<Item = Result<T, tokio_postgres::Error>> + 'a,
-                    tokio_postgres::Error,
-                > {
-                    let stmt = self.stmt.prepare(self.client).await?;
+                ) -> Result<impl futures::Stream<Item = Result<T, E>> + 'a, E> {
                     let it = self
-                        .client
-                        .query_raw(stmt, cornucopia_async::private::slice_iter(&self.params))
-                        .await?
-                        .map(move |res| res.map(|row| (self.mapper)((self.extractor)(&row))))
+                        .stmt
+                        .query_raw(self.client, &self.params)
+                        .await
+                        .map_err(self.error)?
+                        .map(move |res| {
+                            res.and_then(|row| (self.extractor)(&row).map(self.mapper))
+                                .map_err(self.error)
+                        })
                         .into_stream();
                     Ok(it)
                 }
+                #[doc = " Declares a server-side cursor over the rows, fetching them in chunks instead of"]
+                #[doc = " buffering them all. Cursors only live inside a transaction."]
+                pub async fn cursor(self) -> Result<cornucopia_async::Cursor<'a, C, T, E>, E>
+                where
+                    C: cornucopia_async::InTransaction,
+                {
+                    let (extractor, mapper) = (self.extractor, self.mapper);
+                    self.stmt
+                        .cursor(
+                            self.client,
+                            &self.params,
+                            move |row| extractor(row).map(mapper),
+                            self.error,
+                        )
+                        .await
+                }
             }
             pub fn users() -> UsersStmt {
                 UsersStmt(cornucopia_async::private::Stmt::new("SELECT * FROM users"))
             }
             pub struct UsersStmt(cornucopia_async::private::Stmt);
             impl UsersStmt {
+                #[doc = " Latency budget in milliseconds declared by the `budget` option of the query"]
+                pub const BUDGET_MS: Option<u64> = None;
                 pub fn bind<'a, C: GenericClient>(
                     &'a mut self,
                     client: &'a C,
@@ -770,12 +994,15 @@ pub mod queries {
                         client,
                         params: [],
                         stmt: &mut self.0,
-                        extractor: |row| super::UserBorrowed {
-                            id: row.get(0),
-                            name: row.get(1),
-                            hair_color: row.get(2),
+                        extractor: |row| {
+                            Ok(super::UserBorrowed {
+                                id: row.try_get(0)?,
+                                name: row.try_get(1)?,
+                                hair_color: row.try_get(2)?,
+                            })
                         },
                         mapper: |it| <super::User>::from(it),
+                        error: From::from,
                     }
                 }
             }
@@ -786,6 +1013,8 @@ pub mod queries {
             }
             pub struct InsertUserStmt(cornucopia_async::private::Stmt);
             impl InsertUserStmt {
+                #[doc = " Latency budget in milliseconds declared by the `budget` option of the query"]
+                pub const BUDGET_MS: Option<u64> = None;
                 pub async fn bind<
                     'a,
                     C: GenericClient,
@@ -797,8 +1026,7 @@ pub mod queries {
                     name: &'a T1,
                     hair_color: &'a Option<T2>,
                 ) -> Result<u64, tokio_postgres::Error> {
-                    let stmt = self.0.prepare(client).await?;
-                    client.execute(stmt, &[name, hair_color]).await
+                    self.0.execute(client, &[name, hair_color]).await
                 }
             }
             impl<
@@ -839,6 +1067,8 @@ pub mod queries {
             }
             pub struct PostsStmt(cornucopia_async::private::Stmt);
             impl PostsStmt {
+                #[doc = " Latency budget in milliseconds declared by the `budget` option of the query"]
+                pub const BUDGET_MS: Option<u64> = None;
                 pub fn bind<'a, C: GenericClient>(
                     &'a mut self,
                     client: &'a C,
@@ -847,13 +1077,16 @@ pub mod queries {
                         client,
                         params: [],
                         stmt: &mut self.0,
-                        extractor: |row| super::PostBorrowed {
-                            id: row.get(0),
-                            user_id: row.get(1),
-                            title: row.get(2),
-                            body: row.get(3),
+                        extractor: |row| {
+                            Ok(super::PostBorrowed {
+                                id: row.try_get(0)?,
+                                user_id: row.try_get(1)?,
+                                title: row.try_get(2)?,
+                                body: row.try_get(3)?,
+                            })
                         },
                         mapper: |it| <super::Post>::from(it),
+                        error: From::from,
                     }
                 }
             }
@@ -864,6 +1097,8 @@ pub mod queries {
             }
             pub struct PostByUserIdsStmt(cornucopia_async::private::Stmt);
             impl PostByUserIdsStmt {
+                #[doc = " Latency budget in milliseconds declared by the `budget` option of the query"]
+                pub const BUDGET_MS: Option<u64> = None;
                 pub fn bind<'a, C: GenericClient, T1: cornucopia_async::ArraySql<Item = i32>>(
                     &'a mut self,
                     client: &'a C,
@@ -873,13 +1108,16 @@ pub mod queries {
                         client,
                         params: [ids],
                         stmt: &mut self.0,
-                        extractor: |row| super::PostBorrowed {
-                            id: row.get(0),
-                            user_id: row.get(1),
-                            title: row.get(2),
-                            body: row.get(3),
+                        extractor: |row| {
+                            Ok(super::PostBorrowed {
+                                id: row.try_get(0)?,
+                                user_id: row.try_get(1)?,
+                                title: row.try_get(2)?,
+                                body: row.try_get(3)?,
+                            })
                         },
                         mapper: |it| <super::Post>::from(it),
+                        error: From::from,
                     }
                 }
             }
@@ -890,6 +1128,8 @@ pub mod queries {
             }
             pub struct CommentsStmt(cornucopia_async::private::Stmt);
             impl CommentsStmt {
+                #[doc = " Latency budget in milliseconds declared by the `budget` option of the query"]
+                pub const BUDGET_MS: Option<u64> = None;
                 pub fn bind<'a, C: GenericClient>(
                     &'a mut self,
                     client: &'a C,
@@ -898,12 +1138,15 @@ pub mod queries {
                         client,
                         params: [],
                         stmt: &mut self.0,
-                        extractor: |row| super::CommentBorrowed {
-                            id: row.get(0),
-                            post_id: row.get(1),
-                            text: row.get(2),
+                        extractor: |row| {
+                            Ok(super::CommentBorrowed {
+                                id: row.try_get(0)?,
+                                post_id: row.try_get(1)?,
+                                text: row.try_get(2)?,
+                            })
                         },
                         mapper: |it| <super::Comment>::from(it),
+                        error: From::from,
                     }
                 }
             }
@@ -914,6 +1157,8 @@ pub mod queries {
             }
             pub struct CommentsByPostIdStmt(cornucopia_async::private::Stmt);
             impl CommentsByPostIdStmt {
+                #[doc = " Latency budget in milliseconds declared by the `budget` option of the query"]
+                pub const BUDGET_MS: Option<u64> = None;
                 pub fn bind<'a, C: GenericClient, T1: cornucopia_async::ArraySql<Item = i32>>(
                     &'a mut self,
                     client: &'a C,
@@ -923,20 +1168,25 @@ pub mod queries {
                         client,
                         params: [ids],
                         stmt: &mut self.0,
-                        extractor: |row| super::CommentBorrowed {
-                            id: row.get(0),
-                            post_id: row.get(1),
-                            text: row.get(2),
+                        extractor: |row| {
+                            Ok(super::CommentBorrowed {
+                                id: row.try_get(0)?,
+                                post_id: row.try_get(1)?,
+                                text: row.try_get(2)?,
+                            })
                         },
                         mapper: |it| <super::Comment>::from(it),
+                        error: From::from,
                     }
                 }
             }
             pub fn select_complex() -> SelectComplexStmt {
-                SelectComplexStmt(cornucopia_async :: private :: Stmt :: new("SELECT u.id as myuser_id, u.name, u.hair_color, p.id as post_id, p.user_id, p.title, p.body FROM users as u LEFT JOIN posts as p on u.id = p.user_id"))
+                SelectComplexStmt(cornucopia_async::private::Stmt::new("SELECT u.id as myuser_id, u.name, u.hair_color, p.id as post_id, p.user_id, p.title, p.body FROM users as u LEFT JOIN posts as p on u.id = p.user_id"))
             }
             pub struct SelectComplexStmt(cornucopia_async::private::Stmt);
             impl SelectComplexStmt {
+                #[doc = " Latency budget in milliseconds declared by the `budget` option of the query"]
+                pub const BUDGET_MS: Option<u64> = None;
                 pub fn bind<'a, C: GenericClient>(
                     &'a mut self,
                     client: &'a C,
@@ -945,16 +1195,19 @@ pub mod queries {
                         client,
                         params: [],
                         stmt: &mut self.0,
-                        extractor: |row| super::SelectComplexBorrowed {
-                            myuser_id: row.get(0),
-                            name: row.get(1),
-                            hair_color: row.get(2),
-                            post_id: row.get(3),
-                            user_id: row.get(4),
-                            title: row.get(5),
-                            body: row.get(6),
+                        extractor: |row| {
+                            Ok(super::SelectComplexBorrowed {
+                                myuser_id: row.try_get(0)?,
+                                name: row.try_get(1)?,
+                                hair_color: row.try_get(2)?,
+                                post_id: row.try_get(3)?,
+                                user_id: row.try_get(4)?,
+                                title: row.try_get(5)?,
+                                body: row.try_get(6)?,
+                            })
                         },
                         mapper: |it| <super::SelectComplex>::from(it),
+                        error: From::from,
                     }
                 }
             }
@@ -8,12 +8,15 @@ use tokio_postgres::Client;
 
 const NO_PARAMS: Vec<&dyn ToSql> = Vec::new();
 
+// Fields are only written, mapping rows is what is being measured
+#[allow(dead_code)]
 pub struct User {
     pub id: i32,
     pub name: String,
     pub hair_color: Option<String>,
 }
 
+#[allow(dead_code)]
 pub struct Post {
     pub id: i32,
     pub user_id: i32,
@@ -21,6 +24,7 @@ pub struct Post {
     pub body: Option<String>,
 }
 
+#[allow(dead_code)]
 pub struct Comment {
     pub id: i32,
     pub post_id: i32,
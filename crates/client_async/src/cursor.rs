@@ -0,0 +1,58 @@
+use cornucopia_client_core::{close_cursor, fetch_cursor};
+use tokio_postgres::{Error, Row, Transaction};
+
+use crate::generic_client::GenericClient;
+
+/// Clients running inside a transaction, which cursors are bound to.
+pub trait InTransaction: GenericClient {}
+
+impl InTransaction for Transaction<'_> {}
+
+/// Extracts the value of a fetched row
+type Mapper<'a, T> = Box<dyn Fn(&Row) -> Result<T, Error> + Send + Sync + 'a>;
+
+/// Server-side cursor over the rows of a query, fetching them in chunks instead of
+/// buffering them all. It is closed at the end of its transaction, or using `close`.
+pub struct Cursor<'a, C, T, E> {
+    client: &'a C,
+    name: String,
+    mapper: Mapper<'a, T>,
+    error: fn(Error) -> E,
+}
+
+impl<'a, C: InTransaction, T, E> Cursor<'a, C, T, E> {
+    pub(crate) fn new(
+        client: &'a C,
+        name: String,
+        mapper: Mapper<'a, T>,
+        error: fn(Error) -> E,
+    ) -> Self {
+        Self {
+            client,
+            name,
+            mapper,
+            error,
+        }
+    }
+
+    /// Fetches the next `n` rows at most, returning an empty chunk once all were fetched.
+    pub async fn next_chunk(&mut self, n: usize) -> Result<Vec<T>, E> {
+        let rows = self
+            .client
+            .query(&fetch_cursor(&self.name, n), &[])
+            .await
+            .map_err(self.error)?;
+        rows.iter()
+            .map(|row| (self.mapper)(row).map_err(self.error))
+            .collect()
+    }
+
+    /// Closes the cursor before the end of its transaction.
+    pub async fn close(self) -> Result<(), E> {
+        self.client
+            .execute(&close_cursor(&self.name), &[])
+            .await
+            .map(drop)
+            .map_err(self.error)
+    }
+}
@@ -1,13 +1,21 @@
 use async_trait::async_trait;
+#[cfg(feature = "with-copy-in")]
+use bytes::Bytes;
 use deadpool_postgres::{
     Client as DeadpoolClient, ClientWrapper, Transaction as DeadpoolTransaction,
 };
+#[cfg(feature = "with-copy-in")]
+use tokio_postgres::CopyInSink;
+#[cfg(feature = "with-copy-out")]
+use tokio_postgres::CopyOutStream;
+#[cfg(feature = "with-batch")]
+use tokio_postgres::SimpleQueryMessage;
 use tokio_postgres::{
-    types::BorrowToSql, Client as PgClient, Error, RowStream, Statement, ToStatement,
-    Transaction as PgTransaction,
+    types::{BorrowToSql, Type},
+    Client as PgClient, Error, RowStream, Statement, ToStatement, Transaction as PgTransaction,
 };
 
-use crate::generic_client::GenericClient;
+use crate::{generic_client::GenericClient, InTransaction};
 
 #[async_trait]
 impl GenericClient for DeadpoolClient {
@@ -68,6 +76,59 @@ impl GenericClient for DeadpoolClient {
     {
         PgClient::query_raw(self, statement, params).await
     }
+
+    #[cfg(feature = "with-batch")]
+    async fn simple_query(&self, query: &str) -> Result<Vec<SimpleQueryMessage>, Error> {
+        PgClient::simple_query(self, query).await
+    }
+
+    async fn execute_typed(
+        &self,
+        query: &str,
+        params: &[(&(dyn tokio_postgres::types::ToSql + Sync), Type)],
+    ) -> Result<u64, Error> {
+        PgClient::execute_typed(self, query, params).await
+    }
+
+    async fn query_typed_one(
+        &self,
+        query: &str,
+        params: &[(&(dyn tokio_postgres::types::ToSql + Sync), Type)],
+    ) -> Result<tokio_postgres::Row, Error> {
+        PgClient::query_typed_one(self, query, params).await
+    }
+
+    async fn query_typed_opt(
+        &self,
+        query: &str,
+        params: &[(&(dyn tokio_postgres::types::ToSql + Sync), Type)],
+    ) -> Result<Option<tokio_postgres::Row>, Error> {
+        PgClient::query_typed_opt(self, query, params).await
+    }
+
+    async fn query_typed_raw(
+        &self,
+        query: &str,
+        params: &[(&(dyn tokio_postgres::types::ToSql + Sync), Type)],
+    ) -> Result<RowStream, Error> {
+        PgClient::query_typed_raw(self, query, params.iter().map(|(v, t)| (*v, t.clone()))).await
+    }
+
+    #[cfg(feature = "with-copy-out")]
+    async fn copy_out<T>(&self, statement: &T) -> Result<CopyOutStream, Error>
+    where
+        T: ?Sized + ToStatement + Sync + Send,
+    {
+        PgClient::copy_out(self, statement).await
+    }
+
+    #[cfg(feature = "with-copy-in")]
+    async fn copy_in<T>(&self, statement: &T) -> Result<CopyInSink<Bytes>, Error>
+    where
+        T: ?Sized + ToStatement + Sync + Send,
+    {
+        PgClient::copy_in(self, statement).await
+    }
 }
 
 #[async_trait]
@@ -129,4 +190,60 @@ impl GenericClient for DeadpoolTransaction<'_> {
     {
         PgTransaction::query_raw(self, statement, params).await
     }
+
+    #[cfg(feature = "with-batch")]
+    async fn simple_query(&self, query: &str) -> Result<Vec<SimpleQueryMessage>, Error> {
+        PgTransaction::simple_query(self, query).await
+    }
+
+    async fn execute_typed(
+        &self,
+        query: &str,
+        params: &[(&(dyn tokio_postgres::types::ToSql + Sync), Type)],
+    ) -> Result<u64, Error> {
+        PgTransaction::execute_typed(self, query, params).await
+    }
+
+    async fn query_typed_one(
+        &self,
+        query: &str,
+        params: &[(&(dyn tokio_postgres::types::ToSql + Sync), Type)],
+    ) -> Result<tokio_postgres::Row, Error> {
+        PgTransaction::query_typed_one(self, query, params).await
+    }
+
+    async fn query_typed_opt(
+        &self,
+        query: &str,
+        params: &[(&(dyn tokio_postgres::types::ToSql + Sync), Type)],
+    ) -> Result<Option<tokio_postgres::Row>, Error> {
+        PgTransaction::query_typed_opt(self, query, params).await
+    }
+
+    async fn query_typed_raw(
+        &self,
+        query: &str,
+        params: &[(&(dyn tokio_postgres::types::ToSql + Sync), Type)],
+    ) -> Result<RowStream, Error> {
+        PgTransaction::query_typed_raw(self, query, params.iter().map(|(v, t)| (*v, t.clone())))
+            .await
+    }
+
+    #[cfg(feature = "with-copy-out")]
+    async fn copy_out<T>(&self, statement: &T) -> Result<CopyOutStream, Error>
+    where
+        T: ?Sized + ToStatement + Sync + Send,
+    {
+        PgTransaction::copy_out(self, statement).await
+    }
+
+    #[cfg(feature = "with-copy-in")]
+    async fn copy_in<T>(&self, statement: &T) -> Result<CopyInSink<Bytes>, Error>
+    where
+        T: ?Sized + ToStatement + Sync + Send,
+    {
+        PgTransaction::copy_in(self, statement).await
+    }
 }
+
+impl InTransaction for DeadpoolTransaction<'_> {}
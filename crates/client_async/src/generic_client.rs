@@ -1,6 +1,15 @@
 use async_trait::async_trait;
+#[cfg(feature = "with-copy-in")]
+use bytes::Bytes;
+#[cfg(feature = "with-copy-in")]
+use tokio_postgres::CopyInSink;
+#[cfg(feature = "with-copy-out")]
+use tokio_postgres::CopyOutStream;
+#[cfg(feature = "with-batch")]
+use tokio_postgres::SimpleQueryMessage;
 use tokio_postgres::{
-    types::BorrowToSql, Client, Error, RowStream, Statement, ToStatement, Transaction,
+    types::{BorrowToSql, Type},
+    Client, Error, RowStream, Statement, ToStatement, Transaction,
 };
 
 /// Abstraction over multiple types of asynchronous clients.
@@ -46,6 +55,62 @@ pub trait GenericClient: Send + Sync {
         P: BorrowToSql,
         I: IntoIterator<Item = P> + Sync + Send,
         I::IntoIter: ExactSizeIterator;
+
+    #[cfg(feature = "with-batch")]
+    async fn simple_query(&self, query: &str) -> Result<Vec<SimpleQueryMessage>, Error>;
+
+    /// Executes `query` without preparing it, binding `params` with their explicit types.
+    ///
+    /// Defaults to a prepared [`execute`](GenericClient::execute) for clients that can't
+    /// send unnamed statements.
+    async fn execute_typed(
+        &self,
+        query: &str,
+        params: &[(&(dyn tokio_postgres::types::ToSql + Sync), Type)],
+    ) -> Result<u64, Error> {
+        self.execute(query, &untyped(params)).await
+    }
+    /// Unprepared counterpart of [`query_one`](GenericClient::query_one).
+    async fn query_typed_one(
+        &self,
+        query: &str,
+        params: &[(&(dyn tokio_postgres::types::ToSql + Sync), Type)],
+    ) -> Result<tokio_postgres::Row, Error> {
+        self.query_one(query, &untyped(params)).await
+    }
+    /// Unprepared counterpart of [`query_opt`](GenericClient::query_opt).
+    async fn query_typed_opt(
+        &self,
+        query: &str,
+        params: &[(&(dyn tokio_postgres::types::ToSql + Sync), Type)],
+    ) -> Result<Option<tokio_postgres::Row>, Error> {
+        self.query_opt(query, &untyped(params)).await
+    }
+    /// Unprepared counterpart of [`query_raw`](GenericClient::query_raw).
+    async fn query_typed_raw(
+        &self,
+        query: &str,
+        params: &[(&(dyn tokio_postgres::types::ToSql + Sync), Type)],
+    ) -> Result<RowStream, Error> {
+        self.query_raw(query, untyped(params)).await
+    }
+
+    #[cfg(feature = "with-copy-out")]
+    async fn copy_out<T>(&self, statement: &T) -> Result<CopyOutStream, Error>
+    where
+        T: ?Sized + ToStatement + Sync + Send;
+
+    #[cfg(feature = "with-copy-in")]
+    async fn copy_in<T>(&self, statement: &T) -> Result<CopyInSink<Bytes>, Error>
+    where
+        T: ?Sized + ToStatement + Sync + Send;
+}
+
+/// Drops the explicit types of unprepared parameters.
+fn untyped<'a>(
+    params: &[(&'a (dyn tokio_postgres::types::ToSql + Sync), Type)],
+) -> Vec<&'a (dyn tokio_postgres::types::ToSql + Sync)> {
+    params.iter().map(|(param, _)| *param).collect()
 }
 
 #[async_trait]
@@ -107,6 +172,59 @@ impl GenericClient for Transaction<'_> {
     {
         Transaction::query_raw(self, statement, params).await
     }
+
+    #[cfg(feature = "with-batch")]
+    async fn simple_query(&self, query: &str) -> Result<Vec<SimpleQueryMessage>, Error> {
+        Transaction::simple_query(self, query).await
+    }
+
+    async fn execute_typed(
+        &self,
+        query: &str,
+        params: &[(&(dyn tokio_postgres::types::ToSql + Sync), Type)],
+    ) -> Result<u64, Error> {
+        Transaction::execute_typed(self, query, params).await
+    }
+
+    async fn query_typed_one(
+        &self,
+        query: &str,
+        params: &[(&(dyn tokio_postgres::types::ToSql + Sync), Type)],
+    ) -> Result<tokio_postgres::Row, Error> {
+        Transaction::query_typed_one(self, query, params).await
+    }
+
+    async fn query_typed_opt(
+        &self,
+        query: &str,
+        params: &[(&(dyn tokio_postgres::types::ToSql + Sync), Type)],
+    ) -> Result<Option<tokio_postgres::Row>, Error> {
+        Transaction::query_typed_opt(self, query, params).await
+    }
+
+    async fn query_typed_raw(
+        &self,
+        query: &str,
+        params: &[(&(dyn tokio_postgres::types::ToSql + Sync), Type)],
+    ) -> Result<RowStream, Error> {
+        Transaction::query_typed_raw(self, query, params.iter().map(|(v, t)| (*v, t.clone()))).await
+    }
+
+    #[cfg(feature = "with-copy-out")]
+    async fn copy_out<T>(&self, statement: &T) -> Result<CopyOutStream, Error>
+    where
+        T: ?Sized + ToStatement + Sync + Send,
+    {
+        Transaction::copy_out(self, statement).await
+    }
+
+    #[cfg(feature = "with-copy-in")]
+    async fn copy_in<T>(&self, statement: &T) -> Result<CopyInSink<Bytes>, Error>
+    where
+        T: ?Sized + ToStatement + Sync + Send,
+    {
+        Transaction::copy_in(self, statement).await
+    }
 }
 
 #[async_trait]
@@ -168,4 +286,57 @@ impl GenericClient for Client {
     {
         Client::query_raw(self, statement, params).await
     }
+
+    #[cfg(feature = "with-batch")]
+    async fn simple_query(&self, query: &str) -> Result<Vec<SimpleQueryMessage>, Error> {
+        Client::simple_query(self, query).await
+    }
+
+    async fn execute_typed(
+        &self,
+        query: &str,
+        params: &[(&(dyn tokio_postgres::types::ToSql + Sync), Type)],
+    ) -> Result<u64, Error> {
+        Client::execute_typed(self, query, params).await
+    }
+
+    async fn query_typed_one(
+        &self,
+        query: &str,
+        params: &[(&(dyn tokio_postgres::types::ToSql + Sync), Type)],
+    ) -> Result<tokio_postgres::Row, Error> {
+        Client::query_typed_one(self, query, params).await
+    }
+
+    async fn query_typed_opt(
+        &self,
+        query: &str,
+        params: &[(&(dyn tokio_postgres::types::ToSql + Sync), Type)],
+    ) -> Result<Option<tokio_postgres::Row>, Error> {
+        Client::query_typed_opt(self, query, params).await
+    }
+
+    async fn query_typed_raw(
+        &self,
+        query: &str,
+        params: &[(&(dyn tokio_postgres::types::ToSql + Sync), Type)],
+    ) -> Result<RowStream, Error> {
+        Client::query_typed_raw(self, query, params.iter().map(|(v, t)| (*v, t.clone()))).await
+    }
+
+    #[cfg(feature = "with-copy-out")]
+    async fn copy_out<T>(&self, statement: &T) -> Result<CopyOutStream, Error>
+    where
+        T: ?Sized + ToStatement + Sync + Send,
+    {
+        Client::copy_out(self, statement).await
+    }
+
+    #[cfg(feature = "with-copy-in")]
+    async fn copy_in<T>(&self, statement: &T) -> Result<CopyInSink<Bytes>, Error>
+    where
+        T: ?Sized + ToStatement + Sync + Send,
+    {
+        Client::copy_in(self, statement).await
+    }
 }
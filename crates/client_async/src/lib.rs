@@ -1,12 +1,27 @@
 #[doc(hidden)]
 pub mod private;
 
+pub use crate::cursor::{Cursor, InTransaction};
 pub use crate::generic_client::GenericClient;
-pub use cornucopia_client_core::{ArrayIterator, ArraySql, BytesSql, IterSql, StringSql};
+pub use cornucopia_client_core::{
+    ArrayIterator, ArraySql, BatchResult, BuilderError, BytesSql, IterSql, LabelDrift, Migration,
+    StringSql,
+};
+
+/// Error of the `verify` function of a generated enum, reporting the labels of the enum
+/// that changed in the database since code generation.
+pub type EnumDrift = cornucopia_client_core::EnumDrift<tokio_postgres::Error>;
 
 #[cfg(feature = "with-serde_json-1")]
-pub use cornucopia_client_core::JsonSql;
+pub use cornucopia_client_core::{JsonSql, TypedJson};
+
+#[cfg(feature = "ranges")]
+pub use cornucopia_client_core::{Range, RangeBound};
+
+#[cfg(feature = "with-bigdecimal-0_4")]
+pub use cornucopia_client_core::BigNumeric;
 
+mod cursor;
 #[cfg(feature = "deadpool")]
 mod deadpool;
 mod generic_client;
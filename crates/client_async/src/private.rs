@@ -1,12 +1,28 @@
-pub use cornucopia_client_core::{slice_iter, Domain, DomainArray};
+#[cfg(feature = "with-bigdecimal-0_4")]
+pub use cornucopia_client_core::BigNumericSql;
+#[cfg(feature = "with-serde_json-1")]
+pub use cornucopia_client_core::TypedJsonSql;
+pub use cornucopia_client_core::{slice_iter, Domain, DomainArray, RecordFields};
 
-use crate::generic_client::GenericClient;
-use tokio_postgres::{Error, Statement};
+use crate::{generic_client::GenericClient, Cursor, InTransaction};
+#[cfg(feature = "with-batch")]
+use cornucopia_client_core::{batch_results, BatchResult};
+use cornucopia_client_core::{
+    cursor_name, declare_cursor, select_variant, typed_params, EnumDrift, LabelDrift, Variant,
+    ENUM_LABELS_QUERY, SERVER_VERSION_QUERY,
+};
+#[cfg(feature = "with-batch")]
+use tokio_postgres::SimpleQueryMessage;
+use tokio_postgres::{types::ToSql, Error, Row, RowStream, Statement};
 
-/// Cached statement
+/// Cached statement, or query executed without a named prepared statement
 pub struct Stmt {
     query: &'static str,
     cached: Option<Statement>,
+    /// OIDs of the parameters of a query that is not prepared
+    unprepared: Option<&'static [u32]>,
+    /// SQL of the query by server version, the matching one being selected on first use
+    variants: &'static [Variant],
 }
 
 impl Stmt {
@@ -15,10 +31,129 @@ impl Stmt {
         Self {
             query,
             cached: None,
+            unprepared: None,
+            variants: &[],
         }
     }
 
-    pub async fn prepare<'a, C: GenericClient>(
+    /// Statement sent along with its parameters types on each execution, without
+    /// preparing nor caching it. It is prepared instead when a parameter type is not built-in.
+    #[must_use]
+    pub fn unprepared(query: &'static str, params: &'static [u32]) -> Self {
+        Self {
+            query,
+            cached: None,
+            unprepared: Some(params),
+            variants: &[],
+        }
+    }
+
+    /// Executes the variant of the query matching the version of the server instead,
+    /// if any
+    #[must_use]
+    pub fn variants(mut self, variants: &'static [Variant]) -> Self {
+        self.variants = variants;
+        self
+    }
+
+    pub async fn execute<C: GenericClient>(
+        &mut self,
+        client: &C,
+        params: &[&(dyn ToSql + Sync)],
+    ) -> Result<u64, Error> {
+        self.select_variant(client).await?;
+        if let Some(params) = self
+            .unprepared
+            .and_then(|types| typed_params(params, types))
+        {
+            return client.execute_typed(self.query, &params).await;
+        }
+        let stmt = self.prepare(client).await?;
+        client.execute(stmt, params).await
+    }
+
+    pub async fn query_one<C: GenericClient>(
+        &mut self,
+        client: &C,
+        params: &[&(dyn ToSql + Sync)],
+    ) -> Result<Row, Error> {
+        self.select_variant(client).await?;
+        if let Some(params) = self
+            .unprepared
+            .and_then(|types| typed_params(params, types))
+        {
+            return client.query_typed_one(self.query, &params).await;
+        }
+        let stmt = self.prepare(client).await?;
+        client.query_one(stmt, params).await
+    }
+
+    pub async fn query_opt<C: GenericClient>(
+        &mut self,
+        client: &C,
+        params: &[&(dyn ToSql + Sync)],
+    ) -> Result<Option<Row>, Error> {
+        self.select_variant(client).await?;
+        if let Some(params) = self
+            .unprepared
+            .and_then(|types| typed_params(params, types))
+        {
+            return client.query_typed_opt(self.query, &params).await;
+        }
+        let stmt = self.prepare(client).await?;
+        client.query_opt(stmt, params).await
+    }
+
+    pub async fn query_raw<C: GenericClient>(
+        &mut self,
+        client: &C,
+        params: &[&(dyn ToSql + Sync)],
+    ) -> Result<RowStream, Error> {
+        self.select_variant(client).await?;
+        if let Some(params) = self
+            .unprepared
+            .and_then(|types| typed_params(params, types))
+        {
+            return client.query_typed_raw(self.query, &params).await;
+        }
+        let stmt = self.prepare(client).await?;
+        client.query_raw(stmt, slice_iter(params)).await
+    }
+
+    /// Declares a cursor over the rows of the query, mapped using `mapper`
+    pub async fn cursor<'a, C: InTransaction, T, E>(
+        &mut self,
+        client: &'a C,
+        params: &[&(dyn ToSql + Sync)],
+        mapper: impl Fn(&Row) -> Result<T, Error> + Send + Sync + 'a,
+        error: fn(Error) -> E,
+    ) -> Result<Cursor<'a, C, T, E>, E> {
+        self.select_variant(client).await.map_err(error)?;
+        let name = cursor_name();
+        let declare = declare_cursor(&name, self.query);
+        match self
+            .unprepared
+            .and_then(|types| typed_params(params, types))
+        {
+            Some(typed) => client.execute_typed(&declare, &typed).await,
+            None => client.execute(&declare, params).await,
+        }
+        .map_err(error)?;
+        Ok(Cursor::new(client, name, Box::new(mapper), error))
+    }
+
+    async fn select_variant<C: GenericClient>(&mut self, client: &C) -> Result<(), Error> {
+        if !self.variants.is_empty() {
+            let version: i32 = client.query_one(SERVER_VERSION_QUERY, &[]).await?.get(0);
+            if let Some(query) = select_variant(self.variants, version) {
+                self.query = query;
+            }
+            self.variants = &[];
+        }
+        Ok(())
+    }
+
+    async fn prepare<'a, C: GenericClient>(
         &'a mut self,
         client: &C,
     ) -> Result<&'a Statement, Error> {
@@ -30,3 +165,99 @@ impl Stmt {
         Ok(unsafe { self.cached.as_ref().unwrap_unchecked() })
     }
 }
+
+/// Executes the statements of a batch query, reporting the rows count of each command.
+#[cfg(feature = "with-batch")]
+pub async fn batch<C: GenericClient>(
+    client: &C,
+    query: &str,
+    commands: &[&str],
+) -> Result<Vec<BatchResult>, Error> {
+    let messages = client.simple_query(query).await?;
+    let rows = messages.into_iter().filter_map(|it| match it {
+        SimpleQueryMessage::CommandComplete(rows) => Some(rows),
+        _ => None,
+    });
+    Ok(batch_results(commands, rows))
+}
+
+/// Compares the labels of the enum `schema.name` of the database with the generated ones.
+pub async fn verify_enum<C: GenericClient>(
+    client: &C,
+    schema: &str,
+    name: &str,
+    labels: &[&str],
+) -> Result<(), EnumDrift<Error>> {
+    let actual: Vec<String> = client
+        .query(ENUM_LABELS_QUERY, &[&schema, &name])
+        .await
+        .map_err(EnumDrift::Db)?
+        .iter()
+        .map(|row| row.get(0))
+        .collect();
+    match LabelDrift::compare(&format!("{schema}.{name}"), labels, &actual) {
+        Some(drift) => Err(EnumDrift::Labels(drift)),
+        None => Ok(()),
+    }
+}
+
+/// Executes a query acquiring the advisory lock `key`, e.g. `SELECT pg_advisory_lock($1)`.
+pub async fn advisory_lock<C: GenericClient>(
+    client: &C,
+    query: &str,
+    key: i64,
+) -> Result<(), Error> {
+    client.execute(query, &[&key]).await.map(drop)
+}
+
+/// Executes a query trying to acquire or releasing the advisory lock `key`, e.g.
+/// `SELECT pg_try_advisory_lock($1)`, returning whether it succeeded.
+pub async fn try_advisory_lock<C: GenericClient>(
+    client: &C,
+    query: &str,
+    key: i64,
+) -> Result<bool, Error> {
+    Ok(client.query_one(query, &[&key]).await?.get(0))
+}
+
+#[cfg(feature = "with-copy-out")]
+pub use tokio::io::AsyncWrite;
+
+/// Streams the output of a `COPY ... TO STDOUT` statement into `writer`,
+/// returning the number of bytes written.
+#[cfg(feature = "with-copy-out")]
+pub async fn copy_out<C: GenericClient, W: AsyncWrite + Unpin>(
+    client: &C,
+    query: &str,
+    writer: &mut W,
+) -> Result<u64, std::io::Error> {
+    use futures_util::TryStreamExt;
+    use tokio::io::AsyncWriteExt;
+
+    let to_io = std::io::Error::other;
+    let stream = client.copy_out(query).await.map_err(to_io)?;
+    futures_util::pin_mut!(stream);
+    let mut written = 0;
+    while let Some(chunk) = stream.try_next().await.map_err(to_io)? {
+        writer.write_all(&chunk).await?;
+        written += chunk.len() as u64;
+    }
+    writer.flush().await?;
+    Ok(written)
+}
+
+#[cfg(feature = "with-copy-in")]
+pub use tokio_postgres::binary_copy::BinaryCopyInWriter;
+
+/// Starts the binary `COPY ... FROM STDIN` statement `copy`, the types of its columns
+/// being the ones of the params of the equivalent `insert` statement.
+#[cfg(feature = "with-copy-in")]
+pub async fn copy_in<C: GenericClient>(
+    client: &C,
+    insert: &str,
+    copy: &str,
+) -> Result<BinaryCopyInWriter, Error> {
+    let types = client.prepare(insert).await?.params().to_vec();
+    let sink = client.copy_in(copy).await?;
+    Ok(BinaryCopyInWriter::new(sink, &types))
+}
@@ -0,0 +1,20 @@
+/// Outcome of one of the statements of a batch query.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct BatchResult {
+    /// Keyword of the executed command, like `INSERT` or `DELETE`.
+    pub command: String,
+    /// Number of rows affected or returned by the command.
+    pub rows: u64,
+}
+
+/// Pairs the commands of a batch with the row counts reported by the database.
+pub fn batch_results(commands: &[&str], rows: impl Iterator<Item = u64>) -> Vec<BatchResult> {
+    commands
+        .iter()
+        .zip(rows)
+        .map(|(command, rows)| BatchResult {
+            command: (*command).to_string(),
+            rows,
+        })
+        .collect()
+}
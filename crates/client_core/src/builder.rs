@@ -0,0 +1,23 @@
+use std::fmt::Display;
+
+/// Error of the `build` function of a generated params builder, a required field of the
+/// params not being set.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct BuilderError {
+    /// Name of the params struct being built
+    pub params: &'static str,
+    /// Name of the field that was not set
+    pub field: &'static str,
+}
+
+impl Display for BuilderError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(
+            f,
+            "the field `{}` of `{}` was not set",
+            self.field, self.params
+        )
+    }
+}
+
+impl std::error::Error for BuilderError {}
@@ -0,0 +1,31 @@
+use std::sync::atomic::{AtomicU64, Ordering};
+
+/// Number of the next declared cursor, so that the cursors of a transaction don't collide
+static NEXT_CURSOR: AtomicU64 = AtomicU64::new(0);
+
+/// Name of a new cursor, unique in this process
+#[must_use]
+pub fn cursor_name() -> String {
+    format!(
+        "cornucopia_cursor_{}",
+        NEXT_CURSOR.fetch_add(1, Ordering::Relaxed)
+    )
+}
+
+/// Statement declaring the cursor `name` over `query`, whose params are bound as usual
+#[must_use]
+pub fn declare_cursor(name: &str, query: &str) -> String {
+    format!("DECLARE {name} NO SCROLL CURSOR FOR {query}")
+}
+
+/// Statement fetching the next `n` rows of the cursor `name`
+#[must_use]
+pub fn fetch_cursor(name: &str, n: usize) -> String {
+    format!("FETCH FORWARD {n} FROM {name}")
+}
+
+/// Statement closing the cursor `name`
+#[must_use]
+pub fn close_cursor(name: &str) -> String {
+    format!("CLOSE {name}")
+}
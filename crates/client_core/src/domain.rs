@@ -27,7 +27,7 @@ impl<T: ToSql> ToSql for Domain<T> {
     where
         Self: Sized,
     {
-        return T::accepts(escape_domain(ty));
+        T::accepts(escape_domain(ty))
     }
 
     fn to_sql_checked(
@@ -71,7 +71,7 @@ impl<'a, T: ToSql + Sync + 'a, A: ArraySql<Item = T>> ToSql for DomainArray<'a,
 pub fn escape_domain_to_sql<T: ToSql>(
     ty: &Type,
     w: &mut BytesMut,
-    iter: impl Iterator<Item = T> + ExactSizeIterator,
+    iter: impl ExactSizeIterator<Item = T>,
 ) -> Result<IsNull, Box<dyn Error + Sync + Send>> {
     let member_type = match *ty.kind() {
         Kind::Array(ref member) => escape_domain(member),
@@ -97,7 +97,7 @@ pub fn escape_domain_to_sql<T: ToSql>(
 }
 
 fn downcast(len: usize) -> Result<i32, Box<dyn Error + Sync + Send>> {
-    if len > i32::max_value() as usize {
+    if len > i32::MAX as usize {
         Err("value too large to transmit".into())
     } else {
         Ok(len as i32)
@@ -0,0 +1,80 @@
+use std::fmt::Display;
+
+/// Query reading the labels of an enum of the database, given its schema and name
+pub const ENUM_LABELS_QUERY: &str = "SELECT e.enumlabel::text FROM pg_catalog.pg_enum e \
+JOIN pg_catalog.pg_type t ON t.oid = e.enumtypid \
+JOIN pg_catalog.pg_namespace n ON n.oid = t.typnamespace \
+WHERE n.nspname = $1 AND t.typname = $2 ORDER BY e.enumsortorder";
+
+/// Labels of an enum of the database differing from the ones it was generated with.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct LabelDrift {
+    /// Name of the enum, qualified by its schema
+    pub enum_name: String,
+    /// Labels of the generated enum the database no longer has
+    pub missing: Vec<String>,
+    /// Labels of the database unknown to the generated enum, in their declaration order
+    pub extra: Vec<String>,
+}
+
+impl LabelDrift {
+    /// Compares the `generated` labels of an enum with the `actual` ones of the database,
+    /// returning `None` when they are the same.
+    #[must_use]
+    pub fn compare(enum_name: &str, generated: &[&str], actual: &[String]) -> Option<Self> {
+        let missing: Vec<_> = generated
+            .iter()
+            .filter(|label| !actual.iter().any(|it| it == *label))
+            .map(|label| (*label).to_string())
+            .collect();
+        let extra: Vec<_> = actual
+            .iter()
+            .filter(|label| !generated.contains(&label.as_str()))
+            .cloned()
+            .collect();
+        (!missing.is_empty() || !extra.is_empty()).then(|| Self {
+            enum_name: enum_name.to_string(),
+            missing,
+            extra,
+        })
+    }
+}
+
+impl Display for LabelDrift {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        let quoted =
+            |labels: &[String]| -> Vec<_> { labels.iter().map(|it| format!("`{it}`")).collect() };
+        write!(
+            f,
+            "the enum `{}` changed since code generation",
+            self.enum_name
+        )?;
+        if !self.missing.is_empty() {
+            write!(f, ", missing {}", quoted(&self.missing).join(", "))?;
+        }
+        if !self.extra.is_empty() {
+            write!(f, ", with the extra {}", quoted(&self.extra).join(", "))?;
+        }
+        Ok(())
+    }
+}
+
+/// Error of the `verify` function of a generated enum.
+#[derive(Debug)]
+pub enum EnumDrift<E> {
+    /// The labels of the enum differ from the generated ones.
+    Labels(LabelDrift),
+    /// The labels of the enum could not be read.
+    Db(E),
+}
+
+impl<E: Display> Display for EnumDrift<E> {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Self::Labels(drift) => drift.fmt(f),
+            Self::Db(err) => err.fmt(f),
+        }
+    }
+}
+
+impl<E: std::fmt::Debug + Display> std::error::Error for EnumDrift<E> {}
@@ -0,0 +1,98 @@
+use postgres_types::{private::BytesMut, FromSql, IsNull, Json, ToSql, Type};
+use serde::{Deserialize, Serialize};
+use std::{
+    error::Error,
+    fmt::{Debug, Formatter},
+    marker::PhantomData,
+};
+
+use crate::utils::escape_domain;
+
+/// A `json` or `jsonb` value of a row, deserialized into the type declared on its column
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, PartialOrd, Ord)]
+pub struct TypedJson<'a, T>(pub T, PhantomData<&'a [u8]>);
+
+impl<'a, T: Deserialize<'a>> FromSql<'a> for TypedJson<'a, T> {
+    fn from_sql(ty: &Type, raw: &'a [u8]) -> Result<Self, Box<dyn Error + Sync + Send>> {
+        Json::<T>::from_sql(ty, raw).map(|Json(it)| Self(it, PhantomData))
+    }
+
+    fn accepts(ty: &Type) -> bool {
+        <Json<T> as FromSql>::accepts(ty)
+    }
+}
+
+/// Values of `json` and `jsonb` parameters serialized from a borrowed value of a type
+/// declared on the parameter, `None` being written as `NULL` rather than as `null`
+pub trait TypedJsonParam: Debug + Sync {
+    fn json_to_sql(
+        &self,
+        ty: &Type,
+        out: &mut BytesMut,
+    ) -> Result<IsNull, Box<dyn Error + Sync + Send>>;
+}
+
+impl<T: Serialize + Debug + Sync + ?Sized> TypedJsonParam for &T {
+    fn json_to_sql(
+        &self,
+        ty: &Type,
+        out: &mut BytesMut,
+    ) -> Result<IsNull, Box<dyn Error + Sync + Send>> {
+        Json(*self).to_sql(escape_domain(ty), out)
+    }
+}
+
+impl<T: Serialize + Debug + Sync + ?Sized> TypedJsonParam for Option<&T> {
+    fn json_to_sql(
+        &self,
+        ty: &Type,
+        out: &mut BytesMut,
+    ) -> Result<IsNull, Box<dyn Error + Sync + Send>> {
+        match self {
+            Some(it) => it.json_to_sql(ty, out),
+            None => Ok(IsNull::Yes),
+        }
+    }
+}
+
+/// Parameter of type `json` or `jsonb`, written from a value of a type declared on the
+/// parameter
+#[repr(transparent)]
+pub struct TypedJsonSql<P: TypedJsonParam>(P);
+
+impl<P: TypedJsonParam> TypedJsonSql<P> {
+    /// Wraps a borrowed value without copying it, so that queries can hold the wrapped
+    /// parameter as long as the value itself
+    pub fn new(value: &P) -> &Self {
+        // SAFETY: `Self` is a transparent wrapper of `P`
+        unsafe { &*(value as *const P as *const Self) }
+    }
+}
+
+impl<P: TypedJsonParam> Debug for TypedJsonSql<P> {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        f.debug_tuple("TypedJsonSql").field(&self.0).finish()
+    }
+}
+
+impl<P: TypedJsonParam> ToSql for TypedJsonSql<P> {
+    fn to_sql(
+        &self,
+        ty: &Type,
+        out: &mut BytesMut,
+    ) -> Result<IsNull, Box<dyn Error + Sync + Send>> {
+        self.0.json_to_sql(ty, out)
+    }
+
+    fn accepts(ty: &Type) -> bool {
+        matches!(*escape_domain(ty), Type::JSON | Type::JSONB)
+    }
+
+    fn to_sql_checked(
+        &self,
+        ty: &Type,
+        out: &mut BytesMut,
+    ) -> Result<IsNull, Box<dyn Error + Sync + Send>> {
+        postgres_types::__to_sql_checked(self, ty, out)
+    }
+}
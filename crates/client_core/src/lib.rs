@@ -1,13 +1,39 @@
 mod array_iterator;
+mod batch;
+mod builder;
+mod cursor;
 mod domain;
+mod enum_drift;
+#[cfg(feature = "with-serde_json-1")]
+mod json;
+mod migration;
+#[cfg(feature = "with-bigdecimal-0_4")]
+mod numeric;
+#[cfg(feature = "ranges")]
+mod range;
+mod record;
 mod type_traits;
 mod utils;
+mod variant;
 
 pub use array_iterator::ArrayIterator;
+pub use batch::{batch_results, BatchResult};
+pub use builder::BuilderError;
+pub use cursor::{close_cursor, cursor_name, declare_cursor, fetch_cursor};
 pub use domain::{Domain, DomainArray};
+pub use enum_drift::{EnumDrift, LabelDrift, ENUM_LABELS_QUERY};
+#[cfg(feature = "with-serde_json-1")]
+pub use json::{TypedJson, TypedJsonParam, TypedJsonSql};
+pub use migration::Migration;
+#[cfg(feature = "with-bigdecimal-0_4")]
+pub use numeric::{BigDecimalSql, BigNumeric, BigNumericSql};
+#[cfg(feature = "ranges")]
+pub use range::{Range, RangeBound};
+pub use record::RecordFields;
 pub use type_traits::{ArraySql, BytesSql, IterSql, StringSql};
 
 #[cfg(feature = "with-serde_json-1")]
 pub use type_traits::JsonSql;
 
-pub use utils::slice_iter;
+pub use utils::{slice_iter, typed_params};
+pub use variant::{select_variant, Variant, SERVER_VERSION_QUERY};
@@ -0,0 +1,29 @@
+/// A migration embedded in the generated code, applied at runtime by
+/// `cornucopia::run_embedded_migrations`.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Migration {
+    /// Name of the migration file, without its `.sql` extension
+    pub name: &'static str,
+    /// SQL of the migration file
+    pub sql: &'static str,
+    /// Checksum of the SQL, computed by [`Migration::checksum`] when the code was generated
+    pub checksum: &'static str,
+}
+
+impl Migration {
+    /// Checksum of the SQL of a migration, as 16 hexadecimal digits of its 64-bit FNV-1a
+    /// hash, which is the same on every platform.
+    #[must_use]
+    pub fn checksum(sql: &str) -> String {
+        let hash = sql.bytes().fold(0xcbf2_9ce4_8422_2325_u64, |hash, byte| {
+            (hash ^ u64::from(byte)).wrapping_mul(0x0100_0000_01b3)
+        });
+        format!("{hash:016x}")
+    }
+
+    /// Whether the SQL of the migration still matches its checksum.
+    #[must_use]
+    pub fn is_intact(&self) -> bool {
+        Self::checksum(self.sql) == self.checksum
+    }
+}
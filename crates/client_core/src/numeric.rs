@@ -0,0 +1,231 @@
+use bigdecimal::{
+    num_bigint::{BigInt, Sign},
+    BigDecimal,
+};
+use postgres_protocol::types::{array_to_sql, ArrayDimension};
+use postgres_types::{private::BytesMut, FromSql, IsNull, Kind, ToSql, Type};
+use std::{
+    error::Error,
+    fmt::{Debug, Formatter},
+};
+
+use crate::utils::escape_domain;
+
+/// Sign of a negative `numeric`, in its binary format
+const NUMERIC_NEG: u16 = 0x4000;
+/// Sign bits marking a `NaN` or an infinite `numeric`, in its binary format
+const NUMERIC_SPECIAL: u16 = 0xC000;
+/// Largest display scale of a `numeric`
+const NUMERIC_MAX_SCALE: i64 = 0x3FFF;
+
+/// A `numeric` value of a row, converted into a [`BigDecimal`] of arbitrary precision
+/// using `into`. It borrows the binary value of the row, which cannot be a `NaN` or an
+/// infinity.
+#[derive(Clone, Copy)]
+pub struct BigNumeric<'a>(&'a [u8]);
+
+impl<'a> FromSql<'a> for BigNumeric<'a> {
+    fn from_sql(_: &Type, raw: &'a [u8]) -> Result<Self, Box<dyn Error + Sync + Send>> {
+        let header = |idx: usize| -> Result<u16, Box<dyn Error + Sync + Send>> {
+            raw.get(idx * 2..idx * 2 + 2)
+                .map(|it| u16::from_be_bytes([it[0], it[1]]))
+                .ok_or_else(|| "invalid numeric buffer size".into())
+        };
+        let (ndigits, sign) = (header(0)?, header(2)?);
+        if sign & NUMERIC_SPECIAL == NUMERIC_SPECIAL {
+            return Err("cannot read a NaN or infinite numeric as a BigDecimal".into());
+        }
+        if raw.len() != 8 + 2 * ndigits as usize {
+            return Err("invalid numeric buffer size".into());
+        }
+        Ok(Self(raw))
+    }
+
+    fn accepts(ty: &Type) -> bool {
+        *ty == Type::NUMERIC
+    }
+}
+
+impl<'a> From<BigNumeric<'a>> for BigDecimal {
+    fn from(BigNumeric(raw): BigNumeric<'a>) -> Self {
+        let header = |idx: usize| u16::from_be_bytes([raw[idx * 2], raw[idx * 2 + 1]]);
+        let (ndigits, weight, sign, scale) = (header(0), header(1) as i16, header(2), header(3));
+        // Base 10000 digits, the first one being multiplied by 10000^weight
+        let digits: String = (0..ndigits as usize)
+            .map(|idx| format!("{:04}", header(4 + idx)))
+            .collect();
+        let mut int = BigInt::parse_bytes(digits.as_bytes(), 10).unwrap_or_default();
+        if sign == NUMERIC_NEG {
+            int = -int;
+        }
+        let exponent = 4 * (i64::from(weight) + 1 - i64::from(ndigits));
+        BigDecimal::new(int, -exponent).with_scale(i64::from(scale))
+    }
+}
+
+impl Debug for BigNumeric<'_> {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        f.debug_tuple("BigNumeric")
+            .field(&BigDecimal::from(*self))
+            .finish()
+    }
+}
+
+/// Values of `numeric` parameters written from [`BigDecimal`]s, or arrays of them
+pub trait BigDecimalSql: Debug + Sync {
+    fn numeric_to_sql(
+        &self,
+        ty: &Type,
+        out: &mut BytesMut,
+    ) -> Result<IsNull, Box<dyn Error + Sync + Send>>;
+}
+
+impl BigDecimalSql for BigDecimal {
+    fn numeric_to_sql(
+        &self,
+        _: &Type,
+        out: &mut BytesMut,
+    ) -> Result<IsNull, Box<dyn Error + Sync + Send>> {
+        let (int, mut scale) = self.as_bigint_and_exponent();
+        let mut digits = int.magnitude().to_str_radix(10);
+        if scale < 0 {
+            digits.push_str(&"0".repeat(scale.unsigned_abs() as usize));
+            scale = 0;
+        }
+        if scale > NUMERIC_MAX_SCALE {
+            return Err(format!("the scale of {self} exceeds the one of numeric").into());
+        }
+        // Base 10000 digits are aligned on the decimal point
+        let fraction_pad = (4 - scale as usize % 4) % 4;
+        digits.push_str(&"0".repeat(fraction_pad));
+        let int_pad = (4 - digits.len() % 4) % 4;
+        digits.insert_str(0, &"0".repeat(int_pad));
+        let mut groups: Vec<u16> = digits
+            .as_bytes()
+            .chunks(4)
+            .map(|it| std::str::from_utf8(it).unwrap().parse().unwrap())
+            .collect();
+        let fraction_groups = (scale as usize + fraction_pad) / 4;
+        let mut weight = groups.len() as i64 - fraction_groups as i64 - 1;
+        let leading_zeros = groups.iter().take_while(|it| **it == 0).count();
+        groups.drain(..leading_zeros);
+        weight -= leading_zeros as i64;
+        while groups.last() == Some(&0) {
+            groups.pop();
+        }
+        if groups.is_empty() {
+            weight = 0;
+        }
+        let sign = if int.sign() == Sign::Minus {
+            NUMERIC_NEG
+        } else {
+            0
+        };
+        let weight =
+            i16::try_from(weight).map_err(|_| format!("{self} exceeds the range of numeric"))?;
+        out.extend_from_slice(&(groups.len() as u16).to_be_bytes());
+        out.extend_from_slice(&weight.to_be_bytes());
+        out.extend_from_slice(&sign.to_be_bytes());
+        out.extend_from_slice(&(scale as u16).to_be_bytes());
+        for group in groups {
+            out.extend_from_slice(&group.to_be_bytes());
+        }
+        Ok(IsNull::No)
+    }
+}
+
+impl<T: BigDecimalSql> BigDecimalSql for Option<T> {
+    fn numeric_to_sql(
+        &self,
+        ty: &Type,
+        out: &mut BytesMut,
+    ) -> Result<IsNull, Box<dyn Error + Sync + Send>> {
+        match self {
+            Some(it) => it.numeric_to_sql(ty, out),
+            None => Ok(IsNull::Yes),
+        }
+    }
+}
+
+impl<T: BigDecimalSql + ?Sized> BigDecimalSql for &T {
+    fn numeric_to_sql(
+        &self,
+        ty: &Type,
+        out: &mut BytesMut,
+    ) -> Result<IsNull, Box<dyn Error + Sync + Send>> {
+        T::numeric_to_sql(self, ty, out)
+    }
+}
+
+impl<T: BigDecimalSql> BigDecimalSql for [T] {
+    fn numeric_to_sql(
+        &self,
+        ty: &Type,
+        out: &mut BytesMut,
+    ) -> Result<IsNull, Box<dyn Error + Sync + Send>> {
+        let member_type = match *ty.kind() {
+            Kind::Array(ref member) => escape_domain(member),
+            _ => panic!("expected array type got {ty}"),
+        };
+        let dimension = ArrayDimension {
+            len: i32::try_from(self.len()).map_err(|_| "value too large to transmit")?,
+            lower_bound: 1,
+        };
+        array_to_sql(
+            Some(dimension),
+            member_type.oid(),
+            self.iter(),
+            |it, out| match it.numeric_to_sql(member_type, out)? {
+                IsNull::No => Ok(postgres_protocol::IsNull::No),
+                IsNull::Yes => Ok(postgres_protocol::IsNull::Yes),
+            },
+            out,
+        )?;
+        Ok(IsNull::No)
+    }
+}
+
+/// Parameter of type `numeric` or `numeric[]`, written from a [`BigDecimal`] or a slice of
+/// them
+#[repr(transparent)]
+pub struct BigNumericSql<T: BigDecimalSql>(T);
+
+impl<T: BigDecimalSql> BigNumericSql<T> {
+    /// Wraps a borrowed value without copying it, so that queries can hold the wrapped
+    /// parameter as long as the value itself
+    pub fn new(value: &T) -> &Self {
+        // SAFETY: `Self` is a transparent wrapper of `T`
+        unsafe { &*(value as *const T as *const Self) }
+    }
+}
+
+impl<T: BigDecimalSql> Debug for BigNumericSql<T> {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        f.debug_tuple("BigNumericSql").field(&self.0).finish()
+    }
+}
+
+impl<T: BigDecimalSql> ToSql for BigNumericSql<T> {
+    fn to_sql(
+        &self,
+        ty: &Type,
+        out: &mut BytesMut,
+    ) -> Result<IsNull, Box<dyn Error + Sync + Send>> {
+        self.0.numeric_to_sql(ty, out)
+    }
+
+    fn accepts(ty: &Type) -> bool {
+        match *escape_domain(ty).kind() {
+            Kind::Array(ref member) => *escape_domain(member) == Type::NUMERIC,
+            _ => *escape_domain(ty) == Type::NUMERIC,
+        }
+    }
+
+    fn to_sql_checked(
+        &self,
+        ty: &Type,
+        out: &mut BytesMut,
+    ) -> Result<IsNull, Box<dyn Error + Sync + Send>> {
+        postgres_types::__to_sql_checked(self, ty, out)
+    }
+}
@@ -0,0 +1,138 @@
+use std::error::Error;
+
+use postgres_protocol::types::{self, RangeBound as RawBound};
+use postgres_types::{private::BytesMut, FromSql, IsNull, Kind, ToSql, Type};
+
+/// One side of a [`Range`].
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub enum RangeBound<T> {
+    /// The bound is part of the range.
+    Inclusive(T),
+    /// The bound is not part of the range.
+    Exclusive(T),
+    /// The range has no bound on this side.
+    Unbounded,
+}
+
+/// A value of a range type (`int4range`, `tstzrange`, ...), read and written using the
+/// type of its bounds.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub enum Range<T> {
+    /// The range contains no value.
+    Empty,
+    /// The range contains the values between its lower and upper bound.
+    Nonempty(RangeBound<T>, RangeBound<T>),
+}
+
+impl<T> Range<T> {
+    /// The range of the values between `lower` and `upper`.
+    pub fn new(lower: RangeBound<T>, upper: RangeBound<T>) -> Self {
+        Self::Nonempty(lower, upper)
+    }
+
+    /// Whether the range contains no value.
+    pub fn is_empty(&self) -> bool {
+        matches!(self, Self::Empty)
+    }
+
+    /// The lower bound of the range, `None` when it is empty.
+    pub fn lower(&self) -> Option<&RangeBound<T>> {
+        match self {
+            Self::Empty => None,
+            Self::Nonempty(lower, _) => Some(lower),
+        }
+    }
+
+    /// The upper bound of the range, `None` when it is empty.
+    pub fn upper(&self) -> Option<&RangeBound<T>> {
+        match self {
+            Self::Empty => None,
+            Self::Nonempty(_, upper) => Some(upper),
+        }
+    }
+}
+
+/// The type of the bounds of a range type
+fn bound_type(ty: &Type) -> &Type {
+    match ty.kind() {
+        Kind::Range(inner) => inner,
+        _ => panic!("expected range type got {ty}"),
+    }
+}
+
+impl<'a, T: FromSql<'a>> FromSql<'a> for Range<T> {
+    fn from_sql(ty: &Type, raw: &'a [u8]) -> Result<Self, Box<dyn Error + Sync + Send>> {
+        let inner = bound_type(ty);
+        let bound = |bound| -> Result<_, Box<dyn Error + Sync + Send>> {
+            Ok(match bound {
+                RawBound::Inclusive(raw) => {
+                    RangeBound::Inclusive(T::from_sql_nullable(inner, raw)?)
+                }
+                RawBound::Exclusive(raw) => {
+                    RangeBound::Exclusive(T::from_sql_nullable(inner, raw)?)
+                }
+                RawBound::Unbounded => RangeBound::Unbounded,
+            })
+        };
+        Ok(match types::range_from_sql(raw)? {
+            types::Range::Empty => Self::Empty,
+            types::Range::Nonempty(lower, upper) => Self::Nonempty(bound(lower)?, bound(upper)?),
+        })
+    }
+
+    fn accepts(ty: &Type) -> bool {
+        match ty.kind() {
+            Kind::Range(inner) => T::accepts(inner),
+            _ => false,
+        }
+    }
+}
+
+impl<T: ToSql> ToSql for Range<T> {
+    fn to_sql(
+        &self,
+        ty: &Type,
+        out: &mut BytesMut,
+    ) -> Result<IsNull, Box<dyn Error + Sync + Send>> {
+        let inner = bound_type(ty);
+        match self {
+            Self::Empty => types::empty_range_to_sql(out),
+            Self::Nonempty(lower, upper) => types::range_to_sql(
+                |out| write_bound(lower, inner, out),
+                |out| write_bound(upper, inner, out),
+                out,
+            )?,
+        }
+        Ok(IsNull::No)
+    }
+
+    fn accepts(ty: &Type) -> bool {
+        match ty.kind() {
+            Kind::Range(inner) => T::accepts(inner),
+            _ => false,
+        }
+    }
+
+    postgres_types::to_sql_checked!();
+}
+
+/// Writes the value of a bound, returning its side with the nullity flag of the protocol
+fn write_bound<T: ToSql>(
+    bound: &RangeBound<T>,
+    ty: &Type,
+    out: &mut BytesMut,
+) -> Result<RawBound<postgres_protocol::IsNull>, Box<dyn Error + Sync + Send>> {
+    let mut write = |value: &T| -> Result<_, Box<dyn Error + Sync + Send>> {
+        Ok(match value.to_sql(ty, out)? {
+            IsNull::Yes => postgres_protocol::IsNull::Yes,
+            IsNull::No => postgres_protocol::IsNull::No,
+        })
+    };
+    Ok(match bound {
+        RangeBound::Inclusive(value) => RawBound::Inclusive(write(value)?),
+        RangeBound::Exclusive(value) => RawBound::Exclusive(write(value)?),
+        RangeBound::Unbounded => RawBound::Unbounded,
+    })
+}
@@ -0,0 +1,51 @@
+use std::error::Error;
+
+use postgres_types::{private::read_be_i32, FromSql, Type};
+
+/// Reader of the fields of an anonymous record (`ROW(...)`) in the binary format, decoding
+/// them using the built-in types declared by the annotation of its column.
+pub struct RecordFields<'a> {
+    column: &'static str,
+    raw: &'a [u8],
+}
+
+impl<'a> RecordFields<'a> {
+    /// Starts reading the record of `column`, which must have `len` fields.
+    pub fn new(
+        column: &'static str,
+        mut raw: &'a [u8],
+        len: usize,
+    ) -> Result<Self, Box<dyn Error + Sync + Send>> {
+        let count = read_be_i32(&mut raw)?;
+        if usize::try_from(count).ok() != Some(len) {
+            return Err(format!(
+                "the record of the column `{column}` has {count} fields, while {len} are declared"
+            )
+            .into());
+        }
+        Ok(Self { column, raw })
+    }
+
+    /// Reads the next field, named `field`, of the built-in type of OID `oid`.
+    pub fn next<T: FromSql<'a>>(
+        &mut self,
+        field: &str,
+        oid: u32,
+    ) -> Result<T, Box<dyn Error + Sync + Send>> {
+        let column = self.column;
+        let ty = Type::from_oid(oid).expect("declared types are built-in");
+        let actual = read_be_i32(&mut self.raw)? as u32;
+        if actual != oid {
+            let actual =
+                Type::from_oid(actual).map_or_else(|| actual.to_string(), |it| it.to_string());
+            return Err(format!(
+                "the field `{field}` of the record of the column `{column}` is of type `{actual}`, while `{ty}` is declared"
+            )
+            .into());
+        }
+        postgres_types::private::read_value(&ty, &mut self.raw).map_err(|err| {
+            format!("could not decode the field `{field}` of the record of the column `{column}`: {err}")
+                .into()
+        })
+    }
+}
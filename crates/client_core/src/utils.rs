@@ -12,3 +12,15 @@ pub fn slice_iter<'a>(
 ) -> impl ExactSizeIterator<Item = &'a dyn ToSql> + 'a {
     s.iter().map(|s| *s as _)
 }
+
+/// Pairs the parameters of a query that is not prepared with their built-in types, or
+/// returns `None` when one of them isn't built-in and the query must be prepared instead
+pub fn typed_params<'a>(
+    s: &[&'a (dyn ToSql + Sync)],
+    types: &[u32],
+) -> Option<Vec<(&'a (dyn ToSql + Sync), Type)>> {
+    s.iter()
+        .zip(types)
+        .map(|(param, oid)| Some((*param, Type::from_oid(*oid)?)))
+        .collect()
+}
@@ -0,0 +1,15 @@
+/// SQL of a query for the servers whose major version is in `min..max`, as `(min, max, sql)`
+pub type Variant = (u32, u32, &'static str);
+
+/// Query reading the `server_version_num` of the server, to select a variant
+pub const SERVER_VERSION_QUERY: &str = "SELECT current_setting('server_version_num')::int";
+
+/// SQL of the variant matching a server, given its `server_version_num` (e.g. `160002`)
+#[must_use]
+pub fn select_variant(variants: &[Variant], server_version_num: i32) -> Option<&'static str> {
+    let major = u32::try_from(server_version_num / 10000).ok()?;
+    variants
+        .iter()
+        .find(|(min, max, _)| (*min..*max).contains(&major))
+        .map(|(_, _, sql)| *sql)
+}
@@ -0,0 +1,53 @@
+use cornucopia_client_core::{close_cursor, fetch_cursor};
+use postgres::{Error, GenericClient, Row, Transaction};
+
+/// Clients running inside a transaction, which cursors are bound to.
+pub trait InTransaction: GenericClient {}
+
+impl InTransaction for Transaction<'_> {}
+
+/// Extracts the value of a fetched row
+type Mapper<'a, T> = Box<dyn Fn(&Row) -> Result<T, Error> + 'a>;
+
+/// Server-side cursor over the rows of a query, fetching them in chunks instead of
+/// buffering them all. It is closed at the end of its transaction, or using `close`.
+pub struct Cursor<'a, C, T, E> {
+    client: &'a mut C,
+    name: String,
+    mapper: Mapper<'a, T>,
+    error: fn(Error) -> E,
+}
+
+impl<'a, C: InTransaction, T, E> Cursor<'a, C, T, E> {
+    pub(crate) fn new(
+        client: &'a mut C,
+        name: String,
+        mapper: Mapper<'a, T>,
+        error: fn(Error) -> E,
+    ) -> Self {
+        Self {
+            client,
+            name,
+            mapper,
+            error,
+        }
+    }
+
+    /// Fetches the next `n` rows at most, returning an empty chunk once all were fetched.
+    pub fn next_chunk(&mut self, n: usize) -> Result<Vec<T>, E> {
+        let rows = self
+            .client
+            .query(&fetch_cursor(&self.name, n), &[])
+            .map_err(self.error)?;
+        rows.iter()
+            .map(|row| (self.mapper)(row).map_err(self.error))
+            .collect()
+    }
+
+    /// Closes the cursor before the end of its transaction.
+    pub fn close(self) -> Result<(), E> {
+        self.client
+            .batch_execute(&close_cursor(&self.name))
+            .map_err(self.error)
+    }
+}
@@ -1,10 +1,25 @@
+mod cursor;
 #[doc(hidden)]
 pub mod private;
 
-pub use cornucopia_client_core::{ArrayIterator, ArraySql, BytesSql, IterSql, StringSql};
+pub use cornucopia_client_core::{
+    ArrayIterator, ArraySql, BatchResult, BuilderError, BytesSql, IterSql, LabelDrift, Migration,
+    StringSql,
+};
+pub use cursor::{Cursor, InTransaction};
+
+/// Error of the `verify` function of a generated enum, reporting the labels of the enum
+/// that changed in the database since code generation.
+pub type EnumDrift = cornucopia_client_core::EnumDrift<postgres::Error>;
 
 #[cfg(feature = "with-serde_json-1")]
-pub use cornucopia_client_core::JsonSql;
+pub use cornucopia_client_core::{JsonSql, TypedJson};
+
+#[cfg(feature = "ranges")]
+pub use cornucopia_client_core::{Range, RangeBound};
+
+#[cfg(feature = "with-bigdecimal-0_4")]
+pub use cornucopia_client_core::BigNumeric;
 
 /// This trait allows you to bind parameters to a query using a single
 /// struct, rather than passing each bind parameter as a function parameter.
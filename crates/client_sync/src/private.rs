@@ -1,11 +1,25 @@
-pub use cornucopia_client_core::{slice_iter, Domain, DomainArray};
+#[cfg(feature = "with-bigdecimal-0_4")]
+pub use cornucopia_client_core::BigNumericSql;
+#[cfg(feature = "with-serde_json-1")]
+pub use cornucopia_client_core::TypedJsonSql;
+pub use cornucopia_client_core::{slice_iter, Domain, DomainArray, RecordFields};
 
-use postgres::Statement;
+use cornucopia_client_core::{
+    batch_results, cursor_name, declare_cursor, select_variant, typed_params, BatchResult,
+    EnumDrift, LabelDrift, Variant, ENUM_LABELS_QUERY, SERVER_VERSION_QUERY,
+};
 
-/// Cached statement
+use crate::{Cursor, InTransaction};
+use postgres::{types::ToSql, Error, Row, RowIter, SimpleQueryMessage, Statement};
+
+/// Cached statement, or query executed without a named prepared statement
 pub struct Stmt {
     query: &'static str,
     cached: Option<Statement>,
+    /// OIDs of the parameters of a query that is not prepared
+    unprepared: Option<&'static [u32]>,
+    /// SQL of the query by server version, the matching one being selected on first use
+    variants: &'static [Variant],
 }
 
 impl Stmt {
@@ -14,13 +28,46 @@ impl Stmt {
         Self {
             query,
             cached: None,
+            unprepared: None,
+            variants: &[],
+        }
+    }
+
+    /// Statement sent along with its parameters types on each execution, without
+    /// preparing nor caching it. It is prepared instead when a parameter type is not built-in.
+    #[must_use]
+    pub fn unprepared(query: &'static str, params: &'static [u32]) -> Self {
+        Self {
+            query,
+            cached: None,
+            unprepared: Some(params),
+            variants: &[],
         }
     }
 
-    pub fn prepare<'a, C: postgres::GenericClient>(
+    /// Executes the variant of the query matching the version of the server instead,
+    /// if any
+    #[must_use]
+    pub fn variants(mut self, variants: &'static [Variant]) -> Self {
+        self.variants = variants;
+        self
+    }
+
+    fn select_variant<C: postgres::GenericClient>(&mut self, client: &mut C) -> Result<(), Error> {
+        if !self.variants.is_empty() {
+            let version: i32 = client.query_one(SERVER_VERSION_QUERY, &[])?.get(0);
+            if let Some(query) = select_variant(self.variants, version) {
+                self.query = query;
+            }
+            self.variants = &[];
+        }
+        Ok(())
+    }
+
+    fn prepare<'a, C: postgres::GenericClient>(
         &'a mut self,
         client: &mut C,
-    ) -> Result<&'a Statement, postgres::Error> {
+    ) -> Result<&'a Statement, Error> {
         if self.cached.is_none() {
             let stmt = client.prepare(self.query)?;
             self.cached = Some(stmt);
@@ -28,4 +75,169 @@ impl Stmt {
         // the statement is always prepared at this point
         Ok(unsafe { self.cached.as_ref().unwrap_unchecked() })
     }
+
+    pub fn execute<C: postgres::GenericClient>(
+        &mut self,
+        client: &mut C,
+        params: &[&(dyn ToSql + Sync)],
+    ) -> Result<u64, Error> {
+        self.select_variant(client)?;
+        if let Some(params) = self
+            .unprepared
+            .and_then(|types| typed_params(params, types))
+        {
+            return client.execute_typed(self.query, &params);
+        }
+        let stmt = self.prepare(client)?;
+        client.execute(stmt, params)
+    }
+
+    pub fn query_one<C: postgres::GenericClient>(
+        &mut self,
+        client: &mut C,
+        params: &[&(dyn ToSql + Sync)],
+    ) -> Result<Row, Error> {
+        self.select_variant(client)?;
+        if let Some(params) = self
+            .unprepared
+            .and_then(|types| typed_params(params, types))
+        {
+            return client.query_typed_one(self.query, &params);
+        }
+        let stmt = self.prepare(client)?;
+        client.query_one(stmt, params)
+    }
+
+    pub fn query_opt<C: postgres::GenericClient>(
+        &mut self,
+        client: &mut C,
+        params: &[&(dyn ToSql + Sync)],
+    ) -> Result<Option<Row>, Error> {
+        self.select_variant(client)?;
+        if let Some(params) = self
+            .unprepared
+            .and_then(|types| typed_params(params, types))
+        {
+            return client.query_typed_opt(self.query, &params);
+        }
+        let stmt = self.prepare(client)?;
+        client.query_opt(stmt, params)
+    }
+
+    pub fn query_raw<'c, C: postgres::GenericClient>(
+        &mut self,
+        client: &'c mut C,
+        params: &[&(dyn ToSql + Sync)],
+    ) -> Result<RowIter<'c>, Error> {
+        self.select_variant(client)?;
+        if let Some(params) = self
+            .unprepared
+            .and_then(|types| typed_params(params, types))
+        {
+            return client.query_typed_raw(self.query, params);
+        }
+        let stmt = self.prepare(client)?;
+        client.query_raw(stmt, slice_iter(params))
+    }
+
+    /// Declares a cursor over the rows of the query, mapped using `mapper`
+    pub fn cursor<'a, C: InTransaction, T, E>(
+        &mut self,
+        client: &'a mut C,
+        params: &[&(dyn ToSql + Sync)],
+        mapper: impl Fn(&Row) -> Result<T, Error> + 'a,
+        error: fn(Error) -> E,
+    ) -> Result<Cursor<'a, C, T, E>, E> {
+        self.select_variant(client).map_err(error)?;
+        let name = cursor_name();
+        let declare = declare_cursor(&name, self.query);
+        match self
+            .unprepared
+            .and_then(|types| typed_params(params, types))
+        {
+            Some(typed) => client.execute_typed(&declare, &typed),
+            None => client.execute(&declare, params),
+        }
+        .map_err(error)?;
+        Ok(Cursor::new(client, name, Box::new(mapper), error))
+    }
+}
+
+/// Executes the statements of a batch query, reporting the rows count of each command.
+pub fn batch<C: postgres::GenericClient>(
+    client: &mut C,
+    query: &str,
+    commands: &[&str],
+) -> Result<Vec<BatchResult>, Error> {
+    let messages = client.simple_query(query)?;
+    let rows = messages.into_iter().filter_map(|it| match it {
+        SimpleQueryMessage::CommandComplete(rows) => Some(rows),
+        _ => None,
+    });
+    Ok(batch_results(commands, rows))
+}
+
+/// Compares the labels of the enum `schema.name` of the database with the generated ones.
+pub fn verify_enum<C: postgres::GenericClient>(
+    client: &mut C,
+    schema: &str,
+    name: &str,
+    labels: &[&str],
+) -> Result<(), EnumDrift<Error>> {
+    let actual: Vec<String> = client
+        .query(ENUM_LABELS_QUERY, &[&schema, &name])
+        .map_err(EnumDrift::Db)?
+        .iter()
+        .map(|row| row.get(0))
+        .collect();
+    match LabelDrift::compare(&format!("{schema}.{name}"), labels, &actual) {
+        Some(drift) => Err(EnumDrift::Labels(drift)),
+        None => Ok(()),
+    }
+}
+
+/// Executes a query acquiring the advisory lock `key`, e.g. `SELECT pg_advisory_lock($1)`.
+pub fn advisory_lock<C: postgres::GenericClient>(
+    client: &mut C,
+    query: &str,
+    key: i64,
+) -> Result<(), Error> {
+    client.execute(query, &[&key]).map(drop)
+}
+
+/// Executes a query trying to acquire or releasing the advisory lock `key`, e.g.
+/// `SELECT pg_try_advisory_lock($1)`, returning whether it succeeded.
+pub fn try_advisory_lock<C: postgres::GenericClient>(
+    client: &mut C,
+    query: &str,
+    key: i64,
+) -> Result<bool, Error> {
+    Ok(client.query_one(query, &[&key])?.get(0))
+}
+
+/// Streams the output of a `COPY ... TO STDOUT` statement into `writer`,
+/// returning the number of bytes written.
+#[cfg(feature = "with-copy-out")]
+pub fn copy_out<C: postgres::GenericClient, W: std::io::Write>(
+    client: &mut C,
+    query: &str,
+    writer: &mut W,
+) -> Result<u64, std::io::Error> {
+    let mut reader = client.copy_out(query).map_err(std::io::Error::other)?;
+    std::io::copy(&mut reader, writer)
+}
+
+#[cfg(feature = "with-copy-in")]
+pub use postgres::binary_copy::BinaryCopyInWriter;
+
+/// Starts the binary `COPY ... FROM STDIN` statement `copy`, the types of its columns
+/// being the ones of the params of the equivalent `insert` statement.
+#[cfg(feature = "with-copy-in")]
+pub fn copy_in<'a, C: postgres::GenericClient>(
+    client: &'a mut C,
+    insert: &str,
+    copy: &str,
+) -> Result<BinaryCopyInWriter<'a>, Error> {
+    let types = client.prepare(insert)?.params().to_vec();
+    Ok(BinaryCopyInWriter::new(client.copy_in(copy)?, &types))
 }
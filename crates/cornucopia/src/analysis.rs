@@ -0,0 +1,562 @@
+/// Properties inferred from the text of a SQL query.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+#[non_exhaustive]
+pub struct QueryAnalysis {
+    /// Whether the query is known not to write to the database.
+    pub is_read_only: bool,
+}
+
+/// Analyzes the text of a SQL query without requiring a database connection.
+///
+/// A query is considered read-only when it is a `SELECT`, or a `WITH ... SELECT` whose
+/// common table expressions contain no data-modifying statement. Queries locking rows
+/// (`FOR UPDATE`, `FOR SHARE`, ...) or creating tables (`SELECT ... INTO`) are not
+/// read-only. This is a conservative heuristic: functions with side effects called by
+/// a `SELECT` are not detected.
+///
+/// ```
+/// use cornucopia::analyze;
+///
+/// assert!(analyze("SELECT * FROM author WHERE id = $1").is_read_only);
+/// assert!(analyze("WITH b AS (SELECT * FROM book) SELECT title FROM b").is_read_only);
+/// assert!(!analyze("INSERT INTO author (name) VALUES ($1)").is_read_only);
+/// assert!(!analyze("UPDATE author SET name = $1").is_read_only);
+/// assert!(!analyze("WITH d AS (DELETE FROM book RETURNING *) SELECT * FROM d").is_read_only);
+/// assert!(!analyze("SELECT * FROM author FOR UPDATE").is_read_only);
+/// ```
+pub fn analyze(sql: &str) -> QueryAnalysis {
+    QueryAnalysis {
+        is_read_only: is_read_only(sql),
+    }
+}
+
+/// Normalizes the text of a SQL query so that cosmetic edits don't change it: comments
+/// are removed and runs of whitespace are collapsed into a single space. String literals,
+/// quoted identifiers and dollar-quoted strings are kept verbatim. String literals only
+/// separated by a newline, which PostgreSQL concatenates, stay separated by a newline.
+///
+/// ```
+/// use cornucopia::normalize_sql;
+///
+/// assert_eq!(
+///     normalize_sql("\n  SELECT id,\n    name -- the name\n  FROM author\n"),
+///     "SELECT id, name FROM author"
+/// );
+/// // Comments separate tokens, and block comments can be nested
+/// assert_eq!(normalize_sql("SELECT/* a /* b */ c */1--x\n+2"), "SELECT 1 +2");
+/// // Literals and quoted identifiers are kept verbatim
+/// assert_eq!(
+///     normalize_sql("SELECT 'it''s  -- not a comment',  \"a  b\" ,E'\\'  /*'"),
+///     "SELECT 'it''s  -- not a comment', \"a  b\" ,E'\\'  /*'"
+/// );
+/// // Dollar-quoted bodies are kept verbatim, unlike parameters
+/// assert_eq!(
+///     normalize_sql("DO $$ BEGIN  -- kept\n  PERFORM $1; END $$;  SELECT $1,\t$2"),
+///     "DO $$ BEGIN  -- kept\n  PERFORM $1; END $$; SELECT $1, $2"
+/// );
+/// assert_eq!(
+///     normalize_sql("SELECT $fn$ $$  'x $fn$,  a$b  FROM t"),
+///     "SELECT $fn$ $$  'x $fn$, a$b FROM t"
+/// );
+/// // Literals continued on the next line are still concatenated
+/// assert_eq!(normalize_sql("SELECT 'a'  \n  'b'"), "SELECT 'a'\n'b'");
+/// ```
+pub fn normalize_sql(sql: &str) -> String {
+    let mut normalized = String::with_capacity(sql.len());
+    // Whether whitespace or comments were skipped since the last token, and if they
+    // contained a newline
+    let (mut gap, mut newline) = (false, false);
+    let mut idx = 0;
+    while let Some(c) = sql[idx..].chars().next() {
+        let rest = &sql[idx..];
+        let skipped = if c.is_whitespace() {
+            newline |= c == '\n';
+            c.len_utf8()
+        } else if rest.starts_with("--") {
+            rest.find('\n').unwrap_or(rest.len())
+        } else if rest.starts_with("/*") {
+            block_comment_len(rest)
+        } else {
+            0
+        };
+        if skipped > 0 {
+            gap = true;
+            idx += skipped;
+            continue;
+        }
+
+        let len = token_len(rest);
+        if gap && !normalized.is_empty() {
+            if newline && normalized.ends_with('\'') && c == '\'' {
+                normalized.push('\n');
+            } else {
+                normalized.push(' ');
+            }
+        }
+        (gap, newline) = (false, false);
+        normalized.push_str(&rest[..len]);
+        idx += len;
+    }
+    normalized
+}
+
+/// Length of the token starting `s`, which is not whitespace nor a comment. Literals,
+/// quoted identifiers and dollar-quoted strings are single tokens.
+pub(crate) fn token_len(s: &str) -> usize {
+    match s.chars().next() {
+        Some('\'') => quoted_len(s, '\'', false),
+        Some('"') => quoted_len(s, '"', false),
+        Some('$') => dollar_quoted_len(s).unwrap_or(1),
+        Some(c) if c.is_alphabetic() || c == '_' => {
+            let len = s
+                .find(|c: char| !(c.is_alphanumeric() || c == '_' || c == '$'))
+                .unwrap_or(s.len());
+            // String with C-style escapes, like `E'\''`
+            if s[..len].eq_ignore_ascii_case("e") && s[len..].starts_with('\'') {
+                len + quoted_len(&s[len..], '\'', true)
+            } else {
+                len
+            }
+        }
+        Some(c) => c.len_utf8(),
+        None => 0,
+    }
+}
+
+/// Length of the quoted string or identifier starting `s`
+fn quoted_len(s: &str, quote: char, backslash_escapes: bool) -> usize {
+    let mut chars = s.char_indices().skip(1);
+    while let Some((i, c)) = chars.next() {
+        if backslash_escapes && c == '\\' {
+            chars.next();
+        } else if c == quote {
+            return i + 1;
+        }
+    }
+    s.len()
+}
+
+/// Length of the dollar-quoted string (`$$...$$` or `$tag$...$tag$`) starting `s`
+fn dollar_quoted_len(s: &str) -> Option<usize> {
+    if s[1..].starts_with(|c: char| c.is_ascii_digit()) {
+        return None;
+    }
+    let tag_len = s[1..]
+        .find(|c: char| !(c.is_alphanumeric() || c == '_'))
+        .filter(|len| s[1 + len..].starts_with('$'))?;
+    let delimiter = &s[..tag_len + 2];
+    let body = delimiter.len();
+    Some(
+        s[body..]
+            .find(delimiter)
+            .map_or(s.len(), |it| body + it + delimiter.len()),
+    )
+}
+
+/// Length of the possibly nested block comment starting `s`
+pub(crate) fn block_comment_len(s: &str) -> usize {
+    let mut depth = 0usize;
+    let mut idx = 0;
+    while let Some(c) = s[idx..].chars().next() {
+        if s[idx..].starts_with("/*") {
+            depth += 1;
+            idx += 2;
+        } else if s[idx..].starts_with("*/") {
+            depth -= 1;
+            idx += 2;
+            if depth == 0 {
+                return idx;
+            }
+        } else {
+            idx += c.len_utf8();
+        }
+    }
+    s.len()
+}
+
+/// Classifies a query as read-only from its keywords.
+pub(crate) fn is_read_only(sql: &str) -> bool {
+    let words: Vec<_> = lex(sql)
+        .into_iter()
+        .filter_map(|it| match it {
+            Token::Word {
+                value,
+                quoted: false,
+                ..
+            } => Some(value),
+            _ => None,
+        })
+        .collect();
+    let starts_read_only = matches!(words.first().map(String::as_str), Some("SELECT" | "WITH"));
+    let writes = words.iter().any(|it| {
+        matches!(
+            it.as_str(),
+            "INSERT" | "UPDATE" | "DELETE" | "MERGE" | "INTO" | "SHARE"
+        )
+    });
+    starts_read_only && !writes
+}
+
+/// Whether a query inserts a row unless it conflicts, returning it
+/// (`INSERT ... ON CONFLICT ... DO NOTHING RETURNING ...`), so that it returns no row on
+/// conflict. Inserts of the rows of a `SELECT` are not detected, as they can return several.
+pub(crate) fn skips_conflicts(sql: &str) -> bool {
+    let words: Vec<_> = lex(sql)
+        .into_iter()
+        .filter_map(|it| match it {
+            Token::Word {
+                value,
+                depth: 0,
+                quoted: false,
+            } => Some(value),
+            _ => None,
+        })
+        .collect();
+    let position = |pair: [&str; 2]| words.windows(2).position(|it| it == pair);
+    let (Some(insert), Some(conflict), Some(nothing)) = (
+        words.iter().position(|it| it == "INSERT"),
+        position(["ON", "CONFLICT"]),
+        position(["DO", "NOTHING"]),
+    ) else {
+        return false;
+    };
+    insert < conflict
+        && conflict < nothing
+        && !words[insert..conflict].iter().any(|it| it == "SELECT")
+        && words[nothing..].iter().any(|it| it == "RETURNING")
+}
+
+/// The binary `COPY ... FROM STDIN` statement equivalent to an insert of its bind
+/// parameters, each column being given the parameter of its position
+/// (`INSERT INTO book (name, author) VALUES ($1, $2)`), or `None` for any other query.
+pub(crate) fn copy_in_statement(sql: &str) -> Option<String> {
+    // Tokens of the query, skipping whitespace and comments, bind parameters being single ones
+    let mut tokens = Vec::new();
+    let mut idx = 0;
+    while let Some(c) = sql[idx..].chars().next() {
+        let rest = &sql[idx..];
+        let len = if c.is_whitespace() {
+            idx += c.len_utf8();
+            continue;
+        } else if rest.starts_with("--") {
+            idx += rest.find('\n').unwrap_or(rest.len());
+            continue;
+        } else if rest.starts_with("/*") {
+            idx += block_comment_len(rest);
+            continue;
+        } else if c == '$' && rest[1..].starts_with(|c: char| c.is_ascii_digit()) {
+            1 + rest[1..]
+                .find(|c: char| !c.is_ascii_digit())
+                .unwrap_or(rest.len() - 1)
+        } else {
+            token_len(rest)
+        };
+        tokens.push(&rest[..len]);
+        idx += len;
+    }
+    if tokens.last() == Some(&";") {
+        tokens.pop();
+    }
+
+    let mut tokens = tokens.into_iter().peekable();
+    for keyword in ["INSERT", "INTO"] {
+        tokens.next_if(|it| it.eq_ignore_ascii_case(keyword))?;
+    }
+    // Possibly qualified name of the table
+    let mut table = String::new();
+    while let Some(token) = tokens.next_if(|it| *it != "(") {
+        if (token == ".") != table.ends_with(|c: char| c.is_alphanumeric() || c == '_' || c == '"')
+        {
+            return None;
+        }
+        table.push_str(token);
+    }
+    // Items of a parenthesized list separated by commas
+    let list = |tokens: &mut std::iter::Peekable<_>| {
+        tokens.next_if_eq(&"(")?;
+        let mut items = Vec::new();
+        loop {
+            items.push(tokens.next_if(|it| !matches!(*it, "(" | ")" | ","))?);
+            if tokens.next_if_eq(&")").is_some() {
+                return Some(items);
+            }
+            tokens.next_if_eq(&",")?;
+        }
+    };
+    let columns = list(&mut tokens)?;
+    tokens.next_if(|it| it.eq_ignore_ascii_case("VALUES"))?;
+    let params = list(&mut tokens)?;
+    let is_params = params.len() == columns.len()
+        && params
+            .iter()
+            .enumerate()
+            .all(|(i, it)| *it == format!("${}", i + 1));
+    (!table.is_empty() && !table.ends_with('.') && is_params && tokens.next().is_none()).then(
+        || {
+            format!(
+                "COPY {table} ({}) FROM STDIN (FORMAT BINARY)",
+                columns.join(", ")
+            )
+        },
+    )
+}
+
+/// Statements of a multi-statement query with their byte offset, skipping empty ones.
+pub(crate) fn split_statements(sql: &str) -> Vec<(usize, &str)> {
+    let mut statements = Vec::new();
+    let (mut start, mut empty) = (0, true);
+    let mut tokens = lex(sql).into_iter();
+    loop {
+        let end = match tokens.next() {
+            Some(Token::Semicolon(end)) => end,
+            Some(_) => {
+                empty = false;
+                continue;
+            }
+            None => sql.len(),
+        };
+        if !empty {
+            statements.push((start, &sql[start..end]));
+        }
+        if end == sql.len() {
+            return statements;
+        }
+        (start, empty) = (end + 1, true);
+    }
+}
+
+/// Keywords of the commands executed by a multi-statement query (e.g. `DELETE` for
+/// `WITH old AS (...) DELETE FROM ...`), skipping empty statements.
+pub(crate) fn statement_commands(sql: &str) -> Vec<String> {
+    let mut commands = Vec::new();
+    let mut words = Vec::new();
+    let mut tokens = lex(sql).into_iter();
+    loop {
+        let token = tokens.next();
+        match token {
+            Some(Token::Word {
+                value,
+                depth,
+                quoted: false,
+            }) => {
+                words.push((value, depth));
+                continue;
+            }
+            Some(Token::Word { .. } | Token::Dot) => continue,
+            Some(Token::Semicolon(_)) | None => {}
+        }
+        let command = words
+            .iter()
+            .find(|(value, depth)| {
+                *depth == 0
+                    && matches!(
+                        value.as_str(),
+                        "SELECT" | "INSERT" | "UPDATE" | "DELETE" | "MERGE" | "VALUES" | "TABLE"
+                    )
+            })
+            .or(words.first());
+        if let Some((command, _)) = command {
+            commands.push(command.clone());
+        }
+        if token.is_none() {
+            return commands;
+        }
+        words.clear();
+    }
+}
+
+/// Names of the tables on the nullable side of an outer join: the right side of a `LEFT JOIN`,
+/// the left side of a `RIGHT JOIN` and both sides of a `FULL JOIN`.
+///
+/// Unquoted names are folded to lowercase like PostgreSQL does. Tables joined through a
+/// subquery are not detected, and a table joined several times is reported as soon as one
+/// of its occurrences is nullable, as its columns cannot be told apart.
+pub(crate) fn outer_joined_tables(sql: &str) -> Vec<String> {
+    let tokens = lex(sql);
+    let keyword = |idx: usize, depth: usize| match tokens.get(idx) {
+        Some(Token::Word {
+            value,
+            depth: it,
+            quoted: false,
+        }) if *it == depth => Some(value.as_str()),
+        _ => None,
+    };
+    // Name of the table referenced at `idx`, skipping its schema
+    let table_at = |mut idx: usize, depth: usize| {
+        while matches!(keyword(idx, depth), Some("ONLY" | "LATERAL")) {
+            idx += 1;
+        }
+        let mut name = None;
+        while let Some(Token::Word {
+            value,
+            depth: it,
+            quoted,
+        }) = tokens.get(idx)
+        {
+            if *it != depth {
+                break;
+            }
+            name = Some(if *quoted {
+                value.clone()
+            } else {
+                value.to_lowercase()
+            });
+            if !matches!(tokens.get(idx + 1), Some(Token::Dot)) {
+                break;
+            }
+            idx += 2;
+        }
+        name
+    };
+
+    let mut nullable = Vec::new();
+    // Tables of the `FROM` clauses being read, with their parenthesis depth
+    let mut from_tables: Vec<(usize, String)> = Vec::new();
+    for (idx, token) in tokens.iter().enumerate() {
+        let Token::Word {
+            value,
+            depth,
+            quoted: false,
+        } = token
+        else {
+            continue;
+        };
+        match value.as_str() {
+            "FROM" => {
+                from_tables.retain(|(it, _)| it < depth);
+                from_tables.extend(table_at(idx + 1, *depth).map(|it| (*depth, it)));
+            }
+            "JOIN" => {
+                let mut kind_idx = idx.checked_sub(1);
+                if kind_idx.and_then(|it| keyword(it, *depth)) == Some("OUTER") {
+                    kind_idx = kind_idx.and_then(|it| it.checked_sub(1));
+                }
+                let kind = kind_idx.and_then(|it| keyword(it, *depth));
+                if matches!(kind, Some("RIGHT" | "FULL")) {
+                    nullable.extend(
+                        from_tables
+                            .iter()
+                            .filter(|(it, _)| it == depth)
+                            .map(|(_, name)| name.clone()),
+                    );
+                }
+                if let Some(table) = table_at(idx + 1, *depth) {
+                    if matches!(kind, Some("LEFT" | "FULL")) {
+                        nullable.push(table.clone());
+                    }
+                    from_tables.push((*depth, table));
+                }
+            }
+            _ => {}
+        }
+    }
+    nullable.sort();
+    nullable.dedup();
+    nullable
+}
+
+/// Identifiers and keywords of a query, skipping comments and literals, sorted and
+/// deduplicated. Unquoted ones are folded to lowercase like PostgreSQL does, qualified
+/// names being split into their parts.
+pub(crate) fn identifiers(sql: &str) -> Vec<String> {
+    let mut identifiers: Vec<_> = lex(sql)
+        .into_iter()
+        .filter_map(|it| match it {
+            Token::Word {
+                value,
+                quoted: false,
+                ..
+            } => Some(value.to_lowercase()),
+            Token::Word { value, .. } => Some(value),
+            _ => None,
+        })
+        .collect();
+    identifiers.sort();
+    identifiers.dedup();
+    identifiers
+}
+
+enum Token {
+    /// Word with its parenthesis depth, uppercased unless it is a quoted identifier
+    Word {
+        value: String,
+        depth: usize,
+        quoted: bool,
+    },
+    /// Qualified name separator
+    Dot,
+    /// Statement separator, with its byte offset
+    Semicolon(usize),
+}
+
+/// Words and separators of a query, skipping comments and string literals.
+fn lex(sql: &str) -> Vec<Token> {
+    let mut tokens = Vec::new();
+    let mut depth = 0usize;
+    let mut chars = sql.char_indices().peekable();
+    while let Some((i, c)) = chars.next() {
+        match c {
+            '-' if matches!(chars.peek(), Some((_, '-'))) => {
+                chars.by_ref().find(|(_, c)| *c == '\n');
+            }
+            '/' if matches!(chars.peek(), Some((_, '*'))) => {
+                chars.next();
+                let mut prev = ' ';
+                for (_, c) in chars.by_ref() {
+                    if prev == '*' && c == '/' {
+                        break;
+                    }
+                    prev = c;
+                }
+            }
+            '\'' => {
+                chars.by_ref().find(|(_, it)| *it == '\'');
+            }
+            '"' => {
+                let value = chars
+                    .by_ref()
+                    .map(|(_, c)| c)
+                    .take_while(|c| *c != '"')
+                    .collect();
+                tokens.push(Token::Word {
+                    value,
+                    depth,
+                    quoted: true,
+                });
+            }
+            // Dollar-quoted string, like `$$...$$` or `$tag$...$tag$`
+            '$' if !matches!(chars.peek(), Some((_, c)) if c.is_ascii_digit()) => {
+                let tag_len = sql[i + 1..]
+                    .find(|c: char| !(c.is_alphanumeric() || c == '_'))
+                    .filter(|len| sql[i + 1 + len..].starts_with('$'));
+                if let Some(tag_len) = tag_len {
+                    let delimiter = &sql[i..i + tag_len + 2];
+                    let body = i + delimiter.len();
+                    let end = sql[body..]
+                        .find(delimiter)
+                        .map_or(sql.len(), |it| body + it + delimiter.len());
+                    while chars.next_if(|(i, _)| *i < end).is_some() {}
+                }
+            }
+            '(' => depth += 1,
+            ')' => depth = depth.saturating_sub(1),
+            ';' => tokens.push(Token::Semicolon(i)),
+            '.' => tokens.push(Token::Dot),
+            c if c.is_alphabetic() || c == '_' => {
+                let mut value = c.to_uppercase().to_string();
+                while let Some((_, c)) =
+                    chars.next_if(|(_, c)| c.is_alphanumeric() || *c == '_' || *c == '$')
+                {
+                    value.extend(c.to_uppercase());
+                }
+                tokens.push(Token::Word {
+                    value,
+                    depth,
+                    quoted: false,
+                });
+            }
+            _ => {}
+        }
+    }
+    tokens
+}
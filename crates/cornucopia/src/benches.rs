@@ -0,0 +1,333 @@
+use std::{
+    collections::BTreeMap,
+    fmt::Write,
+    path::{Component, Path, PathBuf},
+};
+
+use codegen_template::code;
+
+use crate::{
+    error::WriteOutputError,
+    prepare_queries::{Arity, Preparation, PreparedModule, PreparedQuery},
+    CodegenSettings,
+};
+
+use self::error::Error;
+
+/// Name of the file listing the parameters of the benchmarked queries, in the benches
+/// directory
+const FIXTURES_FILE: &str = "benches.toml";
+
+/// Environment variable holding the URL of the database the benches are executed against
+const URL_VAR: &str = "CORNUCOPIA_BENCH_URL";
+
+/// Literal parameters of the benchmarked queries, by module and query name
+type Fixtures = BTreeMap<String, BTreeMap<String, BTreeMap<String, toml::Value>>>;
+
+/// Writes a criterion bench file per module in `dir`, benchmarking the queries listed in
+/// its `benches.toml` file with their parameters. The generated code at `destination` is
+/// included in each bench file, so that benches call the very functions used in production.
+pub(crate) fn write_benches(
+    preparation: &Preparation,
+    dir: &Path,
+    destination: &Path,
+    settings: &CodegenSettings,
+) -> Result<(), crate::Error> {
+    let fixtures_path = dir.join(FIXTURES_FILE);
+    let content = std::fs::read_to_string(&fixtures_path).map_err(|err| Error::Io {
+        path: fixtures_path.clone(),
+        err,
+    })?;
+    let fixtures: Fixtures = toml::from_str(&content).map_err(|err| Error::Format {
+        path: fixtures_path,
+        err: Box::new(err),
+    })?;
+    let include = relative_path(dir, destination);
+    for (module, code) in generate_benches(preparation, &fixtures, &include, settings)? {
+        let path = dir.join(format!("{module}.rs"));
+        std::fs::write(&path, code).map_err(|err| WriteOutputError {
+            err,
+            action: "write your benches to",
+            file_path: path.clone(),
+        })?;
+    }
+    Ok(())
+}
+
+/// Generates the bench file of every module having benchmarked queries
+fn generate_benches(
+    preparation: &Preparation,
+    fixtures: &Fixtures,
+    include: &str,
+    settings: &CodegenSettings,
+) -> Result<Vec<(String, String)>, Error> {
+    for (module, queries) in fixtures {
+        let prepared = preparation
+            .modules
+            .iter()
+            .find(|it| it.info.name == *module)
+            .ok_or_else(|| Error::UnknownModule {
+                module: module.clone(),
+            })?;
+        if let Some(query) = queries
+            .keys()
+            .find(|name| !prepared.queries.keys().any(|it| it.value == **name))
+        {
+            return Err(Error::UnknownQuery {
+                module: module.clone(),
+                query: query.clone(),
+            });
+        }
+    }
+    let mut benches = Vec::new();
+    for module in &preparation.modules {
+        let Some(queries) = fixtures.get(&module.info.name) else {
+            continue;
+        };
+        let mut bench_fns = Vec::new();
+        for (name, query) in &module.queries {
+            if let Some(params) = queries.get(&name.value) {
+                bench_fns.push(gen_bench_fn(module, query, params, settings)?);
+            }
+        }
+        benches.push((
+            module.info.name.clone(),
+            gen_bench_file(&bench_fns, include, settings),
+        ));
+    }
+    Ok(benches)
+}
+
+/// Generates the benchmark of a query, executed using its literal parameters
+fn gen_bench_fn(
+    module: &PreparedModule,
+    query: &PreparedQuery,
+    params: &BTreeMap<String, toml::Value>,
+    settings: &CodegenSettings,
+) -> Result<String, Error> {
+    let query_name = || format!("{}::{}", module.info.name, query.ident.db);
+    if query.batch.is_some() {
+        return Err(Error::BatchQuery {
+            query: query_name(),
+        });
+    }
+    let fields = match &query.param {
+        Some((idx, order)) => {
+            let item = module.params.get_index(*idx).unwrap().1;
+            order.iter().map(|idx| &item.fields[*idx]).collect()
+        }
+        None => Vec::new(),
+    };
+    if let Some(param) = params
+        .keys()
+        .find(|name| !fields.iter().any(|it| it.ident.db == **name))
+    {
+        return Err(Error::UnknownParam {
+            query: query_name(),
+            param: param.clone(),
+        });
+    }
+    let mut args = Vec::new();
+    for field in fields {
+        let value = params
+            .get(&field.ident.db)
+            .ok_or_else(|| Error::MissingParam {
+                query: query_name(),
+                param: field.ident.db.clone(),
+            })?;
+        let literal = rust_literal(value, field.is_inner_nullable).ok_or_else(|| {
+            Error::UnsupportedValue {
+                query: query_name(),
+                param: field.ident.db.clone(),
+            }
+        })?;
+        args.push(if field.is_nullable {
+            format!("Some({literal})")
+        } else {
+            literal
+        });
+    }
+
+    let (is_async, client) = if settings.gen_sync {
+        (false, "&mut transaction")
+    } else {
+        (true, "&transaction")
+    };
+    let fetch = match query.row {
+        Some((idx, _)) => match module.rows.get_index(idx).unwrap().1.arity {
+            Arity::One => ".one()",
+            Arity::Opt => ".opt()",
+            Arity::Many => ".all()",
+        },
+        None => "",
+    };
+    let await_ = if is_async { ".await" } else { "" };
+    let variant = match (settings.gen_sync, settings.gen_async) {
+        (true, true) => "::sync",
+        _ => "",
+    };
+    let path = format!(
+        "cornucopia::queries::{}{variant}::{}",
+        module.name, query.ident.rs
+    );
+    let call = format!(
+        "stmt.bind({client}, {}){fetch}{await_}.unwrap()",
+        args.iter()
+            .map(|it| format!("&{it}"))
+            .collect::<Vec<_>>()
+            .join(", ")
+    );
+    let name = query_name();
+    let mut buff = String::new();
+    let w = &mut buff;
+    if is_async {
+        code!(w =>
+            let mut stmt = $path();
+            c.bench_function("$name", |b| b.iter(|| runtime.block_on(async { $call })));
+        );
+    } else {
+        code!(w =>
+            let mut stmt = $path();
+            c.bench_function("$name", |b| b.iter(|| $call));
+        );
+    }
+    Ok(buff)
+}
+
+/// Generates a bench file running the given benchmarks in a transaction rolled back at
+/// the end, so that benchmarked queries leave the database untouched
+fn gen_bench_file(bench_fns: &[String], include: &str, settings: &CodegenSettings) -> String {
+    let mut buff = "// This file was generated with `cornucopia`. Do not modify.\n\n".to_string();
+    let w = &mut buff;
+    let connect = |w: &mut String| {
+        if settings.gen_sync {
+            code!(w =>
+                let mut client = postgres::Client::connect(&url, postgres::NoTls).unwrap();
+                let mut transaction = client.transaction().unwrap();
+            );
+        } else {
+            code!(w =>
+                let runtime = tokio::runtime::Runtime::new().unwrap();
+                let (mut client, connection) = runtime
+                    .block_on(tokio_postgres::connect(&url, tokio_postgres::NoTls))
+                    .unwrap();
+                runtime.spawn(connection);
+                let transaction = runtime.block_on(client.transaction()).unwrap();
+            );
+        }
+    };
+    let bench_fns = bench_fns.iter().map(|it| {
+        move |w: &mut String| {
+            code!(w => {
+                $it
+            })
+        }
+    });
+    code!(w =>
+        #[path = $include]
+        mod cornucopia;
+
+        use criterion::{criterion_group, criterion_main, Criterion};
+
+        fn bench(c: &mut Criterion) {
+            let url = std::env::var("$URL_VAR").expect("$URL_VAR should be set");
+            $!connect
+            $($!bench_fns)
+        }
+
+        criterion_group!(benches, bench);
+        criterion_main!(benches);
+    );
+    buff
+}
+
+/// Rust literal of a TOML value, arrays being turned into vectors
+fn rust_literal(value: &toml::Value, is_inner_nullable: bool) -> Option<String> {
+    Some(match value {
+        toml::Value::String(it) => format!("{it:?}"),
+        toml::Value::Integer(it) => it.to_string(),
+        toml::Value::Float(it) => format!("{it:?}"),
+        toml::Value::Boolean(it) => it.to_string(),
+        toml::Value::Array(items) => {
+            let items = items
+                .iter()
+                .map(|it| {
+                    let literal = rust_literal(it, false)?;
+                    Some(if is_inner_nullable {
+                        format!("Some({literal})")
+                    } else {
+                        literal
+                    })
+                })
+                .collect::<Option<Vec<_>>>()?;
+            format!("vec![{}]", items.join(", "))
+        }
+        toml::Value::Datetime(_) | toml::Value::Table(_) => return None,
+    })
+}
+
+/// Path of `to` relative to the directory `from`, as a string literal for a `#[path]`
+/// attribute
+fn relative_path(from: &Path, to: &Path) -> String {
+    let absolute = |path: &Path| -> PathBuf {
+        if path.is_absolute() {
+            path.to_owned()
+        } else {
+            std::env::current_dir().unwrap_or_default().join(path)
+        }
+    };
+    let (from, to) = if from.is_absolute() == to.is_absolute() {
+        (from.to_owned(), to.to_owned())
+    } else {
+        (absolute(from), absolute(to))
+    };
+    let from: Vec<_> = from
+        .components()
+        .filter(|it| *it != Component::CurDir)
+        .collect();
+    let to: Vec<_> = to
+        .components()
+        .filter(|it| *it != Component::CurDir)
+        .collect();
+    let common = from.iter().zip(&to).take_while(|(a, b)| a == b).count();
+    let segments: Vec<_> = std::iter::repeat_n("..".to_string(), from.len() - common)
+        .chain(
+            to[common..]
+                .iter()
+                .map(|it| it.as_os_str().to_string_lossy().into_owned()),
+        )
+        .collect();
+    format!("{:?}", segments.join("/"))
+}
+
+pub(crate) mod error {
+    use std::path::PathBuf;
+
+    use miette::Diagnostic;
+    use thiserror::Error as ThisError;
+
+    #[derive(Debug, ThisError, Diagnostic)]
+    pub enum Error {
+        #[error("Could not read bench fixtures `{path}`: ({err})")]
+        Io { path: PathBuf, err: std::io::Error },
+        #[error("Could not parse bench fixtures `{path}`: ({err})")]
+        Format {
+            path: PathBuf,
+            err: Box<toml::de::Error>,
+        },
+        #[error("bench fixtures reference the unknown module `{module}`")]
+        UnknownModule { module: String },
+        #[error("bench fixtures reference the unknown query `{module}::{query}`")]
+        UnknownQuery { module: String, query: String },
+        #[error("bench fixtures reference the unknown parameter `{param}` of `{query}`")]
+        UnknownParam { query: String, param: String },
+        #[error("bench fixtures are missing the parameter `{param}` of `{query}`")]
+        MissingParam { query: String, param: String },
+        #[error("the parameter `{param}` of `{query}` is not a literal")]
+        #[diagnostic(help("use strings, numbers, booleans or arrays of them"))]
+        UnsupportedValue { query: String, param: String },
+        #[error("the batch query `{query}` cannot be benchmarked")]
+        #[diagnostic(help("batch queries are not prepared, remove it from the bench fixtures"))]
+        BatchQuery { query: String },
+    }
+}
@@ -0,0 +1,115 @@
+use std::{
+    collections::BTreeMap,
+    path::{Path, PathBuf},
+};
+
+use postgres::Client;
+use serde::{Deserialize, Serialize};
+
+use crate::{
+    database::Database,
+    parser::Module,
+    snapshot::{hash, Snapshot},
+    CodegenSettings,
+};
+
+use self::error::Error;
+
+/// Version of the cache format, bumped on every incompatible change
+const CACHE_VERSION: u32 = 1;
+
+/// Name of the cache file, written next to the destination file
+const CACHE_FILE: &str = ".cornucopia-cache.json";
+
+/// Statements prepared by an incremental generation, replayed by the next one for the
+/// query modules whose file did not change
+#[derive(Debug, Serialize, Deserialize)]
+pub(crate) struct Cache {
+    version: u32,
+    /// Hash of the version of cornucopia and of the settings the code was generated with
+    settings: String,
+    /// Hash of the query files by module
+    modules: BTreeMap<String, String>,
+    /// Statements prepared for the modules
+    statements: Snapshot,
+}
+
+impl Cache {
+    /// An empty cache of the statements of `modules`, prepared using `settings`
+    pub(crate) fn new(settings: &CodegenSettings, modules: &[Module]) -> Self {
+        Self {
+            version: CACHE_VERSION,
+            settings: settings_hash(settings),
+            modules: modules
+                .iter()
+                .map(|it| (it.info.name.clone(), hash(&it.info.source)))
+                .collect(),
+            statements: Snapshot::new(),
+        }
+    }
+
+    /// Path of the cache of the code generated at `destination`
+    pub(crate) fn path(destination: &Path) -> PathBuf {
+        destination.with_file_name(CACHE_FILE)
+    }
+
+    /// Reads the cache at `path`. A missing or unreadable cache, or one written by another
+    /// version of cornucopia or with other settings, is discarded rather than reported, every
+    /// module being prepared again.
+    pub(crate) fn read(path: &Path, settings: &CodegenSettings) -> Self {
+        std::fs::read_to_string(path)
+            .ok()
+            .and_then(|content| serde_json::from_str::<Self>(&content).ok())
+            .filter(|it| it.version == CACHE_VERSION && it.settings == settings_hash(settings))
+            .unwrap_or_else(|| Self::new(settings, &[]))
+    }
+
+    pub(crate) fn write(&self, path: &Path) -> Result<(), Error> {
+        let content = serde_json::to_string_pretty(self).expect("cache is serializable");
+        std::fs::write(path, content).map_err(|err| Error {
+            path: path.to_owned(),
+            err,
+        })
+    }
+
+    /// A live database replaying the statements of the modules whose file did not change
+    /// since this cache was written, every statement being recorded in the `next` cache
+    pub(crate) fn database<'a>(
+        &'a self,
+        client: &'a mut Client,
+        next: &'a mut Self,
+    ) -> Database<'a> {
+        let unchanged = next
+            .modules
+            .iter()
+            .filter(|(name, hash)| self.modules.get(*name) == Some(hash))
+            .map(|(name, _)| name.clone())
+            .collect();
+        Database::Incremental {
+            client,
+            cache: &self.statements,
+            unchanged,
+            recording: &mut next.statements,
+        }
+    }
+}
+
+/// Hash of the version of cornucopia and of `settings`, so that changing either of them
+/// discards the cache
+fn settings_hash(settings: &CodegenSettings) -> String {
+    hash(&format!("{} {settings:?}", env!("CARGO_PKG_VERSION")))
+}
+
+pub(crate) mod error {
+    use std::path::PathBuf;
+
+    use miette::Diagnostic;
+    use thiserror::Error as ThisError;
+
+    #[derive(Debug, ThisError, Diagnostic)]
+    #[error("Could not write the incremental cache `{path}`: ({err})")]
+    pub struct Error {
+        pub(crate) path: PathBuf,
+        pub(crate) err: std::io::Error,
+    }
+}
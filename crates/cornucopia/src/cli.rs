@@ -1,8 +1,17 @@
-use std::path::PathBuf;
+use std::{ffi::OsString, path::PathBuf};
 
 use clap::{Parser, Subcommand};
 
-use crate::{conn, container, error::Error, generate_live, generate_managed, CodegenSettings};
+use crate::{
+    config::Config, conn, container, coverage_live_with_observer, error::Error, format_queries,
+    generate_live_with_observer, generate_managed_with_observer, generate_offline_with_observer,
+    generate_types_live_with_observer, progress::PrintObserver, snapshot_live_with_observer,
+    validate_query_against_schema, watch::watch, CodegenSettings, CoverageSettings, DateTimeCrate,
+    FormatSettings, NaiveTimestampPolicy, NumericCrate, TargetSet, ValidationSettings,
+};
+
+/// Name of the file the environment variables are loaded from
+const DOTENV_FILE: &str = ".env";
 
 /// Command line interface to interact with Cornucopia SQL.
 #[derive(Parser, Debug)]
@@ -11,11 +20,32 @@ struct Args {
     /// Use `podman` instead of `docker`
     #[clap(short, long)]
     podman: bool,
+    /// Retry the setup of the container this number of times after transient failures of
+    /// the container manager
+    #[clap(long, default_value_t = 2)]
+    setup_retries: u32,
+    /// Run the container from this image, like one providing the extensions of the schema
+    #[clap(long, value_name = "IMAGE", default_value = container::DEFAULT_IMAGE)]
+    container_image: String,
+    /// Report this `application_name` when connecting to the database, instead of the one
+    /// of its URL or `cornucopia`
+    #[clap(long, value_name = "NAME")]
+    application_name: Option<String>,
     /// Folder containing the queries
-    #[clap(short, long, default_value = "queries/")]
+    #[clap(
+        short,
+        long,
+        default_value = "queries/",
+        env = "CORNUCOPIA_QUERIES_PATH"
+    )]
     queries_path: PathBuf,
     /// Destination folder for generated modules
-    #[clap(short, long, default_value = "src/cornucopia.rs")]
+    #[clap(
+        short,
+        long,
+        default_value = "src/cornucopia.rs",
+        env = "CORNUCOPIA_DESTINATION"
+    )]
     destination: PathBuf,
     #[clap(subcommand)]
     action: Action,
@@ -25,9 +55,133 @@ struct Args {
     /// Generate asynchronous rust code
     #[clap(long)]
     r#async: bool,
-    /// Derive serde's `Serialize` trait for generated types.
+    /// Derive serde's `Serialize` trait on these generated structs: a comma separated list
+    /// of `rows`, `params`, `types` or `all` (rows and types when omitted)
+    #[clap(long, num_args = 0..=1, require_equals = true, default_missing_value = "rows,types")]
+    serialize: Option<TargetSet>,
+    /// Derive serde's `Deserialize` trait on these generated structs: a comma separated
+    /// list of `rows`, `params`, `types` or `all`
+    #[clap(long, require_equals = true)]
+    deserialize: Option<TargetSet>,
+    /// Generate an `IS_READ_ONLY` constant on each statement.
+    #[clap(long)]
+    read_only_flag: bool,
+    /// How returned columns of type `timestamp` (without time zone) are handled
+    #[clap(long, value_enum, default_value_t = NaiveTimestampPolicy::Allow)]
+    naive_timestamp_policy: NaiveTimestampPolicy,
+    /// Crate of the Rust types of the date and time columns and parameters
+    #[clap(long, value_enum, default_value_t = DateTimeCrate::Time)]
+    date_time_crate: DateTimeCrate,
+    /// Crate of the Rust type of the `numeric` columns and parameters
+    #[clap(long, value_enum, default_value_t = NumericCrate::RustDecimal)]
+    numeric_crate: NumericCrate,
+    /// Execute queries without named prepared statements (e.g. behind pgbouncer in transaction mode)
+    #[clap(long)]
+    no_prepared_statements: bool,
+    /// Fail instead of creating the missing parent directories of the destination file
+    #[clap(long)]
+    no_create_dirs: bool,
+    /// Remove comments and redundant whitespace from the generated queries
+    #[clap(long)]
+    normalize_sql: bool,
+    /// Generate every variant of the queries declared for different server versions,
+    /// selecting the one matching the server at runtime
+    #[clap(long)]
+    all_variants: bool,
+    /// Fail when the nullability of a composite field is neither declared nor verified
+    #[clap(long)]
+    strict_nullability: bool,
+    /// Infer the nullability of the undeclared columns from the catalog, only plain table
+    /// columns declared `NOT NULL` being non-nullable
+    #[clap(long)]
+    infer_nullability: bool,
+    /// Merge the uses of a named type whose fields only differ by their nullability, the
+    /// fields nullable in one of them being nullable
+    #[clap(long)]
+    merge_nullability: bool,
+    /// TOML file mapping `schema.table.column` to `nullable` or `not_null`, declaring the
+    /// nullability of these columns for every query reading them
+    #[clap(long, value_name = "FILE")]
+    nullability_file: Option<PathBuf>,
+    /// Report the queries returning columns whose comment starts with this marker, none
+    /// being reported when it is empty
+    #[clap(long, value_name = "MARKER", default_value = "DEPRECATED")]
+    deprecation_marker: String,
+    /// Don't mark the row fields read from deprecated columns `#[deprecated]`
+    #[clap(long)]
+    no_deprecated_fields: bool,
+    /// Continue past the query modules that fail, reporting all their errors and the number
+    /// of failed and passed modules
+    #[clap(long)]
+    keep_going: bool,
+    /// Generate `From` conversions between the explicitly named rows of a module whose
+    /// fields are a subset of one another
+    #[clap(long)]
+    row_conversions: bool,
+    /// Generate a builder along with each explicitly named params struct
+    #[clap(long)]
+    params_builders: bool,
+    /// Generate `From` conversions from the explicitly named rows of a module to its
+    /// explicitly named params structs whose fields are a subset of theirs
+    #[clap(long)]
+    param_conversions: bool,
+    /// Generate helpers preparing transactions for two-phase commits in a `two_phase` module
+    #[clap(long)]
+    two_phase_commit: bool,
+    /// Generate the number of labels of each enum and a `verify` function comparing them
+    /// with the database at runtime
+    #[clap(long)]
+    enum_guards: bool,
+    /// Implement `Display` on the row structs, printing their fields as `name=value`
+    #[clap(long)]
+    display: bool,
+    /// Reference custom types through this path (e.g. `db_types` or `crate::db_types`)
+    /// instead of generating them, see the `types` command
+    #[clap(long)]
+    types_path: Option<String>,
+    /// Return this error type (e.g. `crate::AppError`) from the generated queries, which
+    /// must implement `From` the error of the client and of the query error types
+    #[clap(long)]
+    error_type: Option<String>,
+    /// Write a criterion bench file per module in this directory, benchmarking the queries
+    /// listed in its `benches.toml` file
     #[clap(long)]
-    serialize: bool,
+    emit_benches: Option<PathBuf>,
+    /// Embed the `.sql` migration files of this directory in a `MIGRATIONS` constant of the
+    /// generated code, applied at runtime by `cornucopia::run_embedded_migrations`
+    #[clap(long, value_name = "DIR")]
+    embed_migrations: Option<PathBuf>,
+    /// Only prepare the query modules changed since the previous live generation, caching
+    /// the prepared statements in a `.cornucopia-cache.json` file next to the destination
+    #[clap(long)]
+    incremental: bool,
+    /// Prepare every query as each of these roles, generating code as the first one
+    #[clap(long, value_delimiter = ',')]
+    roles: Vec<String>,
+    /// Generate only the queries matching this `module::query` pattern, in which `*`
+    /// matches any sequence of characters (e.g. `authors::*`), along with the rows, params
+    /// and types they use. Can be repeated
+    #[clap(long, value_name = "PATTERN")]
+    only: Vec<String>,
+    /// Generate only the queries tagged with this `key=value` pair by their `tag` option
+    /// (e.g. `team=payments`), along with those matching `--only`. Also filters the queries
+    /// checked by `coverage`. Can be repeated
+    #[clap(long, value_name = "KEY=VALUE")]
+    only_tag: Vec<String>,
+    /// TOML configuration file, mapping `table.column` or domain names to newtypes in
+    /// a `[newtypes]` table, and listing patterns of fields hidden from `Debug` output
+    /// in a `redact` array. `${VAR}` references are replaced by the value of these
+    /// environment variables
+    #[clap(long, env = "CORNUCOPIA_CONFIG")]
+    config: Option<PathBuf>,
+    /// Don't load environment variables from the `.env` file of the current directory or
+    /// its ancestors
+    #[clap(long)]
+    no_dotenv: bool,
+    /// Print which `.env` file the environment variables were loaded from, and the progress
+    /// of code generation
+    #[clap(short, long)]
+    verbose: bool,
 }
 
 #[derive(Debug, Subcommand)]
@@ -35,51 +189,421 @@ enum Action {
     /// Generate your modules against your own db
     Live {
         /// Postgres url to the database
+        #[clap(env = "DATABASE_URL")]
         url: String,
     },
+    /// Generate your modules against your own db, then again whenever a query file
+    /// changes, until Ctrl-C is pressed
+    Watch {
+        /// Postgres url to the database
+        #[clap(env = "DATABASE_URL")]
+        url: String,
+        /// Also regenerate when a file of this directory of migrations changes, which
+        /// defaults to the one of `--embed-migrations`
+        #[clap(long, value_name = "DIR")]
+        migrations: Option<PathBuf>,
+    },
     /// Generate your modules against schema files
     Schema {
         /// SQL files containing the database schema
         schema_files: Vec<PathBuf>,
     },
+    /// Record a snapshot of your queries prepared against your own db, to generate
+    /// your modules later without any db
+    Snapshot {
+        /// Postgres url to the database
+        #[clap(env = "DATABASE_URL")]
+        url: String,
+        /// Destination file of the snapshot
+        #[clap(long)]
+        out: PathBuf,
+    },
+    /// Generate the custom types of your own db, to be shared by the modules of several
+    /// crates generated using `--types-path`
+    Types {
+        /// Postgres url to the database
+        #[clap(env = "DATABASE_URL")]
+        url: String,
+        /// Destination file of the types
+        #[clap(long)]
+        out: PathBuf,
+    },
+    /// Generate your modules from a snapshot, without any db
+    Offline {
+        /// Snapshot recorded using the `snapshot` command
+        #[clap(long)]
+        snapshot: PathBuf,
+    },
+    /// Validate a single SQL query against your own db
+    Validate {
+        /// Postgres url to the database
+        #[clap(env = "DATABASE_URL")]
+        url: String,
+        /// SQL query to validate, using positional parameters (`$1`, `$2`, ...)
+        #[clap(long)]
+        sql: String,
+        /// Name of a returned column that should be considered nullable
+        #[clap(long = "nullable")]
+        nullable_columns: Vec<String>,
+    },
+    /// List the columns of your own db that no query references, and the tables without
+    /// any query
+    Coverage {
+        /// Postgres url to the database
+        #[clap(env = "DATABASE_URL")]
+        url: String,
+        /// Format of the report
+        #[clap(long, value_enum, default_value_t = ReportFormat::Text)]
+        format: ReportFormat,
+        /// Also report the columns of views and materialized views
+        #[clap(long)]
+        include_views: bool,
+        /// Also report the tables of the system schemas
+        #[clap(long)]
+        include_system_schemas: bool,
+        /// Also list the queries declaring no latency budget
+        #[clap(long)]
+        require_budgets: bool,
+    },
+    /// Format your query files in place, normalizing their annotations and the blank lines
+    /// between them
+    Fmt {
+        /// Don't write the files, failing if some of them are not formatted
+        #[clap(long)]
+        check: bool,
+        /// Also reflow the SQL, trimming trailing whitespace and collapsing blank lines
+        #[clap(long)]
+        sql: bool,
+    },
+}
+
+/// Format of a report printed by the CLI
+#[derive(Debug, Clone, Copy, clap::ValueEnum)]
+enum ReportFormat {
+    /// Human-readable table
+    Text,
+    Json,
 }
 
 // Main entrypoint of the CLI. Parses the args and calls the appropriate routines.
 pub fn run() -> Result<(), Error> {
+    match run_from(std::env::args_os()) {
+        // Help, version and invalid args are printed by clap
+        Err(Error::Cli(self::error::Error::Args(err))) => err.exit(),
+        result => result,
+    }
+}
+
+/// Runs the CLI with the given args, the first one being the name of the binary.
+///
+/// Unless `--no-dotenv` is given, the variables of the closest `.env` file are loaded
+/// first, without overriding the actual environment. Values are then taken from the
+/// args, then from the environment, then from the defaults.
+pub fn run_from<I, T>(args: I) -> Result<(), Error>
+where
+    I: IntoIterator<Item = T>,
+    T: Into<OsString>,
+{
+    let args: Vec<OsString> = args.into_iter().map(Into::into).collect();
+    // Variables must be loaded before parsing the args they provide
+    let dotenv = if args.iter().any(|it| it == "--no-dotenv") {
+        None
+    } else {
+        load_dotenv()?
+    };
     let Args {
         podman,
+        setup_retries,
+        container_image,
+        application_name,
         queries_path,
         destination,
         action,
         sync,
         r#async,
         serialize,
-    } = Args::parse();
+        deserialize,
+        read_only_flag,
+        naive_timestamp_policy,
+        date_time_crate,
+        numeric_crate,
+        no_prepared_statements,
+        no_create_dirs,
+        normalize_sql,
+        all_variants,
+        strict_nullability,
+        infer_nullability,
+        merge_nullability,
+        nullability_file,
+        deprecation_marker,
+        no_deprecated_fields,
+        keep_going,
+        row_conversions,
+        params_builders,
+        param_conversions,
+        two_phase_commit,
+        enum_guards,
+        display,
+        types_path,
+        error_type,
+        emit_benches,
+        embed_migrations,
+        incremental,
+        roles,
+        only,
+        only_tag,
+        config,
+        no_dotenv: _,
+        verbose,
+    } = Args::try_parse_from(args).map_err(self::error::Error::Args)?;
+    if verbose {
+        match &dotenv {
+            Some(path) => eprintln!("Loaded environment variables from `{}`", path.display()),
+            None => eprintln!("No `{DOTENV_FILE}` file loaded"),
+        }
+    }
 
-    let settings = CodegenSettings {
-        gen_async: r#async || !sync,
-        gen_sync: sync,
-        derive_ser: serialize,
+    let config = config
+        .map(|path| Config::read(&path))
+        .transpose()?
+        .unwrap_or_default();
+
+    let settings = config
+        .apply(CodegenSettings::builder())
+        .async_(r#async || !sync)
+        .sync(sync)
+        .serialize(serialize.unwrap_or_default())
+        .deserialize(deserialize.unwrap_or_default())
+        .generate_read_only_flag(read_only_flag)
+        .naive_timestamp_policy(naive_timestamp_policy)
+        .date_time_crate(date_time_crate)
+        .numeric_crate(numeric_crate)
+        .prepared_statements(!no_prepared_statements)
+        .create_destination_dirs(!no_create_dirs)
+        .normalize_sql(normalize_sql)
+        .all_variants(all_variants)
+        .strict_nullability(strict_nullability)
+        .infer_nullability(infer_nullability)
+        .merge_nullability(merge_nullability)
+        .deprecation_marker(deprecation_marker)
+        .deprecated_fields(!no_deprecated_fields)
+        .keep_going(keep_going)
+        .row_conversions(row_conversions)
+        .params_builders(params_builders)
+        .param_conversions(param_conversions)
+        .two_phase_commit(two_phase_commit)
+        .enum_guards(enum_guards)
+        .display(display)
+        .incremental(incremental)
+        .setup_retries(setup_retries)
+        .container_image(container_image)
+        .roles(roles);
+    let settings = only
+        .into_iter()
+        .fold(settings, |settings, pattern| settings.only(pattern));
+    let settings = only_tag
+        .iter()
+        .fold(settings, |settings, tag| settings.only_tag(tag));
+    let settings = match types_path {
+        Some(types_path) => settings.types_path(types_path),
+        None => settings,
+    };
+    let settings = match error_type {
+        Some(error_type) => settings.error_type(error_type),
+        None => settings,
     };
+    let settings = match emit_benches {
+        Some(dir) => settings.emit_benches(dir),
+        None => settings,
+    };
+    let settings = match nullability_file {
+        Some(path) => settings.nullability_file(path),
+        None => settings,
+    };
+    let settings = match embed_migrations {
+        Some(dir) => settings.embed_migrations(dir),
+        None => settings,
+    };
+    let settings = match &application_name {
+        Some(name) => settings.application_name(name),
+        None => settings,
+    }
+    .build();
 
+    // Connects to the database at `url`, printing the effective application name
+    let connect = |url: &str| -> Result<postgres::Client, conn::error::Error> {
+        let config = conn::url_config(url, application_name.as_deref())?;
+        if verbose {
+            let name = config.get_application_name().unwrap_or_default();
+            eprintln!("Connecting as the application `{name}`");
+        }
+        Ok(config.connect(postgres::NoTls)?)
+    };
+    let observer = &mut PrintObserver { verbose };
     match action {
         Action::Live { url } => {
-            let mut client = conn::from_url(&url)?;
-            generate_live(&mut client, &queries_path, Some(&destination), settings)?;
+            let mut client = connect(&url)?;
+            generate_live_with_observer(
+                &mut client,
+                &queries_path,
+                Some(&destination),
+                settings,
+                observer,
+            )?;
+        }
+        Action::Watch { url, migrations } => {
+            let migrations = migrations.or_else(|| settings.embed_migrations().map(Into::into));
+            let paths: Vec<_> = [Some(queries_path.as_path()), migrations.as_deref()]
+                .into_iter()
+                .chain([settings.nullability_file()])
+                .flatten()
+                .collect();
+            watch(&paths, || {
+                let mut client = connect(&url)?;
+                generate_live_with_observer(
+                    &mut client,
+                    &queries_path,
+                    Some(&destination),
+                    settings.clone(),
+                    observer,
+                )?;
+                Ok(())
+            })?;
         }
         Action::Schema { schema_files } => {
+            if verbose {
+                let name = application_name
+                    .as_deref()
+                    .unwrap_or(conn::DEFAULT_APPLICATION_NAME);
+                eprintln!("Connecting as the application `{name}`");
+            }
             // Run the generate command. If the command is unsuccessful, cleanup Cornucopia's container
-            if let Err(e) = generate_managed(
+            if let Err(e) = generate_managed_with_observer(
                 queries_path,
                 &schema_files,
                 Some(destination),
                 podman,
                 settings,
+                observer,
             ) {
                 container::cleanup(podman).ok();
                 return Err(e);
             }
         }
+        Action::Snapshot { url, out } => {
+            let mut client = connect(&url)?;
+            snapshot_live_with_observer(&mut client, &queries_path, &out, observer)?;
+        }
+        Action::Types { url, out } => {
+            let mut client = connect(&url)?;
+            generate_types_live_with_observer(&mut client, Some(&out), settings, observer)?;
+        }
+        Action::Offline { snapshot } => {
+            generate_offline_with_observer(
+                &queries_path,
+                &snapshot,
+                Some(&destination),
+                settings,
+                observer,
+            )?;
+        }
+        Action::Validate {
+            url,
+            sql,
+            nullable_columns,
+        } => {
+            let mut client = connect(&url)?;
+            let validated = validate_query_against_schema(
+                &mut client,
+                &sql,
+                &ValidationSettings { nullable_columns },
+            )?;
+            println!("params:");
+            for (i, ty) in validated.param_types.iter().enumerate() {
+                println!("  ${}: {ty}", i + 1);
+            }
+            println!("columns:");
+            for (name, ty, nullable) in &validated.column_types {
+                if *nullable {
+                    println!("  {name}: Option<{ty}>");
+                } else {
+                    println!("  {name}: {ty}");
+                }
+            }
+        }
+        Action::Coverage {
+            url,
+            format,
+            include_views,
+            include_system_schemas,
+            require_budgets,
+        } => {
+            let mut client = connect(&url)?;
+            let coverage = coverage_live_with_observer(
+                &mut client,
+                &queries_path,
+                &CoverageSettings {
+                    include_views,
+                    include_system_schemas,
+                    only_tags: only_tag,
+                    require_budgets,
+                },
+                observer,
+            )?;
+            match format {
+                ReportFormat::Text => print!("{coverage}"),
+                ReportFormat::Json => println!("{}", coverage.to_json()),
+            }
+        }
+        Action::Fmt { check, sql } => {
+            let files = format_queries(&queries_path, &FormatSettings { check, sql })?;
+            if check && !files.is_empty() {
+                return Err(crate::format_queries::error::Error::Unformatted { files }.into());
+            }
+            for file in files {
+                println!("Formatted `{}`", file.display());
+            }
+        }
     };
     Ok(())
 }
+
+/// Loads the variables of the `.env` file of the current directory or its closest ancestor
+/// that are not already set, returning the path of this file
+fn load_dotenv() -> Result<Option<PathBuf>, self::error::Error> {
+    let current_dir = std::env::current_dir().map_err(|err| self::error::Error::Dotenv {
+        path: PathBuf::from(DOTENV_FILE),
+        err: dotenvy::Error::Io(err),
+    })?;
+    // Paths are relative to the current directory, like the other paths of the CLI
+    let Some(path) = (0..current_dir.ancestors().count())
+        .map(|depth| {
+            std::iter::repeat_n("..", depth)
+                .collect::<PathBuf>()
+                .join(DOTENV_FILE)
+        })
+        .find(|path| path.is_file())
+    else {
+        return Ok(None);
+    };
+    dotenvy::from_path(&path).map_err(|err| self::error::Error::Dotenv {
+        path: path.clone(),
+        err,
+    })?;
+    Ok(Some(path))
+}
+
+pub(crate) mod error {
+    use std::path::PathBuf;
+
+    use miette::Diagnostic;
+    use thiserror::Error as ThisError;
+
+    #[derive(Debug, ThisError, Diagnostic)]
+    pub enum Error {
+        #[error("{0}")]
+        Args(clap::Error),
+        #[error("Could not load environment variables from `{path}`: ({err})")]
+        Dotenv { path: PathBuf, err: dotenvy::Error },
+    }
+}
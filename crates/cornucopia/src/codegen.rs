@@ -1,15 +1,20 @@
 use core::str;
 use std::fmt::{Display, Write};
 
+use std::rc::Rc;
+
 use codegen_template::code;
+use heck::ToShoutySnakeCase;
 use indexmap::IndexMap;
+use postgres_types::{Kind, Type};
 
 use crate::{
     prepare_queries::{
-        Ident, Preparation, PreparedContent, PreparedField, PreparedItem, PreparedModule,
+        Arity, Ident, Preparation, PreparedContent, PreparedField, PreparedItem, PreparedModule,
         PreparedQuery, PreparedType,
     },
-    CodegenSettings,
+    type_registrar::{CornucopiaType, VECTOR_STRUCT, VECTOR_TYPE},
+    CodegenSettings, TargetSet,
 };
 
 pub struct GenCtx {
@@ -17,22 +22,75 @@ pub struct GenCtx {
     pub depth: u8,
     // Should use async client and generate async code
     pub is_async: bool,
-    // Should serializable struct
-    pub gen_derive: bool,
+    // Structs deriving serde traits
+    pub serialize: TargetSet,
+    pub deserialize: TargetSet,
+    // Path of the shared custom types, if they are not generated in the `types` module
+    pub types_path: Option<String>,
+    // Path of the error type returned by queries, instead of the client's
+    pub error_type: Option<String>,
+    // Clients of the generated `verify` functions of enums, as `(sync, async)`
+    pub enum_guards: (bool, bool),
 }
 
 impl GenCtx {
-    pub fn new(depth: u8, is_async: bool, gen_derive: bool) -> Self {
+    pub fn new(depth: u8, is_async: bool) -> Self {
         Self {
             depth,
             is_async,
-            gen_derive,
+            serialize: TargetSet::NONE,
+            deserialize: TargetSet::NONE,
+            types_path: None,
+            error_type: None,
+            enum_guards: (false, false),
+        }
+    }
+
+    pub fn with_derives(mut self, serialize: TargetSet, deserialize: TargetSet) -> Self {
+        self.serialize = serialize;
+        self.deserialize = deserialize;
+        self
+    }
+
+    /// Serde traits derived on the structs of this target, as a comma terminated list
+    pub fn serde_derives(&self, is_target: fn(&TargetSet) -> bool) -> &'static str {
+        match (is_target(&self.serialize), is_target(&self.deserialize)) {
+            (true, true) => "serde::Serialize,serde::Deserialize,",
+            (true, false) => "serde::Serialize,",
+            (false, true) => "serde::Deserialize,",
+            (false, false) => "",
+        }
+    }
+
+    pub fn with_types_path(mut self, types_path: Option<&str>) -> Self {
+        self.types_path = types_path.map(String::from);
+        self
+    }
+
+    pub fn with_error_type(mut self, error_type: Option<&str>) -> Self {
+        self.error_type = error_type.map(String::from);
+        self
+    }
+
+    pub fn with_enum_guards(mut self, settings: &CodegenSettings) -> Self {
+        if settings.gen_enum_guards {
+            self.enum_guards = (settings.gen_sync, settings.gen_async);
+        }
+        self
+    }
+
+    /// Error type returned by the queries that do not handle error codes
+    pub fn error_type(&self) -> String {
+        match &self.error_type {
+            Some(path) => path.clone(),
+            None if self.is_async => "tokio_postgres::Error".to_string(),
+            None => "postgres::Error".to_string(),
         }
     }
 
     pub fn path(&self, depth: u8, name: impl Display) -> String {
-        let depth = std::iter::repeat("super::").take(depth as usize);
-        code!($($depth)$name)
+        let depth = "super::".repeat(depth as usize);
+        code!($depth$name)
     }
 
     pub fn client_name(&self) -> &'static str {
@@ -99,12 +157,39 @@ impl PreparedField {
     }
 }
 
-fn enum_sql(w: &mut impl Write, name: &str, enum_name: &str, variants: &[Ident]) {
+fn enum_sql<W: Write>(
+    w: &mut W,
+    schema: &str,
+    name: &str,
+    enum_name: &str,
+    variants: &[Ident],
+    ctx: &GenCtx,
+) {
     let enum_names = std::iter::repeat(enum_name);
     let db_variants_ident = variants.iter().map(|v| &v.db);
     let rs_variants_ident = variants.iter().map(|v| &v.rs);
 
-    let nb_variants = variants.len();
+    // With guards, the enum of the database may have gained labels, which `verify` and the
+    // decoding report. Otherwise, its labels must be the generated ones.
+    let accepts_variants = |w: &mut W| {
+        let db_variants_ident = variants.iter().map(|v| &v.db);
+        if ctx.enum_guards != (false, false) {
+            code!(w =>
+                [$("$db_variants_ident",)].iter().all(|label| variants.iter().any(|v| v.as_str() == *label))
+            );
+        } else {
+            let nb_variants = variants.len();
+            code!(w =>
+                if variants.len() != $nb_variants {
+                    return false;
+                }
+                variants.iter().all(|v| match &**v {
+                    $("$db_variants_ident" => true,)
+                    _ => false,
+                })
+            );
+        }
+    };
     code!(w =>
         impl<'a> postgres_types::ToSql for $enum_name {
             fn to_sql(
@@ -124,13 +209,7 @@ fn enum_sql(w: &mut impl Write, name: &str, enum_name: &str, variants: &[Ident])
                 }
                 match *ty.kind() {
                     postgres_types::Kind::Enum(ref variants) => {
-                        if variants.len() != $nb_variants {
-                            return false;
-                        }
-                        variants.iter().all(|v| match &**v {
-                            $("$db_variants_ident" => true,)
-                            _ => false,
-                        })
+                        $!accepts_variants
                     }
                     _ => false,
                 }
@@ -151,7 +230,10 @@ fn enum_sql(w: &mut impl Write, name: &str, enum_name: &str, variants: &[Ident])
                 match std::str::from_utf8(buf)? {
                     $("$db_variants_ident" => Ok($enum_names::$rs_variants_ident),)
                     s => Result::Err(Into::into(format!(
-                        "invalid variant `{}`",
+                        concat!(
+                            "unknown label `{}` of the enum `$schema.$name`, ",
+                            "the code must be regenerated to decode it",
+                        ),
                         s
                     ))),
                 }
@@ -162,13 +244,7 @@ fn enum_sql(w: &mut impl Write, name: &str, enum_name: &str, variants: &[Ident])
                 }
                 match *ty.kind() {
                     postgres_types::Kind::Enum(ref variants) => {
-                        if variants.len() != $nb_variants {
-                            return false;
-                        }
-                        variants.iter().all(|v| match &**v {
-                            $("$db_variants_ident" => true,)
-                            _ => false,
-                        })
+                        $!accepts_variants
                     }
                     _ => false,
                 }
@@ -177,6 +253,141 @@ fn enum_sql(w: &mut impl Write, name: &str, enum_name: &str, variants: &[Ident])
     );
 }
 
+/// Generates the number of labels of an enum and its `verify` function, comparing the
+/// labels of the database with the generated ones at runtime
+fn enum_guard<W: Write>(
+    w: &mut W,
+    schema: &str,
+    name: &str,
+    enum_name: &str,
+    variants: &[Ident],
+    ctx: &GenCtx,
+) {
+    let (gen_sync, gen_async) = ctx.enum_guards;
+    let labels = variants.iter().map(|v| &v.db);
+    let nb_variants = variants.len();
+    let sync_fn = |w: &mut W| {
+        if gen_sync {
+            code!(w =>
+                #[doc = " Checks that the labels of the enum in the database are the generated ones,"]
+                #[doc = " reporting the missing and extra labels otherwise. Call it at startup."]
+                pub fn verify<C: postgres::GenericClient>(client: &mut C) -> Result<(), cornucopia_sync::EnumDrift> {
+                    cornucopia_sync::private::verify_enum(client, "$schema", "$name", &[$("$labels",)])
+                }
+            );
+        }
+    };
+    let async_fn = |w: &mut W| {
+        if gen_async {
+            // Both clients being generated, the sync one keeps the plain name
+            let fn_name = if gen_sync { "verify_async" } else { "verify" };
+            code!(w =>
+                #[doc = " Checks that the labels of the enum in the database are the generated ones,"]
+                #[doc = " reporting the missing and extra labels otherwise. Call it at startup."]
+                pub async fn $fn_name<C: cornucopia_async::GenericClient>(client: &C) -> Result<(), cornucopia_async::EnumDrift> {
+                    cornucopia_async::private::verify_enum(client, "$schema", "$name", &[$("$labels",)]).await
+                }
+            );
+        }
+    };
+    code!(w =>
+        impl $enum_name {
+            #[doc = " Number of labels of the enum when the code was generated"]
+            pub const LABEL_COUNT: usize = $nb_variants;
+            $!sync_fn
+            $!async_fn
+        }
+    );
+}
+
+/// Generates the key of each advisory lock of the module
+fn gen_lock_keys(w: &mut impl Write, module: &PreparedModule) {
+    for lock in &module.locks {
+        let name = &lock.name.value;
+        let const_name = format!("{}_LOCK_KEY", name.to_shouty_snake_case());
+        let key = lock.key;
+        code!(w =>
+            #[doc = " Key of the advisory lock `$name`, the FNV-1a 64-bit hash of its name"]
+            pub const $const_name: i64 = $key;
+        );
+    }
+}
+
+/// Generates the functions acquiring and releasing each advisory lock of the module
+fn gen_lock_fns<W: Write>(w: &mut W, module: &PreparedModule, ctx: &GenCtx) {
+    let client_name = ctx.client_name();
+    let (client_mut, fn_async, fn_await) = if ctx.is_async {
+        ("", "async", ".await")
+    } else {
+        ("mut ", "", "")
+    };
+    let error_ty = ctx.error_type();
+    for lock in &module.locks {
+        let name = &lock.name.value;
+        let key = ctx.path(
+            ctx.depth - 2,
+            format!("{}_LOCK_KEY", name.to_shouty_snake_case()),
+        );
+        let fns = [
+            (
+                "lock",
+                "pg_advisory_lock",
+                " Acquires the advisory lock `$name` for the session, waiting until it is available",
+            ),
+            (
+                "try_lock",
+                "pg_try_advisory_lock",
+                " Acquires the advisory lock `$name` for the session if it is available, returning whether it was",
+            ),
+            (
+                "unlock",
+                "pg_advisory_unlock",
+                " Releases the advisory lock `$name` held by the session, returning whether it was held",
+            ),
+            (
+                "xact_lock",
+                "pg_advisory_xact_lock",
+                " Acquires the advisory lock `$name` until the end of the transaction, waiting until it is available",
+            ),
+            (
+                "try_xact_lock",
+                "pg_try_advisory_xact_lock",
+                " Acquires the advisory lock `$name` until the end of the transaction if it is available, returning whether it was",
+            ),
+        ];
+        for (prefix, function, doc) in fns {
+            let doc = doc.replace("$name", name);
+            // Only the lock functions return nothing, the others return whether they succeeded
+            let (helper, ret) = if function.contains("_try_") || function.ends_with("unlock") {
+                ("try_advisory_lock", "bool")
+            } else {
+                ("advisory_lock", "()")
+            };
+            let query = format!("SELECT {function}($1)");
+            let call = |w: &mut W| match &ctx.error_type {
+                Some(_) => {
+                    code!(w => Ok($client_name::private::$helper(client, "$query", $key)$fn_await?))
+                }
+                None => code!(w => $client_name::private::$helper(client, "$query", $key)$fn_await),
+            };
+            code!(w =>
+                #[doc = "$doc"]
+                pub $fn_async fn ${prefix}_$name<C: GenericClient>(client: &$client_mut C) -> Result<$ret, $error_ty> {
+                    $!call
+                }
+            );
+        }
+    }
+}
+
+/// Documents the name an identifier had before it was renamed, to trace it back to the
+/// queries
+fn renamed_doc(w: &mut impl Write, from: &str) {
+    code!(w =>
+        #[doc = " Renamed from `$from`."]
+    );
+}
+
 fn struct_tosql(
     w: &mut impl Write,
     struct_name: &str,
@@ -304,7 +515,7 @@ fn composite_fromsql(
     );
 }
 
-fn gen_params_struct(w: &mut impl Write, params: &PreparedItem, ctx: &GenCtx) {
+fn gen_params_struct(w: &mut impl Write, params: &PreparedItem, has_builder: bool, ctx: &GenCtx) {
     let PreparedItem {
         name,
         fields,
@@ -324,11 +535,217 @@ fn gen_params_struct(w: &mut impl Write, params: &PreparedItem, ctx: &GenCtx) {
             .collect::<Vec<_>>();
         let fields_name = fields.iter().map(|p| &p.ident.rs);
         let traits_idx = (1..=traits.len()).map(idx_char);
+        // Borrowed composites with array or JSON fields cannot derive serde traits
+        let serde = if fields.iter().all(|f| f.ty.is_serde_param()) {
+            ctx.serde_derives(TargetSet::params)
+        } else {
+            ""
+        };
+        let fields_attr = serde_borrow_attrs(serde, fields)
+            .zip(declared_attrs(fields))
+            .map(|(borrow, declared)| format!("{borrow}{declared}"));
+        let derive = derive_attr(&format!("{serde}{copy}Debug"), fields);
+        if let Some(from) = &params.renamed_from {
+            renamed_doc(w, from);
+        }
         code!(w =>
-            #[derive($copy Debug)]
+            $derive
             pub struct $name<$lifetime $($traits_idx: $traits,)> {
+                $($fields_attr pub $fields_name: $fields_ty,)
+            }
+        );
+        let traits_idx = (1..=traits.len()).map(idx_char);
+        let generics = code!(<$lifetime $($traits_idx: $traits + std::fmt::Debug,)>);
+        let traits_idx = (1..=traits.len()).map(idx_char);
+        let args = code!(<$lifetime $($traits_idx,)>);
+        gen_redacted_debug(w, &name.value, &generics, &args, fields);
+        if has_builder {
+            let traits_idx = (1..=traits.len()).map(idx_char);
+            let generics = code!(<$lifetime $($traits_idx: $traits,)>);
+            gen_params_builder(w, &name.value, &generics, &args, fields, &fields_ty, ctx);
+        }
+    }
+}
+
+/// Generates the builder of a params struct, with a setter per field. Nullable fields
+/// default to `None`, while building fails on the other fields that were not set.
+fn gen_params_builder(
+    w: &mut impl Write,
+    name: &str,
+    generics: &str,
+    args: &str,
+    fields: &[PreparedField],
+    fields_ty: &[String],
+    ctx: &GenCtx,
+) {
+    let client_name = ctx.client_name();
+    let fields_name = fields.iter().map(|p| &p.ident.rs);
+    code!(w =>
+        impl $generics $name $args {
+            pub fn builder() -> ${name}Builder $args {
+                ${name}Builder {
+                    $($fields_name: None,)
+                }
+            }
+        }
+    );
+    let fields_name = fields.iter().map(|p| &p.ident.rs);
+    let setters = fields.iter().zip(fields_ty).map(|(f, ty)| {
+        let field = &f.ident.rs;
+        code!(pub fn $field(mut self, $field: $ty) -> Self {
+            self.$field = Some($field);
+            self
+        })
+    });
+    let fields_value = fields.iter().map(|f| {
+        let field = &f.ident.rs;
+        if f.is_nullable {
+            format!("self.{field}.unwrap_or(None)")
+        } else {
+            let label = field.trim_start_matches("r#");
+            format!("self.{field}.ok_or({client_name}::BuilderError {{ params: \"{name}\", field: \"{label}\" }})?")
+        }
+    });
+    let fields_built = fields.iter().map(|p| &p.ident.rs);
+    code!(w =>
+        #[doc = " Builder of [`$name`], created using `$name::builder`"]
+        pub struct ${name}Builder $generics {
+            $($fields_name: Option<$fields_ty>,)
+        }
+        impl $generics ${name}Builder $args {
+            $($setters)
+            pub fn build(self) -> Result<$name $args, $client_name::BuilderError> {
+                Ok($name {
+                    $($fields_built: $fields_value,)
+                })
+            }
+        }
+    );
+}
+
+/// `derive` attribute of these comma separated traits, without `Debug` when some fields
+/// are redacted as `gen_redacted_debug` implements it instead
+fn derive_attr(traits: &str, fields: &[PreparedField]) -> String {
+    let is_redacted = fields.iter().any(|f| f.is_redacted);
+    let traits: Vec<_> = traits
+        .split(',')
+        .map(str::trim)
+        .filter(|it| !it.is_empty() && (!is_redacted || *it != "Debug"))
+        .collect();
+    if traits.is_empty() {
+        String::new()
+    } else {
+        format!("#[derive({})]", traits.join(", "))
+    }
+}
+
+/// `#[serde(borrow)]` attributes of the fields borrowing composites, when deriving
+/// `Deserialize` using these derives
+fn serde_borrow_attrs<'a>(
+    derives: &str,
+    fields: &'a [PreparedField],
+) -> impl Iterator<Item = &'static str> + Clone + 'a {
+    let is_deserialized = derives.contains("Deserialize");
+    fields.iter().map(move |f| {
+        if is_deserialized && f.ty.is_borrowed_composite() {
+            "#[serde(borrow)]"
+        } else {
+            ""
+        }
+    })
+}
+
+/// Attributes declared in the annotations of the fields, emitted verbatim above them
+fn declared_attrs(fields: &[PreparedField]) -> impl Iterator<Item = String> + Clone + '_ {
+    fields.iter().map(|f| f.attributes.concat())
+}
+
+/// `#[deprecated]` attributes of the fields read from deprecated columns
+fn deprecated_attrs(fields: &[PreparedField]) -> impl Iterator<Item = String> + Clone + '_ {
+    fields.iter().map(|f| {
+        f.deprecation
+            .as_ref()
+            .map(|note| format!("#[deprecated(note = {note:?})]"))
+            .unwrap_or_default()
+    })
+}
+
+/// Implements `Debug` printing `***` instead of the value of redacted fields
+fn gen_redacted_debug(
+    w: &mut impl Write,
+    name: &str,
+    generics: &str,
+    args: &str,
+    fields: &[PreparedField],
+) {
+    if !fields.iter().any(|f| f.is_redacted) {
+        return;
+    }
+    let fields_label = fields.iter().map(|f| f.ident.rs.trim_start_matches("r#"));
+    let fields_value = fields.iter().map(|f| {
+        if f.is_redacted {
+            "&\"***\"".to_string()
+        } else {
+            format!("&self.{}", f.ident.rs)
+        }
+    });
+    code!(w =>
+        impl$generics std::fmt::Debug for $name$args {
+            fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+                f.debug_struct("$name")
+                    $(.field("$fields_label", $fields_value))
+                    .finish()
+            }
+        }
+    );
+}
+
+/// Generates the structs of the anonymous records returned by the queries of a module,
+/// decoding the fields declared by their annotation
+fn gen_record_structs(w: &mut impl Write, module: &PreparedModule, ctx: &GenCtx) {
+    let mut generated = Vec::new();
+    for field in module.rows.values().flat_map(|it| &it.fields) {
+        let CornucopiaType::Record {
+            struct_name,
+            column,
+            fields,
+            field_types,
+            ..
+        } = field.ty.as_ref()
+        else {
+            continue;
+        };
+        if generated.contains(&struct_name) {
+            continue;
+        }
+        generated.push(struct_name);
+        let serde = ctx.serde_derives(TargetSet::rows);
+        let client_name = ctx.client_name();
+        let len = fields.len();
+        let fields_name = fields.iter().map(|it| &it.ident.rs);
+        let fields_ty = fields.iter().map(|it| it.own_struct(ctx));
+        let read_name = fields.iter().map(|it| &it.ident.rs);
+        let db_name = fields.iter().map(|it| &it.ident.db);
+        let oid = field_types.iter().map(Type::oid);
+        code!(w =>
+            #[derive($serde Debug, Clone, PartialEq)]
+            pub struct $struct_name {
                 $(pub $fields_name: $fields_ty,)
             }
+            impl<'a> postgres_types::FromSql<'a> for $struct_name {
+                fn from_sql(_ty: &postgres_types::Type, raw: &'a [u8]) ->
+                    Result<Self, Box<dyn std::error::Error + Sync + Send>>
+                {
+                    let mut fields = $client_name::private::RecordFields::new("$column", raw, $len)?;
+                    Ok(Self {
+                        $($read_name: fields.next("$db_name", $oid)?,)
+                    })
+                }
+
+                fn accepts(ty: &postgres_types::Type) -> bool {
+                    *ty == postgres_types::Type::RECORD
+                }
+            }
         );
     }
 }
@@ -338,33 +755,60 @@ fn gen_row_structs(w: &mut impl Write, row: &PreparedItem, ctx: &GenCtx) {
         name,
         fields,
         is_copy,
+        is_hashable,
+        is_orderable,
         is_named,
+        dimensions,
         ..
     } = row;
     if *is_named {
         // Generate row struct
         let fields_name = fields.iter().map(|p| &p.ident.rs);
         let fields_ty = fields.iter().map(|p| p.own_struct(ctx));
+        let fields_attr = declared_attrs(fields)
+            .zip(deprecated_attrs(fields))
+            .map(|(declared, deprecated)| format!("{declared}{deprecated}"));
         let copy = if *is_copy { "Copy" } else { "" };
-        let ser_str = if ctx.gen_derive {
-            "serde::Serialize,"
-        } else {
-            ""
-        };
+        let hash = if *is_hashable { "Eq,Hash," } else { "" };
+        let ord = if *is_orderable { "PartialOrd,Ord," } else { "" };
+        let serde = ctx.serde_derives(TargetSet::rows);
+        let derive = derive_attr(
+            &format!("{serde}Debug,Clone,PartialEq,{hash}{ord}{copy}"),
+            fields,
+        );
+        if let Some(from) = &row.renamed_from {
+            renamed_doc(w, from);
+        }
         code!(w =>
-            #[derive($ser_str Debug, Clone, PartialEq,$copy)]
+            $derive
             pub struct $name {
-                $(pub $fields_name : $fields_ty,)
+                $($fields_attr pub $fields_name : $fields_ty,)
             }
         );
+        gen_redacted_debug(w, &name.value, "", "", fields);
+        if !dimensions.is_empty() {
+            let consts_name = dimensions
+                .iter()
+                .map(|(field, _)| format!("{}_DIMENSIONS", field.to_shouty_snake_case()));
+            let consts_doc = dimensions
+                .iter()
+                .map(|(field, _)| format!("\" Number of dimensions of the `{field}` column\""));
+            let consts_value = dimensions.iter().map(|(_, dims)| dims);
+            code!(w =>
+                impl $name {
+                    $(#[doc = $consts_doc] pub const $consts_name: usize = $consts_value;)
+                }
+            );
+        }
 
         if !is_copy {
             let fields_name = fields.iter().map(|p| &p.ident.rs);
             let fields_ty = fields.iter().map(|p| p.brw_ty(true, ctx));
+            let fields_attr = deprecated_attrs(fields);
             let from_own_assign = fields.iter().map(|f| f.owning_assign());
             code!(w =>
                 pub struct ${name}Borrowed<'a> {
-                    $(pub $fields_name : $fields_ty,)
+                    $($fields_attr pub $fields_name : $fields_ty,)
                 }
                 impl<'a> From<${name}Borrowed<'a>> for $name {
                     fn from(${name}Borrowed { $($fields_name,) }: ${name}Borrowed<'a>) -> Self {
@@ -375,15 +819,221 @@ fn gen_row_structs(w: &mut impl Write, row: &PreparedItem, ctx: &GenCtx) {
                 }
             );
         };
+
+        // Conversions with the user structs, which must have exactly the fields of the row
+        for path in &row.into {
+            let fields_name = fields.iter().map(|p| &p.ident.rs);
+            let into_fields_name = fields_name.clone();
+            let from_fields_name = fields_name.clone();
+            let back_fields_name = fields_name.clone();
+            code!(w =>
+                impl From<$name> for $path {
+                    fn from($name { $($fields_name,) }: $name) -> Self {
+                        Self { $($into_fields_name,) }
+                    }
+                }
+                impl From<$path> for $name {
+                    fn from($path { $($from_fields_name,) }: $path) -> Self {
+                        Self { $($back_fields_name,) }
+                    }
+                }
+            );
+        }
+    }
+}
+
+/// Implements `Display` on the row structs of a module, printing their fields as
+/// `name=value` separated by commas. Fields whose type doesn't implement `Display`, like
+/// nullable ones, are printed using `Debug`, and redacted fields as `***`.
+fn gen_row_displays(w: &mut impl Write, module: &PreparedModule) {
+    for row in module.rows.values().filter(|it| it.is_named) {
+        let name = &row.name.value;
+        let format = row
+            .fields
+            .iter()
+            .map(|f| {
+                let label = f.ident.rs.trim_start_matches("r#");
+                if f.is_redacted {
+                    format!("{label}=***")
+                } else if f.is_nullable || !f.ty.is_display() {
+                    format!("{label}={{:?}}")
+                } else {
+                    format!("{label}={{}}")
+                }
+            })
+            .collect::<Vec<_>>()
+            .join(", ");
+        let fields_value = row
+            .fields
+            .iter()
+            .filter(|f| !f.is_redacted)
+            .map(|f| format!(", self.{}", f.ident.rs));
+        code!(w =>
+            impl std::fmt::Display for $name {
+                fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+                    write!(f, "$format"$($fields_value))
+                }
+            }
+        );
+    }
+}
+
+/// Generates `From` conversions from each explicitly named row to the explicitly named rows
+/// whose fields are a subset of its own, by value and by cloning the fields of a reference.
+fn gen_row_conversions(w: &mut impl Write, module: &PreparedModule) {
+    let named: Vec<_> = module.rows.values().filter(|it| !it.is_implicit).collect();
+    let same_field = |a: &PreparedField, b: &PreparedField| {
+        a.ident == b.ident
+            && a.ty == b.ty
+            && a.is_nullable == b.is_nullable
+            && a.is_inner_nullable == b.is_inner_nullable
+    };
+    for from in &named {
+        for into in &named {
+            let is_subset = into
+                .fields
+                .iter()
+                .all(|field| from.fields.iter().any(|it| same_field(it, field)));
+            if from.name.value == into.name.value || !is_subset {
+                continue;
+            }
+            let fields_name = into.fields.iter().map(|it| &it.ident.rs);
+            let fields_clone = fields_name.clone().map(|it| format!("it.{it}.clone()"));
+            let (from, into) = (&from.name, &into.name);
+            code!(w =>
+                impl From<$from> for $into {
+                    fn from($from { $($fields_name,) .. }: $from) -> Self {
+                        Self { $($fields_name,) }
+                    }
+                }
+                impl From<&$from> for $into {
+                    fn from(it: &$from) -> Self {
+                        Self { $($fields_name: $fields_clone,) }
+                    }
+                }
+            );
+        }
+    }
+}
+
+/// Generates `From` conversions from a reference to each explicitly named row to the
+/// explicitly named params structs of the same module whose fields are a subset of its own, with the same names,
+/// types and nullability. Text, bytes, JSON and array fields of the params borrow the ones
+/// of the row, while copyable fields are copied.
+fn gen_param_conversions(w: &mut impl Write, module: &PreparedModule, ctx: &GenCtx) {
+    let rows: Vec<_> = module.rows.values().filter(|it| !it.is_implicit).collect();
+    let same_field = |a: &PreparedField, b: &PreparedField| {
+        a.ident == b.ident
+            && a.ty == b.ty
+            && a.is_nullable == b.is_nullable
+            && a.is_inner_nullable == b.is_inner_nullable
+    };
+    for params in module.params.values().filter(|it| !it.is_implicit) {
+        // The concrete type of each generic parameter of the params struct
+        let mut args = Vec::new();
+        let mut fields_value = Vec::new();
+        for field in &params.fields {
+            let len = args.len();
+            if !borrowed_args(&field.ty, field.is_inner_nullable, true, &mut args, ctx) {
+                args.clear();
+                fields_value.clear();
+                break;
+            }
+            let name = &field.ident.rs;
+            fields_value.push(if args.len() > len {
+                if field.is_nullable {
+                    format!("it.{name}.as_ref()")
+                } else {
+                    format!("&it.{name}")
+                }
+            } else if field.ty.is_copy() {
+                format!("it.{name}")
+            } else {
+                format!("it.{name}.clone()")
+            });
+        }
+        if fields_value.is_empty() {
+            continue;
+        }
+        let lifetime = if params.is_ref { "'a," } else { "" };
+        let into = format!("{}<{lifetime}{}>", params.name, args.join(","));
+        for row in &rows {
+            let is_subset = params
+                .fields
+                .iter()
+                .all(|field| row.fields.iter().any(|it| same_field(it, field)));
+            if !is_subset {
+                continue;
+            }
+            let from = &row.name;
+            let fields_name = params.fields.iter().map(|it| &it.ident.rs);
+            let fields_value = fields_value.iter();
+            code!(w =>
+                impl<'a> From<&'a $from> for $into {
+                    fn from(it: &'a $from) -> Self {
+                        Self { $($fields_name: $fields_value,) }
+                    }
+                }
+            );
+        }
+    }
+}
+
+/// Pushes the concrete types of the generic parameters of a params field of this type,
+/// in the order of [`CornucopiaType::param_ergo_ty`], so that it borrows the owned field of
+/// a row. Returns whether such a field can be converted at all, composites of parameters
+/// differing from the rows ones.
+fn borrowed_args(
+    ty: &CornucopiaType,
+    is_inner_nullable: bool,
+    is_top: bool,
+    args: &mut Vec<String>,
+    ctx: &GenCtx,
+) -> bool {
+    let own = |ty: &CornucopiaType| {
+        let own = ty.own_ty(is_inner_nullable, ctx);
+        if is_top {
+            format!("&'a {own}")
+        } else {
+            own
+        }
+    };
+    match ty {
+        CornucopiaType::Simple { pg_ty, .. } => {
+            if matches!(
+                *pg_ty,
+                Type::BYTEA | Type::TEXT | Type::VARCHAR | Type::JSON | Type::JSONB
+            ) {
+                args.push(own(ty));
+            }
+            true
+        }
+        CornucopiaType::Array { inner } => {
+            if !borrowed_args(inner, false, false, args, ctx) {
+                return false;
+            }
+            args.push(own(ty));
+            true
+        }
+        CornucopiaType::Domain { inner, .. } => {
+            borrowed_args(inner, is_inner_nullable, is_top, args, ctx)
+        }
+        CornucopiaType::Custom { is_copy, .. } => *is_copy,
+        CornucopiaType::Range { inner, .. } => inner.is_copy(),
+        CornucopiaType::Newtype { .. }
+        | CornucopiaType::Vector { .. }
+        | CornucopiaType::TypedJson { .. } => true,
+        CornucopiaType::Record { .. } => false,
     }
 }
 
-fn gen_row_query(w: &mut impl Write, row: &PreparedItem, ctx: &GenCtx) {
+fn gen_row_query<W: Write>(w: &mut W, row: &PreparedItem, ctx: &GenCtx) {
     let PreparedItem {
         name,
         fields,
         is_copy,
         is_named,
+        arity,
         ..
     } = row;
     // Generate query struct
@@ -421,70 +1071,128 @@ fn gen_row_query(w: &mut impl Write, row: &PreparedItem, ctx: &GenCtx) {
         fields[0].brw_ty(false, ctx)
     };
 
+    // Fetching methods are pruned according to the declared arity
+    let opt_fn = |w: &mut W| {
+        if *arity >= Arity::Opt {
+            code!(w =>
+                pub $fn_async fn opt(self) -> Result<Option<T>, E> {
+                    let row = self.stmt.query_opt(self.client, &self.params)$fn_await.map_err(self.error)?;
+                    row.map(|row| Ok((self.mapper)((self.extractor)(&row).map_err(self.error)?)))
+                        .transpose()
+                }
+            );
+        }
+    };
+    let many_fn = |w: &mut W| {
+        if *arity == Arity::Many {
+            code!(w =>
+                pub $fn_async fn all(self) -> Result<Vec<T>, E> {
+                    self.iter()$fn_await?.$collect
+                }
+
+                pub $fn_async fn iter(
+                    self,
+                ) -> Result<impl $raw_type<Item = Result<T, E>> + 'a, E> {
+                    let it = self
+                        .stmt
+                        .query_raw(self.client, &self.params)
+                        $fn_await
+                        .map_err(self.error)?
+                        $raw_pre
+                        .map(move |res| {
+                            res.and_then(|row| (self.extractor)(&row).map(self.mapper))
+                                .map_err(self.error)
+                        })
+                        $raw_post;
+                    Ok(it)
+                }
+
+                #[doc = " Declares a server-side cursor over the rows, fetching them in chunks instead of"]
+                #[doc = " buffering them all. Cursors only live inside a transaction."]
+                pub $fn_async fn cursor(self) -> Result<$client::Cursor<'a, C, T, E>, E>
+                where
+                    C: $client::InTransaction,
+                {
+                    let (extractor, mapper) = (self.extractor, self.mapper);
+                    self.stmt
+                        .cursor(self.client, &self.params, move |row| extractor(row).map(mapper), self.error)
+                        $fn_await
+                }
+            );
+        }
+    };
+
+    let error_ty = ctx.error_type();
     code!(w =>
-    pub struct ${name}Query<'a, C: GenericClient, T, const N: usize> {
+    pub struct ${name}Query<'a, C: GenericClient, T, const N: usize, E = $error_ty> {
         client: &'a $client_mut C,
         params: [&'a (dyn postgres_types::ToSql + Sync); N],
         stmt: &'a mut $client::private::Stmt,
-        extractor: fn(&$backend::Row) -> $row_struct,
+        extractor: fn(&$backend::Row) -> Result<$row_struct, $backend::Error>,
         mapper: fn($row_struct) -> T,
+        error: fn($backend::Error) -> E,
     }
-    impl<'a, C, T:'a, const N: usize> ${name}Query<'a, C, T, N> where C: GenericClient {
-        pub fn map<R>(self, mapper: fn($row_struct) -> R) -> ${name}Query<'a,C,R,N> {
+    impl<'a, C, T:'a, const N: usize, E: 'a> ${name}Query<'a, C, T, N, E> where C: GenericClient {
+        pub fn map<R>(self, mapper: fn($row_struct) -> R) -> ${name}Query<'a,C,R,N,E> {
             ${name}Query {
                 client: self.client,
                 params: self.params,
                 stmt: self.stmt,
                 extractor: self.extractor,
                 mapper,
+                error: self.error,
             }
         }
 
-        pub $fn_async fn one(self) -> Result<T, $backend::Error> {
-            let stmt = self.stmt.prepare(self.client)$fn_await?;
-            let row = self.client.query_one(stmt, &self.params)$fn_await?;
-            Ok((self.mapper)((self.extractor)(&row)))
-        }
-
-        pub $fn_async fn all(self) -> Result<Vec<T>, $backend::Error> {
-            self.iter()$fn_await?.$collect
-        }
-
-        pub $fn_async fn opt(self) -> Result<Option<T>, $backend::Error> {
-            let stmt = self.stmt.prepare(self.client)$fn_await?;
-            Ok(self
-                .client
-                .query_opt(stmt, &self.params)
-                $fn_await?
-                .map(|row| (self.mapper)((self.extractor)(&row))))
-        }
-
-        pub $fn_async fn iter(
-            self,
-        ) -> Result<impl $raw_type<Item = Result<T, $backend::Error>> + 'a, $backend::Error> {
-            let stmt = self.stmt.prepare(self.client)$fn_await?;
-            let it = self
-                .client
-                .query_raw(stmt, $client::private::slice_iter(&self.params))
-                $fn_await?
-                $raw_pre
-                .map(move |res| res.map(|row| (self.mapper)((self.extractor)(&row))))
-                $raw_post;
-            Ok(it)
+        pub $fn_async fn one(self) -> Result<T, E> {
+            let row = self.stmt.query_one(self.client, &self.params)$fn_await.map_err(self.error)?;
+            Ok((self.mapper)((self.extractor)(&row).map_err(self.error)?))
         }
+        $!opt_fn
+        $!many_fn
     });
 }
 
+/// Name of the argument binding a parameter, suffixed when it would shadow another
+/// argument or a local of the generated functions
+fn param_arg(ident: &Ident) -> String {
+    if ["cache", "client", "hasher"].contains(&ident.rs.as_str()) {
+        format!("{}_", ident.rs)
+    } else {
+        ident.rs.clone()
+    }
+}
+
 pub fn idx_char(idx: usize) -> String {
     format!("T{idx}")
 }
 
-fn gen_query_fn<W: Write>(w: &mut W, module: &PreparedModule, query: &PreparedQuery, ctx: &GenCtx) {
+fn gen_query_fn<W: Write>(
+    w: &mut W,
+    module: &PreparedModule,
+    query: &PreparedQuery,
+    read_only_flag: bool,
+    ctx: &GenCtx,
+) {
     let PreparedQuery {
         ident,
         row,
         sql,
         param,
+        copy_out,
+        copy_in,
+        on_error,
+        is_read_only,
+        batch,
+        unprepared,
+        cache,
+        budget_ms,
+        skips_conflicts,
+        sources: _,
+        variant,
+        variants,
+        tags,
+        renamed_from,
     } = query;
 
     let (client_mut, fn_async, fn_await, backend, client) = if ctx.is_async {
@@ -494,6 +1202,75 @@ fn gen_query_fn<W: Write>(w: &mut W, module: &PreparedModule, query: &PreparedQu
     };
 
     let struct_name = ident.type_ident();
+    let budget = budget_ms.map_or("None".to_string(), |it| format!("Some({it})"));
+    let consts = |w: &mut W| {
+        if read_only_flag {
+            code!(w => pub const IS_READ_ONLY: bool = $is_read_only;)
+        }
+        code!(w =>
+            #[doc = " Latency budget in milliseconds declared by the `budget` option of the query"]
+            pub const BUDGET_MS: Option<u64> = $budget;
+        );
+        if !tags.is_empty() {
+            let tags = tags.iter().map(|(key, value)| {
+                let (key, value) = (key.replace('"', "\\\""), value.replace('"', "\\\""));
+                move |w: &mut W| code!(w => ("$key", "$value"),)
+            });
+            code!(w =>
+                #[doc = " `key=value` tags declared by the `tag` options of the query"]
+                pub const TAGS: &'static [(&'static str, &'static str)] = &[$($!tags)];
+            )
+        }
+    };
+    let renamed_doc = |w: &mut W| {
+        if let Some(from) = renamed_from {
+            renamed_doc(w, from);
+        }
+    };
+    // Batch queries are executed without being prepared, by the `with-batch` feature of the
+    // async client
+    if let Some(commands) = batch {
+        let sql = sql.replace('"', "\\\"");
+        let name = &ident.rs;
+        let call = |w: &mut W| match &ctx.error_type {
+            Some(_) => {
+                code!(w => Ok($client::private::batch(client, "$sql", &[$("$commands",)])$fn_await?))
+            }
+            None => {
+                code!(w => $client::private::batch(client, "$sql", &[$("$commands",)])$fn_await)
+            }
+        };
+        let error_ty = ctx.error_type();
+        code!(w =>
+            $!renamed_doc
+            pub fn $name() -> ${struct_name}Stmt {
+                ${struct_name}Stmt
+            }
+            pub struct ${struct_name}Stmt;
+            impl ${struct_name}Stmt {
+                $!consts
+                pub $fn_async fn bind<C: GenericClient>(&self, client: &$client_mut C) -> Result<Vec<$client::BatchResult>, $error_ty> {
+                    $!call
+                }
+            }
+        );
+        return;
+    }
+    // Queries handling error codes return their own error type, which is converted into
+    // the configured error type if any
+    let mapping =
+        (!on_error.is_empty()).then(|| ctx.path(ctx.depth - 2, format!("{struct_name}Error")));
+    let (error_generic, error_ty) = match (&ctx.error_type, &mapping) {
+        (None, None) => (String::new(), ctx.error_type()),
+        (Some(path), _) | (None, Some(path)) => (format!(", {path}"), path.clone()),
+    };
+    let (error_conv, map_err) = match (&ctx.error_type, &mapping) {
+        (Some(_), Some(mapping)) => (
+            format!("|err| {mapping}::from(err).into()"),
+            format!(".map_err({mapping}::from)"),
+        ),
+        _ => ("From::from".to_string(), String::new()),
+    };
     let (param, param_field, order) = match param {
         Some((idx, order)) => {
             let it = module.params.get_index(*idx).unwrap().1;
@@ -506,7 +1283,12 @@ fn gen_query_fn<W: Write>(w: &mut W, module: &PreparedModule, query: &PreparedQu
         .iter()
         .map(|idx| param_field[*idx].param_ergo_ty(traits, ctx))
         .collect();
-    let params_name = order.iter().map(|idx| &param_field[*idx].ident.rs);
+    let params_name = order.iter().map(|idx| param_arg(&param_field[*idx].ident));
+    let params_ref = order.iter().map(|idx| {
+        let p = &param_field[*idx];
+        p.ty.query_param(&param_arg(&p.ident), ctx)
+    });
+    let params_field = order.iter().map(|idx| &param_field[*idx].ident.rs);
     let traits_idx = (1..=traits.len()).map(idx_char);
     let lazy_impl = |w: &mut W| {
         if let Some((idx, index)) = row {
@@ -532,9 +1314,9 @@ fn gen_query_fn<W: Write>(w: &mut W, module: &PreparedModule, query: &PreparedQu
                         let post = if *is_copy { "" } else { "Borrowed" };
                         let fields_name = fields.iter().map(|p| &p.ident.rs);
                         let fields_idx = (0..fields.len()).map(|i| index[i]);
-                        code!(w => $path$post {
-                            $($fields_name: row.get($fields_idx),)
-                        })
+                        code!(w => Ok($path$post {
+                            $($fields_name: row.try_get($fields_idx)?,)
+                        }))
                     }),
                     code!(<$path>::from(it)),
                 )
@@ -542,18 +1324,19 @@ fn gen_query_fn<W: Write>(w: &mut W, module: &PreparedModule, query: &PreparedQu
                 let field = &fields[0];
                 (
                     field.own_struct(ctx),
-                    Box::new(|w: _| code!(w => row.get(0))),
+                    Box::new(|w: _| code!(w => row.try_get(0))),
                     field.owning_call(Some("it")),
                 )
             };
             code!(w =>
-                pub fn bind<'a, C: GenericClient,$($traits_idx: $traits,)>(&'a mut self, client: &'a $client_mut C, $($params_name: &'a $params_ty,) ) -> ${row_name}Query<'a,C, $row_struct_name, $nb_params> {
+                pub fn bind<'a, C: GenericClient,$($traits_idx: $traits,)>(&'a mut self, client: &'a $client_mut C, $($params_name: &'a $params_ty,) ) -> ${row_name}Query<'a,C, $row_struct_name, $nb_params $error_generic> {
                     ${row_name}Query {
                         client,
-                        params: [$($params_name,)],
+                        params: [$($params_ref,)],
                         stmt: &mut self.0,
                         extractor: |row| { $!extractor },
                         mapper: |it| { $mapper },
+                        error: $error_conv,
                     }
                 }
             );
@@ -561,31 +1344,208 @@ fn gen_query_fn<W: Write>(w: &mut W, module: &PreparedModule, query: &PreparedQu
             // Execute fn
             let params_wrap = order.iter().map(|idx| {
                 let p = &param_field[*idx];
-                p.ty.sql_wrapped(&p.ident.rs, ctx)
+                p.ty.sql_wrapped(&param_arg(&p.ident), ctx)
             });
-            code!(w =>
-                pub $fn_async fn bind<'a, C: GenericClient,$($traits_idx: $traits,)>(&'a mut self, client: &'a $client_mut C, $($params_name: &'a $params_ty,)) -> Result<u64, $backend::Error> {
-                    let stmt = self.0.prepare(client)$fn_await?;
-                    client.execute(stmt, &[ $($params_wrap,) ])$fn_await
-                }
-            );
+            if error_generic.is_empty() {
+                code!(w =>
+                    pub $fn_async fn bind<'a, C: GenericClient,$($traits_idx: $traits,)>(&'a mut self, client: &'a $client_mut C, $($params_name: &'a $params_ty,)) -> Result<u64, $backend::Error> {
+                        self.0.execute(client, &[ $($params_wrap,) ])$fn_await
+                    }
+                );
+            } else {
+                code!(w =>
+                    pub $fn_async fn bind<'a, C: GenericClient,$($traits_idx: $traits,)>(&'a mut self, client: &'a $client_mut C, $($params_name: &'a $params_ty,)) -> Result<u64, $error_ty> {
+                        Ok(self.0.execute(client, &[ $($params_wrap,) ])$fn_await$map_err?)
+                    }
+                );
+            }
         }
     };
     // Gen statement struct
     {
         let sql = sql.replace('"', "\\\""); // Rust string format escaping
         let name = &ident.rs;
-        code!(w =>
-            pub fn $name() -> ${struct_name}Stmt {
-                ${struct_name}Stmt($client::private::Stmt::new("$sql"))
+        let variant_doc = |w: &mut W| match variant {
+            Some(_) if !variants.is_empty() => code!(w =>
+                #[doc = " Executes the variant of the query matching the version of the server, selected"]
+                #[doc = " on first execution."]
+            ),
+            Some((range, version)) => code!(w =>
+                #[doc = " Variant `$range` of the query, selected for the `server_version_num` $version of"]
+                #[doc = " the database."]
+            ),
+            None => {}
+        };
+        let with_variants = |w: &mut W| {
+            if !variants.is_empty() {
+                let variants = variants.iter().map(|(range, sql)| {
+                    let min = range.min.unwrap_or(0).to_string();
+                    let max = range
+                        .max
+                        .map_or("u32::MAX".to_string(), |it| it.to_string());
+                    (min, max, sql.replace('"', "\\\""))
+                });
+                let variants = variants
+                    .map(|(min, max, sql)| move |w: &mut W| code!(w => ($min, $max, "$sql"),));
+                code!(w => .variants(&[$($!variants)]))
             }
+        };
+        let stmt = |w: &mut W| match unprepared {
+            Some(oids) => {
+                let oids = oids.iter();
+                code!(w =>
+                #[doc = " Executed without a named prepared statement, so that it can be used behind connection"]
+                #[doc = " poolers in transaction mode (e.g. pgbouncer). The query text is sent on each execution"]
+                #[doc = " and planned again by the server, which is slower than a cached prepared statement."]
+                $!variant_doc
+                $!renamed_doc
+                pub fn $name() -> ${struct_name}Stmt {
+                    ${struct_name}Stmt($client::private::Stmt::unprepared("$sql", &[$($oids,)])$!with_variants)
+                }
+                )
+            }
+            None => code!(w =>
+                $!variant_doc
+                $!renamed_doc
+                pub fn $name() -> ${struct_name}Stmt {
+                    ${struct_name}Stmt($client::private::Stmt::new("$sql")$!with_variants)
+                }
+            ),
+        };
+        let conflicts = |w: &mut W| {
+            if *skips_conflicts {
+                code!(w =>
+                    #[doc = " Returns no row when the insert conflicts, as it skips conflicts using"]
+                    #[doc = " `ON CONFLICT DO NOTHING`: use `opt` to get `None` in this case."]
+                )
+            }
+        };
+        code!(w =>
+            $!stmt
+            $!conflicts
             pub struct ${struct_name}Stmt($client::private::Stmt);
             impl ${struct_name}Stmt {
+                $!consts
                 $!lazy_impl
             }
         );
     }
 
+    // Copy out fn
+    if let Some(copy_out) = copy_out {
+        let copy_out = copy_out.replace('"', "\\\"");
+        let name = ident.rs.trim_start_matches("r#");
+        if ctx.is_async {
+            code!(w =>
+                pub async fn copy_out_$name<C: GenericClient, W: $client::private::AsyncWrite + Unpin>(client: &C, writer: &mut W) -> Result<u64, std::io::Error> {
+                    $client::private::copy_out(client, "$copy_out", writer).await
+                }
+            );
+        } else {
+            code!(w =>
+                pub fn copy_out_$name<C: GenericClient, W: std::io::Write>(client: &mut C, writer: &mut W) -> Result<u64, std::io::Error> {
+                    $client::private::copy_out(client, "$copy_out", writer)
+                }
+            );
+        }
+    }
+
+    // Copy in fn
+    if let (Some(copy_in), Some(param)) = (copy_in, param) {
+        let (sql, copy_in) = (sql.replace('"', "\\\""), copy_in.replace('"', "\\\""));
+        let name = ident.rs.trim_start_matches("r#");
+        let item_ty = if param.is_named {
+            let lifetime = if param.is_copy || !param.is_ref {
+                ""
+            } else {
+                "'a,"
+            };
+            let param_path = param.path(ctx);
+            let traits_idx = traits_idx.clone();
+            code!($param_path<$lifetime $($traits_idx,)>)
+        } else {
+            params_ty[0].clone()
+        };
+        // Values of the columns, in the order of the bind parameters
+        let values = order.iter().map(|idx| {
+            let field = &param_field[*idx];
+            if param.is_named {
+                field
+                    .ty
+                    .sql_wrapped(&format!("&it.{}", field.ident.rs), ctx)
+            } else {
+                field.ty.sql_wrapped("it", ctx)
+            }
+        });
+        code!(w =>
+            #[doc = " Inserts the params of an iterator at once using a binary `COPY ... FROM STDIN`,"]
+            #[doc = " returning the number of inserted rows."]
+        );
+        if ctx.is_async {
+            code!(w =>
+                pub async fn copy_in_$name<'a, C: GenericClient,$($traits_idx: 'a + $traits,) I: IntoIterator<Item = &'a $item_ty>>(client: &C, rows: I) -> Result<u64, $backend::Error> {
+                    let writer = $client::private::copy_in(client, "$sql", "$copy_in").await?;
+                    let mut writer = std::pin::pin!(writer);
+                    for it in rows {
+                        writer.as_mut().write(&[$($values,)]).await?;
+                    }
+                    writer.finish().await
+                }
+            );
+        } else {
+            code!(w =>
+                pub fn copy_in_$name<'a, C: GenericClient,$($traits_idx: 'a + $traits,) I: IntoIterator<Item = &'a $item_ty>>(client: &mut C, rows: I) -> Result<u64, $backend::Error> {
+                    let mut writer = $client::private::copy_in(client, "$sql", "$copy_in")?;
+                    for it in rows {
+                        writer.write(&[$($values,)])?;
+                    }
+                    writer.finish()
+                }
+            );
+        }
+    }
+
+    // Cached fn
+    if let (Some(ttl), Some((idx, _))) = (cache, row) {
+        let item = module.rows.get_index(*idx).unwrap().1;
+        let row_ty = if item.is_named {
+            item.path(ctx)
+        } else {
+            item.fields[0].own_struct(ctx)
+        };
+        let (fetch, ret_ty) = match item.arity {
+            Arity::One => ("one", row_ty),
+            Arity::Opt => ("opt", format!("Option<{row_ty}>")),
+            Arity::Many => ("all", format!("Vec<{row_ty}>")),
+        };
+        let name = ident.rs.trim_start_matches("r#");
+        let stmt_name = &ident.rs;
+        let key = format!("{}::{}", module.info.name, ident.db);
+        let cache_path = ctx.path(ctx.depth, "cache::QueryCache");
+        let params_name = params_name.clone();
+        let params_hash = params_name.clone();
+        let params_bind = params_name.clone();
+        let params_ty = params_ty.iter();
+        let traits_idx = (1..=traits.len()).map(idx_char);
+        let traits = traits.iter();
+        code!(w =>
+            #[doc = " Returns the result cached for $ttl seconds by `cache`, executing the query when it"]
+            #[doc = " is missing or expired. Results are keyed by the hash of the parameters."]
+            pub $fn_async fn cached_$name<'a, C: GenericClient,$($traits_idx: $traits + std::hash::Hash,)>(cache: &$cache_path, client: &'a $client_mut C, $($params_name: &'a $params_ty,)) -> Result<$ret_ty, $error_ty> {
+                let mut hasher = std::collections::hash_map::DefaultHasher::new();
+                std::hash::Hash::hash("$key", &mut hasher);
+                $(std::hash::Hash::hash($params_hash, &mut hasher);)
+                let key = std::hash::Hasher::finish(&hasher);
+                if let Some(rows) = cache.get(key) {
+                    return Ok(rows);
+                }
+                let rows = $stmt_name().bind(client, $($params_bind,)).$fetch()$fn_await?;
+                cache.insert(key, std::time::Duration::from_secs($ttl), rows.clone());
+                Ok(rows)
+            }
+        );
+    }
+
     // Param impl
     if let Some(param) = param {
         if param.is_named {
@@ -605,9 +1565,9 @@ fn gen_query_fn<W: Write>(w: &mut W, module: &PreparedModule, query: &PreparedQu
                 let name = &module.rows.get_index(*idx).unwrap().1.name;
                 let nb_params = param_field.len();
                 code!(w =>
-                    impl <'a, C: GenericClient,$($traits_idx: $traits,)> $client::Params<'a, $param_path<$lifetime $($traits_idx,)>, ${name}Query<'a, C, $query_row_struct, $nb_params>, C> for ${struct_name}Stmt {
-                        fn params(&'a mut self, client: &'a $client_mut C, params: &'a $param_path<$lifetime $($traits_idx,)>) -> ${name}Query<'a, C, $query_row_struct, $nb_params> {
-                            self.bind(client, $(&params.$params_name,))
+                    impl <'a, C: GenericClient,$($traits_idx: $traits,)> $client::Params<'a, $param_path<$lifetime $($traits_idx,)>, ${name}Query<'a, C, $query_row_struct, $nb_params $error_generic>, C> for ${struct_name}Stmt {
+                        fn params(&'a mut self, client: &'a $client_mut C, params: &'a $param_path<$lifetime $($traits_idx,)>) -> ${name}Query<'a, C, $query_row_struct, $nb_params $error_generic> {
+                            self.bind(client, $(&params.$params_field,))
                         }
                     }
                 );
@@ -624,9 +1584,9 @@ fn gen_query_fn<W: Write>(w: &mut W, module: &PreparedModule, query: &PreparedQu
                     ("", "Result", "", "self", "")
                 };
                 code!(w =>
-                    impl <'a, C: GenericClient $send_sync, $($traits_idx: $traits,)> $client::Params<'a, $param_path<$lifetime $($traits_idx,)>, $pre_ty<u64, $backend::Error>$post_ty_lf, C> for ${struct_name}Stmt {
-                        fn params(&'a mut self, client: &'a $client_mut C, params: &'a $param_path<$lifetime $($traits_idx,)>) -> $pre_ty<u64, $backend::Error>$post_ty_lf {
-                            $pre.bind(client, $(&params.$params_name,))$post
+                    impl <'a, C: GenericClient $send_sync, $($traits_idx: $traits,)> $client::Params<'a, $param_path<$lifetime $($traits_idx,)>, $pre_ty<u64, $error_ty>$post_ty_lf, C> for ${struct_name}Stmt {
+                        fn params(&'a mut self, client: &'a $client_mut C, params: &'a $param_path<$lifetime $($traits_idx,)>) -> $pre_ty<u64, $error_ty>$post_ty_lf {
+                            $pre.bind(client, $(&params.$params_field,))$post
                         }
                     }
                 );
@@ -635,6 +1595,251 @@ fn gen_query_fn<W: Write>(w: &mut W, module: &PreparedModule, query: &PreparedQu
     }
 }
 
+/// Generates the error type of a query handling specific error codes.
+fn gen_query_error(w: &mut impl Write, query: &PreparedQuery, ctx: &GenCtx) {
+    if query.on_error.is_empty() {
+        return;
+    }
+    let name = format!("{}Error", query.ident.type_ident());
+    let backend = if ctx.is_async {
+        "tokio_postgres"
+    } else {
+        "postgres"
+    };
+    let codes = query.on_error.iter().map(|(code, _)| code);
+    let variants = query.on_error.iter().map(|(_, variant)| variant);
+    let backends = std::iter::repeat(backend);
+    code!(w =>
+        #[derive(Debug)]
+        pub enum $name {
+            $($variants($backends::Error),)
+            Other($backend::Error),
+        }
+        impl $name {
+            pub fn inner(&self) -> &$backend::Error {
+                match self {
+                    $(Self::$variants(err) |) Self::Other(err) => err,
+                }
+            }
+            pub fn into_inner(self) -> $backend::Error {
+                match self {
+                    $(Self::$variants(err) |) Self::Other(err) => err,
+                }
+            }
+        }
+        impl From<$backend::Error> for $name {
+            fn from(err: $backend::Error) -> Self {
+                match err.code().map($backend::error::SqlState::code) {
+                    $(Some("$codes") => Self::$variants(err),)
+                    _ => Self::Other(err),
+                }
+            }
+        }
+        impl From<$name> for $backend::Error {
+            fn from(err: $name) -> Self {
+                err.into_inner()
+            }
+        }
+        impl std::fmt::Display for $name {
+            fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+                match self.inner().as_db_error() {
+                    Some(err) => err.fmt(f),
+                    None => self.inner().fmt(f),
+                }
+            }
+        }
+        impl std::error::Error for $name {
+            fn source(&self) -> Option<&(dyn std::error::Error + 'static)> {
+                // The database error is already displayed
+                if self.inner().as_db_error().is_some() {
+                    None
+                } else {
+                    std::error::Error::source(self.inner())
+                }
+            }
+        }
+    );
+}
+
+/// Params of the queries of a transaction module with parameters, as the name and type of
+/// the field of each query, with the traits of their generics and whether they borrow
+fn transaction_params(
+    module: &PreparedModule,
+    ctx: &GenCtx,
+) -> (Vec<(String, String)>, Vec<String>, bool) {
+    let mut traits = Vec::new();
+    let mut is_ref = false;
+    let fields = module
+        .queries
+        .values()
+        .filter_map(|query| {
+            let (idx, _) = query.param.as_ref()?;
+            let item = module.params.get_index(*idx).unwrap().1;
+            is_ref |= item.is_ref;
+            let ty = if item.is_named {
+                let first = traits.len() + 1;
+                for field in &item.fields {
+                    field.param_ergo_ty(&mut traits, ctx);
+                }
+                let lifetime = if item.is_ref { "'a," } else { "" };
+                let traits_idx = (first..=traits.len()).map(idx_char);
+                let path = item.path(ctx);
+                code!($path<$lifetime $($traits_idx,)>)
+            } else {
+                item.fields[0].param_ergo_ty(&mut traits, ctx)
+            };
+            Some((query.ident.rs.clone(), ty))
+        })
+        .collect();
+    (fields, traits, is_ref)
+}
+
+/// Generates the structs of the params and of the results of the queries of a transaction
+/// module, with a field per query
+fn gen_transaction_structs<W: Write>(w: &mut W, module: &PreparedModule, ctx: &GenCtx) {
+    if !module.is_transaction_module {
+        return;
+    }
+    let (params, traits, is_ref) = transaction_params(module, ctx);
+    if !params.is_empty() {
+        let lifetime = if is_ref { "'a," } else { "" };
+        let traits_idx = (1..=traits.len()).map(idx_char);
+        let traits = traits.iter();
+        let names = params.iter().map(|(name, _)| name);
+        let tys = params.iter().map(|(_, ty)| ty);
+        code!(w =>
+            #[doc = " Params of the queries run by `run_in_transaction`, by query"]
+            #[derive(Debug)]
+            pub struct TransactionParams<$lifetime $($traits_idx: $traits,)> {
+                $(pub $names: $tys,)
+            }
+        );
+    }
+    let client = ctx.client_name();
+    let results = module.queries.values().map(|query| {
+        let ty = match (&query.row, &query.batch) {
+            (Some((idx, _)), _) => {
+                let item = module.rows.get_index(*idx).unwrap().1;
+                let row_ty = if item.is_named {
+                    item.path(ctx)
+                } else {
+                    item.fields[0].own_struct(ctx)
+                };
+                match item.arity {
+                    Arity::One => row_ty,
+                    Arity::Opt => format!("Option<{row_ty}>"),
+                    Arity::Many => format!("Vec<{row_ty}>"),
+                }
+            }
+            (None, Some(_)) => format!("Vec<{client}::BatchResult>"),
+            (None, None) => "u64".to_string(),
+        };
+        let name = &query.ident.rs;
+        move |w: &mut W| code!(w => pub $name: $ty,)
+    });
+    code!(w =>
+        #[doc = " Results of the queries run by `run_in_transaction`, by query: their rows, or the"]
+        #[doc = " number of rows they modified"]
+        #[derive(Debug)]
+        pub struct TransactionResults {
+            $($!results)
+        }
+    );
+}
+
+/// Generates the function running every query of a transaction module in a single
+/// transaction, in their declaration order
+fn gen_transaction_fn<W: Write>(w: &mut W, module: &PreparedModule, ctx: &GenCtx) {
+    if !module.is_transaction_module {
+        return;
+    }
+    let (backend, fn_async, fn_await, transaction_mut, client_ref) = if ctx.is_async {
+        ("tokio_postgres", "async", ".await", "", "&transaction")
+    } else {
+        ("postgres", "", "", "mut", "&mut transaction")
+    };
+    let error_ty = ctx.error_type();
+    let (params, traits, is_ref) = transaction_params(module, ctx);
+    let (generics, params_arg) = if params.is_empty() {
+        (String::new(), String::new())
+    } else {
+        let lifetime = if is_ref { "'a," } else { "" };
+        let traits_idx = (1..=traits.len()).map(idx_char);
+        let path = ctx.path(ctx.depth - 2, "TransactionParams");
+        let params_arg = code!(, params: &'a $path<$lifetime $($traits_idx,)>);
+        let traits_idx = (1..=traits.len()).map(idx_char);
+        let traits = traits.iter();
+        (code!(<'a, $($traits_idx: $traits,)>), params_arg)
+    };
+    let calls = module.queries.values().map(|query| {
+        let name = &query.ident.rs;
+        let args: Vec<_> = match &query.param {
+            Some((idx, order)) => {
+                let item = module.params.get_index(*idx).unwrap().1;
+                if item.is_named {
+                    order
+                        .iter()
+                        .map(|idx| format!("&params.{name}.{}", item.fields[*idx].ident.rs))
+                        .collect()
+                } else {
+                    vec![format!("&params.{name}")]
+                }
+            }
+            None => Vec::new(),
+        };
+        let fetch = match &query.row {
+            Some((idx, _)) => match module.rows.get_index(*idx).unwrap().1.arity {
+                Arity::One => ".one()",
+                Arity::Opt => ".opt()",
+                Arity::Many => ".all()",
+            },
+            None => "",
+        };
+        move |w: &mut W| code!(w => $name: $name().bind($client_ref, $($args,))$fetch$fn_await?,)
+    });
+    let results = ctx.path(ctx.depth - 2, "TransactionResults");
+    let run = |w: &mut W| {
+        if ctx.is_async {
+            code!(w => async { Ok($results { $($!calls) }) }.await)
+        } else {
+            code!(w => (|| Ok($results { $($!calls) }))())
+        }
+    };
+    code!(w =>
+        #[doc = " Runs the queries of this module in a single transaction, in their declaration order."]
+        #[doc = " The transaction is committed once every query succeeded, or rolled back on the first"]
+        #[doc = " error, which is returned."]
+        pub $fn_async fn run_in_transaction$generics(client: &mut $backend::Client $params_arg) -> Result<$results, $error_ty> {
+            let $transaction_mut transaction = client.transaction()$fn_await?;
+            let result: Result<$results, $error_ty> = $!run;
+            match result {
+                Ok(results) => {
+                    transaction.commit()$fn_await?;
+                    Ok(results)
+                }
+                Err(err) => {
+                    // The error of the query matters more than the one of the rollback
+                    transaction.rollback()$fn_await.ok();
+                    Err(err)
+                }
+            }
+        }
+    );
+}
+
+/// Name of a variant renamed by `#[serde(rename_all = "snake_case")]`, an underscore being
+/// inserted before each uppercase letter but the first one
+fn serde_snake_case(variant: &str) -> String {
+    let mut name = String::new();
+    for (i, c) in variant.char_indices() {
+        if i > 0 && c.is_uppercase() {
+            name.push('_');
+        }
+        name.push(c.to_ascii_lowercase());
+    }
+    name
+}
+
 /// Generates type definitions for custom user types. This includes domains, composites and enums.
 /// If the type is not `Copy`, then a Borrowed version will be generated.
 fn gen_custom_type(w: &mut impl Write, schema: &str, prepared: &PreparedType, ctx: &GenCtx) {
@@ -646,48 +1851,79 @@ fn gen_custom_type(w: &mut impl Write, schema: &str, prepared: &PreparedType, ct
         name,
     } = prepared;
     let copy = if *is_copy { "Copy," } else { "" };
-    let ser_str = if ctx.gen_derive {
-        "serde::Serialize,"
-    } else {
-        ""
-    };
+    let ser_str = ctx.serde_derives(TargetSet::types);
     match content {
         PreparedContent::Enum(variants) => {
             let variants_ident = variants.iter().map(|v| &v.rs);
+            // Variants are (de)serialized as their postgres label
+            let serde_attr = if ser_str.is_empty() {
+                ""
+            } else {
+                "#[serde(rename_all = \"snake_case\")]"
+            };
+            let variants_attr = variants.iter().map(|v| {
+                let name = v.rs.trim_start_matches("r#");
+                if ser_str.is_empty() || serde_snake_case(name) == v.db {
+                    String::new()
+                } else {
+                    format!("#[serde(rename = \"{}\")]", v.db.escape_default())
+                }
+            });
             code!(w =>
-                #[derive($ser_str Debug, Clone, Copy, PartialEq, Eq)]
+                #[derive($ser_str Debug, Clone, Copy, PartialEq, Eq, Hash, PartialOrd, Ord)]
+                $serde_attr
                 #[allow(non_camel_case_types)]
                 pub enum $struct_name {
-                    $($variants_ident,)
+                    $($variants_attr $variants_ident,)
                 }
             );
-            enum_sql(w, name, struct_name, variants);
+            enum_sql(w, schema, name, struct_name, variants, ctx);
+            if ctx.enum_guards != (false, false) {
+                enum_guard(w, schema, name, struct_name, variants, ctx);
+            }
         }
         PreparedContent::Composite(fields) => {
+            // Parameters borrowing arrays or JSON values cannot derive serde traits
+            let params_serde = if fields.iter().all(|f| f.ty.is_plain()) {
+                ctx.serde_derives(TargetSet::params)
+            } else {
+                ""
+            };
             let fields_original_name = fields.iter().map(|p| &p.ident.db);
             let fields_name = fields.iter().map(|p| &p.ident.rs);
             {
                 let fields_ty = fields.iter().map(|p| p.own_struct(ctx));
+                let fields_attr = declared_attrs(fields);
+                let derive = derive_attr(
+                    &format!("{ser_str}Debug,postgres_types::FromSql,{copy}Clone,PartialEq"),
+                    fields,
+                );
                 code!(w =>
-                    #[derive($ser_str Debug,postgres_types::FromSql,$copy Clone, PartialEq)]
+                    $derive
                     #[postgres(name = "$name")]
                     pub struct $struct_name {
                         $(
                             #[postgres(name = "$fields_original_name")]
+                            $fields_attr
                             pub $fields_name: $fields_ty,
                         )
                     }
                 );
+                gen_redacted_debug(w, struct_name, "", "", fields);
             }
             if *is_copy {
                 struct_tosql(w, struct_name, fields, name, false, *is_params, ctx);
             } else {
                 let fields_owning = fields.iter().map(|p| p.owning_assign());
                 let fields_brw = fields.iter().map(|p| p.brw_ty(true, ctx));
+                // Composites only used as parameters are passed borrowed
+                let serde = if *is_params { params_serde } else { "" };
+                let fields_attr = serde_borrow_attrs(serde, fields);
+                let derive = derive_attr(&format!("{serde}Debug"), fields);
                 code!(w =>
-                    #[derive(Debug)]
+                    $derive
                     pub struct ${struct_name}Borrowed<'a> {
-                        $(pub $fields_name: $fields_brw,)
+                        $($fields_attr pub $fields_name: $fields_brw,)
                     }
                     impl<'a> From<${struct_name}Borrowed<'a>> for $struct_name {
                         fn from(
@@ -701,16 +1937,21 @@ fn gen_custom_type(w: &mut impl Write, schema: &str, prepared: &PreparedType, ct
                         }
                     }
                 );
+                let borrowed_name = format!("{struct_name}Borrowed");
+                gen_redacted_debug(w, &borrowed_name, "<'a>", "<'a>", fields);
                 composite_fromsql(w, struct_name, fields, name, schema);
                 if !is_params {
                     let fields_ty = fields.iter().map(|p| p.param_ty(ctx));
-                    let derive = if *is_copy { ",Copy,Clone" } else { "" };
+                    let fields_attr = serde_borrow_attrs(params_serde, fields);
+                    let derive = derive_attr(&format!("{params_serde}Debug"), fields);
                     code!(w =>
-                        #[derive(Debug $derive)]
+                        $derive
                         pub struct ${struct_name}Params<'a> {
-                            $(pub $fields_name: $fields_ty,)
+                            $($fields_attr pub $fields_name: $fields_ty,)
                         }
                     );
+                    let params_name = format!("{struct_name}Params");
+                    gen_redacted_debug(w, &params_name, "<'a>", "<'a>", fields);
                 }
                 struct_tosql(w, struct_name, fields, name, true, *is_params, ctx);
             }
@@ -718,7 +1959,181 @@ fn gen_custom_type(w: &mut impl Write, schema: &str, prepared: &PreparedType, ct
     }
 }
 
+/// Generates a newtype wrapping a copyable type, reading and writing it as the wrapped type.
+/// Domains are written using their base type, like the columns of this domain are read.
+fn gen_newtype(w: &mut impl Write, newtype: &CornucopiaType, ctx: &GenCtx) {
+    let CornucopiaType::Newtype { path, inner, .. } = newtype else {
+        unreachable!("not a newtype")
+    };
+    let inner_ty = inner.own_ty(false, ctx);
+    let ser_str = ctx.serde_derives(TargetSet::types);
+    // Derive every comparison trait supported by the wrapped type
+    let eq = match inner.as_ref() {
+        CornucopiaType::Simple { pg_ty, .. } if !matches!(*pg_ty, Type::FLOAT4 | Type::FLOAT8) => {
+            "Eq, Hash,"
+        }
+        CornucopiaType::Custom { pg_ty, .. } if matches!(pg_ty.kind(), Kind::Enum(_)) => {
+            "Eq, Hash,"
+        }
+        _ => "",
+    };
+    code!(w =>
+        #[derive($ser_str Debug, Clone, Copy, PartialEq, $eq)]
+        pub struct $path(pub $inner_ty);
+        impl<'a> postgres_types::FromSql<'a> for $path {
+            fn from_sql(
+                ty: &postgres_types::Type,
+                raw: &'a [u8],
+            ) -> Result<Self, Box<dyn std::error::Error + Sync + Send>> {
+                <$inner_ty as postgres_types::FromSql<'a>>::from_sql(ty, raw).map(Self)
+            }
+            fn accepts(ty: &postgres_types::Type) -> bool {
+                <$inner_ty as postgres_types::FromSql<'a>>::accepts(ty)
+            }
+        }
+        impl postgres_types::ToSql for $path {
+            fn to_sql(
+                &self,
+                ty: &postgres_types::Type,
+                out: &mut postgres_types::private::BytesMut,
+            ) -> Result<postgres_types::IsNull, Box<dyn std::error::Error + Sync + Send>> {
+                let ty = match ty.kind() {
+                    postgres_types::Kind::Domain(base) => base,
+                    _ => ty,
+                };
+                postgres_types::ToSql::to_sql(&self.0, ty, out)
+            }
+            fn accepts(ty: &postgres_types::Type) -> bool {
+                match ty.kind() {
+                    postgres_types::Kind::Domain(base) => <$inner_ty as postgres_types::ToSql>::accepts(base),
+                    _ => <$inner_ty as postgres_types::ToSql>::accepts(ty),
+                }
+            }
+            postgres_types::to_sql_checked!();
+        }
+    );
+}
+
+/// Generates the `Vector` type of pgvector embeddings, re-exported from the `pgvector` crate
+/// when the `pgvector` feature of the generated code's crate is enabled, which needs the
+/// `serde` feature of `pgvector` when serde traits are derived. Otherwise, it wraps
+/// the values of the embedding, read and written using the binary format of the extension:
+/// the number of dimensions and an unused field as 16 bits integers, followed by the values.
+fn gen_vector(w: &mut impl Write, ctx: &GenCtx) {
+    let ser_str = ctx.serde_derives(TargetSet::types);
+    let (name, pg_name) = (VECTOR_STRUCT, VECTOR_TYPE);
+    code!(w =>
+        #[allow(unexpected_cfgs)]
+        #[cfg(feature = "pgvector")]
+        pub use pgvector::$name;
+        #[allow(unexpected_cfgs)]
+        #[cfg(not(feature = "pgvector"))]
+        pub use self::vector::$name;
+        #[allow(unexpected_cfgs)]
+        #[cfg(not(feature = "pgvector"))]
+        mod vector {
+            #[doc = " Embedding of the pgvector extension, replaced by `pgvector::$name` with the `pgvector` feature"]
+            #[derive($ser_str Debug, Clone, PartialEq, Default)]
+            pub struct $name(Vec<f32>);
+            impl $name {
+                pub fn as_slice(&self) -> &[f32] {
+                    &self.0
+                }
+                pub fn to_vec(&self) -> Vec<f32> {
+                    self.0.clone()
+                }
+            }
+            impl From<Vec<f32>> for $name {
+                fn from(values: Vec<f32>) -> Self {
+                    Self(values)
+                }
+            }
+            impl From<$name> for Vec<f32> {
+                fn from(vector: $name) -> Self {
+                    vector.0
+                }
+            }
+            impl<'a> postgres_types::FromSql<'a> for $name {
+                fn from_sql(
+                    _ty: &postgres_types::Type,
+                    raw: &'a [u8],
+                ) -> Result<Self, Box<dyn std::error::Error + Sync + Send>> {
+                    let (Some(dim), Some(values)) = (raw.get(..2), raw.get(4..)) else {
+                        return Err("invalid vector header".into());
+                    };
+                    if values.len() != usize::from(u16::from_be_bytes([dim[0], dim[1]])) * 4 {
+                        return Err("invalid vector length".into());
+                    }
+                    Ok(Self(
+                        values
+                            .chunks_exact(4)
+                            .map(|it| f32::from_be_bytes([it[0], it[1], it[2], it[3]]))
+                            .collect(),
+                    ))
+                }
+                fn accepts(ty: &postgres_types::Type) -> bool {
+                    ty.name() == "$pg_name"
+                }
+            }
+            impl postgres_types::ToSql for $name {
+                fn to_sql(
+                    &self,
+                    _ty: &postgres_types::Type,
+                    out: &mut postgres_types::private::BytesMut,
+                ) -> Result<postgres_types::IsNull, Box<dyn std::error::Error + Sync + Send>> {
+                    let dim = u16::try_from(self.0.len()).map_err(|_| "vector has too many dimensions")?;
+                    out.extend_from_slice(&dim.to_be_bytes());
+                    out.extend_from_slice(&0u16.to_be_bytes());
+                    for value in &self.0 {
+                        out.extend_from_slice(&value.to_be_bytes());
+                    }
+                    Ok(postgres_types::IsNull::No)
+                }
+                fn accepts(ty: &postgres_types::Type) -> bool {
+                    ty.name() == "$pg_name"
+                }
+                postgres_types::to_sql_checked!();
+            }
+        }
+    );
+}
+
 fn gen_type_modules<W: Write>(
+    w: &mut W,
+    prepared: &IndexMap<String, Vec<PreparedType>>,
+    newtypes: &[Rc<CornucopiaType>],
+    has_vector: bool,
+    ctx: &GenCtx,
+) {
+    let newtypes = newtypes.iter().map(|newtype| {
+        move |w: &mut W| {
+            let ctx = GenCtx::new(0, ctx.is_async)
+                .with_derives(ctx.serialize, ctx.deserialize)
+                .with_types_path(ctx.types_path.as_deref());
+            gen_newtype(w, newtype, &ctx)
+        }
+    });
+    let vector = |w: &mut W| {
+        if has_vector {
+            gen_vector(w, ctx)
+        }
+    };
+    let modules = |w: &mut W| gen_schema_modules(w, prepared, ctx);
+    code!(w =>
+        #[allow(clippy::all, clippy::pedantic)]
+        #[allow(unused_variables)]
+        #[allow(unused_imports)]
+        #[allow(dead_code)]
+        pub mod types {
+            $($!newtypes)
+            $!vector
+            $!modules
+        }
+    );
+}
+
+/// Generates a module for each schema, containing its custom types
+fn gen_schema_modules<W: Write>(
     w: &mut W,
     prepared: &IndexMap<String, Vec<PreparedType>>,
     ctx: &GenCtx,
@@ -737,44 +2152,108 @@ fn gen_type_modules<W: Write>(
             });
         }
     });
+    code!(w => $($!modules));
+}
+
+/// Generates the custom types shared by the queries of several crates, as the content
+/// of a crate or of a module
+pub(crate) fn generate_types(
+    types: &IndexMap<String, Vec<PreparedType>>,
+    settings: &CodegenSettings,
+) -> String {
+    let mut buff = "// This file was generated with `cornucopia`. Do not modify.\n\n".to_string();
+    let w = &mut buff;
+    let modules = |w: &mut String| {
+        gen_schema_modules(
+            w,
+            types,
+            &GenCtx::new(1, settings.gen_async)
+                .with_derives(settings.serialize, settings.deserialize)
+                .with_enum_guards(settings),
+        )
+    };
     code!(w =>
-        #[allow(clippy::all, clippy::pedantic)]
-        #[allow(unused_variables)]
-        #[allow(unused_imports)]
-        #[allow(dead_code)]
-        pub mod types {
-            $($!modules)
-        }
+        #![allow(clippy::all, clippy::pedantic)]
+        #![allow(unused_variables)]
+        #![allow(unused_imports)]
+        #![allow(dead_code)]
+        $!modules
     );
+    buff
 }
 
-pub(crate) fn generate(preparation: Preparation, settings: CodegenSettings) -> String {
-    let mut buff = "// This file was generated with `cornucopia`. Do not modify.\n\n".to_string();
+pub(crate) fn generate(preparation: Preparation, settings: &CodegenSettings) -> String {
+    let mut buff = "// This file was generated with `cornucopia`. Do not modify.\n".to_string();
+    // Pruned code must not be mistaken for the code of every query
+    if !settings.only.is_empty() || !settings.only_tags.is_empty() {
+        let quoted =
+            |it: &Vec<String>| -> Vec<_> { it.iter().map(|it| format!("`{it}`")).collect() };
+        let mut selections = Vec::new();
+        if !settings.only.is_empty() {
+            selections.push(format!("matching {}", quoted(&settings.only).join(", ")));
+        }
+        if !settings.only_tags.is_empty() {
+            selections.push(format!("tagged {}", quoted(&settings.only_tags).join(", ")));
+        }
+        buff += &format!(
+            "// Only the queries {} were generated.\n",
+            selections.join(" or ")
+        );
+    }
+    buff.push('\n');
     let w = &mut buff;
     // Generate database type
+    let types_path = settings.types_path.as_deref();
+    let error_type = settings.error_type.as_deref();
     gen_type_modules(
         w,
         &preparation.types,
-        &GenCtx::new(1, settings.gen_async, settings.derive_ser),
+        &preparation.newtypes,
+        preparation.has_vector,
+        &GenCtx::new(1, settings.gen_async)
+            .with_derives(settings.serialize, settings.deserialize)
+            .with_types_path(types_path)
+            .with_enum_guards(settings),
     );
     // Generate queries
     let query_modules = preparation.modules.iter().map(|module| {
         move |w: &mut String| {
-            let name = &module.info.name;
-            let ctx = GenCtx::new(2, settings.gen_async, settings.derive_ser);
+            let name = &module.name;
+            let ctx = GenCtx::new(2, settings.gen_async).with_derives(settings.serialize, settings.deserialize)
+                .with_types_path(types_path);
             let params_string = module
                 .params
                 .values()
-                .map(|params| |w: &mut String| gen_params_struct(w, params,  &ctx));
+                .map(|params| |w: &mut String| gen_params_struct(w, params, settings.gen_params_builders, &ctx));
             let rows_struct_string = module
                 .rows
                 .values()
                 .map(|row| |w: &mut String| gen_row_structs(w, row,  &ctx));
+            let records_string = |w: &mut String| gen_record_structs(w, module, &ctx);
+            let lock_keys_string = |w: &mut String| gen_lock_keys(w, module);
+            let transaction_structs_string = |w: &mut String| gen_transaction_structs(w, module, &ctx);
+            let rows_conversions = |w: &mut String| {
+                if settings.gen_row_conversions {
+                    gen_row_conversions(w, module)
+                }
+                if settings.gen_param_conversions {
+                    gen_param_conversions(w, module, &ctx)
+                }
+                if settings.gen_display {
+                    gen_row_displays(w, module)
+                }
+            };
+            let errors_string = module
+                .queries
+                .values()
+                .map(|query| |w: &mut String| gen_query_error(w, query, &ctx));
 
             let sync_specific = |w: &mut String| {
                 let gen_specific = |depth: u8, is_async: bool| {
                     move |w: &mut String| {
-                        let ctx = GenCtx::new(depth, is_async, settings.derive_ser);
+                        let ctx = GenCtx::new(depth, is_async).with_derives(settings.serialize, settings.deserialize)
+                            .with_types_path(types_path)
+                            .with_error_type(error_type);
                         let import = if is_async {
                             "use futures::{StreamExt, TryStreamExt};use futures; use cornucopia_async::GenericClient;"
                         } else {
@@ -785,12 +2264,18 @@ pub(crate) fn generate(preparation: Preparation, settings: CodegenSettings) -> S
                             .values()
                             .map(|row| |w: &mut String| gen_row_query(w, row, &ctx));
                         let queries_string = module.queries.values().map(|query| {
-                            |w: &mut String| gen_query_fn(w, module, query, &ctx)
+                            |w: &mut String| {
+                                gen_query_fn(w, module, query, settings.generate_read_only_flag, &ctx)
+                            }
                         });
+                        let locks_string = |w: &mut String| gen_lock_fns(w, module, &ctx);
+                        let transaction_string = |w: &mut String| gen_transaction_fn(w, module, &ctx);
                         code!(w =>
                             $import
                             $($!rows_query_string)
                             $($!queries_string)
+                            $!locks_string
+                            $!transaction_string
                         )
                     }
                 };
@@ -818,10 +2303,42 @@ pub(crate) fn generate(preparation: Preparation, settings: CodegenSettings) -> S
                 }
             };
 
+            let renamed_doc = |w: &mut String| {
+                if module.name != module.info.name {
+                    renamed_doc(w, &module.info.name);
+                }
+            };
+            let schema_string = |w: &mut String| {
+                if let Some(schema) = &module.info.schema {
+                    let schema = format!("{:?}", schema.value);
+                    code!(w =>
+                        #[doc = " Schema the queries of this module were prepared with, which must come first in the `search_path` of the connections executing them"]
+                        pub const SCHEMA: &str = $schema;
+                    );
+                }
+            };
+            // The generated code reads the deprecated fields, only their users are warned
+            let allow_deprecated = if module
+                .rows
+                .values()
+                .any(|row| row.fields.iter().any(|f| f.deprecation.is_some()))
+            {
+                "#[allow(deprecated)]"
+            } else {
+                ""
+            };
             code!(w =>
+                $!renamed_doc
+                $allow_deprecated
                 pub mod $name {
+                    $!schema_string
+                    $!lock_keys_string
                     $($!params_string)
+                    $!records_string
                     $($!rows_struct_string)
+                    $!rows_conversions
+                    $($!errors_string)
+                    $!transaction_structs_string
                     $!sync_specific
                 }
             );
@@ -836,5 +2353,204 @@ pub(crate) fn generate(preparation: Preparation, settings: CodegenSettings) -> S
             $($!query_modules)
         }
     );
+    // The cache is only generated for queries declared with the `cache` option
+    if preparation
+        .modules
+        .iter()
+        .any(|module| module.queries.values().any(|query| query.cache.is_some()))
+    {
+        gen_query_cache(w);
+    }
+    if settings.gen_two_phase_commit {
+        gen_two_phase(w, settings);
+    }
     buff
 }
+
+/// Generates the helpers of two-phase commits, preparing a transaction running generated
+/// queries so that it is committed or rolled back later, along with an external system.
+fn gen_two_phase(w: &mut String, settings: &CodegenSettings) {
+    let gen_specific = |is_async: bool| {
+        move |w: &mut String| {
+            let (backend, client_ref, fn_async, fn_await) = if is_async {
+                ("tokio_postgres", "&Client", "async", ".await")
+            } else {
+                ("postgres", "&mut Client", "", "")
+            };
+            // The future of an async closure borrows the client
+            let (generics, closure_client) = if is_async {
+                ("'a, T, E, F, Fut", "&'a Client")
+            } else {
+                ("T, E, F", client_ref)
+            };
+            let closure = if is_async {
+                "F: FnOnce(&'a Client) -> Fut, Fut: std::future::Future<Output = Result<T, E>>"
+            } else {
+                "F: FnOnce(&mut Client) -> Result<T, E>"
+            };
+            let call = if is_async {
+                "f(client).await"
+            } else {
+                "f(client)"
+            };
+            code!(w =>
+                use $backend::{Client, Error};
+
+                #[doc = " Error of the two-phase commit helpers"]
+                #[derive(Debug)]
+                pub enum TwoPhaseError<E = Error> {
+                    #[doc = " The global transaction identifier is not valid, see [`is_valid_gid`](super::is_valid_gid)"]
+                    InvalidGid(String),
+                    Db(Error),
+                    #[doc = " Error returned by the closure, the transaction being rolled back"]
+                    Failed(E),
+                }
+
+                impl<E: std::fmt::Display> std::fmt::Display for TwoPhaseError<E> {
+                    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+                        match self {
+                            Self::InvalidGid(gid) => write!(f, "invalid global transaction identifier `{gid}`"),
+                            Self::Db(err) => err.fmt(f),
+                            Self::Failed(err) => err.fmt(f),
+                        }
+                    }
+                }
+
+                impl<E: std::fmt::Debug + std::fmt::Display> std::error::Error for TwoPhaseError<E> {}
+
+                fn check_gid<E>(gid: &str) -> Result<(), TwoPhaseError<E>> {
+                    if super::is_valid_gid(gid) {
+                        Ok(())
+                    } else {
+                        Err(TwoPhaseError::InvalidGid(gid.to_string()))
+                    }
+                }
+
+                #[doc = " Runs the queries of `f` inside a transaction prepared for a two-phase commit under"]
+                #[doc = " `gid` once `f` succeeds, instead of being committed. The transaction is rolled back"]
+                #[doc = " when `f` fails. It must then be finished using [`commit_prepared`] or"]
+                #[doc = " [`rollback_prepared`], possibly from another session."]
+                #[doc = ""]
+                #[doc = " The server must allow prepared transactions using `max_prepared_transactions`."]
+                #[doc = ""]
+                #[doc = " When `f` panics, the transaction is left open on the connection, which must then"]
+                #[doc = " be dropped, or sent a `ROLLBACK`, instead of being reused."]
+                pub $fn_async fn prepare_transaction<$generics>(client: $closure_client, gid: &str, f: F) -> Result<T, TwoPhaseError<E>>
+                where $closure {
+                    check_gid(gid)?;
+                    client.batch_execute("BEGIN")$fn_await.map_err(TwoPhaseError::Db)?;
+                    match $call {
+                        Ok(value) => {
+                            // A failed preparation rolls the transaction back
+                            client
+                                .batch_execute(&format!("PREPARE TRANSACTION '{gid}'"))$fn_await
+                                .map_err(TwoPhaseError::Db)?;
+                            Ok(value)
+                        }
+                        Err(err) => {
+                            client.batch_execute("ROLLBACK")$fn_await.map_err(TwoPhaseError::Db)?;
+                            Err(TwoPhaseError::Failed(err))
+                        }
+                    }
+                }
+
+                #[doc = " Commits the transaction prepared under `gid`"]
+                pub $fn_async fn commit_prepared(client: $client_ref, gid: &str) -> Result<(), TwoPhaseError> {
+                    check_gid(gid)?;
+                    client
+                        .batch_execute(&format!("COMMIT PREPARED '{gid}'"))$fn_await
+                        .map_err(TwoPhaseError::Db)
+                }
+
+                #[doc = " Rolls back the transaction prepared under `gid`"]
+                pub $fn_async fn rollback_prepared(client: $client_ref, gid: &str) -> Result<(), TwoPhaseError> {
+                    check_gid(gid)?;
+                    client
+                        .batch_execute(&format!("ROLLBACK PREPARED '{gid}'"))$fn_await
+                        .map_err(TwoPhaseError::Db)
+                }
+            )
+        }
+    };
+    let specific = |w: &mut String| {
+        if settings.gen_async != settings.gen_sync {
+            let gen = gen_specific(settings.gen_async);
+            code!(w => $!gen)
+        } else {
+            let sync = gen_specific(false);
+            let async_ = gen_specific(true);
+            code!(w =>
+                pub mod sync {
+                    $!sync
+                }
+                pub mod async_ {
+                    $!async_
+                }
+            )
+        }
+    };
+    code!(w =>
+        #[allow(clippy::all, clippy::pedantic)]
+        #[allow(dead_code)]
+        pub mod two_phase {
+            #[doc = " Whether `gid` can identify a prepared transaction: it is embedded in the statements"]
+            #[doc = " of the helpers, so it must be made of 1 to 199 ASCII letters, digits, `_`, `-`, `.`"]
+            #[doc = " or `:`."]
+            pub fn is_valid_gid(gid: &str) -> bool {
+                (1..200).contains(&gid.len())
+                    && gid.bytes().all(|it| it.is_ascii_alphanumeric() || matches!(it, b'_' | b'-' | b'.' | b':'))
+            }
+
+            $!specific
+        }
+    );
+}
+
+/// Generates the cache of the results of the queries declared with the `cache` option,
+/// a map from the hash of a query and its parameters to its result and expiration time.
+fn gen_query_cache(w: &mut impl Write) {
+    code!(w =>
+        #[allow(clippy::all, clippy::pedantic)]
+        #[allow(dead_code)]
+        pub mod cache {
+            use std::any::Any;
+            use std::collections::HashMap;
+            use std::sync::{Mutex, PoisonError};
+            use std::time::{Duration, Instant};
+
+            #[doc = " Results of the queries declared with the `cache` option, used by their `cached_`"]
+            #[doc = " functions. A single cache can be shared between threads and tasks."]
+            #[derive(Default)]
+            pub struct QueryCache {
+                entries: Mutex<HashMap<u64, (Instant, Box<dyn Any + Send + Sync>)>>,
+            }
+
+            impl QueryCache {
+                pub fn new() -> Self {
+                    Self::default()
+                }
+
+                #[doc = " Removes every cached result, so that the next calls execute their query"]
+                pub fn clear(&self) {
+                    self.entries.lock().unwrap_or_else(PoisonError::into_inner).clear();
+                }
+
+                pub(crate) fn get<T: Clone + 'static>(&self, key: u64) -> Option<T> {
+                    let entries = self.entries.lock().unwrap_or_else(PoisonError::into_inner);
+                    entries
+                        .get(&key)
+                        .filter(|(expires, _)| Instant::now() < *expires)
+                        .and_then(|(_, value)| value.downcast_ref::<T>())
+                        .cloned()
+                }
+
+                pub(crate) fn insert<T: Send + Sync + 'static>(&self, key: u64, ttl: Duration, value: T) {
+                    let mut entries = self.entries.lock().unwrap_or_else(PoisonError::into_inner);
+                    let now = Instant::now();
+                    entries.retain(|_, (expires, _)| now < *expires);
+                    entries.insert(key, (now + ttl, Box::new(value)));
+                }
+            }
+        }
+    );
+}
@@ -0,0 +1,170 @@
+use std::{collections::BTreeMap, path::Path};
+
+use serde::Deserialize;
+
+use crate::{settings::CodegenSettingsBuilder, RenameTarget};
+
+use self::error::Error;
+
+/// Configuration file of the CLI, given using `--config`
+#[derive(Debug, Default, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub(crate) struct Config {
+    /// Newtypes by `table.column` or domain name
+    #[serde(default)]
+    newtypes: BTreeMap<String, String>,
+    /// Patterns of the fields hidden from `Debug` output
+    #[serde(default)]
+    redact: Vec<String>,
+    #[serde(default)]
+    rename: RenameConfig,
+}
+
+/// Renames of the generated identifiers, in the `[rename]` section
+#[derive(Debug, Default, Deserialize)]
+#[serde(deny_unknown_fields)]
+struct RenameConfig {
+    #[serde(default)]
+    modules: RenameRules,
+    #[serde(default)]
+    functions: RenameRules,
+    #[serde(default)]
+    rows: RenameRules,
+    #[serde(default)]
+    params: RenameRules,
+    /// Exact renames by module name
+    #[serde(default)]
+    overrides: BTreeMap<String, RenameOverrides>,
+}
+
+/// Renames applied to every identifier of a kind
+#[derive(Debug, Default, Deserialize)]
+#[serde(deny_unknown_fields)]
+struct RenameRules {
+    prefix: Option<String>,
+    suffix: Option<String>,
+    /// Replacements of the prefixes of the names
+    #[serde(default)]
+    prefix_map: BTreeMap<String, String>,
+}
+
+/// Exact renames of the identifiers of a module
+#[derive(Debug, Default, Deserialize)]
+#[serde(deny_unknown_fields)]
+struct RenameOverrides {
+    /// New name of the module itself
+    module: Option<String>,
+    #[serde(default)]
+    functions: BTreeMap<String, String>,
+    #[serde(default)]
+    rows: BTreeMap<String, String>,
+    #[serde(default)]
+    params: BTreeMap<String, String>,
+}
+
+impl Config {
+    pub(crate) fn read(path: &Path) -> Result<Self, Error> {
+        let content = std::fs::read_to_string(path).map_err(|err| Error::Io {
+            path: path.to_owned(),
+            err,
+        })?;
+        let content = expand_vars(path, &content)?;
+        toml::from_str(&content).map_err(|err| Error::Format {
+            path: path.to_owned(),
+            err: Box::new(err),
+        })
+    }
+
+    pub(crate) fn apply(self, mut builder: CodegenSettingsBuilder) -> CodegenSettingsBuilder {
+        for (target, path) in self.newtypes {
+            builder = builder.newtype(target, path);
+        }
+        for pattern in self.redact {
+            builder = builder.redact(pattern);
+        }
+        let RenameConfig {
+            modules,
+            functions,
+            rows,
+            params,
+            overrides,
+        } = self.rename;
+        for (target, rules) in [
+            (RenameTarget::Modules, modules),
+            (RenameTarget::Functions, functions),
+            (RenameTarget::Rows, rows),
+            (RenameTarget::Params, params),
+        ] {
+            if let Some(prefix) = rules.prefix {
+                builder = builder.rename_prefix(target, prefix);
+            }
+            if let Some(suffix) = rules.suffix {
+                builder = builder.rename_suffix(target, suffix);
+            }
+            for (from, to) in rules.prefix_map {
+                builder = builder.rename_prefix_map(target, from, to);
+            }
+        }
+        for (module, overrides) in overrides {
+            if let Some(to) = overrides.module {
+                builder = builder.rename(&module, RenameTarget::Modules, &module, to);
+            }
+            for (target, renames) in [
+                (RenameTarget::Functions, overrides.functions),
+                (RenameTarget::Rows, overrides.rows),
+                (RenameTarget::Params, overrides.params),
+            ] {
+                for (from, to) in renames {
+                    builder = builder.rename(&module, target, from, to);
+                }
+            }
+        }
+        builder
+    }
+}
+
+/// Replaces the `${VAR}` references of a configuration by the value of these environment
+/// variables
+fn expand_vars(path: &Path, content: &str) -> Result<String, Error> {
+    let mut expanded = String::with_capacity(content.len());
+    let mut rest = content;
+    while let Some(start) = rest.find("${") {
+        expanded.push_str(&rest[..start]);
+        let reference = &rest[start + 2..];
+        let end = reference.find('}').ok_or_else(|| Error::UnclosedVariable {
+            path: path.to_owned(),
+        })?;
+        let name = &reference[..end];
+        let value = std::env::var(name).map_err(|_| Error::UndefinedVariable {
+            path: path.to_owned(),
+            name: name.to_string(),
+        })?;
+        expanded.push_str(&value);
+        rest = &reference[end + 1..];
+    }
+    expanded.push_str(rest);
+    Ok(expanded)
+}
+
+pub(crate) mod error {
+    use std::path::PathBuf;
+
+    use miette::Diagnostic;
+    use thiserror::Error as ThisError;
+
+    #[derive(Debug, ThisError, Diagnostic)]
+    pub enum Error {
+        #[error("Could not read config `{path}`: ({err})")]
+        Io { path: PathBuf, err: std::io::Error },
+        #[error("Could not parse config `{path}`: ({err})")]
+        Format {
+            path: PathBuf,
+            err: Box<toml::de::Error>,
+        },
+        #[error("config `{path}` references the undefined environment variable `{name}`")]
+        #[diagnostic(help("set it in the environment or in a `.env` file"))]
+        UndefinedVariable { path: PathBuf, name: String },
+        #[error("config `{path}` has a `${{` without its closing `}}`")]
+        UnclosedVariable { path: PathBuf },
+    }
+}
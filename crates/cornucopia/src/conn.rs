@@ -2,19 +2,40 @@ use postgres::{Client, Config, NoTls};
 
 use self::error::Error;
 
-/// Creates a non-TLS connection from a URL.
-pub(crate) fn from_url(url: &str) -> Result<Client, Error> {
-    Ok(Client::connect(url, NoTls)?)
+/// `application_name` of the connections of Cornucopia, unless another one is set.
+pub const DEFAULT_APPLICATION_NAME: &str = "cornucopia";
+
+/// Connection configuration of a URL, reporting `application_name` if some, or else the
+/// one of the URL, or else [`DEFAULT_APPLICATION_NAME`].
+pub(crate) fn url_config(url: &str, application_name: Option<&str>) -> Result<Config, Error> {
+    let mut config: Config = url.parse()?;
+    match application_name {
+        Some(name) => {
+            config.application_name(name);
+        }
+        None if config.get_application_name().is_none() => {
+            config.application_name(DEFAULT_APPLICATION_NAME);
+        }
+        None => {}
+    }
+    Ok(config)
 }
 
 /// Create a non-TLS connection to the container managed by Cornucopia.
 pub fn cornucopia_conn() -> Result<Client, Error> {
+    managed_conn(DEFAULT_APPLICATION_NAME)
+}
+
+/// Create a non-TLS connection to the container managed by Cornucopia, reporting
+/// `application_name`.
+pub(crate) fn managed_conn(application_name: &str) -> Result<Client, Error> {
     Ok(Config::new()
         .user("postgres")
         .password("postgres")
         .host("127.0.0.1")
         .port(5435)
         .dbname("postgres")
+        .application_name(application_name)
         .connect(NoTls)?)
 }
 
@@ -1,104 +1,234 @@
-use std::process::{Command, Stdio};
+use std::{
+    process::{Command, Stdio},
+    time::Duration,
+};
+
+use crate::{conn, progress::PrintObserver, ProgressEvent, ProgressObserver};
 
 use self::error::Error;
 
-/// Starts Cornucopia's database container and wait until it reports healthy.
+/// Image of Cornucopia's database container, unless another one is set.
+pub const DEFAULT_IMAGE: &str = "docker.io/library/postgres:latest";
+
+/// Settings of Cornucopia's database container.
+#[derive(Clone, Debug)]
+pub struct ContainerSettings {
+    /// Whether the container manager is Podman instead of Docker.
+    pub podman: bool,
+    /// Number of times the transient failures of the container manager are retried.
+    pub retries: u32,
+    /// Image the container is run from, like one providing the extensions of the schema.
+    pub image: String,
+    /// Whether the database allows the transactions prepared by two-phase commits.
+    pub prepared_transactions: bool,
+}
+
+impl ContainerSettings {
+    /// The default settings, using Podman when `podman`.
+    pub fn new(podman: bool) -> Self {
+        Self {
+            podman,
+            retries: 2,
+            image: DEFAULT_IMAGE.to_string(),
+            prepared_transactions: false,
+        }
+    }
+}
+
+impl Default for ContainerSettings {
+    fn default() -> Self {
+        Self::new(false)
+    }
+}
+
+/// Starts Cornucopia's database container and wait until it accepts connections.
 pub fn setup(podman: bool) -> Result<(), Error> {
-    spawn_container(podman)?;
-    healthcheck(podman, 120, 50)?;
-    Ok(())
+    setup_with_observer(
+        &ContainerSettings::new(podman),
+        &mut PrintObserver { verbose: false },
+    )
+}
+
+/// Like [`setup`], using these `settings` and reporting the startup of the container to
+/// `observer`. Transient failures of the container manager are retried, waiting longer
+/// before each attempt.
+pub fn setup_with_observer(
+    settings: &ContainerSettings,
+    observer: &mut dyn ProgressObserver,
+) -> Result<(), Error> {
+    setup_with(
+        &mut CommandRunner {
+            podman: settings.podman,
+        },
+        settings,
+        observer,
+    )
 }
 
 /// Stop and remove a container and its volume.
 pub fn cleanup(podman: bool) -> Result<(), Error> {
-    stop_container(podman)?;
-    remove_container(podman)?;
+    let runner = &mut CommandRunner { podman };
+    stop_container(runner)?;
+    remove_container(runner)?;
     Ok(())
 }
 
+/// Runs the container manager and connects to the container, a seam to simulate the
+/// failures of the container manager
+trait Runner {
+    /// Runs the container manager with these args, `action` describing them in errors
+    fn run(&mut self, args: &[&str], action: &'static str) -> Result<(), Error>;
+    /// Connects to the database of the container
+    fn connect(&mut self) -> Result<(), Error>;
+    fn sleep(&mut self, duration: Duration);
+    /// Whether the container manager is Podman, which changes the help of errors
+    fn is_podman(&self) -> bool;
+}
+
+/// Runs the `docker` or `podman` command
+struct CommandRunner {
+    podman: bool,
+}
+
+impl Runner for CommandRunner {
+    fn run(&mut self, args: &[&str], action: &'static str) -> Result<(), Error> {
+        let command = if self.podman { "podman" } else { "docker" };
+        let output = Command::new(command)
+            .args(args)
+            .stderr(Stdio::piped())
+            .stdout(Stdio::null())
+            .output()?;
+
+        if output.status.success() {
+            Ok(())
+        } else {
+            let stderr = String::from_utf8_lossy(&output.stderr);
+            Err(Error::command(command, action, &stderr, self.podman))
+        }
+    }
+
+    fn connect(&mut self) -> Result<(), Error> {
+        conn::cornucopia_conn().map(drop).map_err(|err| {
+            Error::new(
+                format!("Couldn't connect to the container: {}", err.0),
+                self.podman,
+            )
+        })
+    }
+
+    fn sleep(&mut self, duration: Duration) {
+        std::thread::sleep(duration);
+    }
+
+    fn is_podman(&self) -> bool {
+        self.podman
+    }
+}
+
+/// Starts the container, retrying transient failures. The container of a failed attempt
+/// is removed, its name being reused by the next one.
+fn setup_with(
+    runner: &mut impl Runner,
+    settings: &ContainerSettings,
+    observer: &mut dyn ProgressObserver,
+) -> Result<(), Error> {
+    let retries = settings.retries;
+    observer.on_event(ProgressEvent::ContainerStarting);
+    let mut attempt = 0;
+    loop {
+        let result = spawn_container(runner, settings)
+            .and_then(|()| healthcheck(runner, 120, 50, observer))
+            .and_then(|()| runner.connect());
+        match result {
+            Err(err) if err.is_transient && attempt < retries => {
+                attempt += 1;
+                observer.on_event(ProgressEvent::ContainerRetry {
+                    attempt,
+                    retries,
+                    reason: err.to_string(),
+                });
+                runner
+                    .run(
+                        &["rm", "--force", "--volumes", "cornucopia_postgres"],
+                        "remove container",
+                    )
+                    .ok();
+                // Waits 1s, 2s, 4s, ... up to 32s between the attempts
+                runner.sleep(Duration::from_secs(1u64 << (attempt - 1).min(5)));
+            }
+            result => return result,
+        }
+    }
+}
+
 /// Starts Cornucopia's database container.
-fn spawn_container(podman: bool) -> Result<(), Error> {
-    cmd(
-        podman,
-        &[
-            "run",
-            "-d",
-            "--name",
-            "cornucopia_postgres",
-            "-p",
-            "5435:5432",
-            "-e",
-            "POSTGRES_PASSWORD=postgres",
-            "docker.io/library/postgres:latest",
-        ],
-        "spawn container",
-    )
+fn spawn_container(runner: &mut impl Runner, settings: &ContainerSettings) -> Result<(), Error> {
+    let mut args = vec![
+        "run",
+        "-d",
+        "--name",
+        "cornucopia_postgres",
+        "-p",
+        "5435:5432",
+        "-e",
+        "POSTGRES_PASSWORD=postgres",
+        &settings.image,
+    ];
+    if settings.prepared_transactions {
+        args.extend(["-c", "max_prepared_transactions=10"]);
+    }
+    runner.run(&args, "spawn container")
 }
 
 /// Checks if Cornucopia's container reports healthy
-fn is_postgres_healthy(podman: bool) -> Result<bool, Error> {
-    Ok(cmd(
-        podman,
-        &["exec", "cornucopia_postgres", "pg_isready"],
-        "check container health",
-    )
-    .is_ok())
+fn is_postgres_healthy(runner: &mut impl Runner) -> bool {
+    runner
+        .run(
+            &["exec", "cornucopia_postgres", "pg_isready"],
+            "check container health",
+        )
+        .is_ok()
 }
 
 /// This function controls how the healthcheck retries are handled.
-fn healthcheck(podman: bool, max_retries: u64, ms_per_retry: u64) -> Result<(), Error> {
+fn healthcheck(
+    runner: &mut impl Runner,
+    max_retries: u64,
+    ms_per_retry: u64,
+    observer: &mut dyn ProgressObserver,
+) -> Result<(), Error> {
     let slow_threshold = 10 + max_retries / 10;
     let mut nb_retries = 0;
-    while !is_postgres_healthy(podman)? {
+    while !is_postgres_healthy(runner) {
         if nb_retries >= max_retries {
             return Err(Error::new(
                 String::from("Cornucopia reached the max number of connection retries"),
-                podman,
+                runner.is_podman(),
             ));
         };
-        std::thread::sleep(std::time::Duration::from_millis(ms_per_retry));
+        runner.sleep(Duration::from_millis(ms_per_retry));
         nb_retries += 1;
 
         if nb_retries % slow_threshold == 0 {
-            println!("Container startup slower than expected ({nb_retries} retries out of {max_retries})");
+            observer.on_event(ProgressEvent::ContainerSlow {
+                retries: nb_retries,
+                max_retries,
+            });
         }
     }
     // Just for extra safety...
-    std::thread::sleep(std::time::Duration::from_millis(250));
+    runner.sleep(Duration::from_millis(250));
     Ok(())
 }
 
 /// Stops Cornucopia's container.
-fn stop_container(podman: bool) -> Result<(), Error> {
-    cmd(podman, &["stop", "cornucopia_postgres"], "stop container")
+fn stop_container(runner: &mut impl Runner) -> Result<(), Error> {
+    runner.run(&["stop", "cornucopia_postgres"], "stop container")
 }
 
 /// Removes Cornucopia's container and its volume.
-fn remove_container(podman: bool) -> Result<(), Error> {
-    cmd(
-        podman,
-        &["rm", "-v", "cornucopia_postgres"],
-        "remove container",
-    )
-}
-
-fn cmd(podman: bool, args: &[&'static str], action: &'static str) -> Result<(), Error> {
-    let command = if podman { "podman" } else { "docker" };
-    let output = Command::new(command)
-        .args(args)
-        .stderr(Stdio::piped())
-        .stdout(Stdio::null())
-        .output()?;
-
-    if output.status.success() {
-        Ok(())
-    } else {
-        let err = String::from_utf8_lossy(&output.stderr);
-        Err(Error::new(
-            format!("`{command}` couldn't {action}: {err}"),
-            podman,
-        ))
-    }
+fn remove_container(runner: &mut impl Runner) -> Result<(), Error> {
+    runner.run(&["rm", "-v", "cornucopia_postgres"], "remove container")
 }
 
 pub(crate) mod error {
@@ -107,12 +237,24 @@ pub(crate) mod error {
     use miette::Diagnostic;
     use thiserror::Error as ThisError;
 
+    /// Errors of the container manager reporting these, in lowercase, are not retried
+    const PERMANENT_ERRORS: [&str; 6] = [
+        "no such image",
+        "manifest unknown",
+        "pull access denied",
+        "repository does not exist",
+        "unauthorized",
+        "permission denied",
+    ];
+
     #[derive(Debug, ThisError, Diagnostic)]
     #[error("{msg}")]
     pub struct Error {
         msg: String,
         #[help]
         pub help: Option<String>,
+        /// Whether setting up the container again may succeed
+        pub(crate) is_transient: bool,
     }
 
     impl Error {
@@ -125,16 +267,165 @@ pub(crate) mod error {
             Error {
                 msg,
                 help: Some(String::from(help)),
+                is_transient: true,
+            }
+        }
+
+        /// Failure of the container manager `command`, reported on its `stderr`
+        pub(crate) fn command(command: &str, action: &str, stderr: &str, podman: bool) -> Self {
+            let lowercase = stderr.to_lowercase();
+            Error {
+                is_transient: !PERMANENT_ERRORS.iter().any(|it| lowercase.contains(it)),
+                ..Self::new(format!("`{command}` couldn't {action}: {stderr}"), podman)
             }
         }
     }
 
     impl From<std::io::Error> for Error {
         fn from(e: std::io::Error) -> Self {
+            // The container manager cannot be run at all
             Self {
                 msg: format!("{e:#}"),
                 help: None,
+                is_transient: false,
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use std::time::Duration;
+
+    use crate::ProgressEvent;
+
+    use super::{error::Error, setup_with, ContainerSettings, Runner};
+
+    /// Fails to spawn the container with these errors of the container manager, then
+    /// succeeds, recording the commands it runs
+    #[derive(Default)]
+    struct FlakyRunner {
+        failures: Vec<&'static str>,
+        commands: Vec<String>,
+        sleeps: Vec<Duration>,
+        /// Args of the last `run` command
+        spawn_args: Vec<String>,
+    }
+
+    impl Runner for FlakyRunner {
+        fn run(&mut self, args: &[&str], action: &'static str) -> Result<(), Error> {
+            self.commands.push(args[0].to_string());
+            if args[0] == "run" {
+                self.spawn_args = args.iter().map(ToString::to_string).collect();
+            }
+            if args[0] == "run" && !self.failures.is_empty() {
+                let stderr = self.failures.remove(0);
+                return Err(Error::command("docker", action, stderr, false));
             }
+            Ok(())
+        }
+
+        fn connect(&mut self) -> Result<(), Error> {
+            Ok(())
+        }
+
+        fn sleep(&mut self, duration: Duration) {
+            self.sleeps.push(duration);
+        }
+
+        fn is_podman(&self) -> bool {
+            false
+        }
+    }
+
+    fn setup_runner(
+        failures: Vec<&'static str>,
+        retries: u32,
+    ) -> (Result<(), Error>, FlakyRunner, u32) {
+        let mut runner = FlakyRunner {
+            failures,
+            ..FlakyRunner::default()
+        };
+        let settings = ContainerSettings {
+            retries,
+            ..ContainerSettings::default()
+        };
+        let mut attempts = 0;
+        let result = setup_with(&mut runner, &settings, &mut |event| {
+            if let ProgressEvent::ContainerRetry { attempt, .. } = event {
+                attempts = attempt;
+            }
+        });
+        (result, runner, attempts)
+    }
+
+    fn setup(failures: Vec<&'static str>, retries: u32) -> (Result<(), Error>, Vec<String>, u32) {
+        let (result, runner, attempts) = setup_runner(failures, retries);
+        (result, runner.commands, attempts)
+    }
+
+    const TRANSIENT: &str = "driver failed programming external connectivity on endpoint";
+
+    #[test]
+    fn transient_failures_are_retried() {
+        let (result, commands, attempts) = setup(vec![TRANSIENT, TRANSIENT], 2);
+        assert!(result.is_ok());
+        assert_eq!(attempts, 2);
+        // The container of each failed attempt is removed before the next one
+        assert_eq!(commands, ["run", "rm", "run", "rm", "run", "exec"]);
+    }
+
+    #[test]
+    fn retries_are_bounded() {
+        let (result, commands, attempts) = setup(vec![TRANSIENT, TRANSIENT], 1);
+        assert!(result.unwrap_err().to_string().contains(TRANSIENT));
+        assert_eq!(attempts, 1);
+        assert_eq!(commands, ["run", "rm", "run"]);
+    }
+
+    #[test]
+    fn permanent_failures_are_not_retried() {
+        let (result, commands, attempts) =
+            setup(vec!["Error: No such image: pgvector/pgvector:pg18"], 2);
+        assert!(result.is_err());
+        assert_eq!(attempts, 0);
+        assert_eq!(commands, ["run"]);
+    }
+
+    #[test]
+    fn backoff_is_capped() {
+        let (result, runner, attempts) = setup_runner(vec![TRANSIENT; 40], 40);
+        assert!(result.is_ok());
+        assert_eq!(attempts, 40);
+        let backoffs: Vec<_> = runner
+            .sleeps
+            .iter()
+            .map(Duration::as_secs)
+            .filter(|secs| *secs > 0)
+            .collect();
+        assert_eq!(backoffs[..7], [1, 2, 4, 8, 16, 32, 32]);
+        assert_eq!(backoffs.iter().max(), Some(&32));
+    }
+
+    #[test]
+    fn prepared_transactions_are_allowed_when_requested() {
+        for prepared_transactions in [false, true] {
+            let mut runner = FlakyRunner::default();
+            let settings = ContainerSettings {
+                image: "docker.io/pgvector/pgvector:pg18".to_string(),
+                prepared_transactions,
+                ..ContainerSettings::default()
+            };
+            setup_with(&mut runner, &settings, &mut ()).unwrap();
+            assert!(runner
+                .spawn_args
+                .contains(&"docker.io/pgvector/pgvector:pg18".to_string()));
+            assert_eq!(
+                runner
+                    .spawn_args
+                    .contains(&"max_prepared_transactions=10".to_string()),
+                prepared_transactions
+            );
         }
     }
 }
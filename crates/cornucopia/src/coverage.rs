@@ -0,0 +1,196 @@
+use std::fmt::Display;
+
+use postgres::Client;
+use serde::Serialize;
+
+use crate::{
+    analysis::identifiers,
+    database::{relation_columns, RelationColumn},
+    prepare_queries::Preparation,
+    CoverageSettings,
+};
+
+use self::error::Error;
+
+/// How the columns are traced back to the queries, stated in the header of the report
+const LIMITATIONS: &str = "Columns are traced through the results of the queries when \
+they are returned as plain table columns. Columns only used in expressions \
+(e.g. `upper(name)`), in clauses or by parameters are matched by their name appearing \
+in a query naming their table, which is best-effort: `INSERT` statements without a \
+column list are missed, while columns named like another identifier of a query, and \
+tables named alike in several schemas, are not told apart.";
+
+/// Columns of the database referenced by no query, reported by
+/// [`coverage_live`](crate::coverage_live).
+///
+/// It is displayed as a human-readable table, or serialized using [`Coverage::to_json`].
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+#[non_exhaustive]
+pub struct Coverage {
+    /// Number of queries the columns were checked against.
+    pub queries: usize,
+    /// Tables with columns referenced by no query, sorted by schema and name.
+    pub tables: Vec<TableCoverage>,
+    /// Queries declaring no latency budget, as `module::query`, only listed when
+    /// [`require_budgets`](CoverageSettings::require_budgets) is set.
+    pub unbudgeted_queries: Vec<String>,
+}
+
+/// Columns of a table referenced by no query.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+#[non_exhaustive]
+pub struct TableCoverage {
+    pub schema: String,
+    pub name: String,
+    /// Whether no query references the table at all, all its columns being unreferenced.
+    pub unqueried: bool,
+    /// Columns referenced by no query, in declaration order.
+    pub unreferenced_columns: Vec<String>,
+}
+
+impl Coverage {
+    /// The report as a JSON object, with its limitations and tables.
+    #[must_use]
+    pub fn to_json(&self) -> String {
+        let json = serde_json::json!({
+            "limitations": LIMITATIONS,
+            "queries": self.queries,
+            "tables": self.tables,
+            "unbudgeted_queries": self.unbudgeted_queries,
+        });
+        serde_json::to_string_pretty(&json).unwrap()
+    }
+}
+
+impl Display for Coverage {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        writeln!(f, "Column coverage of {} queries.", self.queries)?;
+        // The limitations are wrapped to be read in a terminal
+        let mut line = String::new();
+        for word in LIMITATIONS.split(' ') {
+            if !line.is_empty() && line.len() + word.len() >= 88 {
+                writeln!(f, "{line}")?;
+                line.clear();
+            }
+            if !line.is_empty() {
+                line.push(' ');
+            }
+            line.push_str(word);
+        }
+        writeln!(f, "{line}")?;
+        writeln!(f)?;
+        if self.tables.is_empty() {
+            writeln!(f, "Every column is referenced by a query.")?;
+        } else {
+            let names: Vec<_> = self
+                .tables
+                .iter()
+                .map(|it| format!("{}.{}", it.schema, it.name))
+                .collect();
+            let width = names.iter().map(String::len).max().unwrap_or_default();
+            writeln!(f, "{:width$}  UNREFERENCED COLUMNS", "TABLE")?;
+            for (name, table) in names.iter().zip(&self.tables) {
+                let columns = table.unreferenced_columns.join(", ");
+                if table.unqueried {
+                    writeln!(f, "{name:width$}  no query ({columns})")?;
+                } else {
+                    writeln!(f, "{name:width$}  {columns}")?;
+                }
+            }
+        }
+        if !self.unbudgeted_queries.is_empty() {
+            writeln!(f)?;
+            writeln!(f, "QUERIES WITHOUT A BUDGET")?;
+            for query in &self.unbudgeted_queries {
+                writeln!(f, "{query}")?;
+            }
+        }
+        Ok(())
+    }
+}
+
+/// Checks the columns of the database against the prepared queries
+pub(crate) fn coverage(
+    client: &mut Client,
+    preparation: &Preparation,
+    settings: &CoverageSettings,
+) -> Result<Coverage, Error> {
+    let columns = relation_columns(
+        client,
+        settings.include_views,
+        settings.include_system_schemas,
+    )?;
+    let queries: Vec<_> = preparation
+        .modules
+        .iter()
+        .flat_map(|it| it.queries.values())
+        .map(|query| (&query.sources, identifiers(&query.sql)))
+        .collect();
+
+    let mut tables = Vec::new();
+    for table in columns.chunk_by(|a, b| a.oid == b.oid) {
+        let RelationColumn {
+            oid,
+            schema,
+            relation,
+            ..
+        } = &table[0];
+        let naming: Vec<_> = queries
+            .iter()
+            .filter(|(_, identifiers)| identifiers.binary_search(relation).is_ok())
+            .collect();
+        let returned: Vec<_> = queries
+            .iter()
+            .flat_map(|(sources, _)| sources.iter())
+            .filter(|(it, _)| it == oid)
+            .map(|(_, column)| column)
+            .collect();
+        let unqueried = naming.is_empty() && returned.is_empty();
+        let is_referenced = |column: &String| {
+            returned.contains(&column)
+                || naming
+                    .iter()
+                    .any(|(_, identifiers)| identifiers.binary_search(column).is_ok())
+        };
+        let unreferenced_columns: Vec<_> = table
+            .iter()
+            .map(|it| &it.column)
+            .filter(|column| !is_referenced(column))
+            .cloned()
+            .collect();
+        if !unreferenced_columns.is_empty() {
+            tables.push(TableCoverage {
+                schema: schema.clone(),
+                name: relation.clone(),
+                unqueried,
+                unreferenced_columns,
+            });
+        }
+    }
+    let mut unbudgeted_queries = Vec::new();
+    if settings.require_budgets {
+        for module in &preparation.modules {
+            for (name, query) in &module.queries {
+                if query.budget_ms.is_none() {
+                    unbudgeted_queries.push(format!("{}::{}", module.name, name.value));
+                }
+            }
+        }
+    }
+    Ok(Coverage {
+        queries: queries.len(),
+        tables,
+        unbudgeted_queries,
+    })
+}
+
+pub(crate) mod error {
+    use miette::Diagnostic;
+    use thiserror::Error as ThisError;
+
+    #[derive(Debug, ThisError, Diagnostic)]
+    pub enum Error {
+        #[error("Could not read the columns of the database: ({0})")]
+        Db(#[from] postgres::Error),
+    }
+}
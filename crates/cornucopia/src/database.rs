@@ -0,0 +1,467 @@
+use std::collections::BTreeSet;
+
+use postgres::Client;
+use postgres_types::Type;
+use serde::{Deserialize, Serialize};
+
+use crate::{
+    analysis::split_statements,
+    snapshot::{ReplayError, Snapshot},
+    type_registrar::VECTOR_TYPE,
+};
+
+/// Parameters and columns types of a prepared statement
+#[derive(Debug, Clone)]
+pub(crate) struct Statement {
+    pub(crate) params: Vec<Type>,
+    pub(crate) columns: Vec<Column>,
+}
+
+impl Statement {
+    /// Prepares a statement, resolving the table columns its columns come from
+    pub(crate) fn prepare(client: &mut Client, sql: &str) -> Result<Self, postgres::Error> {
+        let stmt = client.prepare(sql)?;
+        let oids: Vec<u32> = stmt
+            .columns()
+            .iter()
+            .filter_map(postgres::Column::table_oid)
+            .collect();
+        // Result columns are described using the base type of domains, which is
+        // only found in the declaration of the table column
+        let table_columns: Vec<(u32, i16, ColumnOrigin)> = if oids.is_empty() {
+            Vec::new()
+        } else {
+            client
+                .query(
+                    "SELECT c.oid, a.attnum, c.relname::text, a.attname::text,
+                        CASE WHEN t.typtype = 'd' THEN t.typname::text END,
+                        CASE WHEN t.typname = 'vector' AND a.atttypmod > 0 THEN a.atttypmod END,
+                        a.attnotnull, pg_catalog.col_description(c.oid, a.attnum), n.nspname::text
+                    FROM pg_catalog.pg_class c
+                    JOIN pg_catalog.pg_namespace n ON n.oid = c.relnamespace
+                    JOIN pg_catalog.pg_attribute a ON a.attrelid = c.oid
+                    JOIN pg_catalog.pg_type t ON t.oid = a.atttypid
+                    WHERE c.oid = ANY($1) AND a.attnum > 0",
+                    &[&oids],
+                )?
+                .iter()
+                .map(|row| {
+                    let origin = ColumnOrigin {
+                        table_oid: row.get(0),
+                        schema: row.get(8),
+                        table: row.get(2),
+                        column: row.get(3),
+                        domain: row.get(4),
+                        dimensions: row.get(5),
+                        not_null: row.get(6),
+                        comment: row.get(7),
+                    };
+                    (row.get(0), row.get(1), origin)
+                })
+                .collect()
+        };
+        Ok(Self {
+            params: stmt.params().to_vec(),
+            columns: stmt
+                .columns()
+                .iter()
+                .map(|col| {
+                    let origin = col.table_oid().and_then(|oid| {
+                        table_columns
+                            .iter()
+                            .find(|(it, attnum, _)| *it == oid && Some(*attnum) == col.column_id())
+                            .map(|(_, _, origin)| origin.clone())
+                    });
+                    Column::new(col.name().to_string(), col.type_().clone(), origin)
+                })
+                .collect(),
+        })
+    }
+}
+
+/// Schemas and names of the enums and composite types declared in the database, excluding
+/// those of the system and of extensions, sorted independently of the database collation
+pub(crate) fn custom_types(client: &mut Client) -> Result<Vec<(String, String)>, postgres::Error> {
+    Ok(client
+        .query(
+            "SELECT n.nspname::text, t.typname::text
+            FROM pg_catalog.pg_type t
+            JOIN pg_catalog.pg_namespace n ON n.oid = t.typnamespace
+            LEFT JOIN pg_catalog.pg_class c ON c.oid = t.typrelid
+            WHERE (t.typtype = 'e' OR (t.typtype = 'c' AND c.relkind = 'c'))
+                AND n.nspname <> 'information_schema' AND n.nspname NOT LIKE 'pg\\_%'
+                AND NOT EXISTS (
+                    SELECT FROM pg_catalog.pg_depend d
+                    WHERE d.classid = 'pg_catalog.pg_type'::regclass
+                        AND d.objid = t.oid AND d.deptype = 'e'
+                )
+            ORDER BY n.nspname COLLATE \"C\", t.typname COLLATE \"C\"",
+            &[],
+        )?
+        .iter()
+        .map(|row| (row.get(0), row.get(1)))
+        .collect())
+}
+
+/// A column of a table or view of the database
+pub(crate) struct RelationColumn {
+    pub(crate) oid: u32,
+    pub(crate) schema: String,
+    pub(crate) relation: String,
+    pub(crate) column: String,
+}
+
+/// Columns of the tables declared in the database, in the order of their declaration.
+/// Views and materialized views, and the relations of the system schemas, are only
+/// included when asked for. Partitions are left out, their columns being read through
+/// their partitioned table.
+pub(crate) fn relation_columns(
+    client: &mut Client,
+    include_views: bool,
+    include_system_schemas: bool,
+) -> Result<Vec<RelationColumn>, postgres::Error> {
+    Ok(client
+        .query(
+            "SELECT c.oid, n.nspname::text, c.relname::text, a.attname::text
+            FROM pg_catalog.pg_class c
+            JOIN pg_catalog.pg_namespace n ON n.oid = c.relnamespace
+            JOIN pg_catalog.pg_attribute a ON a.attrelid = c.oid
+            WHERE (c.relkind IN ('r', 'p') OR ($1 AND c.relkind IN ('v', 'm')))
+                AND NOT c.relispartition AND a.attnum > 0 AND NOT a.attisdropped
+                AND ($2 OR (n.nspname <> 'information_schema' AND n.nspname NOT LIKE 'pg\\_%'))
+            ORDER BY n.nspname COLLATE \"C\", c.relname COLLATE \"C\", a.attnum",
+            &[&include_views, &include_system_schemas],
+        )?
+        .iter()
+        .map(|row| RelationColumn {
+            oid: row.get(0),
+            schema: row.get(1),
+            relation: row.get(2),
+            column: row.get(3),
+        })
+        .collect())
+}
+
+/// Names and versions of the extensions installed in the database
+pub(crate) fn extensions(client: &mut Client) -> Result<Vec<(String, String)>, postgres::Error> {
+    Ok(client
+        .query(
+            "SELECT extname::text, extversion FROM pg_catalog.pg_extension",
+            &[],
+        )?
+        .iter()
+        .map(|row| (row.get(0), row.get(1)))
+        .collect())
+}
+
+/// OID of the `vector` type declared by the pgvector extension, if it is installed
+pub(crate) fn vector_type(client: &mut Client) -> Result<Option<u32>, postgres::Error> {
+    Ok(client
+        .query_opt(
+            "SELECT t.oid FROM pg_catalog.pg_type t
+            JOIN pg_catalog.pg_depend d ON d.classid = 'pg_catalog.pg_type'::regclass
+                AND d.objid = t.oid AND d.deptype = 'e'
+            JOIN pg_catalog.pg_extension e ON e.oid = d.refobjid
+            WHERE e.extname = $1 AND t.typname = $1",
+            &[&VECTOR_TYPE],
+        )?
+        .map(|row| row.get(0)))
+}
+
+/// A column returned by a prepared statement
+#[derive(Debug, Clone)]
+pub(crate) struct Column {
+    name: String,
+    type_: Type,
+    /// Table column this column is read from, if it is a plain table column
+    origin: Option<ColumnOrigin>,
+}
+
+/// The table column a returned column is read from
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub(crate) struct ColumnOrigin {
+    /// OID of the table, which is not recorded in snapshots
+    #[serde(skip)]
+    pub(crate) table_oid: u32,
+    /// Schema of the table, unknown to older snapshots
+    #[serde(default)]
+    pub(crate) schema: String,
+    pub(crate) table: String,
+    pub(crate) column: String,
+    /// Name of the domain the table column is declared with
+    pub(crate) domain: Option<String>,
+    /// Dimensions of the embeddings of the table column, if it is declared with `vector(n)`
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub(crate) dimensions: Option<i32>,
+    /// Whether the table column is declared `NOT NULL`, unknown to older snapshots
+    #[serde(default)]
+    pub(crate) not_null: bool,
+    /// Comment of the table column, if any
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub(crate) comment: Option<String>,
+}
+
+impl Column {
+    pub(crate) fn new(name: String, type_: Type, origin: Option<ColumnOrigin>) -> Self {
+        Self {
+            name,
+            type_,
+            origin,
+        }
+    }
+
+    pub(crate) fn name(&self) -> &str {
+        &self.name
+    }
+
+    pub(crate) fn type_(&self) -> &Type {
+        &self.type_
+    }
+
+    pub(crate) fn table(&self) -> Option<&str> {
+        self.origin.as_ref().map(|it| it.table.as_str())
+    }
+
+    pub(crate) fn origin(&self) -> Option<&ColumnOrigin> {
+        self.origin.as_ref()
+    }
+}
+
+/// Reason why a statement could not be prepared
+pub(crate) enum PrepareError {
+    Db(postgres::Error),
+    Replay(ReplayError),
+    /// Error of the statement of a batch query starting at `offset`
+    Batch {
+        offset: usize,
+        err: postgres::Error,
+    },
+}
+
+/// Database against which queries are prepared
+pub(crate) enum Database<'a> {
+    /// A live database, optionally recording every prepared statement in a snapshot
+    Live {
+        client: &'a mut Client,
+        recording: Option<&'a mut Snapshot>,
+    },
+    /// A snapshot previously recorded from a live database
+    Offline(&'a Snapshot),
+    /// A live database, the statements of the `unchanged` modules being replayed from the
+    /// cache of the previous generation, while every statement is cached for the next one
+    Incremental {
+        client: &'a mut Client,
+        cache: &'a Snapshot,
+        unchanged: BTreeSet<String>,
+        recording: &'a mut Snapshot,
+    },
+}
+
+impl<'a> Database<'a> {
+    pub(crate) fn live(client: &'a mut Client) -> Self {
+        Self::Live {
+            client,
+            recording: None,
+        }
+    }
+
+    /// Whether statements are only prepared to be recorded in a snapshot
+    pub(crate) fn is_recording(&self) -> bool {
+        matches!(
+            self,
+            Self::Live {
+                recording: Some(_),
+                ..
+            }
+        )
+    }
+
+    /// Whether the statements of this module are replayed from the cache of the previous
+    /// generation
+    pub(crate) fn is_cached(&self, module: &str) -> bool {
+        matches!(self, Self::Incremental { unchanged, .. } if unchanged.contains(module))
+    }
+
+    /// The client of a live database, with the snapshot its statements are recorded in
+    fn connection(&mut self) -> Option<(&mut Client, Option<&mut Snapshot>)> {
+        match self {
+            Self::Live { client, recording } => Some((client, recording.as_deref_mut())),
+            Self::Incremental {
+                client, recording, ..
+            } => Some((client, Some(recording))),
+            Self::Offline(_) => None,
+        }
+    }
+
+    /// Replays the preparation of a statement of an unchanged module from the cache. A
+    /// statement missing from the cache or stale has the rest of its module prepared again.
+    fn replay_cached(&mut self, module: &str, query: &str, sql: &str) -> Option<Statement> {
+        let Self::Incremental {
+            cache,
+            unchanged,
+            recording,
+            ..
+        } = self
+        else {
+            return None;
+        };
+        if !unchanged.contains(module) {
+            return None;
+        }
+        match cache.replay(module, query, sql) {
+            Ok(stmt) => {
+                recording.record(module, query, sql, &stmt);
+                Some(stmt)
+            }
+            Err(_) => {
+                unchanged.remove(module);
+                None
+            }
+        }
+    }
+
+    /// Prepares a statement, or replays its preparation when offline or cached
+    pub(crate) fn prepare(
+        &mut self,
+        module: &str,
+        query: &str,
+        sql: &str,
+    ) -> Result<Statement, PrepareError> {
+        if let Self::Offline(snapshot) = self {
+            return snapshot
+                .replay(module, query, sql)
+                .map_err(PrepareError::Replay);
+        }
+        if let Some(stmt) = self.replay_cached(module, query, sql) {
+            return Ok(stmt);
+        }
+        let (client, recording) = self.connection().expect("database is live");
+        let stmt = Statement::prepare(client, sql).map_err(PrepareError::Db)?;
+        if let Some(snapshot) = recording {
+            snapshot.record(module, query, sql, &stmt);
+        }
+        Ok(stmt)
+    }
+
+    /// Checks a batch query by preparing each of its statements, which parses and analyzes
+    /// them without executing them, or that it did not change since the snapshot was
+    /// recorded when offline or cached. Statements can't use the objects created by the
+    /// previous ones of the batch, which don't exist yet.
+    pub(crate) fn check_batch(
+        &mut self,
+        module: &str,
+        query: &str,
+        sql: &str,
+    ) -> Result<(), PrepareError> {
+        if let Self::Offline(snapshot) = self {
+            return snapshot
+                .replay(module, query, sql)
+                .map(|_| ())
+                .map_err(PrepareError::Replay);
+        }
+        if self.replay_cached(module, query, sql).is_some() {
+            return Ok(());
+        }
+        let (client, recording) = self.connection().expect("database is live");
+        for (offset, statement) in split_statements(sql) {
+            client
+                .prepare(statement)
+                .map_err(|err| PrepareError::Batch { offset, err })?;
+        }
+        if let Some(snapshot) = recording {
+            let stmt = Statement {
+                params: Vec::new(),
+                columns: Vec::new(),
+            };
+            snapshot.record(module, query, sql, &stmt);
+        }
+        Ok(())
+    }
+
+    /// Records the extensions installed in the database in the snapshot being recorded
+    pub(crate) fn record_extensions(&mut self) -> Result<(), postgres::Error> {
+        if let Self::Live {
+            client,
+            recording: Some(snapshot),
+        } = self
+        {
+            snapshot.record_extensions(extensions(client)?);
+        }
+        Ok(())
+    }
+
+    /// OIDs of the given domains declared `NOT NULL`, as recorded in the snapshot when offline
+    pub(crate) fn not_null_domains(&mut self, oids: &[u32]) -> Result<Vec<u32>, postgres::Error> {
+        if let Self::Offline(snapshot) = self {
+            return Ok(snapshot.not_null_domains(oids));
+        }
+        let (client, recording) = self.connection().expect("database is live");
+        let not_null: Vec<u32> = if oids.is_empty() {
+            Vec::new()
+        } else {
+            client
+                .query(
+                    "SELECT oid FROM pg_catalog.pg_type WHERE oid = ANY($1) AND typnotnull",
+                    &[&oids],
+                )?
+                .iter()
+                .map(|row| row.get(0))
+                .collect()
+        };
+        if let Some(snapshot) = recording {
+            snapshot.record_not_null_domains(&not_null);
+        }
+        Ok(not_null)
+    }
+
+    /// OID of the `vector` type of the pgvector extension, as recorded in the snapshot when
+    /// offline
+    pub(crate) fn vector_type(&mut self) -> Result<Option<u32>, postgres::Error> {
+        if let Self::Offline(snapshot) = self {
+            return Ok(snapshot.vector_type());
+        }
+        let (client, recording) = self.connection().expect("database is live");
+        let oid = vector_type(client)?;
+        if let Some(snapshot) = recording {
+            snapshot.record_vector_type(oid);
+        }
+        Ok(oid)
+    }
+
+    /// Whether the schema exists in the database, which is assumed when offline
+    pub(crate) fn has_schema(&mut self, schema: &str) -> Result<bool, postgres::Error> {
+        match self.connection() {
+            Some((client, _)) => Ok(client
+                .query_one(
+                    "SELECT EXISTS (SELECT FROM pg_catalog.pg_namespace WHERE nspname = $1)",
+                    &[&schema],
+                )?
+                .get(0)),
+            None => Ok(true),
+        }
+    }
+
+    /// `server_version_num` of the database, as recorded in the snapshot when offline
+    pub(crate) fn server_version(&mut self) -> Result<i32, PrepareError> {
+        if let Self::Offline(snapshot) = self {
+            return snapshot.server_version().map_err(PrepareError::Replay);
+        }
+        let (client, recording) = self.connection().expect("database is live");
+        let version = client
+            .query_one("SELECT current_setting('server_version_num')::int", &[])
+            .map_err(PrepareError::Db)?
+            .get(0);
+        if let Some(snapshot) = recording {
+            snapshot.record_server_version(version);
+        }
+        Ok(version)
+    }
+
+    /// Executes some statements, doing nothing when offline
+    pub(crate) fn batch_execute(&mut self, sql: &str) -> Result<(), postgres::Error> {
+        match self.connection() {
+            Some((client, _)) => client.batch_execute(sql),
+            None => Ok(()),
+        }
+    }
+}
@@ -8,12 +8,16 @@ use thiserror::Error as ThisError;
 #[error(transparent)]
 #[diagnostic(transparent)]
 pub enum Error {
+    /// An error while parsing the args of the CLI or loading its `.env` file.
+    Cli(#[from] crate::cli::error::Error),
     /// An error while trying to connect to a database.
     Connection(#[from] crate::conn::error::Error),
     /// An error while trying to read PostgreSQL query files.
     ReadQueries(#[from] crate::read_queries::error::Error),
     /// An error while trying to parse PostgreSQL query files.
     ParseQueries(#[from] crate::parser::error::Error),
+    /// An error while formatting PostgreSQL query files.
+    Format(#[from] crate::format_queries::error::Error),
     /// An error while trying to validate PostgreSQL query files.
     ValidateQueries(#[from] Box<crate::validation::error::Error>),
     /// An error while manipulating a container managed by Cornucopia.
@@ -22,8 +26,24 @@ pub enum Error {
     PrepareQueries(#[from] crate::prepare_queries::error::Error),
     /// An error while reading PostgreSQL schema files.
     LoadSchema(#[from] crate::load_schema::error::Error),
+    /// An error while reading the configuration file of the CLI.
+    Config(#[from] crate::config::error::Error),
+    /// An error while checking the columns of the database against the queries.
+    Coverage(#[from] crate::coverage::error::Error),
+    /// An error while generating the benches of the queries.
+    Benches(#[from] crate::benches::error::Error),
+    /// An error while embedding or applying migrations.
+    Migrations(#[from] crate::migrations::error::Error),
+    /// An error while reading or writing a snapshot.
+    Snapshot(#[from] crate::snapshot::error::Error),
+    /// An error while writing the cache of an incremental generation.
+    Cache(#[from] crate::cache::error::Error),
+    /// An error while watching the query files to regenerate the code.
+    Watch(#[from] crate::watch::error::Error),
     /// An error while trying to write the generated code to its destination file.
     WriteCodeGenFile(#[from] WriteOutputError),
+    /// Errors of several query modules, reported at once when generation keeps going.
+    Modules(#[from] ModuleErrors),
 }
 
 impl Error {
@@ -39,8 +59,17 @@ impl Error {
 }
 
 #[derive(Debug, ThisError, Diagnostic)]
-#[error("Could not write your queries to destination file `{file_path}`: ({err})")]
+#[error("query modules: {} failed, {passed} passed", errors.len())]
+pub struct ModuleErrors {
+    #[related]
+    pub(crate) errors: Vec<Error>,
+    pub(crate) passed: usize,
+}
+
+#[derive(Debug, ThisError, Diagnostic)]
+#[error("Could not {action} destination file `{file_path}`: ({err})")]
 pub struct WriteOutputError {
     pub(crate) file_path: PathBuf,
+    pub(crate) action: &'static str,
     pub(crate) err: std::io::Error,
 }
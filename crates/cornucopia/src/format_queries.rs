@@ -0,0 +1,281 @@
+use std::{
+    ops::Range,
+    path::{Path, PathBuf},
+};
+
+use miette::SourceSpan;
+
+use crate::{
+    analysis::{block_comment_len, token_len},
+    parser::{parse_query_module, Module, NullableIdent, Query, QueryDataStruct, QueryOption},
+    read_queries::read_query_modules,
+    FormatSettings,
+};
+
+use self::error::Error;
+
+/// Formats the `.sql` query files of the directory, returning the paths of the files that
+/// were reformatted, or would be when only checking.
+pub(crate) fn format_queries(
+    dir_path: &Path,
+    settings: &FormatSettings,
+) -> Result<Vec<PathBuf>, crate::Error> {
+    let mut reformatted = Vec::new();
+    for info in read_query_modules(dir_path)? {
+        // Embedded queries are part of a Rust file, left to rustfmt
+        if info.path.extension().is_some_and(|it| it != "sql") {
+            continue;
+        }
+        let module = parse_query_module(info)?;
+        let formatted = format_module(&module, settings.sql);
+        if formatted == *module.info.content {
+            continue;
+        }
+        if !settings.check {
+            std::fs::write(&module.info.path, formatted).map_err(|err| Error::Write {
+                path: module.info.path.clone(),
+                err,
+            })?;
+        }
+        reformatted.push(module.info.path);
+    }
+    Ok(reformatted)
+}
+
+/// Formats the text of a parsed module. Only the annotations, the lines between the
+/// statements and optionally the SQL are rewritten, the rest of the text being kept
+/// verbatim using the spans of the statements.
+fn format_module(module: &Module, reflow: bool) -> String {
+    let content = module.info.content.as_str();
+    // Ranges of the text replaced by their formatted version
+    let mut edits: Vec<(Range<usize>, String)> = Vec::new();
+    let mut statements = Vec::new();
+    for ty in &module.types {
+        statements.push(range(ty.span));
+        edits.push((
+            range(ty.span),
+            format!("--: {}{}", ident(&ty.name.value), fields(&ty.fields)),
+        ));
+    }
+    for lock in &module.locks {
+        statements.push(range(lock.span));
+        edits.push((range(lock.span), format!("--! lock {}", lock.name.value)));
+    }
+    for query in &module.queries {
+        let sql = range(query.sql_span);
+        statements.push(range(query.annotation_span).start..sql.end);
+        edits.push((range(query.annotation_span), query_annotation(query)));
+        for option in &query.options {
+            edits.push((range(option.span), query_option(option)));
+        }
+        if reflow {
+            edits.push((sql.clone(), reflow_sql(&content[sql])));
+        }
+    }
+    statements.sort_by_key(|it| it.start);
+
+    let mut start = 0;
+    for (i, statement) in statements.iter().enumerate() {
+        let text = gap(&content[start..statement.start], i == 0, false);
+        edits.push((start..statement.start, text));
+        start = statement.end;
+    }
+    let text = gap(&content[start..], statements.is_empty(), true);
+    edits.push((start..content.len(), text));
+
+    // Empty gaps sort before the statement they precede
+    edits.sort_by_key(|(range, _)| (range.start, range.end));
+    let mut formatted = String::with_capacity(content.len());
+    let mut idx = 0;
+    for (range, text) in edits {
+        formatted.push_str(&content[idx..range.start]);
+        formatted.push_str(&text);
+        idx = range.end;
+    }
+    formatted.push_str(&content[idx..]);
+    formatted
+}
+
+fn range(span: SourceSpan) -> Range<usize> {
+    span.offset()..span.offset() + span.len()
+}
+
+/// The identifier, quoted unless it is made of alphanumeric characters and underscores
+fn ident(name: &str) -> String {
+    if name.chars().all(|c| c.is_ascii_alphanumeric() || c == '_') {
+        name.to_string()
+    } else {
+        format!("\"{name}\"")
+    }
+}
+
+/// `(a?, b[?] #[attr], c record(d: int4), e: crate::E)`
+fn fields(fields: &[NullableIdent]) -> String {
+    let fields: Vec<_> = fields
+        .iter()
+        .map(|field| {
+            let mut formatted = ident(&field.name.value);
+            if field.nullable {
+                formatted.push('?');
+            }
+            if field.inner_nullable {
+                formatted.push_str("[?]");
+            }
+            if let Some(record) = &field.record {
+                let record: Vec<_> = record
+                    .iter()
+                    .map(|it| {
+                        let null = if it.nullable { "?" } else { "" };
+                        format!("{}{null}: {}", ident(&it.name.value), it.ty.value)
+                    })
+                    .collect();
+                formatted.push_str(&format!(" record({})", record.join(", ")));
+            }
+            if let Some(path) = &field.json_type {
+                formatted.push_str(&format!(": {}", path.value));
+            }
+            for attribute in &field.attributes {
+                formatted.push(' ');
+                formatted.push_str(attribute);
+            }
+            formatted
+        })
+        .collect();
+    format!("({})", fields.join(", "))
+}
+
+/// `Name`, `(a?, b)` or `Name(a?, b)`
+fn data_struct(data: &QueryDataStruct) -> String {
+    let name = data.name.as_ref().map_or("", |it| it.value.as_str());
+    let idents = data.idents.as_deref().map(fields).unwrap_or_default();
+    format!("{name}{idents}")
+}
+
+/// `--! name @pg>=15 Params(a?) : Row(b?)`, the colon sticking to the name of the query
+/// when it has no params: `--! name: Row(b?)`
+fn query_annotation(query: &Query) -> String {
+    let mut annotation = format!("--! {}", query.name.value);
+    if let Some(version) = &query.version {
+        annotation.push(' ');
+        annotation.push_str(&version.value.to_string());
+    }
+    if !query.param.is_empty() {
+        annotation.push(' ');
+        annotation.push_str(&data_struct(&query.param));
+    }
+    if !query.row.is_empty() {
+        annotation.push_str(if query.param.is_empty() { ": " } else { " : " });
+        annotation.push_str(&data_struct(&query.row));
+    }
+    annotation
+}
+
+/// `-- :name arg1 arg2`
+fn query_option(option: &QueryOption) -> String {
+    let mut formatted = format!("-- :{}", option.name.value);
+    for arg in &option.args {
+        formatted.push(' ');
+        formatted.push_str(&arg.value);
+    }
+    formatted
+}
+
+/// Formats the text between two statements, made of whitespace and comments. Trailing
+/// whitespace is trimmed, runs of blank lines are collapsed into one and each statement
+/// starts its own line. The `first` text of the file has no leading blank line and the
+/// `last` one ends with a single line break.
+fn gap(text: &str, first: bool, last: bool) -> String {
+    let mut lines = text.split('\n').map(str::trim_end);
+    // The end of the line of the previous statement
+    let end = if first { None } else { lines.next() };
+    let mut lines: Vec<_> = lines.collect();
+    if !last {
+        // The indentation of the next statement
+        lines.pop();
+    }
+    let mut kept: Vec<&str> = Vec::new();
+    for line in lines {
+        let blank = line.is_empty();
+        if blank && (kept.last().map_or(first, |it| it.is_empty())) {
+            continue;
+        }
+        kept.push(line);
+    }
+    if last {
+        while kept.last().is_some_and(|it| it.is_empty()) {
+            kept.pop();
+        }
+    }
+
+    let mut formatted = String::new();
+    for line in end.into_iter().chain(kept) {
+        formatted.push_str(line);
+        formatted.push('\n');
+    }
+    formatted
+}
+
+/// Reflows the SQL of a query conservatively: leading blank lines are removed, trailing
+/// whitespace is trimmed and runs of blank lines are collapsed into one. Literals, quoted
+/// identifiers and block comments are kept verbatim.
+fn reflow_sql(sql: &str) -> String {
+    let mut reflowed = String::with_capacity(sql.len());
+    let mut idx = 0;
+    while let Some(c) = sql[idx..].chars().next() {
+        let rest = &sql[idx..];
+        if c.is_whitespace() {
+            let len = rest
+                .find(|c: char| !c.is_whitespace())
+                .unwrap_or(rest.len());
+            let space = &rest[..len];
+            match space.rfind('\n') {
+                Some(last) => {
+                    if !reflowed.is_empty() {
+                        let lines = space.matches('\n').count().min(2);
+                        reflowed.push_str(&"\n".repeat(lines));
+                    }
+                    reflowed.push_str(&space[last + 1..]);
+                }
+                None => reflowed.push_str(space),
+            }
+            idx += len;
+        } else if rest.starts_with("--") {
+            let len = rest.find('\n').unwrap_or(rest.len());
+            reflowed.push_str(rest[..len].trim_end());
+            idx += len;
+        } else {
+            let len = if rest.starts_with("/*") {
+                block_comment_len(rest)
+            } else {
+                token_len(rest)
+            };
+            reflowed.push_str(&rest[..len]);
+            idx += len;
+        }
+    }
+    reflowed
+}
+
+pub(crate) mod error {
+    use std::path::PathBuf;
+
+    use miette::Diagnostic;
+    use thiserror::Error as ThisError;
+
+    #[derive(Debug, ThisError, Diagnostic)]
+    pub enum Error {
+        #[error("Could not write the formatted query file `{path}`: ({err})")]
+        Write { path: PathBuf, err: std::io::Error },
+        #[error("the query files {} are not formatted", list(files))]
+        #[diagnostic(help("run `cornucopia fmt` to format them"))]
+        Unformatted { files: Vec<PathBuf> },
+    }
+
+    fn list(files: &[PathBuf]) -> String {
+        let files: Vec<_> = files
+            .iter()
+            .map(|it| format!("`{}`", it.display()))
+            .collect();
+        files.join(", ")
+    }
+}
@@ -1,64 +1,441 @@
+mod analysis;
+mod benches;
+mod cache;
 mod cli;
 mod codegen;
+mod config;
+mod coverage;
+mod database;
 mod error;
+mod format_queries;
 mod load_schema;
+mod migrations;
+mod nullability;
 mod parser;
 mod prepare_queries;
+mod progress;
 mod read_queries;
+mod settings;
+mod snapshot;
 mod type_registrar;
 mod utils;
 mod validation;
+mod watch;
 
 /// Helpers to establish connections to database instances.
 pub mod conn;
 /// High-level interfaces to work with Cornucopia's container manager.
 pub mod container;
 
-use std::path::Path;
+use std::path::{Path, PathBuf};
 
 use postgres::Client;
 
-use codegen::generate as generate_internal;
-use error::WriteOutputError;
-use parser::parse_query_module;
-use prepare_queries::prepare;
-use read_queries::read_query_modules;
+use benches::write_benches;
+use cache::Cache;
+use codegen::{generate as generate_internal, generate_types, GenCtx};
+use container::ContainerSettings;
+use database::Database;
+use error::{ModuleErrors, WriteOutputError};
+use migrations::{gen_migrations, read_migrations};
+use parser::{parse_query_module, Module};
+use prepare_queries::{prepare, prepare_shared_types, prepare_sql, Preparation};
+use progress::PrintObserver;
+use read_queries::{read_query_modules, ModuleInfo};
+use snapshot::Snapshot;
 
 #[doc(hidden)]
-pub use cli::run;
+pub use cli::{run, run_from};
 
+pub use analysis::{analyze, normalize_sql, QueryAnalysis};
+pub use cornucopia_client_core::Migration;
+pub use coverage::{Coverage, TableCoverage};
 pub use error::Error;
-pub use load_schema::load_schema;
+pub use load_schema::{load_schema, load_schema_with_observer};
+pub use migrations::run_embedded_migrations;
+pub use progress::{ProgressEvent, ProgressObserver, Warning};
+pub use settings::{
+    CodegenSettings, CodegenSettingsBuilder, DateTimeCrate, NaiveTimestampPolicy, NumericCrate,
+    RenameTarget, TargetSet,
+};
 
-/// Struct containing the settings for code generation.
-#[derive(Clone, Copy)]
-pub struct CodegenSettings {
-    pub gen_async: bool,
-    pub gen_sync: bool,
-    pub derive_ser: bool,
+/// Struct containing the settings for standalone query validation.
+#[derive(Clone, Debug, Default)]
+pub struct ValidationSettings {
+    /// Names of the returned columns that should be considered nullable.
+    pub nullable_columns: Vec<String>,
+}
+
+/// Struct containing the settings of the column coverage report.
+#[derive(Clone, Debug, Default)]
+pub struct CoverageSettings {
+    /// Whether the columns of views and materialized views are reported along with those
+    /// of tables.
+    pub include_views: bool,
+    /// Whether the tables of the system schemas (`pg_catalog`, `information_schema`, ...)
+    /// are reported.
+    pub include_system_schemas: bool,
+    /// `key=value` tags of the queries the columns are checked against, every query being
+    /// checked if empty.
+    pub only_tags: Vec<String>,
+    /// Whether the queries declaring no latency budget using the `budget` option are
+    /// listed along with the columns.
+    pub require_budgets: bool,
+}
+
+/// Struct containing the settings of the formatting of query files.
+#[derive(Clone, Debug, Default)]
+pub struct FormatSettings {
+    /// Whether the files are left untouched, only reporting the ones that are not formatted.
+    pub check: bool,
+    /// Whether the SQL of the queries is also reflowed, trimming trailing whitespace and
+    /// collapsing blank lines outside of literals.
+    pub sql: bool,
+}
+
+/// The Rust interface inferred for a validated SQL query.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct ValidatedQuery {
+    /// Rust types of the query parameters, in positional order.
+    pub param_types: Vec<String>,
+    /// Name, Rust type and nullity of each returned column.
+    pub column_types: Vec<(String, String, bool)>,
+}
+
+/// Validates a single SQL query against the schema of a live database managed by you,
+/// without requiring a queries directory. The query uses PostgreSQL's positional
+/// parameters (`$1`, `$2`, ...).
+pub fn validate_query_against_schema(
+    client: &mut Client,
+    sql: &str,
+    settings: &ValidationSettings,
+) -> Result<ValidatedQuery, Error> {
+    let (params, row) = prepare_sql(client, sql, &settings.nullable_columns)?;
+    let ctx = GenCtx::new(0, false);
+    Ok(ValidatedQuery {
+        param_types: params
+            .iter()
+            .map(|p| p.ty.own_ty(p.is_inner_nullable, &ctx))
+            .collect(),
+        column_types: row
+            .iter()
+            .map(|f| {
+                (
+                    f.ident.db.clone(),
+                    f.ty.own_ty(f.is_inner_nullable, &ctx),
+                    f.is_nullable,
+                )
+            })
+            .collect(),
+    })
 }
 
 /// Generates Rust queries from PostgreSQL queries located at `queries_path`,
 /// using a live database managed by you. If some `destination` is given,
-/// the generated code will be written at that path. Code generation settings are
-/// set using the `settings` parameter.
+/// the generated code will be written at that path, along with the benches requested
+/// using [`CodegenSettingsBuilder::emit_benches`] and the cache of the statements
+/// requested using [`CodegenSettingsBuilder::incremental`]. Code generation settings are
+/// set using the `settings` parameter, and the warnings are printed to stderr.
 pub fn generate_live<P: AsRef<Path>>(
     client: &mut Client,
     queries_path: P,
     destination: Option<P>,
     settings: CodegenSettings,
+) -> Result<String, Error> {
+    generate_live_with_observer(
+        client,
+        queries_path,
+        destination,
+        settings,
+        &mut PrintObserver { verbose: false },
+    )
+}
+
+/// Like [`generate_live`], the progress of the generation being reported to `observer`
+/// instead of the warnings being printed to stderr.
+pub fn generate_live_with_observer<P: AsRef<Path>>(
+    client: &mut Client,
+    queries_path: P,
+    destination: Option<P>,
+    settings: CodegenSettings,
+    observer: &mut dyn ProgressObserver,
 ) -> Result<String, Error> {
     // Read
-    let modules = read_query_modules(queries_path.as_ref())?
-        .into_iter()
-        .map(parse_query_module)
-        .collect::<Result<_, parser::error::Error>>()?;
+    let mut failures = Vec::new();
+    let modules = parse_modules(queries_path.as_ref(), &settings, &mut failures)?;
+    let cache_path = destination
+        .as_ref()
+        .filter(|_| settings.incremental)
+        .map(|d| Cache::path(d.as_ref()));
+    // Generate, the unchanged modules being prepared from the cache of the previous generation
+    let (prepared_modules, cache) = match &cache_path {
+        Some(path) => {
+            let previous = Cache::read(path, &settings);
+            let mut next = Cache::new(&settings, &modules);
+            let prepared_modules = prepare_modules(
+                &mut previous.database(client, &mut next),
+                modules,
+                &settings,
+                failures,
+                observer,
+            )?;
+            (prepared_modules, Some(next))
+        }
+        None => {
+            let prepared_modules = prepare_modules(
+                &mut Database::live(client),
+                modules,
+                &settings,
+                failures,
+                observer,
+            )?;
+            (prepared_modules, None)
+        }
+    };
+    let generated_code = generate_code(prepared_modules.clone(), &settings)?;
+    // Write
+    if let Some(d) = destination {
+        write_generated_code(d.as_ref(), &generated_code, &settings, observer)?;
+        if let Some(dir) = settings.emit_benches() {
+            write_benches(&prepared_modules, dir, d.as_ref(), &settings)?;
+        }
+    };
+    if let (Some(cache), Some(path)) = (cache, cache_path) {
+        cache.write(&path)?;
+    }
+
+    Ok(generated_code)
+}
+
+/// Generates the Rust code of a single query module named `name`, whose queries are given
+/// as the `sql` string, using a live database managed by you. The code has the layout of
+/// the one of [`generate_live`], the queries being in the `queries::name` module. Code
+/// generation settings are set using the `settings` parameter, and the warnings are
+/// printed to stderr.
+pub fn generate_module_live(
+    client: &mut Client,
+    name: &str,
+    sql: &str,
+    settings: CodegenSettings,
+) -> Result<String, Error> {
+    generate_module_live_with_observer(
+        client,
+        name,
+        sql,
+        settings,
+        &mut PrintObserver { verbose: false },
+    )
+}
+
+/// Like [`generate_module_live`], the progress of the generation being reported to `observer`
+/// instead of the warnings being printed to stderr.
+pub fn generate_module_live_with_observer(
+    client: &mut Client,
+    name: &str,
+    sql: &str,
+    settings: CodegenSettings,
+    observer: &mut dyn ProgressObserver,
+) -> Result<String, Error> {
+    let info = ModuleInfo::new(
+        PathBuf::from(format!("{name}.sql")),
+        name.to_string(),
+        sql.to_string(),
+    );
+    let module = parse_query_module(info)?;
+    let prepared_modules = prepare_modules(
+        &mut Database::live(client),
+        vec![module],
+        &settings,
+        Vec::new(),
+        observer,
+    )?;
+    generate_code(prepared_modules, &settings)
+}
+
+/// Generates the custom types (enums and composites) of a live database managed by you,
+/// to be shared by several crates whose queries reference them using
+/// [`CodegenSettingsBuilder::types_path`]. If some `destination` is given, the generated
+/// code will be written at that path, as the content of a crate or of a module. Code
+/// generation settings are set using the `settings` parameter.
+///
+/// Every custom type of the database is generated, sorted by schema and name, so that
+/// the shared types don't depend on the queries of any crate.
+pub fn generate_types_live<P: AsRef<Path>>(
+    client: &mut Client,
+    destination: Option<P>,
+    settings: CodegenSettings,
+) -> Result<String, Error> {
+    generate_types_live_with_observer(
+        client,
+        destination,
+        settings,
+        &mut PrintObserver { verbose: false },
+    )
+}
+
+/// Like [`generate_types_live`], the progress of the generation being reported to `observer`
+/// instead of the warnings being printed to stderr.
+pub fn generate_types_live_with_observer<P: AsRef<Path>>(
+    client: &mut Client,
+    destination: Option<P>,
+    settings: CodegenSettings,
+    observer: &mut dyn ProgressObserver,
+) -> Result<String, Error> {
+    let types = prepare_shared_types(client, &settings)?;
+    let generated_code = generate_types(&types, &settings);
+    if let Some(d) = destination {
+        write_generated_code(d.as_ref(), &generated_code, &settings, observer)?;
+    };
+
+    Ok(generated_code)
+}
+
+/// Reports the columns of the tables of a live database managed by you that none of the
+/// queries located at `queries_path` references, and the tables no query references at
+/// all, to find dead columns.
+///
+/// Returned columns are traced back to their table by the database, while the columns
+/// used elsewhere, like in clauses or by parameters, are matched by name on a best-effort
+/// basis, as stated in the header of the report.
+pub fn coverage_live<P: AsRef<Path>>(
+    client: &mut Client,
+    queries_path: P,
+    settings: &CoverageSettings,
+) -> Result<Coverage, Error> {
+    coverage_live_with_observer(
+        client,
+        queries_path,
+        settings,
+        &mut PrintObserver { verbose: false },
+    )
+}
+
+/// Like [`coverage_live`], the progress of the preparation of the queries being reported to `observer`
+/// instead of the warnings being printed to stderr.
+pub fn coverage_live_with_observer<P: AsRef<Path>>(
+    client: &mut Client,
+    queries_path: P,
+    settings: &CoverageSettings,
+    observer: &mut dyn ProgressObserver,
+) -> Result<Coverage, Error> {
+    // Read
+    let codegen_settings = settings
+        .only_tags
+        .iter()
+        .fold(CodegenSettings::builder(), |builder, tag| {
+            builder.only_tag(tag)
+        })
+        .build();
+    let modules = parse_modules(queries_path.as_ref(), &codegen_settings, &mut Vec::new())?;
+    // Check
+    let preparation = prepare(
+        &mut Database::live(client),
+        modules,
+        &codegen_settings,
+        &mut Vec::new(),
+        observer,
+    )?;
+    Ok(coverage::coverage(client, &preparation, settings)?)
+}
+
+/// Records a snapshot of the queries located at `queries_path` prepared against
+/// a live database managed by you, and writes it at `snapshot_path`. This snapshot
+/// can then be used to generate code without any database using [`generate_offline`].
+///
+/// The snapshot also records the extensions installed in the database with their version,
+/// in its `extensions` object, so that deployment tooling can check them against production.
+pub fn snapshot_live<P: AsRef<Path>>(
+    client: &mut Client,
+    queries_path: P,
+    snapshot_path: P,
+) -> Result<(), Error> {
+    snapshot_live_with_observer(
+        client,
+        queries_path,
+        snapshot_path,
+        &mut PrintObserver { verbose: false },
+    )
+}
+
+/// Like [`snapshot_live`], the progress of the preparation being reported to `observer`
+/// instead of the warnings being printed to stderr.
+pub fn snapshot_live_with_observer<P: AsRef<Path>>(
+    client: &mut Client,
+    queries_path: P,
+    snapshot_path: P,
+    observer: &mut dyn ProgressObserver,
+) -> Result<(), Error> {
+    // Read
+    let settings = CodegenSettings::default();
+    let modules = parse_modules(queries_path.as_ref(), &settings, &mut Vec::new())?;
+    // Record
+    let mut snapshot = Snapshot::new();
+    prepare(
+        &mut Database::Live {
+            client,
+            recording: Some(&mut snapshot),
+        },
+        modules,
+        &settings,
+        &mut Vec::new(),
+        observer,
+    )?;
+    // Write
+    snapshot.write(snapshot_path.as_ref())?;
+
+    Ok(())
+}
+
+/// Generates Rust queries from PostgreSQL queries located at `queries_path`, without
+/// any database, using the snapshot at `snapshot_path` recorded by [`snapshot_live`].
+/// If some `destination` is given, the generated code will be written at that path.
+/// Code generation settings are set using the `settings` parameter, and the warnings are
+/// printed to stderr.
+///
+/// Queries missing from the snapshot or modified since it was recorded are reported as errors.
+pub fn generate_offline<P: AsRef<Path>>(
+    queries_path: P,
+    snapshot_path: P,
+    destination: Option<P>,
+    settings: CodegenSettings,
+) -> Result<String, Error> {
+    generate_offline_with_observer(
+        queries_path,
+        snapshot_path,
+        destination,
+        settings,
+        &mut PrintObserver { verbose: false },
+    )
+}
+
+/// Like [`generate_offline`], the progress of the generation being reported to `observer`
+/// instead of the warnings being printed to stderr.
+pub fn generate_offline_with_observer<P: AsRef<Path>>(
+    queries_path: P,
+    snapshot_path: P,
+    destination: Option<P>,
+    settings: CodegenSettings,
+    observer: &mut dyn ProgressObserver,
+) -> Result<String, Error> {
+    // Read
+    let mut failures = Vec::new();
+    let modules = parse_modules(queries_path.as_ref(), &settings, &mut failures)?;
+    let snapshot = Snapshot::read(snapshot_path.as_ref())?;
     // Generate
-    let prepared_modules = prepare(client, modules)?;
-    let generated_code = generate_internal(prepared_modules, settings);
+    let prepared_modules = prepare_modules(
+        &mut Database::Offline(&snapshot),
+        modules,
+        &settings,
+        failures,
+        observer,
+    )?;
+    let generated_code = generate_code(prepared_modules.clone(), &settings)?;
     // Write
     if let Some(d) = destination {
-        write_generated_code(d.as_ref(), &generated_code)?;
+        write_generated_code(d.as_ref(), &generated_code, &settings, observer)?;
+        if let Some(dir) = settings.emit_benches() {
+            write_benches(&prepared_modules, dir, d.as_ref(), &settings)?;
+        }
     };
 
     Ok(generated_code)
@@ -67,7 +444,8 @@ pub fn generate_live<P: AsRef<Path>>(
 /// Generates Rust queries from PostgreSQL queries located at `queries_path`, using
 /// a container managed by cornucopia. The database schema is created using `schema_files`.
 /// If some `destination` is given, the generated code will be written at that path.
-/// Code generation settings are set using the `settings` parameter.
+/// Code generation settings are set using the `settings` parameter, and the warnings are
+/// printed to stderr.
 ///
 /// By default, the container manager is Docker, but Podman can be used by setting the
 /// `podman` parameter to `true`.
@@ -77,31 +455,155 @@ pub fn generate_managed<P: AsRef<Path>>(
     destination: Option<P>,
     podman: bool,
     settings: CodegenSettings,
+) -> Result<String, Error> {
+    generate_managed_with_observer(
+        queries_path,
+        schema_files,
+        destination,
+        podman,
+        settings,
+        &mut PrintObserver { verbose: false },
+    )
+}
+
+/// Like [`generate_managed`], the progress of the generation being reported to `observer`
+/// instead of the warnings being printed to stderr.
+pub fn generate_managed_with_observer<P: AsRef<Path>>(
+    queries_path: P,
+    schema_files: &[P],
+    destination: Option<P>,
+    podman: bool,
+    settings: CodegenSettings,
+    observer: &mut dyn ProgressObserver,
 ) -> Result<String, Error> {
     // Read
-    let modules = read_query_modules(queries_path.as_ref())?
-        .into_iter()
-        .map(parse_query_module)
-        .collect::<Result<_, parser::error::Error>>()?;
-    container::setup(podman)?;
-    let mut client = conn::cornucopia_conn()?;
-    load_schema(&mut client, schema_files)?;
-    let prepared_modules = prepare(&mut client, modules)?;
-    let generated_code = generate_internal(prepared_modules, settings);
+    let mut failures = Vec::new();
+    let modules = parse_modules(queries_path.as_ref(), &settings, &mut failures)?;
+    container::setup_with_observer(
+        &ContainerSettings {
+            retries: settings.setup_retries(),
+            image: settings.container_image().to_string(),
+            prepared_transactions: settings.gen_two_phase_commit(),
+            ..ContainerSettings::new(podman)
+        },
+        observer,
+    )?;
+    let application_name = settings
+        .application_name()
+        .unwrap_or(conn::DEFAULT_APPLICATION_NAME);
+    let mut client = conn::managed_conn(application_name)?;
+    load_schema_with_observer(&mut client, schema_files, observer)?;
+    let prepared_modules = prepare_modules(
+        &mut Database::live(&mut client),
+        modules,
+        &settings,
+        failures,
+        observer,
+    )?;
+    let generated_code = generate_code(prepared_modules.clone(), &settings)?;
     container::cleanup(podman)?;
 
     if let Some(destination) = destination {
-        write_generated_code(destination.as_ref(), &generated_code)?;
+        write_generated_code(destination.as_ref(), &generated_code, &settings, observer)?;
+        if let Some(dir) = settings.emit_benches() {
+            write_benches(&prepared_modules, dir, destination.as_ref(), &settings)?;
+        }
     };
 
     Ok(generated_code)
 }
 
-fn write_generated_code(destination: &Path, generated_code: &str) -> Result<(), Error> {
-    Ok(
-        std::fs::write(destination, generated_code).map_err(|err| WriteOutputError {
-            err,
-            file_path: destination.to_owned(),
-        })?,
-    )
+/// Formats the `.sql` query files located at `queries_path` in place, returning the paths
+/// of the files that were reformatted, or would be when [`FormatSettings::check`] is set.
+///
+/// The annotations of the queries and types are normalized, as well as the spacing
+/// between them, while the comments and the SQL are kept verbatim unless
+/// [`FormatSettings::sql`] is set. Formatting a formatted file leaves it unchanged.
+pub fn format_queries<P: AsRef<Path>>(
+    queries_path: P,
+    settings: &FormatSettings,
+) -> Result<Vec<PathBuf>, Error> {
+    format_queries::format_queries(queries_path.as_ref(), settings)
+}
+
+/// Reads and parses the query modules at `queries_path`. When generation keeps going, the
+/// errors of the modules that cannot be parsed are pushed to `failures` and those modules
+/// are skipped.
+fn parse_modules(
+    queries_path: &Path,
+    settings: &CodegenSettings,
+    failures: &mut Vec<Error>,
+) -> Result<Vec<Module>, Error> {
+    let mut modules = Vec::new();
+    for info in read_query_modules(queries_path)? {
+        match parse_query_module(info) {
+            Ok(module) => modules.push(module),
+            Err(err) if settings.keep_going => failures.push(err.into()),
+            Err(err) => return Err(err.into()),
+        }
+    }
+    Ok(modules)
+}
+
+/// Prepares the parsed modules, reporting the errors of every failing module, including
+/// those that could not be parsed, at once
+fn prepare_modules(
+    db: &mut Database,
+    modules: Vec<Module>,
+    settings: &CodegenSettings,
+    mut failures: Vec<Error>,
+    observer: &mut dyn ProgressObserver,
+) -> Result<Preparation, Error> {
+    let mut prepare_failures = Vec::new();
+    let result = prepare(db, modules, settings, &mut prepare_failures, observer);
+    failures.extend(prepare_failures.into_iter().map(Error::from));
+    let preparation = result?;
+    if failures.is_empty() {
+        Ok(preparation)
+    } else {
+        Err(ModuleErrors {
+            errors: failures,
+            passed: preparation.modules.len(),
+        }
+        .into())
+    }
+}
+
+/// Generates the code of the prepared modules, along with the migrations embedded using
+/// [`CodegenSettingsBuilder::embed_migrations`]
+fn generate_code(preparation: Preparation, settings: &CodegenSettings) -> Result<String, Error> {
+    let mut generated_code = generate_internal(preparation, settings);
+    if let Some(dir) = settings.embed_migrations() {
+        let migrations = read_migrations(dir)?;
+        let client = GenCtx::new(0, settings.gen_async).client_name();
+        gen_migrations(&mut generated_code, &migrations, client);
+    }
+    Ok(generated_code)
+}
+
+fn write_generated_code(
+    destination: &Path,
+    generated_code: &str,
+    settings: &CodegenSettings,
+    observer: &mut dyn ProgressObserver,
+) -> Result<(), Error> {
+    if settings.create_destination_dirs {
+        if let Some(parent) = destination.parent() {
+            std::fs::create_dir_all(parent).map_err(|err| WriteOutputError {
+                err,
+                action: "create the parent directories of",
+                file_path: destination.to_owned(),
+            })?;
+        }
+    }
+    std::fs::write(destination, generated_code).map_err(|err| WriteOutputError {
+        err,
+        action: "write your queries to",
+        file_path: destination.to_owned(),
+    })?;
+    observer.on_event(ProgressEvent::CodegenWritten {
+        path: destination.to_owned(),
+        bytes: generated_code.len(),
+    });
+    Ok(())
 }
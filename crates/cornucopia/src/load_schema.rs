@@ -3,7 +3,10 @@ use std::path::Path;
 use miette::NamedSource;
 use postgres::Client;
 
-use crate::utils::db_err;
+use crate::{
+    progress::{PrintObserver, ProgressEvent, ProgressObserver},
+    utils::{char_offset, db_err, sanitize, snippet},
+};
 
 use self::error::Error;
 
@@ -11,6 +14,15 @@ use self::error::Error;
 ///
 /// Takes a list of file paths as parameter and loads them in their given order.
 pub fn load_schema<P: AsRef<Path>>(client: &mut Client, paths: &[P]) -> Result<(), Error> {
+    load_schema_with_observer(client, paths, &mut PrintObserver { verbose: false })
+}
+
+/// Like [`load_schema`], each loaded file being reported to `observer`.
+pub fn load_schema_with_observer<P: AsRef<Path>>(
+    client: &mut Client,
+    paths: &[P],
+    observer: &mut dyn ProgressObserver,
+) -> Result<(), Error> {
     for path in paths {
         let path = path.as_ref();
         let sql = std::fs::read_to_string(path).map_err(|err| Error::Io {
@@ -19,23 +31,26 @@ pub fn load_schema<P: AsRef<Path>>(client: &mut Client, paths: &[P]) -> Result<(
         })?;
         client.batch_execute(&sql).map_err(|err| {
             let msg = format!("{err:#}");
-            let src = NamedSource::new(path.to_string_lossy(), sql);
+            let name = path.to_string_lossy();
             if let Some((position, msg, help)) = db_err(&err) {
+                let offset = char_offset(&sql, position as usize);
+                let (src, err_span) = snippet(&name, &sql, (offset..offset).into());
                 Error::Postgres {
                     msg,
                     help,
                     src,
-                    err_span: Some((position as usize..position as usize).into()),
+                    err_span: Some(err_span),
                 }
             } else {
                 Error::Postgres {
                     msg,
                     help: None,
-                    src,
+                    src: NamedSource::new(name, sanitize(&sql)),
                     err_span: None,
                 }
             }
         })?;
+        observer.on_event(ProgressEvent::SchemaLoaded(path.to_owned()));
     }
     Ok(())
 }
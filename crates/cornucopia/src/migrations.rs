@@ -0,0 +1,162 @@
+use std::{collections::HashMap, fmt::Write, path::Path};
+
+use codegen_template::code;
+use cornucopia_client_core::Migration;
+use postgres::Client;
+
+use self::error::Error;
+
+/// Table recording the migrations applied to a database, with their checksum
+const TRACKING_TABLE: &str = "cornucopia_migrations";
+
+/// A migration file read to be embedded in the generated code
+pub(crate) struct MigrationFile {
+    name: String,
+    sql: String,
+}
+
+/// Reads the `.sql` migration files of `dir`, sorted by name so that the embedded
+/// migrations don't depend on the order of the directory entries.
+pub(crate) fn read_migrations(dir: &Path) -> Result<Vec<MigrationFile>, Error> {
+    let io_err = |path: &Path| {
+        let path = path.to_owned();
+        move |err| Error::Io { path, err }
+    };
+    let mut migrations = Vec::new();
+    for entry in std::fs::read_dir(dir).map_err(io_err(dir))? {
+        let path = entry.map_err(io_err(dir))?.path();
+        if path.extension().and_then(|it| it.to_str()) != Some("sql") {
+            continue;
+        }
+        let name = path
+            .file_stem()
+            .expect("is a file")
+            .to_str()
+            .expect("file name is valid utf8")
+            .to_string();
+        let sql = std::fs::read_to_string(&path).map_err(io_err(&path))?;
+        migrations.push(MigrationFile { name, sql });
+    }
+    migrations.sort_by(|a, b| a.name.cmp(&b.name));
+    Ok(migrations)
+}
+
+/// Generates the `MIGRATIONS` constant embedding the migration files, with `client` the
+/// crate their type is re-exported by
+pub(crate) fn gen_migrations(w: &mut String, migrations: &[MigrationFile], client: &str) {
+    let migrations = migrations.iter().map(|MigrationFile { name, sql }| {
+        let checksum = Migration::checksum(sql);
+        let sql = sql.replace('\\', "\\\\").replace('"', "\\\"");
+        move |w: &mut String| {
+            code!(w =>
+                $client::Migration {
+                    name: "$name",
+                    sql: "$sql",
+                    checksum: "$checksum",
+                },
+            )
+        }
+    });
+    code!(w =>
+        #[doc = " Embedded migrations, applied in this order by `cornucopia::run_embedded_migrations`"]
+        pub const MIGRATIONS: &[$client::Migration] = &[
+            $($!migrations)
+        ];
+    );
+}
+
+/// Applies the `migrations` embedded in the generated code using
+/// [`CodegenSettingsBuilder::embed_migrations`](crate::CodegenSettingsBuilder::embed_migrations)
+/// that were not applied to the database yet, in their order, returning their names.
+///
+/// Applied migrations are recorded with their checksum in the `cornucopia_migrations`
+/// table, created if needed. The migrations are applied in a single transaction, so that
+/// none of them is applied when one fails. A migration applied with a different checksum,
+/// because its file was modified since, is reported as an error.
+pub fn run_embedded_migrations(
+    client: &mut Client,
+    migrations: &[Migration],
+) -> Result<Vec<&'static str>, crate::Error> {
+    let mut transaction = client.transaction().map_err(Error::from)?;
+    // Concurrent runs wait for the first one to commit
+    transaction
+        .batch_execute(&format!(
+            "CREATE TABLE IF NOT EXISTS {TRACKING_TABLE} (name TEXT PRIMARY KEY, \
+            checksum TEXT NOT NULL, applied_at TIMESTAMPTZ NOT NULL DEFAULT now()); \
+            LOCK TABLE {TRACKING_TABLE} IN EXCLUSIVE MODE;"
+        ))
+        .map_err(Error::from)?;
+    let applied: HashMap<String, String> = transaction
+        .query(&format!("SELECT name, checksum FROM {TRACKING_TABLE}"), &[])
+        .map_err(Error::from)?
+        .iter()
+        .map(|row| (row.get(0), row.get(1)))
+        .collect();
+    let mut names = Vec::new();
+    for migration in migrations {
+        if !migration.is_intact() {
+            return Err(Error::Modified {
+                name: migration.name.to_string(),
+            }
+            .into());
+        }
+        match applied.get(migration.name) {
+            Some(checksum) if checksum == migration.checksum => continue,
+            Some(checksum) => {
+                return Err(Error::ChecksumMismatch {
+                    name: migration.name.to_string(),
+                    applied: checksum.clone(),
+                    embedded: migration.checksum.to_string(),
+                }
+                .into())
+            }
+            None => {}
+        }
+        transaction
+            .batch_execute(migration.sql)
+            .map_err(|err| Error::Failed {
+                name: migration.name.to_string(),
+                msg: format!("{err:#}"),
+            })?;
+        transaction
+            .execute(
+                &format!("INSERT INTO {TRACKING_TABLE} (name, checksum) VALUES ($1, $2)"),
+                &[&migration.name, &migration.checksum],
+            )
+            .map_err(Error::from)?;
+        names.push(migration.name);
+    }
+    transaction.commit().map_err(Error::from)?;
+    Ok(names)
+}
+
+pub(crate) mod error {
+    use std::path::PathBuf;
+
+    use miette::Diagnostic;
+    use thiserror::Error as ThisError;
+
+    #[derive(Debug, ThisError, Diagnostic)]
+    pub enum Error {
+        #[error("Could not read migrations `{path}`: ({err})")]
+        Io { path: PathBuf, err: std::io::Error },
+        #[error("Could not track the applied migrations: {0}")]
+        Postgres(#[from] postgres::Error),
+        #[error("Could not apply migration `{name}`: {msg}")]
+        Failed { name: String, msg: String },
+        #[error("the embedded migration `{name}` does not match its checksum")]
+        #[diagnostic(help("regenerate the code instead of editing the embedded migrations"))]
+        Modified { name: String },
+        #[error(
+            "the migration `{name}` was applied with the checksum `{applied}`, not `{embedded}`"
+        )]
+        #[diagnostic(help(
+            "applied migrations must not be modified, add a new migration file instead"
+        ))]
+        ChecksumMismatch {
+            name: String,
+            applied: String,
+            embedded: String,
+        },
+    }
+}
@@ -0,0 +1,90 @@
+use std::{
+    collections::BTreeMap,
+    path::{Path, PathBuf},
+};
+
+use serde::Deserialize;
+
+use crate::database::ColumnOrigin;
+
+use self::error::Error;
+
+/// Nullability of a column in the nullability file
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Deserialize)]
+#[serde(rename_all = "snake_case")]
+enum Nullity {
+    Nullable,
+    NotNull,
+}
+
+/// Nullability of the table and view columns declared in the file given using
+/// [`CodegenSettingsBuilder::nullability_file`](crate::CodegenSettingsBuilder::nullability_file),
+/// by `schema.table.column`
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub(crate) struct ColumnNullability {
+    path: PathBuf,
+    columns: BTreeMap<String, Nullity>,
+}
+
+impl ColumnNullability {
+    /// Reads the nullability file at `path`, if any
+    pub(crate) fn read(path: Option<&Path>) -> Result<Self, Error> {
+        let Some(path) = path else {
+            return Ok(Self::default());
+        };
+        let content = std::fs::read_to_string(path).map_err(|err| Error::Io {
+            path: path.to_owned(),
+            err,
+        })?;
+        let columns: BTreeMap<String, Nullity> =
+            toml::from_str(&content).map_err(|err| Error::Format {
+                path: path.to_owned(),
+                err: Box::new(err),
+            })?;
+        if let Some(column) = columns.keys().find(|it| it.split('.').count() != 3) {
+            return Err(Error::InvalidColumn {
+                path: path.to_owned(),
+                column: column.clone(),
+            });
+        }
+        Ok(Self {
+            path: path.to_owned(),
+            columns,
+        })
+    }
+
+    /// Path of the nullability file
+    pub(crate) fn path(&self) -> &Path {
+        &self.path
+    }
+
+    /// Whether the column read from `origin` is declared nullable, if it is declared
+    pub(crate) fn get(&self, origin: &ColumnOrigin) -> Option<bool> {
+        let key = format!("{}.{}.{}", origin.schema, origin.table, origin.column);
+        self.columns.get(&key).map(|it| *it == Nullity::Nullable)
+    }
+}
+
+pub(crate) mod error {
+    use std::path::PathBuf;
+
+    use miette::Diagnostic;
+    use thiserror::Error as ThisError;
+
+    #[derive(Debug, ThisError, Diagnostic)]
+    pub enum Error {
+        #[error("Could not read nullability file `{path}`: ({err})")]
+        Io { path: PathBuf, err: std::io::Error },
+        #[error("Could not parse nullability file `{path}`: ({err})")]
+        #[diagnostic(help("map each `schema.table.column` to `\"nullable\"` or `\"not_null\"`"))]
+        Format {
+            path: PathBuf,
+            err: Box<toml::de::Error>,
+        },
+        #[error("nullability file `{path}` declares the column `{column}`, which is not a `schema.table.column`")]
+        #[diagnostic(help(
+            "name the columns after their schema and table: `\"public.author.name\" = \"not_null\"`"
+        ))]
+        InvalidColumn { path: PathBuf, column: String },
+    }
+}
@@ -5,7 +5,7 @@ use error::Error;
 use heck::ToUpperCamelCase;
 use miette::SourceSpan;
 
-use crate::read_queries::ModuleInfo;
+use crate::{read_queries::ModuleInfo, utils::snippet};
 
 /// Th    if is data structure holds a value and the context in which it was parsed.
 /// This context is used for error reporting.
@@ -110,18 +110,113 @@ pub struct NullableIdent {
     pub name: Span<String>,
     pub nullable: bool,
     pub inner_nullable: bool,
+    /// Fields of the anonymous record (`ROW(...)`) returned in this column, declared
+    /// as `pair record(first: int4, second?: text)`
+    pub record: Option<Vec<RecordField>>,
+    /// Path of the Rust type a `json` or `jsonb` value is deserialized into, declared as
+    /// `settings: crate::Settings`
+    pub json_type: Option<Span<String>>,
+    /// Attributes emitted verbatim above the generated field, e.g. `#[serde(skip)]`
+    pub attributes: Vec<String>,
 }
 
-fn parse_nullable_ident() -> impl Parser<char, Vec<NullableIdent>, Error = Simple<char>> {
+/// A field of an anonymous record: `second?: text`
+#[derive(Debug, Clone)]
+pub struct RecordField {
+    pub name: Span<String>,
+    pub nullable: bool,
+    /// Name of the PostgreSQL type of the field, arrays ending with `[]`
+    pub ty: Span<String>,
+}
+
+fn parse_record_fields() -> impl Parser<char, Vec<RecordField>, Error = Simple<char>> {
+    let ty = plain_ident().then(just("[]").or_not()).map_with_span(
+        |(name, array), span: Range<usize>| Span {
+            value: format!("{}{}", name.value, array.unwrap_or_default()),
+            span: span.into(),
+        },
+    );
     space()
         .ignore_then(ident())
         .then(just('?').or_not())
-        .then(just("[?]").or_not())
-        .map(|((name, null), inner_null)| NullableIdent {
+        .then_ignore(space())
+        .then_ignore(just(':'))
+        .then_ignore(space())
+        .then(ty)
+        .then_ignore(space())
+        .map(|((name, null), ty)| RecordField {
             name,
             nullable: null.is_some(),
-            inner_nullable: inner_null.is_some(),
+            ty,
+        })
+        .separated_by(just(','))
+        .allow_trailing()
+        .delimited_by(just('('), just(')'))
+}
+
+/// A Rust attribute whose brackets are balanced, outside of string literals, kept verbatim
+fn attribute() -> impl Parser<char, String, Error = Simple<char>> {
+    let string = just('\\')
+        .then(none_of('\n'))
+        .map(|(a, b)| format!("{a}{b}"))
+        .or(none_of("\\\"\n").map(String::from))
+        .repeated()
+        .collect::<String>()
+        .delimited_by(just('"'), just('"'))
+        .map(|it| format!("\"{it}\""));
+    let group = recursive(|group| {
+        string
+            .or(group)
+            .or(none_of("[]\"\n").map(String::from))
+            .repeated()
+            .collect::<String>()
+            .delimited_by(just('['), just(']'))
+            .map(|it| format!("[{it}]"))
+    });
+    just('#').ignore_then(group).map(|it| format!("#{it}"))
+}
+
+/// Path of a Rust type, e.g. `crate::Settings`: identifiers separated by `::`
+fn rust_path() -> impl Parser<char, Span<String>, Error = Simple<char>> {
+    text::ident()
+        .separated_by(just("::"))
+        .at_least(1)
+        .map_with_span(|segments: Vec<String>, span: Range<usize>| Span {
+            value: segments.join("::"),
+            span: span.into(),
         })
+}
+
+fn parse_nullable_ident() -> impl Parser<char, Vec<NullableIdent>, Error = Simple<char>> {
+    space()
+        .ignore_then(ident())
+        .then(just('?').or_not())
+        .then(just("[?]").or_not())
+        .then(
+            space()
+                .ignore_then(just("record"))
+                .ignore_then(space())
+                .ignore_then(parse_record_fields())
+                .or_not(),
+        )
+        .then(
+            space()
+                .ignore_then(just(':'))
+                .ignore_then(space())
+                .ignore_then(rust_path())
+                .or_not(),
+        )
+        .then(space().ignore_then(attribute()).repeated())
+        .map(
+            |(((((name, null), inner_null), record), json_type), attributes)| NullableIdent {
+                name,
+                nullable: null.is_some(),
+                inner_nullable: inner_null.is_some(),
+                record,
+                json_type,
+                attributes,
+            },
+        )
         .then_ignore(space())
         .separated_by(just(','))
         .allow_trailing()
@@ -130,6 +225,8 @@ fn parse_nullable_ident() -> impl Parser<char, Vec<NullableIdent>, Error = Simpl
 
 #[derive(Debug, Clone)]
 pub struct TypeAnnotation {
+    /// Span of the whole annotation, from `--:` to its closing parenthesis
+    pub span: SourceSpan,
     pub name: Span<String>,
     pub fields: Vec<NullableIdent>,
 }
@@ -141,21 +238,136 @@ impl TypeAnnotation {
             .ignore_then(ident())
             .then_ignore(space())
             .then(parse_nullable_ident())
-            .map(|(name, fields)| Self { name, fields })
+            .map_with_span(|(name, fields), span: Range<usize>| Self {
+                span: span.into(),
+                name,
+                fields,
+            })
     }
 }
 
-#[derive(Debug)]
+/// Range of server major versions a variant of a query is selected for, declared after
+/// its name: `@pg>=15`, `@pg<15` or `@pg>=12,<15`.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub(crate) struct VersionRange {
+    /// Inclusive lower bound
+    pub(crate) min: Option<u32>,
+    /// Exclusive upper bound
+    pub(crate) max: Option<u32>,
+}
+
+impl VersionRange {
+    pub(crate) fn contains(&self, major: u32) -> bool {
+        self.min.is_none_or(|min| min <= major) && self.max.is_none_or(|max| major < max)
+    }
+
+    fn parser() -> impl Parser<char, Self, Error = Simple<char>> {
+        let major = filter(char::is_ascii_digit)
+            .repeated()
+            .at_least(1)
+            .collect::<String>()
+            .try_map(|it, span| {
+                it.parse::<u32>()
+                    .map_err(|_| Simple::custom(span, "invalid major version"))
+            });
+        let min = just(">=").ignore_then(major);
+        let max = just('<').ignore_then(major);
+        just("@pg").ignore_then(
+            min.then(just(',').ignore_then(max).or_not())
+                .map(|(min, max)| Self {
+                    min: Some(min),
+                    max,
+                })
+                .or(max.map(|max| Self {
+                    min: None,
+                    max: Some(max),
+                })),
+        )
+    }
+}
+
+impl Display for VersionRange {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match (self.min, self.max) {
+            (Some(min), Some(max)) => write!(f, "@pg>={min},<{max}"),
+            (Some(min), None) => write!(f, "@pg>={min}"),
+            (None, Some(max)) => write!(f, "@pg<{max}"),
+            (None, None) => write!(f, "@pg"),
+        }
+    }
+}
+
+/// A query option declared on its own line between the query annotation
+/// and the SQL, e.g. `-- :arity opt`.
+#[derive(Debug, Clone)]
+pub(crate) struct QueryOption {
+    /// Span of the whole line, without its line break
+    pub(crate) span: SourceSpan,
+    pub(crate) name: Span<String>,
+    pub(crate) args: Vec<Span<String>>,
+}
+
+impl QueryOption {
+    /// Tags declared by the `tag` options among these as `key=value` pairs
+    pub(crate) fn tags(options: &[Self]) -> impl Iterator<Item = (&str, &str)> {
+        options
+            .iter()
+            .filter(|it| it.name.value == "tag")
+            .flat_map(|it| &it.args)
+            .filter_map(|it| it.value.split_once('='))
+    }
+
+    fn parser() -> impl Parser<char, Self, Error = Simple<char>> {
+        let arg = filter(|c: &char| !c.is_whitespace())
+            .repeated()
+            .at_least(1)
+            .collect()
+            .map_with_span(|value: String, span: Range<usize>| Span {
+                value,
+                span: span.into(),
+            });
+        just("--")
+            .then(space())
+            .then(just(':'))
+            .ignore_then(plain_ident())
+            .then(space().ignore_then(arg).repeated())
+            .then_ignore(space())
+            .map_with_span(|(name, args), span: Range<usize>| Self {
+                span: span.into(),
+                name,
+                args,
+            })
+    }
+}
+
+/// A bind parameter annotated with a newtype: `:param@Newtype`
+pub(crate) type BindNewtype = (Span<String>, Span<String>);
+
+/// A bind parameter with its optional newtype annotation
+type Bind = (Span<String>, Option<Span<String>>);
+
+#[derive(Debug, Clone)]
 pub(crate) struct Query {
+    /// Span of the whole `--!` line, without its line break
+    pub(crate) annotation_span: SourceSpan,
     pub(crate) name: Span<String>,
+    /// Server versions this variant of the query is selected for
+    pub(crate) version: Option<Span<VersionRange>>,
     pub(crate) param: QueryDataStruct,
     pub(crate) row: QueryDataStruct,
+    pub(crate) options: Vec<QueryOption>,
     pub(crate) sql_span: SourceSpan,
     pub(crate) sql_str: String,
     pub(crate) bind_params: Vec<Span<String>>,
+    pub(crate) bind_newtypes: Vec<BindNewtype>,
 }
 
 impl Query {
+    /// Tags declared by `tag` options as `key=value` pairs
+    pub(crate) fn tags(&self) -> impl Iterator<Item = (&str, &str)> {
+        QueryOption::tags(&self.options)
+    }
+
     /// Escape sql string and pattern that are not bind
     fn sql_escaping() -> impl Parser<char, (), Error = Simple<char>> {
         // https://www.postgresql.org/docs/current/sql-syntax-lexical.html
@@ -201,24 +413,52 @@ impl Query {
             .ignored()
     }
 
-    /// Parse all bind from an SQL query
-    fn parse_bind() -> impl Parser<char, Vec<Span<String>>, Error = Simple<char>> {
+    /// Parse all bind from an SQL query, with their optional newtype annotation
+    fn parse_bind() -> impl Parser<char, Vec<Bind>, Error = Simple<char>> {
         just(':')
             .ignore_then(plain_ident())
+            .then(just('@').ignore_then(plain_ident()).or_not())
             .separated_by(Self::sql_escaping())
             .allow_leading()
             .allow_trailing()
     }
 
-    /// Parse sql query, normalizing named parameters
+    /// Parse sql query, normalizing named parameters. Batch queries can contain multiple
+    /// statements, each following one starting outside of a comment and an annotation.
     fn parse_sql_query(
-    ) -> impl Parser<char, (String, SourceSpan, Vec<Span<String>>), Error = Simple<char>> {
+        batch: bool,
+    ) -> impl Parser<char, (String, SourceSpan, Vec<Span<String>>, Vec<BindNewtype>), Error = Simple<char>>
+    {
+        let comment = just('-')
+            .chain(just('-'))
+            .then_ignore(none_of(":!").rewind())
+            .chain(none_of('\n').repeated());
+        let gap = filter(|c: &char| c.is_whitespace())
+            .map(|c| vec![c])
+            .or(comment)
+            .repeated()
+            .flatten();
+        let next_statement = just(';')
+            .chain::<char, Vec<char>, _>(gap)
+            .chain(none_of("-;"))
+            .chain::<char, Vec<char>, _>(none_of(";").repeated());
         none_of(";")
             .repeated()
+            .chain::<char, Vec<char>, _>(
+                next_statement
+                    .repeated()
+                    .at_most(if batch { usize::MAX } else { 0 })
+                    .flatten(),
+            )
             .then_ignore(just(';'))
             .collect::<String>()
             .map_with_span(|mut sql_str, span: Range<usize>| {
-                let bind_params: Vec<_> = Self::parse_bind().parse(sql_str.clone()).unwrap();
+                let binds: Vec<_> = parse_str(Self::parse_bind(), &sql_str).unwrap();
+                let bind_params: Vec<_> = binds.iter().map(|(param, _)| param.clone()).collect();
+                let bind_newtypes: Vec<_> = binds
+                    .iter()
+                    .filter_map(|(param, newtype)| Some((param.clone(), newtype.clone()?)))
+                    .collect();
                 // Remove duplicate
                 let dedup_params: Vec<_> = bind_params
                     .iter()
@@ -229,23 +469,54 @@ impl Query {
                     .rev()
                     .collect();
 
-                for bind_param in bind_params.iter().rev() {
+                // Row counts are cast to `bigint` wherever they are used, as Postgres deduces
+                // the type of a parameter from its first use, which may be in another context
+                let row_counts: Vec<_> = binds
+                    .iter()
+                    .filter(|(param, newtype)| {
+                        let start = param.span.offset() - 1;
+                        let end = newtype.as_ref().unwrap_or(param);
+                        is_row_count(&sql_str[..start])
+                            && !sql_str[end.span.offset() + end.span.len()..].starts_with("::")
+                    })
+                    .map(|(param, _)| param)
+                    .collect();
+                for (bind_param, newtype) in binds.iter().rev() {
                     let index = dedup_params.iter().position(|bp| bp == bind_param).unwrap();
                     let start = bind_param.span.offset() - 1;
-                    let end = start + bind_param.span.len();
-                    sql_str.replace_range(start..=end, &format!("${}", index + 1));
+                    let end = newtype.as_ref().unwrap_or(bind_param);
+                    let end = end.span.offset() + end.span.len();
+                    let cast =
+                        if row_counts.contains(&bind_param) && !sql_str[end..].starts_with("::") {
+                            "::int8"
+                        } else {
+                            ""
+                        };
+                    sql_str.replace_range(start..end, &format!("${}{cast}", index + 1));
                 }
 
-                (sql_str, span.into(), dedup_params)
+                (sql_str, span.into(), dedup_params, bind_newtypes)
             })
     }
 
-    fn parse_query_annotation(
-    ) -> impl Parser<char, (Span<String>, QueryDataStruct, QueryDataStruct), Error = Simple<char>>
-    {
+    #[allow(clippy::type_complexity)]
+    fn parse_query_annotation() -> impl Parser<
+        char,
+        (
+            (Span<String>, Option<Span<VersionRange>>),
+            QueryDataStruct,
+            QueryDataStruct,
+        ),
+        Error = Simple<char>,
+    > {
+        let version = VersionRange::parser().map_with_span(|value, span: Range<usize>| Span {
+            value,
+            span: span.into(),
+        });
         just("--!")
             .ignore_then(space())
             .ignore_then(plain_ident())
+            .then(space().ignore_then(version).or_not())
             .then_ignore(space())
             .then(QueryDataStruct::parser())
             .then_ignore(space())
@@ -261,22 +532,47 @@ impl Query {
     fn parser() -> impl Parser<char, Self, Error = Simple<char>> {
         Self::parse_query_annotation()
             .then_ignore(space())
+            .map_with_span(|annotation, span: Range<usize>| (annotation, span.into()))
             .then_ignore(ln())
-            .then(Self::parse_sql_query())
+            .then(QueryOption::parser().then_ignore(ln()).repeated())
+            .then_with(|(annotation, options)| {
+                let batch = options.iter().any(|it| it.name.value == "batch");
+                Self::parse_sql_query(batch)
+                    .map(move |sql| (annotation.clone(), options.clone(), sql))
+            })
             .map(
-                |((name, param, row), (sql_str, sql_span, bind_params))| Self {
-                    name,
-                    param,
-                    row,
-                    sql_span,
-                    sql_str,
-                    bind_params,
+                |(
+                    (((name, version), param, row), annotation_span),
+                    options,
+                    (sql_str, sql_span, bind_params, bind_newtypes),
+                )| {
+                    Self {
+                        annotation_span,
+                        name,
+                        version,
+                        param,
+                        row,
+                        options,
+                        sql_span,
+                        sql_str,
+                        bind_params,
+                        bind_newtypes,
+                    }
                 },
             )
     }
 }
 
-#[derive(Debug)]
+/// Whether a bind parameter following this SQL is the argument of `LIMIT` or `OFFSET`
+fn is_row_count(before: &str) -> bool {
+    let before = before.trim_end();
+    let word = before
+        .rfind(|c: char| !c.is_ascii_alphanumeric() && c != '_')
+        .map_or(before, |idx| &before[idx + 1..]);
+    word.eq_ignore_ascii_case("limit") || word.eq_ignore_ascii_case("offset")
+}
+
+#[derive(Debug, Clone)]
 pub(crate) struct QueryDataStruct {
     pub span: SourceSpan,
     pub name: Option<Span<String>>,
@@ -354,49 +650,102 @@ impl QueryDataStruct {
     }
 }
 
+/// An advisory lock declared as `--! lock name`, generating helpers to acquire and
+/// release it
+#[derive(Debug, Clone)]
+pub(crate) struct Lock {
+    /// Span of the declaration, without its line break
+    pub(crate) span: SourceSpan,
+    pub(crate) name: Span<String>,
+}
+
+impl Lock {
+    fn parser() -> impl Parser<char, Self, Error = Simple<char>> {
+        // A declaration is not followed by any SQL, unlike a query named `lock`
+        let next = blank().then(just("--!").ignored().or(just("--:").ignored()).or(end()));
+        just("--!")
+            .ignore_then(space())
+            .ignore_then(just("lock"))
+            .ignore_then(
+                filter(|c: &char| c.is_whitespace() && *c != '\n')
+                    .repeated()
+                    .at_least(1),
+            )
+            .ignore_then(plain_ident())
+            .map_with_span(|name, span: Range<usize>| Self {
+                span: span.into(),
+                name,
+            })
+            .then_ignore(space())
+            .then_ignore(next.rewind())
+    }
+}
+
 #[derive(Debug)]
 enum Statement {
     Type(TypeAnnotation),
-    Query(Query),
+    Lock(Lock),
+    Query(Box<Query>),
 }
 
-#[derive(Debug)]
+#[derive(Debug, Clone)]
 pub(crate) struct Module {
     pub(crate) info: ModuleInfo,
     pub(crate) types: Vec<TypeAnnotation>,
+    pub(crate) locks: Vec<Lock>,
     pub(crate) queries: Vec<Query>,
 }
 
+/// Parses a string, spans being byte offsets as expected by miette rather than the
+/// character offsets produced by parsing it directly
+fn parse_str<T>(
+    parser: impl Parser<char, T, Error = Simple<char>>,
+    s: &str,
+) -> Result<T, Vec<Simple<char>>> {
+    let chars = s.char_indices().map(|(i, c)| (c, i..i + c.len_utf8()));
+    parser.parse(chumsky::Stream::from_iter(s.len()..s.len(), chars))
+}
+
 pub(crate) fn parse_query_module(info: ModuleInfo) -> Result<Module, Error> {
-    match TypeAnnotation::parser()
+    let parser = TypeAnnotation::parser()
         .map(Statement::Type)
-        .or(Query::parser().map(Statement::Query))
+        .or(Lock::parser().map(Statement::Lock))
+        .or(Query::parser().map(|it| Statement::Query(Box::new(it))))
         .separated_by(blank())
         .allow_leading()
         .allow_trailing()
-        .then_ignore(end())
-        .parse(info.content.as_str())
-    {
+        .then_ignore(end());
+    match parse_str(parser, &info.content) {
         Ok(statements) => {
             let mut types = Vec::new();
+            let mut locks = Vec::new();
             let mut queries = Vec::new();
             for item in statements {
                 match item {
                     Statement::Type(it) => types.push(it),
-                    Statement::Query(it) => queries.push(it),
+                    Statement::Lock(it) => locks.push(it),
+                    Statement::Query(it) => queries.push(*it),
                 }
             }
             Ok(Module {
                 info,
                 types,
+                locks,
                 queries,
             })
         }
-        Err(e) => Err(Error {
-            src: (&info).into(),
-            err_span: e[0].span().into(),
-            help: e[0].to_string().replace('\n', "\\n"),
-        }),
+        Err(e) => {
+            let (src, err_span) = snippet(
+                &info.path.to_string_lossy(),
+                &info.source,
+                e[0].span().into(),
+            );
+            Err(Error {
+                src,
+                err_span,
+                help: e[0].to_string().replace('\n', "\\n"),
+            })
+        }
     }
 }
 
@@ -1,16 +1,26 @@
-use std::rc::Rc;
+use std::{rc::Rc, str::FromStr};
 
 use heck::ToUpperCamelCase;
 use indexmap::{map::Entry, IndexMap};
+use miette::SourceSpan;
 use postgres::Client;
 use postgres_types::{Kind, Type};
 
 use crate::{
+    analysis::{
+        copy_in_statement, is_read_only, normalize_sql, outer_joined_tables, skips_conflicts,
+        statement_commands,
+    },
     codegen::GenCtx,
-    parser::{Module, NullableIdent, Query, Span, TypeAnnotation},
+    database::{custom_types, vector_type, Column, Database, Statement},
+    nullability::ColumnNullability,
+    parser::{Module, NullableIdent, Query, QueryOption, Span, TypeAnnotation, VersionRange},
+    progress::{ProgressEvent, ProgressObserver, Warning},
     read_queries::ModuleInfo,
-    type_registrar::CornucopiaType,
-    type_registrar::TypeRegistrar,
+    settings::{
+        CodegenSettings, DateTimeCrate, Newtype, NewtypeTarget, NumericCrate, RenameTarget,
+    },
+    type_registrar::{self, CornucopiaType, TypeRegistrar},
     utils::KEYWORD,
     validation,
 };
@@ -25,6 +35,34 @@ pub(crate) struct PreparedQuery {
     pub(crate) param: Option<(usize, Vec<usize>)>,
     pub(crate) row: Option<(usize, Vec<usize>)>,
     pub(crate) sql: String,
+    /// `COPY ... TO STDOUT` statement exporting the query result
+    pub(crate) copy_out: Option<String>,
+    /// Binary `COPY ... FROM STDIN` statement inserting many params at once
+    pub(crate) copy_in: Option<String>,
+    /// Error codes matched by the query error type, with their variant name
+    pub(crate) on_error: Vec<(String, String)>,
+    /// Whether the query is known not to write to the database
+    pub(crate) is_read_only: bool,
+    /// Commands of a batch query, executed without being prepared
+    pub(crate) batch: Option<Vec<String>>,
+    /// OIDs of the parameters of a query executed without a named prepared statement
+    pub(crate) unprepared: Option<Vec<u32>>,
+    /// Time to live in seconds of the results of a query declared with the `cache` option
+    pub(crate) cache: Option<u64>,
+    /// Latency budget in milliseconds declared with the `budget` option
+    pub(crate) budget_ms: Option<u64>,
+    /// Whether the query inserts a row unless it conflicts, returning no row on conflict
+    pub(crate) skips_conflicts: bool,
+    /// Table OIDs and names of the table columns the returned columns are read from
+    pub(crate) sources: Vec<(u32, String)>,
+    /// Version range of the variant selected for the `server_version_num` of the database
+    pub(crate) variant: Option<(VersionRange, i32)>,
+    /// Version ranges and SQL of every variant of the query, selected at runtime
+    pub(crate) variants: Vec<(VersionRange, String)>,
+    /// `key=value` tags declared by `tag` options
+    pub(crate) tags: Vec<(String, String)>,
+    /// Name of the query when its function is renamed
+    pub(crate) renamed_from: Option<String>,
 }
 
 /// A normalized ident replacing all non-alphanumeric characters with an underscore (`_`)
@@ -69,6 +107,12 @@ pub struct PreparedField {
     pub(crate) ty: Rc<CornucopiaType>,
     pub(crate) is_nullable: bool,
     pub(crate) is_inner_nullable: bool, // Vec only
+    /// Whether the value is hidden from `Debug` output
+    pub(crate) is_redacted: bool,
+    /// Attributes declared in the annotation of the field, emitted verbatim
+    pub(crate) attributes: Vec<String>,
+    /// Note of the `#[deprecated]` attribute of the field, the comment of its column
+    pub(crate) deprecation: Option<String>,
 }
 
 impl PreparedField {
@@ -80,27 +124,170 @@ impl PreparedField {
         Self {
             ident: Ident::new(db_ident),
             ty,
-            is_nullable: nullity.map_or(false, |it| it.nullable),
-            is_inner_nullable: nullity.map_or(false, |it| it.inner_nullable),
+            is_nullable: nullity.is_some_and(|it| it.nullable),
+            is_inner_nullable: nullity.is_some_and(|it| it.inner_nullable),
+            is_redacted: false,
+            attributes: nullity.map_or_else(Vec::new, |it| it.attributes.clone()),
+            deprecation: None,
         }
     }
 }
 
 impl PreparedField {
     pub fn unwrapped_name(&self) -> String {
-        self.own_struct(&GenCtx::new(0, false, false))
+        if let CornucopiaType::Newtype { path, .. } = self.ty.as_ref() {
+            // Newtypes provided by the user are named after their last path segment
+            return path.rsplit("::").next().unwrap_or_default().to_string();
+        }
+        self.own_struct(&GenCtx::new(0, false))
             .replace(['<', '>', '_'], "")
             .to_upper_camel_case()
     }
 }
 
+/// Number of rows a query is declared to return using the `arity` option,
+/// which decides the fetching methods generated for its row.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Default)]
+pub(crate) enum Arity {
+    /// Exactly one row: `one`
+    One,
+    /// Zero or one row: `one` and `opt`
+    Opt,
+    /// Any number of rows: `one`, `opt`, `all` and `iter`
+    #[default]
+    Many,
+}
+
+impl FromStr for Arity {
+    type Err = ();
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s {
+            "one" => Ok(Self::One),
+            "opt" => Ok(Self::Opt),
+            "many" => Ok(Self::Many),
+            _ => Err(()),
+        }
+    }
+}
+
+/// Time to live of the results of a query declared with the `cache` option, in seconds,
+/// minutes or hours: `ttl=60s`, `ttl=5m` or `ttl=1h`
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub(crate) struct CacheTtl(pub(crate) u64);
+
+impl FromStr for CacheTtl {
+    type Err = ();
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let value = s.strip_prefix("ttl=").ok_or(())?;
+        let (amount, factor) = if let Some(it) = value.strip_suffix('s') {
+            (it, 1)
+        } else if let Some(it) = value.strip_suffix('m') {
+            (it, 60)
+        } else if let Some(it) = value.strip_suffix('h') {
+            (it, 60 * 60)
+        } else {
+            return Err(());
+        };
+        if !amount.chars().all(|c| c.is_ascii_digit()) {
+            return Err(());
+        }
+        match amount
+            .parse::<u64>()
+            .ok()
+            .and_then(|it| it.checked_mul(factor))
+        {
+            Some(secs) if secs > 0 => Ok(Self(secs)),
+            _ => Err(()),
+        }
+    }
+}
+
+/// Latency budget of a query declared with the `budget` option, in milliseconds or
+/// seconds: `50ms` or `2s`
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub(crate) struct QueryBudget(pub(crate) u64);
+
+impl FromStr for QueryBudget {
+    type Err = ();
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let (amount, factor) = if let Some(it) = s.strip_suffix("ms") {
+            (it, 1)
+        } else if let Some(it) = s.strip_suffix('s') {
+            (it, 1000)
+        } else {
+            return Err(());
+        };
+        if !amount.chars().all(|c| c.is_ascii_digit()) {
+            return Err(());
+        }
+        match amount
+            .parse::<u64>()
+            .ok()
+            .and_then(|it| it.checked_mul(factor))
+        {
+            Some(ms) if ms > 0 => Ok(Self(ms)),
+            _ => Err(()),
+        }
+    }
+}
+
+/// Output format of a query declared with the `copy_out` option
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub(crate) enum CopyFormat {
+    #[default]
+    Csv,
+    Text,
+    Binary,
+}
+
+impl CopyFormat {
+    /// `COPY` options selecting this format
+    fn copy_options(self) -> &'static str {
+        match self {
+            Self::Csv => "FORMAT CSV, HEADER true",
+            Self::Text => "FORMAT TEXT",
+            Self::Binary => "FORMAT BINARY",
+        }
+    }
+}
+
+impl FromStr for CopyFormat {
+    type Err = ();
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s {
+            "csv" => Ok(Self::Csv),
+            "text" => Ok(Self::Text),
+            "binary" => Ok(Self::Binary),
+            _ => Err(()),
+        }
+    }
+}
+
 #[derive(Debug, Clone)]
 pub(crate) struct PreparedItem {
     pub(crate) name: Span<String>,
     pub(crate) fields: Vec<PreparedField>,
     pub(crate) is_copy: bool,
+    /// Whether every field implements `Eq` and `Hash`, so that the row struct derives them
+    pub(crate) is_hashable: bool,
+    /// Whether every field implements `Ord`, so that the row struct derives it
+    pub(crate) is_orderable: bool,
     pub(crate) is_named: bool,
+    /// Whether the name of this item is derived from its query rather than declared
+    pub(crate) is_implicit: bool,
     pub(crate) is_ref: bool,
+    /// Widest arity among the queries using this item
+    pub(crate) arity: Arity,
+    /// Dimensions of the embedding fields read from table columns declaring them, by name
+    pub(crate) dimensions: Vec<(String, i32)>,
+    /// Name of the item before it was renamed
+    pub(crate) renamed_from: Option<String>,
+    /// Paths of the user structs converted from and into this row, declared by `into` options
+    pub(crate) into: Vec<String>,
 }
 
 impl PreparedItem {
@@ -108,8 +295,15 @@ impl PreparedItem {
         Self {
             name,
             is_copy: fields.iter().all(|f| f.ty.is_copy()),
+            is_hashable: fields.iter().all(|f| f.ty.is_hashable()),
+            is_orderable: fields.iter().all(|f| f.ty.is_orderable()),
             is_ref: fields.iter().any(|f| f.ty.is_ref()),
             is_named: !is_implicit || fields.len() > 1,
+            is_implicit,
+            arity: Arity::One,
+            dimensions: Vec::new(),
+            renamed_from: None,
+            into: Vec::new(),
             fields,
         }
     }
@@ -139,36 +333,108 @@ pub(crate) enum PreparedContent {
 #[derive(Debug, Clone)]
 pub(crate) struct PreparedModule {
     pub(crate) info: ModuleInfo,
+    /// Name of the generated module, differing from the name of its file when renamed
+    pub(crate) name: String,
     pub(crate) queries: IndexMap<Span<String>, PreparedQuery>,
     pub(crate) params: IndexMap<Span<String>, PreparedItem>,
     pub(crate) rows: IndexMap<Span<String>, PreparedItem>,
+    pub(crate) locks: Vec<PreparedLock>,
+    /// Whether a function running the queries in a single transaction is generated, as
+    /// declared by a `-- transaction` annotation
+    pub(crate) is_transaction_module: bool,
+    /// Warnings reported while preparing the queries, emitted once the module is prepared
+    pub(crate) warnings: Vec<Warning>,
+}
+
+/// Prefixes of the functions generated for each lock, followed by its name
+pub(crate) const LOCK_FN_PREFIXES: [&str; 5] =
+    ["lock", "try_lock", "unlock", "xact_lock", "try_xact_lock"];
+
+/// An advisory lock declared with `--! lock name`
+#[derive(Debug, Clone)]
+pub(crate) struct PreparedLock {
+    pub(crate) name: Span<String>,
+    pub(crate) key: i64,
+}
+
+impl PreparedLock {
+    fn new(name: Span<String>) -> Self {
+        let key = lock_key(&name.value);
+        Self { name, key }
+    }
+}
+
+/// Key of an advisory lock: the FNV-1a 64-bit hash of the UTF-8 bytes of its name,
+/// reinterpreted as a signed integer. Applications generated by different versions
+/// sharing the same locks, it must never change.
+pub(crate) fn lock_key(name: &str) -> i64 {
+    let hash = name.bytes().fold(0xcbf2_9ce4_8422_2325_u64, |hash, byte| {
+        (hash ^ u64::from(byte)).wrapping_mul(0x0100_0000_01b3)
+    });
+    i64::from_ne_bytes(hash.to_ne_bytes())
 }
 
 #[derive(Debug, Clone)]
 pub(crate) struct Preparation {
     pub(crate) modules: Vec<PreparedModule>,
     pub(crate) types: IndexMap<String, Vec<PreparedType>>,
+    /// Newtypes generated in the `types` module
+    pub(crate) newtypes: Vec<Rc<CornucopiaType>>,
+    /// Whether the `Vector` type of pgvector embeddings is generated in the `types` module
+    pub(crate) has_vector: bool,
 }
 
 impl PreparedModule {
+    /// Registers a row or params struct, returning its index and the indexes of the fields
+    /// as declared by this query. With `merge_nullability`, named types whose uses only
+    /// differ by the nullability of their fields are made nullable, with a warning.
+    #[allow(clippy::too_many_arguments)]
     fn add(
         info: &ModuleInfo,
         map: &mut IndexMap<Span<String>, PreparedItem>,
+        warnings: &mut Vec<Warning>,
         name: Span<String>,
         fields: Vec<PreparedField>,
         is_implicit: bool,
+        merge_nullability: bool,
     ) -> Result<(usize, Vec<usize>), Error> {
         assert!(!fields.is_empty());
         match map.entry(name.clone()) {
-            Entry::Occupied(o) => {
-                let prev = &o.get();
+            Entry::Occupied(mut o) => {
+                let prev = o.get_mut();
                 // If the row doesn't contain the same fields as a previously
                 // registered row with the same name...
                 let indexes: Vec<_> = if prev.is_named {
-                    validation::named_struct_field(info, &prev.name, &prev.fields, &name, &fields)?;
+                    if merge_nullability
+                        && prev.fields != fields
+                        && validation::differ_in_nullability(&prev.fields, &fields)
+                    {
+                        let mut merged = Vec::new();
+                        for field in &mut prev.fields {
+                            let other = fields.iter().find(|it| it.ident == field.ident).unwrap();
+                            if other.is_nullable != field.is_nullable
+                                || other.is_inner_nullable != field.is_inner_nullable
+                            {
+                                field.is_nullable |= other.is_nullable;
+                                field.is_inner_nullable |= other.is_inner_nullable;
+                                merged.push(field.ident.db.clone());
+                            }
+                        }
+                        warnings.push(validation::merged_nullability(
+                            info, &prev.name, &name, &merged,
+                        ));
+                    } else {
+                        validation::named_struct_field(
+                            info,
+                            &prev.name,
+                            &prev.fields,
+                            &name,
+                            &fields,
+                        )?;
+                    }
                     prev.fields
                         .iter()
-                        .map(|f| fields.iter().position(|it| it == f).unwrap())
+                        .map(|f| fields.iter().position(|it| it.ident == f.ident).unwrap())
                         .collect()
                 } else {
                     vec![0]
@@ -178,7 +444,15 @@ impl PreparedModule {
             }
             Entry::Vacant(v) => {
                 v.insert(PreparedItem::new(name.clone(), fields.clone(), is_implicit));
-                Self::add(info, map, name, fields, is_implicit)
+                Self::add(
+                    info,
+                    map,
+                    warnings,
+                    name,
+                    fields,
+                    is_implicit,
+                    merge_nullability,
+                )
             }
         }
     }
@@ -188,13 +462,34 @@ impl PreparedModule {
         name: Span<String>,
         fields: Vec<PreparedField>,
         is_implicit: bool,
+        arity: Arity,
+        dimensions: Vec<(String, i32)>,
+        merge_nullability: bool,
     ) -> Result<(usize, Vec<usize>), Error> {
         let fuck = if fields.len() == 1 && is_implicit {
             name.map(|_| fields[0].unwrapped_name())
         } else {
             name
         };
-        Self::add(&self.info, &mut self.rows, fuck, fields, is_implicit)
+        let (idx, indexes) = Self::add(
+            &self.info,
+            &mut self.rows,
+            &mut self.warnings,
+            fuck,
+            fields,
+            is_implicit,
+            merge_nullability,
+        )?;
+        // A row shared by multiple queries must support the widest of their arities
+        let row = self.rows.get_index_mut(idx).unwrap().1;
+        row.arity = row.arity.max(arity);
+        // Embedding columns keep the dimensions known to the first query reading them
+        for (field, dims) in dimensions {
+            if !row.dimensions.iter().any(|(it, _)| *it == field) {
+                row.dimensions.push((field, dims));
+            }
+        }
+        Ok((idx, indexes))
     }
 
     fn add_param(
@@ -202,53 +497,282 @@ impl PreparedModule {
         name: Span<String>,
         fields: Vec<PreparedField>,
         is_implicit: bool,
+        merge_nullability: bool,
     ) -> Result<(usize, Vec<usize>), Error> {
-        Self::add(&self.info, &mut self.params, name, fields, is_implicit)
+        Self::add(
+            &self.info,
+            &mut self.params,
+            &mut self.warnings,
+            name,
+            fields,
+            is_implicit,
+            merge_nullability,
+        )
     }
 
-    fn add_query(
-        &mut self,
-        name: Span<String>,
-        param_idx: Option<(usize, Vec<usize>)>,
-        row_idx: Option<(usize, Vec<usize>)>,
-        sql: String,
-    ) {
-        self.queries.insert(
-            name.clone(),
-            PreparedQuery {
-                ident: Ident::new(name.value),
-                row: row_idx,
-                sql,
-                param: param_idx,
-            },
-        );
+    fn add_query(&mut self, name: Span<String>, sql: String) -> &mut PreparedQuery {
+        self.queries.entry(name.clone()).or_insert(PreparedQuery {
+            ident: Ident::new(name.value),
+            row: None,
+            is_read_only: is_read_only(&sql),
+            sql,
+            param: None,
+            copy_out: None,
+            copy_in: None,
+            on_error: Vec::new(),
+            batch: None,
+            unprepared: None,
+            cache: None,
+            budget_ms: None,
+            skips_conflicts: false,
+            sources: Vec::new(),
+            variant: None,
+            variants: Vec::new(),
+            tags: Vec::new(),
+            renamed_from: None,
+        })
     }
 }
 
-/// Prepares all modules
-pub(crate) fn prepare(client: &mut Client, modules: Vec<Module>) -> Result<Preparation, Error> {
-    let mut registrar = TypeRegistrar::default();
+/// Keeps the queries selected by the allowlist of the settings, along with the setup queries
+/// of their modules which they may depend on. Modules without any selected query are removed.
+fn prune_modules(modules: Vec<Module>, settings: &CodegenSettings) -> Vec<Module> {
+    if settings.only.is_empty() && settings.only_tags.is_empty() {
+        return modules;
+    }
+    modules
+        .into_iter()
+        .filter_map(|mut module| {
+            let name = &module.info.name;
+            let is_selected = |query: &Query| settings.is_selected(name, query);
+            if !module.queries.iter().any(is_selected) {
+                return None;
+            }
+            module.queries.retain(|query| {
+                is_selected(query) || query.options.iter().any(|it| it.name.value == "setup")
+            });
+            Some(module)
+        })
+        .collect()
+}
+
+/// Prepares all modules. When generation keeps going, the errors of the modules that
+/// cannot be prepared are pushed to `failures` and those modules are skipped.
+pub(crate) fn prepare(
+    db: &mut Database,
+    modules: Vec<Module>,
+    settings: &CodegenSettings,
+    failures: &mut Vec<Error>,
+    observer: &mut dyn ProgressObserver,
+) -> Result<Preparation, Error> {
+    validation::newtypes(&settings.newtypes)?;
+    // The nullability file is read once, its columns being looked up by every query
+    let mut settings = settings.clone();
+    settings.column_nullability = ColumnNullability::read(settings.nullability_file())?;
+    let settings = &settings;
+    validation::only_patterns(settings.only(), &modules)?;
+    validation::only_tags(settings.only_tags(), &modules)?;
+    let modules = prune_modules(modules, settings);
+    let vector_type = db
+        .vector_type()
+        .map_err(type_registrar::error::Error::from)?;
+    let mut registrar = TypeRegistrar::new(
+        settings.date_time_crate,
+        settings.numeric_crate,
+        vector_type,
+    );
     let mut tmp = Preparation {
         modules: Vec::new(),
         types: IndexMap::new(),
+        newtypes: Vec::new(),
+        has_vector: false,
     };
     let declared: Vec<_> = modules
         .iter()
         .flat_map(|it| &it.types)
         .map(|ty| (*ty).clone())
         .collect();
+    db.record_extensions()
+        .map_err(type_registrar::error::Error::from)?;
+
+    // Roles only matter when preparing against a live database
+    let roles = if matches!(db, Database::Offline(_)) {
+        &[]
+    } else {
+        settings.roles.as_slice()
+    };
+    if let Some((first, others)) = roles.split_first() {
+        let result = prepare_as_roles(db, &modules, &mut registrar, settings, first, others);
+        db.batch_execute("RESET ROLE")
+            .map_err(|err| Error::new_role_err(first, &err))?;
+        tmp.modules = result?;
+        for module in &mut tmp.modules {
+            report_module(module, db, observer);
+        }
+    } else {
+        for module in modules {
+            match prepare_module(db, module, &mut registrar, settings) {
+                Ok(mut module) => {
+                    report_module(&mut module, db, observer);
+                    tmp.modules.push(module);
+                }
+                Err(err) if settings.keep_going => failures.push(err),
+                Err(err) => return Err(err),
+            }
+        }
+    }
+
+    validation::lock_keys(&tmp.modules)?;
+
+    // Shared types are generated separately
+    if settings.types_path.is_none() {
+        check_nullability(db, &registrar, &declared, settings, observer)?;
+        tmp.types = prepare_types(&registrar, &declared);
+    }
+    tmp.newtypes = registrar
+        .newtypes
+        .values()
+        .filter(|it| matches!(it.as_ref(), CornucopiaType::Newtype { is_generated, .. } if *is_generated))
+        .cloned()
+        .collect();
+    tmp.has_vector = registrar
+        .types
+        .values()
+        .any(|it| matches!(it.as_ref(), CornucopiaType::Vector { .. }));
+
+    // Redaction only depends on the field name, so fields shared by queries stay identical
+    let item_fields = tmp
+        .modules
+        .iter_mut()
+        .flat_map(|it| it.params.values_mut().chain(it.rows.values_mut()))
+        .flat_map(|it| &mut it.fields);
+    let composite_fields = tmp
+        .types
+        .values_mut()
+        .flatten()
+        .filter_map(|it| match &mut it.content {
+            PreparedContent::Composite(fields) => Some(fields),
+            PreparedContent::Enum(_) => None,
+        })
+        .flatten();
+    for field in item_fields.chain(composite_fields) {
+        field.is_redacted = settings.is_redacted(&field.ident.db);
+    }
+    rename(&mut tmp.modules, settings)?;
+    Ok(tmp)
+}
+
+/// Renames the generated modules, functions, rows and params as configured. Every
+/// reference to them is generated from their prepared name, so they stay consistent.
+fn rename(modules: &mut [PreparedModule], settings: &CodegenSettings) -> Result<(), Error> {
+    if settings.renames.is_empty() {
+        return Ok(());
+    }
+    let renamed: Vec<_> = modules
+        .iter()
+        .map(|module| {
+            let name = &module.info.name;
+            (
+                name.clone(),
+                settings.renamed(name, RenameTarget::Modules, name),
+            )
+        })
+        .collect();
+    validation::renames("modules", "", &renamed)?;
+    for (module, (_, name)) in modules.iter_mut().zip(renamed) {
+        module.name = name;
+    }
 
     for module in modules {
-        tmp.modules
-            .push(prepare_module(client, module, &mut registrar)?);
+        let info = &module.info;
+        let scope = format!(" of the module `{}`", info.name);
+        let functions: Vec<_> = module
+            .queries
+            .values()
+            .map(|query| {
+                let name = &query.ident.db;
+                (
+                    name.clone(),
+                    settings.renamed(&info.name, RenameTarget::Functions, name),
+                )
+            })
+            .collect();
+        validation::renames("functions", &scope, &functions)?;
+        for (query, (name, renamed)) in module.queries.values_mut().zip(functions) {
+            if name != renamed {
+                query.ident = Ident {
+                    rs: Ident::normalize_ident(&renamed),
+                    db: name.clone(),
+                };
+                query.renamed_from = Some(name);
+            }
+        }
+
+        // Rows and params structs are generated side by side
+        let rename_item = |target, item: &PreparedItem| {
+            let name = &item.name.value;
+            let renamed = if item.is_named {
+                settings.renamed(&info.name, target, name)
+            } else {
+                name.clone()
+            };
+            (name.clone(), renamed)
+        };
+        let rows: Vec<_> = module
+            .rows
+            .values()
+            .map(|it| rename_item(RenameTarget::Rows, it))
+            .collect();
+        let params: Vec<_> = module
+            .params
+            .values()
+            .map(|it| rename_item(RenameTarget::Params, it))
+            .collect();
+        let structs: Vec<_> = rows.iter().chain(&params).cloned().collect();
+        validation::renames("structs", &scope, &structs)?;
+        for (items, renamed) in [(&mut module.rows, rows), (&mut module.params, params)] {
+            *items = std::mem::take(items)
+                .into_values()
+                .zip(renamed)
+                .map(|(mut item, (name, renamed))| {
+                    if name != renamed {
+                        item.name.value = renamed;
+                        item.renamed_from = Some(name);
+                    }
+                    (item.name.clone(), item)
+                })
+                .collect();
+        }
     }
+    Ok(())
+}
+
+/// Emits the warnings of a prepared module, then reports it as prepared, from the cache
+/// of the previous generation if its statements were replayed from it
+fn report_module(module: &mut PreparedModule, db: &Database, observer: &mut dyn ProgressObserver) {
+    for warning in module.warnings.drain(..) {
+        observer.on_event(ProgressEvent::WarningEmitted(warning));
+    }
+    let (name, queries) = (module.info.name.clone(), module.queries.len());
+    observer.on_event(if db.is_cached(&name) {
+        ProgressEvent::ModuleCached { name, queries }
+    } else {
+        ProgressEvent::ModulePrepared { name, queries }
+    });
+}
 
-    // Prepare types grouped by schema
+/// Prepares the registered custom types, grouped by schema
+fn prepare_types(
+    registrar: &TypeRegistrar,
+    declared: &[TypeAnnotation],
+) -> IndexMap<String, Vec<PreparedType>> {
+    let mut types = IndexMap::new();
     for ((schema, name), ty) in &registrar.types {
-        if let Some(ty) = prepare_type(&registrar, name, ty, &declared) {
-            match tmp.types.entry(schema.clone()) {
+        if let Some(ty) = prepare_type(registrar, name, ty, declared) {
+            match types.entry(schema.clone()) {
                 Entry::Occupied(mut entry) => {
-                    entry.get_mut().push(ty);
+                    let prepared: &mut Vec<_> = entry.get_mut();
+                    prepared.push(ty);
                 }
                 Entry::Vacant(entry) => {
                     entry.insert(vec![ty]);
@@ -256,7 +780,196 @@ pub(crate) fn prepare(client: &mut Client, modules: Vec<Module>) -> Result<Prepa
             }
         }
     }
-    Ok(tmp)
+    types
+}
+
+/// Checks that the nullability of every composite field is either declared in a type
+/// annotation or verified in the catalog. Postgres cannot constrain composite attributes
+/// themselves, only the domains they are declared with.
+fn check_nullability(
+    db: &mut Database,
+    registrar: &TypeRegistrar,
+    declared: &[TypeAnnotation],
+    settings: &CodegenSettings,
+    observer: &mut dyn ProgressObserver,
+) -> Result<(), Error> {
+    for ty in registrar.types.values() {
+        let CornucopiaType::Custom { pg_ty, .. } = ty.as_ref() else {
+            continue;
+        };
+        let Kind::Composite(fields) = pg_ty.kind() else {
+            continue;
+        };
+        let annotated = declared
+            .iter()
+            .find(|it| it.name.value == pg_ty.name())
+            .map_or(&[] as &[NullableIdent], |it| it.fields.as_slice());
+        let domains: Vec<_> = fields
+            .iter()
+            .filter(|it| matches!(it.type_().kind(), Kind::Domain(_)))
+            .map(|it| it.type_().oid())
+            .collect();
+        let not_null = db
+            .not_null_domains(&domains)
+            .map_err(type_registrar::error::Error::from)?;
+        let unverified: Vec<_> = fields
+            .iter()
+            .filter(|field| {
+                !annotated.iter().any(|it| it.name.value == field.name())
+                    && !not_null.contains(&field.type_().oid())
+            })
+            .map(|it| it.name())
+            .collect();
+        if unverified.is_empty() {
+            continue;
+        }
+        let (ty, fields, annotation) = (
+            pg_ty.name().to_string(),
+            unverified
+                .iter()
+                .map(|it| format!("`{it}`"))
+                .collect::<Vec<_>>()
+                .join(", "),
+            // The suggested annotation keeps the already declared fields
+            fields
+                .iter()
+                .filter_map(|field| {
+                    match annotated.iter().find(|it| it.name.value == field.name()) {
+                        Some(it) => Some(format!(
+                            "{}{}{}",
+                            field.name(),
+                            if it.nullable { "?" } else { "" },
+                            if it.inner_nullable { "[?]" } else { "" }
+                        )),
+                        None if unverified.contains(&field.name()) => {
+                            Some(format!("{}?", field.name()))
+                        }
+                        None => None,
+                    }
+                })
+                .collect::<Vec<_>>()
+                .join(", "),
+        );
+        if !settings.strict_nullability() {
+            let warning = Warning::new(error::UnverifiedNullabilityWarning {
+                ty,
+                fields,
+                annotation,
+            });
+            observer.on_event(ProgressEvent::WarningEmitted(warning));
+            continue;
+        }
+        return Err(Error::UnverifiedNullability {
+            ty,
+            fields,
+            annotation,
+        });
+    }
+    Ok(())
+}
+
+/// Prepares every custom type of the database, to be shared by the queries of several
+/// crates. Types are sorted by schema and name so that they don't depend on any query.
+pub(crate) fn prepare_shared_types(
+    client: &mut Client,
+    settings: &CodegenSettings,
+) -> Result<IndexMap<String, Vec<PreparedType>>, Error> {
+    let names = custom_types(client).map_err(type_registrar::error::Error::from)?;
+    if names.is_empty() {
+        return Ok(IndexMap::new());
+    }
+    let casts: Vec<_> = names
+        .iter()
+        .map(|(schema, name)| format!("NULL::{}.{}", quote_ident(schema), quote_ident(name)))
+        .collect();
+    let sql = format!("SELECT {}", casts.join(", "));
+    let module_info = ModuleInfo::new("<types>".into(), "types".to_string(), sql.clone());
+    let sql_span: SourceSpan = (0..sql.len()).into();
+    let query_name = Span {
+        span: sql_span,
+        value: "types".to_string(),
+    };
+    let stmt = Statement::prepare(client, &sql)
+        .map_err(|e| Error::new_db_err(&e, &module_info, &sql_span, &query_name))?;
+
+    let mut registrar = TypeRegistrar::new(
+        settings.date_time_crate,
+        settings.numeric_crate,
+        vector_type(client).map_err(type_registrar::error::Error::from)?,
+    );
+    for ((_, name), col) in names.iter().zip(&stmt.columns) {
+        registrar.register(name, col.type_(), &query_name, &module_info)?;
+    }
+    let mut types = prepare_types(&registrar, &[]);
+    types.sort_keys();
+    for prepared in types.values_mut() {
+        prepared.sort_by(|a, b| a.name.cmp(&b.name));
+    }
+    // Redaction is applied like for the types generated along with queries
+    for prepared in types.values_mut().flatten() {
+        if let PreparedContent::Composite(fields) = &mut prepared.content {
+            for field in fields {
+                field.is_redacted = settings.is_redacted(&field.ident.db);
+            }
+        }
+    }
+    Ok(types)
+}
+
+/// Quotes an identifier, escaping its double quotes
+fn quote_ident(ident: &str) -> String {
+    format!("\"{}\"", ident.replace('"', "\"\""))
+}
+
+/// Prepares all modules as each role, checking that every query has the same
+/// parameters and columns as with the first role, whose preparation is returned
+fn prepare_as_roles(
+    db: &mut Database,
+    modules: &[Module],
+    registrar: &mut TypeRegistrar,
+    settings: &CodegenSettings,
+    first: &str,
+    others: &[String],
+) -> Result<Vec<PreparedModule>, Error> {
+    fn prepare_as(
+        db: &mut Database,
+        modules: &[Module],
+        registrar: &mut TypeRegistrar,
+        settings: &CodegenSettings,
+        role: &str,
+    ) -> Result<Vec<PreparedModule>, Error> {
+        db.batch_execute(&format!("SET ROLE {}", quote_ident(role)))
+            .map_err(|err| Error::new_role_err(role, &err))?;
+        modules
+            .iter()
+            .map(|module| {
+                prepare_module(db, module.clone(), registrar, settings).map_err(|err| Error::Role {
+                    role: role.to_string(),
+                    err: err.into(),
+                })
+            })
+            .collect()
+    }
+
+    let prepared = prepare_as(db, modules, registrar, settings, first)?;
+    // Only the warnings of the first role are reported, those of others being identical
+    for role in others {
+        let other = prepare_as(
+            db,
+            modules,
+            &mut TypeRegistrar::new(
+                settings.date_time_crate,
+                settings.numeric_crate,
+                registrar.vector_type,
+            ),
+            settings,
+            role,
+        )?;
+        for (module, other) in prepared.iter().zip(&other) {
+            validation::role_shape(module, other, first, role)?;
+        }
+    }
+    Ok(prepared)
 }
 
 fn normalize_rust_name(name: &str) -> String {
@@ -317,60 +1030,204 @@ fn prepare_type(
 
 /// Prepares all queries in this module
 fn prepare_module(
-    client: &mut Client,
+    db: &mut Database,
     module: Module,
     registrar: &mut TypeRegistrar,
+    settings: &CodegenSettings,
 ) -> Result<PreparedModule, Error> {
     validation::validate_module(&module)?;
+    let (queries, variants) = select_variants(db, module.queries, &module.info, settings)?;
 
     let mut tmp_prepared_module = PreparedModule {
+        name: module.info.name.clone(),
         info: module.info.clone(),
         queries: IndexMap::new(),
         params: IndexMap::new(),
         rows: IndexMap::new(),
+        locks: module
+            .locks
+            .iter()
+            .map(|it| PreparedLock::new(it.name.clone()))
+            .collect(),
+        is_transaction_module: module.info.transaction,
+        warnings: Vec::new(),
     };
 
-    for query in module.queries {
-        prepare_query(
-            client,
-            &mut tmp_prepared_module,
-            registrar,
-            &module.types,
-            query,
-            &module.info,
-        )?;
+    // The names used by the queries of a module declaring its schema are searched in it
+    // first, then in `public`
+    if let Some(schema) = &module.info.schema {
+        if !db
+            .has_schema(&schema.value)
+            .map_err(type_registrar::error::Error::from)?
+        {
+            return Err(Error::new_unknown_schema(&module.info, schema));
+        }
+        db.batch_execute(&format!(
+            "SET search_path TO {}, public",
+            quote_ident(&schema.value)
+        ))
+        .map_err(type_registrar::error::Error::from)?;
     }
 
-    validation::validate_preparation(&tmp_prepared_module)?;
+    // Setup queries are executed inside a transaction rolled back once the module is
+    // prepared, to leave the database untouched
+    let setup = queries
+        .iter()
+        .find(|it| it.options.iter().any(|it| it.name.value == "setup"))
+        .map(|it| (it.sql_span, it.name.clone()));
+    if let Some((sql_span, name)) = &setup {
+        db.batch_execute("BEGIN")
+            .map_err(|e| Error::new_setup_err(&e, &module.info, sql_span, name))?;
+    }
+
+    let prepare_queries = || {
+        for query in queries {
+            prepare_query(
+                db,
+                &mut tmp_prepared_module,
+                registrar,
+                &module.types,
+                query,
+                &module.info,
+                settings,
+            )?;
+        }
+        Ok::<_, Error>(())
+    };
+    let result = prepare_queries();
+
+    if let Some((sql_span, name)) = &setup {
+        db.batch_execute("ROLLBACK")
+            .map_err(|e| Error::new_setup_err(&e, &module.info, sql_span, name))?;
+    }
+    if module.info.schema.is_some() {
+        db.batch_execute("RESET search_path")
+            .map_err(type_registrar::error::Error::from)?;
+    }
+    result?;
+
+    for (name, variant, all) in variants {
+        if let Some(query) = tmp_prepared_module.queries.get_mut(&name) {
+            query.variant = Some(variant);
+            query.variants = all;
+        }
+    }
+    validation::validate_preparation(&tmp_prepared_module, settings.gen_params_builders)?;
 
     Ok(tmp_prepared_module)
 }
 
+/// The variant selected for a query, with the `server_version_num` of the database and
+/// every variant when they are all generated
+type SelectedVariant = (
+    Span<String>,
+    (VersionRange, i32),
+    Vec<(VersionRange, String)>,
+);
+
+/// Keeps the variant of each query matching the version of the server, the other ones
+/// being kept aside when every variant is generated
+fn select_variants(
+    db: &mut Database,
+    queries: Vec<Query>,
+    info: &ModuleInfo,
+    settings: &CodegenSettings,
+) -> Result<(Vec<Query>, Vec<SelectedVariant>), Error> {
+    let Some(versioned) = queries.iter().find(|it| it.version.is_some()) else {
+        return Ok((queries, Vec::new()));
+    };
+    let version = db
+        .server_version()
+        .map_err(|e| Error::new_prepare_err(e, info, &versioned.sql_span, &versioned.name))?;
+    let major = u32::try_from(version / 10000).unwrap_or_default();
+
+    let mut all: IndexMap<String, Vec<(VersionRange, String)>> = IndexMap::new();
+    let mut selected = Vec::new();
+    let mut kept = Vec::new();
+    for query in queries {
+        let Some(range) = query.version.as_ref().map(|it| it.value) else {
+            kept.push(query);
+            continue;
+        };
+        if settings.all_variants {
+            let sql = if settings.normalize_sql {
+                normalize_sql(&query.sql_str)
+            } else {
+                query.sql_str.clone()
+            };
+            all.entry(query.name.value.clone())
+                .or_default()
+                .push((range, sql));
+        }
+        // The variants were validated to cover every version exactly once
+        if range.contains(major) {
+            selected.push((query.name.clone(), (range, version)));
+            kept.push(query);
+        }
+    }
+    let selected = selected
+        .into_iter()
+        .map(|(name, variant)| {
+            let variants = all.swap_remove(&name.value).unwrap_or_default();
+            (name, variant, variants)
+        })
+        .collect();
+    Ok((kept, selected))
+}
+
 /// Prepares a query
 fn prepare_query(
-    client: &mut Client,
+    db: &mut Database,
     module: &mut PreparedModule,
     registrar: &mut TypeRegistrar,
     types: &[TypeAnnotation],
     Query {
+        annotation_span: _,
         name,
+        version: _,
         param,
         bind_params,
         row,
         sql_str,
         sql_span,
+        options,
+        bind_newtypes,
     }: Query,
     module_info: &ModuleInfo,
+    settings: &CodegenSettings,
 ) -> Result<(), Error> {
+    let tags = QueryOption::tags(&options)
+        .map(|(key, value)| (key.to_string(), value.to_string()))
+        .collect();
+    // Batch queries are executed using the simple query protocol, without being prepared
+    if let Some(option) = options.iter().find(|it| it.name.value == "batch") {
+        validation::option_with_params(&module.info, &name, option, !bind_params.is_empty())?;
+        validation::param_on_simple_query(&module.info, &name, &sql_span, &param, &[])?;
+        validation::row_on_execute(&module.info, &name, &sql_span, &row, &[])?;
+        db.check_batch(&module_info.name, &name.value, &sql_str)
+            .map_err(|e| Error::new_prepare_err(e, module_info, &sql_span, &name))?;
+
+        let sql_str = if settings.normalize_sql {
+            normalize_sql(&sql_str)
+        } else {
+            sql_str
+        };
+        let commands = statement_commands(&sql_str);
+        let query = module.add_query(name, sql_str);
+        query.batch = Some(commands);
+        query.tags = tags;
+        return Ok(());
+    }
+
     // Prepare the statement
-    let stmt = client
-        .prepare(&sql_str)
-        .map_err(|e| Error::new_db_err(&e, module_info, &sql_span, &name))?;
+    let stmt = db
+        .prepare(&module_info.name, &name.value, &sql_str)
+        .map_err(|e| Error::new_prepare_err(e, module_info, &sql_span, &name))?;
 
     let (nullable_params_fields, params_name) = param.name_and_fields(types, &name, Some("Params"));
     let (nullable_row_fields, row_name) = row.name_and_fields(types, &name, None);
     let params_fields = {
-        let stmt_params = stmt.params();
+        let stmt_params = &stmt.params;
         let params = bind_params
             .iter()
             .zip(stmt_params)
@@ -382,6 +1239,7 @@ fn prepare_query(
             // If none of the row's columns match the nullable column
             validation::nullable_param_name(&module.info, nullable_col, &params)
                 .map_err(Error::from)?;
+            validation::record_param(&module.info, nullable_col)?;
         }
 
         let mut param_fields = Vec::new();
@@ -389,70 +1247,489 @@ fn prepare_query(
             let nullity = nullable_params_fields
                 .iter()
                 .find(|x| x.name.value == col_name.value);
+            validation::inner_nullable_array(&module.info, nullity, &col_ty)?;
+            if let (Some(nullable_col), Kind::Domain(_)) = (nullity, col_ty.kind()) {
+                let not_null = db
+                    .not_null_domains(&[col_ty.oid()])
+                    .map_err(type_registrar::error::Error::from)?;
+                validation::nullable_not_null_domain(
+                    &module.info,
+                    nullable_col,
+                    &col_ty,
+                    &not_null,
+                )?;
+            }
             // Register type
-            param_fields.push(PreparedField::new(
-                col_name.value.clone(),
-                registrar
-                    .register(&col_name.value, &col_ty, &name, module_info)?
-                    .clone(),
-                nullity,
-            ));
+            let mut ty = registrar
+                .register(&col_name.value, &col_ty, &name, module_info)?
+                .clone();
+            if let Some(path) =
+                validation::json_type(&module.info, &col_name.value, &col_ty, nullity)?
+            {
+                ty = typed_json(&col_ty, path);
+            }
+            // Snapshots are recorded without any codegen settings
+            let annotated = if db.is_recording() {
+                None
+            } else {
+                validation::param_newtype(
+                    &module.info,
+                    &sql_span,
+                    &bind_newtypes,
+                    &col_name,
+                    &settings.newtypes,
+                )?
+            };
+            let newtype = annotated.or_else(|| {
+                let pos = (
+                    sql_span.offset() + col_name.span.offset(),
+                    col_name.span.len(),
+                );
+                domain_newtype(settings, &col_ty).map(|it| (it, pos.into()))
+            });
+            if let Some((newtype, pos)) = newtype {
+                ty = wrap_newtype(registrar, &module.info, newtype, &col_name.value, ty, pos)?;
+            }
+            param_fields.push(PreparedField::new(col_name.value.clone(), ty, nullity));
         }
         param_fields
     };
 
-    let row_fields = {
-        let stmt_cols = stmt.columns();
+    let (row_fields, dimensions) = {
+        let stmt_cols = &stmt.columns;
         // Check for row declaration on execute
         validation::row_on_execute(&module.info, &name, &sql_span, &row, stmt_cols)?;
+        validation::row_option_on_execute(&module.info, &name, &sql_span, &options, stmt_cols)?;
         // Check for duplicate names
-        validation::duplicate_sql_col_name(&module.info, &name, stmt_cols).map_err(Error::from)?;
+        validation::duplicate_sql_col_name(&module.info, &name, &sql_str, stmt_cols)
+            .map_err(Error::from)?;
         for nullable_col in nullable_row_fields {
             // If none of the row's columns match the nullable column
             validation::nullable_column_name(&module.info, nullable_col, stmt_cols)
                 .map_err(Error::from)?;
         }
+        let allow_naive: Vec<_> = options
+            .iter()
+            .filter(|it| it.name.value == "allow_naive")
+            .flat_map(|it| &it.args)
+            .collect();
+        for col_name in &allow_naive {
+            validation::allow_naive_column_name(&module.info, col_name, stmt_cols)?;
+        }
+        for col in stmt_cols {
+            module.warnings.extend(validation::deprecated_column(
+                &module.info,
+                &name,
+                col,
+                settings.deprecation_marker(),
+            ));
+            if !allow_naive.iter().any(|it| it.value == col.name()) {
+                let warning = validation::naive_timestamp(
+                    &module.info,
+                    &name,
+                    col,
+                    settings.naive_timestamp_policy(),
+                )?;
+                module.warnings.extend(warning);
+            }
+        }
 
+        let outer_joined = outer_joined_tables(&sql_str);
         let mut row_fields = Vec::new();
-        for (col_name, col_ty) in stmt_cols.iter().map(|c| (c.name().to_owned(), c.type_())) {
+        let mut dimensions = Vec::new();
+        for col in stmt_cols {
+            let col_name = col.name();
             let nullity = nullable_row_fields
                 .iter()
                 .find(|x| x.name.value == col_name);
+            validation::inner_nullable_array(&module.info, nullity, col.type_())?;
             // Register type
-            let ty = registrar
-                .register(&col_name, col_ty, &name, module_info)?
-                .clone();
-            row_fields.push(PreparedField::new(
-                normalize_rust_name(&col_name),
-                ty,
-                nullity,
-            ));
+            let record = validation::record_column(&module.info, &name, col, nullity)?;
+            let mut ty = match record {
+                Some(types) => prepare_record(
+                    registrar,
+                    module_info,
+                    &name,
+                    &row_name,
+                    col,
+                    nullity,
+                    types,
+                )?,
+                None => registrar
+                    .register(col_name, col.type_(), &name, module_info)?
+                    .clone(),
+            };
+            if let Some(path) = validation::json_type(&module.info, col_name, col.type_(), nullity)?
+            {
+                ty = typed_json(col.type_(), path);
+            }
+            if let Some(newtype) = column_newtype(settings, col) {
+                ty = wrap_newtype(registrar, &module.info, newtype, col_name, ty, name.span)?;
+            }
+            let mut field = PreparedField::new(normalize_rust_name(col_name), ty, nullity);
+            let declared = col
+                .origin()
+                .and_then(|it| Some((it, settings.column_nullability.get(it)?)));
+            match (nullity, declared) {
+                (Some(nullity), Some((origin, nullable))) => {
+                    module.warnings.extend(validation::nullability_conflict(
+                        &module.info,
+                        nullity,
+                        origin,
+                        nullable,
+                        settings.column_nullability.path(),
+                    ));
+                }
+                (None, Some((_, nullable))) => field.is_nullable = nullable,
+                (None, None) if settings.infer_nullability() => {
+                    field.is_nullable = !col.origin().is_some_and(|it| it.not_null);
+                }
+                _ => {}
+            }
+            field.is_nullable |= is_outer_joined(col, &outer_joined);
+            if settings.deprecated_fields() {
+                field.deprecation = deprecation(col, settings.deprecation_marker());
+            }
+            if let Some(dims) = col.origin().and_then(|it| it.dimensions) {
+                if matches!(field.ty.as_ref(), CornucopiaType::Vector { .. }) {
+                    dimensions.push((field.ident.db.clone(), dims));
+                }
+            }
+            row_fields.push(field);
+        }
+        (row_fields, dimensions)
+    };
+
+    // Queries that are not prepared send the OIDs of their parameters on each execution
+    let prepared = options
+        .iter()
+        .find(|it| it.name.value == "prepared")
+        .map_or(settings.prepared_statements(), |it| {
+            it.args[0].value == "true"
+        });
+    let unprepared = if prepared {
+        None
+    } else {
+        for (param, ty) in bind_params.iter().zip(&stmt.params) {
+            validation::unprepared_param(&module.info, &name, &sql_span, param, ty)?;
         }
-        row_fields
+        Some(stmt.params.iter().map(Type::oid).collect())
     };
 
+    // Cached results are keyed by the hash of the parameters
+    let cache = options
+        .iter()
+        .find(|it| it.name.value == "cache")
+        .map(|option| {
+            let params = bind_params.iter().zip(&params_fields).zip(&stmt.params);
+            for ((param, field), ty) in params {
+                validation::cached_param(&module.info, &name, &sql_span, param, field, ty)?;
+            }
+            Ok::<_, Error>(option.args[0].value.parse::<CacheTtl>().unwrap().0)
+        })
+        .transpose()?;
+
+    // Execute setup queries so that the following queries can use what they create
+    if let Some(option) = options.iter().find(|it| it.name.value == "setup") {
+        validation::option_with_params(&module.info, &name, option, !params_fields.is_empty())?;
+        db.batch_execute(&sql_str)
+            .map_err(|e| Error::new_setup_err(&e, module_info, &sql_span, &name))?;
+    }
+
+    // Inserts skipping conflicts return no row on conflict
+    let skips_conflicts = !row_fields.is_empty() && skips_conflicts(&sql_str);
     let row_idx = if row_fields.is_empty() {
         None
     } else {
-        Some(module.add_row(row_name, row_fields, row.is_implicit())?)
+        let arity = match options.iter().find(|it| it.name.value == "arity") {
+            Some(option) => {
+                let arity = option.args[0].value.parse().unwrap();
+                if skips_conflicts && arity == Arity::One {
+                    let warning = validation::conflict_arity(&module.info, &name, option);
+                    module.warnings.push(warning);
+                }
+                arity
+            }
+            None if skips_conflicts => Arity::Opt,
+            None => Arity::Many,
+        };
+        Some(module.add_row(
+            row_name,
+            row_fields,
+            row.is_implicit(),
+            arity,
+            dimensions,
+            settings.merge_nullability,
+        )?)
     };
+    if let (Some(option), Some((idx, _))) =
+        (options.iter().find(|it| it.name.value == "into"), &row_idx)
+    {
+        let row = module.rows.get_index_mut(*idx).unwrap().1;
+        validation::into_row_struct(&module.info, &name, option, row.is_named)?;
+        let path = &option.args[0].value;
+        if !row.into.contains(path) {
+            row.into.push(path.clone());
+        }
+    }
     let param_idx = if params_fields.is_empty() {
         None
     } else {
-        Some(module.add_param(params_name, params_fields, param.is_implicit())?)
+        Some(module.add_param(
+            params_name,
+            params_fields,
+            param.is_implicit(),
+            settings.merge_nullability,
+        )?)
     };
-    module.add_query(name.clone(), param_idx, row_idx, sql_str);
+    // Errors were reported against the query as written, the generated one can now be normalized
+    let sql_str = if settings.normalize_sql {
+        normalize_sql(&sql_str)
+    } else {
+        sql_str
+    };
+    let copy_out = options
+        .iter()
+        .find(|it| it.name.value == "copy_out")
+        .map(|option| {
+            // COPY does not support bind parameters
+            validation::option_with_params(&module.info, &name, option, param_idx.is_some())?;
+            let format = option
+                .args
+                .iter()
+                .find_map(|arg| arg.value.strip_prefix("format="))
+                .map_or(CopyFormat::default(), |it| it.parse().unwrap());
+            Ok::<_, Error>(format!(
+                "COPY ({}) TO STDOUT ({})",
+                sql_str.trim(),
+                format.copy_options()
+            ))
+        })
+        .transpose()?;
+    let copy_in = options
+        .iter()
+        .find(|it| it.name.value == "copy_in")
+        .map(|option| {
+            // Only plain inserts of the params can be turned into a `COPY`
+            let statement = copy_in_statement(&sql_str);
+            validation::copy_in_insert(&module.info, &name, option, statement.is_some())?;
+            Ok::<_, Error>(statement.unwrap())
+        })
+        .transpose()?;
+    let on_error = options
+        .iter()
+        .filter(|it| it.name.value == "on_error")
+        .map(|it| (it.args[0].value.clone(), it.args[1].value.clone()))
+        .collect();
+    let query = module.add_query(name, sql_str);
+    query.tags = tags;
+    query.param = param_idx;
+    query.row = row_idx;
+    query.copy_out = copy_out;
+    query.copy_in = copy_in;
+    query.on_error = on_error;
+    query.unprepared = unprepared;
+    query.cache = cache;
+    query.budget_ms = options
+        .iter()
+        .find(|it| it.name.value == "budget")
+        .map(|it| it.args[0].value.parse::<QueryBudget>().unwrap().0);
+    query.skips_conflicts = skips_conflicts;
+    query.sources = stmt
+        .columns
+        .iter()
+        .filter_map(Column::origin)
+        .map(|it| (it.table_oid, it.column.clone()))
+        .collect();
 
     Ok(())
 }
 
+/// Prepares a bare SQL string outside of any query module, returning its params and row fields.
+pub(crate) fn prepare_sql(
+    client: &mut Client,
+    sql: &str,
+    nullable_columns: &[String],
+) -> Result<(Vec<PreparedField>, Vec<PreparedField>), Error> {
+    let module_info = ModuleInfo::new("<sql>".into(), "sql".to_string(), sql.to_string());
+    let sql_span: SourceSpan = (0..sql.len()).into();
+    let name = Span {
+        span: sql_span,
+        value: "sql".to_string(),
+    };
+    let stmt = Statement::prepare(client, sql)
+        .map_err(|e| Error::new_db_err(&e, &module_info, &sql_span, &name))?;
+
+    let mut registrar = TypeRegistrar::new(
+        DateTimeCrate::default(),
+        NumericCrate::default(),
+        vector_type(client).map_err(type_registrar::error::Error::from)?,
+    );
+    let mut param_fields = Vec::new();
+    for (i, ty) in stmt.params.iter().enumerate() {
+        let param_name = format!("${}", i + 1);
+        let ty = registrar
+            .register(&param_name, ty, &name, &module_info)?
+            .clone();
+        param_fields.push(PreparedField::new(param_name, ty, None));
+    }
+
+    let stmt_cols = &stmt.columns;
+    validation::duplicate_sql_col_name(&module_info, &name, sql, stmt_cols)?;
+    let nullable_idents: Vec<_> = nullable_columns
+        .iter()
+        .map(|col| NullableIdent {
+            name: name.map(|_| col.clone()),
+            nullable: true,
+            inner_nullable: false,
+            record: None,
+            json_type: None,
+            attributes: Vec::new(),
+        })
+        .collect();
+    for nullable_col in &nullable_idents {
+        validation::nullable_column_name(&module_info, nullable_col, stmt_cols)?;
+    }
+    let outer_joined = outer_joined_tables(sql);
+    let mut row_fields = Vec::new();
+    for col in stmt_cols {
+        let nullity = nullable_idents
+            .iter()
+            .find(|it| it.name.value == col.name());
+        let ty = registrar
+            .register(col.name(), col.type_(), &name, &module_info)?
+            .clone();
+        let mut field = PreparedField::new(col.name().to_string(), ty, nullity);
+        field.is_nullable |= is_outer_joined(col, &outer_joined);
+        row_fields.push(field);
+    }
+
+    Ok((param_fields, row_fields))
+}
+
+/// A `json` or `jsonb` value deserialized into the Rust type at `path`
+fn typed_json(ty: &Type, path: &Span<String>) -> Rc<CornucopiaType> {
+    Rc::new(CornucopiaType::TypedJson {
+        pg_ty: ty.clone(),
+        path: path.value.clone(),
+    })
+}
+
+/// Prepares the anonymous record returned in `col` from the `types` of the fields declared
+/// by its annotation, as a struct named after its row and column
+fn prepare_record(
+    registrar: &mut TypeRegistrar,
+    info: &ModuleInfo,
+    query_name: &Span<String>,
+    row_name: &Span<String>,
+    col: &Column,
+    nullity: Option<&NullableIdent>,
+    types: Vec<Type>,
+) -> Result<Rc<CornucopiaType>, Error> {
+    let declared = nullity
+        .and_then(|it| it.record.as_ref())
+        .expect("declared as a record");
+    let mut fields = Vec::new();
+    for (field, ty) in declared.iter().zip(&types) {
+        let ty = registrar
+            .register(&field.name.value, ty, query_name, info)?
+            .clone();
+        let mut prepared = PreparedField::new(normalize_rust_name(&field.name.value), ty, None);
+        prepared.is_nullable = field.nullable;
+        fields.push(prepared);
+    }
+    Ok(Rc::new(CornucopiaType::Record {
+        pg_ty: col.type_().clone(),
+        struct_name: format!("{}{}", row_name.value, col.name().to_upper_camel_case()),
+        column: col.name().to_string(),
+        fields,
+        field_types: types,
+    }))
+}
+
+/// Finds the newtype of a column read from a table column or declared with a domain
+fn column_newtype<'a>(settings: &'a CodegenSettings, col: &Column) -> Option<&'a Newtype> {
+    let origin = col.origin()?;
+    let by_column = settings.newtypes.iter().find(|it| {
+        it.target()
+            == NewtypeTarget::Column {
+                table: &origin.table,
+                column: &origin.column,
+            }
+    });
+    by_column.or_else(|| {
+        let domain = origin.domain.as_deref()?;
+        settings
+            .newtypes
+            .iter()
+            .find(|it| it.target() == NewtypeTarget::Domain(domain))
+    })
+}
+
+/// Finds the newtype of a parameter declared with a domain
+fn domain_newtype<'a>(settings: &'a CodegenSettings, ty: &Type) -> Option<&'a Newtype> {
+    matches!(ty.kind(), Kind::Domain(_))
+        .then(|| {
+            settings
+                .newtypes
+                .iter()
+                .find(|it| it.target() == NewtypeTarget::Domain(ty.name()))
+        })
+        .flatten()
+}
+
+fn wrap_newtype(
+    registrar: &mut TypeRegistrar,
+    info: &ModuleInfo,
+    newtype: &Newtype,
+    field: &str,
+    mut inner: Rc<CornucopiaType>,
+    pos: SourceSpan,
+) -> Result<Rc<CornucopiaType>, Error> {
+    // Result columns are described using the base type of domains, so newtypes
+    // wrap the base type and accept domains when written
+    while let CornucopiaType::Domain { inner: base, .. } = inner.as_ref() {
+        inner = base.clone();
+    }
+    validation::newtype_inner(
+        info,
+        newtype,
+        field,
+        &inner,
+        registrar.newtypes.get(&newtype.path),
+        pos,
+    )?;
+    Ok(registrar.register_newtype(newtype, inner))
+}
+
+/// Whether a column is read from a table on the nullable side of an outer join,
+/// making it nullable even if the table column is `NOT NULL`
+/// Comment of the table column of `col` when it starts with the deprecation `marker`
+pub(crate) fn deprecation(col: &Column, marker: &str) -> Option<String> {
+    col.origin()
+        .and_then(|it| it.comment.as_ref())
+        .filter(|it| !marker.is_empty() && it.starts_with(marker))
+        .cloned()
+}
+
+fn is_outer_joined(col: &Column, outer_joined: &[String]) -> bool {
+    col.table()
+        .is_some_and(|table| outer_joined.iter().any(|it| it == table))
+}
+
 pub(crate) mod error {
     use miette::{Diagnostic, NamedSource, SourceSpan};
     use thiserror::Error as ThisError;
 
     use crate::{
-        parser::Span, read_queries::ModuleInfo, type_registrar::error::Error as PostgresTypeError,
-        utils::db_err, validation::error::Error as ValidationError,
+        database::PrepareError,
+        nullability::error::Error as NullabilityError,
+        parser::Span,
+        read_queries::ModuleInfo,
+        snapshot::ReplayError,
+        type_registrar::error::Error as PostgresTypeError,
+        utils::{char_offset, db_err, extension_hint, snippet},
+        validation::error::Error as ValidationError,
     };
 
     #[derive(Debug, ThisError, Diagnostic)]
@@ -467,12 +1744,86 @@ pub(crate) mod error {
             #[label("error occurs near this location")]
             err_span: Option<SourceSpan>,
         },
+        #[error("Couldn't prepare query from snapshot: {msg}")]
+        #[diagnostic(help("record a new snapshot using `cornucopia snapshot`"))]
+        Snapshot {
+            msg: String,
+            #[source_code]
+            src: NamedSource,
+            #[label("query declared here")]
+            err_span: SourceSpan,
+        },
+        #[error("Couldn't check statement of batch query: {msg}")]
+        Batch {
+            msg: String,
+            #[help]
+            help: Option<String>,
+            #[source_code]
+            src: NamedSource,
+            #[label("error occurs near this location")]
+            err_span: Option<SourceSpan>,
+        },
+        #[error("Couldn't execute setup query: {msg}")]
+        Setup {
+            msg: String,
+            #[help]
+            help: Option<String>,
+            #[source_code]
+            src: NamedSource,
+            #[label("error occurs near this location")]
+            err_span: Option<SourceSpan>,
+        },
+        #[error("the nullability of {fields} in the composite `{ty}` is not verified")]
+        #[diagnostic(help(
+            "list the fields in a type annotation, marking nullable ones with `?`: `--: {ty}({annotation})`"
+        ))]
+        UnverifiedNullability {
+            ty: String,
+            fields: String,
+            annotation: String,
+        },
+        #[error("the schema `{schema}` does not exist in the database")]
+        #[diagnostic(help(
+            "create the schema in the database, or fix the `-- schema:` annotation"
+        ))]
+        UnknownSchema {
+            schema: String,
+            #[source_code]
+            src: NamedSource,
+            #[label("schema declared here")]
+            err_span: SourceSpan,
+        },
+        #[error("Couldn't set role `{role}`: {msg}")]
+        SetRole { role: String, msg: String },
+        #[error("Couldn't prepare queries as role `{role}`")]
+        Role {
+            role: String,
+            #[diagnostic_source]
+            err: miette::Report,
+        },
         #[error(transparent)]
         #[diagnostic(transparent)]
         PostgresType(#[from] PostgresTypeError),
         #[error(transparent)]
         #[diagnostic(transparent)]
         Validation(#[from] Box<ValidationError>),
+        #[error(transparent)]
+        #[diagnostic(transparent)]
+        Nullability(#[from] NullabilityError),
+    }
+
+    #[derive(Debug, ThisError, Diagnostic)]
+    #[error("the nullability of {fields} in the composite `{ty}` is not verified")]
+    #[diagnostic(
+        severity(Warning),
+        help(
+            "the fields are generated as non-nullable, list them in a type annotation, marking nullable ones with `?`: `--: {ty}({annotation})`"
+        )
+    )]
+    pub struct UnverifiedNullabilityWarning {
+        pub(crate) ty: String,
+        pub(crate) fields: String,
+        pub(crate) annotation: String,
     }
 
     impl Error {
@@ -484,11 +1835,26 @@ pub(crate) mod error {
         ) -> Self {
             let msg = format!("{err:#}");
             if let Some((position, msg, help)) = db_err(err) {
+                let query = &module_info.source[query_span.offset()..];
+                let offset = query_span.offset() + char_offset(query, position as usize - 1);
+                let (src, err_span) = snippet(
+                    &module_info.path.to_string_lossy(),
+                    &module_info.source,
+                    offset.into(),
+                );
                 Self::Db {
                     msg,
                     help,
+                    src,
+                    err_span: Some(err_span),
+                }
+            } else if let Some(db_err) = err.as_db_error() {
+                // Errors raised during execution have no position in the query
+                Self::Db {
+                    msg: db_err.message().to_string(),
+                    help: extension_hint(db_err).or_else(|| db_err.hint().map(String::from)),
                     src: module_info.into(),
-                    err_span: Some((query_span.offset() + position as usize - 1).into()),
+                    err_span: Some(query_name.span),
                 }
             } else {
                 Self::Db {
@@ -499,5 +1865,103 @@ pub(crate) mod error {
                 }
             }
         }
+
+        pub(crate) fn new_role_err(role: &str, err: &postgres::Error) -> Self {
+            Self::SetRole {
+                role: role.to_string(),
+                msg: err
+                    .as_db_error()
+                    .map_or_else(|| format!("{err:#}"), |it| it.message().to_string()),
+            }
+        }
+
+        pub(crate) fn new_prepare_err(
+            err: PrepareError,
+            module_info: &ModuleInfo,
+            query_span: &SourceSpan,
+            query_name: &Span<String>,
+        ) -> Self {
+            let msg = match err {
+                PrepareError::Db(err) => {
+                    return Self::new_db_err(&err, module_info, query_span, query_name)
+                }
+                PrepareError::Batch { offset, err } => {
+                    // Errors are located relatively to the statement of the batch
+                    let span = (query_span.offset() + offset, query_span.len() - offset).into();
+                    return Self::new_batch_err(&err, module_info, &span, query_name);
+                }
+                PrepareError::Replay(ReplayError::Missing) => {
+                    format!("the query `{}` was not recorded", query_name.value)
+                }
+                PrepareError::Replay(ReplayError::Stale) => format!(
+                    "the query `{}` changed since the snapshot was recorded",
+                    query_name.value
+                ),
+                PrepareError::Replay(ReplayError::UnknownType(oid)) => {
+                    format!("the type with OID {oid} was not recorded")
+                }
+                PrepareError::Replay(ReplayError::ServerVersion) => {
+                    "the server version selecting the variants of the query was not recorded"
+                        .to_string()
+                }
+            };
+            Self::Snapshot {
+                msg,
+                src: module_info.into(),
+                err_span: query_name.span,
+            }
+        }
+
+        pub(crate) fn new_unknown_schema(module_info: &ModuleInfo, schema: &Span<String>) -> Self {
+            Self::UnknownSchema {
+                schema: schema.value.clone(),
+                src: module_info.into(),
+                err_span: schema.span,
+            }
+        }
+
+        pub(crate) fn new_batch_err(
+            err: &postgres::Error,
+            module_info: &ModuleInfo,
+            statement_span: &SourceSpan,
+            query_name: &Span<String>,
+        ) -> Self {
+            match Self::new_db_err(err, module_info, statement_span, query_name) {
+                Self::Db {
+                    msg,
+                    help,
+                    src,
+                    err_span,
+                } => Self::Batch {
+                    msg,
+                    help,
+                    src,
+                    err_span,
+                },
+                _ => unreachable!(),
+            }
+        }
+
+        pub(crate) fn new_setup_err(
+            err: &postgres::Error,
+            module_info: &ModuleInfo,
+            query_span: &SourceSpan,
+            query_name: &Span<String>,
+        ) -> Self {
+            match Self::new_db_err(err, module_info, query_span, query_name) {
+                Self::Db {
+                    msg,
+                    help,
+                    src,
+                    err_span,
+                } => Self::Setup {
+                    msg,
+                    help,
+                    src,
+                    err_span,
+                },
+                _ => unreachable!(),
+            }
+        }
     }
 }
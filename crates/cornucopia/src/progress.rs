@@ -0,0 +1,120 @@
+use std::{fmt::Display, path::PathBuf, sync::Arc};
+
+use miette::{Diagnostic, GraphicalReportHandler, GraphicalTheme};
+
+/// Observes the progress of code generation, to report it in a GUI or a build script.
+///
+/// Closures taking a [`ProgressEvent`] are observers, and `()` ignores every event.
+pub trait ProgressObserver {
+    /// Called on each step of code generation, in order.
+    fn on_event(&mut self, event: ProgressEvent);
+}
+
+impl ProgressObserver for () {
+    fn on_event(&mut self, _: ProgressEvent) {}
+}
+
+impl<F: FnMut(ProgressEvent)> ProgressObserver for F {
+    fn on_event(&mut self, event: ProgressEvent) {
+        self(event);
+    }
+}
+
+/// Prints warnings and the slow startup of the container, and the other steps of code
+/// generation when `verbose`. The entry points taking no observer use a quiet one.
+pub(crate) struct PrintObserver {
+    pub(crate) verbose: bool,
+}
+
+impl ProgressObserver for PrintObserver {
+    fn on_event(&mut self, event: ProgressEvent) {
+        match event {
+            ProgressEvent::WarningEmitted(warning) => eprint!("{}", warning.report()),
+            ProgressEvent::ContainerSlow {
+                retries,
+                max_retries,
+            } => println!(
+                "Container startup slower than expected ({retries} retries out of {max_retries})"
+            ),
+            ProgressEvent::ContainerRetry {
+                attempt,
+                retries,
+                reason,
+            } => println!("Container setup failed, retrying ({attempt}/{retries}): {reason}"),
+            _ if !self.verbose => {}
+            ProgressEvent::ContainerStarting => eprintln!("Starting the container"),
+            ProgressEvent::SchemaLoaded(path) => {
+                eprintln!("Loaded schema `{}`", path.display());
+            }
+            ProgressEvent::ModulePrepared { name, queries } => {
+                eprintln!("Prepared module `{name}` ({queries} queries)");
+            }
+            ProgressEvent::ModuleCached { name, queries } => {
+                eprintln!("Prepared module `{name}` ({queries} queries) from the cache");
+            }
+            ProgressEvent::CodegenWritten { path, bytes } => {
+                eprintln!("Wrote {bytes} bytes to `{}`", path.display());
+            }
+        }
+    }
+}
+
+/// A step of code generation reported to a [`ProgressObserver`].
+#[derive(Debug, Clone)]
+#[non_exhaustive]
+pub enum ProgressEvent {
+    /// The container managed by Cornucopia is starting.
+    ContainerStarting,
+    /// The container is slower to start than expected, after `retries` health checks.
+    ContainerSlow { retries: u64, max_retries: u64 },
+    /// The setup of the container failed transiently and is retried, for the `attempt`-th
+    /// time out of `retries`.
+    ContainerRetry {
+        attempt: u32,
+        retries: u32,
+        reason: String,
+    },
+    /// A schema file was loaded into the database.
+    SchemaLoaded(PathBuf),
+    /// A query module was prepared against the database.
+    ModulePrepared { name: String, queries: usize },
+    /// A query module unchanged since the previous incremental generation was prepared
+    /// using the statements of its cache.
+    ModuleCached { name: String, queries: usize },
+    /// The generated code was written to `path`.
+    CodegenWritten { path: PathBuf, bytes: usize },
+    /// A warning was reported, generation going on.
+    WarningEmitted(Warning),
+}
+
+/// A warning reported while generating code.
+#[derive(Debug, Clone)]
+pub struct Warning(Arc<dyn Diagnostic + Send + Sync>);
+
+impl Warning {
+    pub(crate) fn new(diagnostic: impl Diagnostic + Send + Sync + 'static) -> Self {
+        Self(Arc::new(diagnostic))
+    }
+
+    /// The diagnostic of this warning, with its source code and labels.
+    #[must_use]
+    pub fn diagnostic(&self) -> &(dyn Diagnostic + Send + Sync) {
+        self.0.as_ref()
+    }
+
+    #[must_use]
+    pub fn report(&self) -> String {
+        let mut buff = String::new();
+        GraphicalReportHandler::new()
+            .with_theme(GraphicalTheme::unicode_nocolor())
+            .render_report(&mut buff, self.diagnostic())
+            .unwrap();
+        buff
+    }
+}
+
+impl Display for Warning {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        self.0.fmt(f)
+    }
+}
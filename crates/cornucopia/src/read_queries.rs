@@ -1,32 +1,78 @@
 use std::{
+    ops::Range,
     path::{Path, PathBuf},
     sync::Arc,
 };
 
 use miette::NamedSource;
 
+use crate::{parser::Span, utils::sanitize};
+
 use self::error::Error;
 
+/// Comment marking the raw string following it as queries embedded in a Rust file
+const EMBEDDED_MARKER: &str = "// cornucopia:query";
+
+/// Comment annotation declaring the schema the queries of a file target
+const SCHEMA_ANNOTATION: &str = "schema:";
+
+/// Comment annotation running the queries of a file in a single transaction
+const TRANSACTION_ANNOTATION: &str = "transaction";
+
 #[derive(Debug, Clone)]
 pub(crate) struct ModuleInfo {
     pub(crate) path: PathBuf,
     pub(crate) name: String,
+    /// Parsed queries
     pub(crate) content: Arc<String>,
+    /// Text errors are reported against, the whole Rust file for embedded queries
+    pub(crate) source: Arc<String>,
+    /// Schema declared by a `-- schema: name` annotation heading the queries, searched
+    /// first for the names used by the queries
+    pub(crate) schema: Option<Span<String>>,
+    /// Whether a `-- transaction` annotation heading the queries asks for a function
+    /// running them in a single transaction
+    pub(crate) transaction: bool,
+}
+
+impl ModuleInfo {
+    pub(crate) fn new(path: PathBuf, name: String, content: String) -> Self {
+        let content = Arc::new(content);
+        Self {
+            path,
+            name,
+            schema: schema_annotation(&content),
+            transaction: transaction_annotation(&content),
+            source: content.clone(),
+            content,
+        }
+    }
 }
 
 impl From<ModuleInfo> for NamedSource {
     fn from(m: ModuleInfo) -> Self {
-        Self::new(m.path.to_string_lossy(), m.content)
+        Self::from(&m)
     }
 }
 
 impl From<&ModuleInfo> for NamedSource {
     fn from(m: &ModuleInfo) -> Self {
-        Self::new(m.path.to_string_lossy(), m.content.clone())
+        Self::new(m.path.to_string_lossy(), sanitize(&m.source))
     }
 }
 
-/// Reads queries in the directory. Only .sql files are considered.
+/// Reads queries in the directory. Queries are read from .sql files, and from the raw
+/// strings of .rs files following a `// cornucopia:query` comment:
+///
+/// ```ignore
+/// // cornucopia:query
+/// const AUTHORS: &str = r#"
+/// --! authors
+/// SELECT * FROM author;
+/// "#;
+/// ```
+///
+/// Rust files without such queries are ignored. Modules are named after their file.
 ///
 /// # Error
 /// Returns an error if `dir_path` does not point to a valid directory or if a query file cannot be parsed.
@@ -34,37 +80,45 @@ pub(crate) fn read_query_modules(dir_path: &Path) -> Result<Vec<ModuleInfo>, Err
     let mut modules_info = Vec::new();
     for entry_result in std::fs::read_dir(dir_path).map_err(|err| Error {
         err,
+        action: "read queries directory",
         path: dir_path.to_owned(),
     })? {
         // Directory entry
         let entry = entry_result.map_err(|err| Error {
             err,
+            action: "read queries directory",
             path: dir_path.to_owned(),
         })?;
         let path_buf = entry.path();
 
-        // Check we're dealing with a .sql file
-        if path_buf
-            .extension()
-            .map(|extension| extension == "sql")
-            .unwrap_or_default()
-        {
-            let module_name = path_buf
-                .file_stem()
-                .expect("is a file")
-                .to_str()
-                .expect("file name is valid utf8")
-                .to_string();
-
-            let file_contents = std::fs::read_to_string(&path_buf).map_err(|err| Error {
-                err,
-                path: dir_path.to_owned(),
-            })?;
+        // Check we're dealing with a .sql or .rs file
+        let extension = path_buf.extension().and_then(|it| it.to_str());
+        if !matches!(extension, Some("sql" | "rs")) {
+            continue;
+        }
+        let module_name = path_buf
+            .file_stem()
+            .expect("is a file")
+            .to_str()
+            .expect("file name is valid utf8")
+            .to_string();
+
+        let file_contents = std::fs::read_to_string(&path_buf).map_err(|err| Error {
+            err,
+            action: "read queries file",
+            path: path_buf.clone(),
+        })?;
 
+        if extension == Some("sql") {
+            modules_info.push(ModuleInfo::new(path_buf, module_name, file_contents));
+        } else if let Some(queries) = embedded_queries(&file_contents) {
             modules_info.push(ModuleInfo {
                 path: path_buf,
                 name: module_name,
-                content: Arc::new(file_contents),
+                schema: schema_annotation(&queries),
+                transaction: transaction_annotation(&queries),
+                content: Arc::new(queries),
+                source: Arc::new(file_contents),
             });
         }
     }
@@ -73,6 +127,104 @@ pub(crate) fn read_query_modules(dir_path: &Path) -> Result<Vec<ModuleInfo>, Err
     Ok(modules_info)
 }
 
+/// Queries embedded in a Rust file, in the raw strings following a `// cornucopia:query`
+/// comment. The rest of the file is blanked out, so that queries keep their position.
+fn embedded_queries(rust: &str) -> Option<String> {
+    let mut queries: String = rust
+        .bytes()
+        .map(|b| if b == b'\n' { '\n' } else { ' ' })
+        .collect();
+    let mut found = false;
+    let mut idx = 0;
+    while let Some(marker) = rust[idx..].find(EMBEDDED_MARKER) {
+        let after = idx + marker + EMBEDDED_MARKER.len();
+        let Some(body) = raw_string_body(&rust[after..]) else {
+            break;
+        };
+        let body = after + body.start..after + body.end;
+        queries.replace_range(body.clone(), &rust[body.clone()]);
+        found = true;
+        idx = body.end;
+    }
+    found.then_some(queries)
+}
+
+/// Comments heading the queries, before their first annotation, trimmed and without their
+/// `--`, each with the offset of its end
+fn header_comments(content: &str) -> Vec<(usize, &str)> {
+    let mut comments = Vec::new();
+    let mut offset = 0;
+    for line in content.split_inclusive('\n') {
+        let trimmed = line.trim();
+        if trimmed.starts_with("--!") || trimmed.starts_with("--:") {
+            break;
+        }
+        if let Some(comment) = trimmed.strip_prefix("--") {
+            comments.push((offset + line.trim_end().len(), comment.trim()));
+        } else if !trimmed.is_empty() {
+            break;
+        }
+        offset += line.len();
+    }
+    comments
+}
+
+/// Schema of the `-- schema: name` annotation among the comments heading the queries:
+///
+/// ```sql
+/// -- schema: analytics
+///
+/// --! visits
+/// SELECT * FROM visit;
+/// ```
+fn schema_annotation(content: &str) -> Option<Span<String>> {
+    header_comments(content)
+        .into_iter()
+        .find_map(|(end, comment)| {
+            let schema = comment.strip_prefix(SCHEMA_ANNOTATION)?.trim();
+            (!schema.is_empty()).then(|| Span {
+                value: schema.to_string(),
+                span: (end - schema.len()..end).into(),
+            })
+        })
+}
+
+/// Whether a `-- transaction` annotation is among the comments heading the queries:
+///
+/// ```sql
+/// -- transaction
+///
+/// --! debit
+/// UPDATE account SET balance = balance - :amount WHERE id = :from;
+/// --! credit
+/// UPDATE account SET balance = balance + :amount WHERE id = :to;
+/// ```
+fn transaction_annotation(content: &str) -> bool {
+    header_comments(content)
+        .into_iter()
+        .any(|(_, comment)| comment == TRANSACTION_ANNOTATION)
+}
+
+/// Range of the body of the first raw string literal (`r"..."`, `r#"..."#`, ...) of `s`
+fn raw_string_body(s: &str) -> Option<Range<usize>> {
+    let mut idx = 0;
+    loop {
+        let r = idx + s[idx..].find('r')?;
+        idx = r + 1;
+        if s[..r].ends_with(|c: char| c.is_alphanumeric() || c == '_') {
+            continue;
+        }
+        let hashes = s[idx..].len() - s[idx..].trim_start_matches('#').len();
+        if !s[idx + hashes..].starts_with('"') {
+            continue;
+        }
+        let start = idx + hashes + 1;
+        let closing = format!("\"{}", "#".repeat(hashes));
+        let end = start + s[start..].find(&closing)?;
+        return Some(start..end);
+    }
+}
+
 pub(crate) mod error {
     use std::path::PathBuf;
 
@@ -80,9 +232,10 @@ pub(crate) mod error {
     use thiserror::Error as ThisError;
 
     #[derive(Debug, ThisError, Diagnostic)]
-    #[error("[{path}] : {err:#}")]
+    #[error("Could not {action} `{path}`: ({err})")]
     pub struct Error {
         pub(crate) err: std::io::Error,
+        pub(crate) action: &'static str,
         pub(crate) path: PathBuf,
     }
 }
@@ -0,0 +1,1350 @@
+use std::{
+    ops::BitOr,
+    path::{Path, PathBuf},
+    str::FromStr,
+};
+
+use crate::{container::DEFAULT_IMAGE, nullability::ColumnNullability, parser::Query};
+
+/// Struct containing the settings for code generation.
+///
+/// This struct is non-exhaustive so that new settings can be added without
+/// breaking your code: build it using [`CodegenSettings::builder`] or start
+/// from its [`Default`] value.
+///
+/// ```
+/// use cornucopia::{CodegenSettings, TargetSet};
+///
+/// let settings = CodegenSettings::builder()
+///     .async_(true)
+///     .serialize(TargetSet::ROWS)
+///     .build();
+/// assert!(settings.gen_async() && settings.serialize().rows());
+/// ```
+#[derive(Debug, Clone, PartialEq, Eq)]
+#[non_exhaustive]
+pub struct CodegenSettings {
+    pub(crate) gen_async: bool,
+    pub(crate) gen_sync: bool,
+    pub(crate) serialize: TargetSet,
+    pub(crate) deserialize: TargetSet,
+    pub(crate) generate_read_only_flag: bool,
+    pub(crate) naive_timestamp_policy: NaiveTimestampPolicy,
+    pub(crate) date_time_crate: DateTimeCrate,
+    pub(crate) numeric_crate: NumericCrate,
+    pub(crate) prepared_statements: bool,
+    pub(crate) create_destination_dirs: bool,
+    pub(crate) normalize_sql: bool,
+    pub(crate) all_variants: bool,
+    pub(crate) strict_nullability: bool,
+    pub(crate) infer_nullability: bool,
+    pub(crate) merge_nullability: bool,
+    pub(crate) nullability_file: Option<PathBuf>,
+    /// Columns of the nullability file, read when the queries are prepared
+    pub(crate) column_nullability: ColumnNullability,
+    pub(crate) deprecation_marker: String,
+    pub(crate) deprecated_fields: bool,
+    pub(crate) keep_going: bool,
+    pub(crate) gen_row_conversions: bool,
+    pub(crate) gen_params_builders: bool,
+    pub(crate) gen_param_conversions: bool,
+    pub(crate) gen_two_phase_commit: bool,
+    pub(crate) gen_enum_guards: bool,
+    pub(crate) gen_display: bool,
+    pub(crate) types_path: Option<String>,
+    pub(crate) error_type: Option<String>,
+    pub(crate) newtypes: Vec<Newtype>,
+    pub(crate) roles: Vec<String>,
+    pub(crate) redact: Vec<String>,
+    pub(crate) only: Vec<String>,
+    pub(crate) only_tags: Vec<String>,
+    pub(crate) renames: Vec<Rename>,
+    pub(crate) benches: Option<PathBuf>,
+    pub(crate) migrations: Option<PathBuf>,
+    pub(crate) incremental: bool,
+    pub(crate) setup_retries: u32,
+    pub(crate) container_image: String,
+    pub(crate) application_name: Option<String>,
+}
+
+impl Default for CodegenSettings {
+    /// Generates async code only, without serde derives nor read-only flags,
+    /// allowing naive timestamps and using prepared statements. Missing parent directories
+    /// of the destination file are created. Queries are generated as written, along with
+    /// their custom types. Columns commented `DEPRECATED` are reported and their fields
+    /// marked `#[deprecated]`. The setup of a managed container is retried twice.
+    fn default() -> Self {
+        Self {
+            gen_async: true,
+            gen_sync: false,
+            serialize: TargetSet::NONE,
+            deserialize: TargetSet::NONE,
+            generate_read_only_flag: false,
+            naive_timestamp_policy: NaiveTimestampPolicy::Allow,
+            date_time_crate: DateTimeCrate::Time,
+            numeric_crate: NumericCrate::RustDecimal,
+            prepared_statements: true,
+            create_destination_dirs: true,
+            normalize_sql: false,
+            all_variants: false,
+            strict_nullability: false,
+            infer_nullability: false,
+            merge_nullability: false,
+            nullability_file: None,
+            column_nullability: ColumnNullability::default(),
+            deprecation_marker: "DEPRECATED".to_string(),
+            deprecated_fields: true,
+            keep_going: false,
+            gen_row_conversions: false,
+            gen_params_builders: false,
+            gen_param_conversions: false,
+            gen_two_phase_commit: false,
+            gen_enum_guards: false,
+            gen_display: false,
+            types_path: None,
+            error_type: None,
+            newtypes: Vec::new(),
+            roles: Vec::new(),
+            redact: Vec::new(),
+            only: Vec::new(),
+            only_tags: Vec::new(),
+            renames: Vec::new(),
+            benches: None,
+            migrations: None,
+            incremental: false,
+            setup_retries: 2,
+            container_image: DEFAULT_IMAGE.to_string(),
+            application_name: None,
+        }
+    }
+}
+
+/// Kinds of generated structs a serde trait is derived on, combined using `|`.
+///
+/// Rows are the structs returned by queries, params the structs of query parameters
+/// and of composites passed as parameters, and types the custom types (enums,
+/// composites and newtypes). Rows and params containing custom types need those
+/// types to implement the trait too. Parameters of composites with array or JSON
+/// fields borrow values that cannot be deserialized, so their structs don't derive
+/// `Deserialize`.
+///
+/// ```
+/// use cornucopia::TargetSet;
+///
+/// let targets: TargetSet = "rows,params".parse().unwrap();
+/// assert_eq!(targets, TargetSet::ROWS | TargetSet::PARAMS);
+/// assert!(!targets.types());
+/// ```
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub struct TargetSet {
+    rows: bool,
+    params: bool,
+    types: bool,
+}
+
+impl TargetSet {
+    /// No struct.
+    pub const NONE: Self = Self {
+        rows: false,
+        params: false,
+        types: false,
+    };
+    /// Rows returned by queries.
+    pub const ROWS: Self = Self {
+        rows: true,
+        ..Self::NONE
+    };
+    /// Parameters of queries and composites.
+    pub const PARAMS: Self = Self {
+        params: true,
+        ..Self::NONE
+    };
+    /// Custom types.
+    pub const TYPES: Self = Self {
+        types: true,
+        ..Self::NONE
+    };
+    /// Rows, params and custom types.
+    pub const ALL: Self = Self {
+        rows: true,
+        params: true,
+        types: true,
+    };
+
+    /// Whether rows are included.
+    pub fn rows(&self) -> bool {
+        self.rows
+    }
+
+    /// Whether params are included.
+    pub fn params(&self) -> bool {
+        self.params
+    }
+
+    /// Whether custom types are included.
+    pub fn types(&self) -> bool {
+        self.types
+    }
+
+    /// Whether no struct is included.
+    pub fn is_empty(&self) -> bool {
+        *self == Self::NONE
+    }
+}
+
+impl BitOr for TargetSet {
+    type Output = Self;
+
+    fn bitor(self, rhs: Self) -> Self {
+        Self {
+            rows: self.rows || rhs.rows,
+            params: self.params || rhs.params,
+            types: self.types || rhs.types,
+        }
+    }
+}
+
+impl FromStr for TargetSet {
+    type Err = String;
+
+    /// Parses a comma separated list of `rows`, `params`, `types` or `all`.
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        s.split(',')
+            .map(str::trim)
+            .filter(|it| !it.is_empty())
+            .try_fold(Self::NONE, |set, target| {
+                Ok(set
+                    | match target {
+                        "rows" => Self::ROWS,
+                        "params" => Self::PARAMS,
+                        "types" => Self::TYPES,
+                        "all" => Self::ALL,
+                        _ => {
+                            return Err(format!(
+                        "unknown target `{target}`, expected `rows`, `params`, `types` or `all`"
+                    ))
+                        }
+                    })
+            })
+    }
+}
+
+/// A table column or domain whose values are wrapped in a newtype,
+/// declared using [`CodegenSettingsBuilder::newtype`].
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub(crate) struct Newtype {
+    /// `table.column` or domain name
+    pub(crate) target: String,
+    /// Name of the generated newtype, or path to a type provided by the user
+    pub(crate) path: String,
+}
+
+/// What a newtype is mapped to
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub(crate) enum NewtypeTarget<'a> {
+    Column { table: &'a str, column: &'a str },
+    Domain(&'a str),
+}
+
+impl Newtype {
+    pub(crate) fn target(&self) -> NewtypeTarget<'_> {
+        match self.target.split_once('.') {
+            Some((table, column)) => NewtypeTarget::Column { table, column },
+            None => NewtypeTarget::Domain(&self.target),
+        }
+    }
+
+    /// Newtypes given as a path (`crate::ids::UserId`) are provided by the user,
+    /// others are generated
+    pub(crate) fn is_generated(&self) -> bool {
+        !self.path.contains("::")
+    }
+
+    /// Name used to refer to this newtype in bind parameters annotations
+    pub(crate) fn name(&self) -> &str {
+        self.path.rsplit("::").next().unwrap_or_default()
+    }
+}
+
+/// Kind of generated identifiers renamed using [`CodegenSettingsBuilder::rename_prefix`],
+/// [`CodegenSettingsBuilder::rename_suffix`], [`CodegenSettingsBuilder::rename_prefix_map`]
+/// or [`CodegenSettingsBuilder::rename`].
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum RenameTarget {
+    /// Modules of the queries, named after their file.
+    Modules,
+    /// Functions of the queries, along with their statement struct.
+    Functions,
+    /// Explicitly named row structs, and row structs named after their query.
+    Rows,
+    /// Explicitly named params structs, and params structs named after their query.
+    Params,
+}
+
+/// A rename of generated identifiers
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub(crate) enum Rename {
+    Prefix(RenameTarget, String),
+    Suffix(RenameTarget, String),
+    /// Replaces the prefix `from` of a name by `to`
+    PrefixMap {
+        target: RenameTarget,
+        from: String,
+        to: String,
+    },
+    /// Renames an identifier of a module, taking precedence over the other renames
+    Exact {
+        module: String,
+        target: RenameTarget,
+        from: String,
+        to: String,
+    },
+}
+
+/// How returned columns of type `timestamp` (without time zone) are handled.
+///
+/// Those columns map to naive datetimes which are easily mistaken for UTC ones.
+/// Individual columns can be accepted by any policy using the `allow_naive` query
+/// option: `-- :allow_naive created_at`.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default, clap::ValueEnum)]
+pub enum NaiveTimestampPolicy {
+    /// Naive timestamps are accepted.
+    #[default]
+    Allow,
+    /// A warning is printed for each naive timestamp column.
+    Warn,
+    /// Naive timestamp columns are reported as errors.
+    Deny,
+}
+
+/// Crate of the Rust types of the `timestamp`, `timestamptz`, `date` and `time` columns and
+/// parameters, whose `postgres` feature must be enabled.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default, clap::ValueEnum)]
+pub enum DateTimeCrate {
+    /// `time::PrimitiveDateTime`, `time::OffsetDateTime`, `time::Date` and `time::Time`,
+    /// using the `with-time-0_3` feature.
+    #[default]
+    Time,
+    /// `chrono::NaiveDateTime`, `chrono::DateTime<chrono::Utc>`, `chrono::NaiveDate` and
+    /// `chrono::NaiveTime`, using the `with-chrono-0_4` feature.
+    Chrono,
+}
+
+/// Crate of the Rust type of the `numeric` columns and parameters.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default, clap::ValueEnum)]
+pub enum NumericCrate {
+    /// `rust_decimal::Decimal`, using its `db-postgres` feature. Its 96-bit mantissa holds
+    /// 28 significant digits.
+    #[default]
+    RustDecimal,
+    /// `bigdecimal::BigDecimal` of arbitrary precision, using the `with-bigdecimal-0_4`
+    /// feature of the client crate.
+    BigDecimal,
+}
+
+impl CodegenSettings {
+    /// Creates a builder starting from the default settings.
+    pub fn builder() -> CodegenSettingsBuilder {
+        CodegenSettingsBuilder::default()
+    }
+
+    /// Whether async code is generated.
+    pub fn gen_async(&self) -> bool {
+        self.gen_async
+    }
+
+    /// Whether sync code is generated.
+    pub fn gen_sync(&self) -> bool {
+        self.gen_sync
+    }
+
+    /// Structs deriving `serde::Serialize`.
+    pub fn serialize(&self) -> TargetSet {
+        self.serialize
+    }
+
+    /// Structs deriving `serde::Deserialize`.
+    pub fn deserialize(&self) -> TargetSet {
+        self.deserialize
+    }
+
+    /// Whether `serde::Serialize` is derived on some structs.
+    #[deprecated(note = "use `serialize` instead")]
+    pub fn derive_ser(&self) -> bool {
+        !self.serialize.is_empty()
+    }
+
+    /// Whether statements expose an `IS_READ_ONLY` constant.
+    pub fn generate_read_only_flag(&self) -> bool {
+        self.generate_read_only_flag
+    }
+
+    /// How returned columns of type `timestamp` are handled.
+    pub fn naive_timestamp_policy(&self) -> NaiveTimestampPolicy {
+        self.naive_timestamp_policy
+    }
+
+    /// Crate of the Rust types of the date and time columns and parameters.
+    pub fn date_time_crate(&self) -> DateTimeCrate {
+        self.date_time_crate
+    }
+
+    /// Crate of the Rust type of the `numeric` columns and parameters.
+    pub fn numeric_crate(&self) -> NumericCrate {
+        self.numeric_crate
+    }
+
+    /// Whether queries are executed using cached prepared statements by default.
+    pub fn prepared_statements(&self) -> bool {
+        self.prepared_statements
+    }
+
+    /// Whether missing parent directories of the destination file are created.
+    pub fn create_destination_dirs(&self) -> bool {
+        self.create_destination_dirs
+    }
+
+    /// Whether comments and redundant whitespace are removed from the generated queries.
+    pub fn normalize_sql(&self) -> bool {
+        self.normalize_sql
+    }
+
+    /// Whether every variant of the queries declared for different server versions is
+    /// generated, the matching one being selected at runtime.
+    pub fn all_variants(&self) -> bool {
+        self.all_variants
+    }
+
+    /// Whether composite fields of unverified nullability are reported as errors rather
+    /// than warnings.
+    pub fn strict_nullability(&self) -> bool {
+        self.strict_nullability
+    }
+
+    /// Whether the nullability of the undeclared columns is inferred from the catalog.
+    pub fn infer_nullability(&self) -> bool {
+        self.infer_nullability
+    }
+
+    /// Whether the uses of a named type differing by the nullability of their fields are
+    /// merged rather than reported as errors.
+    pub fn merge_nullability(&self) -> bool {
+        self.merge_nullability
+    }
+
+    /// TOML file declaring the nullability of table and view columns, if any.
+    pub fn nullability_file(&self) -> Option<&Path> {
+        self.nullability_file.as_deref()
+    }
+
+    /// Prefix of the comments marking the columns as deprecated, empty when they are not
+    /// checked.
+    pub fn deprecation_marker(&self) -> &str {
+        &self.deprecation_marker
+    }
+
+    /// Whether the row fields read from deprecated columns are marked `#[deprecated]`.
+    pub fn deprecated_fields(&self) -> bool {
+        self.deprecated_fields
+    }
+
+    /// Whether generation continues past the query modules that fail, to report all their
+    /// errors at once.
+    pub fn keep_going(&self) -> bool {
+        self.keep_going
+    }
+
+    /// Whether `From` conversions are generated between the rows of a module sharing
+    /// a subset of their fields.
+    pub fn gen_row_conversions(&self) -> bool {
+        self.gen_row_conversions
+    }
+
+    /// Whether a builder is generated along with each explicitly named params struct.
+    pub fn gen_params_builders(&self) -> bool {
+        self.gen_params_builders
+    }
+
+    /// Whether `From` conversions are generated from the rows of a module to its params
+    /// structs sharing a subset of their fields.
+    pub fn gen_param_conversions(&self) -> bool {
+        self.gen_param_conversions
+    }
+
+    /// Whether the helpers of two-phase commits are generated in a `two_phase` module.
+    pub fn gen_two_phase_commit(&self) -> bool {
+        self.gen_two_phase_commit
+    }
+
+    /// Whether enums are generated with their `LABEL_COUNT` and `verify` function.
+    pub fn gen_enum_guards(&self) -> bool {
+        self.gen_enum_guards
+    }
+
+    /// Whether the row structs implement `Display`.
+    pub fn gen_display(&self) -> bool {
+        self.gen_display
+    }
+
+    /// Path of the shared custom types referenced by the generated queries, if they
+    /// are not generated along with them.
+    pub fn types_path(&self) -> Option<&str> {
+        self.types_path.as_deref()
+    }
+
+    /// Path of the error type returned by the generated queries, if it is not the one
+    /// of the client.
+    pub fn error_type(&self) -> Option<&str> {
+        self.error_type.as_deref()
+    }
+
+    /// Roles each query is prepared as, the current role being used if empty.
+    pub fn roles(&self) -> &[String] {
+        &self.roles
+    }
+
+    /// Table columns and domains wrapped in newtypes, with their newtype.
+    pub fn newtypes(&self) -> impl Iterator<Item = (&str, &str)> {
+        self.newtypes
+            .iter()
+            .map(|it| (it.target.as_str(), it.path.as_str()))
+    }
+
+    /// Patterns of the fields whose value is hidden from `Debug` output.
+    pub fn redact(&self) -> impl Iterator<Item = &str> {
+        self.redact.iter().map(String::as_str)
+    }
+
+    /// Patterns of the `module::query` names of the generated queries, every query being
+    /// generated if empty.
+    pub fn only(&self) -> impl Iterator<Item = &str> {
+        self.only.iter().map(String::as_str)
+    }
+
+    /// `key=value` tags of the generated queries, along with those matching
+    /// [`only`](Self::only).
+    pub fn only_tags(&self) -> impl Iterator<Item = &str> {
+        self.only_tags.iter().map(String::as_str)
+    }
+
+    /// Name of a generated identifier of `module` once renamed, which is `name` when no
+    /// rename applies. Modules are renamed given their own name as `module`.
+    ///
+    /// An exact rename takes precedence over the others. Otherwise the longest mapped
+    /// prefix of the name is replaced, then prefixes and suffixes are added.
+    pub fn renamed(&self, module: &str, target: RenameTarget, name: &str) -> String {
+        let exact = self.renames.iter().find_map(|rename| match rename {
+            Rename::Exact {
+                module: it,
+                target: it_target,
+                from,
+                to,
+            } if it == module && *it_target == target && from == name => Some(to.clone()),
+            _ => None,
+        });
+        if let Some(renamed) = exact {
+            return renamed;
+        }
+        let mapped = self
+            .renames
+            .iter()
+            .filter_map(|rename| match rename {
+                Rename::PrefixMap {
+                    target: it_target,
+                    from,
+                    to,
+                } if *it_target == target => name
+                    .strip_prefix(from.as_str())
+                    .map(|rest| (from.len(), format!("{to}{rest}"))),
+                _ => None,
+            })
+            .max_by_key(|(len, _)| *len)
+            .map_or_else(|| name.to_string(), |(_, it)| it);
+        let mut renamed = String::new();
+        for rename in &self.renames {
+            match rename {
+                Rename::Prefix(it, prefix) if *it == target => renamed.push_str(prefix),
+                _ => {}
+            }
+        }
+        renamed.push_str(&mapped);
+        for rename in &self.renames {
+            match rename {
+                Rename::Suffix(it, suffix) if *it == target => renamed.push_str(suffix),
+                _ => {}
+            }
+        }
+        renamed
+    }
+
+    /// Number of times the setup of a container managed by Cornucopia is retried after
+    /// transient failures.
+    pub fn setup_retries(&self) -> u32 {
+        self.setup_retries
+    }
+
+    /// Image of the container managed by Cornucopia.
+    pub fn container_image(&self) -> &str {
+        &self.container_image
+    }
+
+    /// `application_name` reported by the connections to the container managed by
+    /// Cornucopia and by the CLI, if set.
+    pub fn application_name(&self) -> Option<&str> {
+        self.application_name.as_deref()
+    }
+
+    /// Directory of the criterion benches generated along with the queries, if any.
+    pub fn emit_benches(&self) -> Option<&Path> {
+        self.benches.as_deref()
+    }
+
+    /// Directory of the migration files embedded in the generated code, if any.
+    pub fn embed_migrations(&self) -> Option<&Path> {
+        self.migrations.as_deref()
+    }
+
+    /// Whether the statements of the query modules are cached next to the destination
+    /// file, to only prepare the modules that changed since the previous generation.
+    pub fn incremental(&self) -> bool {
+        self.incremental
+    }
+
+    /// Whether the field with this database name is hidden from `Debug` output
+    pub(crate) fn is_redacted(&self, name: &str) -> bool {
+        self.redact
+            .iter()
+            .any(|pattern| matches_pattern(pattern, name))
+    }
+
+    /// Whether the query of this module is generated
+    pub(crate) fn is_selected(&self, module: &str, query: &Query) -> bool {
+        (self.only.is_empty() && self.only_tags.is_empty())
+            || self
+                .only
+                .iter()
+                .any(|pattern| matches_only(pattern, module, &query.name.value))
+            || query
+                .tags()
+                .any(|(key, value)| self.only_tags.contains(&format!("{key}={value}")))
+    }
+}
+
+/// Matches the name of a query against a `module::query` pattern
+pub(crate) fn matches_only(pattern: &str, module: &str, query: &str) -> bool {
+    pattern.split_once("::").is_some_and(|(modules, queries)| {
+        matches_pattern(modules, module) && matches_pattern(queries, query)
+    })
+}
+
+/// Matches a name against a pattern in which `*` matches any sequence of characters
+fn matches_pattern(pattern: &str, name: &str) -> bool {
+    let mut parts = pattern.split('*');
+    let Some(mut rest) = name.strip_prefix(parts.next().unwrap_or_default()) else {
+        return false;
+    };
+    let parts: Vec<_> = parts.collect();
+    let Some((last, middle)) = parts.split_last() else {
+        // No wildcard
+        return rest.is_empty();
+    };
+    for part in middle {
+        match rest.find(part) {
+            Some(idx) => rest = &rest[idx + part.len()..],
+            None => return false,
+        }
+    }
+    rest.ends_with(last)
+}
+
+/// Builder for [`CodegenSettings`].
+#[derive(Debug, Clone, Default)]
+#[must_use]
+pub struct CodegenSettingsBuilder {
+    settings: CodegenSettings,
+}
+
+impl CodegenSettingsBuilder {
+    /// Generates async code.
+    pub fn async_(mut self, gen_async: bool) -> Self {
+        self.settings.gen_async = gen_async;
+        self
+    }
+
+    /// Generates sync code.
+    pub fn sync(mut self, gen_sync: bool) -> Self {
+        self.settings.gen_sync = gen_sync;
+        self
+    }
+
+    /// Derives `serde::Serialize` on these structs.
+    pub fn serialize(mut self, targets: TargetSet) -> Self {
+        self.settings.serialize = targets;
+        self
+    }
+
+    /// Derives `serde::Deserialize` on these structs. Fields of params borrowing
+    /// composites are marked `#[serde(borrow)]`.
+    ///
+    /// ```
+    /// use cornucopia::{CodegenSettings, TargetSet};
+    ///
+    /// // Params arrive as JSON request bodies, rows are sent back as JSON
+    /// let settings = CodegenSettings::builder()
+    ///     .serialize(TargetSet::ROWS | TargetSet::TYPES)
+    ///     .deserialize(TargetSet::PARAMS | TargetSet::TYPES)
+    ///     .build();
+    /// assert!(settings.deserialize().params() && !settings.deserialize().rows());
+    /// ```
+    pub fn deserialize(mut self, targets: TargetSet) -> Self {
+        self.settings.deserialize = targets;
+        self
+    }
+
+    /// Derives `serde::Serialize` on rows and custom types.
+    #[deprecated(note = "use `serialize(TargetSet::ROWS | TargetSet::TYPES)` instead")]
+    pub fn derive_ser(self, derive_ser: bool) -> Self {
+        self.serialize(if derive_ser {
+            TargetSet::ROWS | TargetSet::TYPES
+        } else {
+            TargetSet::NONE
+        })
+    }
+
+    /// Generates an `IS_READ_ONLY` constant on each statement, telling whether the
+    /// query is known not to write to the database (see [`analyze`](crate::analyze)).
+    ///
+    /// With a primary and a read replica, this lets you route each statement to the
+    /// right client:
+    ///
+    /// ```ignore
+    /// use cornucopia_async::GenericClient;
+    ///
+    /// fn route<'a, C: GenericClient>(read_only: bool, primary: &'a C, replica: &'a C) -> &'a C {
+    ///     if read_only { replica } else { primary }
+    /// }
+    ///
+    /// let client = route(AuthorsStmt::IS_READ_ONLY, &primary, &replica);
+    /// let authors = authors().bind(client).all().await?;
+    /// ```
+    pub fn generate_read_only_flag(mut self, generate_read_only_flag: bool) -> Self {
+        self.settings.generate_read_only_flag = generate_read_only_flag;
+        self
+    }
+
+    /// Sets how returned columns of type `timestamp` are handled.
+    pub fn naive_timestamp_policy(mut self, naive_timestamp_policy: NaiveTimestampPolicy) -> Self {
+        self.settings.naive_timestamp_policy = naive_timestamp_policy;
+        self
+    }
+
+    /// Sets the crate of the Rust types of the `timestamp`, `timestamptz`, `date` and
+    /// `time` columns and parameters, `time` by default. The generated code needs the
+    /// matching feature of `postgres` or `tokio-postgres`: `with-time-0_3` or
+    /// `with-chrono-0_4`.
+    ///
+    /// ```
+    /// use cornucopia::{CodegenSettings, DateTimeCrate};
+    ///
+    /// let settings = CodegenSettings::builder()
+    ///     .date_time_crate(DateTimeCrate::Chrono)
+    ///     .build();
+    /// assert_eq!(settings.date_time_crate(), DateTimeCrate::Chrono);
+    /// ```
+    pub fn date_time_crate(mut self, date_time_crate: DateTimeCrate) -> Self {
+        self.settings.date_time_crate = date_time_crate;
+        self
+    }
+
+    /// Sets the crate of the Rust type of the `numeric` columns and parameters,
+    /// `rust_decimal` by default. The generated code needs either the `db-postgres`
+    /// feature of `rust_decimal`, or the `with-bigdecimal-0_4` feature of the client crate,
+    /// `bigdecimal::BigDecimal` implementing neither `FromSql` nor `ToSql`.
+    ///
+    /// ```
+    /// use cornucopia::{CodegenSettings, NumericCrate};
+    ///
+    /// let settings = CodegenSettings::builder()
+    ///     .numeric_crate(NumericCrate::BigDecimal)
+    ///     .build();
+    /// assert_eq!(settings.numeric_crate(), NumericCrate::BigDecimal);
+    /// ```
+    pub fn numeric_crate(mut self, numeric_crate: NumericCrate) -> Self {
+        self.settings.numeric_crate = numeric_crate;
+        self
+    }
+
+    /// Executes queries using prepared statements cached by each statement struct
+    /// (the default), or without any named prepared statement when `false`.
+    ///
+    /// Named prepared statements are not supported by connection poolers in transaction
+    /// mode, like pgbouncer. Queries that are not prepared send their text along with
+    /// their parameters types on each execution, and are planned again by the server
+    /// every time. Their parameters cannot use custom types, whose OIDs are only known
+    /// to the database. Individual queries can override this setting using the
+    /// `prepared` query option: `-- :prepared true`.
+    pub fn prepared_statements(mut self, prepared_statements: bool) -> Self {
+        self.settings.prepared_statements = prepared_statements;
+        self
+    }
+
+    /// Creates the missing parent directories of the destination file before writing
+    /// the generated code (the default). When `false`, a missing directory is reported
+    /// as an error instead.
+    pub fn create_destination_dirs(mut self, create_destination_dirs: bool) -> Self {
+        self.settings.create_destination_dirs = create_destination_dirs;
+        self
+    }
+
+    /// Removes comments and collapses whitespace in the generated queries (see
+    /// [`normalize_sql`](crate::normalize_sql)), so that reformatting a query file doesn't
+    /// change the generated code. Errors still point into the queries as written.
+    pub fn normalize_sql(mut self, normalize_sql: bool) -> Self {
+        self.settings.normalize_sql = normalize_sql;
+        self
+    }
+
+    /// Generates every variant of the queries declared for different server versions
+    /// (`--! sync_users @pg>=15`), the one matching the server being selected on first
+    /// execution. By default, only the variant matching the server queries are prepared
+    /// against is generated.
+    ///
+    /// Only the selected variant is prepared, so the other variants are expected to return
+    /// the same columns.
+    ///
+    /// ```
+    /// # use cornucopia::CodegenSettings;
+    /// let settings = CodegenSettings::builder().all_variants(true).build();
+    /// assert!(settings.all_variants());
+    /// ```
+    pub fn all_variants(mut self, all_variants: bool) -> Self {
+        self.settings.all_variants = all_variants;
+        self
+    }
+
+    /// Reports composite fields whose nullability is neither declared in a type
+    /// annotation (`--: address(street, city?)`) nor guaranteed by a `NOT NULL` domain
+    /// as errors. By default, such fields are generated as non-nullable with a warning.
+    pub fn strict_nullability(mut self, strict_nullability: bool) -> Self {
+        self.settings.strict_nullability = strict_nullability;
+        self
+    }
+
+    /// Infers the nullability of the columns missing from the row declaration of a query
+    /// from the catalog: only plain table columns declared `NOT NULL`, and not read through
+    /// an outer join, are non-nullable, while expressions and view columns are nullable.
+    /// Declared columns keep their nullability either way, `(name)` being non-nullable and
+    /// `(name?)` nullable. By default, undeclared columns are non-nullable.
+    pub fn infer_nullability(mut self, infer_nullability: bool) -> Self {
+        self.settings.infer_nullability = infer_nullability;
+        self
+    }
+
+    /// Merges the uses of a named row or params struct whose fields only differ by their
+    /// nullability, a field nullable in one of them being nullable in the generated struct,
+    /// with a warning. By default, such uses are reported as conflicting.
+    ///
+    /// ```
+    /// # use cornucopia::CodegenSettings;
+    /// let settings = CodegenSettings::builder().merge_nullability(true).build();
+    /// assert!(settings.merge_nullability());
+    /// ```
+    pub fn merge_nullability(mut self, merge_nullability: bool) -> Self {
+        self.settings.merge_nullability = merge_nullability;
+        self
+    }
+
+    /// Reads the nullability of table and view columns from this TOML file, mapping
+    /// `schema.table.column` to `nullable` or `not_null`, so that the columns of views or
+    /// generated columns are declared once rather than in every query reading them:
+    ///
+    /// ```toml
+    /// "public.author_view.country" = "nullable"
+    /// "public.author_view.name" = "not_null"
+    /// ```
+    ///
+    /// The file takes precedence over the nullability inferred from the catalog, while the
+    /// row declaration of a query takes precedence over the file, with a warning when they
+    /// disagree. Outer joins still make the columns they read nullable.
+    ///
+    /// ```
+    /// use cornucopia::CodegenSettings;
+    /// use std::path::Path;
+    ///
+    /// let settings = CodegenSettings::builder().nullability_file("nullability.toml").build();
+    /// assert_eq!(settings.nullability_file(), Some(Path::new("nullability.toml")));
+    /// ```
+    pub fn nullability_file(mut self, path: impl Into<PathBuf>) -> Self {
+        self.settings.nullability_file = Some(path.into());
+        self
+    }
+
+    /// Reports the queries returning table columns whose comment starts with this
+    /// marker, like `COMMENT ON COLUMN author.country IS 'DEPRECATED: use country_code'`.
+    /// An empty marker disables the check. Defaults to `DEPRECATED`.
+    ///
+    /// ```
+    /// use cornucopia::CodegenSettings;
+    ///
+    /// let settings = CodegenSettings::builder().deprecation_marker("OBSOLETE").build();
+    /// assert_eq!(settings.deprecation_marker(), "OBSOLETE");
+    /// ```
+    pub fn deprecation_marker(mut self, marker: impl Into<String>) -> Self {
+        self.settings.deprecation_marker = marker.into();
+        self
+    }
+
+    /// Marks the row fields read from deprecated columns `#[deprecated]`, using the
+    /// comment of the column as note, so that rustc warns where they are used. Enabled
+    /// by default.
+    pub fn deprecated_fields(mut self, deprecated_fields: bool) -> Self {
+        self.settings.deprecated_fields = deprecated_fields;
+        self
+    }
+
+    /// Continues past the query modules that cannot be parsed or prepared, so that the
+    /// errors of every failing module are reported at once along with the number of
+    /// failed and passed modules. Nothing is written when some module fails. By default,
+    /// generation stops at the first error. Modules prepared as several
+    /// [`roles`](Self::roles) still stop at the first error.
+    pub fn keep_going(mut self, keep_going: bool) -> Self {
+        self.settings.keep_going = keep_going;
+        self
+    }
+
+    /// Generates `From` conversions, by value and by reference, from each explicitly named
+    /// row to the explicitly named rows of the same module whose fields are a subset of its
+    /// own, with the same names, types and nullability. For example, with
+    /// `--! user_detail : UserDetail` and `--! user_summary : UserSummary`, a `UserDetail`
+    /// can be turned into a `UserSummary` when it has all its fields.
+    ///
+    /// Rows named after their query are never converted, so that the number of generated
+    /// conversions stays predictable.
+    pub fn row_conversions(mut self, gen_row_conversions: bool) -> Self {
+        self.settings.gen_row_conversions = gen_row_conversions;
+        self
+    }
+
+    /// Generates a builder along with each explicitly named params struct, created using
+    /// its `builder` function. The builder has a setter per field, named after it, and a
+    /// `build` function returning a `BuilderError` of the client crate when a non-nullable
+    /// field was not set. Nullable fields default to `None`.
+    ///
+    /// ```
+    /// # use cornucopia::CodegenSettings;
+    /// let settings = CodegenSettings::builder().params_builders(true).build();
+    /// assert!(settings.gen_params_builders());
+    /// ```
+    pub fn params_builders(mut self, gen_params_builders: bool) -> Self {
+        self.settings.gen_params_builders = gen_params_builders;
+        self
+    }
+
+    /// Generates `From` conversions from a reference to each explicitly named row to the
+    /// explicitly named params structs of the same module whose fields are a subset of its
+    /// own, so that a loaded row can be modified and saved back. For example, with
+    /// `--! author : Author` and `--! update_author Author(id, name)`, an
+    /// `&Author` can be turned into an `AuthorParams`, its text, bytes, JSON and array
+    /// fields borrowing the ones of the row.
+    ///
+    /// A field of the params must have the same name, type and nullability as the one of
+    /// the row, so that a nullability mismatch on any field excludes the pair. Params
+    /// holding borrowed composites or records are never converted.
+    ///
+    /// ```
+    /// # use cornucopia::CodegenSettings;
+    /// let settings = CodegenSettings::builder().param_conversions(true).build();
+    /// assert!(settings.gen_param_conversions());
+    /// ```
+    pub fn param_conversions(mut self, gen_param_conversions: bool) -> Self {
+        self.settings.gen_param_conversions = gen_param_conversions;
+        self
+    }
+
+    /// Generates a `two_phase` module with helpers of two-phase commits, for transactions
+    /// spanning the database and an external system. `prepare_transaction` runs generated
+    /// queries inside a transaction issuing `PREPARE TRANSACTION` instead of `COMMIT`,
+    /// which `commit_prepared` or `rollback_prepared` then finish. Global transaction
+    /// identifiers are checked using `is_valid_gid`, as they are embedded in the
+    /// statements.
+    ///
+    /// ```
+    /// # use cornucopia::CodegenSettings;
+    /// let settings = CodegenSettings::builder().two_phase_commit(true).build();
+    /// assert!(settings.gen_two_phase_commit());
+    /// ```
+    pub fn two_phase_commit(mut self, gen_two_phase_commit: bool) -> Self {
+        self.settings.gen_two_phase_commit = gen_two_phase_commit;
+        self
+    }
+
+    /// Generates each enum with the number of its labels as `LABEL_COUNT`, and a `verify`
+    /// function comparing them with the labels of the database at runtime, reporting the
+    /// missing and extra ones as an `EnumDrift`. When both sync and async code are
+    /// generated, the async function is `verify_async`.
+    ///
+    /// The enums then also accept a database enum that gained labels, decoding one of these
+    /// failing with an error naming it. Otherwise, the labels must be the generated ones.
+    ///
+    /// ```
+    /// # use cornucopia::CodegenSettings;
+    /// let settings = CodegenSettings::builder().enum_guards(true).build();
+    /// assert!(settings.gen_enum_guards());
+    /// ```
+    pub fn enum_guards(mut self, gen_enum_guards: bool) -> Self {
+        self.settings.gen_enum_guards = gen_enum_guards;
+        self
+    }
+
+    /// Implements `Display` on the row structs, printing their fields in order as
+    /// `id=1, name=Ann`, for logs and messages. Fields whose type doesn't implement
+    /// `Display`, like nullable, bytes, array and custom type fields, are printed using
+    /// `Debug`, and redacted fields as `***`.
+    ///
+    /// ```
+    /// # use cornucopia::CodegenSettings;
+    /// let settings = CodegenSettings::builder().display(true).build();
+    /// assert!(settings.gen_display());
+    /// ```
+    pub fn display(mut self, gen_display: bool) -> Self {
+        self.settings.gen_display = gen_display;
+        self
+    }
+
+    /// References custom types (enums and composites) through this Rust path instead of
+    /// generating them along with the queries, so that several crates generating queries
+    /// from the same database share the same types. The types themselves are generated
+    /// once using [`generate_types_live`](crate::generate_types_live), for example in a
+    /// `db_types` crate or in a `crate::db_types` module.
+    ///
+    /// ```
+    /// use cornucopia::CodegenSettings;
+    ///
+    /// let settings = CodegenSettings::builder().types_path("db_types").build();
+    /// assert_eq!(settings.types_path(), Some("db_types"));
+    /// ```
+    pub fn types_path(mut self, types_path: impl Into<String>) -> Self {
+        self.settings.types_path = Some(types_path.into());
+        self
+    }
+
+    /// Makes the generated queries return this error type (e.g. `crate::error::AppError`)
+    /// instead of the one of the client, so that call sites need no `map_err`. The type
+    /// must implement `From<tokio_postgres::Error>` (`From<postgres::Error>` for sync code),
+    /// and `From` the error types generated for queries handling error codes. Copying
+    /// out still returns an `std::io::Error`.
+    ///
+    /// ```
+    /// use cornucopia::CodegenSettings;
+    ///
+    /// let settings = CodegenSettings::builder().error_type("crate::AppError").build();
+    /// assert_eq!(settings.error_type(), Some("crate::AppError"));
+    /// ```
+    pub fn error_type(mut self, error_type: impl Into<String>) -> Self {
+        self.settings.error_type = Some(error_type.into());
+        self
+    }
+
+    /// Wraps the values of a table column (`users.id`) or of a domain (`user_id`)
+    /// in a newtype, for all queries returning this column or using this domain,
+    /// so that values of different columns cannot be mixed up.
+    ///
+    /// A plain name (`UserId`) declares a newtype generated in the `types` module,
+    /// implementing `FromSql` and `ToSql` by delegating to the wrapped type. A path
+    /// (`crate::ids::UserId`) references a type of your own verbatim, which must be
+    /// `Copy` and implement those traits, as well as the serde traits derived on
+    /// custom types. Only copyable types can be wrapped.
+    ///
+    /// Parameters opt into a newtype using an annotation: `WHERE id = :id@UserId`.
+    ///
+    /// ```
+    /// use cornucopia::CodegenSettings;
+    ///
+    /// let settings = CodegenSettings::builder()
+    ///     .newtype("users.id", "UserId")
+    ///     .newtype("posts.id", "crate::ids::PostId")
+    ///     .build();
+    /// assert_eq!(settings.newtypes().count(), 2);
+    /// ```
+    pub fn newtype(mut self, target: impl Into<String>, path: impl Into<String>) -> Self {
+        self.settings.newtypes.push(Newtype {
+            target: target.into(),
+            path: path.into(),
+        });
+        self
+    }
+
+    /// Adds `prefix` to the generated identifiers of this kind, e.g. `Db` to the rows.
+    /// Renames are applied once the queries are prepared, every reference to a renamed
+    /// identifier using its new name, and are reported when they make identifiers collide.
+    ///
+    /// ```
+    /// use cornucopia::{CodegenSettings, RenameTarget};
+    ///
+    /// let settings = CodegenSettings::builder()
+    ///     .rename_prefix(RenameTarget::Rows, "Db")
+    ///     .build();
+    /// assert_eq!(settings.renamed("author", RenameTarget::Rows, "Author"), "DbAuthor");
+    /// ```
+    pub fn rename_prefix(mut self, target: RenameTarget, prefix: impl Into<String>) -> Self {
+        self.settings
+            .renames
+            .push(Rename::Prefix(target, prefix.into()));
+        self
+    }
+
+    /// Adds `suffix` to the generated identifiers of this kind, e.g. `Dto` to the rows.
+    ///
+    /// ```
+    /// use cornucopia::{CodegenSettings, RenameTarget};
+    ///
+    /// let settings = CodegenSettings::builder()
+    ///     .rename_suffix(RenameTarget::Rows, "Dto")
+    ///     .build();
+    /// assert_eq!(settings.renamed("author", RenameTarget::Rows, "Author"), "AuthorDto");
+    /// ```
+    pub fn rename_suffix(mut self, target: RenameTarget, suffix: impl Into<String>) -> Self {
+        self.settings
+            .renames
+            .push(Rename::Suffix(target, suffix.into()));
+        self
+    }
+
+    /// Replaces the prefix `from` of the generated identifiers of this kind by `to`,
+    /// e.g. `get_` by `fetch_` for the functions. When several prefixes match, the longest
+    /// one is replaced.
+    ///
+    /// ```
+    /// use cornucopia::{CodegenSettings, RenameTarget};
+    ///
+    /// let settings = CodegenSettings::builder()
+    ///     .rename_prefix_map(RenameTarget::Functions, "get_", "fetch_")
+    ///     .build();
+    /// let renamed = settings.renamed("author", RenameTarget::Functions, "get_authors");
+    /// assert_eq!(renamed, "fetch_authors");
+    /// ```
+    pub fn rename_prefix_map(
+        mut self,
+        target: RenameTarget,
+        from: impl Into<String>,
+        to: impl Into<String>,
+    ) -> Self {
+        self.settings.renames.push(Rename::PrefixMap {
+            target,
+            from: from.into(),
+            to: to.into(),
+        });
+        self
+    }
+
+    /// Renames the identifier `from` of `module` to `to`, regardless of the other renames.
+    /// A module is renamed by giving its own name as `module`.
+    ///
+    /// ```
+    /// use cornucopia::{CodegenSettings, RenameTarget};
+    ///
+    /// let settings = CodegenSettings::builder()
+    ///     .rename_prefix_map(RenameTarget::Functions, "get_", "fetch_")
+    ///     .rename("author", RenameTarget::Functions, "get_author", "author_by_id")
+    ///     .build();
+    /// let renamed = settings.renamed("author", RenameTarget::Functions, "get_author");
+    /// assert_eq!(renamed, "author_by_id");
+    /// let renamed = settings.renamed("book", RenameTarget::Functions, "get_author");
+    /// assert_eq!(renamed, "fetch_author");
+    /// ```
+    pub fn rename(
+        mut self,
+        module: impl Into<String>,
+        target: RenameTarget,
+        from: impl Into<String>,
+        to: impl Into<String>,
+    ) -> Self {
+        self.settings.renames.push(Rename::Exact {
+            module: module.into(),
+            target,
+            from: from.into(),
+            to: to.into(),
+        });
+        self
+    }
+
+    /// Prepares every query as each of these roles (using `SET ROLE`) instead of the
+    /// current role, reporting queries that fail or whose parameters or columns differ
+    /// for some of them. This catches queries resolving to other relations for some
+    /// application roles, such as per-role views found through the `"$user"` schema of
+    /// the search path. Code is generated from the preparation as the first role. Roles
+    /// are ignored when generating from a snapshot.
+    pub fn roles<I: IntoIterator<Item = S>, S: Into<String>>(mut self, roles: I) -> Self {
+        self.settings.roles = roles.into_iter().map(Into::into).collect();
+        self
+    }
+
+    /// Hides the value of the row, params and composite fields whose name matches this
+    /// pattern from their `Debug` output, printing `***` instead. A `*` in the pattern
+    /// matches any sequence of characters. Structs with such fields implement `Debug`
+    /// manually instead of deriving it, so that secrets like password hashes or tokens
+    /// don't end up in logs.
+    ///
+    /// ```
+    /// use cornucopia::CodegenSettings;
+    ///
+    /// let settings = CodegenSettings::builder()
+    ///     .redact("*password*")
+    ///     .redact("token")
+    ///     .build();
+    /// assert_eq!(settings.redact().count(), 2);
+    /// ```
+    pub fn redact(mut self, pattern: impl Into<String>) -> Self {
+        self.settings.redact.push(pattern.into());
+        self
+    }
+
+    /// Prepares and generates only the queries whose `module::query` name matches this
+    /// pattern, along with the rows, params and types they use, instead of every query.
+    /// A `*` in the pattern matches any sequence of characters, like in `authors::*`. The
+    /// setup queries of their modules are kept too, as they may depend on them. Each
+    /// pattern must match some query, and the generated file records the patterns so
+    /// that it is not mistaken for the code of every query.
+    ///
+    /// ```
+    /// use cornucopia::CodegenSettings;
+    ///
+    /// let settings = CodegenSettings::builder()
+    ///     .only("authors::*")
+    ///     .only("books::book_by_id")
+    ///     .build();
+    /// assert_eq!(settings.only().count(), 2);
+    /// ```
+    pub fn only(mut self, pattern: impl Into<String>) -> Self {
+        self.settings.only.push(pattern.into());
+        self
+    }
+
+    /// Prepares and generates only the queries tagged with this `key=value` pair using the
+    /// `tag` option (`-- :tag team=payments`), in addition to those matching
+    /// [`only`](Self::only). Like patterns, each tag must be declared by some query and is
+    /// recorded in the generated file. Tags don't change the generated code otherwise.
+    ///
+    /// ```
+    /// use cornucopia::CodegenSettings;
+    ///
+    /// let settings = CodegenSettings::builder()
+    ///     .only_tag("team=payments")
+    ///     .build();
+    /// assert_eq!(settings.only_tags().collect::<Vec<_>>(), ["team=payments"]);
+    /// ```
+    pub fn only_tag(mut self, tag: impl Into<String>) -> Self {
+        self.settings.only_tags.push(tag.into());
+        self
+    }
+
+    /// Retries the setup of the container managed by Cornucopia up to this number of
+    /// times when the container manager fails transiently, waiting longer before each
+    /// attempt (1s, 2s, 4s...). Failures that cannot be transient, like a missing image or
+    /// a denied permission, are reported at once. Defaults to 2.
+    ///
+    /// ```
+    /// use cornucopia::CodegenSettings;
+    ///
+    /// let settings = CodegenSettings::builder().setup_retries(5).build();
+    /// assert_eq!(settings.setup_retries(), 5);
+    /// ```
+    pub fn setup_retries(mut self, retries: u32) -> Self {
+        self.settings.setup_retries = retries;
+        self
+    }
+
+    /// Runs the container managed by Cornucopia from this image instead of
+    /// [`DEFAULT_IMAGE`](crate::container::DEFAULT_IMAGE), the latest image of PostgreSQL,
+    /// like an image providing the extensions the schema uses.
+    ///
+    /// ```
+    /// use cornucopia::CodegenSettings;
+    ///
+    /// let settings = CodegenSettings::builder()
+    ///     .container_image("docker.io/pgvector/pgvector:pg18")
+    ///     .build();
+    /// assert_eq!(settings.container_image(), "docker.io/pgvector/pgvector:pg18");
+    /// ```
+    pub fn container_image(mut self, image: impl Into<String>) -> Self {
+        self.settings.container_image = image.into();
+        self
+    }
+
+    /// Reports this `application_name` when connecting to the container managed by
+    /// Cornucopia, instead of [`DEFAULT_APPLICATION_NAME`](crate::conn::DEFAULT_APPLICATION_NAME).
+    /// Clusters allowing some application names only can then be used, and connections
+    /// told apart in `pg_stat_activity`. The CLI also reports it when connecting to a URL,
+    /// overriding the `application_name` of the URL.
+    ///
+    /// ```
+    /// use cornucopia::CodegenSettings;
+    ///
+    /// let settings = CodegenSettings::builder().application_name("billing-codegen").build();
+    /// assert_eq!(settings.application_name(), Some("billing-codegen"));
+    /// ```
+    pub fn application_name(mut self, application_name: impl Into<String>) -> Self {
+        self.settings.application_name = Some(application_name.into());
+        self
+    }
+
+    /// Writes a criterion bench file per module in this directory when the generated
+    /// code is written to a destination file. Each file benchmarks the queries listed in
+    /// the `benches.toml` file of the directory, with literal parameters:
+    ///
+    /// ```toml
+    /// [authors.author_by_id]
+    /// id = 1
+    /// ```
+    ///
+    /// Benches include the generated file and execute the queries against the database
+    /// whose URL is in the `CORNUCOPIA_BENCH_URL` environment variable, in a transaction
+    /// rolled back at the end. They use the sync queries if they are generated. Each bench
+    /// file must be declared in `Cargo.toml` with `harness = false`.
+    pub fn emit_benches(mut self, dir: impl Into<PathBuf>) -> Self {
+        self.settings.benches = Some(dir.into());
+        self
+    }
+
+    /// Embeds the `.sql` migration files of this directory in the generated code, sorted
+    /// by name, as a `MIGRATIONS` constant listing their name, SQL and checksum. A single
+    /// binary can then apply them at startup using
+    /// [`run_embedded_migrations`](crate::run_embedded_migrations), without the directory.
+    ///
+    /// ```
+    /// use cornucopia::CodegenSettings;
+    /// use std::path::Path;
+    ///
+    /// let settings = CodegenSettings::builder().embed_migrations("migrations").build();
+    /// assert_eq!(settings.embed_migrations(), Some(Path::new("migrations")));
+    /// ```
+    pub fn embed_migrations(mut self, dir: impl Into<PathBuf>) -> Self {
+        self.settings.migrations = Some(dir.into());
+        self
+    }
+
+    /// Caches the statements prepared against a live database in a
+    /// `.cornucopia-cache.json` file next to the destination file, along with the hash of
+    /// each query file. The following generations only prepare the modules whose file
+    /// changed, the statements of the others being read from the cache. The cache is
+    /// discarded when the settings or the version of cornucopia change.
+    ///
+    /// Changes to the schema of the database are not detected: the cache must be deleted
+    /// when they affect unchanged query files.
+    ///
+    /// ```
+    /// use cornucopia::CodegenSettings;
+    ///
+    /// let settings = CodegenSettings::builder().incremental(true).build();
+    /// assert!(settings.incremental());
+    /// ```
+    pub fn incremental(mut self, incremental: bool) -> Self {
+        self.settings.incremental = incremental;
+        self
+    }
+
+    /// Builds the settings.
+    pub fn build(self) -> CodegenSettings {
+        self.settings
+    }
+}
@@ -0,0 +1,314 @@
+use std::{
+    collections::{BTreeMap, BTreeSet},
+    path::Path,
+};
+
+use postgres_types::{Field, Kind, Type};
+use serde::{Deserialize, Serialize};
+
+use crate::database::{Column, ColumnOrigin, Statement};
+
+use self::error::Error;
+
+/// Version of the snapshot format, bumped on every incompatible change
+const SNAPSHOT_VERSION: u32 = 4;
+
+/// Recording of the statements prepared against a live database, allowing
+/// to generate code without a connection.
+#[derive(Debug, Serialize, Deserialize)]
+pub(crate) struct Snapshot {
+    version: u32,
+    /// Catalog of the non built-in types used by the statements, by OID
+    types: BTreeMap<u32, TypeEntry>,
+    /// Domains declared `NOT NULL` among those of composite fields, by OID
+    #[serde(default, skip_serializing_if = "BTreeSet::is_empty")]
+    not_null_domains: BTreeSet<u32>,
+    /// Versions of the extensions installed in the database, by name, so that deployment
+    /// tooling can check that they are also installed in production
+    #[serde(default)]
+    extensions: BTreeMap<String, String>,
+    /// `server_version_num` of the database, selecting the variants of the queries declared
+    /// for different server versions
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    server_version: Option<i32>,
+    /// OID of the `vector` type of the pgvector extension, if it is installed
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    vector_type: Option<u32>,
+    /// Prepared statements by module and query name
+    statements: BTreeMap<String, BTreeMap<String, StatementEntry>>,
+}
+
+/// Serializable mirror of a [`Type`]
+#[derive(Debug, Serialize, Deserialize)]
+struct TypeEntry {
+    name: String,
+    schema: String,
+    kind: KindEntry,
+}
+
+/// Serializable mirror of a [`Kind`], referencing its inner types by OID
+#[derive(Debug, Serialize, Deserialize)]
+enum KindEntry {
+    Simple,
+    Enum(Vec<String>),
+    Pseudo,
+    Array(u32),
+    Range(u32),
+    Multirange(u32),
+    Domain(u32),
+    Composite(Vec<(String, u32)>),
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+struct StatementEntry {
+    /// Hash of the SQL, to detect queries modified since the snapshot was recorded
+    sql_hash: String,
+    params: Vec<u32>,
+    /// Name, type OID and source table column of every column
+    columns: Vec<(String, u32, Option<ColumnOrigin>)>,
+}
+
+/// Reason why a statement cannot be replayed from a snapshot
+pub(crate) enum ReplayError {
+    Missing,
+    Stale,
+    UnknownType(u32),
+    ServerVersion,
+}
+
+impl Snapshot {
+    pub(crate) fn new() -> Self {
+        Self {
+            version: SNAPSHOT_VERSION,
+            types: BTreeMap::new(),
+            not_null_domains: BTreeSet::new(),
+            extensions: BTreeMap::new(),
+            server_version: None,
+            vector_type: None,
+            statements: BTreeMap::new(),
+        }
+    }
+
+    pub(crate) fn read(path: &Path) -> Result<Self, Error> {
+        let content = std::fs::read_to_string(path).map_err(|err| Error::Io {
+            action: "read",
+            path: path.to_owned(),
+            err,
+        })?;
+        let snapshot: Self = serde_json::from_str(&content).map_err(|err| Error::Format {
+            path: path.to_owned(),
+            err,
+        })?;
+        if snapshot.version != SNAPSHOT_VERSION {
+            return Err(Error::Version {
+                path: path.to_owned(),
+                found: snapshot.version,
+                expected: SNAPSHOT_VERSION,
+            });
+        }
+        Ok(snapshot)
+    }
+
+    pub(crate) fn write(&self, path: &Path) -> Result<(), Error> {
+        let content = serde_json::to_string_pretty(self).expect("snapshot is serializable");
+        std::fs::write(path, content).map_err(|err| Error::Io {
+            action: "write",
+            path: path.to_owned(),
+            err,
+        })
+    }
+
+    /// Records a statement and the custom types it uses
+    pub(crate) fn record(&mut self, module: &str, query: &str, sql: &str, stmt: &Statement) {
+        let entry = StatementEntry {
+            sql_hash: hash(sql),
+            params: stmt.params.iter().map(|ty| self.record_type(ty)).collect(),
+            columns: stmt
+                .columns
+                .iter()
+                .map(|col| {
+                    (
+                        col.name().to_string(),
+                        self.record_type(col.type_()),
+                        col.origin().cloned(),
+                    )
+                })
+                .collect(),
+        };
+        self.statements
+            .entry(module.to_string())
+            .or_default()
+            .insert(query.to_string(), entry);
+    }
+
+    /// Records the extensions installed in the database, with their version
+    pub(crate) fn record_extensions(&mut self, extensions: Vec<(String, String)>) {
+        self.extensions = extensions.into_iter().collect();
+    }
+
+    /// Records the `server_version_num` of the database
+    pub(crate) fn record_server_version(&mut self, version: i32) {
+        self.server_version = Some(version);
+    }
+
+    /// The recorded `server_version_num` of the database
+    pub(crate) fn server_version(&self) -> Result<i32, ReplayError> {
+        self.server_version.ok_or(ReplayError::ServerVersion)
+    }
+
+    /// Records the OID of the `vector` type of the pgvector extension
+    pub(crate) fn record_vector_type(&mut self, oid: Option<u32>) {
+        self.vector_type = oid;
+    }
+
+    /// The recorded OID of the `vector` type of the pgvector extension
+    pub(crate) fn vector_type(&self) -> Option<u32> {
+        self.vector_type
+    }
+
+    /// Records the domains declared `NOT NULL` among those of composite fields
+    pub(crate) fn record_not_null_domains(&mut self, oids: &[u32]) {
+        self.not_null_domains.extend(oids);
+    }
+
+    /// The given domains recorded as `NOT NULL`
+    pub(crate) fn not_null_domains(&self, oids: &[u32]) -> Vec<u32> {
+        oids.iter()
+            .copied()
+            .filter(|it| self.not_null_domains.contains(it))
+            .collect()
+    }
+
+    fn record_type(&mut self, ty: &Type) -> u32 {
+        if Type::from_oid(ty.oid()).is_none() && !self.types.contains_key(&ty.oid()) {
+            let kind = match ty.kind() {
+                Kind::Simple => KindEntry::Simple,
+                Kind::Enum(variants) => KindEntry::Enum(variants.clone()),
+                Kind::Pseudo => KindEntry::Pseudo,
+                Kind::Array(inner) => KindEntry::Array(self.record_type(inner)),
+                Kind::Range(inner) => KindEntry::Range(self.record_type(inner)),
+                Kind::Multirange(inner) => KindEntry::Multirange(self.record_type(inner)),
+                Kind::Domain(inner) => KindEntry::Domain(self.record_type(inner)),
+                Kind::Composite(fields) => KindEntry::Composite(
+                    fields
+                        .iter()
+                        .map(|field| (field.name().to_string(), self.record_type(field.type_())))
+                        .collect(),
+                ),
+                // Unknown kinds are not supported by cornucopia either
+                _ => KindEntry::Pseudo,
+            };
+            self.types.insert(
+                ty.oid(),
+                TypeEntry {
+                    name: ty.name().to_string(),
+                    schema: ty.schema().to_string(),
+                    kind,
+                },
+            );
+        }
+        ty.oid()
+    }
+
+    /// Replays a statement recorded in this snapshot
+    pub(crate) fn replay(
+        &self,
+        module: &str,
+        query: &str,
+        sql: &str,
+    ) -> Result<Statement, ReplayError> {
+        let entry = self
+            .statements
+            .get(module)
+            .and_then(|it| it.get(query))
+            .ok_or(ReplayError::Missing)?;
+        if entry.sql_hash != hash(sql) {
+            return Err(ReplayError::Stale);
+        }
+        Ok(Statement {
+            params: entry
+                .params
+                .iter()
+                .map(|oid| self.replay_type(*oid))
+                .collect::<Result<_, _>>()?,
+            columns: entry
+                .columns
+                .iter()
+                .map(|(name, oid, origin)| {
+                    Ok(Column::new(
+                        name.clone(),
+                        self.replay_type(*oid)?,
+                        origin.clone(),
+                    ))
+                })
+                .collect::<Result<_, _>>()?,
+        })
+    }
+
+    fn replay_type(&self, oid: u32) -> Result<Type, ReplayError> {
+        if let Some(ty) = Type::from_oid(oid) {
+            return Ok(ty);
+        }
+        let entry = self.types.get(&oid).ok_or(ReplayError::UnknownType(oid))?;
+        let kind = match &entry.kind {
+            KindEntry::Simple => Kind::Simple,
+            KindEntry::Enum(variants) => Kind::Enum(variants.clone()),
+            KindEntry::Pseudo => Kind::Pseudo,
+            KindEntry::Array(inner) => Kind::Array(self.replay_type(*inner)?),
+            KindEntry::Range(inner) => Kind::Range(self.replay_type(*inner)?),
+            KindEntry::Multirange(inner) => Kind::Multirange(self.replay_type(*inner)?),
+            KindEntry::Domain(inner) => Kind::Domain(self.replay_type(*inner)?),
+            KindEntry::Composite(fields) => Kind::Composite(
+                fields
+                    .iter()
+                    .map(|(name, oid)| Ok(Field::new(name.clone(), self.replay_type(*oid)?)))
+                    .collect::<Result<_, _>>()?,
+            ),
+        };
+        Ok(Type::new(
+            entry.name.clone(),
+            oid,
+            kind,
+            entry.schema.clone(),
+        ))
+    }
+}
+
+/// FNV-1a hash of some SQL or query file, stable across platforms and compiler versions
+pub(crate) fn hash(content: &str) -> String {
+    let hash = content
+        .bytes()
+        .fold(0xcbf2_9ce4_8422_2325_u64, |hash, byte| {
+            (hash ^ u64::from(byte)).wrapping_mul(0x0100_0000_01b3)
+        });
+    format!("{hash:016x}")
+}
+
+pub(crate) mod error {
+    use std::path::PathBuf;
+
+    use miette::Diagnostic;
+    use thiserror::Error as ThisError;
+
+    #[derive(Debug, ThisError, Diagnostic)]
+    pub enum Error {
+        #[error("Could not {action} snapshot `{path}`: ({err})")]
+        Io {
+            action: &'static str,
+            path: PathBuf,
+            err: std::io::Error,
+        },
+        #[error("Could not parse snapshot `{path}`: ({err})")]
+        Format {
+            path: PathBuf,
+            err: serde_json::Error,
+        },
+        #[error("Snapshot `{path}` has version {found} but this version of cornucopia expects {expected}")]
+        #[diagnostic(help("record a new snapshot using `cornucopia snapshot`"))]
+        Version {
+            path: PathBuf,
+            found: u32,
+            expected: u32,
+        },
+    }
+}
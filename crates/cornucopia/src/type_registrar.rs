@@ -7,12 +7,24 @@ use postgres_types::{Kind, Type};
 use crate::{
     codegen::{idx_char, GenCtx},
     parser::Span,
+    prepare_queries::PreparedField,
     read_queries::ModuleInfo,
+    settings::{DateTimeCrate, Newtype, NumericCrate},
     utils::SchemaKey,
 };
 
 use self::error::Error;
 
+/// Name of the embedding type of the pgvector extension, which is also the name of the
+/// extension
+pub(crate) const VECTOR_TYPE: &str = "vector";
+/// Name of the struct generated in the `types` module for `vector`, like the one of the
+/// `pgvector` crate
+pub(crate) const VECTOR_STRUCT: &str = "Vector";
+/// Rust type of `numeric` using [`NumericCrate::BigDecimal`], implementing neither `FromSql`
+/// nor `ToSql`
+const BIG_DECIMAL: &str = "bigdecimal::BigDecimal";
+
 /// A struct containing a postgres type and its Rust-equivalent.
 #[derive(PartialEq, Eq, Debug, Clone)]
 pub(crate) enum CornucopiaType {
@@ -28,25 +40,63 @@ pub(crate) enum CornucopiaType {
         pg_ty: Type,
         inner: Rc<CornucopiaType>,
     },
+    /// A range of its bounds type, using the `Range` of the client crate
+    Range {
+        pg_ty: Type,
+        inner: Rc<CornucopiaType>,
+    },
     Custom {
         pg_ty: Type,
         struct_name: String,
         is_copy: bool,
         is_params: bool,
     },
+    /// A copyable type wrapped in a newtype, either generated or provided by the user
+    Newtype {
+        path: String,
+        is_generated: bool,
+        inner: Rc<CornucopiaType>,
+    },
+    /// An embedding of the pgvector extension, generated in the `types` module
+    Vector {
+        pg_ty: Type,
+    },
+    /// An anonymous record whose fields are declared on its column, generated in the module
+    /// of its query
+    Record {
+        pg_ty: Type,
+        struct_name: String,
+        column: String,
+        fields: Vec<PreparedField>,
+        /// Declared types of the fields, checked against the decoded ones
+        field_types: Vec<Type>,
+    },
+    /// A `json` or `jsonb` value deserialized into a type declared on its column or
+    /// parameter, e.g. `settings: crate::Settings`
+    TypedJson {
+        pg_ty: Type,
+        path: String,
+    },
 }
 
 impl CornucopiaType {
     /// Is this type need a generic lifetime
     pub fn is_ref(&self) -> bool {
         match self {
-            CornucopiaType::Simple { pg_ty, .. } => match *pg_ty {
-                Type::BYTEA | Type::TEXT | Type::VARCHAR | Type::JSON | Type::JSONB => false,
-                _ => !self.is_copy(),
-            },
-            CornucopiaType::Domain { inner, .. } | CornucopiaType::Array { inner } => {
-                inner.is_ref()
+            CornucopiaType::Simple { pg_ty, .. }
+                if matches!(
+                    *pg_ty,
+                    Type::BYTEA | Type::TEXT | Type::VARCHAR | Type::JSON | Type::JSONB
+                ) =>
+            {
+                false
             }
+            CornucopiaType::Domain { inner, .. }
+            | CornucopiaType::Array { inner }
+            | CornucopiaType::Range { inner, .. } => inner.is_ref(),
+            CornucopiaType::Newtype { .. }
+            | CornucopiaType::Vector { .. }
+            | CornucopiaType::Record { .. } => false,
             _ => !self.is_copy(),
         }
     }
@@ -57,25 +107,202 @@ impl CornucopiaType {
             CornucopiaType::Simple { is_copy, .. } | CornucopiaType::Custom { is_copy, .. } => {
                 *is_copy
             }
-            CornucopiaType::Domain { inner, .. } => inner.is_copy(),
-            CornucopiaType::Array { .. } => false,
+            CornucopiaType::Domain { inner, .. } | CornucopiaType::Range { inner, .. } => {
+                inner.is_copy()
+            }
+            CornucopiaType::Array { .. }
+            | CornucopiaType::Vector { .. }
+            | CornucopiaType::Record { .. }
+            | CornucopiaType::TypedJson { .. } => false,
+            CornucopiaType::Newtype { .. } => true,
         }
     }
 
     /// Can this used in parameters as it is
     pub fn is_params(&self) -> bool {
         match self {
-            CornucopiaType::Simple { .. } => true,
-            CornucopiaType::Array { .. } => false,
-            CornucopiaType::Domain { inner, .. } => inner.is_params(),
+            CornucopiaType::Simple { .. }
+            | CornucopiaType::Newtype { .. }
+            | CornucopiaType::Vector { .. }
+            | CornucopiaType::TypedJson { .. } => true,
+            CornucopiaType::Array { .. } | CornucopiaType::Record { .. } => false,
+            CornucopiaType::Domain { inner, .. } | CornucopiaType::Range { inner, .. } => {
+                inner.is_params()
+            }
             CornucopiaType::Custom { is_params, .. } => *is_params,
         }
     }
 
+    /// Is this type free of arrays and JSON values, which borrowed parameters and
+    /// composites hold in types not implementing serde traits
+    pub(crate) fn is_plain(&self) -> bool {
+        fn is_plain_pg(ty: &Type) -> bool {
+            match ty.kind() {
+                Kind::Array(_) => false,
+                Kind::Domain(inner) => is_plain_pg(inner),
+                Kind::Composite(fields) => fields.iter().all(|f| is_plain_pg(f.type_())),
+                _ => !matches!(*ty, Type::JSON | Type::JSONB),
+            }
+        }
+        match self {
+            CornucopiaType::Simple { pg_ty, .. } | CornucopiaType::Custom { pg_ty, .. } => {
+                is_plain_pg(pg_ty)
+            }
+            CornucopiaType::Array { .. }
+            | CornucopiaType::Record { .. }
+            | CornucopiaType::TypedJson { .. } => false,
+            CornucopiaType::Domain { inner, .. } | CornucopiaType::Range { inner, .. } => {
+                inner.is_plain()
+            }
+            CornucopiaType::Newtype { .. } | CornucopiaType::Vector { .. } => true,
+        }
+    }
+
+    /// Can a params struct field of this type derive serde traits, arrays and JSON values
+    /// being generic but borrowed composites and typed JSON values being concrete
+    pub(crate) fn is_serde_param(&self) -> bool {
+        match self {
+            CornucopiaType::Custom { is_copy, .. } => *is_copy || self.is_plain(),
+            CornucopiaType::TypedJson { .. } => false,
+            CornucopiaType::Domain { inner, .. } => inner.is_serde_param(),
+            _ => true,
+        }
+    }
+
+    /// Is this type a composite borrowed in parameters, requiring `#[serde(borrow)]`
+    /// to be deserialized
+    pub(crate) fn is_borrowed_composite(&self) -> bool {
+        match self {
+            CornucopiaType::Custom { is_copy, .. } => !is_copy,
+            CornucopiaType::Domain { inner, .. } => inner.is_borrowed_composite(),
+            _ => false,
+        }
+    }
+
+    /// Does the owned type of this type implement `Eq` and `Hash`, floating point numbers,
+    /// JSON values, composites and provided newtypes not being known to implement them
+    pub(crate) fn is_hashable(&self) -> bool {
+        match self {
+            CornucopiaType::Simple { pg_ty, .. } => !matches!(
+                *pg_ty,
+                Type::FLOAT4 | Type::FLOAT8 | Type::JSON | Type::JSONB
+            ),
+            CornucopiaType::Array { inner }
+            | CornucopiaType::Domain { inner, .. }
+            | CornucopiaType::Range { inner, .. } => inner.is_hashable(),
+            CornucopiaType::Custom { pg_ty, .. } => matches!(pg_ty.kind(), Kind::Enum(_)),
+            CornucopiaType::Vector { .. }
+            | CornucopiaType::Record { .. }
+            | CornucopiaType::TypedJson { .. } => false,
+            // Generated newtypes derive `Eq` and `Hash` like their wrapped type
+            CornucopiaType::Newtype {
+                is_generated,
+                inner,
+                ..
+            } => {
+                *is_generated
+                    && matches!(
+                        inner.as_ref(),
+                        CornucopiaType::Simple { .. } | CornucopiaType::Custom { .. }
+                    )
+                    && inner.is_hashable()
+            }
+        }
+    }
+
+    /// Does the owned type of this type have a natural ordering implementing `Ord`, like
+    /// numbers, text, dates and enums, ordered as declared. Arrays are ordered
+    /// lexicographically when their elements are orderable.
+    pub(crate) fn is_orderable(&self) -> bool {
+        match self {
+            CornucopiaType::Simple { pg_ty, .. } => matches!(
+                *pg_ty,
+                Type::BOOL
+                    | Type::CHAR
+                    | Type::INT2
+                    | Type::INT4
+                    | Type::INT8
+                    | Type::TEXT
+                    | Type::VARCHAR
+                    | Type::TIMESTAMP
+                    | Type::TIMESTAMPTZ
+                    | Type::DATE
+                    | Type::TIME
+                    | Type::UUID
+                    | Type::INET
+                    | Type::NUMERIC
+            ),
+            CornucopiaType::Array { inner } | CornucopiaType::Domain { inner, .. } => {
+                inner.is_orderable()
+            }
+            CornucopiaType::Custom { pg_ty, .. } => matches!(pg_ty.kind(), Kind::Enum(_)),
+            CornucopiaType::Range { .. }
+            | CornucopiaType::Newtype { .. }
+            | CornucopiaType::Vector { .. }
+            | CornucopiaType::Record { .. }
+            | CornucopiaType::TypedJson { .. } => false,
+        }
+    }
+
+    /// Does the owned type of this type implement `Display`, bytes, arrays, custom types
+    /// and newtypes being printed using `Debug` instead
+    pub(crate) fn is_display(&self) -> bool {
+        match self {
+            CornucopiaType::Simple { pg_ty, .. } => *pg_ty != Type::BYTEA,
+            CornucopiaType::Domain { inner, .. } => inner.is_display(),
+            CornucopiaType::Array { .. }
+            | CornucopiaType::Range { .. }
+            | CornucopiaType::Custom { .. }
+            | CornucopiaType::Newtype { .. }
+            | CornucopiaType::Vector { .. }
+            | CornucopiaType::Record { .. }
+            | CornucopiaType::TypedJson { .. } => false,
+        }
+    }
+
+    /// Does the parameter type of this type implement `Hash`, floating point numbers, JSON
+    /// values and custom types not implementing it
+    pub(crate) fn is_hash(&self) -> bool {
+        match self {
+            CornucopiaType::Simple { pg_ty, .. } => !matches!(
+                *pg_ty,
+                Type::FLOAT4 | Type::FLOAT8 | Type::JSON | Type::JSONB
+            ),
+            CornucopiaType::Array { inner }
+            | CornucopiaType::Domain { inner, .. }
+            | CornucopiaType::Range { inner, .. } => inner.is_hash(),
+            CornucopiaType::Custom { .. }
+            | CornucopiaType::Vector { .. }
+            | CornucopiaType::Record { .. }
+            | CornucopiaType::TypedJson { .. } => false,
+            // Generated newtypes derive `Hash` like their wrapped type, provided ones must implement it
+            CornucopiaType::Newtype {
+                is_generated,
+                inner,
+                ..
+            } => {
+                !is_generated
+                    || matches!(inner.as_ref(), CornucopiaType::Simple { .. }) && inner.is_hash()
+            }
+        }
+    }
+
+    /// Is this type a `numeric` read and written as a `BigDecimal`, through the wrappers of
+    /// the client crate
+    fn is_big_decimal(&self) -> bool {
+        matches!(self, CornucopiaType::Simple { rust_name, .. } if *rust_name == BIG_DECIMAL)
+    }
+
     /// Wrap type to escape domains in parameters
     pub(crate) fn sql_wrapped(&self, name: &str, ctx: &GenCtx) -> String {
         let client_name = ctx.client_name();
         match self {
+            _ if self.is_big_decimal() => {
+                format!("{client_name}::private::BigNumericSql::new({name})")
+            }
+            CornucopiaType::TypedJson { .. } => {
+                format!("{client_name}::private::TypedJsonSql::new({name})")
+            }
             CornucopiaType::Domain { inner, .. } => {
                 format!(
                     "&{client_name}::private::Domain({})",
@@ -89,16 +316,41 @@ impl CornucopiaType {
                         inner.sql_wrapped(name, ctx)
                     )
                 }
+                _ if inner.is_big_decimal() => {
+                    format!("{client_name}::private::BigNumericSql::new({name})")
+                }
                 _ => name.to_string(),
             },
             _ => name.to_string(),
         }
     }
 
+    /// Reference to a parameter held by a query until it is executed. Only `BigDecimal`s and
+    /// typed JSON values are wrapped, wrapping them not creating a temporary.
+    pub(crate) fn query_param(&self, name: &str, ctx: &GenCtx) -> String {
+        match self {
+            CornucopiaType::Domain { inner, .. } => inner.query_param(name, ctx),
+            CornucopiaType::TypedJson { .. } => self.sql_wrapped(name, ctx),
+            CornucopiaType::Array { inner } if inner.is_big_decimal() => {
+                format!("{}::private::BigNumericSql::new({name})", ctx.client_name())
+            }
+            _ if self.is_big_decimal() => {
+                format!("{}::private::BigNumericSql::new({name})", ctx.client_name())
+            }
+            _ => name.to_string(),
+        }
+    }
+
     /// Wrap type to escape domains when writing to sql
     pub(crate) fn accept_to_sql(&self, ctx: &GenCtx) -> String {
         let client_name = ctx.client_name();
         match self {
+            _ if self.is_big_decimal() => {
+                format!("{client_name}::private::BigNumericSql::<{BIG_DECIMAL}>")
+            }
+            CornucopiaType::TypedJson { path, .. } => {
+                format!("{client_name}::private::TypedJsonSql::<&{path}>")
+            }
             CornucopiaType::Domain { inner, .. } => format!(
                 "{client_name}::private::Domain::<{}>",
                 inner.accept_to_sql(ctx)
@@ -108,6 +360,9 @@ impl CornucopiaType {
                     let ty = inner.accept_to_sql(ctx);
                     format!("{client_name}::private::DomainArray::<{ty}, &[{ty}]>")
                 }
+                _ if inner.is_big_decimal() => {
+                    format!("{client_name}::private::BigNumericSql::<&[{BIG_DECIMAL}]>")
+                }
                 _ => self.param_ty(false, ctx),
             },
             _ => self.param_ty(false, ctx),
@@ -119,8 +374,14 @@ impl CornucopiaType {
         match self {
             CornucopiaType::Simple { pg_ty, .. }
             | CornucopiaType::Custom { pg_ty, .. }
-            | CornucopiaType::Domain { pg_ty, .. } => pg_ty,
-            CornucopiaType::Array { inner } => inner.pg_ty(),
+            | CornucopiaType::Domain { pg_ty, .. }
+            | CornucopiaType::Range { pg_ty, .. }
+            | CornucopiaType::Vector { pg_ty }
+            | CornucopiaType::Record { pg_ty, .. }
+            | CornucopiaType::TypedJson { pg_ty, .. } => pg_ty,
+            CornucopiaType::Array { inner } | CornucopiaType::Newtype { inner, .. } => {
+                inner.pg_ty()
+            }
         }
     }
 
@@ -149,6 +410,7 @@ impl CornucopiaType {
                 format!("{name}.map(|v| {inner}).collect()")
             }
             CornucopiaType::Domain { inner, .. } => inner.owning_call(name, is_nullable, false),
+            CornucopiaType::TypedJson { .. } => format!("{name}.0"),
             _ => {
                 format!("{name}.into()")
             }
@@ -168,9 +430,34 @@ impl CornucopiaType {
                 }
             }
             CornucopiaType::Domain { inner, .. } => inner.own_ty(false, ctx),
+            CornucopiaType::Range { inner, .. } => {
+                format!("{}::Range<{}>", ctx.client_name(), inner.own_ty(false, ctx))
+            }
             CornucopiaType::Custom {
                 struct_name, pg_ty, ..
             } => custom_ty_path(pg_ty.schema(), struct_name, ctx),
+            CornucopiaType::Newtype { .. } => self.newtype_path(ctx),
+            CornucopiaType::Vector { .. } => types_item_path(VECTOR_STRUCT, ctx),
+            // Named without any path outside of the queries
+            CornucopiaType::Record { struct_name, .. } => {
+                ctx.path(ctx.depth.saturating_sub(2), struct_name)
+            }
+            CornucopiaType::TypedJson { path, .. } => path.clone(),
+        }
+    }
+
+    /// Path of a newtype, generated in the `types` module or provided by the user
+    fn newtype_path(&self, ctx: &GenCtx) -> String {
+        let CornucopiaType::Newtype {
+            path, is_generated, ..
+        } = self
+        else {
+            unreachable!("not a newtype")
+        };
+        if *is_generated {
+            types_item_path(path, ctx)
+        } else {
+            path.clone()
         }
     }
 
@@ -198,6 +485,10 @@ impl CornucopiaType {
                 }
                 _ => self.param_ty(is_inner_nullable, ctx),
             },
+            // Slices of `BigDecimal` are written through a wrapper, not as an `ArraySql`
+            CornucopiaType::Array { inner } if inner.is_big_decimal() => {
+                self.param_ty(is_inner_nullable, ctx)
+            }
             CornucopiaType::Array { inner, .. } => {
                 let inner = inner.param_ergo_ty(is_inner_nullable, traits, ctx);
                 let inner = if is_inner_nullable {
@@ -211,7 +502,12 @@ impl CornucopiaType {
             CornucopiaType::Domain { inner, .. } => {
                 inner.param_ergo_ty(is_inner_nullable, traits, ctx)
             }
-            CornucopiaType::Custom { .. } => self.param_ty(is_inner_nullable, ctx),
+            CornucopiaType::Range { .. }
+            | CornucopiaType::Custom { .. }
+            | CornucopiaType::Newtype { .. }
+            | CornucopiaType::Vector { .. }
+            | CornucopiaType::Record { .. }
+            | CornucopiaType::TypedJson { .. } => self.param_ty(is_inner_nullable, ctx),
         }
     }
 
@@ -220,8 +516,16 @@ impl CornucopiaType {
         match self {
             CornucopiaType::Simple { pg_ty, .. } => match *pg_ty {
                 Type::JSON | Type::JSONB => "&'a serde_json::value::Value".to_string(),
+                _ if self.is_big_decimal() => format!("&'a {BIG_DECIMAL}"),
                 _ => self.brw_ty(is_inner_nullable, true, ctx),
             },
+            CornucopiaType::Array { inner } if inner.is_big_decimal() => {
+                if is_inner_nullable {
+                    format!("&'a [Option<{BIG_DECIMAL}>]")
+                } else {
+                    format!("&'a [{BIG_DECIMAL}]")
+                }
+            }
             CornucopiaType::Array { inner, .. } => {
                 let inner = inner.param_ty(is_inner_nullable, ctx);
                 let inner = if is_inner_nullable {
@@ -233,6 +537,13 @@ impl CornucopiaType {
                 format!("&'a [{inner}]")
             }
             CornucopiaType::Domain { inner, .. } => inner.param_ty(false, ctx),
+            CornucopiaType::Range { inner, .. } => {
+                format!(
+                    "{}::Range<{}>",
+                    ctx.client_name(),
+                    inner.param_ty(false, ctx)
+                )
+            }
             CornucopiaType::Custom {
                 is_params,
                 is_copy,
@@ -247,6 +558,11 @@ impl CornucopiaType {
                     self.brw_ty(is_inner_nullable, true, ctx)
                 }
             }
+            CornucopiaType::Newtype { .. } => self.newtype_path(ctx),
+            CornucopiaType::Vector { .. } => types_item_path(VECTOR_STRUCT, ctx),
+            // Records are only returned
+            CornucopiaType::Record { .. } => self.own_ty(false, ctx),
+            CornucopiaType::TypedJson { path, .. } => format!("&'a {path}"),
         }
     }
 
@@ -268,6 +584,10 @@ impl CornucopiaType {
                 Type::JSON | Type::JSONB => {
                     format!("postgres_types::Json<&{lifetime} serde_json::value::RawValue>")
                 }
+                _ if self.is_big_decimal() => {
+                    let lifetime = if has_lifetime { lifetime } else { "'_" };
+                    format!("{}::BigNumeric<{lifetime}>", ctx.client_name())
+                }
                 _ => (*rust_name).to_string(),
             },
             CornucopiaType::Array { inner, .. } => {
@@ -283,6 +603,10 @@ impl CornucopiaType {
                 format!("{client_name}::ArrayIterator<{lifetime}, {inner}>")
             }
             CornucopiaType::Domain { inner, .. } => inner.brw_ty(false, has_lifetime, ctx),
+            CornucopiaType::Range { inner, .. } => {
+                let inner = inner.brw_ty(false, has_lifetime, ctx);
+                format!("{}::Range<{inner}>", ctx.client_name())
+            }
             CornucopiaType::Custom {
                 is_copy,
                 pg_ty,
@@ -296,12 +620,34 @@ impl CornucopiaType {
                     format!("{path}Borrowed<{lifetime}>")
                 }
             }
+            CornucopiaType::Newtype { .. } => self.newtype_path(ctx),
+            CornucopiaType::Vector { .. } => types_item_path(VECTOR_STRUCT, ctx),
+            // Records are decoded as owned structs
+            CornucopiaType::Record { .. } => self.own_ty(false, ctx),
+            // Typed JSON values are deserialized when decoded
+            CornucopiaType::TypedJson { path, .. } => {
+                let lifetime = if has_lifetime { lifetime } else { "'_" };
+                format!("{}::TypedJson<{lifetime}, {path}>", ctx.client_name())
+            }
         }
     }
 }
 
-pub fn custom_ty_path(schema: &str, struct_name: &str, ctx: &GenCtx) -> String {
+/// Path of an item generated at the root of the `types` module
+fn types_item_path(name: &str, ctx: &GenCtx) -> String {
     if ctx.depth == 0 {
+        name.to_string()
+    } else if ctx.depth == 1 {
+        format!("super::{name}")
+    } else {
+        ctx.path(ctx.depth, format_args!("types::{name}"))
+    }
+}
+
+pub fn custom_ty_path(schema: &str, struct_name: &str, ctx: &GenCtx) -> String {
+    if let Some(types_path) = &ctx.types_path {
+        format!("{types_path}::{schema}::{struct_name}")
+    } else if ctx.depth == 0 {
         format!("{schema}::{struct_name}")
     } else if ctx.depth == 1 {
         format!("super::{schema}::{struct_name}")
@@ -314,9 +660,30 @@ pub fn custom_ty_path(schema: &str, struct_name: &str, ctx: &GenCtx) -> String {
 #[derive(Debug, Clone, Default)]
 pub(crate) struct TypeRegistrar {
     pub types: IndexMap<(String, String), Rc<CornucopiaType>>,
+    /// Newtypes by path, each wrapping a single type
+    pub newtypes: IndexMap<String, Rc<CornucopiaType>>,
+    /// Crate of the date and time types
+    date_time_crate: DateTimeCrate,
+    /// Crate of the `numeric` type
+    numeric_crate: NumericCrate,
+    /// OID of the `vector` type of the pgvector extension, if it is installed
+    pub(crate) vector_type: Option<u32>,
 }
 
 impl TypeRegistrar {
+    pub(crate) fn new(
+        date_time_crate: DateTimeCrate,
+        numeric_crate: NumericCrate,
+        vector_type: Option<u32>,
+    ) -> Self {
+        Self {
+            date_time_crate,
+            numeric_crate,
+            vector_type,
+            ..Self::default()
+        }
+    }
+
     pub(crate) fn register(
         &mut self,
         name: &str,
@@ -361,6 +728,15 @@ impl TypeRegistrar {
                     .clone();
                 self.insert(ty, || domain(ty, inner.clone()))
             }
+            Kind::Range(inner_ty) => {
+                let inner = self
+                    .register(name, inner_ty, query_name, module_info)?
+                    .clone();
+                self.insert(ty, || CornucopiaType::Range {
+                    pg_ty: ty.clone(),
+                    inner: inner.clone(),
+                })
+            }
             Kind::Composite(composite_fields) => {
                 let mut is_copy = true;
                 let mut is_params = true;
@@ -382,15 +758,22 @@ impl TypeRegistrar {
                     Type::FLOAT8 => ("f64", true),
                     Type::TEXT | Type::VARCHAR => ("String", false),
                     Type::BYTEA => ("Vec<u8>", false),
-                    Type::TIMESTAMP => ("time::PrimitiveDateTime", true),
-                    Type::TIMESTAMPTZ => ("time::OffsetDateTime", true),
-                    Type::DATE => ("time::Date", true),
-                    Type::TIME => ("time::Time", true),
+                    Type::TIMESTAMP | Type::TIMESTAMPTZ | Type::DATE | Type::TIME => {
+                        (date_time_type(ty, self.date_time_crate), true)
+                    }
                     Type::JSON | Type::JSONB => ("serde_json::Value", false),
                     Type::UUID => ("uuid::Uuid", true),
                     Type::INET => ("std::net::IpAddr", true),
                     Type::MACADDR => ("eui48::MacAddress", true),
-                    Type::NUMERIC => ("rust_decimal::Decimal", true),
+                    Type::NUMERIC => match self.numeric_crate {
+                        NumericCrate::RustDecimal => ("rust_decimal::Decimal", true),
+                        NumericCrate::BigDecimal => (BIG_DECIMAL, false),
+                    },
+                    // Extension types have no fixed OID, and a type of another schema or
+                    // extension may be named `vector` too
+                    _ if Some(ty.oid()) == self.vector_type => {
+                        return Ok(self.insert(ty, || CornucopiaType::Vector { pg_ty: ty.clone() }))
+                    }
                     _ => {
                         return Err(Error::UnsupportedPostgresType {
                             src: module_info.clone().into(),
@@ -417,6 +800,24 @@ impl TypeRegistrar {
         })
     }
 
+    /// Wraps a registered type in a newtype. A newtype must always wrap the same type.
+    pub(crate) fn register_newtype(
+        &mut self,
+        newtype: &Newtype,
+        inner: Rc<CornucopiaType>,
+    ) -> Rc<CornucopiaType> {
+        self.newtypes
+            .entry(newtype.path.clone())
+            .or_insert_with(|| {
+                Rc::new(CornucopiaType::Newtype {
+                    path: newtype.path.clone(),
+                    is_generated: newtype.is_generated(),
+                    inner,
+                })
+            })
+            .clone()
+    }
+
     pub(crate) fn ref_of(&self, ty: &Type) -> Rc<CornucopiaType> {
         self.types
             .get(&SchemaKey::from(ty))
@@ -440,6 +841,20 @@ impl TypeRegistrar {
     }
 }
 
+/// Rust type of a date and time type, from the crate of the settings
+fn date_time_type(ty: &Type, date_time_crate: DateTimeCrate) -> &'static str {
+    match (date_time_crate, ty) {
+        (DateTimeCrate::Time, &Type::TIMESTAMP) => "time::PrimitiveDateTime",
+        (DateTimeCrate::Time, &Type::TIMESTAMPTZ) => "time::OffsetDateTime",
+        (DateTimeCrate::Time, &Type::DATE) => "time::Date",
+        (DateTimeCrate::Time, _) => "time::Time",
+        (DateTimeCrate::Chrono, &Type::TIMESTAMP) => "chrono::NaiveDateTime",
+        (DateTimeCrate::Chrono, &Type::TIMESTAMPTZ) => "chrono::DateTime<chrono::Utc>",
+        (DateTimeCrate::Chrono, &Type::DATE) => "chrono::NaiveDate",
+        (DateTimeCrate::Chrono, _) => "chrono::NaiveTime",
+    }
+}
+
 impl std::ops::Index<&Type> for TypeRegistrar {
     type Output = Rc<CornucopiaType>;
 
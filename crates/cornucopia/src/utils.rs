@@ -1,5 +1,6 @@
 use indexmap::Equivalent;
-use postgres::error::ErrorPosition;
+use miette::{NamedSource, SourceSpan};
+use postgres::error::{DbError, ErrorPosition, SqlState};
 use postgres_types::Type;
 
 /// Allows us to query a map using type schema as key without having to own the key strings
@@ -24,7 +25,7 @@ impl<'a> Equivalent<(String, String)> for SchemaKey<'a> {
     }
 }
 
-pub fn find_duplicate<T>(slice: &[T], eq: fn(&T, &T) -> bool) -> Option<(&T, &T)> {
+pub fn find_duplicate<T>(slice: &[T], eq: impl Fn(&T, &T) -> bool) -> Option<(&T, &T)> {
     for (i, first) in slice.iter().enumerate() {
         if let Some(second) = slice[i + 1..].iter().find(|second| eq(first, second)) {
             return Some((first, second));
@@ -33,6 +34,21 @@ pub fn find_duplicate<T>(slice: &[T], eq: fn(&T, &T) -> bool) -> Option<(&T, &T)
     None
 }
 
+/// Number of single character insertions, deletions and substitutions turning `a` into `b`
+pub(crate) fn edit_distance(a: &str, b: &str) -> usize {
+    let b: Vec<_> = b.chars().collect();
+    let mut prev: Vec<_> = (0..=b.len()).collect();
+    for (i, ca) in a.chars().enumerate() {
+        let mut row = vec![i + 1];
+        for (j, cb) in b.iter().enumerate() {
+            let cost = usize::from(ca != *cb);
+            row.push((prev[j] + cost).min(prev[j + 1] + 1).min(row[j] + 1));
+        }
+        prev = row;
+    }
+    prev[b.len()]
+}
+
 /// Extracts useful info from a `postgres`-generated error.
 pub(crate) fn db_err(err: &postgres::Error) -> Option<(u32, String, Option<String>)> {
     if let Some(db_err) = err.as_db_error() {
@@ -40,7 +56,7 @@ pub(crate) fn db_err(err: &postgres::Error) -> Option<(u32, String, Option<Strin
             Some((
                 *position,
                 db_err.message().to_string(),
-                db_err.hint().map(String::from),
+                extension_hint(db_err).or_else(|| db_err.hint().map(String::from)),
             ))
         } else {
             None
@@ -50,9 +66,181 @@ pub(crate) fn db_err(err: &postgres::Error) -> Option<(u32, String, Option<Strin
     }
 }
 
+/// Byte offset of the character at this index, PostgreSQL error positions being counted
+/// in characters
+pub(crate) fn char_offset(text: &str, chars: usize) -> usize {
+    text.char_indices()
+        .nth(chars)
+        .map_or(text.len(), |(i, _)| i)
+}
+
+/// Maximum number of characters of a line displayed in an error snippet
+const SNIPPET_WIDTH: usize = 120;
+
+/// Replaces control characters, except line breaks and tabs, so that printing a snippet
+/// doesn't corrupt the terminal. Byte offsets are preserved.
+pub(crate) fn sanitize(text: &str) -> String {
+    let mut sanitized = String::with_capacity(text.len());
+    for c in text.chars() {
+        if c.is_control() && !matches!(c, '\n' | '\r' | '\t') {
+            sanitized.extend(std::iter::repeat_n('?', c.len_utf8()));
+        } else {
+            sanitized.push(c);
+        }
+    }
+    sanitized
+}
+
+/// Source of an error snippet pointing at `span`. The line of the span is truncated
+/// around it, with `…` markers, when it is too long to be displayed.
+pub(crate) fn snippet(name: &str, text: &str, span: SourceSpan) -> (NamedSource, SourceSpan) {
+    let text = sanitize(text);
+    let start = span.offset().min(text.len());
+    let line_start = text[..start].rfind('\n').map_or(0, |i| i + 1);
+    let line_end = text[start..].find('\n').map_or(text.len(), |i| start + i);
+    let line = &text[line_start..line_end];
+    let width = line.chars().count();
+    if width <= SNIPPET_WIDTH {
+        return (NamedSource::new(name, text), span);
+    }
+    let column = text[line_start..start].chars().count();
+    let first = column
+        .saturating_sub(SNIPPET_WIDTH / 2)
+        .min(width - SNIPPET_WIDTH);
+    let (from, to) = (
+        char_offset(line, first),
+        char_offset(line, first + SNIPPET_WIDTH),
+    );
+    let prefix = if from > 0 { "…" } else { "" };
+    let suffix = if to < line.len() { "…" } else { "" };
+    let truncated = format!(
+        "{}{prefix}{}{suffix}{}",
+        &text[..line_start],
+        &line[from..to],
+        &text[line_end..]
+    );
+    let offset = line_start + prefix.len() + (start - line_start - from);
+    let len = span.len().min(line_start + to - start);
+    (NamedSource::new(name, truncated), (offset, len).into())
+}
+
+/// Functions and types provided by commonly used extensions
+const EXTENSION_OBJECTS: [(&str, &[&str]); 11] = [
+    (
+        "pgcrypto",
+        &[
+            "gen_random_uuid",
+            "gen_random_bytes",
+            "crypt",
+            "gen_salt",
+            "digest",
+            "hmac",
+            "encrypt",
+            "decrypt",
+            "pgp_sym_encrypt",
+            "pgp_sym_decrypt",
+            "pgp_pub_encrypt",
+            "pgp_pub_decrypt",
+        ],
+    ),
+    (
+        "uuid-ossp",
+        &[
+            "uuid_generate_v1",
+            "uuid_generate_v1mc",
+            "uuid_generate_v3",
+            "uuid_generate_v4",
+            "uuid_generate_v5",
+            "uuid_nil",
+        ],
+    ),
+    (
+        "pg_trgm",
+        &[
+            "similarity",
+            "word_similarity",
+            "strict_word_similarity",
+            "show_trgm",
+        ],
+    ),
+    (
+        "fuzzystrmatch",
+        &[
+            "levenshtein",
+            "levenshtein_less_equal",
+            "soundex",
+            "difference",
+            "metaphone",
+            "dmetaphone",
+        ],
+    ),
+    ("unaccent", &["unaccent"]),
+    ("citext", &["citext"]),
+    ("hstore", &["hstore", "akeys", "avals", "skeys", "svals"]),
+    (
+        "ltree",
+        &[
+            "ltree",
+            "lquery",
+            "ltxtquery",
+            "subltree",
+            "subpath",
+            "nlevel",
+        ],
+    ),
+    ("earthdistance", &["earth_distance", "ll_to_earth"]),
+    ("tablefunc", &["crosstab", "normal_rand", "connectby"]),
+    (
+        "vector",
+        &[
+            "vector",
+            "halfvec",
+            "sparsevec",
+            "l2_distance",
+            "cosine_distance",
+        ],
+    ),
+];
+
+/// Hints at the extension providing the function or type reported missing by this error
+pub(crate) fn extension_hint(db_err: &DbError) -> Option<String> {
+    let message = db_err.message();
+    let name = if *db_err.code() == SqlState::UNDEFINED_FUNCTION {
+        message.strip_prefix("function ")?.split('(').next()?
+    } else if *db_err.code() == SqlState::UNDEFINED_OBJECT {
+        message.strip_prefix("type \"")?.split('"').next()?
+    } else {
+        return None;
+    };
+    let name = name.rsplit('.').next().unwrap_or(name);
+    let extension = if name.starts_with("st_") || matches!(name, "geometry" | "geography") {
+        "postgis"
+    } else {
+        EXTENSION_OBJECTS
+            .iter()
+            .find(|(_, objects)| objects.contains(&name))?
+            .0
+    };
+    let quoted = if extension.contains('-') {
+        format!("\"{extension}\"")
+    } else {
+        extension.to_string()
+    };
+    Some(format!(
+        "`{name}` is provided by the `{extension}` extension, which is not installed: add `CREATE EXTENSION {quoted};` to a migration"
+    ))
+}
+
 /// Sorted list of rust reserved keywords that cannot be escaped
 pub(crate) const STRICT_KEYWORD: [&str; 5] = ["Self", "_", "crate", "self", "super"];
 
+/// Sorted list of the prelude names the generated code uses unqualified, that a generated
+/// struct cannot be named after
+pub(crate) const PRELUDE_NAME: [&str; 16] = [
+    "Box", "Clone", "Copy", "Default", "FnOnce", "From", "Hash", "Into", "Iterator", "Option",
+    "Result", "Send", "String", "Sync", "Unpin", "Vec",
+];
+
 /// Sorted list of rust reserved keywords
 pub(crate) const KEYWORD: [&str; 53] = [
     "Self", "_", "abstract", "as", "async", "await", "become", "box", "break", "const", "continue",
@@ -1,16 +1,28 @@
-use std::collections::BTreeMap;
+use std::{collections::BTreeMap, path::Path, rc::Rc};
 
 use crate::{
-    parser::{Module, NullableIdent, Query, QueryDataStruct, Span, TypeAnnotation},
-    prepare_queries::{PreparedField, PreparedModule},
+    analysis::statement_commands,
+    codegen::GenCtx,
+    database::{Column, ColumnOrigin},
+    parser::{
+        BindNewtype, Lock, Module, NullableIdent, Query, QueryDataStruct, QueryOption, Span,
+        TypeAnnotation, VersionRange,
+    },
+    prepare_queries::{
+        deprecation, Arity, CacheTtl, CopyFormat, PreparedField, PreparedItem, PreparedModule,
+        QueryBudget, LOCK_FN_PREFIXES,
+    },
+    progress::Warning,
     read_queries::ModuleInfo,
-    utils::{find_duplicate, STRICT_KEYWORD},
+    settings::{matches_only, NaiveTimestampPolicy, Newtype},
+    type_registrar::CornucopiaType,
+    utils::{edit_distance, find_duplicate, PRELUDE_NAME, STRICT_KEYWORD},
 };
 
 use error::Error;
+use indexmap::IndexMap;
 use miette::SourceSpan;
-use postgres::Column;
-use postgres_types::Type;
+use postgres_types::{Kind, Type};
 
 pub(crate) fn duplicate_nullable_ident(
     info: &ModuleInfo,
@@ -29,12 +41,21 @@ pub(crate) fn duplicate_nullable_ident(
 pub(crate) fn duplicate_sql_col_name(
     info: &ModuleInfo,
     query_name: &Span<String>,
+    sql: &str,
     cols: &[Column],
 ) -> Result<(), Box<Error>> {
     find_duplicate(cols, |a, b| a.name() == b.name()).map_or(Ok(()), |(_, second)| {
+        // Data-modifying statements return their rows using a `RETURNING` clause
+        let clause = match statement_commands(sql).first().map(String::as_str) {
+            Some(command @ ("INSERT" | "UPDATE" | "DELETE" | "MERGE")) => {
+                format!("the `RETURNING` clause of your `{command}`")
+            }
+            _ => "your SQL".to_string(),
+        };
         Err(Box::new(Error::DuplicateSqlColName {
             src: info.clone().into(),
             name: second.name().to_string(),
+            clause,
             pos: query_name.span,
         }))
     })
@@ -44,7 +65,10 @@ pub(crate) fn query_name_already_used(
     info: &ModuleInfo,
     queries: &[Query],
 ) -> Result<(), Box<Error>> {
-    find_duplicate(queries, |a, b| a.name == b.name).map_or(Ok(()), |(first, second)| {
+    // Variants of a query declared for different server versions share its name
+    let is_duplicate =
+        |a: &Query, b: &Query| a.name == b.name && (a.version.is_none() || b.version.is_none());
+    find_duplicate(queries, is_duplicate).map_or(Ok(()), |(first, second)| {
         Err(Box::new(Error::DuplicateType {
             src: info.into(),
             ty: "query",
@@ -55,6 +79,118 @@ pub(crate) fn query_name_already_used(
     })
 }
 
+pub(crate) fn lock_name_already_used(info: &ModuleInfo, locks: &[Lock]) -> Result<(), Box<Error>> {
+    find_duplicate(locks, |a, b| a.name == b.name).map_or(Ok(()), |(first, second)| {
+        Err(Box::new(Error::DuplicateType {
+            src: info.into(),
+            ty: "lock",
+            name: first.name.value.clone(),
+            first: first.name.span,
+            second: second.name.span,
+        }))
+    })
+}
+
+/// Locks of different names must not share a key, as they would exclude each other.
+/// Locks of the same name are the same lock, whatever module declares them.
+pub(crate) fn lock_keys(modules: &[PreparedModule]) -> Result<(), Box<Error>> {
+    let mut keys: BTreeMap<i64, &str> = BTreeMap::new();
+    for module in modules {
+        for lock in &module.locks {
+            match keys.insert(lock.key, &lock.name.value) {
+                Some(prev) if prev != lock.name.value => {
+                    return Err(Box::new(Error::LockKeyCollision {
+                        src: (&module.info).into(),
+                        name: lock.name.value.clone(),
+                        prev: prev.to_string(),
+                        key: lock.key,
+                        pos: lock.name.span,
+                    }));
+                }
+                _ => {}
+            }
+        }
+    }
+    Ok(())
+}
+
+/// Checks that the variants of each query cover every server version exactly once, and
+/// bind the same parameters
+pub(crate) fn query_variants(info: &ModuleInfo, queries: &[Query]) -> Result<(), Box<Error>> {
+    let mut variants: IndexMap<&str, Vec<(&Query, &Span<VersionRange>)>> = IndexMap::new();
+    for query in queries {
+        if let Some(version) = &query.version {
+            if version.value.min.unwrap_or(0) >= version.value.max.unwrap_or(u32::MAX) {
+                return Err(Box::new(Error::EmptyVersionRange {
+                    src: info.into(),
+                    range: version.value.to_string(),
+                    pos: version.span,
+                }));
+            }
+            variants
+                .entry(&query.name.value)
+                .or_default()
+                .push((query, version));
+        }
+    }
+    for (name, mut variants) in variants {
+        let (first, _) = variants[0];
+        if let Some((query, _)) = variants.iter().find(|(it, _)| {
+            let names = |query: &Query| -> Vec<String> {
+                query
+                    .bind_params
+                    .iter()
+                    .map(|it| it.value.clone())
+                    .collect()
+            };
+            names(it) != names(first)
+        }) {
+            return Err(Box::new(Error::VariantParams {
+                src: info.into(),
+                name: name.to_string(),
+                first: first.name.span,
+                second: query.name.span,
+            }));
+        }
+        variants.sort_by_key(|(_, it)| it.value.min);
+        let gap = |min, max, pos: &Span<VersionRange>| {
+            Box::new(Error::VariantGap {
+                src: info.into(),
+                name: name.to_string(),
+                range: VersionRange { min, max }.to_string(),
+                pos: pos.span,
+            })
+        };
+        let (_, lowest) = variants[0];
+        if let Some(min) = lowest.value.min {
+            return Err(gap(None, Some(min), lowest));
+        }
+        for pair in variants.windows(2) {
+            let [(_, prev), (_, next)] = pair else {
+                unreachable!()
+            };
+            let next_min = next.value.min.unwrap();
+            match prev.value.max {
+                Some(max) if max == next_min => {}
+                Some(max) if max < next_min => return Err(gap(Some(max), Some(next_min), prev)),
+                _ => {
+                    return Err(Box::new(Error::OverlappingVariants {
+                        src: info.into(),
+                        name: name.to_string(),
+                        first: prev.span,
+                        second: next.span,
+                    }))
+                }
+            }
+        }
+        let (_, highest) = variants[variants.len() - 1];
+        if let Some(max) = highest.value.max {
+            return Err(gap(Some(max), None, highest));
+        }
+    }
+    Ok(())
+}
+
 pub(crate) fn named_type_already_used(
     info: &ModuleInfo,
     types: &[TypeAnnotation],
@@ -115,16 +251,492 @@ pub(crate) fn nullable_column_name(
         .iter()
         .all(|row_col| row_col.name() != nullable_col.name.value)
     {
-        return Err(Box::new(Error::UnknownFieldName {
+        let known: Vec<_> = stmt_cols.iter().map(Column::name).collect();
+        return Err(unknown_field(info, &nullable_col.name, &known));
+    }
+    Ok(())
+}
+
+/// Error of a field name matching none of the `known` ones, suggesting the closest one
+fn unknown_field(info: &ModuleInfo, name: &Span<String>, known: &[&str]) -> Box<Error> {
+    let closest = known
+        .iter()
+        .map(|it| (edit_distance(&name.value, it), *it))
+        .filter(|(distance, _)| *distance <= (name.value.len() / 3).max(1))
+        .min();
+    let names = known.join(", ");
+    let help = match closest {
+        Some((_, it)) => format!("did you mean `{it}`? use one of those names: {names}"),
+        None => format!("use one of those names: {names}"),
+    };
+    Box::new(Error::UnknownFieldName {
+        src: info.into(),
+        pos: name.span,
+        help,
+    })
+}
+
+/// Resolves the types of the fields declared for the anonymous record returned in `col`,
+/// returning `None` when it is not declared as a record
+pub(crate) fn record_column(
+    info: &ModuleInfo,
+    query_name: &Span<String>,
+    col: &Column,
+    nullity: Option<&NullableIdent>,
+) -> Result<Option<Vec<Type>>, Box<Error>> {
+    let is_record = *col.type_() == Type::RECORD;
+    let Some((annotation, fields)) = nullity.and_then(|it| Some((it, it.record.as_ref()?))) else {
+        if is_record {
+            return Err(Box::new(Error::UndeclaredRecord {
+                src: info.into(),
+                name: col.name().to_string(),
+                pos: query_name.span,
+            }));
+        }
+        return Ok(None);
+    };
+    if !is_record {
+        return Err(Box::new(Error::NotARecord {
+            src: info.into(),
+            name: col.name().to_string(),
+            ty: col.type_().to_string(),
+            pos: annotation.name.span,
+        }));
+    }
+    if let Some((first, second)) = find_duplicate(fields, |a, b| a.name == b.name) {
+        return Err(Box::new(Error::DuplicateRecordField {
+            src: info.into(),
+            name: first.name.value.clone(),
+            first: first.name.span,
+            second: second.name.span,
+        }));
+    }
+    fields
+        .iter()
+        .map(|field| {
+            builtin_type(&field.ty.value).ok_or_else(|| {
+                Box::new(Error::UnknownRecordFieldType {
+                    src: info.into(),
+                    ty: field.ty.value.clone(),
+                    pos: field.ty.span,
+                })
+            })
+        })
+        .collect::<Result<_, _>>()
+        .map(Some)
+}
+
+/// The Rust type declared for the `json` or `jsonb` value `name` of type `ty`, if any
+pub(crate) fn json_type<'a>(
+    info: &ModuleInfo,
+    name: &str,
+    ty: &Type,
+    nullity: Option<&'a NullableIdent>,
+) -> Result<Option<&'a Span<String>>, Box<Error>> {
+    let Some(path) = nullity.and_then(|it| it.json_type.as_ref()) else {
+        return Ok(None);
+    };
+    if !matches!(*ty, Type::JSON | Type::JSONB) {
+        return Err(Box::new(Error::NotJson {
+            src: info.into(),
+            name: name.to_string(),
+            ty: ty.to_string(),
+            pos: path.span,
+        }));
+    }
+    Ok(Some(path))
+}
+
+/// The built-in type of this name, arrays ending with `[]`
+fn builtin_type(name: &str) -> Option<Type> {
+    let name = match name.strip_suffix("[]") {
+        Some(inner) => format!("_{inner}"),
+        None => name.to_string(),
+    };
+    // Built-in types are only known by their OID
+    (0..=u32::from(u16::MAX))
+        .filter_map(Type::from_oid)
+        .find(|it| it.name() == name)
+}
+
+pub(crate) fn record_param(
+    info: &ModuleInfo,
+    nullable_col: &NullableIdent,
+) -> Result<(), Box<Error>> {
+    if nullable_col.record.is_some() {
+        return Err(Box::new(Error::RecordParam {
             src: info.into(),
             pos: nullable_col.name.span,
-            known: stmt_cols
+        }));
+    }
+    Ok(())
+}
+
+pub(crate) fn allow_naive_column_name(
+    info: &ModuleInfo,
+    col_name: &Span<String>,
+    stmt_cols: &[Column],
+) -> Result<(), Box<Error>> {
+    if stmt_cols.iter().all(|it| it.name() != col_name.value) {
+        let known: Vec<_> = stmt_cols.iter().map(Column::name).collect();
+        return Err(unknown_field(info, col_name, &known));
+    }
+    Ok(())
+}
+
+/// Whether a type contains a `timestamp` without time zone
+fn is_naive_timestamp(ty: &Type) -> bool {
+    match ty.kind() {
+        Kind::Array(inner) | Kind::Domain(inner) | Kind::Range(inner) | Kind::Multirange(inner) => {
+            is_naive_timestamp(inner)
+        }
+        Kind::Composite(fields) => fields.iter().any(|it| is_naive_timestamp(it.type_())),
+        _ => *ty == Type::TIMESTAMP,
+    }
+}
+
+/// Applies the naive timestamp policy to a returned column
+pub(crate) fn naive_timestamp(
+    info: &ModuleInfo,
+    query_name: &Span<String>,
+    col: &Column,
+    policy: NaiveTimestampPolicy,
+) -> Result<Option<Warning>, Box<Error>> {
+    if policy == NaiveTimestampPolicy::Allow || !is_naive_timestamp(col.type_()) {
+        return Ok(None);
+    }
+    let (name, column, ty, pos) = (
+        query_name.value.clone(),
+        col.name().to_string(),
+        col.type_().name().to_string(),
+        query_name.span,
+    );
+    if policy == NaiveTimestampPolicy::Warn {
+        return Ok(Some(Warning::new(error::NaiveTimestampWarning {
+            src: info.into(),
+            name,
+            column,
+            ty,
+            pos,
+        })));
+    }
+    Err(Box::new(Error::NaiveTimestamp {
+        src: info.into(),
+        name,
+        column,
+        ty,
+        pos,
+    }))
+}
+
+/// Warns about queries returning a table column whose comment starts with the deprecation
+/// `marker`
+pub(crate) fn deprecated_column(
+    info: &ModuleInfo,
+    query_name: &Span<String>,
+    col: &Column,
+    marker: &str,
+) -> Option<Warning> {
+    let comment = deprecation(col, marker)?;
+    let origin = col.origin()?;
+    Some(Warning::new(error::DeprecatedColumnWarning {
+        src: info.into(),
+        name: query_name.value.clone(),
+        column: format!("{}.{}", origin.table, origin.column),
+        comment,
+        pos: query_name.span,
+    }))
+}
+
+/// Warns about the row declarations of a column disagreeing with the nullability file,
+/// which they take precedence over
+pub(crate) fn nullability_conflict(
+    info: &ModuleInfo,
+    declared: &NullableIdent,
+    origin: &ColumnOrigin,
+    nullable: bool,
+    path: &Path,
+) -> Option<Warning> {
+    let nullity = |nullable: bool| if nullable { "nullable" } else { "not null" };
+    (declared.nullable != nullable).then(|| {
+        Warning::new(error::NullabilityConflictWarning {
+            src: info.into(),
+            name: declared.name.value.clone(),
+            column: format!("{}.{}.{}", origin.schema, origin.table, origin.column),
+            declared: nullity(declared.nullable),
+            file: nullity(nullable),
+            path: path.to_string_lossy().to_string(),
+            pos: declared.name.span,
+        })
+    })
+}
+
+/// Warns about inserts skipping conflicts declared to return exactly one row, which fail
+/// instead of returning `None` on conflict
+pub(crate) fn conflict_arity(
+    info: &ModuleInfo,
+    query_name: &Span<String>,
+    option: &QueryOption,
+) -> Warning {
+    Warning::new(error::ConflictArityWarning {
+        src: info.into(),
+        name: query_name.value.clone(),
+        pos: option.args[0].span,
+    })
+}
+
+/// Queries that are not prepared can only send parameters of built-in types, whose
+/// OIDs are the same in every database
+pub(crate) fn unprepared_param(
+    info: &ModuleInfo,
+    query_name: &Span<String>,
+    query: &SourceSpan,
+    param: &Span<String>,
+    ty: &Type,
+) -> Result<(), Box<Error>> {
+    if Type::from_oid(ty.oid()).is_none() {
+        // Bind parameters are located relatively to the query
+        let pos = (query.offset() + param.span.offset(), param.span.len()).into();
+        return Err(Box::new(Error::UnpreparedCustomParam {
+            src: info.into(),
+            name: query_name.value.clone(),
+            param: param.value.clone(),
+            ty: ty.name().to_string(),
+            pos,
+        }));
+    }
+    Ok(())
+}
+
+/// Cached results are keyed by the hash of the parameters, which must all implement `Hash`
+pub(crate) fn cached_param(
+    info: &ModuleInfo,
+    query_name: &Span<String>,
+    query: &SourceSpan,
+    param: &Span<String>,
+    field: &PreparedField,
+    ty: &Type,
+) -> Result<(), Box<Error>> {
+    if !field.ty.is_hash() {
+        // Bind parameters are located relatively to the query
+        let pos = (query.offset() + param.span.offset(), param.span.len()).into();
+        return Err(Box::new(Error::UncachableParam {
+            src: info.into(),
+            name: query_name.value.clone(),
+            param: param.value.clone(),
+            ty: ty.name().to_string(),
+            pos,
+        }));
+    }
+    Ok(())
+}
+
+/// Newtypes map a `table.column` or a domain to a newtype name or path
+pub(crate) fn newtypes(newtypes: &[Newtype]) -> Result<(), Box<Error>> {
+    let is_ident = |it: &str| {
+        it.chars().next().is_some_and(|c| !c.is_ascii_digit())
+            && it.chars().all(|c| c.is_ascii_alphanumeric() || c == '_')
+    };
+    for newtype in newtypes {
+        let target_is_valid = newtype.target.split('.').count() <= 2
+            && newtype.target.split('.').all(|it| !it.is_empty());
+        if !target_is_valid || !newtype.path.split("::").all(is_ident) {
+            return Err(Box::new(Error::InvalidNewtype {
+                target: newtype.target.clone(),
+                path: newtype.path.clone(),
+            }));
+        }
+    }
+    Ok(())
+}
+
+/// Renamed identifiers must be valid and stay distinct, the original names of the
+/// identifiers renamed alike being reported otherwise
+pub(crate) fn renames(
+    kind: &str,
+    scope: &str,
+    renamed: &[(String, String)],
+) -> Result<(), Box<Error>> {
+    let is_ident = |it: &str| {
+        it.chars().next().is_some_and(|c| !c.is_ascii_digit())
+            && it.chars().all(|c| c.is_ascii_alphanumeric() || c == '_')
+    };
+    if let Some((name, it)) = renamed
+        .iter()
+        .find(|(name, it)| name != it && !is_ident(it))
+    {
+        return Err(Box::new(Error::InvalidRename {
+            name: name.clone(),
+            scope: scope.to_string(),
+            renamed: it.clone(),
+        }));
+    }
+    // Identifiers that are not renamed are already distinct
+    for (i, (first, it)) in renamed.iter().enumerate() {
+        if let Some((second, _)) = renamed[i + 1..]
+            .iter()
+            .find(|(second, other)| other == it && (first != it || second != other))
+        {
+            return Err(Box::new(Error::RenameCollision {
+                names: format!("{kind} `{first}` and `{second}`{scope}"),
+                renamed: it.clone(),
+            }));
+        }
+    }
+    Ok(())
+}
+
+/// Every pattern of the allowlist must match some query, the closest query names being
+/// suggested otherwise
+pub(crate) fn only_patterns<'a>(
+    patterns: impl Iterator<Item = &'a str>,
+    modules: &[Module],
+) -> Result<(), Box<Error>> {
+    let names = || {
+        modules.iter().flat_map(|module| {
+            let name = &module.info.name;
+            module
+                .queries
                 .iter()
-                .map(|it| it.name().to_string())
-                .collect::<Vec<_>>()
-                .join(", "),
+                .map(move |query| (name.as_str(), query.name.value.as_str()))
+        })
+    };
+    for pattern in patterns {
+        if names().any(|(module, query)| matches_only(pattern, module, query)) {
+            continue;
+        }
+        // Patterns selecting a whole module are compared to module globs
+        let mut candidates: Vec<_> = modules
+            .iter()
+            .map(|it| format!("{}::*", it.info.name))
+            .chain(names().map(|(module, query)| format!("{module}::{query}")))
+            .map(|it| (edit_distance(pattern, &it), it))
+            .filter(|(distance, _)| *distance <= (pattern.len() / 3).max(1))
+            .collect();
+        candidates.sort();
+        let help = if candidates.is_empty() {
+            "select queries using `module::query`, a `*` matching any sequence of characters like in `module::*`".to_string()
+        } else {
+            let closest: Vec<_> = candidates
+                .iter()
+                .take(3)
+                .map(|(_, it)| format!("`{it}`"))
+                .collect();
+            format!("the closest queries are {}", closest.join(", "))
+        };
+        return Err(Box::new(Error::UnknownOnlyPattern {
+            pattern: pattern.to_string(),
+            help,
+        }));
+    }
+    Ok(())
+}
+
+/// Checks that each `key=value` tag selecting the generated queries is declared by a query
+pub(crate) fn only_tags<'a>(
+    tags: impl Iterator<Item = &'a str>,
+    modules: &[Module],
+) -> Result<(), Box<Error>> {
+    let mut known: Vec<_> = modules
+        .iter()
+        .flat_map(|it| &it.queries)
+        .flat_map(|it| it.tags().map(|(key, value)| format!("{key}={value}")))
+        .collect();
+    known.sort();
+    known.dedup();
+    for tag in tags {
+        if known.iter().any(|it| it == tag) {
+            continue;
+        }
+        let help = if known.is_empty() {
+            "tag queries using the `tag` option, like `-- :tag team=payments`".to_string()
+        } else {
+            let known: Vec<_> = known.iter().map(|it| format!("`{it}`")).collect();
+            format!("the declared tags are {}", known.join(", "))
+        };
+        return Err(Box::new(Error::UnknownOnlyTag {
+            tag: tag.to_string(),
+            help,
+        }));
+    }
+    Ok(())
+}
+
+/// Finds the newtype a bind parameter is annotated with, using `:param@Newtype`
+pub(crate) fn param_newtype<'a>(
+    info: &ModuleInfo,
+    query: &SourceSpan,
+    bind_newtypes: &[BindNewtype],
+    param: &Span<String>,
+    newtypes: &'a [Newtype],
+) -> Result<Option<(&'a Newtype, SourceSpan)>, Box<Error>> {
+    // Bind parameters are located relatively to the query
+    let pos = |it: &Span<String>| (query.offset() + it.span.offset(), it.span.len()).into();
+    let mut annotations = bind_newtypes
+        .iter()
+        .filter(|(it, _)| it == param)
+        .map(|(_, newtype)| newtype);
+    let Some(first) = annotations.next() else {
+        return Ok(None);
+    };
+    if let Some(second) = annotations.find(|it| *it != first) {
+        return Err(Box::new(Error::ConflictingParamNewtype {
+            src: info.into(),
+            param: param.value.clone(),
+            first: pos(first),
+            second: pos(second),
         }));
     }
+    match newtypes.iter().find(|it| it.name() == first.value) {
+        Some(newtype) => Ok(Some((newtype, pos(first)))),
+        None => {
+            let help = if newtypes.is_empty() {
+                "map this newtype to a table column or a domain in your settings".to_string()
+            } else {
+                let known: Vec<_> = newtypes.iter().map(Newtype::name).collect();
+                format!("use one of those newtypes: {}", known.join(", "))
+            };
+            Err(Box::new(Error::UnknownNewtype {
+                src: info.into(),
+                name: first.value.clone(),
+                pos: pos(first),
+                help,
+            }))
+        }
+    }
+}
+
+/// Newtypes wrap a single copyable type
+pub(crate) fn newtype_inner(
+    info: &ModuleInfo,
+    newtype: &Newtype,
+    field: &str,
+    inner: &CornucopiaType,
+    registered: Option<&Rc<CornucopiaType>>,
+    pos: SourceSpan,
+) -> Result<(), Box<Error>> {
+    let ty = inner.pg_ty().name().to_string();
+    if !inner.is_copy() {
+        return Err(Box::new(Error::NewtypeNotCopy {
+            src: info.into(),
+            newtype: newtype.path.clone(),
+            field: field.to_string(),
+            ty,
+            pos,
+        }));
+    }
+    if let Some(CornucopiaType::Newtype { inner: first, .. }) = registered.map(Rc::as_ref) {
+        if first.as_ref() != inner {
+            return Err(Box::new(Error::NewtypeConflict {
+                src: info.into(),
+                newtype: newtype.path.clone(),
+                first: first.pg_ty().name().to_string(),
+                field: field.to_string(),
+                ty,
+                pos,
+            }));
+        }
+    }
     Ok(())
 }
 
@@ -138,14 +750,51 @@ pub(crate) fn nullable_param_name(
         .iter()
         .all(|(name, _)| name.value != nullable_col.name.value)
     {
-        return Err(Box::new(Error::UnknownFieldName {
+        let known: Vec<_> = params.iter().map(|it| it.0.value.as_str()).collect();
+        return Err(unknown_field(info, &nullable_col.name, &known));
+    }
+    Ok(())
+}
+
+/// Only the elements of arrays can be declared nullable with `[?]`
+pub(crate) fn inner_nullable_array(
+    info: &ModuleInfo,
+    nullity: Option<&NullableIdent>,
+    ty: &Type,
+) -> Result<(), Box<Error>> {
+    fn is_array(ty: &Type) -> bool {
+        match ty.kind() {
+            Kind::Array(_) => true,
+            Kind::Domain(inner) => is_array(inner),
+            _ => false,
+        }
+    }
+    match nullity {
+        Some(nullable_col) if nullable_col.inner_nullable && !is_array(ty) => {
+            Err(Box::new(Error::InnerNullableNotArray {
+                src: info.into(),
+                name: nullable_col.name.value.clone(),
+                ty: ty.name().to_string(),
+                pos: nullable_col.name.span,
+            }))
+        }
+        _ => Ok(()),
+    }
+}
+
+/// A parameter of a domain declared `NOT NULL` cannot be bound to `NULL`
+pub(crate) fn nullable_not_null_domain(
+    info: &ModuleInfo,
+    nullable_col: &NullableIdent,
+    ty: &Type,
+    not_null: &[u32],
+) -> Result<(), Box<Error>> {
+    if nullable_col.nullable && not_null.contains(&ty.oid()) {
+        return Err(Box::new(Error::NullableNotNullDomain {
             src: info.into(),
+            name: nullable_col.name.value.clone(),
+            ty: ty.name().to_string(),
             pos: nullable_col.name.span,
-            known: params
-                .iter()
-                .map(|it| it.0.value.to_string())
-                .collect::<Vec<_>>()
-                .join(", "),
         }));
     }
     Ok(())
@@ -169,6 +818,329 @@ pub(crate) fn row_on_execute(
     Ok(())
 }
 
+/// Options a query can declare
+const QUERY_OPTIONS: [&str; 12] = [
+    "allow_naive",
+    "arity",
+    "batch",
+    "budget",
+    "cache",
+    "copy_in",
+    "copy_out",
+    "into",
+    "on_error",
+    "prepared",
+    "setup",
+    "tag",
+];
+
+/// Query options that only make sense for queries returning rows
+const ROW_OPTIONS: [&str; 5] = ["allow_naive", "arity", "cache", "copy_out", "into"];
+
+pub(crate) fn row_option_on_execute(
+    info: &ModuleInfo,
+    name: &Span<String>,
+    query: &SourceSpan,
+    options: &[QueryOption],
+    columns: &[Column],
+) -> Result<(), Box<Error>> {
+    if !columns.is_empty() {
+        return Ok(());
+    }
+    if let Some(option) = options
+        .iter()
+        .find(|it| ROW_OPTIONS.contains(&it.name.value.as_str()))
+    {
+        return Err(Box::new(Error::RowOptionOnExecute {
+            src: info.into(),
+            name: name.value.clone(),
+            option_name: option.name.value.clone(),
+            option: option.name.span,
+            query: *query,
+        }));
+    }
+    Ok(())
+}
+
+/// Query options that can be declared multiple times
+const REPEATABLE_OPTIONS: [&str; 2] = ["on_error", "tag"];
+
+fn query_options(info: &ModuleInfo, options: &[QueryOption]) -> Result<(), Box<Error>> {
+    find_duplicate(options, |a, b| {
+        a.name == b.name && !REPEATABLE_OPTIONS.contains(&a.name.value.as_str())
+    })
+    .map_or(Ok(()), |(first, second)| {
+        Err(Box::new(Error::DuplicateType {
+            src: info.into(),
+            ty: "option",
+            name: first.name.value.clone(),
+            first: first.name.span,
+            second: second.name.span,
+        }))
+    })?;
+    let on_error: Vec<_> = options
+        .iter()
+        .filter(|it| it.name.value == "on_error")
+        .collect();
+    for option in options {
+        match option.name.value.as_str() {
+            "arity" => match option.args.as_slice() {
+                [arg] if arg.value.parse::<Arity>().is_ok() => {}
+                args => {
+                    return Err(Box::new(Error::InvalidQueryOption {
+                        src: info.into(),
+                        name: option.name.value.clone(),
+                        pos: args.get(1).or(args.first()).unwrap_or(&option.name).span,
+                        expected: "one of `one`, `opt` or `many`",
+                    }))
+                }
+            },
+            "budget" => match option.args.as_slice() {
+                [arg] if arg.value.parse::<QueryBudget>().is_ok() => {}
+                args => {
+                    return Err(Box::new(Error::InvalidQueryOption {
+                        src: info.into(),
+                        name: option.name.value.clone(),
+                        pos: args.get(1).or(args.first()).unwrap_or(&option.name).span,
+                        expected: "a latency budget in milliseconds or seconds, like `50ms` or `2s`",
+                    }))
+                }
+            },
+            "cache" => match option.args.as_slice() {
+                [arg] if arg.value.parse::<CacheTtl>().is_ok() => {}
+                args => {
+                    return Err(Box::new(Error::InvalidQueryOption {
+                        src: info.into(),
+                        name: option.name.value.clone(),
+                        pos: args.get(1).or(args.first()).unwrap_or(&option.name).span,
+                        expected: "a time to live in seconds, minutes or hours, like `ttl=60s`, `ttl=5m` or `ttl=1h`",
+                    }))
+                }
+            },
+            "copy_out" => {
+                if let Some(arg) = option.args.iter().find(|arg| {
+                    !matches!(
+                        arg.value
+                            .strip_prefix("format=")
+                            .map(str::parse::<CopyFormat>),
+                        Some(Ok(_))
+                    )
+                }) {
+                    return Err(Box::new(Error::InvalidQueryOption {
+                        src: info.into(),
+                        name: option.name.value.clone(),
+                        pos: arg.span,
+                        expected: "`format=csv`, `format=text` or `format=binary`",
+                    }));
+                }
+            }
+            "on_error" => {
+                let is_code = |it: &Span<String>| {
+                    it.value.len() == 5
+                        && it
+                            .value
+                            .chars()
+                            .all(|c| c.is_ascii_digit() || c.is_ascii_uppercase())
+                };
+                let is_variant = |it: &Span<String>| {
+                    it.value.starts_with(|c: char| c.is_ascii_uppercase())
+                        && it
+                            .value
+                            .chars()
+                            .all(|c| c.is_ascii_alphanumeric() || c == '_')
+                        && it.value != "Other"
+                };
+                let invalid = match option.args.as_slice() {
+                    [code, variant] if is_code(code) && is_variant(variant) => None,
+                    [code, variant] if is_code(code) => Some(variant),
+                    args => Some(args.first().unwrap_or(&option.name)),
+                };
+                if let Some(arg) = invalid {
+                    return Err(Box::new(Error::InvalidQueryOption {
+                        src: info.into(),
+                        name: option.name.value.clone(),
+                        pos: arg.span,
+                        expected: "a SQLSTATE error code followed by a variant name other than `Other`, like `23505 UniqueViolation`",
+                    }));
+                }
+            }
+            "into" => match option.args.as_slice() {
+                [arg]
+                    if arg.value.starts_with(|c: char| c.is_ascii_alphabetic() || c == '_')
+                        && arg
+                            .value
+                            .chars()
+                            .all(|c| c.is_ascii_alphanumeric() || c == '_' || c == ':') => {}
+                args => {
+                    return Err(Box::new(Error::InvalidQueryOption {
+                        src: info.into(),
+                        name: option.name.value.clone(),
+                        pos: args.get(1).or(args.first()).unwrap_or(&option.name).span,
+                        expected: "the path of a struct with the fields of the row, like `crate::User`",
+                    }))
+                }
+            },
+            "prepared" => match option.args.as_slice() {
+                [arg] if matches!(arg.value.as_str(), "true" | "false") => {}
+                args => {
+                    return Err(Box::new(Error::InvalidQueryOption {
+                        src: info.into(),
+                        name: option.name.value.clone(),
+                        pos: args.get(1).or(args.first()).unwrap_or(&option.name).span,
+                        expected: "one of `true` or `false`",
+                    }))
+                }
+            },
+            "allow_naive" => {
+                if option.args.is_empty() {
+                    return Err(Box::new(Error::InvalidQueryOption {
+                        src: info.into(),
+                        name: option.name.value.clone(),
+                        pos: option.name.span,
+                        expected: "the names of the accepted `timestamp` columns",
+                    }));
+                }
+            }
+            "tag" => {
+                let is_tag = |it: &&Span<String>| {
+                    it.value.split_once('=').is_some_and(|(key, value)| {
+                        !key.is_empty()
+                            && key.chars().all(|c| c.is_ascii_alphanumeric() || c == '_')
+                            && !value.is_empty()
+                    })
+                };
+                let invalid = match option.args.as_slice() {
+                    [] => Some(&option.name),
+                    args => args.iter().find(|it| !is_tag(it)),
+                };
+                if let Some(arg) = invalid {
+                    return Err(Box::new(Error::InvalidQueryOption {
+                        src: info.into(),
+                        name: option.name.value.clone(),
+                        pos: arg.span,
+                        expected: "`key=value` tags, like `team=payments`",
+                    }));
+                }
+            }
+            "batch" | "copy_in" | "setup" => {
+                if let Some(arg) = option.args.first() {
+                    return Err(Box::new(Error::InvalidQueryOption {
+                        src: info.into(),
+                        name: option.name.value.clone(),
+                        pos: arg.span,
+                        expected: "no argument",
+                    }));
+                }
+            }
+            _ => {
+                return Err(Box::new(Error::UnknownQueryOption {
+                    src: info.into(),
+                    name: option.name.value.clone(),
+                    pos: option.name.span,
+                    known: QUERY_OPTIONS.join(", "),
+                }))
+            }
+        }
+    }
+    // Batch queries are executed as is, other options than tags and budgets cannot apply
+    // to them
+    if let Some(batch) = options.iter().find(|it| it.name.value == "batch") {
+        if let Some(other) = options
+            .iter()
+            .find(|it| !matches!(it.name.value.as_str(), "batch" | "budget" | "tag"))
+        {
+            return Err(Box::new(Error::IncompatibleQueryOptions {
+                src: info.into(),
+                first_name: batch.name.value.clone(),
+                first: batch.name.span,
+                second_name: other.name.value.clone(),
+                second: other.name.span,
+            }));
+        }
+    }
+    // Each tag key must only be declared once
+    let tags: Vec<_> = options
+        .iter()
+        .filter(|it| it.name.value == "tag")
+        .flat_map(|it| &it.args)
+        .collect();
+    let key = |it: &Span<String>| it.value.split_once('=').map(|(key, _)| key.to_string());
+    find_duplicate(&tags, |a, b| key(a) == key(b)).map_or(Ok(()), |(first, second)| {
+        Err(Box::new(Error::DuplicateType {
+            src: info.into(),
+            ty: "tag",
+            name: key(first).unwrap_or_default(),
+            first: first.span,
+            second: second.span,
+        }))
+    })?;
+    // Each error code and variant must only be handled once
+    for (idx, ty) in [(0, "error code"), (1, "error variant")] {
+        find_duplicate(&on_error, |a, b| a.args[idx] == b.args[idx]).map_or(
+            Ok(()),
+            |(first, second)| {
+                Err(Box::new(Error::DuplicateType {
+                    src: info.into(),
+                    ty,
+                    name: first.args[idx].value.clone(),
+                    first: first.args[idx].span,
+                    second: second.args[idx].span,
+                }))
+            },
+        )?;
+    }
+    Ok(())
+}
+
+pub(crate) fn option_with_params(
+    info: &ModuleInfo,
+    name: &Span<String>,
+    option: &QueryOption,
+    has_params: bool,
+) -> Result<(), Box<Error>> {
+    if has_params {
+        return Err(Box::new(Error::OptionWithParams {
+            src: info.into(),
+            name: name.value.clone(),
+            option_name: option.name.value.clone(),
+            pos: option.name.span,
+        }));
+    }
+    Ok(())
+}
+
+pub(crate) fn into_row_struct(
+    info: &ModuleInfo,
+    name: &Span<String>,
+    option: &QueryOption,
+    is_named: bool,
+) -> Result<(), Box<Error>> {
+    if !is_named {
+        return Err(Box::new(Error::IntoWithoutRowStruct {
+            src: info.into(),
+            name: name.value.clone(),
+            pos: option.name.span,
+        }));
+    }
+    Ok(())
+}
+
+pub(crate) fn copy_in_insert(
+    info: &ModuleInfo,
+    name: &Span<String>,
+    option: &QueryOption,
+    is_insert: bool,
+) -> Result<(), Box<Error>> {
+    if !is_insert {
+        return Err(Box::new(Error::CopyInNotInsert {
+            src: info.into(),
+            name: name.value.clone(),
+            pos: option.name.span,
+        }));
+    }
+    Ok(())
+}
+
 pub(crate) fn param_on_simple_query(
     info: &ModuleInfo,
     name: &Span<String>,
@@ -191,8 +1163,25 @@ fn reserved_type_keyword(info: &ModuleInfo, s: &Span<String>) -> Result<(), Box<
     if let Ok(it) = STRICT_KEYWORD.binary_search(&s.value.as_str()) {
         return Err(Box::new(Error::TypeRustKeyword {
             src: info.into(),
-            name: STRICT_KEYWORD[it],
-            pos: s.span,
+            name: STRICT_KEYWORD[it],
+            pos: s.span,
+        }));
+    }
+    Ok(())
+}
+
+fn reserved_prelude_name(
+    info: &ModuleInfo,
+    name: &str,
+    pos: &SourceSpan,
+    ty: &'static str,
+) -> Result<(), Box<Error>> {
+    if let Ok(it) = PRELUDE_NAME.binary_search(&name) {
+        return Err(Box::new(Error::PreludeName {
+            src: info.into(),
+            name: PRELUDE_NAME[it],
+            pos: *pos,
+            ty,
         }));
     }
     Ok(())
@@ -222,6 +1211,7 @@ pub(crate) fn named_struct_field(
     prev_name: &Span<String>,
     prev_fields: &[PreparedField],
 ) -> Result<(), Box<Error>> {
+    let diff = || field_diff(info, name, fields, prev_name, prev_fields);
     if let Some((field, prev_field)) = fields.iter().find_map(|f| {
         prev_fields.iter().find_map(|prev_f| {
             (f.ident == prev_f.ident && f.ty != prev_f.ty).then_some((f, prev_f))
@@ -230,6 +1220,7 @@ pub(crate) fn named_struct_field(
         return Err(Box::new(Error::IncompatibleNamedType {
             src: info.into(),
             name: name.value.clone(),
+            diff: diff(),
             first_label: format!(
                 "column `{}` has type `{}` here",
                 field.ident.db,
@@ -241,10 +1232,34 @@ pub(crate) fn named_struct_field(
         }));
     }
 
+    if let Some((field, prev_field)) = fields.iter().find_map(|f| {
+        prev_fields.iter().find_map(|prev_f| {
+            (f.ident == prev_f.ident && f.is_nullable != prev_f.is_nullable).then_some((f, prev_f))
+        })
+    }) {
+        let nullity = |it: &PreparedField| {
+            if it.is_nullable {
+                "nullable"
+            } else {
+                "not nullable"
+            }
+        };
+        return Err(Box::new(Error::IncompatibleNamedType {
+            src: info.into(),
+            name: name.value.clone(),
+            diff: diff(),
+            first_label: format!("column `{}` is {} here", field.ident.db, nullity(field)),
+            second: prev_name.span,
+            second_label: format!("but here it is {}", nullity(prev_field)),
+            first: name.span,
+        }));
+    }
+
     if let Some(field) = fields.iter().find(|f| !prev_fields.contains(f)) {
         return Err(Box::new(Error::IncompatibleNamedType {
             src: info.into(),
             name: name.value.clone(),
+            diff: diff(),
             second_label: format!("column `{}` expected here", &field.ident.db),
             second: name.span,
             first_label: format!("column `{}` not found", &field.ident.db),
@@ -256,6 +1271,7 @@ pub(crate) fn named_struct_field(
         return Err(Box::new(Error::IncompatibleNamedType {
             src: info.into(),
             name: name.value.clone(),
+            diff: diff(),
             second_label: format!("column `{}` expected here", &prev_field.ident.db),
             second: prev_name.span,
             first_label: format!("column `{}` not found", &prev_field.ident.db),
@@ -266,11 +1282,174 @@ pub(crate) fn named_struct_field(
     Ok(())
 }
 
-pub(crate) fn validate_preparation(module: &PreparedModule) -> Result<(), Box<Error>> {
-    // Check generated name clash
-    let mut name_registrar = BTreeMap::new();
+/// The fields differing between two uses of a named type, one per line, each use being
+/// described by its line
+fn field_diff(
+    info: &ModuleInfo,
+    name: &Span<String>,
+    fields: &[PreparedField],
+    prev_name: &Span<String>,
+    prev_fields: &[PreparedField],
+) -> String {
+    let line = |span: &SourceSpan| info.source[..span.offset()].matches('\n').count() + 1;
+    let (first, second) = (line(&name.span), line(&prev_name.span));
+    let ctx = GenCtx::new(0, false);
+    let mut diff = Vec::new();
+    for field in fields {
+        match prev_fields.iter().find(|it| it.ident == field.ident) {
+            None => diff.push(format!(
+                "the field `{}` is missing on line {second}",
+                field.ident.db
+            )),
+            Some(it) if it != field => {
+                // Types are compared as generated, nullability included
+                let (mut a, mut b) = (field.own_struct(&ctx), it.own_struct(&ctx));
+                if a == b {
+                    (a, b) = (
+                        field.ty.pg_ty().name().to_string(),
+                        it.ty.pg_ty().name().to_string(),
+                    );
+                }
+                diff.push(format!(
+                    "the field `{}` is `{a}` on line {first} but `{b}` on line {second}",
+                    field.ident.db
+                ));
+            }
+            Some(_) => {}
+        }
+    }
+    for field in prev_fields
+        .iter()
+        .filter(|it| !fields.iter().any(|field| field.ident == it.ident))
+    {
+        diff.push(format!(
+            "the field `{}` is missing on line {first}",
+            field.ident.db
+        ));
+    }
+    diff.iter().map(|it| format!("\n{it}")).collect()
+}
+
+/// Whether two uses of a named type only differ by the nullability of their fields
+pub(crate) fn differ_in_nullability(
+    fields: &[PreparedField],
+    prev_fields: &[PreparedField],
+) -> bool {
+    fields.len() == prev_fields.len()
+        && fields.iter().all(|f| {
+            prev_fields
+                .iter()
+                .any(|prev_f| f.ident == prev_f.ident && f.ty == prev_f.ty)
+        })
+}
+
+/// Warns about the fields of a named type made nullable as they are nullable in another
+/// of its uses
+pub(crate) fn merged_nullability(
+    info: &ModuleInfo,
+    name: &Span<String>,
+    prev_name: &Span<String>,
+    merged: &[String],
+) -> Warning {
+    let merged: Vec<_> = merged.iter().map(|it| format!("`{it}`")).collect();
+    Warning::new(error::MergedNullabilityWarning {
+        src: info.into(),
+        name: name.value.clone(),
+        fields: merged.join(", "),
+        first: name.span,
+        second: prev_name.span,
+    })
+}
+
+/// Queries prepared as different roles must have the same parameters and columns
+pub(crate) fn role_shape(
+    module: &PreparedModule,
+    other: &PreparedModule,
+    first_role: &str,
+    role: &str,
+) -> Result<(), Box<Error>> {
+    fn fields<'a>(
+        module: &'a PreparedModule,
+        item: Option<&(usize, Vec<usize>)>,
+        map: impl Fn(&'a PreparedModule) -> &'a IndexMap<Span<String>, PreparedItem>,
+    ) -> &'a [PreparedField] {
+        item.map_or(&[], |(idx, _)| map(module)[*idx].fields.as_slice())
+    }
+
+    let ctx = GenCtx::new(0, false);
+    let describe = |field: &PreparedField, other: &PreparedField| {
+        let (a, b) = (field.own_struct(&ctx), other.own_struct(&ctx));
+        if a == b {
+            (
+                field.ty.pg_ty().name().to_string(),
+                other.ty.pg_ty().name().to_string(),
+            )
+        } else {
+            (a, b)
+        }
+    };
+    for (name, query) in &module.queries {
+        let other_query = &other.queries[name];
+        let shapes = [
+            (
+                "parameter",
+                fields(module, query.param.as_ref(), |it| &it.params),
+                fields(other, other_query.param.as_ref(), |it| &it.params),
+            ),
+            (
+                "column",
+                fields(module, query.row.as_ref(), |it| &it.rows),
+                fields(other, other_query.row.as_ref(), |it| &it.rows),
+            ),
+        ];
+        let mut diff = Vec::new();
+        for (kind, first, second) in shapes {
+            for field in first {
+                match second.iter().find(|it| it.ident == field.ident) {
+                    None => diff.push(format!(
+                        "the {kind} `{}` is missing as role `{role}`",
+                        field.ident.db
+                    )),
+                    Some(it) if it != field => {
+                        let (a, b) = describe(field, it);
+                        diff.push(format!(
+                            "the {kind} `{}` is `{a}` as role `{first_role}` but `{b}` as role `{role}`",
+                            field.ident.db
+                        ));
+                    }
+                    Some(_) => {}
+                }
+            }
+            for field in second
+                .iter()
+                .filter(|it| !first.iter().any(|field| field.ident == it.ident))
+            {
+                diff.push(format!(
+                    "the {kind} `{}` only exists as role `{role}`",
+                    field.ident.db
+                ));
+            }
+        }
+        if !diff.is_empty() {
+            return Err(Box::new(Error::RoleShapeMismatch {
+                src: (&module.info).into(),
+                name: name.value.clone(),
+                role: role.to_string(),
+                first_role: first_role.to_string(),
+                pos: name.span,
+                diff: diff.join("\n"),
+            }));
+        }
+    }
+    Ok(())
+}
 
-    let mut check_name = |name: String, span: SourceSpan, ty: &'static str| {
+/// Registers generated names, reporting the first one generated twice
+fn name_checker(
+    info: &ModuleInfo,
+) -> impl FnMut(String, SourceSpan, &'static str) -> Result<(), Box<Error>> + '_ {
+    let mut name_registrar = BTreeMap::new();
+    move |name: String, span: SourceSpan, ty: &'static str| {
         if let Some(prev) = name_registrar.insert(name.clone(), (span, ty)) {
             // Sort by span
             let (first, second) = if prev.0.offset() < span.offset() {
@@ -278,19 +1457,28 @@ pub(crate) fn validate_preparation(module: &PreparedModule) -> Result<(), Box<Er
             } else {
                 ((span, ty), prev)
             };
-            Err(Error::DuplicateName {
-                src: (&module.info).into(),
+            Err(Box::new(Error::DuplicateName {
+                src: info.into(),
                 name,
                 first: first.0,
                 first_ty: first.1,
                 second: second.0,
                 second_ty: second.1,
-            })
+            }))
         } else {
             Ok(())
         }
-    };
+    }
+}
 
+/// Checks the names of the generated items, including the builders of the params structs
+/// when `has_builders`
+pub(crate) fn validate_preparation(
+    module: &PreparedModule,
+    has_builders: bool,
+) -> Result<(), Box<Error>> {
+    // Check generated name clash
+    let mut check_name = name_checker(&module.info);
     for (origin, query) in &module.queries {
         reserved_type_keyword(&module.info, origin)?;
         check_name(
@@ -298,10 +1486,18 @@ pub(crate) fn validate_preparation(module: &PreparedModule) -> Result<(), Box<Er
             origin.span,
             "statement",
         )?;
+        if !query.on_error.is_empty() {
+            check_name(
+                format!("{}Error", query.ident.type_ident()),
+                origin.span,
+                "error",
+            )?;
+        }
     }
     for (origin, row) in &module.rows {
         reserved_type_keyword(&module.info, origin)?;
         if row.is_named {
+            reserved_prelude_name(&module.info, &row.name.value, &origin.span, "row")?;
             check_name(row.name.value.clone(), origin.span, "row")?;
             for field in &row.fields {
                 reserved_name_keyword(&module.info, &field.ident.db, &origin.span, "row")?;
@@ -316,10 +1512,40 @@ pub(crate) fn validate_preparation(module: &PreparedModule) -> Result<(), Box<Er
     for (origin, params) in &module.params {
         reserved_type_keyword(&module.info, origin)?;
         if params.is_named {
+            reserved_prelude_name(&module.info, &params.name.value, &origin.span, "params")?;
             check_name(params.name.value.clone(), origin.span, "params")?;
             for field in &params.fields {
                 reserved_name_keyword(&module.info, &field.ident.db, &origin.span, "param")?;
             }
+            if has_builders {
+                check_name(
+                    format!("{}Builder", params.name),
+                    origin.span,
+                    "params builder",
+                )?;
+                if params.fields.iter().any(|it| it.ident.rs == "build") {
+                    return Err(Box::new(Error::BuilderField {
+                        src: (&module.info).into(),
+                        name: params.name.value.clone(),
+                        pos: origin.span,
+                    }));
+                }
+            }
+        }
+    }
+
+    // Functions live apart from types
+    let mut check_fn = name_checker(&module.info);
+    for (origin, query) in &module.queries {
+        check_fn(query.ident.rs.clone(), origin.span, "query")?;
+    }
+    for lock in &module.locks {
+        for prefix in LOCK_FN_PREFIXES {
+            check_fn(
+                format!("{prefix}_{}", lock.name.value),
+                lock.name.span,
+                "lock helper",
+            )?;
         }
     }
     Ok(())
@@ -329,15 +1555,19 @@ pub(crate) fn validate_module(
     Module {
         info,
         types,
+        locks,
         queries,
     }: &Module,
 ) -> Result<(), Box<Error>> {
     query_name_already_used(info, queries)?;
+    lock_name_already_used(info, locks)?;
+    query_variants(info, queries)?;
     named_type_already_used(info, types)?;
     for ty in types {
         duplicate_nullable_ident(info, &ty.fields)?;
     }
     for query in queries {
+        query_options(info, &query.options)?;
         for (it, ty) in [(&query.param, "param"), (&query.row, "row")] {
             if let Some(idents) = &it.idents {
                 duplicate_nullable_ident(info, idents)?;
@@ -363,11 +1593,12 @@ pub mod error {
     #[derive(Debug, ThisError, Diagnostic)]
     pub enum Error {
         #[error("column `{name}` appear multiple time")]
-        #[diagnostic(help("disambiguate column names in your SQL using an `AS` clause"))]
+        #[diagnostic(help("disambiguate column names in {clause} using an `AS` clause"))]
         DuplicateSqlColName {
             #[source_code]
             src: NamedSource,
             name: String,
+            clause: String,
             #[label("query returns one or more columns with the same name")]
             pos: SourceSpan,
         },
@@ -382,6 +1613,69 @@ pub mod error {
             #[label("redeclared here")]
             second: SourceSpan,
         },
+        #[error("the column `{name}` is an anonymous record whose fields are not declared")]
+        #[diagnostic(help(
+            "declare its fields in the row of the query, e.g. `({name} record(first: int4, second: text))`"
+        ))]
+        UndeclaredRecord {
+            #[source_code]
+            src: NamedSource,
+            name: String,
+            #[label("this query returns an anonymous record")]
+            pos: SourceSpan,
+        },
+        #[error("the column `{name}` is of type `{ty}`, not an anonymous record")]
+        #[diagnostic(help("only declare the fields of columns returning `ROW(...)` records"))]
+        NotARecord {
+            #[source_code]
+            src: NamedSource,
+            name: String,
+            ty: String,
+            #[label("declared as a record")]
+            pos: SourceSpan,
+        },
+        #[error("the field `{name}` of the record is declared multiple time")]
+        #[diagnostic(help("remove one of the two declaration"))]
+        DuplicateRecordField {
+            #[source_code]
+            src: NamedSource,
+            name: String,
+            #[label("previous declaration")]
+            first: SourceSpan,
+            #[label("redeclared here")]
+            second: SourceSpan,
+        },
+        #[error("unknown built-in type `{ty}`")]
+        #[diagnostic(help(
+            "declare the fields of records using the names of built-in types, e.g. `int4`, `text` or `int4[]`"
+        ))]
+        UnknownRecordFieldType {
+            #[source_code]
+            src: NamedSource,
+            ty: String,
+            #[label("unknown type")]
+            pos: SourceSpan,
+        },
+        #[error("`{name}` is of type `{ty}`, not `json` or `jsonb`")]
+        #[diagnostic(help(
+            "only declare the Rust type of `json` and `jsonb` values, e.g. `(settings: crate::Settings)`"
+        ))]
+        NotJson {
+            #[source_code]
+            src: NamedSource,
+            name: String,
+            ty: String,
+            #[label("declared as a JSON value")]
+            pos: SourceSpan,
+        },
+        #[error("parameters cannot be declared as records")]
+        #[diagnostic(help("only declare the fields of returned columns"))]
+        RecordParam {
+            #[source_code]
+            src: NamedSource,
+            #[label("declared as a record")]
+            pos: SourceSpan,
+        },
         #[error("the {ty} `{name}` is defined multiple time")]
         #[diagnostic(help("use a different name for one of those"))]
         DuplicateType {
@@ -394,6 +1688,62 @@ pub mod error {
             #[label("redefined here")]
             second: SourceSpan,
         },
+        #[error("the locks `{prev}` and `{name}` share the key {key}")]
+        #[diagnostic(help("rename one of those locks, they would exclude each other"))]
+        LockKeyCollision {
+            #[source_code]
+            src: NamedSource,
+            name: String,
+            prev: String,
+            key: i64,
+            #[label("lock colliding with `{prev}`")]
+            pos: SourceSpan,
+        },
+        #[error("the version range `{range}` matches no server")]
+        #[diagnostic(help("use a lower bound below the upper bound, e.g. `@pg>=12,<15`"))]
+        EmptyVersionRange {
+            #[source_code]
+            src: NamedSource,
+            range: String,
+            #[label("empty version range")]
+            pos: SourceSpan,
+        },
+        #[error("the variants of the query `{name}` overlap")]
+        #[diagnostic(help(
+            "declare contiguous version ranges selecting a single variant, e.g. `@pg<15` and `@pg>=15`"
+        ))]
+        OverlappingVariants {
+            #[source_code]
+            src: NamedSource,
+            name: String,
+            #[label("this version range")]
+            first: SourceSpan,
+            #[label("overlaps this one")]
+            second: SourceSpan,
+        },
+        #[error("no variant of the query `{name}` is declared for `{range}`")]
+        #[diagnostic(help(
+            "declare contiguous version ranges covering every server, e.g. `@pg<15` and `@pg>=15`"
+        ))]
+        VariantGap {
+            #[source_code]
+            src: NamedSource,
+            name: String,
+            range: String,
+            #[label("next to this version range")]
+            pos: SourceSpan,
+        },
+        #[error("the variants of the query `{name}` bind different parameters")]
+        #[diagnostic(help("bind the same parameters in the same order in every variant"))]
+        VariantParams {
+            #[source_code]
+            src: NamedSource,
+            name: String,
+            #[label("this variant")]
+            first: SourceSpan,
+            #[label("binds other parameters than this one")]
+            second: SourceSpan,
+        },
         #[error("reference to an unknown named {ty} `{name}`")]
         #[diagnostic(help("declare an inline named type using `()`: {name}()"))]
         UnknownNamedType {
@@ -404,21 +1754,48 @@ pub mod error {
             #[label("unknown named {ty}")]
             pos: SourceSpan,
         },
+        #[error(
+            "the parameter `{name}` is declared nullable, but its domain `{ty}` is `NOT NULL`"
+        )]
+        #[diagnostic(help("remove the `?` marker, `NULL` values being rejected by the domain"))]
+        NullableNotNullDomain {
+            #[source_code]
+            src: NamedSource,
+            name: String,
+            ty: String,
+            #[label("declared nullable here")]
+            pos: SourceSpan,
+        },
+        #[error("the field `{name}` is of type `{ty}`, which is not an array")]
+        #[diagnostic(help(
+            "remove the `[?]` marker, only array elements can be declared nullable"
+        ))]
+        InnerNullableNotArray {
+            #[source_code]
+            src: NamedSource,
+            name: String,
+            ty: String,
+            #[label("elements declared nullable here")]
+            pos: SourceSpan,
+        },
         #[error("unknown field")]
-        #[diagnostic(help("use one of those names: {known}"))]
         UnknownFieldName {
             #[source_code]
             src: NamedSource,
             #[label("no field with this name was found")]
             pos: SourceSpan,
-            known: String,
+            #[help]
+            help: String,
         },
         #[error("conflicting uses of named type `{name}`")]
-        #[diagnostic(help("use a different named type for each query"))]
+        #[diagnostic(help(
+            "use a different named type for each query, its fields differing as follows:{diff}"
+        ))]
         IncompatibleNamedType {
             #[source_code]
             src: NamedSource,
             name: String,
+            diff: String,
             first_label: String,
             #[label("{first_label}")]
             first: SourceSpan,
@@ -448,6 +1825,78 @@ pub mod error {
             #[label("but query has no binding")]
             query: SourceSpan,
         },
+        #[error("the query `{name}` declares the `{option_name}` option but return nothing")]
+        #[diagnostic(help("remove the option"))]
+        RowOptionOnExecute {
+            #[source_code]
+            src: NamedSource,
+            name: String,
+            option_name: String,
+            #[label("option declared here")]
+            option: SourceSpan,
+            #[label("but query return nothing")]
+            query: SourceSpan,
+        },
+        #[error("the query `{name}` cannot use the `{option_name}` option because it has bind parameters")]
+        #[diagnostic(help("remove the parameters or the `{option_name}` option"))]
+        OptionWithParams {
+            #[source_code]
+            src: NamedSource,
+            name: String,
+            option_name: String,
+            #[label("option declared here")]
+            pos: SourceSpan,
+        },
+        #[error("the query `{name}` cannot use the `copy_in` option because it is not an insert of its bind parameters")]
+        #[diagnostic(help("only queries like `INSERT INTO table (a, b) VALUES (:a, :b)`, giving a distinct parameter to each column, can be copied in"))]
+        CopyInNotInsert {
+            #[source_code]
+            src: NamedSource,
+            name: String,
+            #[label("option declared here")]
+            pos: SourceSpan,
+        },
+        #[error("the query `{name}` cannot use the `into` option because it returns a single column without a row struct")]
+        #[diagnostic(help("name the row of the query, like `--! {name} : Row`"))]
+        IntoWithoutRowStruct {
+            #[source_code]
+            src: NamedSource,
+            name: String,
+            #[label("option declared here")]
+            pos: SourceSpan,
+        },
+        #[error("unknown query option `{name}`")]
+        #[diagnostic(help("use one of those options: {known}"))]
+        UnknownQueryOption {
+            #[source_code]
+            src: NamedSource,
+            name: String,
+            #[label("unknown option")]
+            pos: SourceSpan,
+            known: String,
+        },
+        #[error("invalid argument for the `{name}` option")]
+        #[diagnostic(help("expected {expected}"))]
+        InvalidQueryOption {
+            #[source_code]
+            src: NamedSource,
+            name: String,
+            #[label("unexpected argument")]
+            pos: SourceSpan,
+            expected: &'static str,
+        },
+        #[error("the `{first_name}` option cannot be combined with the `{second_name}` option")]
+        #[diagnostic(help("remove one of those options"))]
+        IncompatibleQueryOptions {
+            #[source_code]
+            src: NamedSource,
+            first_name: String,
+            #[label("`{first_name}` declared here")]
+            first: SourceSpan,
+            second_name: String,
+            #[label("`{second_name}` declared here")]
+            second: SourceSpan,
+        },
         #[error("`{name}` is used multiple time")]
         #[diagnostic(help("use a different name for one of those"))]
         DuplicateName {
@@ -470,6 +1919,25 @@ pub mod error {
             #[label("reserved rust keyword")]
             pos: SourceSpan,
         },
+        #[error("`{name}` is used by the generated code and cannot name a {ty} struct")]
+        #[diagnostic(help("use a different name"))]
+        PreludeName {
+            #[source_code]
+            src: NamedSource,
+            name: &'static str,
+            ty: &'static str,
+            #[label("{ty} declared here")]
+            pos: SourceSpan,
+        },
+        #[error("the params `{name}` have a `build` field, clashing with the `build` function of their builder")]
+        #[diagnostic(help("rename the field, or disable the params builders"))]
+        BuilderField {
+            #[source_code]
+            src: NamedSource,
+            name: String,
+            #[label("params declared here")]
+            pos: SourceSpan,
+        },
         #[error("`{name}` is a reserved rust keyword that cannot be escaped")]
         #[diagnostic(help("use a different name"))]
         NameRustKeyword {
@@ -480,5 +1948,203 @@ pub mod error {
             #[label("from {ty} declared here")]
             pos: SourceSpan,
         },
+        #[error("the query `{name}` returns the column `{column}` of naive type `{ty}`")]
+        #[diagnostic(help(
+            "alter the column to `timestamptz`, or accept it using `-- :allow_naive {column}`"
+        ))]
+        NaiveTimestamp {
+            #[source_code]
+            src: NamedSource,
+            name: String,
+            column: String,
+            ty: String,
+            #[label("query declared here")]
+            pos: SourceSpan,
+        },
+        #[error("the query `{name}` cannot be executed without a prepared statement because its parameter `{param}` has the custom type `{ty}`")]
+        #[diagnostic(help("prepare this query using `-- :prepared true`"))]
+        UnpreparedCustomParam {
+            #[source_code]
+            src: NamedSource,
+            name: String,
+            param: String,
+            ty: String,
+            #[label("parameter of custom type `{ty}`")]
+            pos: SourceSpan,
+        },
+        #[error("the query `{name}` cannot be cached because its parameter `{param}` has the type `{ty}`, whose Rust type does not implement `Hash`")]
+        #[diagnostic(help("cached results are keyed by the hash of the parameters, remove the `cache` option or avoid floating point numbers, JSON values and custom types in its parameters"))]
+        UncachableParam {
+            #[source_code]
+            src: NamedSource,
+            name: String,
+            param: String,
+            ty: String,
+            #[label("parameter of type `{ty}`")]
+            pos: SourceSpan,
+        },
+        #[error("the query `{name}` has different parameters or columns as role `{role}` than as role `{first_role}`")]
+        RoleShapeMismatch {
+            #[source_code]
+            src: NamedSource,
+            name: String,
+            role: String,
+            first_role: String,
+            #[label("query declared here")]
+            pos: SourceSpan,
+            #[help]
+            diff: String,
+        },
+        #[error("invalid newtype `{path}` for `{target}`")]
+        #[diagnostic(help(
+            "map a `table.column` or a domain name to a newtype name (`UserId`) or path (`crate::ids::UserId`)"
+        ))]
+        InvalidNewtype { target: String, path: String },
+        #[error("`{name}`{scope} is renamed `{renamed}`, which is not a valid identifier")]
+        #[diagnostic(help("renamed identifiers are made of ASCII letters, digits and `_`"))]
+        InvalidRename {
+            name: String,
+            scope: String,
+            renamed: String,
+        },
+        #[error("the {names} are both renamed `{renamed}`")]
+        #[diagnostic(help("change the renames so that the generated identifiers stay distinct"))]
+        RenameCollision { names: String, renamed: String },
+        #[error("no query matches `{pattern}`")]
+        UnknownOnlyPattern {
+            pattern: String,
+            #[help]
+            help: String,
+        },
+        #[error("no query is tagged `{tag}`")]
+        UnknownOnlyTag {
+            tag: String,
+            #[help]
+            help: String,
+        },
+        #[error("unknown newtype `{name}`")]
+        UnknownNewtype {
+            #[source_code]
+            src: NamedSource,
+            name: String,
+            #[label("no newtype with this name was found")]
+            pos: SourceSpan,
+            #[help]
+            help: String,
+        },
+        #[error("the parameter `{param}` is annotated with different newtypes")]
+        #[diagnostic(help("use the same newtype for every occurrence of `{param}`"))]
+        ConflictingParamNewtype {
+            #[source_code]
+            src: NamedSource,
+            param: String,
+            #[label("first annotation")]
+            first: SourceSpan,
+            #[label("conflicting annotation")]
+            second: SourceSpan,
+        },
+        #[error("the newtype `{newtype}` cannot wrap `{field}` of non-copyable type `{ty}`")]
+        #[diagnostic(help("newtypes can only wrap copyable types, like integers or uuids"))]
+        NewtypeNotCopy {
+            #[source_code]
+            src: NamedSource,
+            newtype: String,
+            field: String,
+            ty: String,
+            #[label("`{field}` is wrapped in `{newtype}` here")]
+            pos: SourceSpan,
+        },
+        #[error("the newtype `{newtype}` already wraps `{first}` but `{field}` has type `{ty}`")]
+        #[diagnostic(help("map `{newtype}` to columns and parameters of a single type"))]
+        NewtypeConflict {
+            #[source_code]
+            src: NamedSource,
+            newtype: String,
+            first: String,
+            field: String,
+            ty: String,
+            #[label("`{field}` is wrapped in `{newtype}` here")]
+            pos: SourceSpan,
+        },
+    }
+
+    #[derive(Debug, ThisError, Diagnostic)]
+    #[error("the query `{name}` returns the column `{column}` of naive type `{ty}`")]
+    #[diagnostic(
+        severity(Warning),
+        help("alter the column to `timestamptz`, or accept it using `-- :allow_naive {column}`")
+    )]
+    pub struct NaiveTimestampWarning {
+        #[source_code]
+        pub(crate) src: NamedSource,
+        pub(crate) name: String,
+        pub(crate) column: String,
+        pub(crate) ty: String,
+        #[label("query declared here")]
+        pub(crate) pos: SourceSpan,
+    }
+
+    #[derive(Debug, ThisError, Diagnostic)]
+    #[error("the fields {fields} of the named type `{name}` are made nullable, being nullable in another of its uses")]
+    #[diagnostic(
+        severity(Warning),
+        help("declare them nullable in every use of the named type to silence this warning")
+    )]
+    pub struct MergedNullabilityWarning {
+        #[source_code]
+        pub(crate) src: NamedSource,
+        pub(crate) name: String,
+        pub(crate) fields: String,
+        #[label("used here")]
+        pub(crate) first: SourceSpan,
+        #[label("and here")]
+        pub(crate) second: SourceSpan,
+    }
+
+    #[derive(Debug, ThisError, Diagnostic)]
+    #[error("the query `{name}` returns the deprecated column `{column}`")]
+    #[diagnostic(severity(Warning), help("{comment}"))]
+    pub struct DeprecatedColumnWarning {
+        #[source_code]
+        pub(crate) src: NamedSource,
+        pub(crate) name: String,
+        pub(crate) column: String,
+        pub(crate) comment: String,
+        #[label("query declared here")]
+        pub(crate) pos: SourceSpan,
+    }
+
+    #[derive(Debug, ThisError, Diagnostic)]
+    #[error("the field `{name}` is declared {declared}, while `{path}` declares `{column}` {file}")]
+    #[diagnostic(
+        severity(Warning),
+        help(
+            "the declaration of the query takes precedence, remove it to use the one of `{path}`"
+        )
+    )]
+    pub struct NullabilityConflictWarning {
+        #[source_code]
+        pub(crate) src: NamedSource,
+        pub(crate) name: String,
+        pub(crate) column: String,
+        pub(crate) declared: &'static str,
+        pub(crate) file: &'static str,
+        pub(crate) path: String,
+        #[label("declared here")]
+        pub(crate) pos: SourceSpan,
+    }
+
+    #[derive(Debug, ThisError, Diagnostic)]
+    #[error("the query `{name}` returns no row when its insert conflicts")]
+    #[diagnostic(
+        severity(Warning),
+        help("declare it using `-- :arity opt` to get `None` instead of an error on conflict")
+    )]
+    pub struct ConflictArityWarning {
+        #[source_code]
+        pub(crate) src: NamedSource,
+        pub(crate) name: String,
+        #[label("declared to return exactly one row here")]
+        pub(crate) pos: SourceSpan,
     }
 }
@@ -0,0 +1,124 @@
+use std::{
+    path::{Path, PathBuf},
+    sync::mpsc::{channel, RecvTimeoutError},
+    time::Duration,
+};
+
+use notify::{event::EventKind, RecursiveMode, Watcher};
+use owo_colors::OwoColorize;
+
+use self::error::Error;
+
+/// Quiet period after a change before regenerating, so that rapid saves only regenerate once
+const DEBOUNCE: Duration = Duration::from_millis(300);
+
+/// What the watcher waits for
+enum Signal {
+    /// A file of the watched paths was created, modified or removed
+    Changed(Vec<PathBuf>),
+    /// Ctrl-C was pressed
+    Interrupted,
+}
+
+/// Runs `regenerate` once, then again whenever a file of `paths` changes, until Ctrl-C is
+/// pressed. Each run is reported on a status line, the errors of failing runs being printed
+/// without stopping the watcher.
+pub(crate) fn watch(
+    paths: &[&Path],
+    mut regenerate: impl FnMut() -> Result<(), crate::Error>,
+) -> Result<(), Error> {
+    let (sender, receiver) = channel();
+    let interrupt = sender.clone();
+    ctrlc::set_handler(move || {
+        interrupt.send(Signal::Interrupted).ok();
+    })?;
+    let mut watcher = notify::recommended_watcher(move |event: notify::Result<notify::Event>| {
+        // Reading a file is not a change
+        if let Ok(event) = event {
+            if matches!(
+                event.kind,
+                EventKind::Create(_) | EventKind::Modify(_) | EventKind::Remove(_)
+            ) {
+                sender.send(Signal::Changed(event.paths)).ok();
+            }
+        }
+    })?;
+    for path in paths {
+        watcher
+            .watch(path, RecursiveMode::Recursive)
+            .map_err(|err| Error::Path {
+                path: path.to_path_buf(),
+                err,
+            })?;
+    }
+    let watched: Vec<_> = paths
+        .iter()
+        .map(|it| format!("`{}`", it.display()))
+        .collect();
+    println!("{} watching {}", "[watch]".magenta(), watched.join(", "));
+
+    // Changed paths are absolute, they are printed relatively to the current directory
+    let current_dir = std::env::current_dir().unwrap_or_default();
+    let mut run = |changed: &[PathBuf]| {
+        let reason = match changed {
+            [] => String::new(),
+            [path] => format!(
+                " (`{}` changed)",
+                path.strip_prefix(&current_dir).unwrap_or(path).display()
+            ),
+            [..] => format!(" ({} files changed)", changed.len()),
+        };
+        match regenerate() {
+            Ok(()) => println!(
+                "{} regenerated{reason} {}",
+                "[watch]".magenta(),
+                "OK".green()
+            ),
+            Err(err) => {
+                println!(
+                    "{} regenerated{reason} {}",
+                    "[watch]".magenta(),
+                    "ERR".red()
+                );
+                eprint!("{}", err.report());
+            }
+        }
+    };
+    run(&[]);
+    while let Ok(Signal::Changed(mut changed)) = receiver.recv() {
+        // Wait for the changes to settle
+        loop {
+            match receiver.recv_timeout(DEBOUNCE) {
+                Ok(Signal::Changed(paths)) => changed.extend(paths),
+                Ok(Signal::Interrupted) | Err(RecvTimeoutError::Disconnected) => {
+                    println!("{} stopped", "[watch]".magenta());
+                    return Ok(());
+                }
+                Err(RecvTimeoutError::Timeout) => break,
+            }
+        }
+        changed.sort();
+        changed.dedup();
+        run(&changed);
+    }
+    println!("{} stopped", "[watch]".magenta());
+    Ok(())
+}
+
+pub(crate) mod error {
+    use std::path::PathBuf;
+
+    use miette::Diagnostic;
+    use thiserror::Error as ThisError;
+
+    #[derive(Debug, ThisError, Diagnostic)]
+    pub enum Error {
+        #[error("Could not watch the files: ({0})")]
+        Watcher(#[from] notify::Error),
+        #[error("Could not watch `{path}`: ({err})")]
+        #[diagnostic(help("check that the path exists"))]
+        Path { path: PathBuf, err: notify::Error },
+        #[error("Could not handle Ctrl-C: ({0})")]
+        Interrupt(#[from] ctrlc::Error),
+    }
+}
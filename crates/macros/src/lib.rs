@@ -0,0 +1,176 @@
+use cornucopia::{CodegenSettings, CodegenSettingsBuilder, DateTimeCrate, NumericCrate, TargetSet};
+use proc_macro::TokenStream;
+use proc_macro2::TokenStream as TokenStream2;
+use quote::{format_ident, quote};
+use syn::{meta::ParseNestedMeta, parse_macro_input, Expr, ExprLit, ItemConst, Lit, LitStr};
+
+/// Generates the Rust code of the queries of a string constant, prepared at compile time
+/// against the database of the `DATABASE_URL` environment variable. The code is put in a
+/// module named after the constant, next to it:
+///
+/// ```ignore
+/// #[cornucopia_macros::query(sync)]
+/// const AUTHORS: &str = r#"
+/// --! authors
+/// SELECT * FROM author;
+/// "#;
+///
+/// let authors = authors::authors().bind(&mut client).all()?;
+/// ```
+///
+/// The arguments are the settings of the CLI: the `async` (default) and `sync` flags,
+/// `derive_ser` and `derive_deser` deriving the serde traits on every struct, the
+/// `serialize` and `deserialize` targets, e.g. `serialize = "rows,types"`, the
+/// `error_type` and `types_path` paths, the `date_time_crate`, `"time"` (default) or
+/// `"chrono"`, the `numeric_crate`, `"rust_decimal"` (default) or `"bigdecimal"`, and the
+/// `read_only_flag`, `normalize_sql`, `strict_nullability`, `infer_nullability`,
+/// `merge_nullability`, `row_conversions`, `params_builders`, `param_conversions` and
+/// `display` flags.
+#[proc_macro_attribute]
+pub fn query(args: TokenStream, item: TokenStream) -> TokenStream {
+    let mut settings = Settings::default();
+    let parser = syn::meta::parser(|meta| settings.parse(meta));
+    parse_macro_input!(args with parser);
+    let item = parse_macro_input!(item as ItemConst);
+    expand(settings.build(), &item)
+        .unwrap_or_else(syn::Error::into_compile_error)
+        .into()
+}
+
+/// Settings given as arguments of the attribute
+#[derive(Default)]
+struct Settings {
+    sync: bool,
+    r#async: bool,
+    serialize: TargetSet,
+    deserialize: TargetSet,
+    flags: Vec<String>,
+    error_type: Option<String>,
+    types_path: Option<String>,
+    date_time_crate: DateTimeCrate,
+    numeric_crate: NumericCrate,
+}
+
+impl Settings {
+    fn parse(&mut self, meta: ParseNestedMeta) -> syn::Result<()> {
+        let name = meta
+            .path
+            .get_ident()
+            .map(ToString::to_string)
+            .unwrap_or_default();
+        let targets = |meta: &ParseNestedMeta| -> syn::Result<TargetSet> {
+            let value: LitStr = meta.value()?.parse()?;
+            value
+                .value()
+                .parse()
+                .map_err(|err: String| syn::Error::new(value.span(), err))
+        };
+        match name.as_str() {
+            "sync" => self.sync = true,
+            "async" => self.r#async = true,
+            "derive_ser" => self.serialize = TargetSet::ALL,
+            "derive_deser" => self.deserialize = TargetSet::ALL,
+            "serialize" => self.serialize = targets(&meta)?,
+            "deserialize" => self.deserialize = targets(&meta)?,
+            "error_type" => self.error_type = Some(meta.value()?.parse::<LitStr>()?.value()),
+            "types_path" => self.types_path = Some(meta.value()?.parse::<LitStr>()?.value()),
+            "date_time_crate" => {
+                let value: LitStr = meta.value()?.parse()?;
+                self.date_time_crate = match value.value().as_str() {
+                    "time" => DateTimeCrate::Time,
+                    "chrono" => DateTimeCrate::Chrono,
+                    _ => return Err(syn::Error::new(value.span(), "expected `time` or `chrono`")),
+                };
+            }
+            "numeric_crate" => {
+                let value: LitStr = meta.value()?.parse()?;
+                self.numeric_crate = match value.value().as_str() {
+                    "rust_decimal" => NumericCrate::RustDecimal,
+                    "bigdecimal" => NumericCrate::BigDecimal,
+                    _ => {
+                        return Err(syn::Error::new(
+                            value.span(),
+                            "expected `rust_decimal` or `bigdecimal`",
+                        ))
+                    }
+                };
+            }
+            "read_only_flag" | "normalize_sql" | "strict_nullability" | "infer_nullability"
+            | "merge_nullability" | "row_conversions" | "params_builders" | "param_conversions"
+            | "display" => self.flags.push(name),
+            _ => return Err(meta.error("unknown cornucopia setting")),
+        }
+        Ok(())
+    }
+
+    fn build(self) -> CodegenSettings {
+        let flag = |name: &str| self.flags.iter().any(|it| it == name);
+        let mut builder: CodegenSettingsBuilder = CodegenSettings::builder()
+            .async_(self.r#async || !self.sync)
+            .sync(self.sync)
+            .serialize(self.serialize)
+            .deserialize(self.deserialize)
+            .date_time_crate(self.date_time_crate)
+            .numeric_crate(self.numeric_crate)
+            .generate_read_only_flag(flag("read_only_flag"))
+            .normalize_sql(flag("normalize_sql"))
+            .strict_nullability(flag("strict_nullability"))
+            .infer_nullability(flag("infer_nullability"))
+            .merge_nullability(flag("merge_nullability"))
+            .row_conversions(flag("row_conversions"))
+            .params_builders(flag("params_builders"))
+            .param_conversions(flag("param_conversions"))
+            .display(flag("display"));
+        if let Some(path) = self.error_type {
+            builder = builder.error_type(path);
+        }
+        if let Some(path) = self.types_path {
+            builder = builder.types_path(path);
+        }
+        builder.build()
+    }
+}
+
+/// The constant followed by the module of its generated queries
+fn expand(settings: CodegenSettings, item: &ItemConst) -> syn::Result<TokenStream2> {
+    let Expr::Lit(ExprLit {
+        lit: Lit::Str(sql), ..
+    }) = item.expr.as_ref()
+    else {
+        return Err(syn::Error::new_spanned(
+            &item.expr,
+            "expected the queries as a string literal",
+        ));
+    };
+    let name = item.ident.to_string().to_lowercase();
+    let url = std::env::var("DATABASE_URL").map_err(|_| {
+        syn::Error::new(
+            sql.span(),
+            "set `DATABASE_URL` to the database the queries are prepared against",
+        )
+    })?;
+    let mut client = postgres::Client::connect(&url, postgres::NoTls).map_err(|err| {
+        syn::Error::new(
+            sql.span(),
+            format!("could not connect to `DATABASE_URL`: {err}"),
+        )
+    })?;
+    let generated = cornucopia::generate_module_live_with_observer(
+        &mut client,
+        &name,
+        &sql.value(),
+        settings,
+        &mut (),
+    )
+    .map_err(|err| syn::Error::new(sql.span(), err.report()))?;
+    let generated: TokenStream2 = generated.parse()?;
+
+    let (vis, module) = (&item.vis, format_ident!("{name}"));
+    Ok(quote! {
+        #item
+        #vis mod #module {
+            #generated
+            pub use self::queries::#module::*;
+        }
+    })
+}
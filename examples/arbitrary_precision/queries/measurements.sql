@@ -0,0 +1,15 @@
+--! insert_measurement (uncertainty?)
+INSERT INTO measurement (name, value, uncertainty, samples)
+VALUES (:name, :value, :uncertainty, :samples);
+
+--! measurements : (uncertainty?)
+SELECT * FROM measurement ORDER BY name;
+
+--! total
+SELECT sum(value) AS "total!" FROM measurement;
+
+--! above
+SELECT name FROM measurement WHERE value > :threshold ORDER BY name;
+
+--! nan
+SELECT 'NaN'::NUMERIC AS "value!";
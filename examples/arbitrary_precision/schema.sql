@@ -0,0 +1,6 @@
+CREATE TABLE measurement (
+    name TEXT PRIMARY KEY,
+    value NUMERIC NOT NULL,
+    uncertainty NUMERIC,
+    samples NUMERIC[] NOT NULL
+);
@@ -0,0 +1,418 @@
+// This file was generated with `cornucopia`. Do not modify.
+
+#[allow(clippy::all, clippy::pedantic)]
+#[allow(unused_variables)]
+#[allow(unused_imports)]
+#[allow(dead_code)]
+pub mod types {}
+#[allow(clippy::all, clippy::pedantic)]
+#[allow(unused_variables)]
+#[allow(unused_imports)]
+#[allow(dead_code)]
+pub mod queries {
+    pub mod measurements {
+        #[derive(Debug)]
+        pub struct InsertMeasurementParams<'a, T1: cornucopia_sync::StringSql> {
+            pub name: T1,
+            pub value: &'a bigdecimal::BigDecimal,
+            pub uncertainty: Option<&'a bigdecimal::BigDecimal>,
+            pub samples: &'a [bigdecimal::BigDecimal],
+        }
+        #[derive(Debug, Clone, PartialEq, Eq, Hash, PartialOrd, Ord)]
+        pub struct Measurements {
+            pub name: String,
+            pub value: bigdecimal::BigDecimal,
+            pub uncertainty: Option<bigdecimal::BigDecimal>,
+            pub samples: Vec<bigdecimal::BigDecimal>,
+        }
+        pub struct MeasurementsBorrowed<'a> {
+            pub name: &'a str,
+            pub value: cornucopia_sync::BigNumeric<'a>,
+            pub uncertainty: Option<cornucopia_sync::BigNumeric<'a>>,
+            pub samples: cornucopia_sync::ArrayIterator<'a, cornucopia_sync::BigNumeric<'a>>,
+        }
+        impl<'a> From<MeasurementsBorrowed<'a>> for Measurements {
+            fn from(
+                MeasurementsBorrowed {
+                    name,
+                    value,
+                    uncertainty,
+                    samples,
+                }: MeasurementsBorrowed<'a>,
+            ) -> Self {
+                Self {
+                    name: name.into(),
+                    value: value.into(),
+                    uncertainty: uncertainty.map(|v| v.into()),
+                    samples: samples.map(|v| v.into()).collect(),
+                }
+            }
+        }
+        use postgres::{fallible_iterator::FallibleIterator, GenericClient};
+        pub struct MeasurementsQuery<'a, C: GenericClient, T, const N: usize, E = postgres::Error> {
+            client: &'a mut C,
+            params: [&'a (dyn postgres_types::ToSql + Sync); N],
+            stmt: &'a mut cornucopia_sync::private::Stmt,
+            extractor: fn(&postgres::Row) -> Result<MeasurementsBorrowed, postgres::Error>,
+            mapper: fn(MeasurementsBorrowed) -> T,
+            error: fn(postgres::Error) -> E,
+        }
+        impl<'a, C, T: 'a, const N: usize, E: 'a> MeasurementsQuery<'a, C, T, N, E>
+        where
+            C: GenericClient,
+        {
+            pub fn map<R>(
+                self,
+                mapper: fn(MeasurementsBorrowed) -> R,
+            ) -> MeasurementsQuery<'a, C, R, N, E> {
+                MeasurementsQuery {
+                    client: self.client,
+                    params: self.params,
+                    stmt: self.stmt,
+                    extractor: self.extractor,
+                    mapper,
+                    error: self.error,
+                }
+            }
+            pub fn one(self) -> Result<T, E> {
+                let row = self
+                    .stmt
+                    .query_one(self.client, &self.params)
+                    .map_err(self.error)?;
+                Ok((self.mapper)((self.extractor)(&row).map_err(self.error)?))
+            }
+            pub fn opt(self) -> Result<Option<T>, E> {
+                let row = self
+                    .stmt
+                    .query_opt(self.client, &self.params)
+                    .map_err(self.error)?;
+                row.map(|row| Ok((self.mapper)((self.extractor)(&row).map_err(self.error)?)))
+                    .transpose()
+            }
+            pub fn all(self) -> Result<Vec<T>, E> {
+                self.iter()?.collect()
+            }
+            pub fn iter(self) -> Result<impl Iterator<Item = Result<T, E>> + 'a, E> {
+                let it = self
+                    .stmt
+                    .query_raw(self.client, &self.params)
+                    .map_err(self.error)?
+                    .iterator()
+                    .map(move |res| {
+                        res.and_then(|row| (self.extractor)(&row).map(self.mapper))
+                            .map_err(self.error)
+                    });
+                Ok(it)
+            }
+            #[doc = " Declares a server-side cursor over the rows, fetching them in chunks instead of"]
+            #[doc = " buffering them all. Cursors only live inside a transaction."]
+            pub fn cursor(self) -> Result<cornucopia_sync::Cursor<'a, C, T, E>, E>
+            where
+                C: cornucopia_sync::InTransaction,
+            {
+                let (extractor, mapper) = (self.extractor, self.mapper);
+                self.stmt.cursor(
+                    self.client,
+                    &self.params,
+                    move |row| extractor(row).map(mapper),
+                    self.error,
+                )
+            }
+        }
+        pub struct BigdecimalBigDecimalQuery<
+            'a,
+            C: GenericClient,
+            T,
+            const N: usize,
+            E = postgres::Error,
+        > {
+            client: &'a mut C,
+            params: [&'a (dyn postgres_types::ToSql + Sync); N],
+            stmt: &'a mut cornucopia_sync::private::Stmt,
+            extractor:
+                fn(&postgres::Row) -> Result<cornucopia_sync::BigNumeric<'_>, postgres::Error>,
+            mapper: fn(cornucopia_sync::BigNumeric<'_>) -> T,
+            error: fn(postgres::Error) -> E,
+        }
+        impl<'a, C, T: 'a, const N: usize, E: 'a> BigdecimalBigDecimalQuery<'a, C, T, N, E>
+        where
+            C: GenericClient,
+        {
+            pub fn map<R>(
+                self,
+                mapper: fn(cornucopia_sync::BigNumeric<'_>) -> R,
+            ) -> BigdecimalBigDecimalQuery<'a, C, R, N, E> {
+                BigdecimalBigDecimalQuery {
+                    client: self.client,
+                    params: self.params,
+                    stmt: self.stmt,
+                    extractor: self.extractor,
+                    mapper,
+                    error: self.error,
+                }
+            }
+            pub fn one(self) -> Result<T, E> {
+                let row = self
+                    .stmt
+                    .query_one(self.client, &self.params)
+                    .map_err(self.error)?;
+                Ok((self.mapper)((self.extractor)(&row).map_err(self.error)?))
+            }
+            pub fn opt(self) -> Result<Option<T>, E> {
+                let row = self
+                    .stmt
+                    .query_opt(self.client, &self.params)
+                    .map_err(self.error)?;
+                row.map(|row| Ok((self.mapper)((self.extractor)(&row).map_err(self.error)?)))
+                    .transpose()
+            }
+            pub fn all(self) -> Result<Vec<T>, E> {
+                self.iter()?.collect()
+            }
+            pub fn iter(self) -> Result<impl Iterator<Item = Result<T, E>> + 'a, E> {
+                let it = self
+                    .stmt
+                    .query_raw(self.client, &self.params)
+                    .map_err(self.error)?
+                    .iterator()
+                    .map(move |res| {
+                        res.and_then(|row| (self.extractor)(&row).map(self.mapper))
+                            .map_err(self.error)
+                    });
+                Ok(it)
+            }
+            #[doc = " Declares a server-side cursor over the rows, fetching them in chunks instead of"]
+            #[doc = " buffering them all. Cursors only live inside a transaction."]
+            pub fn cursor(self) -> Result<cornucopia_sync::Cursor<'a, C, T, E>, E>
+            where
+                C: cornucopia_sync::InTransaction,
+            {
+                let (extractor, mapper) = (self.extractor, self.mapper);
+                self.stmt.cursor(
+                    self.client,
+                    &self.params,
+                    move |row| extractor(row).map(mapper),
+                    self.error,
+                )
+            }
+        }
+        pub struct StringQuery<'a, C: GenericClient, T, const N: usize, E = postgres::Error> {
+            client: &'a mut C,
+            params: [&'a (dyn postgres_types::ToSql + Sync); N],
+            stmt: &'a mut cornucopia_sync::private::Stmt,
+            extractor: fn(&postgres::Row) -> Result<&str, postgres::Error>,
+            mapper: fn(&str) -> T,
+            error: fn(postgres::Error) -> E,
+        }
+        impl<'a, C, T: 'a, const N: usize, E: 'a> StringQuery<'a, C, T, N, E>
+        where
+            C: GenericClient,
+        {
+            pub fn map<R>(self, mapper: fn(&str) -> R) -> StringQuery<'a, C, R, N, E> {
+                StringQuery {
+                    client: self.client,
+                    params: self.params,
+                    stmt: self.stmt,
+                    extractor: self.extractor,
+                    mapper,
+                    error: self.error,
+                }
+            }
+            pub fn one(self) -> Result<T, E> {
+                let row = self
+                    .stmt
+                    .query_one(self.client, &self.params)
+                    .map_err(self.error)?;
+                Ok((self.mapper)((self.extractor)(&row).map_err(self.error)?))
+            }
+            pub fn opt(self) -> Result<Option<T>, E> {
+                let row = self
+                    .stmt
+                    .query_opt(self.client, &self.params)
+                    .map_err(self.error)?;
+                row.map(|row| Ok((self.mapper)((self.extractor)(&row).map_err(self.error)?)))
+                    .transpose()
+            }
+            pub fn all(self) -> Result<Vec<T>, E> {
+                self.iter()?.collect()
+            }
+            pub fn iter(self) -> Result<impl Iterator<Item = Result<T, E>> + 'a, E> {
+                let it = self
+                    .stmt
+                    .query_raw(self.client, &self.params)
+                    .map_err(self.error)?
+                    .iterator()
+                    .map(move |res| {
+                        res.and_then(|row| (self.extractor)(&row).map(self.mapper))
+                            .map_err(self.error)
+                    });
+                Ok(it)
+            }
+            #[doc = " Declares a server-side cursor over the rows, fetching them in chunks instead of"]
+            #[doc = " buffering them all. Cursors only live inside a transaction."]
+            pub fn cursor(self) -> Result<cornucopia_sync::Cursor<'a, C, T, E>, E>
+            where
+                C: cornucopia_sync::InTransaction,
+            {
+                let (extractor, mapper) = (self.extractor, self.mapper);
+                self.stmt.cursor(
+                    self.client,
+                    &self.params,
+                    move |row| extractor(row).map(mapper),
+                    self.error,
+                )
+            }
+        }
+        pub fn insert_measurement() -> InsertMeasurementStmt {
+            InsertMeasurementStmt(cornucopia_sync::private::Stmt::new(
+                "INSERT INTO measurement (name, value, uncertainty, samples)
+VALUES ($1, $2, $3, $4)",
+            ))
+        }
+        pub struct InsertMeasurementStmt(cornucopia_sync::private::Stmt);
+        impl InsertMeasurementStmt {
+            #[doc = " Latency budget in milliseconds declared by the `budget` option of the query"]
+            pub const BUDGET_MS: Option<u64> = None;
+            pub fn bind<'a, C: GenericClient, T1: cornucopia_sync::StringSql>(
+                &'a mut self,
+                client: &'a mut C,
+                name: &'a T1,
+                value: &'a &'a bigdecimal::BigDecimal,
+                uncertainty: &'a Option<&'a bigdecimal::BigDecimal>,
+                samples: &'a &'a [bigdecimal::BigDecimal],
+            ) -> Result<u64, postgres::Error> {
+                self.0.execute(
+                    client,
+                    &[
+                        name,
+                        cornucopia_sync::private::BigNumericSql::new(value),
+                        cornucopia_sync::private::BigNumericSql::new(uncertainty),
+                        cornucopia_sync::private::BigNumericSql::new(samples),
+                    ],
+                )
+            }
+        }
+        impl<'a, C: GenericClient, T1: cornucopia_sync::StringSql>
+            cornucopia_sync::Params<
+                'a,
+                InsertMeasurementParams<'a, T1>,
+                Result<u64, postgres::Error>,
+                C,
+            > for InsertMeasurementStmt
+        {
+            fn params(
+                &'a mut self,
+                client: &'a mut C,
+                params: &'a InsertMeasurementParams<'a, T1>,
+            ) -> Result<u64, postgres::Error> {
+                self.bind(
+                    client,
+                    &params.name,
+                    &params.value,
+                    &params.uncertainty,
+                    &params.samples,
+                )
+            }
+        }
+        pub fn measurements() -> MeasurementsStmt {
+            MeasurementsStmt(cornucopia_sync::private::Stmt::new(
+                "SELECT * FROM measurement ORDER BY name",
+            ))
+        }
+        pub struct MeasurementsStmt(cornucopia_sync::private::Stmt);
+        impl MeasurementsStmt {
+            #[doc = " Latency budget in milliseconds declared by the `budget` option of the query"]
+            pub const BUDGET_MS: Option<u64> = None;
+            pub fn bind<'a, C: GenericClient>(
+                &'a mut self,
+                client: &'a mut C,
+            ) -> MeasurementsQuery<'a, C, Measurements, 0> {
+                MeasurementsQuery {
+                    client,
+                    params: [],
+                    stmt: &mut self.0,
+                    extractor: |row| {
+                        Ok(MeasurementsBorrowed {
+                            name: row.try_get(0)?,
+                            value: row.try_get(1)?,
+                            uncertainty: row.try_get(2)?,
+                            samples: row.try_get(3)?,
+                        })
+                    },
+                    mapper: |it| <Measurements>::from(it),
+                    error: From::from,
+                }
+            }
+        }
+        pub fn total() -> TotalStmt {
+            TotalStmt(cornucopia_sync::private::Stmt::new(
+                "SELECT sum(value) AS \"total!\" FROM measurement",
+            ))
+        }
+        pub struct TotalStmt(cornucopia_sync::private::Stmt);
+        impl TotalStmt {
+            #[doc = " Latency budget in milliseconds declared by the `budget` option of the query"]
+            pub const BUDGET_MS: Option<u64> = None;
+            pub fn bind<'a, C: GenericClient>(
+                &'a mut self,
+                client: &'a mut C,
+            ) -> BigdecimalBigDecimalQuery<'a, C, bigdecimal::BigDecimal, 0> {
+                BigdecimalBigDecimalQuery {
+                    client,
+                    params: [],
+                    stmt: &mut self.0,
+                    extractor: |row| row.try_get(0),
+                    mapper: |it| it.into(),
+                    error: From::from,
+                }
+            }
+        }
+        pub fn above() -> AboveStmt {
+            AboveStmt(cornucopia_sync::private::Stmt::new(
+                "SELECT name FROM measurement WHERE value > $1 ORDER BY name",
+            ))
+        }
+        pub struct AboveStmt(cornucopia_sync::private::Stmt);
+        impl AboveStmt {
+            #[doc = " Latency budget in milliseconds declared by the `budget` option of the query"]
+            pub const BUDGET_MS: Option<u64> = None;
+            pub fn bind<'a, C: GenericClient>(
+                &'a mut self,
+                client: &'a mut C,
+                threshold: &'a &'a bigdecimal::BigDecimal,
+            ) -> StringQuery<'a, C, String, 1> {
+                StringQuery {
+                    client,
+                    params: [cornucopia_sync::private::BigNumericSql::new(threshold)],
+                    stmt: &mut self.0,
+                    extractor: |row| row.try_get(0),
+                    mapper: |it| it.into(),
+                    error: From::from,
+                }
+            }
+        }
+        pub fn nan() -> NanStmt {
+            NanStmt(cornucopia_sync::private::Stmt::new(
+                "SELECT 'NaN'::NUMERIC AS \"value!\"",
+            ))
+        }
+        pub struct NanStmt(cornucopia_sync::private::Stmt);
+        impl NanStmt {
+            #[doc = " Latency budget in milliseconds declared by the `budget` option of the query"]
+            pub const BUDGET_MS: Option<u64> = None;
+            pub fn bind<'a, C: GenericClient>(
+                &'a mut self,
+                client: &'a mut C,
+            ) -> BigdecimalBigDecimalQuery<'a, C, bigdecimal::BigDecimal, 0> {
+                BigdecimalBigDecimalQuery {
+                    client,
+                    params: [],
+                    stmt: &mut self.0,
+                    extractor: |row| row.try_get(0),
+                    mapper: |it| it.into(),
+                    error: From::from,
+                }
+            }
+        }
+    }
+}
@@ -1,4 +1,4 @@
-use cornucopia::{CodegenSettings, Error};
+use cornucopia::{CodegenSettings, Error, ProgressEvent};
 
 // This script will generate a new cornucopia file every time your schema or queries change.
 // In this example, we generate the module in our project, but
@@ -8,19 +8,22 @@ fn main() -> Result<(), Error> {
     let queries_path = "queries";
     let schema_file = "schema.sql";
     let destination = "src/cornucopia.rs";
-    let settings = CodegenSettings {
-        is_async: true,
-        derive_ser: false,
-    };
+    let settings = CodegenSettings::builder().async_(true).build();
 
     println!("cargo:rerun-if-changed={queries_path}");
     println!("cargo:rerun-if-changed={schema_file}");
-    cornucopia::generate_managed(
+    cornucopia::generate_managed_with_observer(
         queries_path,
         &[schema_file],
         Some(destination),
         false,
         settings,
+        // Show the warnings of code generation in cargo's output
+        &mut |event| {
+            if let ProgressEvent::WarningEmitted(warning) = event {
+                println!("cargo:warning={warning}");
+            }
+        },
     )?;
 
     Ok(())
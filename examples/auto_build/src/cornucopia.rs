@@ -14,57 +14,77 @@ pub mod queries {
         use cornucopia_async::GenericClient;
         use futures;
         use futures::{StreamExt, TryStreamExt};
-        pub struct StringQuery<'a, C: GenericClient, T, const N: usize> {
+        pub struct StringQuery<'a, C: GenericClient, T, const N: usize, E = tokio_postgres::Error> {
             client: &'a C,
             params: [&'a (dyn postgres_types::ToSql + Sync); N],
             stmt: &'a mut cornucopia_async::private::Stmt,
-            extractor: fn(&tokio_postgres::Row) -> &str,
+            extractor: fn(&tokio_postgres::Row) -> Result<&str, tokio_postgres::Error>,
             mapper: fn(&str) -> T,
+            error: fn(tokio_postgres::Error) -> E,
         }
-        impl<'a, C, T: 'a, const N: usize> StringQuery<'a, C, T, N>
+        impl<'a, C, T: 'a, const N: usize, E: 'a> StringQuery<'a, C, T, N, E>
         where
             C: GenericClient,
         {
-            pub fn map<R>(self, mapper: fn(&str) -> R) -> StringQuery<'a, C, R, N> {
+            pub fn map<R>(self, mapper: fn(&str) -> R) -> StringQuery<'a, C, R, N, E> {
                 StringQuery {
                     client: self.client,
                     params: self.params,
                     stmt: self.stmt,
                     extractor: self.extractor,
                     mapper,
+                    error: self.error,
                 }
             }
-            pub async fn one(self) -> Result<T, tokio_postgres::Error> {
-                let stmt = self.stmt.prepare(self.client).await?;
-                let row = self.client.query_one(stmt, &self.params).await?;
-                Ok((self.mapper)((self.extractor)(&row)))
+            pub async fn one(self) -> Result<T, E> {
+                let row = self
+                    .stmt
+                    .query_one(self.client, &self.params)
+                    .await
+                    .map_err(self.error)?;
+                Ok((self.mapper)((self.extractor)(&row).map_err(self.error)?))
             }
-            pub async fn all(self) -> Result<Vec<T>, tokio_postgres::Error> {
-                self.iter().await?.try_collect().await
+            pub async fn opt(self) -> Result<Option<T>, E> {
+                let row = self
+                    .stmt
+                    .query_opt(self.client, &self.params)
+                    .await
+                    .map_err(self.error)?;
+                row.map(|row| Ok((self.mapper)((self.extractor)(&row).map_err(self.error)?)))
+                    .transpose()
             }
-            pub async fn opt(self) -> Result<Option<T>, tokio_postgres::Error> {
-                let stmt = self.stmt.prepare(self.client).await?;
-                Ok(self
-                    .client
-                    .query_opt(stmt, &self.params)
-                    .await?
-                    .map(|row| (self.mapper)((self.extractor)(&row))))
+            pub async fn all(self) -> Result<Vec<T>, E> {
+                self.iter().await?.try_collect().await
             }
-            pub async fn iter(
-                self,
-            ) -> Result<
-                impl futures::Stream<Item = Result<T, tokio_postgres::Error>> + 'a,
-                tokio_postgres::Error,
-            > {
-                let stmt = self.stmt.prepare(self.client).await?;
+            pub async fn iter(self) -> Result<impl futures::Stream<Item = Result<T, E>> + 'a, E> {
                 let it = self
-                    .client
-                    .query_raw(stmt, cornucopia_async::private::slice_iter(&self.params))
-                    .await?
-                    .map(move |res| res.map(|row| (self.mapper)((self.extractor)(&row))))
+                    .stmt
+                    .query_raw(self.client, &self.params)
+                    .await
+                    .map_err(self.error)?
+                    .map(move |res| {
+                        res.and_then(|row| (self.extractor)(&row).map(self.mapper))
+                            .map_err(self.error)
+                    })
                     .into_stream();
                 Ok(it)
             }
+            #[doc = " Declares a server-side cursor over the rows, fetching them in chunks instead of"]
+            #[doc = " buffering them all. Cursors only live inside a transaction."]
+            pub async fn cursor(self) -> Result<cornucopia_async::Cursor<'a, C, T, E>, E>
+            where
+                C: cornucopia_async::InTransaction,
+            {
+                let (extractor, mapper) = (self.extractor, self.mapper);
+                self.stmt
+                    .cursor(
+                        self.client,
+                        &self.params,
+                        move |row| extractor(row).map(mapper),
+                        self.error,
+                    )
+                    .await
+            }
         }
         pub fn example_query() -> ExampleQueryStmt {
             ExampleQueryStmt(cornucopia_async::private::Stmt::new(
@@ -76,6 +96,8 @@ FROM
         }
         pub struct ExampleQueryStmt(cornucopia_async::private::Stmt);
         impl ExampleQueryStmt {
+            #[doc = " Latency budget in milliseconds declared by the `budget` option of the query"]
+            pub const BUDGET_MS: Option<u64> = None;
             pub fn bind<'a, C: GenericClient>(
                 &'a mut self,
                 client: &'a C,
@@ -84,8 +106,9 @@ FROM
                     client,
                     params: [],
                     stmt: &mut self.0,
-                    extractor: |row| row.get(0),
+                    extractor: |row| row.try_get(0),
                     mapper: |it| it.into(),
+                    error: From::from,
                 }
             }
         }
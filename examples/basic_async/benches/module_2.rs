@@ -0,0 +1,36 @@
+// This file was generated with `cornucopia`. Do not modify.
+
+#[path = "../src/cornucopia.rs"]
+mod cornucopia;
+use criterion::{criterion_group, criterion_main, Criterion};
+fn bench(c: &mut Criterion) {
+    let url = std::env::var("CORNUCOPIA_BENCH_URL").expect("CORNUCOPIA_BENCH_URL should be set");
+    let runtime = tokio::runtime::Runtime::new().unwrap();
+    let (mut client, connection) = runtime
+        .block_on(tokio_postgres::connect(&url, tokio_postgres::NoTls))
+        .unwrap();
+    runtime.spawn(connection);
+    let transaction = runtime.block_on(client.transaction()).unwrap();
+    {
+        let mut stmt = cornucopia::queries::module_2::authors();
+        c.bench_function("module_2::authors", |b| {
+            b.iter(|| runtime.block_on(async { stmt.bind(&transaction).all().await.unwrap() }))
+        });
+    }
+    {
+        let mut stmt = cornucopia::queries::module_2::author_name_by_id();
+        c.bench_function("module_2::author_name_by_id", |b| {
+            b.iter(|| runtime.block_on(async { stmt.bind(&transaction, &1).all().await.unwrap() }))
+        });
+    }
+    {
+        let mut stmt = cornucopia::queries::module_2::author_name_starting_with();
+        c.bench_function("module_2::author_name_starting_with", |b| {
+            b.iter(|| {
+                runtime.block_on(async { stmt.bind(&transaction, &"Jo").all().await.unwrap() })
+            })
+        });
+    }
+}
+criterion_group!(benches, bench);
+criterion_main!(benches);
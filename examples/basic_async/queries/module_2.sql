@@ -31,7 +31,10 @@ FROM
 WHERE
     Author.Name LIKE CONCAT(:start_str::text, '%');
 
+-- Custom types are only known once prepared, so this query stays prepared even when
+-- generated using `--no-prepared-statements`
 --! select_voice_actor_with_character
+-- :prepared true
 SELECT
     voice_actor
 FROM
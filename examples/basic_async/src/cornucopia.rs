@@ -6,7 +6,7 @@
 #[allow(dead_code)]
 pub mod types {
     pub mod public {
-        #[derive(Debug, Clone, Copy, PartialEq, Eq)]
+        #[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, PartialOrd, Ord)]
         #[allow(non_camel_case_types)]
         pub enum SpongeBobCharacter {
             Bob,
@@ -65,7 +65,13 @@ pub mod types {
                     "Bob" => Ok(SpongeBobCharacter::Bob),
                     "Patrick" => Ok(SpongeBobCharacter::Patrick),
                     "Squidward" => Ok(SpongeBobCharacter::Squidward),
-                    s => Result::Err(Into::into(format!("invalid variant `{}`", s))),
+                    s => Result::Err(Into::into(format!(
+                        concat!(
+                            "unknown label `{}` of the enum `public.sponge_bob_character`, ",
+                            "the code must be regenerated to decode it",
+                        ),
+                        s
+                    ))),
                 }
             }
             fn accepts(ty: &postgres_types::Type) -> bool {
@@ -88,7 +94,7 @@ pub mod types {
                 }
             }
         }
-        #[derive(Debug, postgres_types :: FromSql, Clone, PartialEq)]
+        #[derive(Debug, postgres_types::FromSql, Clone, PartialEq)]
         #[postgres(name = "voiceactor")]
         pub struct Voiceactor {
             #[postgres(name = "name")]
@@ -220,13 +226,14 @@ pub mod queries {
         }
         pub struct InsertBookStmt(cornucopia_async::private::Stmt);
         impl InsertBookStmt {
+            #[doc = " Latency budget in milliseconds declared by the `budget` option of the query"]
+            pub const BUDGET_MS: Option<u64> = None;
             pub async fn bind<'a, C: GenericClient, T1: cornucopia_async::StringSql>(
                 &'a mut self,
                 client: &'a C,
                 title: &'a T1,
             ) -> Result<u64, tokio_postgres::Error> {
-                let stmt = self.0.prepare(client).await?;
-                client.execute(stmt, &[title]).await
+                self.0.execute(client, &[title]).await
             }
         }
     }
@@ -235,7 +242,7 @@ pub mod queries {
         pub struct AuthorNameStartingWithParams<T1: cornucopia_async::StringSql> {
             pub start_str: T1,
         }
-        #[derive(Debug, Clone, PartialEq)]
+        #[derive(Debug, Clone, PartialEq, Eq, Hash, PartialOrd, Ord)]
         pub struct Authors {
             pub id: i32,
             pub name: String,
@@ -255,7 +262,7 @@ pub mod queries {
                 }
             }
         }
-        #[derive(Debug, Clone, PartialEq)]
+        #[derive(Debug, Clone, PartialEq, Eq, Hash, PartialOrd, Ord)]
         pub struct AuthorNameStartingWith {
             pub authorid: i32,
             pub name: String,
@@ -285,7 +292,7 @@ pub mod queries {
                 }
             }
         }
-        #[derive(Debug, Clone, PartialEq)]
+        #[derive(Debug, Clone, PartialEq, Eq, Hash, PartialOrd, Ord)]
         pub struct SelectTranslations {
             pub title: String,
             pub translations: Vec<String>,
@@ -310,274 +317,402 @@ pub mod queries {
         use cornucopia_async::GenericClient;
         use futures;
         use futures::{StreamExt, TryStreamExt};
-        pub struct AuthorsQuery<'a, C: GenericClient, T, const N: usize> {
+        pub struct AuthorsQuery<'a, C: GenericClient, T, const N: usize, E = tokio_postgres::Error> {
             client: &'a C,
             params: [&'a (dyn postgres_types::ToSql + Sync); N],
             stmt: &'a mut cornucopia_async::private::Stmt,
-            extractor: fn(&tokio_postgres::Row) -> AuthorsBorrowed,
+            extractor: fn(&tokio_postgres::Row) -> Result<AuthorsBorrowed, tokio_postgres::Error>,
             mapper: fn(AuthorsBorrowed) -> T,
+            error: fn(tokio_postgres::Error) -> E,
         }
-        impl<'a, C, T: 'a, const N: usize> AuthorsQuery<'a, C, T, N>
+        impl<'a, C, T: 'a, const N: usize, E: 'a> AuthorsQuery<'a, C, T, N, E>
         where
             C: GenericClient,
         {
-            pub fn map<R>(self, mapper: fn(AuthorsBorrowed) -> R) -> AuthorsQuery<'a, C, R, N> {
+            pub fn map<R>(self, mapper: fn(AuthorsBorrowed) -> R) -> AuthorsQuery<'a, C, R, N, E> {
                 AuthorsQuery {
                     client: self.client,
                     params: self.params,
                     stmt: self.stmt,
                     extractor: self.extractor,
                     mapper,
+                    error: self.error,
                 }
             }
-            pub async fn one(self) -> Result<T, tokio_postgres::Error> {
-                let stmt = self.stmt.prepare(self.client).await?;
-                let row = self.client.query_one(stmt, &self.params).await?;
-                Ok((self.mapper)((self.extractor)(&row)))
-            }
-            pub async fn all(self) -> Result<Vec<T>, tokio_postgres::Error> {
+            pub async fn one(self) -> Result<T, E> {
+                let row = self
+                    .stmt
+                    .query_one(self.client, &self.params)
+                    .await
+                    .map_err(self.error)?;
+                Ok((self.mapper)((self.extractor)(&row).map_err(self.error)?))
+            }
+            pub async fn opt(self) -> Result<Option<T>, E> {
+                let row = self
+                    .stmt
+                    .query_opt(self.client, &self.params)
+                    .await
+                    .map_err(self.error)?;
+                row.map(|row| Ok((self.mapper)((self.extractor)(&row).map_err(self.error)?)))
+                    .transpose()
+            }
+            pub async fn all(self) -> Result<Vec<T>, E> {
                 self.iter().await?.try_collect().await
             }
-            pub async fn opt(self) -> Result<Option<T>, tokio_postgres::Error> {
-                let stmt = self.stmt.prepare(self.client).await?;
-                Ok(self
-                    .client
-                    .query_opt(stmt, &self.params)
-                    .await?
-                    .map(|row| (self.mapper)((self.extractor)(&row))))
-            }
-            pub async fn iter(
-                self,
-            ) -> Result<
-                impl futures::Stream<Item = Result<T, tokio_postgres::Error>> + 'a,
-                tokio_postgres::Error,
-            > {
-                let stmt = self.stmt.prepare(self.client).await?;
+            pub async fn iter(self) -> Result<impl futures::Stream<Item = Result<T, E>> + 'a, E> {
                 let it = self
-                    .client
-                    .query_raw(stmt, cornucopia_async::private::slice_iter(&self.params))
-                    .await?
-                    .map(move |res| res.map(|row| (self.mapper)((self.extractor)(&row))))
+                    .stmt
+                    .query_raw(self.client, &self.params)
+                    .await
+                    .map_err(self.error)?
+                    .map(move |res| {
+                        res.and_then(|row| (self.extractor)(&row).map(self.mapper))
+                            .map_err(self.error)
+                    })
                     .into_stream();
                 Ok(it)
             }
-        }
-        pub struct StringQuery<'a, C: GenericClient, T, const N: usize> {
+            #[doc = " Declares a server-side cursor over the rows, fetching them in chunks instead of"]
+            #[doc = " buffering them all. Cursors only live inside a transaction."]
+            pub async fn cursor(self) -> Result<cornucopia_async::Cursor<'a, C, T, E>, E>
+            where
+                C: cornucopia_async::InTransaction,
+            {
+                let (extractor, mapper) = (self.extractor, self.mapper);
+                self.stmt
+                    .cursor(
+                        self.client,
+                        &self.params,
+                        move |row| extractor(row).map(mapper),
+                        self.error,
+                    )
+                    .await
+            }
+        }
+        pub struct StringQuery<'a, C: GenericClient, T, const N: usize, E = tokio_postgres::Error> {
             client: &'a C,
             params: [&'a (dyn postgres_types::ToSql + Sync); N],
             stmt: &'a mut cornucopia_async::private::Stmt,
-            extractor: fn(&tokio_postgres::Row) -> &str,
+            extractor: fn(&tokio_postgres::Row) -> Result<&str, tokio_postgres::Error>,
             mapper: fn(&str) -> T,
+            error: fn(tokio_postgres::Error) -> E,
         }
-        impl<'a, C, T: 'a, const N: usize> StringQuery<'a, C, T, N>
+        impl<'a, C, T: 'a, const N: usize, E: 'a> StringQuery<'a, C, T, N, E>
         where
             C: GenericClient,
         {
-            pub fn map<R>(self, mapper: fn(&str) -> R) -> StringQuery<'a, C, R, N> {
+            pub fn map<R>(self, mapper: fn(&str) -> R) -> StringQuery<'a, C, R, N, E> {
                 StringQuery {
                     client: self.client,
                     params: self.params,
                     stmt: self.stmt,
                     extractor: self.extractor,
                     mapper,
+                    error: self.error,
                 }
             }
-            pub async fn one(self) -> Result<T, tokio_postgres::Error> {
-                let stmt = self.stmt.prepare(self.client).await?;
-                let row = self.client.query_one(stmt, &self.params).await?;
-                Ok((self.mapper)((self.extractor)(&row)))
-            }
-            pub async fn all(self) -> Result<Vec<T>, tokio_postgres::Error> {
+            pub async fn one(self) -> Result<T, E> {
+                let row = self
+                    .stmt
+                    .query_one(self.client, &self.params)
+                    .await
+                    .map_err(self.error)?;
+                Ok((self.mapper)((self.extractor)(&row).map_err(self.error)?))
+            }
+            pub async fn opt(self) -> Result<Option<T>, E> {
+                let row = self
+                    .stmt
+                    .query_opt(self.client, &self.params)
+                    .await
+                    .map_err(self.error)?;
+                row.map(|row| Ok((self.mapper)((self.extractor)(&row).map_err(self.error)?)))
+                    .transpose()
+            }
+            pub async fn all(self) -> Result<Vec<T>, E> {
                 self.iter().await?.try_collect().await
             }
-            pub async fn opt(self) -> Result<Option<T>, tokio_postgres::Error> {
-                let stmt = self.stmt.prepare(self.client).await?;
-                Ok(self
-                    .client
-                    .query_opt(stmt, &self.params)
-                    .await?
-                    .map(|row| (self.mapper)((self.extractor)(&row))))
-            }
-            pub async fn iter(
-                self,
-            ) -> Result<
-                impl futures::Stream<Item = Result<T, tokio_postgres::Error>> + 'a,
-                tokio_postgres::Error,
-            > {
-                let stmt = self.stmt.prepare(self.client).await?;
+            pub async fn iter(self) -> Result<impl futures::Stream<Item = Result<T, E>> + 'a, E> {
                 let it = self
-                    .client
-                    .query_raw(stmt, cornucopia_async::private::slice_iter(&self.params))
-                    .await?
-                    .map(move |res| res.map(|row| (self.mapper)((self.extractor)(&row))))
+                    .stmt
+                    .query_raw(self.client, &self.params)
+                    .await
+                    .map_err(self.error)?
+                    .map(move |res| {
+                        res.and_then(|row| (self.extractor)(&row).map(self.mapper))
+                            .map_err(self.error)
+                    })
                     .into_stream();
                 Ok(it)
             }
-        }
-        pub struct AuthorNameStartingWithQuery<'a, C: GenericClient, T, const N: usize> {
+            #[doc = " Declares a server-side cursor over the rows, fetching them in chunks instead of"]
+            #[doc = " buffering them all. Cursors only live inside a transaction."]
+            pub async fn cursor(self) -> Result<cornucopia_async::Cursor<'a, C, T, E>, E>
+            where
+                C: cornucopia_async::InTransaction,
+            {
+                let (extractor, mapper) = (self.extractor, self.mapper);
+                self.stmt
+                    .cursor(
+                        self.client,
+                        &self.params,
+                        move |row| extractor(row).map(mapper),
+                        self.error,
+                    )
+                    .await
+            }
+        }
+        pub struct AuthorNameStartingWithQuery<
+            'a,
+            C: GenericClient,
+            T,
+            const N: usize,
+            E = tokio_postgres::Error,
+        > {
             client: &'a C,
             params: [&'a (dyn postgres_types::ToSql + Sync); N],
             stmt: &'a mut cornucopia_async::private::Stmt,
-            extractor: fn(&tokio_postgres::Row) -> AuthorNameStartingWithBorrowed,
+            extractor: fn(
+                &tokio_postgres::Row,
+            )
+                -> Result<AuthorNameStartingWithBorrowed, tokio_postgres::Error>,
             mapper: fn(AuthorNameStartingWithBorrowed) -> T,
+            error: fn(tokio_postgres::Error) -> E,
         }
-        impl<'a, C, T: 'a, const N: usize> AuthorNameStartingWithQuery<'a, C, T, N>
+        impl<'a, C, T: 'a, const N: usize, E: 'a> AuthorNameStartingWithQuery<'a, C, T, N, E>
         where
             C: GenericClient,
         {
             pub fn map<R>(
                 self,
                 mapper: fn(AuthorNameStartingWithBorrowed) -> R,
-            ) -> AuthorNameStartingWithQuery<'a, C, R, N> {
+            ) -> AuthorNameStartingWithQuery<'a, C, R, N, E> {
                 AuthorNameStartingWithQuery {
                     client: self.client,
                     params: self.params,
                     stmt: self.stmt,
                     extractor: self.extractor,
                     mapper,
+                    error: self.error,
                 }
             }
-            pub async fn one(self) -> Result<T, tokio_postgres::Error> {
-                let stmt = self.stmt.prepare(self.client).await?;
-                let row = self.client.query_one(stmt, &self.params).await?;
-                Ok((self.mapper)((self.extractor)(&row)))
-            }
-            pub async fn all(self) -> Result<Vec<T>, tokio_postgres::Error> {
+            pub async fn one(self) -> Result<T, E> {
+                let row = self
+                    .stmt
+                    .query_one(self.client, &self.params)
+                    .await
+                    .map_err(self.error)?;
+                Ok((self.mapper)((self.extractor)(&row).map_err(self.error)?))
+            }
+            pub async fn opt(self) -> Result<Option<T>, E> {
+                let row = self
+                    .stmt
+                    .query_opt(self.client, &self.params)
+                    .await
+                    .map_err(self.error)?;
+                row.map(|row| Ok((self.mapper)((self.extractor)(&row).map_err(self.error)?)))
+                    .transpose()
+            }
+            pub async fn all(self) -> Result<Vec<T>, E> {
                 self.iter().await?.try_collect().await
             }
-            pub async fn opt(self) -> Result<Option<T>, tokio_postgres::Error> {
-                let stmt = self.stmt.prepare(self.client).await?;
-                Ok(self
-                    .client
-                    .query_opt(stmt, &self.params)
-                    .await?
-                    .map(|row| (self.mapper)((self.extractor)(&row))))
-            }
-            pub async fn iter(
-                self,
-            ) -> Result<
-                impl futures::Stream<Item = Result<T, tokio_postgres::Error>> + 'a,
-                tokio_postgres::Error,
-            > {
-                let stmt = self.stmt.prepare(self.client).await?;
+            pub async fn iter(self) -> Result<impl futures::Stream<Item = Result<T, E>> + 'a, E> {
                 let it = self
-                    .client
-                    .query_raw(stmt, cornucopia_async::private::slice_iter(&self.params))
-                    .await?
-                    .map(move |res| res.map(|row| (self.mapper)((self.extractor)(&row))))
+                    .stmt
+                    .query_raw(self.client, &self.params)
+                    .await
+                    .map_err(self.error)?
+                    .map(move |res| {
+                        res.and_then(|row| (self.extractor)(&row).map(self.mapper))
+                            .map_err(self.error)
+                    })
                     .into_stream();
                 Ok(it)
             }
-        }
-        pub struct PublicVoiceactorQuery<'a, C: GenericClient, T, const N: usize> {
+            #[doc = " Declares a server-side cursor over the rows, fetching them in chunks instead of"]
+            #[doc = " buffering them all. Cursors only live inside a transaction."]
+            pub async fn cursor(self) -> Result<cornucopia_async::Cursor<'a, C, T, E>, E>
+            where
+                C: cornucopia_async::InTransaction,
+            {
+                let (extractor, mapper) = (self.extractor, self.mapper);
+                self.stmt
+                    .cursor(
+                        self.client,
+                        &self.params,
+                        move |row| extractor(row).map(mapper),
+                        self.error,
+                    )
+                    .await
+            }
+        }
+        pub struct PublicVoiceactorQuery<
+            'a,
+            C: GenericClient,
+            T,
+            const N: usize,
+            E = tokio_postgres::Error,
+        > {
             client: &'a C,
             params: [&'a (dyn postgres_types::ToSql + Sync); N],
             stmt: &'a mut cornucopia_async::private::Stmt,
-            extractor: fn(&tokio_postgres::Row) -> super::super::types::public::VoiceactorBorrowed,
+            extractor: fn(
+                &tokio_postgres::Row,
+            ) -> Result<
+                super::super::types::public::VoiceactorBorrowed,
+                tokio_postgres::Error,
+            >,
             mapper: fn(super::super::types::public::VoiceactorBorrowed) -> T,
+            error: fn(tokio_postgres::Error) -> E,
         }
-        impl<'a, C, T: 'a, const N: usize> PublicVoiceactorQuery<'a, C, T, N>
+        impl<'a, C, T: 'a, const N: usize, E: 'a> PublicVoiceactorQuery<'a, C, T, N, E>
         where
             C: GenericClient,
         {
             pub fn map<R>(
                 self,
                 mapper: fn(super::super::types::public::VoiceactorBorrowed) -> R,
-            ) -> PublicVoiceactorQuery<'a, C, R, N> {
+            ) -> PublicVoiceactorQuery<'a, C, R, N, E> {
                 PublicVoiceactorQuery {
                     client: self.client,
                     params: self.params,
                     stmt: self.stmt,
                     extractor: self.extractor,
                     mapper,
+                    error: self.error,
                 }
             }
-            pub async fn one(self) -> Result<T, tokio_postgres::Error> {
-                let stmt = self.stmt.prepare(self.client).await?;
-                let row = self.client.query_one(stmt, &self.params).await?;
-                Ok((self.mapper)((self.extractor)(&row)))
-            }
-            pub async fn all(self) -> Result<Vec<T>, tokio_postgres::Error> {
+            pub async fn one(self) -> Result<T, E> {
+                let row = self
+                    .stmt
+                    .query_one(self.client, &self.params)
+                    .await
+                    .map_err(self.error)?;
+                Ok((self.mapper)((self.extractor)(&row).map_err(self.error)?))
+            }
+            pub async fn opt(self) -> Result<Option<T>, E> {
+                let row = self
+                    .stmt
+                    .query_opt(self.client, &self.params)
+                    .await
+                    .map_err(self.error)?;
+                row.map(|row| Ok((self.mapper)((self.extractor)(&row).map_err(self.error)?)))
+                    .transpose()
+            }
+            pub async fn all(self) -> Result<Vec<T>, E> {
                 self.iter().await?.try_collect().await
             }
-            pub async fn opt(self) -> Result<Option<T>, tokio_postgres::Error> {
-                let stmt = self.stmt.prepare(self.client).await?;
-                Ok(self
-                    .client
-                    .query_opt(stmt, &self.params)
-                    .await?
-                    .map(|row| (self.mapper)((self.extractor)(&row))))
-            }
-            pub async fn iter(
-                self,
-            ) -> Result<
-                impl futures::Stream<Item = Result<T, tokio_postgres::Error>> + 'a,
-                tokio_postgres::Error,
-            > {
-                let stmt = self.stmt.prepare(self.client).await?;
+            pub async fn iter(self) -> Result<impl futures::Stream<Item = Result<T, E>> + 'a, E> {
                 let it = self
-                    .client
-                    .query_raw(stmt, cornucopia_async::private::slice_iter(&self.params))
-                    .await?
-                    .map(move |res| res.map(|row| (self.mapper)((self.extractor)(&row))))
+                    .stmt
+                    .query_raw(self.client, &self.params)
+                    .await
+                    .map_err(self.error)?
+                    .map(move |res| {
+                        res.and_then(|row| (self.extractor)(&row).map(self.mapper))
+                            .map_err(self.error)
+                    })
                     .into_stream();
                 Ok(it)
             }
-        }
-        pub struct SelectTranslationsQuery<'a, C: GenericClient, T, const N: usize> {
+            #[doc = " Declares a server-side cursor over the rows, fetching them in chunks instead of"]
+            #[doc = " buffering them all. Cursors only live inside a transaction."]
+            pub async fn cursor(self) -> Result<cornucopia_async::Cursor<'a, C, T, E>, E>
+            where
+                C: cornucopia_async::InTransaction,
+            {
+                let (extractor, mapper) = (self.extractor, self.mapper);
+                self.stmt
+                    .cursor(
+                        self.client,
+                        &self.params,
+                        move |row| extractor(row).map(mapper),
+                        self.error,
+                    )
+                    .await
+            }
+        }
+        pub struct SelectTranslationsQuery<
+            'a,
+            C: GenericClient,
+            T,
+            const N: usize,
+            E = tokio_postgres::Error,
+        > {
             client: &'a C,
             params: [&'a (dyn postgres_types::ToSql + Sync); N],
             stmt: &'a mut cornucopia_async::private::Stmt,
-            extractor: fn(&tokio_postgres::Row) -> SelectTranslationsBorrowed,
+            extractor: fn(
+                &tokio_postgres::Row,
+            ) -> Result<SelectTranslationsBorrowed, tokio_postgres::Error>,
             mapper: fn(SelectTranslationsBorrowed) -> T,
+            error: fn(tokio_postgres::Error) -> E,
         }
-        impl<'a, C, T: 'a, const N: usize> SelectTranslationsQuery<'a, C, T, N>
+        impl<'a, C, T: 'a, const N: usize, E: 'a> SelectTranslationsQuery<'a, C, T, N, E>
         where
             C: GenericClient,
         {
             pub fn map<R>(
                 self,
                 mapper: fn(SelectTranslationsBorrowed) -> R,
-            ) -> SelectTranslationsQuery<'a, C, R, N> {
+            ) -> SelectTranslationsQuery<'a, C, R, N, E> {
                 SelectTranslationsQuery {
                     client: self.client,
                     params: self.params,
                     stmt: self.stmt,
                     extractor: self.extractor,
                     mapper,
+                    error: self.error,
                 }
             }
-            pub async fn one(self) -> Result<T, tokio_postgres::Error> {
-                let stmt = self.stmt.prepare(self.client).await?;
-                let row = self.client.query_one(stmt, &self.params).await?;
-                Ok((self.mapper)((self.extractor)(&row)))
-            }
-            pub async fn all(self) -> Result<Vec<T>, tokio_postgres::Error> {
+            pub async fn one(self) -> Result<T, E> {
+                let row = self
+                    .stmt
+                    .query_one(self.client, &self.params)
+                    .await
+                    .map_err(self.error)?;
+                Ok((self.mapper)((self.extractor)(&row).map_err(self.error)?))
+            }
+            pub async fn opt(self) -> Result<Option<T>, E> {
+                let row = self
+                    .stmt
+                    .query_opt(self.client, &self.params)
+                    .await
+                    .map_err(self.error)?;
+                row.map(|row| Ok((self.mapper)((self.extractor)(&row).map_err(self.error)?)))
+                    .transpose()
+            }
+            pub async fn all(self) -> Result<Vec<T>, E> {
                 self.iter().await?.try_collect().await
             }
-            pub async fn opt(self) -> Result<Option<T>, tokio_postgres::Error> {
-                let stmt = self.stmt.prepare(self.client).await?;
-                Ok(self
-                    .client
-                    .query_opt(stmt, &self.params)
-                    .await?
-                    .map(|row| (self.mapper)((self.extractor)(&row))))
-            }
-            pub async fn iter(
-                self,
-            ) -> Result<
-                impl futures::Stream<Item = Result<T, tokio_postgres::Error>> + 'a,
-                tokio_postgres::Error,
-            > {
-                let stmt = self.stmt.prepare(self.client).await?;
+            pub async fn iter(self) -> Result<impl futures::Stream<Item = Result<T, E>> + 'a, E> {
                 let it = self
-                    .client
-                    .query_raw(stmt, cornucopia_async::private::slice_iter(&self.params))
-                    .await?
-                    .map(move |res| res.map(|row| (self.mapper)((self.extractor)(&row))))
+                    .stmt
+                    .query_raw(self.client, &self.params)
+                    .await
+                    .map_err(self.error)?
+                    .map(move |res| {
+                        res.and_then(|row| (self.extractor)(&row).map(self.mapper))
+                            .map_err(self.error)
+                    })
                     .into_stream();
                 Ok(it)
             }
+            #[doc = " Declares a server-side cursor over the rows, fetching them in chunks instead of"]
+            #[doc = " buffering them all. Cursors only live inside a transaction."]
+            pub async fn cursor(self) -> Result<cornucopia_async::Cursor<'a, C, T, E>, E>
+            where
+                C: cornucopia_async::InTransaction,
+            {
+                let (extractor, mapper) = (self.extractor, self.mapper);
+                self.stmt
+                    .cursor(
+                        self.client,
+                        &self.params,
+                        move |row| extractor(row).map(mapper),
+                        self.error,
+                    )
+                    .await
+            }
         }
         pub fn authors() -> AuthorsStmt {
             AuthorsStmt(cornucopia_async::private::Stmt::new(
@@ -589,6 +724,8 @@ FROM
         }
         pub struct AuthorsStmt(cornucopia_async::private::Stmt);
         impl AuthorsStmt {
+            #[doc = " Latency budget in milliseconds declared by the `budget` option of the query"]
+            pub const BUDGET_MS: Option<u64> = None;
             pub fn bind<'a, C: GenericClient>(
                 &'a mut self,
                 client: &'a C,
@@ -597,12 +734,15 @@ FROM
                     client,
                     params: [],
                     stmt: &mut self.0,
-                    extractor: |row| AuthorsBorrowed {
-                        id: row.get(0),
-                        name: row.get(1),
-                        country: row.get(2),
+                    extractor: |row| {
+                        Ok(AuthorsBorrowed {
+                            id: row.try_get(0)?,
+                            name: row.try_get(1)?,
+                            country: row.try_get(2)?,
+                        })
                     },
                     mapper: |it| <Authors>::from(it),
+                    error: From::from,
                 }
             }
         }
@@ -616,6 +756,8 @@ FROM
         }
         pub struct BooksStmt(cornucopia_async::private::Stmt);
         impl BooksStmt {
+            #[doc = " Latency budget in milliseconds declared by the `budget` option of the query"]
+            pub const BUDGET_MS: Option<u64> = None;
             pub fn bind<'a, C: GenericClient>(
                 &'a mut self,
                 client: &'a C,
@@ -624,8 +766,9 @@ FROM
                     client,
                     params: [],
                     stmt: &mut self.0,
-                    extractor: |row| row.get(0),
+                    extractor: |row| row.try_get(0),
                     mapper: |it| it.into(),
+                    error: From::from,
                 }
             }
         }
@@ -641,6 +784,8 @@ WHERE
         }
         pub struct AuthorNameByIdStmt(cornucopia_async::private::Stmt);
         impl AuthorNameByIdStmt {
+            #[doc = " Latency budget in milliseconds declared by the `budget` option of the query"]
+            pub const BUDGET_MS: Option<u64> = None;
             pub fn bind<'a, C: GenericClient>(
                 &'a mut self,
                 client: &'a C,
@@ -650,8 +795,9 @@ WHERE
                     client,
                     params: [id],
                     stmt: &mut self.0,
-                    extractor: |row| row.get(0),
+                    extractor: |row| row.try_get(0),
                     mapper: |it| it.into(),
+                    error: From::from,
                 }
             }
         }
@@ -672,6 +818,8 @@ WHERE
         }
         pub struct AuthorNameStartingWithStmt(cornucopia_async::private::Stmt);
         impl AuthorNameStartingWithStmt {
+            #[doc = " Latency budget in milliseconds declared by the `budget` option of the query"]
+            pub const BUDGET_MS: Option<u64> = None;
             pub fn bind<'a, C: GenericClient, T1: cornucopia_async::StringSql>(
                 &'a mut self,
                 client: &'a C,
@@ -681,13 +829,16 @@ WHERE
                     client,
                     params: [start_str],
                     stmt: &mut self.0,
-                    extractor: |row| AuthorNameStartingWithBorrowed {
-                        authorid: row.get(0),
-                        name: row.get(1),
-                        bookid: row.get(2),
-                        title: row.get(3),
+                    extractor: |row| {
+                        Ok(AuthorNameStartingWithBorrowed {
+                            authorid: row.try_get(0)?,
+                            name: row.try_get(1)?,
+                            bookid: row.try_get(2)?,
+                            title: row.try_get(3)?,
+                        })
                     },
                     mapper: |it| <AuthorNameStartingWith>::from(it),
+                    error: From::from,
                 }
             }
         }
@@ -719,6 +870,8 @@ WHERE
         }
         pub struct SelectVoiceActorWithCharacterStmt(cornucopia_async::private::Stmt);
         impl SelectVoiceActorWithCharacterStmt {
+            #[doc = " Latency budget in milliseconds declared by the `budget` option of the query"]
+            pub const BUDGET_MS: Option<u64> = None;
             pub fn bind<'a, C: GenericClient>(
                 &'a mut self,
                 client: &'a C,
@@ -729,8 +882,9 @@ WHERE
                     client,
                     params: [spongebob_character],
                     stmt: &mut self.0,
-                    extractor: |row| row.get(0),
+                    extractor: |row| row.try_get(0),
                     mapper: |it| it.into(),
+                    error: From::from,
                 }
             }
         }
@@ -745,6 +899,8 @@ FROM
         }
         pub struct SelectTranslationsStmt(cornucopia_async::private::Stmt);
         impl SelectTranslationsStmt {
+            #[doc = " Latency budget in milliseconds declared by the `budget` option of the query"]
+            pub const BUDGET_MS: Option<u64> = None;
             pub fn bind<'a, C: GenericClient>(
                 &'a mut self,
                 client: &'a C,
@@ -753,11 +909,14 @@ FROM
                     client,
                     params: [],
                     stmt: &mut self.0,
-                    extractor: |row| SelectTranslationsBorrowed {
-                        title: row.get(0),
-                        translations: row.get(1),
+                    extractor: |row| {
+                        Ok(SelectTranslationsBorrowed {
+                            title: row.try_get(0)?,
+                            translations: row.try_get(1)?,
+                        })
                     },
                     mapper: |it| <SelectTranslations>::from(it),
+                    error: From::from,
                 }
             }
         }
@@ -0,0 +1,948 @@
+// This file was generated with `cornucopia`. Do not modify.
+
+#[allow(clippy::all, clippy::pedantic)]
+#[allow(unused_variables)]
+#[allow(unused_imports)]
+#[allow(dead_code)]
+pub mod types {
+    pub mod public {
+        #[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, PartialOrd, Ord)]
+        #[allow(non_camel_case_types)]
+        pub enum SpongeBobCharacter {
+            Bob,
+            Patrick,
+            Squidward,
+        }
+        impl<'a> postgres_types::ToSql for SpongeBobCharacter {
+            fn to_sql(
+                &self,
+                ty: &postgres_types::Type,
+                buf: &mut postgres_types::private::BytesMut,
+            ) -> Result<postgres_types::IsNull, Box<dyn std::error::Error + Sync + Send>>
+            {
+                let s = match *self {
+                    SpongeBobCharacter::Bob => "Bob",
+                    SpongeBobCharacter::Patrick => "Patrick",
+                    SpongeBobCharacter::Squidward => "Squidward",
+                };
+                buf.extend_from_slice(s.as_bytes());
+                std::result::Result::Ok(postgres_types::IsNull::No)
+            }
+            fn accepts(ty: &postgres_types::Type) -> bool {
+                if ty.name() != "sponge_bob_character" {
+                    return false;
+                }
+                match *ty.kind() {
+                    postgres_types::Kind::Enum(ref variants) => {
+                        if variants.len() != 3 {
+                            return false;
+                        }
+                        variants.iter().all(|v| match &**v {
+                            "Bob" => true,
+                            "Patrick" => true,
+                            "Squidward" => true,
+                            _ => false,
+                        })
+                    }
+                    _ => false,
+                }
+            }
+            fn to_sql_checked(
+                &self,
+                ty: &postgres_types::Type,
+                out: &mut postgres_types::private::BytesMut,
+            ) -> Result<postgres_types::IsNull, Box<dyn std::error::Error + Sync + Send>>
+            {
+                postgres_types::__to_sql_checked(self, ty, out)
+            }
+        }
+        impl<'a> postgres_types::FromSql<'a> for SpongeBobCharacter {
+            fn from_sql(
+                ty: &postgres_types::Type,
+                buf: &'a [u8],
+            ) -> Result<SpongeBobCharacter, Box<dyn std::error::Error + Sync + Send>> {
+                match std::str::from_utf8(buf)? {
+                    "Bob" => Ok(SpongeBobCharacter::Bob),
+                    "Patrick" => Ok(SpongeBobCharacter::Patrick),
+                    "Squidward" => Ok(SpongeBobCharacter::Squidward),
+                    s => Result::Err(Into::into(format!(
+                        concat!(
+                            "unknown label `{}` of the enum `public.sponge_bob_character`, ",
+                            "the code must be regenerated to decode it",
+                        ),
+                        s
+                    ))),
+                }
+            }
+            fn accepts(ty: &postgres_types::Type) -> bool {
+                if ty.name() != "sponge_bob_character" {
+                    return false;
+                }
+                match *ty.kind() {
+                    postgres_types::Kind::Enum(ref variants) => {
+                        if variants.len() != 3 {
+                            return false;
+                        }
+                        variants.iter().all(|v| match &**v {
+                            "Bob" => true,
+                            "Patrick" => true,
+                            "Squidward" => true,
+                            _ => false,
+                        })
+                    }
+                    _ => false,
+                }
+            }
+        }
+        #[derive(Debug, postgres_types::FromSql, Clone, PartialEq)]
+        #[postgres(name = "voiceactor")]
+        pub struct Voiceactor {
+            #[postgres(name = "name")]
+            pub name: String,
+            #[postgres(name = "age")]
+            pub age: i32,
+        }
+        #[derive(Debug)]
+        pub struct VoiceactorBorrowed<'a> {
+            pub name: &'a str,
+            pub age: i32,
+        }
+        impl<'a> From<VoiceactorBorrowed<'a>> for Voiceactor {
+            fn from(VoiceactorBorrowed { name, age }: VoiceactorBorrowed<'a>) -> Self {
+                Self {
+                    name: name.into(),
+                    age,
+                }
+            }
+        }
+        impl<'a> postgres_types::FromSql<'a> for VoiceactorBorrowed<'a> {
+            fn from_sql(
+                ty: &postgres_types::Type,
+                out: &'a [u8],
+            ) -> Result<VoiceactorBorrowed<'a>, Box<dyn std::error::Error + Sync + Send>>
+            {
+                let fields = match *ty.kind() {
+                    postgres_types::Kind::Composite(ref fields) => fields,
+                    _ => unreachable!(),
+                };
+                let mut out = out;
+                let num_fields = postgres_types::private::read_be_i32(&mut out)?;
+                if num_fields as usize != fields.len() {
+                    return std::result::Result::Err(std::convert::Into::into(format!(
+                        "invalid field count: {} vs {}",
+                        num_fields,
+                        fields.len()
+                    )));
+                }
+                let _oid = postgres_types::private::read_be_i32(&mut out)?;
+                let name = postgres_types::private::read_value(fields[0].type_(), &mut out)?;
+                let _oid = postgres_types::private::read_be_i32(&mut out)?;
+                let age = postgres_types::private::read_value(fields[1].type_(), &mut out)?;
+                Ok(VoiceactorBorrowed { name, age })
+            }
+            fn accepts(ty: &postgres_types::Type) -> bool {
+                ty.name() == "voiceactor" && ty.schema() == "public"
+            }
+        }
+        impl<'a> postgres_types::ToSql for VoiceactorBorrowed<'a> {
+            fn to_sql(
+                &self,
+                ty: &postgres_types::Type,
+                out: &mut postgres_types::private::BytesMut,
+            ) -> Result<postgres_types::IsNull, Box<dyn std::error::Error + Sync + Send>>
+            {
+                let VoiceactorBorrowed { name, age } = self;
+                let fields = match *ty.kind() {
+                    postgres_types::Kind::Composite(ref fields) => fields,
+                    _ => unreachable!(),
+                };
+                out.extend_from_slice(&(fields.len() as i32).to_be_bytes());
+                for field in fields {
+                    out.extend_from_slice(&field.type_().oid().to_be_bytes());
+                    let base = out.len();
+                    out.extend_from_slice(&[0; 4]);
+                    let r = match field.name() {
+                        "name" => postgres_types::ToSql::to_sql(name, field.type_(), out),
+                        "age" => postgres_types::ToSql::to_sql(age, field.type_(), out),
+                        _ => unreachable!(),
+                    };
+                    let count = match r? {
+                        postgres_types::IsNull::Yes => -1,
+                        postgres_types::IsNull::No => {
+                            let len = out.len() - base - 4;
+                            if len > i32::max_value() as usize {
+                                return Err(Into::into("value too large to transmit"));
+                            }
+                            len as i32
+                        }
+                    };
+                    out[base..base + 4].copy_from_slice(&count.to_be_bytes());
+                }
+                Ok(postgres_types::IsNull::No)
+            }
+            fn accepts(ty: &postgres_types::Type) -> bool {
+                if ty.name() != "voiceactor" {
+                    return false;
+                }
+                match *ty.kind() {
+                    postgres_types::Kind::Composite(ref fields) => {
+                        if fields.len() != 2 {
+                            return false;
+                        }
+                        fields.iter().all(|f| match f.name() {
+                            "name" => <&'a str as postgres_types::ToSql>::accepts(f.type_()),
+                            "age" => <i32 as postgres_types::ToSql>::accepts(f.type_()),
+                            _ => false,
+                        })
+                    }
+                    _ => false,
+                }
+            }
+            fn to_sql_checked(
+                &self,
+                ty: &postgres_types::Type,
+                out: &mut postgres_types::private::BytesMut,
+            ) -> Result<postgres_types::IsNull, Box<dyn std::error::Error + Sync + Send>>
+            {
+                postgres_types::__to_sql_checked(self, ty, out)
+            }
+        }
+    }
+}
+#[allow(clippy::all, clippy::pedantic)]
+#[allow(unused_variables)]
+#[allow(unused_imports)]
+#[allow(dead_code)]
+pub mod queries {
+    pub mod module_1 {
+        use cornucopia_async::GenericClient;
+        use futures;
+        use futures::{StreamExt, TryStreamExt};
+        #[doc = " Executed without a named prepared statement, so that it can be used behind connection"]
+        #[doc = " poolers in transaction mode (e.g. pgbouncer). The query text is sent on each execution"]
+        #[doc = " and planned again by the server, which is slower than a cached prepared statement."]
+        pub fn insert_book() -> InsertBookStmt {
+            InsertBookStmt(cornucopia_async::private::Stmt::unprepared(
+                "INSERT INTO Book (title)
+  VALUES ($1)",
+                &[1043],
+            ))
+        }
+        pub struct InsertBookStmt(cornucopia_async::private::Stmt);
+        impl InsertBookStmt {
+            #[doc = " Latency budget in milliseconds declared by the `budget` option of the query"]
+            pub const BUDGET_MS: Option<u64> = None;
+            pub async fn bind<'a, C: GenericClient, T1: cornucopia_async::StringSql>(
+                &'a mut self,
+                client: &'a C,
+                title: &'a T1,
+            ) -> Result<u64, tokio_postgres::Error> {
+                self.0.execute(client, &[title]).await
+            }
+        }
+    }
+    pub mod module_2 {
+        #[derive(Debug)]
+        pub struct AuthorNameStartingWithParams<T1: cornucopia_async::StringSql> {
+            pub start_str: T1,
+        }
+        #[derive(Debug, Clone, PartialEq, Eq, Hash, PartialOrd, Ord)]
+        pub struct Authors {
+            pub id: i32,
+            pub name: String,
+            pub country: String,
+        }
+        pub struct AuthorsBorrowed<'a> {
+            pub id: i32,
+            pub name: &'a str,
+            pub country: &'a str,
+        }
+        impl<'a> From<AuthorsBorrowed<'a>> for Authors {
+            fn from(AuthorsBorrowed { id, name, country }: AuthorsBorrowed<'a>) -> Self {
+                Self {
+                    id,
+                    name: name.into(),
+                    country: country.into(),
+                }
+            }
+        }
+        #[derive(Debug, Clone, PartialEq, Eq, Hash, PartialOrd, Ord)]
+        pub struct AuthorNameStartingWith {
+            pub authorid: i32,
+            pub name: String,
+            pub bookid: i32,
+            pub title: String,
+        }
+        pub struct AuthorNameStartingWithBorrowed<'a> {
+            pub authorid: i32,
+            pub name: &'a str,
+            pub bookid: i32,
+            pub title: &'a str,
+        }
+        impl<'a> From<AuthorNameStartingWithBorrowed<'a>> for AuthorNameStartingWith {
+            fn from(
+                AuthorNameStartingWithBorrowed {
+                    authorid,
+                    name,
+                    bookid,
+                    title,
+                }: AuthorNameStartingWithBorrowed<'a>,
+            ) -> Self {
+                Self {
+                    authorid,
+                    name: name.into(),
+                    bookid,
+                    title: title.into(),
+                }
+            }
+        }
+        #[derive(Debug, Clone, PartialEq, Eq, Hash, PartialOrd, Ord)]
+        pub struct SelectTranslations {
+            pub title: String,
+            pub translations: Vec<String>,
+        }
+        pub struct SelectTranslationsBorrowed<'a> {
+            pub title: &'a str,
+            pub translations: cornucopia_async::ArrayIterator<'a, &'a str>,
+        }
+        impl<'a> From<SelectTranslationsBorrowed<'a>> for SelectTranslations {
+            fn from(
+                SelectTranslationsBorrowed {
+                    title,
+                    translations,
+                }: SelectTranslationsBorrowed<'a>,
+            ) -> Self {
+                Self {
+                    title: title.into(),
+                    translations: translations.map(|v| v.into()).collect(),
+                }
+            }
+        }
+        use cornucopia_async::GenericClient;
+        use futures;
+        use futures::{StreamExt, TryStreamExt};
+        pub struct AuthorsQuery<'a, C: GenericClient, T, const N: usize, E = tokio_postgres::Error> {
+            client: &'a C,
+            params: [&'a (dyn postgres_types::ToSql + Sync); N],
+            stmt: &'a mut cornucopia_async::private::Stmt,
+            extractor: fn(&tokio_postgres::Row) -> Result<AuthorsBorrowed, tokio_postgres::Error>,
+            mapper: fn(AuthorsBorrowed) -> T,
+            error: fn(tokio_postgres::Error) -> E,
+        }
+        impl<'a, C, T: 'a, const N: usize, E: 'a> AuthorsQuery<'a, C, T, N, E>
+        where
+            C: GenericClient,
+        {
+            pub fn map<R>(self, mapper: fn(AuthorsBorrowed) -> R) -> AuthorsQuery<'a, C, R, N, E> {
+                AuthorsQuery {
+                    client: self.client,
+                    params: self.params,
+                    stmt: self.stmt,
+                    extractor: self.extractor,
+                    mapper,
+                    error: self.error,
+                }
+            }
+            pub async fn one(self) -> Result<T, E> {
+                let row = self
+                    .stmt
+                    .query_one(self.client, &self.params)
+                    .await
+                    .map_err(self.error)?;
+                Ok((self.mapper)((self.extractor)(&row).map_err(self.error)?))
+            }
+            pub async fn opt(self) -> Result<Option<T>, E> {
+                let row = self
+                    .stmt
+                    .query_opt(self.client, &self.params)
+                    .await
+                    .map_err(self.error)?;
+                row.map(|row| Ok((self.mapper)((self.extractor)(&row).map_err(self.error)?)))
+                    .transpose()
+            }
+            pub async fn all(self) -> Result<Vec<T>, E> {
+                self.iter().await?.try_collect().await
+            }
+            pub async fn iter(self) -> Result<impl futures::Stream<Item = Result<T, E>> + 'a, E> {
+                let it = self
+                    .stmt
+                    .query_raw(self.client, &self.params)
+                    .await
+                    .map_err(self.error)?
+                    .map(move |res| {
+                        res.and_then(|row| (self.extractor)(&row).map(self.mapper))
+                            .map_err(self.error)
+                    })
+                    .into_stream();
+                Ok(it)
+            }
+            #[doc = " Declares a server-side cursor over the rows, fetching them in chunks instead of"]
+            #[doc = " buffering them all. Cursors only live inside a transaction."]
+            pub async fn cursor(self) -> Result<cornucopia_async::Cursor<'a, C, T, E>, E>
+            where
+                C: cornucopia_async::InTransaction,
+            {
+                let (extractor, mapper) = (self.extractor, self.mapper);
+                self.stmt
+                    .cursor(
+                        self.client,
+                        &self.params,
+                        move |row| extractor(row).map(mapper),
+                        self.error,
+                    )
+                    .await
+            }
+        }
+        pub struct StringQuery<'a, C: GenericClient, T, const N: usize, E = tokio_postgres::Error> {
+            client: &'a C,
+            params: [&'a (dyn postgres_types::ToSql + Sync); N],
+            stmt: &'a mut cornucopia_async::private::Stmt,
+            extractor: fn(&tokio_postgres::Row) -> Result<&str, tokio_postgres::Error>,
+            mapper: fn(&str) -> T,
+            error: fn(tokio_postgres::Error) -> E,
+        }
+        impl<'a, C, T: 'a, const N: usize, E: 'a> StringQuery<'a, C, T, N, E>
+        where
+            C: GenericClient,
+        {
+            pub fn map<R>(self, mapper: fn(&str) -> R) -> StringQuery<'a, C, R, N, E> {
+                StringQuery {
+                    client: self.client,
+                    params: self.params,
+                    stmt: self.stmt,
+                    extractor: self.extractor,
+                    mapper,
+                    error: self.error,
+                }
+            }
+            pub async fn one(self) -> Result<T, E> {
+                let row = self
+                    .stmt
+                    .query_one(self.client, &self.params)
+                    .await
+                    .map_err(self.error)?;
+                Ok((self.mapper)((self.extractor)(&row).map_err(self.error)?))
+            }
+            pub async fn opt(self) -> Result<Option<T>, E> {
+                let row = self
+                    .stmt
+                    .query_opt(self.client, &self.params)
+                    .await
+                    .map_err(self.error)?;
+                row.map(|row| Ok((self.mapper)((self.extractor)(&row).map_err(self.error)?)))
+                    .transpose()
+            }
+            pub async fn all(self) -> Result<Vec<T>, E> {
+                self.iter().await?.try_collect().await
+            }
+            pub async fn iter(self) -> Result<impl futures::Stream<Item = Result<T, E>> + 'a, E> {
+                let it = self
+                    .stmt
+                    .query_raw(self.client, &self.params)
+                    .await
+                    .map_err(self.error)?
+                    .map(move |res| {
+                        res.and_then(|row| (self.extractor)(&row).map(self.mapper))
+                            .map_err(self.error)
+                    })
+                    .into_stream();
+                Ok(it)
+            }
+            #[doc = " Declares a server-side cursor over the rows, fetching them in chunks instead of"]
+            #[doc = " buffering them all. Cursors only live inside a transaction."]
+            pub async fn cursor(self) -> Result<cornucopia_async::Cursor<'a, C, T, E>, E>
+            where
+                C: cornucopia_async::InTransaction,
+            {
+                let (extractor, mapper) = (self.extractor, self.mapper);
+                self.stmt
+                    .cursor(
+                        self.client,
+                        &self.params,
+                        move |row| extractor(row).map(mapper),
+                        self.error,
+                    )
+                    .await
+            }
+        }
+        pub struct AuthorNameStartingWithQuery<
+            'a,
+            C: GenericClient,
+            T,
+            const N: usize,
+            E = tokio_postgres::Error,
+        > {
+            client: &'a C,
+            params: [&'a (dyn postgres_types::ToSql + Sync); N],
+            stmt: &'a mut cornucopia_async::private::Stmt,
+            extractor: fn(
+                &tokio_postgres::Row,
+            )
+                -> Result<AuthorNameStartingWithBorrowed, tokio_postgres::Error>,
+            mapper: fn(AuthorNameStartingWithBorrowed) -> T,
+            error: fn(tokio_postgres::Error) -> E,
+        }
+        impl<'a, C, T: 'a, const N: usize, E: 'a> AuthorNameStartingWithQuery<'a, C, T, N, E>
+        where
+            C: GenericClient,
+        {
+            pub fn map<R>(
+                self,
+                mapper: fn(AuthorNameStartingWithBorrowed) -> R,
+            ) -> AuthorNameStartingWithQuery<'a, C, R, N, E> {
+                AuthorNameStartingWithQuery {
+                    client: self.client,
+                    params: self.params,
+                    stmt: self.stmt,
+                    extractor: self.extractor,
+                    mapper,
+                    error: self.error,
+                }
+            }
+            pub async fn one(self) -> Result<T, E> {
+                let row = self
+                    .stmt
+                    .query_one(self.client, &self.params)
+                    .await
+                    .map_err(self.error)?;
+                Ok((self.mapper)((self.extractor)(&row).map_err(self.error)?))
+            }
+            pub async fn opt(self) -> Result<Option<T>, E> {
+                let row = self
+                    .stmt
+                    .query_opt(self.client, &self.params)
+                    .await
+                    .map_err(self.error)?;
+                row.map(|row| Ok((self.mapper)((self.extractor)(&row).map_err(self.error)?)))
+                    .transpose()
+            }
+            pub async fn all(self) -> Result<Vec<T>, E> {
+                self.iter().await?.try_collect().await
+            }
+            pub async fn iter(self) -> Result<impl futures::Stream<Item = Result<T, E>> + 'a, E> {
+                let it = self
+                    .stmt
+                    .query_raw(self.client, &self.params)
+                    .await
+                    .map_err(self.error)?
+                    .map(move |res| {
+                        res.and_then(|row| (self.extractor)(&row).map(self.mapper))
+                            .map_err(self.error)
+                    })
+                    .into_stream();
+                Ok(it)
+            }
+            #[doc = " Declares a server-side cursor over the rows, fetching them in chunks instead of"]
+            #[doc = " buffering them all. Cursors only live inside a transaction."]
+            pub async fn cursor(self) -> Result<cornucopia_async::Cursor<'a, C, T, E>, E>
+            where
+                C: cornucopia_async::InTransaction,
+            {
+                let (extractor, mapper) = (self.extractor, self.mapper);
+                self.stmt
+                    .cursor(
+                        self.client,
+                        &self.params,
+                        move |row| extractor(row).map(mapper),
+                        self.error,
+                    )
+                    .await
+            }
+        }
+        pub struct PublicVoiceactorQuery<
+            'a,
+            C: GenericClient,
+            T,
+            const N: usize,
+            E = tokio_postgres::Error,
+        > {
+            client: &'a C,
+            params: [&'a (dyn postgres_types::ToSql + Sync); N],
+            stmt: &'a mut cornucopia_async::private::Stmt,
+            extractor: fn(
+                &tokio_postgres::Row,
+            ) -> Result<
+                super::super::types::public::VoiceactorBorrowed,
+                tokio_postgres::Error,
+            >,
+            mapper: fn(super::super::types::public::VoiceactorBorrowed) -> T,
+            error: fn(tokio_postgres::Error) -> E,
+        }
+        impl<'a, C, T: 'a, const N: usize, E: 'a> PublicVoiceactorQuery<'a, C, T, N, E>
+        where
+            C: GenericClient,
+        {
+            pub fn map<R>(
+                self,
+                mapper: fn(super::super::types::public::VoiceactorBorrowed) -> R,
+            ) -> PublicVoiceactorQuery<'a, C, R, N, E> {
+                PublicVoiceactorQuery {
+                    client: self.client,
+                    params: self.params,
+                    stmt: self.stmt,
+                    extractor: self.extractor,
+                    mapper,
+                    error: self.error,
+                }
+            }
+            pub async fn one(self) -> Result<T, E> {
+                let row = self
+                    .stmt
+                    .query_one(self.client, &self.params)
+                    .await
+                    .map_err(self.error)?;
+                Ok((self.mapper)((self.extractor)(&row).map_err(self.error)?))
+            }
+            pub async fn opt(self) -> Result<Option<T>, E> {
+                let row = self
+                    .stmt
+                    .query_opt(self.client, &self.params)
+                    .await
+                    .map_err(self.error)?;
+                row.map(|row| Ok((self.mapper)((self.extractor)(&row).map_err(self.error)?)))
+                    .transpose()
+            }
+            pub async fn all(self) -> Result<Vec<T>, E> {
+                self.iter().await?.try_collect().await
+            }
+            pub async fn iter(self) -> Result<impl futures::Stream<Item = Result<T, E>> + 'a, E> {
+                let it = self
+                    .stmt
+                    .query_raw(self.client, &self.params)
+                    .await
+                    .map_err(self.error)?
+                    .map(move |res| {
+                        res.and_then(|row| (self.extractor)(&row).map(self.mapper))
+                            .map_err(self.error)
+                    })
+                    .into_stream();
+                Ok(it)
+            }
+            #[doc = " Declares a server-side cursor over the rows, fetching them in chunks instead of"]
+            #[doc = " buffering them all. Cursors only live inside a transaction."]
+            pub async fn cursor(self) -> Result<cornucopia_async::Cursor<'a, C, T, E>, E>
+            where
+                C: cornucopia_async::InTransaction,
+            {
+                let (extractor, mapper) = (self.extractor, self.mapper);
+                self.stmt
+                    .cursor(
+                        self.client,
+                        &self.params,
+                        move |row| extractor(row).map(mapper),
+                        self.error,
+                    )
+                    .await
+            }
+        }
+        pub struct SelectTranslationsQuery<
+            'a,
+            C: GenericClient,
+            T,
+            const N: usize,
+            E = tokio_postgres::Error,
+        > {
+            client: &'a C,
+            params: [&'a (dyn postgres_types::ToSql + Sync); N],
+            stmt: &'a mut cornucopia_async::private::Stmt,
+            extractor: fn(
+                &tokio_postgres::Row,
+            ) -> Result<SelectTranslationsBorrowed, tokio_postgres::Error>,
+            mapper: fn(SelectTranslationsBorrowed) -> T,
+            error: fn(tokio_postgres::Error) -> E,
+        }
+        impl<'a, C, T: 'a, const N: usize, E: 'a> SelectTranslationsQuery<'a, C, T, N, E>
+        where
+            C: GenericClient,
+        {
+            pub fn map<R>(
+                self,
+                mapper: fn(SelectTranslationsBorrowed) -> R,
+            ) -> SelectTranslationsQuery<'a, C, R, N, E> {
+                SelectTranslationsQuery {
+                    client: self.client,
+                    params: self.params,
+                    stmt: self.stmt,
+                    extractor: self.extractor,
+                    mapper,
+                    error: self.error,
+                }
+            }
+            pub async fn one(self) -> Result<T, E> {
+                let row = self
+                    .stmt
+                    .query_one(self.client, &self.params)
+                    .await
+                    .map_err(self.error)?;
+                Ok((self.mapper)((self.extractor)(&row).map_err(self.error)?))
+            }
+            pub async fn opt(self) -> Result<Option<T>, E> {
+                let row = self
+                    .stmt
+                    .query_opt(self.client, &self.params)
+                    .await
+                    .map_err(self.error)?;
+                row.map(|row| Ok((self.mapper)((self.extractor)(&row).map_err(self.error)?)))
+                    .transpose()
+            }
+            pub async fn all(self) -> Result<Vec<T>, E> {
+                self.iter().await?.try_collect().await
+            }
+            pub async fn iter(self) -> Result<impl futures::Stream<Item = Result<T, E>> + 'a, E> {
+                let it = self
+                    .stmt
+                    .query_raw(self.client, &self.params)
+                    .await
+                    .map_err(self.error)?
+                    .map(move |res| {
+                        res.and_then(|row| (self.extractor)(&row).map(self.mapper))
+                            .map_err(self.error)
+                    })
+                    .into_stream();
+                Ok(it)
+            }
+            #[doc = " Declares a server-side cursor over the rows, fetching them in chunks instead of"]
+            #[doc = " buffering them all. Cursors only live inside a transaction."]
+            pub async fn cursor(self) -> Result<cornucopia_async::Cursor<'a, C, T, E>, E>
+            where
+                C: cornucopia_async::InTransaction,
+            {
+                let (extractor, mapper) = (self.extractor, self.mapper);
+                self.stmt
+                    .cursor(
+                        self.client,
+                        &self.params,
+                        move |row| extractor(row).map(mapper),
+                        self.error,
+                    )
+                    .await
+            }
+        }
+        #[doc = " Executed without a named prepared statement, so that it can be used behind connection"]
+        #[doc = " poolers in transaction mode (e.g. pgbouncer). The query text is sent on each execution"]
+        #[doc = " and planned again by the server, which is slower than a cached prepared statement."]
+        pub fn authors() -> AuthorsStmt {
+            AuthorsStmt(cornucopia_async::private::Stmt::unprepared(
+                "SELECT
+    *
+FROM
+    Author",
+                &[],
+            ))
+        }
+        pub struct AuthorsStmt(cornucopia_async::private::Stmt);
+        impl AuthorsStmt {
+            #[doc = " Latency budget in milliseconds declared by the `budget` option of the query"]
+            pub const BUDGET_MS: Option<u64> = None;
+            pub fn bind<'a, C: GenericClient>(
+                &'a mut self,
+                client: &'a C,
+            ) -> AuthorsQuery<'a, C, Authors, 0> {
+                AuthorsQuery {
+                    client,
+                    params: [],
+                    stmt: &mut self.0,
+                    extractor: |row| {
+                        Ok(AuthorsBorrowed {
+                            id: row.try_get(0)?,
+                            name: row.try_get(1)?,
+                            country: row.try_get(2)?,
+                        })
+                    },
+                    mapper: |it| <Authors>::from(it),
+                    error: From::from,
+                }
+            }
+        }
+        #[doc = " Executed without a named prepared statement, so that it can be used behind connection"]
+        #[doc = " poolers in transaction mode (e.g. pgbouncer). The query text is sent on each execution"]
+        #[doc = " and planned again by the server, which is slower than a cached prepared statement."]
+        pub fn books() -> BooksStmt {
+            BooksStmt(cornucopia_async::private::Stmt::unprepared(
+                "SELECT
+    Title
+FROM
+    Book",
+                &[],
+            ))
+        }
+        pub struct BooksStmt(cornucopia_async::private::Stmt);
+        impl BooksStmt {
+            #[doc = " Latency budget in milliseconds declared by the `budget` option of the query"]
+            pub const BUDGET_MS: Option<u64> = None;
+            pub fn bind<'a, C: GenericClient>(
+                &'a mut self,
+                client: &'a C,
+            ) -> StringQuery<'a, C, String, 0> {
+                StringQuery {
+                    client,
+                    params: [],
+                    stmt: &mut self.0,
+                    extractor: |row| row.try_get(0),
+                    mapper: |it| it.into(),
+                    error: From::from,
+                }
+            }
+        }
+        #[doc = " Executed without a named prepared statement, so that it can be used behind connection"]
+        #[doc = " poolers in transaction mode (e.g. pgbouncer). The query text is sent on each execution"]
+        #[doc = " and planned again by the server, which is slower than a cached prepared statement."]
+        pub fn author_name_by_id() -> AuthorNameByIdStmt {
+            AuthorNameByIdStmt(cornucopia_async::private::Stmt::unprepared(
+                "SELECT
+    Author.Name
+FROM
+    Author
+WHERE
+    Author.Id = $1",
+                &[23],
+            ))
+        }
+        pub struct AuthorNameByIdStmt(cornucopia_async::private::Stmt);
+        impl AuthorNameByIdStmt {
+            #[doc = " Latency budget in milliseconds declared by the `budget` option of the query"]
+            pub const BUDGET_MS: Option<u64> = None;
+            pub fn bind<'a, C: GenericClient>(
+                &'a mut self,
+                client: &'a C,
+                id: &'a i32,
+            ) -> StringQuery<'a, C, String, 1> {
+                StringQuery {
+                    client,
+                    params: [id],
+                    stmt: &mut self.0,
+                    extractor: |row| row.try_get(0),
+                    mapper: |it| it.into(),
+                    error: From::from,
+                }
+            }
+        }
+        #[doc = " Executed without a named prepared statement, so that it can be used behind connection"]
+        #[doc = " poolers in transaction mode (e.g. pgbouncer). The query text is sent on each execution"]
+        #[doc = " and planned again by the server, which is slower than a cached prepared statement."]
+        pub fn author_name_starting_with() -> AuthorNameStartingWithStmt {
+            AuthorNameStartingWithStmt(cornucopia_async::private::Stmt::unprepared(
+                "SELECT
+    BookAuthor.AuthorId,
+    Author.Name,
+    BookAuthor.BookId,
+    Book.Title
+FROM
+    BookAuthor
+    INNER JOIN Author ON Author.id = BookAuthor.AuthorId
+    INNER JOIN Book ON Book.Id = BookAuthor.BookId
+WHERE
+    Author.Name LIKE CONCAT($1::text, '%')",
+                &[25],
+            ))
+        }
+        pub struct AuthorNameStartingWithStmt(cornucopia_async::private::Stmt);
+        impl AuthorNameStartingWithStmt {
+            #[doc = " Latency budget in milliseconds declared by the `budget` option of the query"]
+            pub const BUDGET_MS: Option<u64> = None;
+            pub fn bind<'a, C: GenericClient, T1: cornucopia_async::StringSql>(
+                &'a mut self,
+                client: &'a C,
+                start_str: &'a T1,
+            ) -> AuthorNameStartingWithQuery<'a, C, AuthorNameStartingWith, 1> {
+                AuthorNameStartingWithQuery {
+                    client,
+                    params: [start_str],
+                    stmt: &mut self.0,
+                    extractor: |row| {
+                        Ok(AuthorNameStartingWithBorrowed {
+                            authorid: row.try_get(0)?,
+                            name: row.try_get(1)?,
+                            bookid: row.try_get(2)?,
+                            title: row.try_get(3)?,
+                        })
+                    },
+                    mapper: |it| <AuthorNameStartingWith>::from(it),
+                    error: From::from,
+                }
+            }
+        }
+        impl<'a, C: GenericClient, T1: cornucopia_async::StringSql>
+            cornucopia_async::Params<
+                'a,
+                AuthorNameStartingWithParams<T1>,
+                AuthorNameStartingWithQuery<'a, C, AuthorNameStartingWith, 1>,
+                C,
+            > for AuthorNameStartingWithStmt
+        {
+            fn params(
+                &'a mut self,
+                client: &'a C,
+                params: &'a AuthorNameStartingWithParams<T1>,
+            ) -> AuthorNameStartingWithQuery<'a, C, AuthorNameStartingWith, 1> {
+                self.bind(client, &params.start_str)
+            }
+        }
+        pub fn select_voice_actor_with_character() -> SelectVoiceActorWithCharacterStmt {
+            SelectVoiceActorWithCharacterStmt(cornucopia_async::private::Stmt::new(
+                "SELECT
+    voice_actor
+FROM
+    SpongeBobVoiceActor
+WHERE
+    character = $1",
+            ))
+        }
+        pub struct SelectVoiceActorWithCharacterStmt(cornucopia_async::private::Stmt);
+        impl SelectVoiceActorWithCharacterStmt {
+            #[doc = " Latency budget in milliseconds declared by the `budget` option of the query"]
+            pub const BUDGET_MS: Option<u64> = None;
+            pub fn bind<'a, C: GenericClient>(
+                &'a mut self,
+                client: &'a C,
+                spongebob_character: &'a super::super::types::public::SpongeBobCharacter,
+            ) -> PublicVoiceactorQuery<'a, C, super::super::types::public::Voiceactor, 1>
+            {
+                PublicVoiceactorQuery {
+                    client,
+                    params: [spongebob_character],
+                    stmt: &mut self.0,
+                    extractor: |row| row.try_get(0),
+                    mapper: |it| it.into(),
+                    error: From::from,
+                }
+            }
+        }
+        #[doc = " Executed without a named prepared statement, so that it can be used behind connection"]
+        #[doc = " poolers in transaction mode (e.g. pgbouncer). The query text is sent on each execution"]
+        #[doc = " and planned again by the server, which is slower than a cached prepared statement."]
+        pub fn select_translations() -> SelectTranslationsStmt {
+            SelectTranslationsStmt(cornucopia_async::private::Stmt::unprepared(
+                "SELECT
+    Title,
+    Translations
+FROM
+    Book",
+                &[],
+            ))
+        }
+        pub struct SelectTranslationsStmt(cornucopia_async::private::Stmt);
+        impl SelectTranslationsStmt {
+            #[doc = " Latency budget in milliseconds declared by the `budget` option of the query"]
+            pub const BUDGET_MS: Option<u64> = None;
+            pub fn bind<'a, C: GenericClient>(
+                &'a mut self,
+                client: &'a C,
+            ) -> SelectTranslationsQuery<'a, C, SelectTranslations, 0> {
+                SelectTranslationsQuery {
+                    client,
+                    params: [],
+                    stmt: &mut self.0,
+                    extractor: |row| {
+                        Ok(SelectTranslationsBorrowed {
+                            title: row.try_get(0)?,
+                            translations: row.try_get(1)?,
+                        })
+                    },
+                    mapper: |it| <SelectTranslations>::from(it),
+                    error: From::from,
+                }
+            }
+        }
+    }
+}
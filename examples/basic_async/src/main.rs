@@ -1,5 +1,6 @@
 // Take a look at the generated `cornucopia.rs` file if you want to
 // see what it looks like under the hood.
+#[cfg_attr(feature = "unprepared", path = "cornucopia_unprepared.rs")]
 mod cornucopia;
 use crate::cornucopia::{
     queries::{
@@ -0,0 +1,21 @@
+// This file was generated with `cornucopia`. Do not modify.
+
+#[path = "../src/cornucopia.rs"]
+mod cornucopia;
+use criterion::{criterion_group, criterion_main, Criterion};
+fn bench(c: &mut Criterion) {
+    let url = std::env::var("CORNUCOPIA_BENCH_URL").expect("CORNUCOPIA_BENCH_URL should be set");
+    let mut client = postgres::Client::connect(&url, postgres::NoTls).unwrap();
+    let mut transaction = client.transaction().unwrap();
+    {
+        let mut stmt = cornucopia::queries::module_1::insert_book();
+        c.bench_function("module_1::insert_book", |b| {
+            b.iter(|| {
+                stmt.bind(&mut transaction, &"The Left Hand of Darkness")
+                    .unwrap()
+            })
+        });
+    }
+}
+criterion_group!(benches, bench);
+criterion_main!(benches);
@@ -0,0 +1,30 @@
+// This file was generated with `cornucopia`. Do not modify.
+
+#[path = "../src/cornucopia.rs"]
+mod cornucopia;
+use criterion::{criterion_group, criterion_main, Criterion};
+fn bench(c: &mut Criterion) {
+    let url = std::env::var("CORNUCOPIA_BENCH_URL").expect("CORNUCOPIA_BENCH_URL should be set");
+    let mut client = postgres::Client::connect(&url, postgres::NoTls).unwrap();
+    let mut transaction = client.transaction().unwrap();
+    {
+        let mut stmt = cornucopia::queries::module_2::authors();
+        c.bench_function("module_2::authors", |b| {
+            b.iter(|| stmt.bind(&mut transaction).all().unwrap())
+        });
+    }
+    {
+        let mut stmt = cornucopia::queries::module_2::author_name_by_id();
+        c.bench_function("module_2::author_name_by_id", |b| {
+            b.iter(|| stmt.bind(&mut transaction, &1).all().unwrap())
+        });
+    }
+    {
+        let mut stmt = cornucopia::queries::module_2::author_name_starting_with();
+        c.bench_function("module_2::author_name_starting_with", |b| {
+            b.iter(|| stmt.bind(&mut transaction, &"Jo").all().unwrap())
+        });
+    }
+}
+criterion_group!(benches, bench);
+criterion_main!(benches);
@@ -6,11 +6,15 @@
 #[allow(dead_code)]
 pub mod types {
     pub mod public {
-        #[derive(Debug, Clone, Copy, PartialEq, Eq)]
+        #[derive(serde::Serialize, Debug, Clone, Copy, PartialEq, Eq, Hash, PartialOrd, Ord)]
+        #[serde(rename_all = "snake_case")]
         #[allow(non_camel_case_types)]
         pub enum SpongeBobCharacter {
+            #[serde(rename = "Bob")]
             Bob,
+            #[serde(rename = "Patrick")]
             Patrick,
+            #[serde(rename = "Squidward")]
             Squidward,
         }
         impl<'a> postgres_types::ToSql for SpongeBobCharacter {
@@ -65,7 +69,13 @@ pub mod types {
                     "Bob" => Ok(SpongeBobCharacter::Bob),
                     "Patrick" => Ok(SpongeBobCharacter::Patrick),
                     "Squidward" => Ok(SpongeBobCharacter::Squidward),
-                    s => Result::Err(Into::into(format!("invalid variant `{}`", s))),
+                    s => Result::Err(Into::into(format!(
+                        concat!(
+                            "unknown label `{}` of the enum `public.sponge_bob_character`, ",
+                            "the code must be regenerated to decode it",
+                        ),
+                        s
+                    ))),
                 }
             }
             fn accepts(ty: &postgres_types::Type) -> bool {
@@ -88,7 +98,7 @@ pub mod types {
                 }
             }
         }
-        #[derive(Debug, postgres_types :: FromSql, Clone, PartialEq)]
+        #[derive(serde::Serialize, Debug, postgres_types::FromSql, Clone, PartialEq)]
         #[postgres(name = "voiceactor")]
         pub struct Voiceactor {
             #[postgres(name = "name")]
@@ -96,7 +106,7 @@ pub mod types {
             #[postgres(name = "age")]
             pub age: i32,
         }
-        #[derive(Debug)]
+        #[derive(serde::Deserialize, Debug)]
         pub struct VoiceactorBorrowed<'a> {
             pub name: &'a str,
             pub age: i32,
@@ -218,22 +228,23 @@ pub mod queries {
         }
         pub struct InsertBookStmt(cornucopia_sync::private::Stmt);
         impl InsertBookStmt {
+            #[doc = " Latency budget in milliseconds declared by the `budget` option of the query"]
+            pub const BUDGET_MS: Option<u64> = None;
             pub fn bind<'a, C: GenericClient, T1: cornucopia_sync::StringSql>(
                 &'a mut self,
                 client: &'a mut C,
                 title: &'a T1,
             ) -> Result<u64, postgres::Error> {
-                let stmt = self.0.prepare(client)?;
-                client.execute(stmt, &[title])
+                self.0.execute(client, &[title])
             }
         }
     }
     pub mod module_2 {
-        #[derive(Debug)]
+        #[derive(serde::Deserialize, Debug)]
         pub struct AuthorNameStartingWithParams<T1: cornucopia_sync::StringSql> {
             pub start_str: T1,
         }
-        #[derive(Debug, Clone, PartialEq)]
+        #[derive(serde::Serialize, Debug, Clone, PartialEq, Eq, Hash, PartialOrd, Ord)]
         pub struct Authors {
             pub id: i32,
             pub name: String,
@@ -253,7 +264,7 @@ pub mod queries {
                 }
             }
         }
-        #[derive(Debug, Clone, PartialEq)]
+        #[derive(serde::Serialize, Debug, Clone, PartialEq, Eq, Hash, PartialOrd, Ord)]
         pub struct AuthorNameStartingWith {
             pub authorid: i32,
             pub name: String,
@@ -283,7 +294,7 @@ pub mod queries {
                 }
             }
         }
-        #[derive(Debug, Clone, PartialEq)]
+        #[derive(serde::Serialize, Debug, Clone, PartialEq, Eq, Hash, PartialOrd, Ord)]
         pub struct SelectTranslations {
             pub title: String,
             pub translations: Vec<String>,
@@ -306,254 +317,372 @@ pub mod queries {
             }
         }
         use postgres::{fallible_iterator::FallibleIterator, GenericClient};
-        pub struct AuthorsQuery<'a, C: GenericClient, T, const N: usize> {
+        pub struct AuthorsQuery<'a, C: GenericClient, T, const N: usize, E = postgres::Error> {
             client: &'a mut C,
             params: [&'a (dyn postgres_types::ToSql + Sync); N],
             stmt: &'a mut cornucopia_sync::private::Stmt,
-            extractor: fn(&postgres::Row) -> AuthorsBorrowed,
+            extractor: fn(&postgres::Row) -> Result<AuthorsBorrowed, postgres::Error>,
             mapper: fn(AuthorsBorrowed) -> T,
+            error: fn(postgres::Error) -> E,
         }
-        impl<'a, C, T: 'a, const N: usize> AuthorsQuery<'a, C, T, N>
+        impl<'a, C, T: 'a, const N: usize, E: 'a> AuthorsQuery<'a, C, T, N, E>
         where
             C: GenericClient,
         {
-            pub fn map<R>(self, mapper: fn(AuthorsBorrowed) -> R) -> AuthorsQuery<'a, C, R, N> {
+            pub fn map<R>(self, mapper: fn(AuthorsBorrowed) -> R) -> AuthorsQuery<'a, C, R, N, E> {
                 AuthorsQuery {
                     client: self.client,
                     params: self.params,
                     stmt: self.stmt,
                     extractor: self.extractor,
                     mapper,
+                    error: self.error,
                 }
             }
-            pub fn one(self) -> Result<T, postgres::Error> {
-                let stmt = self.stmt.prepare(self.client)?;
-                let row = self.client.query_one(stmt, &self.params)?;
-                Ok((self.mapper)((self.extractor)(&row)))
-            }
-            pub fn all(self) -> Result<Vec<T>, postgres::Error> {
+            pub fn one(self) -> Result<T, E> {
+                let row = self
+                    .stmt
+                    .query_one(self.client, &self.params)
+                    .map_err(self.error)?;
+                Ok((self.mapper)((self.extractor)(&row).map_err(self.error)?))
+            }
+            pub fn opt(self) -> Result<Option<T>, E> {
+                let row = self
+                    .stmt
+                    .query_opt(self.client, &self.params)
+                    .map_err(self.error)?;
+                row.map(|row| Ok((self.mapper)((self.extractor)(&row).map_err(self.error)?)))
+                    .transpose()
+            }
+            pub fn all(self) -> Result<Vec<T>, E> {
                 self.iter()?.collect()
             }
-            pub fn opt(self) -> Result<Option<T>, postgres::Error> {
-                let stmt = self.stmt.prepare(self.client)?;
-                Ok(self
-                    .client
-                    .query_opt(stmt, &self.params)?
-                    .map(|row| (self.mapper)((self.extractor)(&row))))
-            }
-            pub fn iter(
-                self,
-            ) -> Result<impl Iterator<Item = Result<T, postgres::Error>> + 'a, postgres::Error>
-            {
-                let stmt = self.stmt.prepare(self.client)?;
+            pub fn iter(self) -> Result<impl Iterator<Item = Result<T, E>> + 'a, E> {
                 let it = self
-                    .client
-                    .query_raw(stmt, cornucopia_sync::private::slice_iter(&self.params))?
+                    .stmt
+                    .query_raw(self.client, &self.params)
+                    .map_err(self.error)?
                     .iterator()
-                    .map(move |res| res.map(|row| (self.mapper)((self.extractor)(&row))));
+                    .map(move |res| {
+                        res.and_then(|row| (self.extractor)(&row).map(self.mapper))
+                            .map_err(self.error)
+                    });
                 Ok(it)
             }
+            #[doc = " Declares a server-side cursor over the rows, fetching them in chunks instead of"]
+            #[doc = " buffering them all. Cursors only live inside a transaction."]
+            pub fn cursor(self) -> Result<cornucopia_sync::Cursor<'a, C, T, E>, E>
+            where
+                C: cornucopia_sync::InTransaction,
+            {
+                let (extractor, mapper) = (self.extractor, self.mapper);
+                self.stmt.cursor(
+                    self.client,
+                    &self.params,
+                    move |row| extractor(row).map(mapper),
+                    self.error,
+                )
+            }
         }
-        pub struct StringQuery<'a, C: GenericClient, T, const N: usize> {
+        pub struct StringQuery<'a, C: GenericClient, T, const N: usize, E = postgres::Error> {
             client: &'a mut C,
             params: [&'a (dyn postgres_types::ToSql + Sync); N],
             stmt: &'a mut cornucopia_sync::private::Stmt,
-            extractor: fn(&postgres::Row) -> &str,
+            extractor: fn(&postgres::Row) -> Result<&str, postgres::Error>,
             mapper: fn(&str) -> T,
+            error: fn(postgres::Error) -> E,
         }
-        impl<'a, C, T: 'a, const N: usize> StringQuery<'a, C, T, N>
+        impl<'a, C, T: 'a, const N: usize, E: 'a> StringQuery<'a, C, T, N, E>
         where
             C: GenericClient,
         {
-            pub fn map<R>(self, mapper: fn(&str) -> R) -> StringQuery<'a, C, R, N> {
+            pub fn map<R>(self, mapper: fn(&str) -> R) -> StringQuery<'a, C, R, N, E> {
                 StringQuery {
                     client: self.client,
                     params: self.params,
                     stmt: self.stmt,
                     extractor: self.extractor,
                     mapper,
+                    error: self.error,
                 }
             }
-            pub fn one(self) -> Result<T, postgres::Error> {
-                let stmt = self.stmt.prepare(self.client)?;
-                let row = self.client.query_one(stmt, &self.params)?;
-                Ok((self.mapper)((self.extractor)(&row)))
-            }
-            pub fn all(self) -> Result<Vec<T>, postgres::Error> {
+            pub fn one(self) -> Result<T, E> {
+                let row = self
+                    .stmt
+                    .query_one(self.client, &self.params)
+                    .map_err(self.error)?;
+                Ok((self.mapper)((self.extractor)(&row).map_err(self.error)?))
+            }
+            pub fn opt(self) -> Result<Option<T>, E> {
+                let row = self
+                    .stmt
+                    .query_opt(self.client, &self.params)
+                    .map_err(self.error)?;
+                row.map(|row| Ok((self.mapper)((self.extractor)(&row).map_err(self.error)?)))
+                    .transpose()
+            }
+            pub fn all(self) -> Result<Vec<T>, E> {
                 self.iter()?.collect()
             }
-            pub fn opt(self) -> Result<Option<T>, postgres::Error> {
-                let stmt = self.stmt.prepare(self.client)?;
-                Ok(self
-                    .client
-                    .query_opt(stmt, &self.params)?
-                    .map(|row| (self.mapper)((self.extractor)(&row))))
-            }
-            pub fn iter(
-                self,
-            ) -> Result<impl Iterator<Item = Result<T, postgres::Error>> + 'a, postgres::Error>
-            {
-                let stmt = self.stmt.prepare(self.client)?;
+            pub fn iter(self) -> Result<impl Iterator<Item = Result<T, E>> + 'a, E> {
                 let it = self
-                    .client
-                    .query_raw(stmt, cornucopia_sync::private::slice_iter(&self.params))?
+                    .stmt
+                    .query_raw(self.client, &self.params)
+                    .map_err(self.error)?
                     .iterator()
-                    .map(move |res| res.map(|row| (self.mapper)((self.extractor)(&row))));
+                    .map(move |res| {
+                        res.and_then(|row| (self.extractor)(&row).map(self.mapper))
+                            .map_err(self.error)
+                    });
                 Ok(it)
             }
+            #[doc = " Declares a server-side cursor over the rows, fetching them in chunks instead of"]
+            #[doc = " buffering them all. Cursors only live inside a transaction."]
+            pub fn cursor(self) -> Result<cornucopia_sync::Cursor<'a, C, T, E>, E>
+            where
+                C: cornucopia_sync::InTransaction,
+            {
+                let (extractor, mapper) = (self.extractor, self.mapper);
+                self.stmt.cursor(
+                    self.client,
+                    &self.params,
+                    move |row| extractor(row).map(mapper),
+                    self.error,
+                )
+            }
         }
-        pub struct AuthorNameStartingWithQuery<'a, C: GenericClient, T, const N: usize> {
+        pub struct AuthorNameStartingWithQuery<
+            'a,
+            C: GenericClient,
+            T,
+            const N: usize,
+            E = postgres::Error,
+        > {
             client: &'a mut C,
             params: [&'a (dyn postgres_types::ToSql + Sync); N],
             stmt: &'a mut cornucopia_sync::private::Stmt,
-            extractor: fn(&postgres::Row) -> AuthorNameStartingWithBorrowed,
+            extractor:
+                fn(&postgres::Row) -> Result<AuthorNameStartingWithBorrowed, postgres::Error>,
             mapper: fn(AuthorNameStartingWithBorrowed) -> T,
+            error: fn(postgres::Error) -> E,
         }
-        impl<'a, C, T: 'a, const N: usize> AuthorNameStartingWithQuery<'a, C, T, N>
+        impl<'a, C, T: 'a, const N: usize, E: 'a> AuthorNameStartingWithQuery<'a, C, T, N, E>
         where
             C: GenericClient,
         {
             pub fn map<R>(
                 self,
                 mapper: fn(AuthorNameStartingWithBorrowed) -> R,
-            ) -> AuthorNameStartingWithQuery<'a, C, R, N> {
+            ) -> AuthorNameStartingWithQuery<'a, C, R, N, E> {
                 AuthorNameStartingWithQuery {
                     client: self.client,
                     params: self.params,
                     stmt: self.stmt,
                     extractor: self.extractor,
                     mapper,
+                    error: self.error,
                 }
             }
-            pub fn one(self) -> Result<T, postgres::Error> {
-                let stmt = self.stmt.prepare(self.client)?;
-                let row = self.client.query_one(stmt, &self.params)?;
-                Ok((self.mapper)((self.extractor)(&row)))
-            }
-            pub fn all(self) -> Result<Vec<T>, postgres::Error> {
+            pub fn one(self) -> Result<T, E> {
+                let row = self
+                    .stmt
+                    .query_one(self.client, &self.params)
+                    .map_err(self.error)?;
+                Ok((self.mapper)((self.extractor)(&row).map_err(self.error)?))
+            }
+            pub fn opt(self) -> Result<Option<T>, E> {
+                let row = self
+                    .stmt
+                    .query_opt(self.client, &self.params)
+                    .map_err(self.error)?;
+                row.map(|row| Ok((self.mapper)((self.extractor)(&row).map_err(self.error)?)))
+                    .transpose()
+            }
+            pub fn all(self) -> Result<Vec<T>, E> {
                 self.iter()?.collect()
             }
-            pub fn opt(self) -> Result<Option<T>, postgres::Error> {
-                let stmt = self.stmt.prepare(self.client)?;
-                Ok(self
-                    .client
-                    .query_opt(stmt, &self.params)?
-                    .map(|row| (self.mapper)((self.extractor)(&row))))
-            }
-            pub fn iter(
-                self,
-            ) -> Result<impl Iterator<Item = Result<T, postgres::Error>> + 'a, postgres::Error>
-            {
-                let stmt = self.stmt.prepare(self.client)?;
+            pub fn iter(self) -> Result<impl Iterator<Item = Result<T, E>> + 'a, E> {
                 let it = self
-                    .client
-                    .query_raw(stmt, cornucopia_sync::private::slice_iter(&self.params))?
+                    .stmt
+                    .query_raw(self.client, &self.params)
+                    .map_err(self.error)?
                     .iterator()
-                    .map(move |res| res.map(|row| (self.mapper)((self.extractor)(&row))));
+                    .map(move |res| {
+                        res.and_then(|row| (self.extractor)(&row).map(self.mapper))
+                            .map_err(self.error)
+                    });
                 Ok(it)
             }
+            #[doc = " Declares a server-side cursor over the rows, fetching them in chunks instead of"]
+            #[doc = " buffering them all. Cursors only live inside a transaction."]
+            pub fn cursor(self) -> Result<cornucopia_sync::Cursor<'a, C, T, E>, E>
+            where
+                C: cornucopia_sync::InTransaction,
+            {
+                let (extractor, mapper) = (self.extractor, self.mapper);
+                self.stmt.cursor(
+                    self.client,
+                    &self.params,
+                    move |row| extractor(row).map(mapper),
+                    self.error,
+                )
+            }
         }
-        pub struct PublicVoiceactorQuery<'a, C: GenericClient, T, const N: usize> {
+        pub struct PublicVoiceactorQuery<
+            'a,
+            C: GenericClient,
+            T,
+            const N: usize,
+            E = postgres::Error,
+        > {
             client: &'a mut C,
             params: [&'a (dyn postgres_types::ToSql + Sync); N],
             stmt: &'a mut cornucopia_sync::private::Stmt,
-            extractor: fn(&postgres::Row) -> super::super::types::public::VoiceactorBorrowed,
+            extractor:
+                fn(
+                    &postgres::Row,
+                )
+                    -> Result<super::super::types::public::VoiceactorBorrowed, postgres::Error>,
             mapper: fn(super::super::types::public::VoiceactorBorrowed) -> T,
+            error: fn(postgres::Error) -> E,
         }
-        impl<'a, C, T: 'a, const N: usize> PublicVoiceactorQuery<'a, C, T, N>
+        impl<'a, C, T: 'a, const N: usize, E: 'a> PublicVoiceactorQuery<'a, C, T, N, E>
         where
             C: GenericClient,
         {
             pub fn map<R>(
                 self,
                 mapper: fn(super::super::types::public::VoiceactorBorrowed) -> R,
-            ) -> PublicVoiceactorQuery<'a, C, R, N> {
+            ) -> PublicVoiceactorQuery<'a, C, R, N, E> {
                 PublicVoiceactorQuery {
                     client: self.client,
                     params: self.params,
                     stmt: self.stmt,
                     extractor: self.extractor,
                     mapper,
+                    error: self.error,
                 }
             }
-            pub fn one(self) -> Result<T, postgres::Error> {
-                let stmt = self.stmt.prepare(self.client)?;
-                let row = self.client.query_one(stmt, &self.params)?;
-                Ok((self.mapper)((self.extractor)(&row)))
-            }
-            pub fn all(self) -> Result<Vec<T>, postgres::Error> {
+            pub fn one(self) -> Result<T, E> {
+                let row = self
+                    .stmt
+                    .query_one(self.client, &self.params)
+                    .map_err(self.error)?;
+                Ok((self.mapper)((self.extractor)(&row).map_err(self.error)?))
+            }
+            pub fn opt(self) -> Result<Option<T>, E> {
+                let row = self
+                    .stmt
+                    .query_opt(self.client, &self.params)
+                    .map_err(self.error)?;
+                row.map(|row| Ok((self.mapper)((self.extractor)(&row).map_err(self.error)?)))
+                    .transpose()
+            }
+            pub fn all(self) -> Result<Vec<T>, E> {
                 self.iter()?.collect()
             }
-            pub fn opt(self) -> Result<Option<T>, postgres::Error> {
-                let stmt = self.stmt.prepare(self.client)?;
-                Ok(self
-                    .client
-                    .query_opt(stmt, &self.params)?
-                    .map(|row| (self.mapper)((self.extractor)(&row))))
-            }
-            pub fn iter(
-                self,
-            ) -> Result<impl Iterator<Item = Result<T, postgres::Error>> + 'a, postgres::Error>
-            {
-                let stmt = self.stmt.prepare(self.client)?;
+            pub fn iter(self) -> Result<impl Iterator<Item = Result<T, E>> + 'a, E> {
                 let it = self
-                    .client
-                    .query_raw(stmt, cornucopia_sync::private::slice_iter(&self.params))?
+                    .stmt
+                    .query_raw(self.client, &self.params)
+                    .map_err(self.error)?
                     .iterator()
-                    .map(move |res| res.map(|row| (self.mapper)((self.extractor)(&row))));
+                    .map(move |res| {
+                        res.and_then(|row| (self.extractor)(&row).map(self.mapper))
+                            .map_err(self.error)
+                    });
                 Ok(it)
             }
+            #[doc = " Declares a server-side cursor over the rows, fetching them in chunks instead of"]
+            #[doc = " buffering them all. Cursors only live inside a transaction."]
+            pub fn cursor(self) -> Result<cornucopia_sync::Cursor<'a, C, T, E>, E>
+            where
+                C: cornucopia_sync::InTransaction,
+            {
+                let (extractor, mapper) = (self.extractor, self.mapper);
+                self.stmt.cursor(
+                    self.client,
+                    &self.params,
+                    move |row| extractor(row).map(mapper),
+                    self.error,
+                )
+            }
         }
-        pub struct SelectTranslationsQuery<'a, C: GenericClient, T, const N: usize> {
+        pub struct SelectTranslationsQuery<
+            'a,
+            C: GenericClient,
+            T,
+            const N: usize,
+            E = postgres::Error,
+        > {
             client: &'a mut C,
             params: [&'a (dyn postgres_types::ToSql + Sync); N],
             stmt: &'a mut cornucopia_sync::private::Stmt,
-            extractor: fn(&postgres::Row) -> SelectTranslationsBorrowed,
+            extractor: fn(&postgres::Row) -> Result<SelectTranslationsBorrowed, postgres::Error>,
             mapper: fn(SelectTranslationsBorrowed) -> T,
+            error: fn(postgres::Error) -> E,
         }
-        impl<'a, C, T: 'a, const N: usize> SelectTranslationsQuery<'a, C, T, N>
+        impl<'a, C, T: 'a, const N: usize, E: 'a> SelectTranslationsQuery<'a, C, T, N, E>
         where
             C: GenericClient,
         {
             pub fn map<R>(
                 self,
                 mapper: fn(SelectTranslationsBorrowed) -> R,
-            ) -> SelectTranslationsQuery<'a, C, R, N> {
+            ) -> SelectTranslationsQuery<'a, C, R, N, E> {
                 SelectTranslationsQuery {
                     client: self.client,
                     params: self.params,
                     stmt: self.stmt,
                     extractor: self.extractor,
                     mapper,
+                    error: self.error,
                 }
             }
-            pub fn one(self) -> Result<T, postgres::Error> {
-                let stmt = self.stmt.prepare(self.client)?;
-                let row = self.client.query_one(stmt, &self.params)?;
-                Ok((self.mapper)((self.extractor)(&row)))
-            }
-            pub fn all(self) -> Result<Vec<T>, postgres::Error> {
+            pub fn one(self) -> Result<T, E> {
+                let row = self
+                    .stmt
+                    .query_one(self.client, &self.params)
+                    .map_err(self.error)?;
+                Ok((self.mapper)((self.extractor)(&row).map_err(self.error)?))
+            }
+            pub fn opt(self) -> Result<Option<T>, E> {
+                let row = self
+                    .stmt
+                    .query_opt(self.client, &self.params)
+                    .map_err(self.error)?;
+                row.map(|row| Ok((self.mapper)((self.extractor)(&row).map_err(self.error)?)))
+                    .transpose()
+            }
+            pub fn all(self) -> Result<Vec<T>, E> {
                 self.iter()?.collect()
             }
-            pub fn opt(self) -> Result<Option<T>, postgres::Error> {
-                let stmt = self.stmt.prepare(self.client)?;
-                Ok(self
-                    .client
-                    .query_opt(stmt, &self.params)?
-                    .map(|row| (self.mapper)((self.extractor)(&row))))
-            }
-            pub fn iter(
-                self,
-            ) -> Result<impl Iterator<Item = Result<T, postgres::Error>> + 'a, postgres::Error>
-            {
-                let stmt = self.stmt.prepare(self.client)?;
+            pub fn iter(self) -> Result<impl Iterator<Item = Result<T, E>> + 'a, E> {
                 let it = self
-                    .client
-                    .query_raw(stmt, cornucopia_sync::private::slice_iter(&self.params))?
+                    .stmt
+                    .query_raw(self.client, &self.params)
+                    .map_err(self.error)?
                     .iterator()
-                    .map(move |res| res.map(|row| (self.mapper)((self.extractor)(&row))));
+                    .map(move |res| {
+                        res.and_then(|row| (self.extractor)(&row).map(self.mapper))
+                            .map_err(self.error)
+                    });
                 Ok(it)
             }
+            #[doc = " Declares a server-side cursor over the rows, fetching them in chunks instead of"]
+            #[doc = " buffering them all. Cursors only live inside a transaction."]
+            pub fn cursor(self) -> Result<cornucopia_sync::Cursor<'a, C, T, E>, E>
+            where
+                C: cornucopia_sync::InTransaction,
+            {
+                let (extractor, mapper) = (self.extractor, self.mapper);
+                self.stmt.cursor(
+                    self.client,
+                    &self.params,
+                    move |row| extractor(row).map(mapper),
+                    self.error,
+                )
+            }
         }
         pub fn authors() -> AuthorsStmt {
             AuthorsStmt(cornucopia_sync::private::Stmt::new(
@@ -565,6 +694,8 @@ FROM
         }
         pub struct AuthorsStmt(cornucopia_sync::private::Stmt);
         impl AuthorsStmt {
+            #[doc = " Latency budget in milliseconds declared by the `budget` option of the query"]
+            pub const BUDGET_MS: Option<u64> = None;
             pub fn bind<'a, C: GenericClient>(
                 &'a mut self,
                 client: &'a mut C,
@@ -573,12 +704,15 @@ FROM
                     client,
                     params: [],
                     stmt: &mut self.0,
-                    extractor: |row| AuthorsBorrowed {
-                        id: row.get(0),
-                        name: row.get(1),
-                        country: row.get(2),
+                    extractor: |row| {
+                        Ok(AuthorsBorrowed {
+                            id: row.try_get(0)?,
+                            name: row.try_get(1)?,
+                            country: row.try_get(2)?,
+                        })
                     },
                     mapper: |it| <Authors>::from(it),
+                    error: From::from,
                 }
             }
         }
@@ -592,6 +726,8 @@ FROM
         }
         pub struct BooksStmt(cornucopia_sync::private::Stmt);
         impl BooksStmt {
+            #[doc = " Latency budget in milliseconds declared by the `budget` option of the query"]
+            pub const BUDGET_MS: Option<u64> = None;
             pub fn bind<'a, C: GenericClient>(
                 &'a mut self,
                 client: &'a mut C,
@@ -600,8 +736,9 @@ FROM
                     client,
                     params: [],
                     stmt: &mut self.0,
-                    extractor: |row| row.get(0),
+                    extractor: |row| row.try_get(0),
                     mapper: |it| it.into(),
+                    error: From::from,
                 }
             }
         }
@@ -617,6 +754,8 @@ WHERE
         }
         pub struct AuthorNameByIdStmt(cornucopia_sync::private::Stmt);
         impl AuthorNameByIdStmt {
+            #[doc = " Latency budget in milliseconds declared by the `budget` option of the query"]
+            pub const BUDGET_MS: Option<u64> = None;
             pub fn bind<'a, C: GenericClient>(
                 &'a mut self,
                 client: &'a mut C,
@@ -626,8 +765,9 @@ WHERE
                     client,
                     params: [id],
                     stmt: &mut self.0,
-                    extractor: |row| row.get(0),
+                    extractor: |row| row.try_get(0),
                     mapper: |it| it.into(),
+                    error: From::from,
                 }
             }
         }
@@ -648,6 +788,8 @@ WHERE
         }
         pub struct AuthorNameStartingWithStmt(cornucopia_sync::private::Stmt);
         impl AuthorNameStartingWithStmt {
+            #[doc = " Latency budget in milliseconds declared by the `budget` option of the query"]
+            pub const BUDGET_MS: Option<u64> = None;
             pub fn bind<'a, C: GenericClient, T1: cornucopia_sync::StringSql>(
                 &'a mut self,
                 client: &'a mut C,
@@ -657,13 +799,16 @@ WHERE
                     client,
                     params: [start_str],
                     stmt: &mut self.0,
-                    extractor: |row| AuthorNameStartingWithBorrowed {
-                        authorid: row.get(0),
-                        name: row.get(1),
-                        bookid: row.get(2),
-                        title: row.get(3),
+                    extractor: |row| {
+                        Ok(AuthorNameStartingWithBorrowed {
+                            authorid: row.try_get(0)?,
+                            name: row.try_get(1)?,
+                            bookid: row.try_get(2)?,
+                            title: row.try_get(3)?,
+                        })
                     },
                     mapper: |it| <AuthorNameStartingWith>::from(it),
+                    error: From::from,
                 }
             }
         }
@@ -695,6 +840,8 @@ WHERE
         }
         pub struct SelectVoiceActorWithCharacterStmt(cornucopia_sync::private::Stmt);
         impl SelectVoiceActorWithCharacterStmt {
+            #[doc = " Latency budget in milliseconds declared by the `budget` option of the query"]
+            pub const BUDGET_MS: Option<u64> = None;
             pub fn bind<'a, C: GenericClient>(
                 &'a mut self,
                 client: &'a mut C,
@@ -705,8 +852,9 @@ WHERE
                     client,
                     params: [spongebob_character],
                     stmt: &mut self.0,
-                    extractor: |row| row.get(0),
+                    extractor: |row| row.try_get(0),
                     mapper: |it| it.into(),
+                    error: From::from,
                 }
             }
         }
@@ -721,6 +869,8 @@ FROM
         }
         pub struct SelectTranslationsStmt(cornucopia_sync::private::Stmt);
         impl SelectTranslationsStmt {
+            #[doc = " Latency budget in milliseconds declared by the `budget` option of the query"]
+            pub const BUDGET_MS: Option<u64> = None;
             pub fn bind<'a, C: GenericClient>(
                 &'a mut self,
                 client: &'a mut C,
@@ -729,11 +879,14 @@ FROM
                     client,
                     params: [],
                     stmt: &mut self.0,
-                    extractor: |row| SelectTranslationsBorrowed {
-                        title: row.get(0),
-                        translations: row.get(1),
+                    extractor: |row| {
+                        Ok(SelectTranslationsBorrowed {
+                            title: row.try_get(0)?,
+                            translations: row.try_get(1)?,
+                        })
                     },
                     mapper: |it| <SelectTranslations>::from(it),
+                    error: From::from,
                 }
             }
         }
@@ -79,6 +79,18 @@ pub fn main() {
         .unwrap();
     dbg!(name_starting_with_jo);
 
+    // Serde traits can be derived on the generated structs, for example to receive
+    // parameters in JSON request bodies and send rows back as JSON.
+    // ! Note: This example derives `Deserialize` on parameters and `Serialize` on rows
+    // ! and custom types, using `--serialize=rows,types --deserialize=params`.
+    let params: AuthorNameStartingWithParams<String> =
+        serde_json::from_str(r#"{ "start_str": "Jo" }"#).unwrap();
+    let rows = author_name_starting_with()
+        .params(&mut client, &params)
+        .all()
+        .unwrap();
+    println!("{}", serde_json::to_string(&rows).unwrap());
+
     // Custom PostgreSQL types from your queries also work!
     // This includes domains, composites and enums.
     // They will be automatically generated by Cornucopia.
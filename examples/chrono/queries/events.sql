@@ -0,0 +1,9 @@
+--! insert_event
+INSERT INTO event (name, starts_at, local_start, day, opens_at)
+VALUES (:name, :starts_at, :local_start, :day, :opens_at);
+
+--! events
+SELECT * FROM event ORDER BY starts_at;
+
+--! events_after
+SELECT name FROM event WHERE starts_at > :after ORDER BY starts_at;
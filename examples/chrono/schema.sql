@@ -0,0 +1,7 @@
+CREATE TABLE event (
+    name TEXT PRIMARY KEY,
+    starts_at TIMESTAMPTZ NOT NULL,
+    local_start TIMESTAMP NOT NULL,
+    day DATE NOT NULL,
+    opens_at TIME NOT NULL
+);
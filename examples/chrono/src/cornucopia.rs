@@ -0,0 +1,289 @@
+// This file was generated with `cornucopia`. Do not modify.
+
+#[allow(clippy::all, clippy::pedantic)]
+#[allow(unused_variables)]
+#[allow(unused_imports)]
+#[allow(dead_code)]
+pub mod types {}
+#[allow(clippy::all, clippy::pedantic)]
+#[allow(unused_variables)]
+#[allow(unused_imports)]
+#[allow(dead_code)]
+pub mod queries {
+    pub mod events {
+        #[derive(Debug)]
+        pub struct InsertEventParams<T1: cornucopia_sync::StringSql> {
+            pub name: T1,
+            pub starts_at: chrono::DateTime<chrono::Utc>,
+            pub local_start: chrono::NaiveDateTime,
+            pub day: chrono::NaiveDate,
+            pub opens_at: chrono::NaiveTime,
+        }
+        #[derive(Debug, Clone, PartialEq, Eq, Hash, PartialOrd, Ord)]
+        pub struct Events {
+            pub name: String,
+            pub starts_at: chrono::DateTime<chrono::Utc>,
+            pub local_start: chrono::NaiveDateTime,
+            pub day: chrono::NaiveDate,
+            pub opens_at: chrono::NaiveTime,
+        }
+        pub struct EventsBorrowed<'a> {
+            pub name: &'a str,
+            pub starts_at: chrono::DateTime<chrono::Utc>,
+            pub local_start: chrono::NaiveDateTime,
+            pub day: chrono::NaiveDate,
+            pub opens_at: chrono::NaiveTime,
+        }
+        impl<'a> From<EventsBorrowed<'a>> for Events {
+            fn from(
+                EventsBorrowed {
+                    name,
+                    starts_at,
+                    local_start,
+                    day,
+                    opens_at,
+                }: EventsBorrowed<'a>,
+            ) -> Self {
+                Self {
+                    name: name.into(),
+                    starts_at,
+                    local_start,
+                    day,
+                    opens_at,
+                }
+            }
+        }
+        use postgres::{fallible_iterator::FallibleIterator, GenericClient};
+        pub struct EventsQuery<'a, C: GenericClient, T, const N: usize, E = postgres::Error> {
+            client: &'a mut C,
+            params: [&'a (dyn postgres_types::ToSql + Sync); N],
+            stmt: &'a mut cornucopia_sync::private::Stmt,
+            extractor: fn(&postgres::Row) -> Result<EventsBorrowed, postgres::Error>,
+            mapper: fn(EventsBorrowed) -> T,
+            error: fn(postgres::Error) -> E,
+        }
+        impl<'a, C, T: 'a, const N: usize, E: 'a> EventsQuery<'a, C, T, N, E>
+        where
+            C: GenericClient,
+        {
+            pub fn map<R>(self, mapper: fn(EventsBorrowed) -> R) -> EventsQuery<'a, C, R, N, E> {
+                EventsQuery {
+                    client: self.client,
+                    params: self.params,
+                    stmt: self.stmt,
+                    extractor: self.extractor,
+                    mapper,
+                    error: self.error,
+                }
+            }
+            pub fn one(self) -> Result<T, E> {
+                let row = self
+                    .stmt
+                    .query_one(self.client, &self.params)
+                    .map_err(self.error)?;
+                Ok((self.mapper)((self.extractor)(&row).map_err(self.error)?))
+            }
+            pub fn opt(self) -> Result<Option<T>, E> {
+                let row = self
+                    .stmt
+                    .query_opt(self.client, &self.params)
+                    .map_err(self.error)?;
+                row.map(|row| Ok((self.mapper)((self.extractor)(&row).map_err(self.error)?)))
+                    .transpose()
+            }
+            pub fn all(self) -> Result<Vec<T>, E> {
+                self.iter()?.collect()
+            }
+            pub fn iter(self) -> Result<impl Iterator<Item = Result<T, E>> + 'a, E> {
+                let it = self
+                    .stmt
+                    .query_raw(self.client, &self.params)
+                    .map_err(self.error)?
+                    .iterator()
+                    .map(move |res| {
+                        res.and_then(|row| (self.extractor)(&row).map(self.mapper))
+                            .map_err(self.error)
+                    });
+                Ok(it)
+            }
+            #[doc = " Declares a server-side cursor over the rows, fetching them in chunks instead of"]
+            #[doc = " buffering them all. Cursors only live inside a transaction."]
+            pub fn cursor(self) -> Result<cornucopia_sync::Cursor<'a, C, T, E>, E>
+            where
+                C: cornucopia_sync::InTransaction,
+            {
+                let (extractor, mapper) = (self.extractor, self.mapper);
+                self.stmt.cursor(
+                    self.client,
+                    &self.params,
+                    move |row| extractor(row).map(mapper),
+                    self.error,
+                )
+            }
+        }
+        pub struct StringQuery<'a, C: GenericClient, T, const N: usize, E = postgres::Error> {
+            client: &'a mut C,
+            params: [&'a (dyn postgres_types::ToSql + Sync); N],
+            stmt: &'a mut cornucopia_sync::private::Stmt,
+            extractor: fn(&postgres::Row) -> Result<&str, postgres::Error>,
+            mapper: fn(&str) -> T,
+            error: fn(postgres::Error) -> E,
+        }
+        impl<'a, C, T: 'a, const N: usize, E: 'a> StringQuery<'a, C, T, N, E>
+        where
+            C: GenericClient,
+        {
+            pub fn map<R>(self, mapper: fn(&str) -> R) -> StringQuery<'a, C, R, N, E> {
+                StringQuery {
+                    client: self.client,
+                    params: self.params,
+                    stmt: self.stmt,
+                    extractor: self.extractor,
+                    mapper,
+                    error: self.error,
+                }
+            }
+            pub fn one(self) -> Result<T, E> {
+                let row = self
+                    .stmt
+                    .query_one(self.client, &self.params)
+                    .map_err(self.error)?;
+                Ok((self.mapper)((self.extractor)(&row).map_err(self.error)?))
+            }
+            pub fn opt(self) -> Result<Option<T>, E> {
+                let row = self
+                    .stmt
+                    .query_opt(self.client, &self.params)
+                    .map_err(self.error)?;
+                row.map(|row| Ok((self.mapper)((self.extractor)(&row).map_err(self.error)?)))
+                    .transpose()
+            }
+            pub fn all(self) -> Result<Vec<T>, E> {
+                self.iter()?.collect()
+            }
+            pub fn iter(self) -> Result<impl Iterator<Item = Result<T, E>> + 'a, E> {
+                let it = self
+                    .stmt
+                    .query_raw(self.client, &self.params)
+                    .map_err(self.error)?
+                    .iterator()
+                    .map(move |res| {
+                        res.and_then(|row| (self.extractor)(&row).map(self.mapper))
+                            .map_err(self.error)
+                    });
+                Ok(it)
+            }
+            #[doc = " Declares a server-side cursor over the rows, fetching them in chunks instead of"]
+            #[doc = " buffering them all. Cursors only live inside a transaction."]
+            pub fn cursor(self) -> Result<cornucopia_sync::Cursor<'a, C, T, E>, E>
+            where
+                C: cornucopia_sync::InTransaction,
+            {
+                let (extractor, mapper) = (self.extractor, self.mapper);
+                self.stmt.cursor(
+                    self.client,
+                    &self.params,
+                    move |row| extractor(row).map(mapper),
+                    self.error,
+                )
+            }
+        }
+        pub fn insert_event() -> InsertEventStmt {
+            InsertEventStmt(cornucopia_sync::private::Stmt::new(
+                "INSERT INTO event (name, starts_at, local_start, day, opens_at)
+VALUES ($1, $2, $3, $4, $5)",
+            ))
+        }
+        pub struct InsertEventStmt(cornucopia_sync::private::Stmt);
+        impl InsertEventStmt {
+            #[doc = " Latency budget in milliseconds declared by the `budget` option of the query"]
+            pub const BUDGET_MS: Option<u64> = None;
+            pub fn bind<'a, C: GenericClient, T1: cornucopia_sync::StringSql>(
+                &'a mut self,
+                client: &'a mut C,
+                name: &'a T1,
+                starts_at: &'a chrono::DateTime<chrono::Utc>,
+                local_start: &'a chrono::NaiveDateTime,
+                day: &'a chrono::NaiveDate,
+                opens_at: &'a chrono::NaiveTime,
+            ) -> Result<u64, postgres::Error> {
+                self.0
+                    .execute(client, &[name, starts_at, local_start, day, opens_at])
+            }
+        }
+        impl<'a, C: GenericClient, T1: cornucopia_sync::StringSql>
+            cornucopia_sync::Params<'a, InsertEventParams<T1>, Result<u64, postgres::Error>, C>
+            for InsertEventStmt
+        {
+            fn params(
+                &'a mut self,
+                client: &'a mut C,
+                params: &'a InsertEventParams<T1>,
+            ) -> Result<u64, postgres::Error> {
+                self.bind(
+                    client,
+                    &params.name,
+                    &params.starts_at,
+                    &params.local_start,
+                    &params.day,
+                    &params.opens_at,
+                )
+            }
+        }
+        pub fn events() -> EventsStmt {
+            EventsStmt(cornucopia_sync::private::Stmt::new(
+                "SELECT * FROM event ORDER BY starts_at",
+            ))
+        }
+        pub struct EventsStmt(cornucopia_sync::private::Stmt);
+        impl EventsStmt {
+            #[doc = " Latency budget in milliseconds declared by the `budget` option of the query"]
+            pub const BUDGET_MS: Option<u64> = None;
+            pub fn bind<'a, C: GenericClient>(
+                &'a mut self,
+                client: &'a mut C,
+            ) -> EventsQuery<'a, C, Events, 0> {
+                EventsQuery {
+                    client,
+                    params: [],
+                    stmt: &mut self.0,
+                    extractor: |row| {
+                        Ok(EventsBorrowed {
+                            name: row.try_get(0)?,
+                            starts_at: row.try_get(1)?,
+                            local_start: row.try_get(2)?,
+                            day: row.try_get(3)?,
+                            opens_at: row.try_get(4)?,
+                        })
+                    },
+                    mapper: |it| <Events>::from(it),
+                    error: From::from,
+                }
+            }
+        }
+        pub fn events_after() -> EventsAfterStmt {
+            EventsAfterStmt(cornucopia_sync::private::Stmt::new(
+                "SELECT name FROM event WHERE starts_at > $1 ORDER BY starts_at",
+            ))
+        }
+        pub struct EventsAfterStmt(cornucopia_sync::private::Stmt);
+        impl EventsAfterStmt {
+            #[doc = " Latency budget in milliseconds declared by the `budget` option of the query"]
+            pub const BUDGET_MS: Option<u64> = None;
+            pub fn bind<'a, C: GenericClient>(
+                &'a mut self,
+                client: &'a mut C,
+                after: &'a chrono::DateTime<chrono::Utc>,
+            ) -> StringQuery<'a, C, String, 1> {
+                StringQuery {
+                    client,
+                    params: [after],
+                    stmt: &mut self.0,
+                    extractor: |row| row.try_get(0),
+                    mapper: |it| it.into(),
+                    error: From::from,
+                }
+            }
+        }
+    }
+}
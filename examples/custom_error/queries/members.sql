@@ -0,0 +1,17 @@
+--! members
+SELECT * FROM member ORDER BY id;
+
+--! member_by_email
+SELECT * FROM member WHERE email = :email;
+
+--! insert_member
+-- :on_error 23505 AlreadyMember
+INSERT INTO member (email, age) VALUES (:email, :age) RETURNING id;
+
+--! set_age
+UPDATE member SET age = :age WHERE email = :email;
+
+--! clear_members
+-- :batch
+DELETE FROM member;
+ALTER SEQUENCE member_id_seq RESTART;
@@ -0,0 +1,5 @@
+CREATE TABLE member (
+    id SERIAL PRIMARY KEY,
+    email TEXT NOT NULL UNIQUE,
+    age INT NOT NULL CHECK (age >= 0)
+);
@@ -0,0 +1,493 @@
+// This file was generated with `cornucopia`. Do not modify.
+
+#[allow(clippy::all, clippy::pedantic)]
+#[allow(unused_variables)]
+#[allow(unused_imports)]
+#[allow(dead_code)]
+pub mod types {}
+#[allow(clippy::all, clippy::pedantic)]
+#[allow(unused_variables)]
+#[allow(unused_imports)]
+#[allow(dead_code)]
+pub mod queries {
+    pub mod members {
+        #[derive(Debug)]
+        pub struct InsertMemberParams<T1: cornucopia_async::StringSql> {
+            pub email: T1,
+            pub age: i32,
+        }
+        #[derive(Debug)]
+        pub struct SetAgeParams<T1: cornucopia_async::StringSql> {
+            pub age: i32,
+            pub email: T1,
+        }
+        #[derive(Debug, Clone, PartialEq, Eq, Hash, PartialOrd, Ord)]
+        pub struct Members {
+            pub id: i32,
+            pub email: String,
+            pub age: i32,
+        }
+        pub struct MembersBorrowed<'a> {
+            pub id: i32,
+            pub email: &'a str,
+            pub age: i32,
+        }
+        impl<'a> From<MembersBorrowed<'a>> for Members {
+            fn from(MembersBorrowed { id, email, age }: MembersBorrowed<'a>) -> Self {
+                Self {
+                    id,
+                    email: email.into(),
+                    age,
+                }
+            }
+        }
+        #[derive(Debug, Clone, PartialEq, Eq, Hash, PartialOrd, Ord)]
+        pub struct MemberByEmail {
+            pub id: i32,
+            pub email: String,
+            pub age: i32,
+        }
+        pub struct MemberByEmailBorrowed<'a> {
+            pub id: i32,
+            pub email: &'a str,
+            pub age: i32,
+        }
+        impl<'a> From<MemberByEmailBorrowed<'a>> for MemberByEmail {
+            fn from(MemberByEmailBorrowed { id, email, age }: MemberByEmailBorrowed<'a>) -> Self {
+                Self {
+                    id,
+                    email: email.into(),
+                    age,
+                }
+            }
+        }
+        #[derive(Debug)]
+        pub enum InsertMemberError {
+            AlreadyMember(tokio_postgres::Error),
+            Other(tokio_postgres::Error),
+        }
+        impl InsertMemberError {
+            pub fn inner(&self) -> &tokio_postgres::Error {
+                match self {
+                    Self::AlreadyMember(err) | Self::Other(err) => err,
+                }
+            }
+            pub fn into_inner(self) -> tokio_postgres::Error {
+                match self {
+                    Self::AlreadyMember(err) | Self::Other(err) => err,
+                }
+            }
+        }
+        impl From<tokio_postgres::Error> for InsertMemberError {
+            fn from(err: tokio_postgres::Error) -> Self {
+                match err.code().map(tokio_postgres::error::SqlState::code) {
+                    Some("23505") => Self::AlreadyMember(err),
+                    _ => Self::Other(err),
+                }
+            }
+        }
+        impl From<InsertMemberError> for tokio_postgres::Error {
+            fn from(err: InsertMemberError) -> Self {
+                err.into_inner()
+            }
+        }
+        impl std::fmt::Display for InsertMemberError {
+            fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+                match self.inner().as_db_error() {
+                    Some(err) => err.fmt(f),
+                    None => self.inner().fmt(f),
+                }
+            }
+        }
+        impl std::error::Error for InsertMemberError {
+            fn source(&self) -> Option<&(dyn std::error::Error + 'static)> {
+                if self.inner().as_db_error().is_some() {
+                    None
+                } else {
+                    std::error::Error::source(self.inner())
+                }
+            }
+        }
+        use cornucopia_async::GenericClient;
+        use futures;
+        use futures::{StreamExt, TryStreamExt};
+        pub struct MembersQuery<'a, C: GenericClient, T, const N: usize, E = crate::AppError> {
+            client: &'a C,
+            params: [&'a (dyn postgres_types::ToSql + Sync); N],
+            stmt: &'a mut cornucopia_async::private::Stmt,
+            extractor: fn(&tokio_postgres::Row) -> Result<MembersBorrowed, tokio_postgres::Error>,
+            mapper: fn(MembersBorrowed) -> T,
+            error: fn(tokio_postgres::Error) -> E,
+        }
+        impl<'a, C, T: 'a, const N: usize, E: 'a> MembersQuery<'a, C, T, N, E>
+        where
+            C: GenericClient,
+        {
+            pub fn map<R>(self, mapper: fn(MembersBorrowed) -> R) -> MembersQuery<'a, C, R, N, E> {
+                MembersQuery {
+                    client: self.client,
+                    params: self.params,
+                    stmt: self.stmt,
+                    extractor: self.extractor,
+                    mapper,
+                    error: self.error,
+                }
+            }
+            pub async fn one(self) -> Result<T, E> {
+                let row = self
+                    .stmt
+                    .query_one(self.client, &self.params)
+                    .await
+                    .map_err(self.error)?;
+                Ok((self.mapper)((self.extractor)(&row).map_err(self.error)?))
+            }
+            pub async fn opt(self) -> Result<Option<T>, E> {
+                let row = self
+                    .stmt
+                    .query_opt(self.client, &self.params)
+                    .await
+                    .map_err(self.error)?;
+                row.map(|row| Ok((self.mapper)((self.extractor)(&row).map_err(self.error)?)))
+                    .transpose()
+            }
+            pub async fn all(self) -> Result<Vec<T>, E> {
+                self.iter().await?.try_collect().await
+            }
+            pub async fn iter(self) -> Result<impl futures::Stream<Item = Result<T, E>> + 'a, E> {
+                let it = self
+                    .stmt
+                    .query_raw(self.client, &self.params)
+                    .await
+                    .map_err(self.error)?
+                    .map(move |res| {
+                        res.and_then(|row| (self.extractor)(&row).map(self.mapper))
+                            .map_err(self.error)
+                    })
+                    .into_stream();
+                Ok(it)
+            }
+            #[doc = " Declares a server-side cursor over the rows, fetching them in chunks instead of"]
+            #[doc = " buffering them all. Cursors only live inside a transaction."]
+            pub async fn cursor(self) -> Result<cornucopia_async::Cursor<'a, C, T, E>, E>
+            where
+                C: cornucopia_async::InTransaction,
+            {
+                let (extractor, mapper) = (self.extractor, self.mapper);
+                self.stmt
+                    .cursor(
+                        self.client,
+                        &self.params,
+                        move |row| extractor(row).map(mapper),
+                        self.error,
+                    )
+                    .await
+            }
+        }
+        pub struct MemberByEmailQuery<'a, C: GenericClient, T, const N: usize, E = crate::AppError> {
+            client: &'a C,
+            params: [&'a (dyn postgres_types::ToSql + Sync); N],
+            stmt: &'a mut cornucopia_async::private::Stmt,
+            extractor:
+                fn(&tokio_postgres::Row) -> Result<MemberByEmailBorrowed, tokio_postgres::Error>,
+            mapper: fn(MemberByEmailBorrowed) -> T,
+            error: fn(tokio_postgres::Error) -> E,
+        }
+        impl<'a, C, T: 'a, const N: usize, E: 'a> MemberByEmailQuery<'a, C, T, N, E>
+        where
+            C: GenericClient,
+        {
+            pub fn map<R>(
+                self,
+                mapper: fn(MemberByEmailBorrowed) -> R,
+            ) -> MemberByEmailQuery<'a, C, R, N, E> {
+                MemberByEmailQuery {
+                    client: self.client,
+                    params: self.params,
+                    stmt: self.stmt,
+                    extractor: self.extractor,
+                    mapper,
+                    error: self.error,
+                }
+            }
+            pub async fn one(self) -> Result<T, E> {
+                let row = self
+                    .stmt
+                    .query_one(self.client, &self.params)
+                    .await
+                    .map_err(self.error)?;
+                Ok((self.mapper)((self.extractor)(&row).map_err(self.error)?))
+            }
+            pub async fn opt(self) -> Result<Option<T>, E> {
+                let row = self
+                    .stmt
+                    .query_opt(self.client, &self.params)
+                    .await
+                    .map_err(self.error)?;
+                row.map(|row| Ok((self.mapper)((self.extractor)(&row).map_err(self.error)?)))
+                    .transpose()
+            }
+            pub async fn all(self) -> Result<Vec<T>, E> {
+                self.iter().await?.try_collect().await
+            }
+            pub async fn iter(self) -> Result<impl futures::Stream<Item = Result<T, E>> + 'a, E> {
+                let it = self
+                    .stmt
+                    .query_raw(self.client, &self.params)
+                    .await
+                    .map_err(self.error)?
+                    .map(move |res| {
+                        res.and_then(|row| (self.extractor)(&row).map(self.mapper))
+                            .map_err(self.error)
+                    })
+                    .into_stream();
+                Ok(it)
+            }
+            #[doc = " Declares a server-side cursor over the rows, fetching them in chunks instead of"]
+            #[doc = " buffering them all. Cursors only live inside a transaction."]
+            pub async fn cursor(self) -> Result<cornucopia_async::Cursor<'a, C, T, E>, E>
+            where
+                C: cornucopia_async::InTransaction,
+            {
+                let (extractor, mapper) = (self.extractor, self.mapper);
+                self.stmt
+                    .cursor(
+                        self.client,
+                        &self.params,
+                        move |row| extractor(row).map(mapper),
+                        self.error,
+                    )
+                    .await
+            }
+        }
+        pub struct I32Query<'a, C: GenericClient, T, const N: usize, E = crate::AppError> {
+            client: &'a C,
+            params: [&'a (dyn postgres_types::ToSql + Sync); N],
+            stmt: &'a mut cornucopia_async::private::Stmt,
+            extractor: fn(&tokio_postgres::Row) -> Result<i32, tokio_postgres::Error>,
+            mapper: fn(i32) -> T,
+            error: fn(tokio_postgres::Error) -> E,
+        }
+        impl<'a, C, T: 'a, const N: usize, E: 'a> I32Query<'a, C, T, N, E>
+        where
+            C: GenericClient,
+        {
+            pub fn map<R>(self, mapper: fn(i32) -> R) -> I32Query<'a, C, R, N, E> {
+                I32Query {
+                    client: self.client,
+                    params: self.params,
+                    stmt: self.stmt,
+                    extractor: self.extractor,
+                    mapper,
+                    error: self.error,
+                }
+            }
+            pub async fn one(self) -> Result<T, E> {
+                let row = self
+                    .stmt
+                    .query_one(self.client, &self.params)
+                    .await
+                    .map_err(self.error)?;
+                Ok((self.mapper)((self.extractor)(&row).map_err(self.error)?))
+            }
+            pub async fn opt(self) -> Result<Option<T>, E> {
+                let row = self
+                    .stmt
+                    .query_opt(self.client, &self.params)
+                    .await
+                    .map_err(self.error)?;
+                row.map(|row| Ok((self.mapper)((self.extractor)(&row).map_err(self.error)?)))
+                    .transpose()
+            }
+            pub async fn all(self) -> Result<Vec<T>, E> {
+                self.iter().await?.try_collect().await
+            }
+            pub async fn iter(self) -> Result<impl futures::Stream<Item = Result<T, E>> + 'a, E> {
+                let it = self
+                    .stmt
+                    .query_raw(self.client, &self.params)
+                    .await
+                    .map_err(self.error)?
+                    .map(move |res| {
+                        res.and_then(|row| (self.extractor)(&row).map(self.mapper))
+                            .map_err(self.error)
+                    })
+                    .into_stream();
+                Ok(it)
+            }
+            #[doc = " Declares a server-side cursor over the rows, fetching them in chunks instead of"]
+            #[doc = " buffering them all. Cursors only live inside a transaction."]
+            pub async fn cursor(self) -> Result<cornucopia_async::Cursor<'a, C, T, E>, E>
+            where
+                C: cornucopia_async::InTransaction,
+            {
+                let (extractor, mapper) = (self.extractor, self.mapper);
+                self.stmt
+                    .cursor(
+                        self.client,
+                        &self.params,
+                        move |row| extractor(row).map(mapper),
+                        self.error,
+                    )
+                    .await
+            }
+        }
+        pub fn members() -> MembersStmt {
+            MembersStmt(cornucopia_async::private::Stmt::new(
+                "SELECT * FROM member ORDER BY id",
+            ))
+        }
+        pub struct MembersStmt(cornucopia_async::private::Stmt);
+        impl MembersStmt {
+            #[doc = " Latency budget in milliseconds declared by the `budget` option of the query"]
+            pub const BUDGET_MS: Option<u64> = None;
+            pub fn bind<'a, C: GenericClient>(
+                &'a mut self,
+                client: &'a C,
+            ) -> MembersQuery<'a, C, Members, 0, crate::AppError> {
+                MembersQuery {
+                    client,
+                    params: [],
+                    stmt: &mut self.0,
+                    extractor: |row| {
+                        Ok(MembersBorrowed {
+                            id: row.try_get(0)?,
+                            email: row.try_get(1)?,
+                            age: row.try_get(2)?,
+                        })
+                    },
+                    mapper: |it| <Members>::from(it),
+                    error: From::from,
+                }
+            }
+        }
+        pub fn member_by_email() -> MemberByEmailStmt {
+            MemberByEmailStmt(cornucopia_async::private::Stmt::new(
+                "SELECT * FROM member WHERE email = $1",
+            ))
+        }
+        pub struct MemberByEmailStmt(cornucopia_async::private::Stmt);
+        impl MemberByEmailStmt {
+            #[doc = " Latency budget in milliseconds declared by the `budget` option of the query"]
+            pub const BUDGET_MS: Option<u64> = None;
+            pub fn bind<'a, C: GenericClient, T1: cornucopia_async::StringSql>(
+                &'a mut self,
+                client: &'a C,
+                email: &'a T1,
+            ) -> MemberByEmailQuery<'a, C, MemberByEmail, 1, crate::AppError> {
+                MemberByEmailQuery {
+                    client,
+                    params: [email],
+                    stmt: &mut self.0,
+                    extractor: |row| {
+                        Ok(MemberByEmailBorrowed {
+                            id: row.try_get(0)?,
+                            email: row.try_get(1)?,
+                            age: row.try_get(2)?,
+                        })
+                    },
+                    mapper: |it| <MemberByEmail>::from(it),
+                    error: From::from,
+                }
+            }
+        }
+        pub fn insert_member() -> InsertMemberStmt {
+            InsertMemberStmt(cornucopia_async::private::Stmt::new(
+                "INSERT INTO member (email, age) VALUES ($1, $2) RETURNING id",
+            ))
+        }
+        pub struct InsertMemberStmt(cornucopia_async::private::Stmt);
+        impl InsertMemberStmt {
+            #[doc = " Latency budget in milliseconds declared by the `budget` option of the query"]
+            pub const BUDGET_MS: Option<u64> = None;
+            pub fn bind<'a, C: GenericClient, T1: cornucopia_async::StringSql>(
+                &'a mut self,
+                client: &'a C,
+                email: &'a T1,
+                age: &'a i32,
+            ) -> I32Query<'a, C, i32, 2, crate::AppError> {
+                I32Query {
+                    client,
+                    params: [email, age],
+                    stmt: &mut self.0,
+                    extractor: |row| row.try_get(0),
+                    mapper: |it| it,
+                    error: |err| InsertMemberError::from(err).into(),
+                }
+            }
+        }
+        impl<'a, C: GenericClient, T1: cornucopia_async::StringSql>
+            cornucopia_async::Params<
+                'a,
+                InsertMemberParams<T1>,
+                I32Query<'a, C, i32, 2, crate::AppError>,
+                C,
+            > for InsertMemberStmt
+        {
+            fn params(
+                &'a mut self,
+                client: &'a C,
+                params: &'a InsertMemberParams<T1>,
+            ) -> I32Query<'a, C, i32, 2, crate::AppError> {
+                self.bind(client, &params.email, &params.age)
+            }
+        }
+        pub fn set_age() -> SetAgeStmt {
+            SetAgeStmt(cornucopia_async::private::Stmt::new(
+                "UPDATE member SET age = $1 WHERE email = $2",
+            ))
+        }
+        pub struct SetAgeStmt(cornucopia_async::private::Stmt);
+        impl SetAgeStmt {
+            #[doc = " Latency budget in milliseconds declared by the `budget` option of the query"]
+            pub const BUDGET_MS: Option<u64> = None;
+            pub async fn bind<'a, C: GenericClient, T1: cornucopia_async::StringSql>(
+                &'a mut self,
+                client: &'a C,
+                age: &'a i32,
+                email: &'a T1,
+            ) -> Result<u64, crate::AppError> {
+                Ok(self.0.execute(client, &[age, email]).await?)
+            }
+        }
+        impl<'a, C: GenericClient + Send + Sync, T1: cornucopia_async::StringSql>
+            cornucopia_async::Params<
+                'a,
+                SetAgeParams<T1>,
+                std::pin::Pin<
+                    Box<dyn futures::Future<Output = Result<u64, crate::AppError>> + Send + 'a>,
+                >,
+                C,
+            > for SetAgeStmt
+        {
+            fn params(
+                &'a mut self,
+                client: &'a C,
+                params: &'a SetAgeParams<T1>,
+            ) -> std::pin::Pin<
+                Box<dyn futures::Future<Output = Result<u64, crate::AppError>> + Send + 'a>,
+            > {
+                Box::pin(self.bind(client, &params.age, &params.email))
+            }
+        }
+        pub fn clear_members() -> ClearMembersStmt {
+            ClearMembersStmt
+        }
+        pub struct ClearMembersStmt;
+        impl ClearMembersStmt {
+            #[doc = " Latency budget in milliseconds declared by the `budget` option of the query"]
+            pub const BUDGET_MS: Option<u64> = None;
+            pub async fn bind<C: GenericClient>(
+                &self,
+                client: &C,
+            ) -> Result<Vec<cornucopia_async::BatchResult>, crate::AppError> {
+                Ok(cornucopia_async::private::batch(
+                    client,
+                    "DELETE FROM member;
+ALTER SEQUENCE member_id_seq RESTART",
+                    &["DELETE", "ALTER"],
+                )
+                .await?)
+            }
+        }
+    }
+}
@@ -0,0 +1,4 @@
+CREATE TABLE book (
+    id SERIAL PRIMARY KEY,
+    title TEXT NOT NULL
+);
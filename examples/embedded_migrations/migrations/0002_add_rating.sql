@@ -0,0 +1,3 @@
+ALTER TABLE book ADD COLUMN rating INT CHECK (rating BETWEEN 1 AND 5);
+
+COMMENT ON COLUMN book.rating IS E'From 1 to 5, "unrated" when NULL\n';
@@ -0,0 +1,5 @@
+--! insert_book (rating?)
+INSERT INTO book (title, rating) VALUES (:title, :rating);
+
+--! books : (rating?)
+SELECT title, rating FROM book ORDER BY id;
@@ -0,0 +1,183 @@
+// This file was generated with `cornucopia`. Do not modify.
+
+#[allow(clippy::all, clippy::pedantic)]
+#[allow(unused_variables)]
+#[allow(unused_imports)]
+#[allow(dead_code)]
+pub mod types {}
+#[allow(clippy::all, clippy::pedantic)]
+#[allow(unused_variables)]
+#[allow(unused_imports)]
+#[allow(dead_code)]
+pub mod queries {
+    pub mod books {
+        #[derive(Debug)]
+        pub struct InsertBookParams<T1: cornucopia_sync::StringSql> {
+            pub title: T1,
+            pub rating: Option<i32>,
+        }
+        #[derive(Debug, Clone, PartialEq, Eq, Hash, PartialOrd, Ord)]
+        pub struct Books {
+            pub title: String,
+            pub rating: Option<i32>,
+        }
+        pub struct BooksBorrowed<'a> {
+            pub title: &'a str,
+            pub rating: Option<i32>,
+        }
+        impl<'a> From<BooksBorrowed<'a>> for Books {
+            fn from(BooksBorrowed { title, rating }: BooksBorrowed<'a>) -> Self {
+                Self {
+                    title: title.into(),
+                    rating,
+                }
+            }
+        }
+        use postgres::{fallible_iterator::FallibleIterator, GenericClient};
+        pub struct BooksQuery<'a, C: GenericClient, T, const N: usize, E = postgres::Error> {
+            client: &'a mut C,
+            params: [&'a (dyn postgres_types::ToSql + Sync); N],
+            stmt: &'a mut cornucopia_sync::private::Stmt,
+            extractor: fn(&postgres::Row) -> Result<BooksBorrowed, postgres::Error>,
+            mapper: fn(BooksBorrowed) -> T,
+            error: fn(postgres::Error) -> E,
+        }
+        impl<'a, C, T: 'a, const N: usize, E: 'a> BooksQuery<'a, C, T, N, E>
+        where
+            C: GenericClient,
+        {
+            pub fn map<R>(self, mapper: fn(BooksBorrowed) -> R) -> BooksQuery<'a, C, R, N, E> {
+                BooksQuery {
+                    client: self.client,
+                    params: self.params,
+                    stmt: self.stmt,
+                    extractor: self.extractor,
+                    mapper,
+                    error: self.error,
+                }
+            }
+            pub fn one(self) -> Result<T, E> {
+                let row = self
+                    .stmt
+                    .query_one(self.client, &self.params)
+                    .map_err(self.error)?;
+                Ok((self.mapper)((self.extractor)(&row).map_err(self.error)?))
+            }
+            pub fn opt(self) -> Result<Option<T>, E> {
+                let row = self
+                    .stmt
+                    .query_opt(self.client, &self.params)
+                    .map_err(self.error)?;
+                row.map(|row| Ok((self.mapper)((self.extractor)(&row).map_err(self.error)?)))
+                    .transpose()
+            }
+            pub fn all(self) -> Result<Vec<T>, E> {
+                self.iter()?.collect()
+            }
+            pub fn iter(self) -> Result<impl Iterator<Item = Result<T, E>> + 'a, E> {
+                let it = self
+                    .stmt
+                    .query_raw(self.client, &self.params)
+                    .map_err(self.error)?
+                    .iterator()
+                    .map(move |res| {
+                        res.and_then(|row| (self.extractor)(&row).map(self.mapper))
+                            .map_err(self.error)
+                    });
+                Ok(it)
+            }
+            #[doc = " Declares a server-side cursor over the rows, fetching them in chunks instead of"]
+            #[doc = " buffering them all. Cursors only live inside a transaction."]
+            pub fn cursor(self) -> Result<cornucopia_sync::Cursor<'a, C, T, E>, E>
+            where
+                C: cornucopia_sync::InTransaction,
+            {
+                let (extractor, mapper) = (self.extractor, self.mapper);
+                self.stmt.cursor(
+                    self.client,
+                    &self.params,
+                    move |row| extractor(row).map(mapper),
+                    self.error,
+                )
+            }
+        }
+        pub fn insert_book() -> InsertBookStmt {
+            InsertBookStmt(cornucopia_sync::private::Stmt::new(
+                "INSERT INTO book (title, rating) VALUES ($1, $2)",
+            ))
+        }
+        pub struct InsertBookStmt(cornucopia_sync::private::Stmt);
+        impl InsertBookStmt {
+            #[doc = " Latency budget in milliseconds declared by the `budget` option of the query"]
+            pub const BUDGET_MS: Option<u64> = None;
+            pub fn bind<'a, C: GenericClient, T1: cornucopia_sync::StringSql>(
+                &'a mut self,
+                client: &'a mut C,
+                title: &'a T1,
+                rating: &'a Option<i32>,
+            ) -> Result<u64, postgres::Error> {
+                self.0.execute(client, &[title, rating])
+            }
+        }
+        impl<'a, C: GenericClient, T1: cornucopia_sync::StringSql>
+            cornucopia_sync::Params<'a, InsertBookParams<T1>, Result<u64, postgres::Error>, C>
+            for InsertBookStmt
+        {
+            fn params(
+                &'a mut self,
+                client: &'a mut C,
+                params: &'a InsertBookParams<T1>,
+            ) -> Result<u64, postgres::Error> {
+                self.bind(client, &params.title, &params.rating)
+            }
+        }
+        pub fn books() -> BooksStmt {
+            BooksStmt(cornucopia_sync::private::Stmt::new(
+                "SELECT title, rating FROM book ORDER BY id",
+            ))
+        }
+        pub struct BooksStmt(cornucopia_sync::private::Stmt);
+        impl BooksStmt {
+            #[doc = " Latency budget in milliseconds declared by the `budget` option of the query"]
+            pub const BUDGET_MS: Option<u64> = None;
+            pub fn bind<'a, C: GenericClient>(
+                &'a mut self,
+                client: &'a mut C,
+            ) -> BooksQuery<'a, C, Books, 0> {
+                BooksQuery {
+                    client,
+                    params: [],
+                    stmt: &mut self.0,
+                    extractor: |row| {
+                        Ok(BooksBorrowed {
+                            title: row.try_get(0)?,
+                            rating: row.try_get(1)?,
+                        })
+                    },
+                    mapper: |it| <Books>::from(it),
+                    error: From::from,
+                }
+            }
+        }
+    }
+}
+#[doc = " Embedded migrations, applied in this order by `cornucopia::run_embedded_migrations`"]
+pub const MIGRATIONS: &[cornucopia_sync::Migration] = &[
+    cornucopia_sync::Migration {
+        name: "0001_create_book",
+        sql: "CREATE TABLE book (
+    id SERIAL PRIMARY KEY,
+    title TEXT NOT NULL
+);
+",
+        checksum: "f486eac7a84e0499",
+    },
+    cornucopia_sync::Migration {
+        name: "0002_add_rating",
+        sql: "ALTER TABLE book ADD COLUMN rating INT CHECK (rating BETWEEN 1 AND 5);
+
+COMMENT ON COLUMN book.rating IS E'From 1 to 5, \"unrated\" when NULL\\n';
+",
+        checksum: "cb0c1988b694ba9c",
+    },
+];
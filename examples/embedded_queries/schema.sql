@@ -0,0 +1,10 @@
+CREATE TABLE author (
+    id SERIAL PRIMARY KEY,
+    name TEXT NOT NULL
+);
+
+CREATE TABLE book (
+    id SERIAL PRIMARY KEY,
+    author_id INT NOT NULL REFERENCES author (id),
+    title TEXT NOT NULL
+);
@@ -0,0 +1,25 @@
+use postgres::GenericClient;
+
+use crate::cornucopia::queries::authors::{author_titles, insert_author, AuthorTitles};
+
+// The queries of this raw string are generated in the `authors` module.
+// cornucopia:query
+#[allow(dead_code)]
+const QUERIES: &str = r#"
+--! insert_author
+INSERT INTO author (name) VALUES (:name) RETURNING id;
+
+--! author_titles : AuthorTitles()
+SELECT author.name, array_agg(book.title ORDER BY book.title) AS titles
+FROM author JOIN book ON book.author_id = author.id
+GROUP BY author.id, author.name
+ORDER BY author.name;
+"#;
+
+pub fn create(client: &mut impl GenericClient, name: &str) -> i32 {
+    insert_author().bind(client, &name).one().unwrap()
+}
+
+pub fn titles(client: &mut impl GenericClient) -> Vec<AuthorTitles> {
+    author_titles().bind(client).all().unwrap()
+}
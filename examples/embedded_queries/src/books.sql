@@ -0,0 +1,5 @@
+--! insert_book
+INSERT INTO book (author_id, title) VALUES (:author_id, :title);
+
+--! books_by_author
+SELECT title FROM book WHERE author_id = :author_id ORDER BY title;
@@ -0,0 +1,354 @@
+// This file was generated with `cornucopia`. Do not modify.
+
+#[allow(clippy::all, clippy::pedantic)]
+#[allow(unused_variables)]
+#[allow(unused_imports)]
+#[allow(dead_code)]
+pub mod types {}
+#[allow(clippy::all, clippy::pedantic)]
+#[allow(unused_variables)]
+#[allow(unused_imports)]
+#[allow(dead_code)]
+pub mod queries {
+    pub mod authors {
+        #[derive(Debug, Clone, PartialEq, Eq, Hash, PartialOrd, Ord)]
+        pub struct AuthorTitles {
+            pub name: String,
+            pub titles: Vec<String>,
+        }
+        pub struct AuthorTitlesBorrowed<'a> {
+            pub name: &'a str,
+            pub titles: cornucopia_sync::ArrayIterator<'a, &'a str>,
+        }
+        impl<'a> From<AuthorTitlesBorrowed<'a>> for AuthorTitles {
+            fn from(AuthorTitlesBorrowed { name, titles }: AuthorTitlesBorrowed<'a>) -> Self {
+                Self {
+                    name: name.into(),
+                    titles: titles.map(|v| v.into()).collect(),
+                }
+            }
+        }
+        use postgres::{fallible_iterator::FallibleIterator, GenericClient};
+        pub struct I32Query<'a, C: GenericClient, T, const N: usize, E = postgres::Error> {
+            client: &'a mut C,
+            params: [&'a (dyn postgres_types::ToSql + Sync); N],
+            stmt: &'a mut cornucopia_sync::private::Stmt,
+            extractor: fn(&postgres::Row) -> Result<i32, postgres::Error>,
+            mapper: fn(i32) -> T,
+            error: fn(postgres::Error) -> E,
+        }
+        impl<'a, C, T: 'a, const N: usize, E: 'a> I32Query<'a, C, T, N, E>
+        where
+            C: GenericClient,
+        {
+            pub fn map<R>(self, mapper: fn(i32) -> R) -> I32Query<'a, C, R, N, E> {
+                I32Query {
+                    client: self.client,
+                    params: self.params,
+                    stmt: self.stmt,
+                    extractor: self.extractor,
+                    mapper,
+                    error: self.error,
+                }
+            }
+            pub fn one(self) -> Result<T, E> {
+                let row = self
+                    .stmt
+                    .query_one(self.client, &self.params)
+                    .map_err(self.error)?;
+                Ok((self.mapper)((self.extractor)(&row).map_err(self.error)?))
+            }
+            pub fn opt(self) -> Result<Option<T>, E> {
+                let row = self
+                    .stmt
+                    .query_opt(self.client, &self.params)
+                    .map_err(self.error)?;
+                row.map(|row| Ok((self.mapper)((self.extractor)(&row).map_err(self.error)?)))
+                    .transpose()
+            }
+            pub fn all(self) -> Result<Vec<T>, E> {
+                self.iter()?.collect()
+            }
+            pub fn iter(self) -> Result<impl Iterator<Item = Result<T, E>> + 'a, E> {
+                let it = self
+                    .stmt
+                    .query_raw(self.client, &self.params)
+                    .map_err(self.error)?
+                    .iterator()
+                    .map(move |res| {
+                        res.and_then(|row| (self.extractor)(&row).map(self.mapper))
+                            .map_err(self.error)
+                    });
+                Ok(it)
+            }
+            #[doc = " Declares a server-side cursor over the rows, fetching them in chunks instead of"]
+            #[doc = " buffering them all. Cursors only live inside a transaction."]
+            pub fn cursor(self) -> Result<cornucopia_sync::Cursor<'a, C, T, E>, E>
+            where
+                C: cornucopia_sync::InTransaction,
+            {
+                let (extractor, mapper) = (self.extractor, self.mapper);
+                self.stmt.cursor(
+                    self.client,
+                    &self.params,
+                    move |row| extractor(row).map(mapper),
+                    self.error,
+                )
+            }
+        }
+        pub struct AuthorTitlesQuery<'a, C: GenericClient, T, const N: usize, E = postgres::Error> {
+            client: &'a mut C,
+            params: [&'a (dyn postgres_types::ToSql + Sync); N],
+            stmt: &'a mut cornucopia_sync::private::Stmt,
+            extractor: fn(&postgres::Row) -> Result<AuthorTitlesBorrowed, postgres::Error>,
+            mapper: fn(AuthorTitlesBorrowed) -> T,
+            error: fn(postgres::Error) -> E,
+        }
+        impl<'a, C, T: 'a, const N: usize, E: 'a> AuthorTitlesQuery<'a, C, T, N, E>
+        where
+            C: GenericClient,
+        {
+            pub fn map<R>(
+                self,
+                mapper: fn(AuthorTitlesBorrowed) -> R,
+            ) -> AuthorTitlesQuery<'a, C, R, N, E> {
+                AuthorTitlesQuery {
+                    client: self.client,
+                    params: self.params,
+                    stmt: self.stmt,
+                    extractor: self.extractor,
+                    mapper,
+                    error: self.error,
+                }
+            }
+            pub fn one(self) -> Result<T, E> {
+                let row = self
+                    .stmt
+                    .query_one(self.client, &self.params)
+                    .map_err(self.error)?;
+                Ok((self.mapper)((self.extractor)(&row).map_err(self.error)?))
+            }
+            pub fn opt(self) -> Result<Option<T>, E> {
+                let row = self
+                    .stmt
+                    .query_opt(self.client, &self.params)
+                    .map_err(self.error)?;
+                row.map(|row| Ok((self.mapper)((self.extractor)(&row).map_err(self.error)?)))
+                    .transpose()
+            }
+            pub fn all(self) -> Result<Vec<T>, E> {
+                self.iter()?.collect()
+            }
+            pub fn iter(self) -> Result<impl Iterator<Item = Result<T, E>> + 'a, E> {
+                let it = self
+                    .stmt
+                    .query_raw(self.client, &self.params)
+                    .map_err(self.error)?
+                    .iterator()
+                    .map(move |res| {
+                        res.and_then(|row| (self.extractor)(&row).map(self.mapper))
+                            .map_err(self.error)
+                    });
+                Ok(it)
+            }
+            #[doc = " Declares a server-side cursor over the rows, fetching them in chunks instead of"]
+            #[doc = " buffering them all. Cursors only live inside a transaction."]
+            pub fn cursor(self) -> Result<cornucopia_sync::Cursor<'a, C, T, E>, E>
+            where
+                C: cornucopia_sync::InTransaction,
+            {
+                let (extractor, mapper) = (self.extractor, self.mapper);
+                self.stmt.cursor(
+                    self.client,
+                    &self.params,
+                    move |row| extractor(row).map(mapper),
+                    self.error,
+                )
+            }
+        }
+        pub fn insert_author() -> InsertAuthorStmt {
+            InsertAuthorStmt(cornucopia_sync::private::Stmt::new(
+                "INSERT INTO author (name) VALUES ($1) RETURNING id",
+            ))
+        }
+        pub struct InsertAuthorStmt(cornucopia_sync::private::Stmt);
+        impl InsertAuthorStmt {
+            #[doc = " Latency budget in milliseconds declared by the `budget` option of the query"]
+            pub const BUDGET_MS: Option<u64> = None;
+            pub fn bind<'a, C: GenericClient, T1: cornucopia_sync::StringSql>(
+                &'a mut self,
+                client: &'a mut C,
+                name: &'a T1,
+            ) -> I32Query<'a, C, i32, 1> {
+                I32Query {
+                    client,
+                    params: [name],
+                    stmt: &mut self.0,
+                    extractor: |row| row.try_get(0),
+                    mapper: |it| it,
+                    error: From::from,
+                }
+            }
+        }
+        pub fn author_titles() -> AuthorTitlesStmt {
+            AuthorTitlesStmt(cornucopia_sync::private::Stmt::new(
+                "SELECT author.name, array_agg(book.title ORDER BY book.title) AS titles
+FROM author JOIN book ON book.author_id = author.id
+GROUP BY author.id, author.name
+ORDER BY author.name",
+            ))
+        }
+        pub struct AuthorTitlesStmt(cornucopia_sync::private::Stmt);
+        impl AuthorTitlesStmt {
+            #[doc = " Latency budget in milliseconds declared by the `budget` option of the query"]
+            pub const BUDGET_MS: Option<u64> = None;
+            pub fn bind<'a, C: GenericClient>(
+                &'a mut self,
+                client: &'a mut C,
+            ) -> AuthorTitlesQuery<'a, C, AuthorTitles, 0> {
+                AuthorTitlesQuery {
+                    client,
+                    params: [],
+                    stmt: &mut self.0,
+                    extractor: |row| {
+                        Ok(AuthorTitlesBorrowed {
+                            name: row.try_get(0)?,
+                            titles: row.try_get(1)?,
+                        })
+                    },
+                    mapper: |it| <AuthorTitles>::from(it),
+                    error: From::from,
+                }
+            }
+        }
+    }
+    pub mod books {
+        #[derive(Debug)]
+        pub struct InsertBookParams<T1: cornucopia_sync::StringSql> {
+            pub author_id: i32,
+            pub title: T1,
+        }
+        use postgres::{fallible_iterator::FallibleIterator, GenericClient};
+        pub struct StringQuery<'a, C: GenericClient, T, const N: usize, E = postgres::Error> {
+            client: &'a mut C,
+            params: [&'a (dyn postgres_types::ToSql + Sync); N],
+            stmt: &'a mut cornucopia_sync::private::Stmt,
+            extractor: fn(&postgres::Row) -> Result<&str, postgres::Error>,
+            mapper: fn(&str) -> T,
+            error: fn(postgres::Error) -> E,
+        }
+        impl<'a, C, T: 'a, const N: usize, E: 'a> StringQuery<'a, C, T, N, E>
+        where
+            C: GenericClient,
+        {
+            pub fn map<R>(self, mapper: fn(&str) -> R) -> StringQuery<'a, C, R, N, E> {
+                StringQuery {
+                    client: self.client,
+                    params: self.params,
+                    stmt: self.stmt,
+                    extractor: self.extractor,
+                    mapper,
+                    error: self.error,
+                }
+            }
+            pub fn one(self) -> Result<T, E> {
+                let row = self
+                    .stmt
+                    .query_one(self.client, &self.params)
+                    .map_err(self.error)?;
+                Ok((self.mapper)((self.extractor)(&row).map_err(self.error)?))
+            }
+            pub fn opt(self) -> Result<Option<T>, E> {
+                let row = self
+                    .stmt
+                    .query_opt(self.client, &self.params)
+                    .map_err(self.error)?;
+                row.map(|row| Ok((self.mapper)((self.extractor)(&row).map_err(self.error)?)))
+                    .transpose()
+            }
+            pub fn all(self) -> Result<Vec<T>, E> {
+                self.iter()?.collect()
+            }
+            pub fn iter(self) -> Result<impl Iterator<Item = Result<T, E>> + 'a, E> {
+                let it = self
+                    .stmt
+                    .query_raw(self.client, &self.params)
+                    .map_err(self.error)?
+                    .iterator()
+                    .map(move |res| {
+                        res.and_then(|row| (self.extractor)(&row).map(self.mapper))
+                            .map_err(self.error)
+                    });
+                Ok(it)
+            }
+            #[doc = " Declares a server-side cursor over the rows, fetching them in chunks instead of"]
+            #[doc = " buffering them all. Cursors only live inside a transaction."]
+            pub fn cursor(self) -> Result<cornucopia_sync::Cursor<'a, C, T, E>, E>
+            where
+                C: cornucopia_sync::InTransaction,
+            {
+                let (extractor, mapper) = (self.extractor, self.mapper);
+                self.stmt.cursor(
+                    self.client,
+                    &self.params,
+                    move |row| extractor(row).map(mapper),
+                    self.error,
+                )
+            }
+        }
+        pub fn insert_book() -> InsertBookStmt {
+            InsertBookStmt(cornucopia_sync::private::Stmt::new(
+                "INSERT INTO book (author_id, title) VALUES ($1, $2)",
+            ))
+        }
+        pub struct InsertBookStmt(cornucopia_sync::private::Stmt);
+        impl InsertBookStmt {
+            #[doc = " Latency budget in milliseconds declared by the `budget` option of the query"]
+            pub const BUDGET_MS: Option<u64> = None;
+            pub fn bind<'a, C: GenericClient, T1: cornucopia_sync::StringSql>(
+                &'a mut self,
+                client: &'a mut C,
+                author_id: &'a i32,
+                title: &'a T1,
+            ) -> Result<u64, postgres::Error> {
+                self.0.execute(client, &[author_id, title])
+            }
+        }
+        impl<'a, C: GenericClient, T1: cornucopia_sync::StringSql>
+            cornucopia_sync::Params<'a, InsertBookParams<T1>, Result<u64, postgres::Error>, C>
+            for InsertBookStmt
+        {
+            fn params(
+                &'a mut self,
+                client: &'a mut C,
+                params: &'a InsertBookParams<T1>,
+            ) -> Result<u64, postgres::Error> {
+                self.bind(client, &params.author_id, &params.title)
+            }
+        }
+        pub fn books_by_author() -> BooksByAuthorStmt {
+            BooksByAuthorStmt(cornucopia_sync::private::Stmt::new(
+                "SELECT title FROM book WHERE author_id = $1 ORDER BY title",
+            ))
+        }
+        pub struct BooksByAuthorStmt(cornucopia_sync::private::Stmt);
+        impl BooksByAuthorStmt {
+            #[doc = " Latency budget in milliseconds declared by the `budget` option of the query"]
+            pub const BUDGET_MS: Option<u64> = None;
+            pub fn bind<'a, C: GenericClient>(
+                &'a mut self,
+                client: &'a mut C,
+                author_id: &'a i32,
+            ) -> StringQuery<'a, C, String, 1> {
+                StringQuery {
+                    client,
+                    params: [author_id],
+                    stmt: &mut self.0,
+                    extractor: |row| row.try_get(0),
+                    mapper: |it| it.into(),
+                    error: From::from,
+                }
+            }
+        }
+    }
+}
@@ -0,0 +1,34 @@
+// Queries are read from `books.sql` and from the raw string embedded in `authors.rs`
+mod authors;
+mod cornucopia;
+
+use postgres::{Config, NoTls};
+
+use crate::cornucopia::queries::books::{books_by_author, insert_book};
+
+pub fn main() {
+    let mut client = get_client().unwrap();
+    let mut transaction = client.transaction().unwrap();
+
+    let id = authors::create(&mut transaction, "Ursula K. Le Guin");
+    for title in ["The Dispossessed", "A Wizard of Earthsea"] {
+        insert_book().bind(&mut transaction, &id, &title).unwrap();
+    }
+
+    let books = books_by_author().bind(&mut transaction, &id).all().unwrap();
+    assert_eq!(books, ["A Wizard of Earthsea", "The Dispossessed"]);
+
+    let titles = authors::titles(&mut transaction);
+    assert_eq!(titles[0].titles, books);
+    dbg!(titles);
+}
+
+fn get_client() -> Result<postgres::Client, postgres::Error> {
+    Config::new()
+        .user("postgres")
+        .password("postgres")
+        .host("127.0.0.1")
+        .port(5435)
+        .dbname("postgres")
+        .connect(NoTls)
+}
@@ -0,0 +1,11 @@
+--! insert_document (draft?)
+INSERT INTO document (title, embedding, draft) VALUES (:title, :embedding, :draft) RETURNING id;
+
+--! document : (draft?)
+SELECT title, embedding, draft FROM document WHERE id = :id;
+
+--! nearest_documents
+SELECT title FROM document ORDER BY embedding <-> :embedding LIMIT :limit;
+
+--! embeddings
+SELECT array_agg(embedding ORDER BY id) FROM document;
@@ -0,0 +1,8 @@
+CREATE EXTENSION IF NOT EXISTS vector;
+
+CREATE TABLE document (
+    id SERIAL PRIMARY KEY,
+    title TEXT NOT NULL,
+    embedding vector(3) NOT NULL,
+    draft vector
+);
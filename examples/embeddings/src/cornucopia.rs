@@ -0,0 +1,539 @@
+// This file was generated with `cornucopia`. Do not modify.
+
+#[allow(clippy::all, clippy::pedantic)]
+#[allow(unused_variables)]
+#[allow(unused_imports)]
+#[allow(dead_code)]
+pub mod types {
+    #[allow(unexpected_cfgs)]
+    #[cfg(not(feature = "pgvector"))]
+    pub use self::vector::Vector;
+    #[allow(unexpected_cfgs)]
+    #[cfg(feature = "pgvector")]
+    pub use pgvector::Vector;
+    #[allow(unexpected_cfgs)]
+    #[cfg(not(feature = "pgvector"))]
+    mod vector {
+        #[doc = " Embedding of the pgvector extension, replaced by `pgvector::Vector` with the `pgvector` feature"]
+        #[derive(Debug, Clone, PartialEq, Default)]
+        pub struct Vector(Vec<f32>);
+        impl Vector {
+            pub fn as_slice(&self) -> &[f32] {
+                &self.0
+            }
+            pub fn to_vec(&self) -> Vec<f32> {
+                self.0.clone()
+            }
+        }
+        impl From<Vec<f32>> for Vector {
+            fn from(values: Vec<f32>) -> Self {
+                Self(values)
+            }
+        }
+        impl From<Vector> for Vec<f32> {
+            fn from(vector: Vector) -> Self {
+                vector.0
+            }
+        }
+        impl<'a> postgres_types::FromSql<'a> for Vector {
+            fn from_sql(
+                _ty: &postgres_types::Type,
+                raw: &'a [u8],
+            ) -> Result<Self, Box<dyn std::error::Error + Sync + Send>> {
+                let (Some(dim), Some(values)) = (raw.get(..2), raw.get(4..)) else {
+                    return Err("invalid vector header".into());
+                };
+                if values.len() != usize::from(u16::from_be_bytes([dim[0], dim[1]])) * 4 {
+                    return Err("invalid vector length".into());
+                }
+                Ok(Self(
+                    values
+                        .chunks_exact(4)
+                        .map(|it| f32::from_be_bytes([it[0], it[1], it[2], it[3]]))
+                        .collect(),
+                ))
+            }
+            fn accepts(ty: &postgres_types::Type) -> bool {
+                ty.name() == "vector"
+            }
+        }
+        impl postgres_types::ToSql for Vector {
+            fn to_sql(
+                &self,
+                _ty: &postgres_types::Type,
+                out: &mut postgres_types::private::BytesMut,
+            ) -> Result<postgres_types::IsNull, Box<dyn std::error::Error + Sync + Send>>
+            {
+                let dim =
+                    u16::try_from(self.0.len()).map_err(|_| "vector has too many dimensions")?;
+                out.extend_from_slice(&dim.to_be_bytes());
+                out.extend_from_slice(&0u16.to_be_bytes());
+                for value in &self.0 {
+                    out.extend_from_slice(&value.to_be_bytes());
+                }
+                Ok(postgres_types::IsNull::No)
+            }
+            fn accepts(ty: &postgres_types::Type) -> bool {
+                ty.name() == "vector"
+            }
+            postgres_types::to_sql_checked!();
+        }
+    }
+}
+#[allow(clippy::all, clippy::pedantic)]
+#[allow(unused_variables)]
+#[allow(unused_imports)]
+#[allow(dead_code)]
+pub mod queries {
+    pub mod documents {
+        #[derive(Debug)]
+        pub struct InsertDocumentParams<T1: cornucopia_sync::StringSql> {
+            pub title: T1,
+            pub embedding: super::super::types::Vector,
+            pub draft: Option<super::super::types::Vector>,
+        }
+        #[derive(Debug)]
+        pub struct NearestDocumentsParams {
+            pub embedding: super::super::types::Vector,
+            pub limit: i64,
+        }
+        #[derive(Debug, Clone, PartialEq)]
+        pub struct Document {
+            pub title: String,
+            pub embedding: super::super::types::Vector,
+            pub draft: Option<super::super::types::Vector>,
+        }
+        impl Document {
+            #[doc = " Number of dimensions of the `embedding` column"]
+            pub const EMBEDDING_DIMENSIONS: usize = 3;
+        }
+        pub struct DocumentBorrowed<'a> {
+            pub title: &'a str,
+            pub embedding: super::super::types::Vector,
+            pub draft: Option<super::super::types::Vector>,
+        }
+        impl<'a> From<DocumentBorrowed<'a>> for Document {
+            fn from(
+                DocumentBorrowed {
+                    title,
+                    embedding,
+                    draft,
+                }: DocumentBorrowed<'a>,
+            ) -> Self {
+                Self {
+                    title: title.into(),
+                    embedding: embedding.into(),
+                    draft: draft.map(|v| v.into()),
+                }
+            }
+        }
+        use postgres::{fallible_iterator::FallibleIterator, GenericClient};
+        pub struct I32Query<'a, C: GenericClient, T, const N: usize, E = postgres::Error> {
+            client: &'a mut C,
+            params: [&'a (dyn postgres_types::ToSql + Sync); N],
+            stmt: &'a mut cornucopia_sync::private::Stmt,
+            extractor: fn(&postgres::Row) -> Result<i32, postgres::Error>,
+            mapper: fn(i32) -> T,
+            error: fn(postgres::Error) -> E,
+        }
+        impl<'a, C, T: 'a, const N: usize, E: 'a> I32Query<'a, C, T, N, E>
+        where
+            C: GenericClient,
+        {
+            pub fn map<R>(self, mapper: fn(i32) -> R) -> I32Query<'a, C, R, N, E> {
+                I32Query {
+                    client: self.client,
+                    params: self.params,
+                    stmt: self.stmt,
+                    extractor: self.extractor,
+                    mapper,
+                    error: self.error,
+                }
+            }
+            pub fn one(self) -> Result<T, E> {
+                let row = self
+                    .stmt
+                    .query_one(self.client, &self.params)
+                    .map_err(self.error)?;
+                Ok((self.mapper)((self.extractor)(&row).map_err(self.error)?))
+            }
+            pub fn opt(self) -> Result<Option<T>, E> {
+                let row = self
+                    .stmt
+                    .query_opt(self.client, &self.params)
+                    .map_err(self.error)?;
+                row.map(|row| Ok((self.mapper)((self.extractor)(&row).map_err(self.error)?)))
+                    .transpose()
+            }
+            pub fn all(self) -> Result<Vec<T>, E> {
+                self.iter()?.collect()
+            }
+            pub fn iter(self) -> Result<impl Iterator<Item = Result<T, E>> + 'a, E> {
+                let it = self
+                    .stmt
+                    .query_raw(self.client, &self.params)
+                    .map_err(self.error)?
+                    .iterator()
+                    .map(move |res| {
+                        res.and_then(|row| (self.extractor)(&row).map(self.mapper))
+                            .map_err(self.error)
+                    });
+                Ok(it)
+            }
+            #[doc = " Declares a server-side cursor over the rows, fetching them in chunks instead of"]
+            #[doc = " buffering them all. Cursors only live inside a transaction."]
+            pub fn cursor(self) -> Result<cornucopia_sync::Cursor<'a, C, T, E>, E>
+            where
+                C: cornucopia_sync::InTransaction,
+            {
+                let (extractor, mapper) = (self.extractor, self.mapper);
+                self.stmt.cursor(
+                    self.client,
+                    &self.params,
+                    move |row| extractor(row).map(mapper),
+                    self.error,
+                )
+            }
+        }
+        pub struct DocumentQuery<'a, C: GenericClient, T, const N: usize, E = postgres::Error> {
+            client: &'a mut C,
+            params: [&'a (dyn postgres_types::ToSql + Sync); N],
+            stmt: &'a mut cornucopia_sync::private::Stmt,
+            extractor: fn(&postgres::Row) -> Result<DocumentBorrowed, postgres::Error>,
+            mapper: fn(DocumentBorrowed) -> T,
+            error: fn(postgres::Error) -> E,
+        }
+        impl<'a, C, T: 'a, const N: usize, E: 'a> DocumentQuery<'a, C, T, N, E>
+        where
+            C: GenericClient,
+        {
+            pub fn map<R>(
+                self,
+                mapper: fn(DocumentBorrowed) -> R,
+            ) -> DocumentQuery<'a, C, R, N, E> {
+                DocumentQuery {
+                    client: self.client,
+                    params: self.params,
+                    stmt: self.stmt,
+                    extractor: self.extractor,
+                    mapper,
+                    error: self.error,
+                }
+            }
+            pub fn one(self) -> Result<T, E> {
+                let row = self
+                    .stmt
+                    .query_one(self.client, &self.params)
+                    .map_err(self.error)?;
+                Ok((self.mapper)((self.extractor)(&row).map_err(self.error)?))
+            }
+            pub fn opt(self) -> Result<Option<T>, E> {
+                let row = self
+                    .stmt
+                    .query_opt(self.client, &self.params)
+                    .map_err(self.error)?;
+                row.map(|row| Ok((self.mapper)((self.extractor)(&row).map_err(self.error)?)))
+                    .transpose()
+            }
+            pub fn all(self) -> Result<Vec<T>, E> {
+                self.iter()?.collect()
+            }
+            pub fn iter(self) -> Result<impl Iterator<Item = Result<T, E>> + 'a, E> {
+                let it = self
+                    .stmt
+                    .query_raw(self.client, &self.params)
+                    .map_err(self.error)?
+                    .iterator()
+                    .map(move |res| {
+                        res.and_then(|row| (self.extractor)(&row).map(self.mapper))
+                            .map_err(self.error)
+                    });
+                Ok(it)
+            }
+            #[doc = " Declares a server-side cursor over the rows, fetching them in chunks instead of"]
+            #[doc = " buffering them all. Cursors only live inside a transaction."]
+            pub fn cursor(self) -> Result<cornucopia_sync::Cursor<'a, C, T, E>, E>
+            where
+                C: cornucopia_sync::InTransaction,
+            {
+                let (extractor, mapper) = (self.extractor, self.mapper);
+                self.stmt.cursor(
+                    self.client,
+                    &self.params,
+                    move |row| extractor(row).map(mapper),
+                    self.error,
+                )
+            }
+        }
+        pub struct StringQuery<'a, C: GenericClient, T, const N: usize, E = postgres::Error> {
+            client: &'a mut C,
+            params: [&'a (dyn postgres_types::ToSql + Sync); N],
+            stmt: &'a mut cornucopia_sync::private::Stmt,
+            extractor: fn(&postgres::Row) -> Result<&str, postgres::Error>,
+            mapper: fn(&str) -> T,
+            error: fn(postgres::Error) -> E,
+        }
+        impl<'a, C, T: 'a, const N: usize, E: 'a> StringQuery<'a, C, T, N, E>
+        where
+            C: GenericClient,
+        {
+            pub fn map<R>(self, mapper: fn(&str) -> R) -> StringQuery<'a, C, R, N, E> {
+                StringQuery {
+                    client: self.client,
+                    params: self.params,
+                    stmt: self.stmt,
+                    extractor: self.extractor,
+                    mapper,
+                    error: self.error,
+                }
+            }
+            pub fn one(self) -> Result<T, E> {
+                let row = self
+                    .stmt
+                    .query_one(self.client, &self.params)
+                    .map_err(self.error)?;
+                Ok((self.mapper)((self.extractor)(&row).map_err(self.error)?))
+            }
+            pub fn opt(self) -> Result<Option<T>, E> {
+                let row = self
+                    .stmt
+                    .query_opt(self.client, &self.params)
+                    .map_err(self.error)?;
+                row.map(|row| Ok((self.mapper)((self.extractor)(&row).map_err(self.error)?)))
+                    .transpose()
+            }
+            pub fn all(self) -> Result<Vec<T>, E> {
+                self.iter()?.collect()
+            }
+            pub fn iter(self) -> Result<impl Iterator<Item = Result<T, E>> + 'a, E> {
+                let it = self
+                    .stmt
+                    .query_raw(self.client, &self.params)
+                    .map_err(self.error)?
+                    .iterator()
+                    .map(move |res| {
+                        res.and_then(|row| (self.extractor)(&row).map(self.mapper))
+                            .map_err(self.error)
+                    });
+                Ok(it)
+            }
+            #[doc = " Declares a server-side cursor over the rows, fetching them in chunks instead of"]
+            #[doc = " buffering them all. Cursors only live inside a transaction."]
+            pub fn cursor(self) -> Result<cornucopia_sync::Cursor<'a, C, T, E>, E>
+            where
+                C: cornucopia_sync::InTransaction,
+            {
+                let (extractor, mapper) = (self.extractor, self.mapper);
+                self.stmt.cursor(
+                    self.client,
+                    &self.params,
+                    move |row| extractor(row).map(mapper),
+                    self.error,
+                )
+            }
+        }
+        pub struct VecVectorQuery<'a, C: GenericClient, T, const N: usize, E = postgres::Error> {
+            client: &'a mut C,
+            params: [&'a (dyn postgres_types::ToSql + Sync); N],
+            stmt: &'a mut cornucopia_sync::private::Stmt,
+            extractor: fn(
+                &postgres::Row,
+            ) -> Result<
+                cornucopia_sync::ArrayIterator<'_, super::super::types::Vector>,
+                postgres::Error,
+            >,
+            mapper: fn(cornucopia_sync::ArrayIterator<'_, super::super::types::Vector>) -> T,
+            error: fn(postgres::Error) -> E,
+        }
+        impl<'a, C, T: 'a, const N: usize, E: 'a> VecVectorQuery<'a, C, T, N, E>
+        where
+            C: GenericClient,
+        {
+            pub fn map<R>(
+                self,
+                mapper: fn(cornucopia_sync::ArrayIterator<'_, super::super::types::Vector>) -> R,
+            ) -> VecVectorQuery<'a, C, R, N, E> {
+                VecVectorQuery {
+                    client: self.client,
+                    params: self.params,
+                    stmt: self.stmt,
+                    extractor: self.extractor,
+                    mapper,
+                    error: self.error,
+                }
+            }
+            pub fn one(self) -> Result<T, E> {
+                let row = self
+                    .stmt
+                    .query_one(self.client, &self.params)
+                    .map_err(self.error)?;
+                Ok((self.mapper)((self.extractor)(&row).map_err(self.error)?))
+            }
+            pub fn opt(self) -> Result<Option<T>, E> {
+                let row = self
+                    .stmt
+                    .query_opt(self.client, &self.params)
+                    .map_err(self.error)?;
+                row.map(|row| Ok((self.mapper)((self.extractor)(&row).map_err(self.error)?)))
+                    .transpose()
+            }
+            pub fn all(self) -> Result<Vec<T>, E> {
+                self.iter()?.collect()
+            }
+            pub fn iter(self) -> Result<impl Iterator<Item = Result<T, E>> + 'a, E> {
+                let it = self
+                    .stmt
+                    .query_raw(self.client, &self.params)
+                    .map_err(self.error)?
+                    .iterator()
+                    .map(move |res| {
+                        res.and_then(|row| (self.extractor)(&row).map(self.mapper))
+                            .map_err(self.error)
+                    });
+                Ok(it)
+            }
+            #[doc = " Declares a server-side cursor over the rows, fetching them in chunks instead of"]
+            #[doc = " buffering them all. Cursors only live inside a transaction."]
+            pub fn cursor(self) -> Result<cornucopia_sync::Cursor<'a, C, T, E>, E>
+            where
+                C: cornucopia_sync::InTransaction,
+            {
+                let (extractor, mapper) = (self.extractor, self.mapper);
+                self.stmt.cursor(
+                    self.client,
+                    &self.params,
+                    move |row| extractor(row).map(mapper),
+                    self.error,
+                )
+            }
+        }
+        pub fn insert_document() -> InsertDocumentStmt {
+            InsertDocumentStmt(cornucopia_sync::private::Stmt::new(
+                "INSERT INTO document (title, embedding, draft) VALUES ($1, $2, $3) RETURNING id",
+            ))
+        }
+        pub struct InsertDocumentStmt(cornucopia_sync::private::Stmt);
+        impl InsertDocumentStmt {
+            #[doc = " Latency budget in milliseconds declared by the `budget` option of the query"]
+            pub const BUDGET_MS: Option<u64> = None;
+            pub fn bind<'a, C: GenericClient, T1: cornucopia_sync::StringSql>(
+                &'a mut self,
+                client: &'a mut C,
+                title: &'a T1,
+                embedding: &'a super::super::types::Vector,
+                draft: &'a Option<super::super::types::Vector>,
+            ) -> I32Query<'a, C, i32, 3> {
+                I32Query {
+                    client,
+                    params: [title, embedding, draft],
+                    stmt: &mut self.0,
+                    extractor: |row| row.try_get(0),
+                    mapper: |it| it,
+                    error: From::from,
+                }
+            }
+        }
+        impl<'a, C: GenericClient, T1: cornucopia_sync::StringSql>
+            cornucopia_sync::Params<'a, InsertDocumentParams<T1>, I32Query<'a, C, i32, 3>, C>
+            for InsertDocumentStmt
+        {
+            fn params(
+                &'a mut self,
+                client: &'a mut C,
+                params: &'a InsertDocumentParams<T1>,
+            ) -> I32Query<'a, C, i32, 3> {
+                self.bind(client, &params.title, &params.embedding, &params.draft)
+            }
+        }
+        pub fn document() -> DocumentStmt {
+            DocumentStmt(cornucopia_sync::private::Stmt::new(
+                "SELECT title, embedding, draft FROM document WHERE id = $1",
+            ))
+        }
+        pub struct DocumentStmt(cornucopia_sync::private::Stmt);
+        impl DocumentStmt {
+            #[doc = " Latency budget in milliseconds declared by the `budget` option of the query"]
+            pub const BUDGET_MS: Option<u64> = None;
+            pub fn bind<'a, C: GenericClient>(
+                &'a mut self,
+                client: &'a mut C,
+                id: &'a i32,
+            ) -> DocumentQuery<'a, C, Document, 1> {
+                DocumentQuery {
+                    client,
+                    params: [id],
+                    stmt: &mut self.0,
+                    extractor: |row| {
+                        Ok(DocumentBorrowed {
+                            title: row.try_get(0)?,
+                            embedding: row.try_get(1)?,
+                            draft: row.try_get(2)?,
+                        })
+                    },
+                    mapper: |it| <Document>::from(it),
+                    error: From::from,
+                }
+            }
+        }
+        pub fn nearest_documents() -> NearestDocumentsStmt {
+            NearestDocumentsStmt(cornucopia_sync::private::Stmt::new(
+                "SELECT title FROM document ORDER BY embedding <-> $1 LIMIT $2::int8",
+            ))
+        }
+        pub struct NearestDocumentsStmt(cornucopia_sync::private::Stmt);
+        impl NearestDocumentsStmt {
+            #[doc = " Latency budget in milliseconds declared by the `budget` option of the query"]
+            pub const BUDGET_MS: Option<u64> = None;
+            pub fn bind<'a, C: GenericClient>(
+                &'a mut self,
+                client: &'a mut C,
+                embedding: &'a super::super::types::Vector,
+                limit: &'a i64,
+            ) -> StringQuery<'a, C, String, 2> {
+                StringQuery {
+                    client,
+                    params: [embedding, limit],
+                    stmt: &mut self.0,
+                    extractor: |row| row.try_get(0),
+                    mapper: |it| it.into(),
+                    error: From::from,
+                }
+            }
+        }
+        impl<'a, C: GenericClient>
+            cornucopia_sync::Params<'a, NearestDocumentsParams, StringQuery<'a, C, String, 2>, C>
+            for NearestDocumentsStmt
+        {
+            fn params(
+                &'a mut self,
+                client: &'a mut C,
+                params: &'a NearestDocumentsParams,
+            ) -> StringQuery<'a, C, String, 2> {
+                self.bind(client, &params.embedding, &params.limit)
+            }
+        }
+        pub fn embeddings() -> EmbeddingsStmt {
+            EmbeddingsStmt(cornucopia_sync::private::Stmt::new(
+                "SELECT array_agg(embedding ORDER BY id) FROM document",
+            ))
+        }
+        pub struct EmbeddingsStmt(cornucopia_sync::private::Stmt);
+        impl EmbeddingsStmt {
+            #[doc = " Latency budget in milliseconds declared by the `budget` option of the query"]
+            pub const BUDGET_MS: Option<u64> = None;
+            pub fn bind<'a, C: GenericClient>(
+                &'a mut self,
+                client: &'a mut C,
+            ) -> VecVectorQuery<'a, C, Vec<super::super::types::Vector>, 0> {
+                VecVectorQuery {
+                    client,
+                    params: [],
+                    stmt: &mut self.0,
+                    extractor: |row| row.try_get(0),
+                    mapper: |it| it.map(|v| v.into()).collect(),
+                    error: From::from,
+                }
+            }
+        }
+    }
+}
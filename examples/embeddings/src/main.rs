@@ -0,0 +1,80 @@
+// Take a look at the generated `cornucopia.rs` file if you want to
+// see what it looks like under the hood.
+mod cornucopia;
+
+use postgres::{Config, NoTls};
+
+use crate::cornucopia::{
+    queries::documents::{document, embeddings, insert_document, nearest_documents, Document},
+    types::Vector,
+};
+
+pub fn main() {
+    let mut client = get_client().unwrap();
+    let mut transaction = client.transaction().unwrap();
+
+    // Embeddings are built from their values, using `pgvector::Vector` with the `pgvector` feature
+    let documents = [
+        ("Cats", vec![1.0, 0.0, 0.0]),
+        ("Dogs", vec![0.9, 0.1, 0.0]),
+        ("Planets", vec![0.0, 0.0, 1.0]),
+    ];
+    let mut ids = Vec::new();
+    for (title, values) in documents {
+        let embedding = Vector::from(values);
+        let id = insert_document()
+            .bind(&mut transaction, &title, &embedding, &None)
+            .one()
+            .unwrap();
+        ids.push(id);
+    }
+
+    // Embeddings round-trip through the binary format of the extension
+    let draft = Vector::from(vec![-1.5, f32::MAX, f32::MIN_POSITIVE, 0.25]);
+    let id = insert_document()
+        .bind(
+            &mut transaction,
+            &"Draft",
+            &Vector::from(vec![0.0, 1.0, 0.0]),
+            &Some(draft.clone()),
+        )
+        .one()
+        .unwrap();
+    let row = document().bind(&mut transaction, &id).one().unwrap();
+    assert_eq!(
+        row,
+        Document {
+            title: "Draft".to_string(),
+            embedding: Vector::from(vec![0.0, 1.0, 0.0]),
+            draft: Some(draft),
+        }
+    );
+    assert_eq!(row.embedding.to_vec(), [0.0, 1.0, 0.0]);
+
+    // The dimensions of `vector(n)` columns are known, unlike those of `vector` ones
+    assert_eq!(Document::EMBEDDING_DIMENSIONS, 3);
+
+    // Parameters compared using the distance operators are bound as embeddings
+    let query = Vector::from(vec![1.0, 0.05, 0.0]);
+    let nearest = nearest_documents()
+        .bind(&mut transaction, &query, &2)
+        .all()
+        .unwrap();
+    assert_eq!(nearest, ["Cats", "Dogs"]);
+
+    // Arrays of embeddings are read like arrays of any other type
+    let all = embeddings().bind(&mut transaction).one().unwrap();
+    assert_eq!(all.len(), ids.len() + 1);
+    assert_eq!(all[0].as_slice(), [1.0, 0.0, 0.0]);
+    dbg!(nearest);
+}
+
+fn get_client() -> Result<postgres::Client, postgres::Error> {
+    Config::new()
+        .user("postgres")
+        .password("postgres")
+        .host("127.0.0.1")
+        .port(5435)
+        .dbname("postgres")
+        .connect(NoTls)
+}
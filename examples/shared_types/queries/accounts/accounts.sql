@@ -0,0 +1,5 @@
+--! insert_account
+INSERT INTO account (name, role) VALUES (:name, :role) RETURNING id;
+
+--! accounts_by_role
+SELECT id, name FROM account WHERE role = :role ORDER BY id;
@@ -0,0 +1,6 @@
+--! insert_invoice
+INSERT INTO invoice (account_id, billing_address, approved_by)
+VALUES (:account_id, :billing_address, :approved_by);
+
+--! invoices
+SELECT * FROM invoice ORDER BY id;
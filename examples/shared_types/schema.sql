@@ -0,0 +1,22 @@
+CREATE TYPE role AS ENUM ('member', 'admin');
+
+CREATE TYPE address AS (
+    street TEXT,
+    city TEXT
+);
+
+-- Not used by any query, but still shared
+CREATE TYPE currency AS ENUM ('eur', 'usd');
+
+CREATE TABLE account (
+    id SERIAL PRIMARY KEY,
+    name TEXT NOT NULL,
+    role role NOT NULL
+);
+
+CREATE TABLE invoice (
+    id SERIAL PRIMARY KEY,
+    account_id INT NOT NULL REFERENCES account (id),
+    billing_address address NOT NULL,
+    approved_by role NOT NULL
+);
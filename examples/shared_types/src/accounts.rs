@@ -0,0 +1,241 @@
+// This file was generated with `cornucopia`. Do not modify.
+
+#[allow(clippy::all, clippy::pedantic)]
+#[allow(unused_variables)]
+#[allow(unused_imports)]
+#[allow(dead_code)]
+pub mod types {}
+#[allow(clippy::all, clippy::pedantic)]
+#[allow(unused_variables)]
+#[allow(unused_imports)]
+#[allow(dead_code)]
+pub mod queries {
+    pub mod accounts {
+        #[derive(Debug)]
+        pub struct InsertAccountParams<T1: cornucopia_sync::StringSql> {
+            pub name: T1,
+            pub role: crate::db_types::public::Role,
+        }
+        #[derive(Debug, Clone, PartialEq, Eq, Hash, PartialOrd, Ord)]
+        pub struct AccountsByRole {
+            pub id: i32,
+            pub name: String,
+        }
+        pub struct AccountsByRoleBorrowed<'a> {
+            pub id: i32,
+            pub name: &'a str,
+        }
+        impl<'a> From<AccountsByRoleBorrowed<'a>> for AccountsByRole {
+            fn from(AccountsByRoleBorrowed { id, name }: AccountsByRoleBorrowed<'a>) -> Self {
+                Self {
+                    id,
+                    name: name.into(),
+                }
+            }
+        }
+        use postgres::{fallible_iterator::FallibleIterator, GenericClient};
+        pub struct I32Query<'a, C: GenericClient, T, const N: usize, E = postgres::Error> {
+            client: &'a mut C,
+            params: [&'a (dyn postgres_types::ToSql + Sync); N],
+            stmt: &'a mut cornucopia_sync::private::Stmt,
+            extractor: fn(&postgres::Row) -> Result<i32, postgres::Error>,
+            mapper: fn(i32) -> T,
+            error: fn(postgres::Error) -> E,
+        }
+        impl<'a, C, T: 'a, const N: usize, E: 'a> I32Query<'a, C, T, N, E>
+        where
+            C: GenericClient,
+        {
+            pub fn map<R>(self, mapper: fn(i32) -> R) -> I32Query<'a, C, R, N, E> {
+                I32Query {
+                    client: self.client,
+                    params: self.params,
+                    stmt: self.stmt,
+                    extractor: self.extractor,
+                    mapper,
+                    error: self.error,
+                }
+            }
+            pub fn one(self) -> Result<T, E> {
+                let row = self
+                    .stmt
+                    .query_one(self.client, &self.params)
+                    .map_err(self.error)?;
+                Ok((self.mapper)((self.extractor)(&row).map_err(self.error)?))
+            }
+            pub fn opt(self) -> Result<Option<T>, E> {
+                let row = self
+                    .stmt
+                    .query_opt(self.client, &self.params)
+                    .map_err(self.error)?;
+                row.map(|row| Ok((self.mapper)((self.extractor)(&row).map_err(self.error)?)))
+                    .transpose()
+            }
+            pub fn all(self) -> Result<Vec<T>, E> {
+                self.iter()?.collect()
+            }
+            pub fn iter(self) -> Result<impl Iterator<Item = Result<T, E>> + 'a, E> {
+                let it = self
+                    .stmt
+                    .query_raw(self.client, &self.params)
+                    .map_err(self.error)?
+                    .iterator()
+                    .map(move |res| {
+                        res.and_then(|row| (self.extractor)(&row).map(self.mapper))
+                            .map_err(self.error)
+                    });
+                Ok(it)
+            }
+            #[doc = " Declares a server-side cursor over the rows, fetching them in chunks instead of"]
+            #[doc = " buffering them all. Cursors only live inside a transaction."]
+            pub fn cursor(self) -> Result<cornucopia_sync::Cursor<'a, C, T, E>, E>
+            where
+                C: cornucopia_sync::InTransaction,
+            {
+                let (extractor, mapper) = (self.extractor, self.mapper);
+                self.stmt.cursor(
+                    self.client,
+                    &self.params,
+                    move |row| extractor(row).map(mapper),
+                    self.error,
+                )
+            }
+        }
+        pub struct AccountsByRoleQuery<'a, C: GenericClient, T, const N: usize, E = postgres::Error> {
+            client: &'a mut C,
+            params: [&'a (dyn postgres_types::ToSql + Sync); N],
+            stmt: &'a mut cornucopia_sync::private::Stmt,
+            extractor: fn(&postgres::Row) -> Result<AccountsByRoleBorrowed, postgres::Error>,
+            mapper: fn(AccountsByRoleBorrowed) -> T,
+            error: fn(postgres::Error) -> E,
+        }
+        impl<'a, C, T: 'a, const N: usize, E: 'a> AccountsByRoleQuery<'a, C, T, N, E>
+        where
+            C: GenericClient,
+        {
+            pub fn map<R>(
+                self,
+                mapper: fn(AccountsByRoleBorrowed) -> R,
+            ) -> AccountsByRoleQuery<'a, C, R, N, E> {
+                AccountsByRoleQuery {
+                    client: self.client,
+                    params: self.params,
+                    stmt: self.stmt,
+                    extractor: self.extractor,
+                    mapper,
+                    error: self.error,
+                }
+            }
+            pub fn one(self) -> Result<T, E> {
+                let row = self
+                    .stmt
+                    .query_one(self.client, &self.params)
+                    .map_err(self.error)?;
+                Ok((self.mapper)((self.extractor)(&row).map_err(self.error)?))
+            }
+            pub fn opt(self) -> Result<Option<T>, E> {
+                let row = self
+                    .stmt
+                    .query_opt(self.client, &self.params)
+                    .map_err(self.error)?;
+                row.map(|row| Ok((self.mapper)((self.extractor)(&row).map_err(self.error)?)))
+                    .transpose()
+            }
+            pub fn all(self) -> Result<Vec<T>, E> {
+                self.iter()?.collect()
+            }
+            pub fn iter(self) -> Result<impl Iterator<Item = Result<T, E>> + 'a, E> {
+                let it = self
+                    .stmt
+                    .query_raw(self.client, &self.params)
+                    .map_err(self.error)?
+                    .iterator()
+                    .map(move |res| {
+                        res.and_then(|row| (self.extractor)(&row).map(self.mapper))
+                            .map_err(self.error)
+                    });
+                Ok(it)
+            }
+            #[doc = " Declares a server-side cursor over the rows, fetching them in chunks instead of"]
+            #[doc = " buffering them all. Cursors only live inside a transaction."]
+            pub fn cursor(self) -> Result<cornucopia_sync::Cursor<'a, C, T, E>, E>
+            where
+                C: cornucopia_sync::InTransaction,
+            {
+                let (extractor, mapper) = (self.extractor, self.mapper);
+                self.stmt.cursor(
+                    self.client,
+                    &self.params,
+                    move |row| extractor(row).map(mapper),
+                    self.error,
+                )
+            }
+        }
+        pub fn insert_account() -> InsertAccountStmt {
+            InsertAccountStmt(cornucopia_sync::private::Stmt::new(
+                "INSERT INTO account (name, role) VALUES ($1, $2) RETURNING id",
+            ))
+        }
+        pub struct InsertAccountStmt(cornucopia_sync::private::Stmt);
+        impl InsertAccountStmt {
+            #[doc = " Latency budget in milliseconds declared by the `budget` option of the query"]
+            pub const BUDGET_MS: Option<u64> = None;
+            pub fn bind<'a, C: GenericClient, T1: cornucopia_sync::StringSql>(
+                &'a mut self,
+                client: &'a mut C,
+                name: &'a T1,
+                role: &'a crate::db_types::public::Role,
+            ) -> I32Query<'a, C, i32, 2> {
+                I32Query {
+                    client,
+                    params: [name, role],
+                    stmt: &mut self.0,
+                    extractor: |row| row.try_get(0),
+                    mapper: |it| it,
+                    error: From::from,
+                }
+            }
+        }
+        impl<'a, C: GenericClient, T1: cornucopia_sync::StringSql>
+            cornucopia_sync::Params<'a, InsertAccountParams<T1>, I32Query<'a, C, i32, 2>, C>
+            for InsertAccountStmt
+        {
+            fn params(
+                &'a mut self,
+                client: &'a mut C,
+                params: &'a InsertAccountParams<T1>,
+            ) -> I32Query<'a, C, i32, 2> {
+                self.bind(client, &params.name, &params.role)
+            }
+        }
+        pub fn accounts_by_role() -> AccountsByRoleStmt {
+            AccountsByRoleStmt(cornucopia_sync::private::Stmt::new(
+                "SELECT id, name FROM account WHERE role = $1 ORDER BY id",
+            ))
+        }
+        pub struct AccountsByRoleStmt(cornucopia_sync::private::Stmt);
+        impl AccountsByRoleStmt {
+            #[doc = " Latency budget in milliseconds declared by the `budget` option of the query"]
+            pub const BUDGET_MS: Option<u64> = None;
+            pub fn bind<'a, C: GenericClient>(
+                &'a mut self,
+                client: &'a mut C,
+                role: &'a crate::db_types::public::Role,
+            ) -> AccountsByRoleQuery<'a, C, AccountsByRole, 1> {
+                AccountsByRoleQuery {
+                    client,
+                    params: [role],
+                    stmt: &mut self.0,
+                    extractor: |row| {
+                        Ok(AccountsByRoleBorrowed {
+                            id: row.try_get(0)?,
+                            name: row.try_get(1)?,
+                        })
+                    },
+                    mapper: |it| <AccountsByRole>::from(it),
+                    error: From::from,
+                }
+            }
+        }
+    }
+}
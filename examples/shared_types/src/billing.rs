@@ -0,0 +1,190 @@
+// This file was generated with `cornucopia`. Do not modify.
+
+#[allow(clippy::all, clippy::pedantic)]
+#[allow(unused_variables)]
+#[allow(unused_imports)]
+#[allow(dead_code)]
+pub mod types {}
+#[allow(clippy::all, clippy::pedantic)]
+#[allow(unused_variables)]
+#[allow(unused_imports)]
+#[allow(dead_code)]
+pub mod queries {
+    pub mod invoices {
+        #[derive(Debug)]
+        pub struct InsertInvoiceParams<'a> {
+            pub account_id: i32,
+            pub billing_address: crate::db_types::public::AddressBorrowed<'a>,
+            pub approved_by: crate::db_types::public::Role,
+        }
+        #[derive(Debug, Clone, PartialEq)]
+        pub struct Invoices {
+            pub id: i32,
+            pub account_id: i32,
+            pub billing_address: crate::db_types::public::Address,
+            pub approved_by: crate::db_types::public::Role,
+        }
+        pub struct InvoicesBorrowed<'a> {
+            pub id: i32,
+            pub account_id: i32,
+            pub billing_address: crate::db_types::public::AddressBorrowed<'a>,
+            pub approved_by: crate::db_types::public::Role,
+        }
+        impl<'a> From<InvoicesBorrowed<'a>> for Invoices {
+            fn from(
+                InvoicesBorrowed {
+                    id,
+                    account_id,
+                    billing_address,
+                    approved_by,
+                }: InvoicesBorrowed<'a>,
+            ) -> Self {
+                Self {
+                    id,
+                    account_id,
+                    billing_address: billing_address.into(),
+                    approved_by,
+                }
+            }
+        }
+        use postgres::{fallible_iterator::FallibleIterator, GenericClient};
+        pub struct InvoicesQuery<'a, C: GenericClient, T, const N: usize, E = postgres::Error> {
+            client: &'a mut C,
+            params: [&'a (dyn postgres_types::ToSql + Sync); N],
+            stmt: &'a mut cornucopia_sync::private::Stmt,
+            extractor: fn(&postgres::Row) -> Result<InvoicesBorrowed, postgres::Error>,
+            mapper: fn(InvoicesBorrowed) -> T,
+            error: fn(postgres::Error) -> E,
+        }
+        impl<'a, C, T: 'a, const N: usize, E: 'a> InvoicesQuery<'a, C, T, N, E>
+        where
+            C: GenericClient,
+        {
+            pub fn map<R>(
+                self,
+                mapper: fn(InvoicesBorrowed) -> R,
+            ) -> InvoicesQuery<'a, C, R, N, E> {
+                InvoicesQuery {
+                    client: self.client,
+                    params: self.params,
+                    stmt: self.stmt,
+                    extractor: self.extractor,
+                    mapper,
+                    error: self.error,
+                }
+            }
+            pub fn one(self) -> Result<T, E> {
+                let row = self
+                    .stmt
+                    .query_one(self.client, &self.params)
+                    .map_err(self.error)?;
+                Ok((self.mapper)((self.extractor)(&row).map_err(self.error)?))
+            }
+            pub fn opt(self) -> Result<Option<T>, E> {
+                let row = self
+                    .stmt
+                    .query_opt(self.client, &self.params)
+                    .map_err(self.error)?;
+                row.map(|row| Ok((self.mapper)((self.extractor)(&row).map_err(self.error)?)))
+                    .transpose()
+            }
+            pub fn all(self) -> Result<Vec<T>, E> {
+                self.iter()?.collect()
+            }
+            pub fn iter(self) -> Result<impl Iterator<Item = Result<T, E>> + 'a, E> {
+                let it = self
+                    .stmt
+                    .query_raw(self.client, &self.params)
+                    .map_err(self.error)?
+                    .iterator()
+                    .map(move |res| {
+                        res.and_then(|row| (self.extractor)(&row).map(self.mapper))
+                            .map_err(self.error)
+                    });
+                Ok(it)
+            }
+            #[doc = " Declares a server-side cursor over the rows, fetching them in chunks instead of"]
+            #[doc = " buffering them all. Cursors only live inside a transaction."]
+            pub fn cursor(self) -> Result<cornucopia_sync::Cursor<'a, C, T, E>, E>
+            where
+                C: cornucopia_sync::InTransaction,
+            {
+                let (extractor, mapper) = (self.extractor, self.mapper);
+                self.stmt.cursor(
+                    self.client,
+                    &self.params,
+                    move |row| extractor(row).map(mapper),
+                    self.error,
+                )
+            }
+        }
+        pub fn insert_invoice() -> InsertInvoiceStmt {
+            InsertInvoiceStmt(cornucopia_sync::private::Stmt::new(
+                "INSERT INTO invoice (account_id, billing_address, approved_by)
+VALUES ($1, $2, $3)",
+            ))
+        }
+        pub struct InsertInvoiceStmt(cornucopia_sync::private::Stmt);
+        impl InsertInvoiceStmt {
+            #[doc = " Latency budget in milliseconds declared by the `budget` option of the query"]
+            pub const BUDGET_MS: Option<u64> = None;
+            pub fn bind<'a, C: GenericClient>(
+                &'a mut self,
+                client: &'a mut C,
+                account_id: &'a i32,
+                billing_address: &'a crate::db_types::public::AddressBorrowed<'a>,
+                approved_by: &'a crate::db_types::public::Role,
+            ) -> Result<u64, postgres::Error> {
+                self.0
+                    .execute(client, &[account_id, billing_address, approved_by])
+            }
+        }
+        impl<'a, C: GenericClient>
+            cornucopia_sync::Params<'a, InsertInvoiceParams<'a>, Result<u64, postgres::Error>, C>
+            for InsertInvoiceStmt
+        {
+            fn params(
+                &'a mut self,
+                client: &'a mut C,
+                params: &'a InsertInvoiceParams<'a>,
+            ) -> Result<u64, postgres::Error> {
+                self.bind(
+                    client,
+                    &params.account_id,
+                    &params.billing_address,
+                    &params.approved_by,
+                )
+            }
+        }
+        pub fn invoices() -> InvoicesStmt {
+            InvoicesStmt(cornucopia_sync::private::Stmt::new(
+                "SELECT * FROM invoice ORDER BY id",
+            ))
+        }
+        pub struct InvoicesStmt(cornucopia_sync::private::Stmt);
+        impl InvoicesStmt {
+            #[doc = " Latency budget in milliseconds declared by the `budget` option of the query"]
+            pub const BUDGET_MS: Option<u64> = None;
+            pub fn bind<'a, C: GenericClient>(
+                &'a mut self,
+                client: &'a mut C,
+            ) -> InvoicesQuery<'a, C, Invoices, 0> {
+                InvoicesQuery {
+                    client,
+                    params: [],
+                    stmt: &mut self.0,
+                    extractor: |row| {
+                        Ok(InvoicesBorrowed {
+                            id: row.try_get(0)?,
+                            account_id: row.try_get(1)?,
+                            billing_address: row.try_get(2)?,
+                            approved_by: row.try_get(3)?,
+                        })
+                    },
+                    mapper: |it| <Invoices>::from(it),
+                    error: From::from,
+                }
+            }
+        }
+    }
+}
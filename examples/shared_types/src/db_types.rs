@@ -0,0 +1,280 @@
+// This file was generated with `cornucopia`. Do not modify.
+
+#![allow(clippy::all, clippy::pedantic)]
+#![allow(unused_variables)]
+#![allow(unused_imports)]
+#![allow(dead_code)]
+pub mod public {
+    #[derive(Debug, postgres_types::FromSql, Clone, PartialEq)]
+    #[postgres(name = "address")]
+    pub struct Address {
+        #[postgres(name = "street")]
+        pub street: String,
+        #[postgres(name = "city")]
+        pub city: String,
+    }
+    #[derive(Debug)]
+    pub struct AddressBorrowed<'a> {
+        pub street: &'a str,
+        pub city: &'a str,
+    }
+    impl<'a> From<AddressBorrowed<'a>> for Address {
+        fn from(AddressBorrowed { street, city }: AddressBorrowed<'a>) -> Self {
+            Self {
+                street: street.into(),
+                city: city.into(),
+            }
+        }
+    }
+    impl<'a> postgres_types::FromSql<'a> for AddressBorrowed<'a> {
+        fn from_sql(
+            ty: &postgres_types::Type,
+            out: &'a [u8],
+        ) -> Result<AddressBorrowed<'a>, Box<dyn std::error::Error + Sync + Send>> {
+            let fields = match *ty.kind() {
+                postgres_types::Kind::Composite(ref fields) => fields,
+                _ => unreachable!(),
+            };
+            let mut out = out;
+            let num_fields = postgres_types::private::read_be_i32(&mut out)?;
+            if num_fields as usize != fields.len() {
+                return std::result::Result::Err(std::convert::Into::into(format!(
+                    "invalid field count: {} vs {}",
+                    num_fields,
+                    fields.len()
+                )));
+            }
+            let _oid = postgres_types::private::read_be_i32(&mut out)?;
+            let street = postgres_types::private::read_value(fields[0].type_(), &mut out)?;
+            let _oid = postgres_types::private::read_be_i32(&mut out)?;
+            let city = postgres_types::private::read_value(fields[1].type_(), &mut out)?;
+            Ok(AddressBorrowed { street, city })
+        }
+        fn accepts(ty: &postgres_types::Type) -> bool {
+            ty.name() == "address" && ty.schema() == "public"
+        }
+    }
+    impl<'a> postgres_types::ToSql for AddressBorrowed<'a> {
+        fn to_sql(
+            &self,
+            ty: &postgres_types::Type,
+            out: &mut postgres_types::private::BytesMut,
+        ) -> Result<postgres_types::IsNull, Box<dyn std::error::Error + Sync + Send>> {
+            let AddressBorrowed { street, city } = self;
+            let fields = match *ty.kind() {
+                postgres_types::Kind::Composite(ref fields) => fields,
+                _ => unreachable!(),
+            };
+            out.extend_from_slice(&(fields.len() as i32).to_be_bytes());
+            for field in fields {
+                out.extend_from_slice(&field.type_().oid().to_be_bytes());
+                let base = out.len();
+                out.extend_from_slice(&[0; 4]);
+                let r = match field.name() {
+                    "street" => postgres_types::ToSql::to_sql(street, field.type_(), out),
+                    "city" => postgres_types::ToSql::to_sql(city, field.type_(), out),
+                    _ => unreachable!(),
+                };
+                let count = match r? {
+                    postgres_types::IsNull::Yes => -1,
+                    postgres_types::IsNull::No => {
+                        let len = out.len() - base - 4;
+                        if len > i32::max_value() as usize {
+                            return Err(Into::into("value too large to transmit"));
+                        }
+                        len as i32
+                    }
+                };
+                out[base..base + 4].copy_from_slice(&count.to_be_bytes());
+            }
+            Ok(postgres_types::IsNull::No)
+        }
+        fn accepts(ty: &postgres_types::Type) -> bool {
+            if ty.name() != "address" {
+                return false;
+            }
+            match *ty.kind() {
+                postgres_types::Kind::Composite(ref fields) => {
+                    if fields.len() != 2 {
+                        return false;
+                    }
+                    fields.iter().all(|f| match f.name() {
+                        "street" => <&'a str as postgres_types::ToSql>::accepts(f.type_()),
+                        "city" => <&'a str as postgres_types::ToSql>::accepts(f.type_()),
+                        _ => false,
+                    })
+                }
+                _ => false,
+            }
+        }
+        fn to_sql_checked(
+            &self,
+            ty: &postgres_types::Type,
+            out: &mut postgres_types::private::BytesMut,
+        ) -> Result<postgres_types::IsNull, Box<dyn std::error::Error + Sync + Send>> {
+            postgres_types::__to_sql_checked(self, ty, out)
+        }
+    }
+    #[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, PartialOrd, Ord)]
+    #[allow(non_camel_case_types)]
+    pub enum Currency {
+        eur,
+        usd,
+    }
+    impl<'a> postgres_types::ToSql for Currency {
+        fn to_sql(
+            &self,
+            ty: &postgres_types::Type,
+            buf: &mut postgres_types::private::BytesMut,
+        ) -> Result<postgres_types::IsNull, Box<dyn std::error::Error + Sync + Send>> {
+            let s = match *self {
+                Currency::eur => "eur",
+                Currency::usd => "usd",
+            };
+            buf.extend_from_slice(s.as_bytes());
+            std::result::Result::Ok(postgres_types::IsNull::No)
+        }
+        fn accepts(ty: &postgres_types::Type) -> bool {
+            if ty.name() != "currency" {
+                return false;
+            }
+            match *ty.kind() {
+                postgres_types::Kind::Enum(ref variants) => {
+                    if variants.len() != 2 {
+                        return false;
+                    }
+                    variants.iter().all(|v| match &**v {
+                        "eur" => true,
+                        "usd" => true,
+                        _ => false,
+                    })
+                }
+                _ => false,
+            }
+        }
+        fn to_sql_checked(
+            &self,
+            ty: &postgres_types::Type,
+            out: &mut postgres_types::private::BytesMut,
+        ) -> Result<postgres_types::IsNull, Box<dyn std::error::Error + Sync + Send>> {
+            postgres_types::__to_sql_checked(self, ty, out)
+        }
+    }
+    impl<'a> postgres_types::FromSql<'a> for Currency {
+        fn from_sql(
+            ty: &postgres_types::Type,
+            buf: &'a [u8],
+        ) -> Result<Currency, Box<dyn std::error::Error + Sync + Send>> {
+            match std::str::from_utf8(buf)? {
+                "eur" => Ok(Currency::eur),
+                "usd" => Ok(Currency::usd),
+                s => Result::Err(Into::into(format!(
+                    concat!(
+                        "unknown label `{}` of the enum `public.currency`, ",
+                        "the code must be regenerated to decode it",
+                    ),
+                    s
+                ))),
+            }
+        }
+        fn accepts(ty: &postgres_types::Type) -> bool {
+            if ty.name() != "currency" {
+                return false;
+            }
+            match *ty.kind() {
+                postgres_types::Kind::Enum(ref variants) => {
+                    if variants.len() != 2 {
+                        return false;
+                    }
+                    variants.iter().all(|v| match &**v {
+                        "eur" => true,
+                        "usd" => true,
+                        _ => false,
+                    })
+                }
+                _ => false,
+            }
+        }
+    }
+    #[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, PartialOrd, Ord)]
+    #[allow(non_camel_case_types)]
+    pub enum Role {
+        member,
+        admin,
+    }
+    impl<'a> postgres_types::ToSql for Role {
+        fn to_sql(
+            &self,
+            ty: &postgres_types::Type,
+            buf: &mut postgres_types::private::BytesMut,
+        ) -> Result<postgres_types::IsNull, Box<dyn std::error::Error + Sync + Send>> {
+            let s = match *self {
+                Role::member => "member",
+                Role::admin => "admin",
+            };
+            buf.extend_from_slice(s.as_bytes());
+            std::result::Result::Ok(postgres_types::IsNull::No)
+        }
+        fn accepts(ty: &postgres_types::Type) -> bool {
+            if ty.name() != "role" {
+                return false;
+            }
+            match *ty.kind() {
+                postgres_types::Kind::Enum(ref variants) => {
+                    if variants.len() != 2 {
+                        return false;
+                    }
+                    variants.iter().all(|v| match &**v {
+                        "member" => true,
+                        "admin" => true,
+                        _ => false,
+                    })
+                }
+                _ => false,
+            }
+        }
+        fn to_sql_checked(
+            &self,
+            ty: &postgres_types::Type,
+            out: &mut postgres_types::private::BytesMut,
+        ) -> Result<postgres_types::IsNull, Box<dyn std::error::Error + Sync + Send>> {
+            postgres_types::__to_sql_checked(self, ty, out)
+        }
+    }
+    impl<'a> postgres_types::FromSql<'a> for Role {
+        fn from_sql(
+            ty: &postgres_types::Type,
+            buf: &'a [u8],
+        ) -> Result<Role, Box<dyn std::error::Error + Sync + Send>> {
+            match std::str::from_utf8(buf)? {
+                "member" => Ok(Role::member),
+                "admin" => Ok(Role::admin),
+                s => Result::Err(Into::into(format!(
+                    concat!(
+                        "unknown label `{}` of the enum `public.role`, ",
+                        "the code must be regenerated to decode it",
+                    ),
+                    s
+                ))),
+            }
+        }
+        fn accepts(ty: &postgres_types::Type) -> bool {
+            if ty.name() != "role" {
+                return false;
+            }
+            match *ty.kind() {
+                postgres_types::Kind::Enum(ref variants) => {
+                    if variants.len() != 2 {
+                        return false;
+                    }
+                    variants.iter().all(|v| match &**v {
+                        "member" => true,
+                        "admin" => true,
+                        _ => false,
+                    })
+                }
+                _ => false,
+            }
+        }
+    }
+}
@@ -0,0 +1,51 @@
+// The custom types are generated once in `db_types.rs` using the `types` command, and
+// the queries generated in `accounts.rs` and `billing.rs` reference them using
+// `--types-path crate::db_types`. With several crates, the types would instead be
+// generated in a crate depended on by all of them, like `--types-path db_types`.
+mod accounts;
+mod billing;
+mod db_types;
+
+use postgres::{Config, NoTls};
+
+use crate::{
+    accounts::queries::accounts::{accounts_by_role, insert_account},
+    billing::queries::invoices::{insert_invoice, invoices},
+    db_types::public::{AddressBorrowed, Role},
+};
+
+pub fn main() {
+    let mut client = get_client().unwrap();
+    let mut transaction = client.transaction().unwrap();
+
+    let id = insert_account()
+        .bind(&mut transaction, &"Ada", &Role::admin)
+        .one()
+        .unwrap();
+    let address = AddressBorrowed {
+        street: "1 Main Street",
+        city: "Springfield",
+    };
+    insert_invoice()
+        .bind(&mut transaction, &id, &address, &Role::admin)
+        .unwrap();
+
+    // The role read by the billing queries is the very type expected by the accounts ones
+    let invoice = invoices().bind(&mut transaction).one().unwrap();
+    let approvers = accounts_by_role()
+        .bind(&mut transaction, &invoice.approved_by)
+        .all()
+        .unwrap();
+    assert_eq!(approvers[0].name, "Ada");
+    dbg!(invoice, approvers);
+}
+
+fn get_client() -> Result<postgres::Client, postgres::Error> {
+    Config::new()
+        .user("postgres")
+        .password("postgres")
+        .host("127.0.0.1")
+        .port(5435)
+        .dbname("postgres")
+        .connect(NoTls)
+}
@@ -0,0 +1,20 @@
+--! tasks
+SELECT * FROM task ORDER BY id;
+
+--! count_tasks
+SELECT count(*) AS count FROM task;
+
+--! pending_tasks
+-- :cache ttl=60s
+SELECT title FROM task WHERE NOT done ORDER BY id;
+
+--! insert_task
+INSERT INTO task (title) VALUES (:title);
+
+--! complete_tasks
+UPDATE task SET done = true WHERE NOT done;
+
+--! clear_tasks
+-- :batch
+DELETE FROM task;
+ALTER SEQUENCE task_id_seq RESTART;
@@ -0,0 +1,5 @@
+CREATE TABLE task (
+    id SERIAL PRIMARY KEY,
+    title TEXT NOT NULL,
+    done BOOLEAN NOT NULL DEFAULT false
+);
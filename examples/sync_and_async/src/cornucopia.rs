@@ -0,0 +1,813 @@
+// This file was generated with `cornucopia`. Do not modify.
+
+#[allow(clippy::all, clippy::pedantic)]
+#[allow(unused_variables)]
+#[allow(unused_imports)]
+#[allow(dead_code)]
+pub mod types {}
+#[allow(clippy::all, clippy::pedantic)]
+#[allow(unused_variables)]
+#[allow(unused_imports)]
+#[allow(dead_code)]
+pub mod queries {
+    pub mod tasks {
+        #[derive(Debug, Clone, PartialEq, Eq, Hash, PartialOrd, Ord)]
+        pub struct Tasks {
+            pub id: i32,
+            pub title: String,
+            pub done: bool,
+        }
+        pub struct TasksBorrowed<'a> {
+            pub id: i32,
+            pub title: &'a str,
+            pub done: bool,
+        }
+        impl<'a> From<TasksBorrowed<'a>> for Tasks {
+            fn from(TasksBorrowed { id, title, done }: TasksBorrowed<'a>) -> Self {
+                Self {
+                    id,
+                    title: title.into(),
+                    done,
+                }
+            }
+        }
+        pub mod sync {
+            use postgres::{fallible_iterator::FallibleIterator, GenericClient};
+            pub struct TasksQuery<'a, C: GenericClient, T, const N: usize, E = postgres::Error> {
+                client: &'a mut C,
+                params: [&'a (dyn postgres_types::ToSql + Sync); N],
+                stmt: &'a mut cornucopia_sync::private::Stmt,
+                extractor: fn(&postgres::Row) -> Result<super::TasksBorrowed, postgres::Error>,
+                mapper: fn(super::TasksBorrowed) -> T,
+                error: fn(postgres::Error) -> E,
+            }
+            impl<'a, C, T: 'a, const N: usize, E: 'a> TasksQuery<'a, C, T, N, E>
+            where
+                C: GenericClient,
+            {
+                pub fn map<R>(
+                    self,
+                    mapper: fn(super::TasksBorrowed) -> R,
+                ) -> TasksQuery<'a, C, R, N, E> {
+                    TasksQuery {
+                        client: self.client,
+                        params: self.params,
+                        stmt: self.stmt,
+                        extractor: self.extractor,
+                        mapper,
+                        error: self.error,
+                    }
+                }
+                pub fn one(self) -> Result<T, E> {
+                    let row = self
+                        .stmt
+                        .query_one(self.client, &self.params)
+                        .map_err(self.error)?;
+                    Ok((self.mapper)((self.extractor)(&row).map_err(self.error)?))
+                }
+                pub fn opt(self) -> Result<Option<T>, E> {
+                    let row = self
+                        .stmt
+                        .query_opt(self.client, &self.params)
+                        .map_err(self.error)?;
+                    row.map(|row| Ok((self.mapper)((self.extractor)(&row).map_err(self.error)?)))
+                        .transpose()
+                }
+                pub fn all(self) -> Result<Vec<T>, E> {
+                    self.iter()?.collect()
+                }
+                pub fn iter(self) -> Result<impl Iterator<Item = Result<T, E>> + 'a, E> {
+                    let it = self
+                        .stmt
+                        .query_raw(self.client, &self.params)
+                        .map_err(self.error)?
+                        .iterator()
+                        .map(move |res| {
+                            res.and_then(|row| (self.extractor)(&row).map(self.mapper))
+                                .map_err(self.error)
+                        });
+                    Ok(it)
+                }
+                #[doc = " Declares a server-side cursor over the rows, fetching them in chunks instead of"]
+                #[doc = " buffering them all. Cursors only live inside a transaction."]
+                pub fn cursor(self) -> Result<cornucopia_sync::Cursor<'a, C, T, E>, E>
+                where
+                    C: cornucopia_sync::InTransaction,
+                {
+                    let (extractor, mapper) = (self.extractor, self.mapper);
+                    self.stmt.cursor(
+                        self.client,
+                        &self.params,
+                        move |row| extractor(row).map(mapper),
+                        self.error,
+                    )
+                }
+            }
+            pub struct I64Query<'a, C: GenericClient, T, const N: usize, E = postgres::Error> {
+                client: &'a mut C,
+                params: [&'a (dyn postgres_types::ToSql + Sync); N],
+                stmt: &'a mut cornucopia_sync::private::Stmt,
+                extractor: fn(&postgres::Row) -> Result<i64, postgres::Error>,
+                mapper: fn(i64) -> T,
+                error: fn(postgres::Error) -> E,
+            }
+            impl<'a, C, T: 'a, const N: usize, E: 'a> I64Query<'a, C, T, N, E>
+            where
+                C: GenericClient,
+            {
+                pub fn map<R>(self, mapper: fn(i64) -> R) -> I64Query<'a, C, R, N, E> {
+                    I64Query {
+                        client: self.client,
+                        params: self.params,
+                        stmt: self.stmt,
+                        extractor: self.extractor,
+                        mapper,
+                        error: self.error,
+                    }
+                }
+                pub fn one(self) -> Result<T, E> {
+                    let row = self
+                        .stmt
+                        .query_one(self.client, &self.params)
+                        .map_err(self.error)?;
+                    Ok((self.mapper)((self.extractor)(&row).map_err(self.error)?))
+                }
+                pub fn opt(self) -> Result<Option<T>, E> {
+                    let row = self
+                        .stmt
+                        .query_opt(self.client, &self.params)
+                        .map_err(self.error)?;
+                    row.map(|row| Ok((self.mapper)((self.extractor)(&row).map_err(self.error)?)))
+                        .transpose()
+                }
+                pub fn all(self) -> Result<Vec<T>, E> {
+                    self.iter()?.collect()
+                }
+                pub fn iter(self) -> Result<impl Iterator<Item = Result<T, E>> + 'a, E> {
+                    let it = self
+                        .stmt
+                        .query_raw(self.client, &self.params)
+                        .map_err(self.error)?
+                        .iterator()
+                        .map(move |res| {
+                            res.and_then(|row| (self.extractor)(&row).map(self.mapper))
+                                .map_err(self.error)
+                        });
+                    Ok(it)
+                }
+                #[doc = " Declares a server-side cursor over the rows, fetching them in chunks instead of"]
+                #[doc = " buffering them all. Cursors only live inside a transaction."]
+                pub fn cursor(self) -> Result<cornucopia_sync::Cursor<'a, C, T, E>, E>
+                where
+                    C: cornucopia_sync::InTransaction,
+                {
+                    let (extractor, mapper) = (self.extractor, self.mapper);
+                    self.stmt.cursor(
+                        self.client,
+                        &self.params,
+                        move |row| extractor(row).map(mapper),
+                        self.error,
+                    )
+                }
+            }
+            pub struct StringQuery<'a, C: GenericClient, T, const N: usize, E = postgres::Error> {
+                client: &'a mut C,
+                params: [&'a (dyn postgres_types::ToSql + Sync); N],
+                stmt: &'a mut cornucopia_sync::private::Stmt,
+                extractor: fn(&postgres::Row) -> Result<&str, postgres::Error>,
+                mapper: fn(&str) -> T,
+                error: fn(postgres::Error) -> E,
+            }
+            impl<'a, C, T: 'a, const N: usize, E: 'a> StringQuery<'a, C, T, N, E>
+            where
+                C: GenericClient,
+            {
+                pub fn map<R>(self, mapper: fn(&str) -> R) -> StringQuery<'a, C, R, N, E> {
+                    StringQuery {
+                        client: self.client,
+                        params: self.params,
+                        stmt: self.stmt,
+                        extractor: self.extractor,
+                        mapper,
+                        error: self.error,
+                    }
+                }
+                pub fn one(self) -> Result<T, E> {
+                    let row = self
+                        .stmt
+                        .query_one(self.client, &self.params)
+                        .map_err(self.error)?;
+                    Ok((self.mapper)((self.extractor)(&row).map_err(self.error)?))
+                }
+                pub fn opt(self) -> Result<Option<T>, E> {
+                    let row = self
+                        .stmt
+                        .query_opt(self.client, &self.params)
+                        .map_err(self.error)?;
+                    row.map(|row| Ok((self.mapper)((self.extractor)(&row).map_err(self.error)?)))
+                        .transpose()
+                }
+                pub fn all(self) -> Result<Vec<T>, E> {
+                    self.iter()?.collect()
+                }
+                pub fn iter(self) -> Result<impl Iterator<Item = Result<T, E>> + 'a, E> {
+                    let it = self
+                        .stmt
+                        .query_raw(self.client, &self.params)
+                        .map_err(self.error)?
+                        .iterator()
+                        .map(move |res| {
+                            res.and_then(|row| (self.extractor)(&row).map(self.mapper))
+                                .map_err(self.error)
+                        });
+                    Ok(it)
+                }
+                #[doc = " Declares a server-side cursor over the rows, fetching them in chunks instead of"]
+                #[doc = " buffering them all. Cursors only live inside a transaction."]
+                pub fn cursor(self) -> Result<cornucopia_sync::Cursor<'a, C, T, E>, E>
+                where
+                    C: cornucopia_sync::InTransaction,
+                {
+                    let (extractor, mapper) = (self.extractor, self.mapper);
+                    self.stmt.cursor(
+                        self.client,
+                        &self.params,
+                        move |row| extractor(row).map(mapper),
+                        self.error,
+                    )
+                }
+            }
+            pub fn tasks() -> TasksStmt {
+                TasksStmt(cornucopia_sync::private::Stmt::new(
+                    "SELECT * FROM task ORDER BY id",
+                ))
+            }
+            pub struct TasksStmt(cornucopia_sync::private::Stmt);
+            impl TasksStmt {
+                #[doc = " Latency budget in milliseconds declared by the `budget` option of the query"]
+                pub const BUDGET_MS: Option<u64> = None;
+                pub fn bind<'a, C: GenericClient>(
+                    &'a mut self,
+                    client: &'a mut C,
+                ) -> TasksQuery<'a, C, super::Tasks, 0> {
+                    TasksQuery {
+                        client,
+                        params: [],
+                        stmt: &mut self.0,
+                        extractor: |row| {
+                            Ok(super::TasksBorrowed {
+                                id: row.try_get(0)?,
+                                title: row.try_get(1)?,
+                                done: row.try_get(2)?,
+                            })
+                        },
+                        mapper: |it| <super::Tasks>::from(it),
+                        error: From::from,
+                    }
+                }
+            }
+            pub fn count_tasks() -> CountTasksStmt {
+                CountTasksStmt(cornucopia_sync::private::Stmt::new(
+                    "SELECT count(*) AS count FROM task",
+                ))
+            }
+            pub struct CountTasksStmt(cornucopia_sync::private::Stmt);
+            impl CountTasksStmt {
+                #[doc = " Latency budget in milliseconds declared by the `budget` option of the query"]
+                pub const BUDGET_MS: Option<u64> = None;
+                pub fn bind<'a, C: GenericClient>(
+                    &'a mut self,
+                    client: &'a mut C,
+                ) -> I64Query<'a, C, i64, 0> {
+                    I64Query {
+                        client,
+                        params: [],
+                        stmt: &mut self.0,
+                        extractor: |row| row.try_get(0),
+                        mapper: |it| it,
+                        error: From::from,
+                    }
+                }
+            }
+            pub fn pending_tasks() -> PendingTasksStmt {
+                PendingTasksStmt(cornucopia_sync::private::Stmt::new(
+                    "SELECT title FROM task WHERE NOT done ORDER BY id",
+                ))
+            }
+            pub struct PendingTasksStmt(cornucopia_sync::private::Stmt);
+            impl PendingTasksStmt {
+                #[doc = " Latency budget in milliseconds declared by the `budget` option of the query"]
+                pub const BUDGET_MS: Option<u64> = None;
+                pub fn bind<'a, C: GenericClient>(
+                    &'a mut self,
+                    client: &'a mut C,
+                ) -> StringQuery<'a, C, String, 0> {
+                    StringQuery {
+                        client,
+                        params: [],
+                        stmt: &mut self.0,
+                        extractor: |row| row.try_get(0),
+                        mapper: |it| it.into(),
+                        error: From::from,
+                    }
+                }
+            }
+            #[doc = " Returns the result cached for 60 seconds by `cache`, executing the query when it"]
+            #[doc = " is missing or expired. Results are keyed by the hash of the parameters."]
+            pub fn cached_pending_tasks<'a, C: GenericClient>(
+                cache: &super::super::super::cache::QueryCache,
+                client: &'a mut C,
+            ) -> Result<Vec<String>, postgres::Error> {
+                let mut hasher = std::collections::hash_map::DefaultHasher::new();
+                std::hash::Hash::hash("tasks::pending_tasks", &mut hasher);
+                let key = std::hash::Hasher::finish(&hasher);
+                if let Some(rows) = cache.get(key) {
+                    return Ok(rows);
+                }
+                let rows = pending_tasks().bind(client).all()?;
+                cache.insert(key, std::time::Duration::from_secs(60), rows.clone());
+                Ok(rows)
+            }
+            pub fn insert_task() -> InsertTaskStmt {
+                InsertTaskStmt(cornucopia_sync::private::Stmt::new(
+                    "INSERT INTO task (title) VALUES ($1)",
+                ))
+            }
+            pub struct InsertTaskStmt(cornucopia_sync::private::Stmt);
+            impl InsertTaskStmt {
+                #[doc = " Latency budget in milliseconds declared by the `budget` option of the query"]
+                pub const BUDGET_MS: Option<u64> = None;
+                pub fn bind<'a, C: GenericClient, T1: cornucopia_sync::StringSql>(
+                    &'a mut self,
+                    client: &'a mut C,
+                    title: &'a T1,
+                ) -> Result<u64, postgres::Error> {
+                    self.0.execute(client, &[title])
+                }
+            }
+            pub fn complete_tasks() -> CompleteTasksStmt {
+                CompleteTasksStmt(cornucopia_sync::private::Stmt::new(
+                    "UPDATE task SET done = true WHERE NOT done",
+                ))
+            }
+            pub struct CompleteTasksStmt(cornucopia_sync::private::Stmt);
+            impl CompleteTasksStmt {
+                #[doc = " Latency budget in milliseconds declared by the `budget` option of the query"]
+                pub const BUDGET_MS: Option<u64> = None;
+                pub fn bind<'a, C: GenericClient>(
+                    &'a mut self,
+                    client: &'a mut C,
+                ) -> Result<u64, postgres::Error> {
+                    self.0.execute(client, &[])
+                }
+            }
+            pub fn clear_tasks() -> ClearTasksStmt {
+                ClearTasksStmt
+            }
+            pub struct ClearTasksStmt;
+            impl ClearTasksStmt {
+                #[doc = " Latency budget in milliseconds declared by the `budget` option of the query"]
+                pub const BUDGET_MS: Option<u64> = None;
+                pub fn bind<C: GenericClient>(
+                    &self,
+                    client: &mut C,
+                ) -> Result<Vec<cornucopia_sync::BatchResult>, postgres::Error> {
+                    cornucopia_sync::private::batch(
+                        client,
+                        "DELETE FROM task;
+ALTER SEQUENCE task_id_seq RESTART",
+                        &["DELETE", "ALTER"],
+                    )
+                }
+            }
+        }
+        pub mod async_ {
+            use cornucopia_async::GenericClient;
+            use futures;
+            use futures::{StreamExt, TryStreamExt};
+            pub struct TasksQuery<
+                'a,
+                C: GenericClient,
+                T,
+                const N: usize,
+                E = tokio_postgres::Error,
+            > {
+                client: &'a C,
+                params: [&'a (dyn postgres_types::ToSql + Sync); N],
+                stmt: &'a mut cornucopia_async::private::Stmt,
+                extractor:
+                    fn(&tokio_postgres::Row) -> Result<super::TasksBorrowed, tokio_postgres::Error>,
+                mapper: fn(super::TasksBorrowed) -> T,
+                error: fn(tokio_postgres::Error) -> E,
+            }
+            impl<'a, C, T: 'a, const N: usize, E: 'a> TasksQuery<'a, C, T, N, E>
+            where
+                C: GenericClient,
+            {
+                pub fn map<R>(
+                    self,
+                    mapper: fn(super::TasksBorrowed) -> R,
+                ) -> TasksQuery<'a, C, R, N, E> {
+                    TasksQuery {
+                        client: self.client,
+                        params: self.params,
+                        stmt: self.stmt,
+                        extractor: self.extractor,
+                        mapper,
+                        error: self.error,
+                    }
+                }
+                pub async fn one(self) -> Result<T, E> {
+                    let row = self
+                        .stmt
+                        .query_one(self.client, &self.params)
+                        .await
+                        .map_err(self.error)?;
+                    Ok((self.mapper)((self.extractor)(&row).map_err(self.error)?))
+                }
+                pub async fn opt(self) -> Result<Option<T>, E> {
+                    let row = self
+                        .stmt
+                        .query_opt(self.client, &self.params)
+                        .await
+                        .map_err(self.error)?;
+                    row.map(|row| Ok((self.mapper)((self.extractor)(&row).map_err(self.error)?)))
+                        .transpose()
+                }
+                pub async fn all(self) -> Result<Vec<T>, E> {
+                    self.iter().await?.try_collect().await
+                }
+                pub async fn iter(
+                    self,
+                ) -> Result<impl futures::Stream<Item = Result<T, E>> + 'a, E> {
+                    let it = self
+                        .stmt
+                        .query_raw(self.client, &self.params)
+                        .await
+                        .map_err(self.error)?
+                        .map(move |res| {
+                            res.and_then(|row| (self.extractor)(&row).map(self.mapper))
+                                .map_err(self.error)
+                        })
+                        .into_stream();
+                    Ok(it)
+                }
+                #[doc = " Declares a server-side cursor over the rows, fetching them in chunks instead of"]
+                #[doc = " buffering them all. Cursors only live inside a transaction."]
+                pub async fn cursor(self) -> Result<cornucopia_async::Cursor<'a, C, T, E>, E>
+                where
+                    C: cornucopia_async::InTransaction,
+                {
+                    let (extractor, mapper) = (self.extractor, self.mapper);
+                    self.stmt
+                        .cursor(
+                            self.client,
+                            &self.params,
+                            move |row| extractor(row).map(mapper),
+                            self.error,
+                        )
+                        .await
+                }
+            }
+            pub struct I64Query<'a, C: GenericClient, T, const N: usize, E = tokio_postgres::Error> {
+                client: &'a C,
+                params: [&'a (dyn postgres_types::ToSql + Sync); N],
+                stmt: &'a mut cornucopia_async::private::Stmt,
+                extractor: fn(&tokio_postgres::Row) -> Result<i64, tokio_postgres::Error>,
+                mapper: fn(i64) -> T,
+                error: fn(tokio_postgres::Error) -> E,
+            }
+            impl<'a, C, T: 'a, const N: usize, E: 'a> I64Query<'a, C, T, N, E>
+            where
+                C: GenericClient,
+            {
+                pub fn map<R>(self, mapper: fn(i64) -> R) -> I64Query<'a, C, R, N, E> {
+                    I64Query {
+                        client: self.client,
+                        params: self.params,
+                        stmt: self.stmt,
+                        extractor: self.extractor,
+                        mapper,
+                        error: self.error,
+                    }
+                }
+                pub async fn one(self) -> Result<T, E> {
+                    let row = self
+                        .stmt
+                        .query_one(self.client, &self.params)
+                        .await
+                        .map_err(self.error)?;
+                    Ok((self.mapper)((self.extractor)(&row).map_err(self.error)?))
+                }
+                pub async fn opt(self) -> Result<Option<T>, E> {
+                    let row = self
+                        .stmt
+                        .query_opt(self.client, &self.params)
+                        .await
+                        .map_err(self.error)?;
+                    row.map(|row| Ok((self.mapper)((self.extractor)(&row).map_err(self.error)?)))
+                        .transpose()
+                }
+                pub async fn all(self) -> Result<Vec<T>, E> {
+                    self.iter().await?.try_collect().await
+                }
+                pub async fn iter(
+                    self,
+                ) -> Result<impl futures::Stream<Item = Result<T, E>> + 'a, E> {
+                    let it = self
+                        .stmt
+                        .query_raw(self.client, &self.params)
+                        .await
+                        .map_err(self.error)?
+                        .map(move |res| {
+                            res.and_then(|row| (self.extractor)(&row).map(self.mapper))
+                                .map_err(self.error)
+                        })
+                        .into_stream();
+                    Ok(it)
+                }
+                #[doc = " Declares a server-side cursor over the rows, fetching them in chunks instead of"]
+                #[doc = " buffering them all. Cursors only live inside a transaction."]
+                pub async fn cursor(self) -> Result<cornucopia_async::Cursor<'a, C, T, E>, E>
+                where
+                    C: cornucopia_async::InTransaction,
+                {
+                    let (extractor, mapper) = (self.extractor, self.mapper);
+                    self.stmt
+                        .cursor(
+                            self.client,
+                            &self.params,
+                            move |row| extractor(row).map(mapper),
+                            self.error,
+                        )
+                        .await
+                }
+            }
+            pub struct StringQuery<
+                'a,
+                C: GenericClient,
+                T,
+                const N: usize,
+                E = tokio_postgres::Error,
+            > {
+                client: &'a C,
+                params: [&'a (dyn postgres_types::ToSql + Sync); N],
+                stmt: &'a mut cornucopia_async::private::Stmt,
+                extractor: fn(&tokio_postgres::Row) -> Result<&str, tokio_postgres::Error>,
+                mapper: fn(&str) -> T,
+                error: fn(tokio_postgres::Error) -> E,
+            }
+            impl<'a, C, T: 'a, const N: usize, E: 'a> StringQuery<'a, C, T, N, E>
+            where
+                C: GenericClient,
+            {
+                pub fn map<R>(self, mapper: fn(&str) -> R) -> StringQuery<'a, C, R, N, E> {
+                    StringQuery {
+                        client: self.client,
+                        params: self.params,
+                        stmt: self.stmt,
+                        extractor: self.extractor,
+                        mapper,
+                        error: self.error,
+                    }
+                }
+                pub async fn one(self) -> Result<T, E> {
+                    let row = self
+                        .stmt
+                        .query_one(self.client, &self.params)
+                        .await
+                        .map_err(self.error)?;
+                    Ok((self.mapper)((self.extractor)(&row).map_err(self.error)?))
+                }
+                pub async fn opt(self) -> Result<Option<T>, E> {
+                    let row = self
+                        .stmt
+                        .query_opt(self.client, &self.params)
+                        .await
+                        .map_err(self.error)?;
+                    row.map(|row| Ok((self.mapper)((self.extractor)(&row).map_err(self.error)?)))
+                        .transpose()
+                }
+                pub async fn all(self) -> Result<Vec<T>, E> {
+                    self.iter().await?.try_collect().await
+                }
+                pub async fn iter(
+                    self,
+                ) -> Result<impl futures::Stream<Item = Result<T, E>> + 'a, E> {
+                    let it = self
+                        .stmt
+                        .query_raw(self.client, &self.params)
+                        .await
+                        .map_err(self.error)?
+                        .map(move |res| {
+                            res.and_then(|row| (self.extractor)(&row).map(self.mapper))
+                                .map_err(self.error)
+                        })
+                        .into_stream();
+                    Ok(it)
+                }
+                #[doc = " Declares a server-side cursor over the rows, fetching them in chunks instead of"]
+                #[doc = " buffering them all. Cursors only live inside a transaction."]
+                pub async fn cursor(self) -> Result<cornucopia_async::Cursor<'a, C, T, E>, E>
+                where
+                    C: cornucopia_async::InTransaction,
+                {
+                    let (extractor, mapper) = (self.extractor, self.mapper);
+                    self.stmt
+                        .cursor(
+                            self.client,
+                            &self.params,
+                            move |row| extractor(row).map(mapper),
+                            self.error,
+                        )
+                        .await
+                }
+            }
+            pub fn tasks() -> TasksStmt {
+                TasksStmt(cornucopia_async::private::Stmt::new(
+                    "SELECT * FROM task ORDER BY id",
+                ))
+            }
+            pub struct TasksStmt(cornucopia_async::private::Stmt);
+            impl TasksStmt {
+                #[doc = " Latency budget in milliseconds declared by the `budget` option of the query"]
+                pub const BUDGET_MS: Option<u64> = None;
+                pub fn bind<'a, C: GenericClient>(
+                    &'a mut self,
+                    client: &'a C,
+                ) -> TasksQuery<'a, C, super::Tasks, 0> {
+                    TasksQuery {
+                        client,
+                        params: [],
+                        stmt: &mut self.0,
+                        extractor: |row| {
+                            Ok(super::TasksBorrowed {
+                                id: row.try_get(0)?,
+                                title: row.try_get(1)?,
+                                done: row.try_get(2)?,
+                            })
+                        },
+                        mapper: |it| <super::Tasks>::from(it),
+                        error: From::from,
+                    }
+                }
+            }
+            pub fn count_tasks() -> CountTasksStmt {
+                CountTasksStmt(cornucopia_async::private::Stmt::new(
+                    "SELECT count(*) AS count FROM task",
+                ))
+            }
+            pub struct CountTasksStmt(cornucopia_async::private::Stmt);
+            impl CountTasksStmt {
+                #[doc = " Latency budget in milliseconds declared by the `budget` option of the query"]
+                pub const BUDGET_MS: Option<u64> = None;
+                pub fn bind<'a, C: GenericClient>(
+                    &'a mut self,
+                    client: &'a C,
+                ) -> I64Query<'a, C, i64, 0> {
+                    I64Query {
+                        client,
+                        params: [],
+                        stmt: &mut self.0,
+                        extractor: |row| row.try_get(0),
+                        mapper: |it| it,
+                        error: From::from,
+                    }
+                }
+            }
+            pub fn pending_tasks() -> PendingTasksStmt {
+                PendingTasksStmt(cornucopia_async::private::Stmt::new(
+                    "SELECT title FROM task WHERE NOT done ORDER BY id",
+                ))
+            }
+            pub struct PendingTasksStmt(cornucopia_async::private::Stmt);
+            impl PendingTasksStmt {
+                #[doc = " Latency budget in milliseconds declared by the `budget` option of the query"]
+                pub const BUDGET_MS: Option<u64> = None;
+                pub fn bind<'a, C: GenericClient>(
+                    &'a mut self,
+                    client: &'a C,
+                ) -> StringQuery<'a, C, String, 0> {
+                    StringQuery {
+                        client,
+                        params: [],
+                        stmt: &mut self.0,
+                        extractor: |row| row.try_get(0),
+                        mapper: |it| it.into(),
+                        error: From::from,
+                    }
+                }
+            }
+            #[doc = " Returns the result cached for 60 seconds by `cache`, executing the query when it"]
+            #[doc = " is missing or expired. Results are keyed by the hash of the parameters."]
+            pub async fn cached_pending_tasks<'a, C: GenericClient>(
+                cache: &super::super::super::cache::QueryCache,
+                client: &'a C,
+            ) -> Result<Vec<String>, tokio_postgres::Error> {
+                let mut hasher = std::collections::hash_map::DefaultHasher::new();
+                std::hash::Hash::hash("tasks::pending_tasks", &mut hasher);
+                let key = std::hash::Hasher::finish(&hasher);
+                if let Some(rows) = cache.get(key) {
+                    return Ok(rows);
+                }
+                let rows = pending_tasks().bind(client).all().await?;
+                cache.insert(key, std::time::Duration::from_secs(60), rows.clone());
+                Ok(rows)
+            }
+            pub fn insert_task() -> InsertTaskStmt {
+                InsertTaskStmt(cornucopia_async::private::Stmt::new(
+                    "INSERT INTO task (title) VALUES ($1)",
+                ))
+            }
+            pub struct InsertTaskStmt(cornucopia_async::private::Stmt);
+            impl InsertTaskStmt {
+                #[doc = " Latency budget in milliseconds declared by the `budget` option of the query"]
+                pub const BUDGET_MS: Option<u64> = None;
+                pub async fn bind<'a, C: GenericClient, T1: cornucopia_async::StringSql>(
+                    &'a mut self,
+                    client: &'a C,
+                    title: &'a T1,
+                ) -> Result<u64, tokio_postgres::Error> {
+                    self.0.execute(client, &[title]).await
+                }
+            }
+            pub fn complete_tasks() -> CompleteTasksStmt {
+                CompleteTasksStmt(cornucopia_async::private::Stmt::new(
+                    "UPDATE task SET done = true WHERE NOT done",
+                ))
+            }
+            pub struct CompleteTasksStmt(cornucopia_async::private::Stmt);
+            impl CompleteTasksStmt {
+                #[doc = " Latency budget in milliseconds declared by the `budget` option of the query"]
+                pub const BUDGET_MS: Option<u64> = None;
+                pub async fn bind<'a, C: GenericClient>(
+                    &'a mut self,
+                    client: &'a C,
+                ) -> Result<u64, tokio_postgres::Error> {
+                    self.0.execute(client, &[]).await
+                }
+            }
+            pub fn clear_tasks() -> ClearTasksStmt {
+                ClearTasksStmt
+            }
+            pub struct ClearTasksStmt;
+            impl ClearTasksStmt {
+                #[doc = " Latency budget in milliseconds declared by the `budget` option of the query"]
+                pub const BUDGET_MS: Option<u64> = None;
+                pub async fn bind<C: GenericClient>(
+                    &self,
+                    client: &C,
+                ) -> Result<Vec<cornucopia_async::BatchResult>, tokio_postgres::Error>
+                {
+                    cornucopia_async::private::batch(
+                        client,
+                        "DELETE FROM task;
+ALTER SEQUENCE task_id_seq RESTART",
+                        &["DELETE", "ALTER"],
+                    )
+                    .await
+                }
+            }
+        }
+    }
+}
+#[allow(clippy::all, clippy::pedantic)]
+#[allow(dead_code)]
+pub mod cache {
+    use std::any::Any;
+    use std::collections::HashMap;
+    use std::sync::{Mutex, PoisonError};
+    use std::time::{Duration, Instant};
+    #[doc = " Results of the queries declared with the `cache` option, used by their `cached_`"]
+    #[doc = " functions. A single cache can be shared between threads and tasks."]
+    #[derive(Default)]
+    pub struct QueryCache {
+        entries: Mutex<HashMap<u64, (Instant, Box<dyn Any + Send + Sync>)>>,
+    }
+    impl QueryCache {
+        pub fn new() -> Self {
+            Self::default()
+        }
+        #[doc = " Removes every cached result, so that the next calls execute their query"]
+        pub fn clear(&self) {
+            self.entries
+                .lock()
+                .unwrap_or_else(PoisonError::into_inner)
+                .clear();
+        }
+        pub(crate) fn get<T: Clone + 'static>(&self, key: u64) -> Option<T> {
+            let entries = self.entries.lock().unwrap_or_else(PoisonError::into_inner);
+            entries
+                .get(&key)
+                .filter(|(expires, _)| Instant::now() < *expires)
+                .and_then(|(_, value)| value.downcast_ref::<T>())
+                .cloned()
+        }
+        pub(crate) fn insert<T: Send + Sync + 'static>(&self, key: u64, ttl: Duration, value: T) {
+            let mut entries = self.entries.lock().unwrap_or_else(PoisonError::into_inner);
+            let now = Instant::now();
+            entries.retain(|_, (expires, _)| now < *expires);
+            entries.insert(key, (now + ttl, Box::new(value)));
+        }
+    }
+}
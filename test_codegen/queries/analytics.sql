@@ -0,0 +1,13 @@
+-- schema: analytics
+
+--! insert_visit
+INSERT INTO visit (page, device) VALUES (:page, :device);
+
+--! pages_by_device
+SELECT page FROM visit WHERE device = :device ORDER BY page;
+
+--! visits : Visit()
+SELECT page, device FROM visit;
+
+--! sorted_visits : Visit()
+SELECT page, device FROM visit ORDER BY page, device;
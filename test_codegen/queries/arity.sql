@@ -0,0 +1,12 @@
+--! book_by_name: (author?)
+-- :arity opt
+SELECT * FROM book WHERE name = :name;
+
+--! count_books
+-- :arity one
+SELECT count(*) FROM book;
+
+--! insert_publisher_once
+INSERT INTO publisher (id, name) VALUES (:id, :name)
+ON CONFLICT (id) DO NOTHING
+RETURNING name;
@@ -0,0 +1,7 @@
+--: Contact(email? #[serde(skip_serializing_if = "Option::is_none")] #[serde(rename = "mail")])
+--: ContactParams(name #[serde(rename = "full_name")], email?)
+
+--! insert_contact ContactParams
+INSERT INTO contact (name, email) VALUES (:name, :email);
+--! contacts: Contact
+SELECT name, email FROM contact ORDER BY name;
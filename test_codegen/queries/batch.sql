@@ -0,0 +1,4 @@
+--! purge_accounts
+-- :batch
+UPDATE account SET age = NULL WHERE age < 18;
+DELETE FROM account WHERE age IS NULL;
@@ -0,0 +1,9 @@
+--! insert_blob
+INSERT INTO blob (id, name, data) VALUES (:id, :name, :data);
+
+--! blob_by_id
+SELECT name, data FROM blob WHERE id = :id;
+
+--! attachment_by_id : Attachment()
+-- :into crate::Attachment
+SELECT id, name FROM blob WHERE id = :id;
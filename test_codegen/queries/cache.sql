@@ -0,0 +1,12 @@
+--! books_by_author_cached (author?) : (author?)
+-- :cache ttl=60s
+SELECT * FROM book WHERE author IS NOT DISTINCT FROM :author ORDER BY name;
+
+--! count_books_cached
+-- :cache ttl=1h
+-- :arity one
+SELECT count(*) FROM book;
+
+--! find_books_cached : (author?)
+-- :cache ttl=5m
+SELECT * FROM book WHERE name = ANY (:title) ORDER BY name;
@@ -0,0 +1,12 @@
+--! client
+SELECT :client::text AS client, :error::text AS error, :row::text AS row, :params::text AS params;
+
+--! error
+SELECT :stmt::int4 AS stmt, :mapper::int4 AS mapper, :extractor::int4 AS extractor;
+
+--! row
+INSERT INTO book (name) VALUES (:row);
+
+--! params
+-- :cache ttl=60s
+SELECT :it::text AS it, :hasher::text AS hasher, :cache::text AS cache;
@@ -0,0 +1,13 @@
+--: NamedDetail(price?)
+--: NamedPrice()
+
+--! named_details: NamedDetail
+SELECT id, name, price, show FROM named ORDER BY id;
+--! named_summaries: NamedSummary()
+SELECT id, name FROM named ORDER BY id;
+--! named_prices: NamedPrice
+SELECT name, price FROM named WHERE price IS NOT NULL ORDER BY id;
+--! named_visibility
+SELECT id, show FROM named ORDER BY id;
+--! update_named NamedUpdate(price?)
+UPDATE named SET name = :name, price = :price, show = :show WHERE id = :id;
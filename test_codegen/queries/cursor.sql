@@ -0,0 +1,2 @@
+--! numbers : Number()
+SELECT g AS n, 'number ' || g AS label FROM generate_series(1, :count::int) g;
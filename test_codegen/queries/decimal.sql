@@ -0,0 +1,13 @@
+--! insert_invoice_line
+INSERT INTO invoice_line (label, amount) VALUES (:label, :amount);
+
+--! invoice_total
+-- :arity one
+SELECT coalesce(sum(amount), 0) AS total FROM invoice_line;
+
+--! invoice_amounts
+SELECT array_agg(amount ORDER BY label) AS amounts FROM invoice_line;
+
+--! nan_amount
+-- :arity opt
+SELECT 'NaN'::NUMERIC AS amount;
@@ -0,0 +1,5 @@
+--! insert_legacy_user
+INSERT INTO legacy_user (id, nickname) VALUES (:id, :nickname);
+
+--! legacy_users
+SELECT id, nickname FROM legacy_user ORDER BY id;
@@ -0,0 +1,8 @@
+--! insert_document
+INSERT INTO document (id, body, revisions) VALUES (:id, :body, :revisions);
+
+--! document_by_id
+SELECT body, revisions FROM document WHERE id = :id;
+
+--! document_authors
+SELECT body -> 'author' ->> 'name' AS author FROM document ORDER BY id;
@@ -2,6 +2,7 @@
 SELECT txt, json, nb, arr FROM nightmare_domain;
 
 --! insert_nightmare_domain (composite?)
+-- :copy_in
 INSERT INTO nightmare_domain (txt, json, nb, arr, composite) VALUES (:txt, :json, :nb, :arr, :composite);
 
 --! select_nightmare_domain_null: (txt?, json?, nb?, arr?[?], composite?)
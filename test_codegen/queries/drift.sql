@@ -0,0 +1,2 @@
+--! drift_statuses
+SELECT unnest(enum_range(NULL::drift_status)) AS status;
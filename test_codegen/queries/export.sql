@@ -0,0 +1,7 @@
+--! export_books
+-- :copy_out format=csv
+SELECT name, author FROM book ORDER BY name;
+
+--! import_books BookImport(author?)
+-- :copy_in
+INSERT INTO book (name, author) VALUES (:name, :author);
@@ -0,0 +1,5 @@
+--! insert_issue
+INSERT INTO issue_log (issue) VALUES (:issue);
+
+--! issues
+SELECT issue FROM issue_log;
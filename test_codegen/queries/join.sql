@@ -0,0 +1,19 @@
+--! editions_with_publisher
+SELECT e.title, p.name AS publisher
+FROM edition e LEFT JOIN publisher p ON p.id = e.publisher_id
+ORDER BY e.title;
+
+--! publishers_with_edition
+SELECT p.name, e.title
+FROM edition AS e RIGHT OUTER JOIN publisher AS p ON p.id = e.publisher_id
+ORDER BY p.name;
+
+--! editions_and_publishers
+SELECT e.title, p.name
+FROM public.edition e FULL JOIN "publisher" p ON p.id = e.publisher_id
+ORDER BY e.title, p.name;
+
+--! editions_with_mood : (mood?)
+SELECT e.title, p.mood
+FROM edition e LEFT JOIN publisher p ON p.id = e.publisher_id
+ORDER BY e.title;
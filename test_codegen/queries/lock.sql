@@ -0,0 +1 @@
+--! lock job_sync
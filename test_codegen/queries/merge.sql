@@ -0,0 +1,10 @@
+--! merge_account (age?)
+MERGE INTO account a
+USING (SELECT :email::TEXT AS email, :age::INT AS age) s ON a.email = s.email
+WHEN MATCHED THEN UPDATE SET age = s.age
+WHEN NOT MATCHED THEN INSERT (email, age) VALUES (s.email, s.age);
+
+--! merge_account_from_minors
+MERGE INTO account a
+USING (SELECT email, age FROM account WHERE age < 18) s ON a.email = s.email
+WHEN MATCHED AND s.age IS NOT NULL THEN DELETE;
@@ -0,0 +1,14 @@
+--! insert_member
+INSERT INTO member (id, name) VALUES (:id@MemberId, :name);
+
+--! insert_article
+INSERT INTO article (id, member_id, rating) VALUES (:id@ArticleId, :member_id@MemberId, :rating);
+
+--! articles_by_member
+SELECT article.id, article.member_id, member.id AS member, article.rating, member.name
+FROM article JOIN member ON member.id = article.member_id
+WHERE article.member_id = :member_id@MemberId
+ORDER BY article.id;
+
+--! rated_articles
+SELECT id FROM article WHERE rating >= :rating@Rating ORDER BY id;
@@ -1,8 +1,17 @@
 --: Nullity(texts[?], composite?)
 --: NullityParams(texts[?], composite?)
 --: nullity_composite(jsons?[?])
+--: nullity_item(name, description?)
 
 --! new_nullity NullityParams
 INSERT INTO nullity(texts, name, composite) VALUES (:texts, :name, :composite); 
 --! nullity: Nullity
 SELECT * FROM nullity;
+
+--! nullable_ints (ints[?]) : (ints[?])
+SELECT :ints::int4[] AS ints;
+
+--! new_nullity_item (description?)
+INSERT INTO nullity_items(name, description) VALUES (:name, :description);
+--! nullity_items
+SELECT array_agg(ROW(name, description)::nullity_item ORDER BY name) AS items FROM nullity_items;
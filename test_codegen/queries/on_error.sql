@@ -0,0 +1,8 @@
+--! insert_account
+-- :on_error 23505 UniqueViolation
+-- :on_error 23514 CheckViolation
+INSERT INTO account (email, age) VALUES (:email, :age);
+
+--! insert_account_returning
+-- :on_error 23505 UniqueViolation
+INSERT INTO account (email, age) VALUES (:email, :age) RETURNING email;
@@ -11,4 +11,6 @@ SELECT * FROM book WHERE name = ANY (:title);
 UPDATE book SET name = :name WHERE length(name) > 42 AND length(:name) < 42;
 
 --! params_order
-UPDATE imaginary SET c=:c, a=:a, z=:a, r=:c;
\ No newline at end of file
+UPDATE imaginary SET c=:c, a=:a, z=:a, r=:c;
+--! books_page
+SELECT :page_size AS page_size, name FROM book ORDER BY name LIMIT :page_size OFFSET :skipped;
@@ -0,0 +1,8 @@
+--! insert_profile (settings: crate::Settings, previous?: crate::Settings)
+INSERT INTO profile (id, settings, previous) VALUES (:id, :settings, :previous);
+
+--! profile_by_id : (settings: crate::Settings, previous?: crate::Settings)
+SELECT settings, previous FROM profile WHERE id = :id;
+
+--! profile_settings : (settings: crate::Settings)
+SELECT settings FROM profile WHERE settings ->> 'theme' = :theme ORDER BY id;
@@ -0,0 +1,10 @@
+--! insert_booking (stay?)
+INSERT INTO booking (id, seats, quota, price, slot, span, stay, shifts)
+VALUES (:id, :seats, :quota, :price, :slot, :span, :stay, :shifts);
+
+--! bookings : (stay?)
+-- :allow_naive slot
+SELECT * FROM booking ORDER BY id;
+
+--! bookings_overlapping
+SELECT id FROM booking WHERE seats && :seats ORDER BY id;
@@ -0,0 +1,18 @@
+--! adult_accounts
+-- :budget 50ms
+SELECT email FROM account WHERE age >= 18;
+
+--! accounts_older_than
+-- :budget 2s
+WITH older AS (SELECT * FROM account WHERE age > :age)
+SELECT email FROM older;
+
+--! update_account_age
+UPDATE account SET age = :age WHERE email = :email;
+
+--! delete_account_returning
+WITH deleted AS (DELETE FROM account WHERE email = :email RETURNING email)
+SELECT email FROM deleted;
+
+--! lock_account
+SELECT email FROM account WHERE email = :email FOR UPDATE;
@@ -0,0 +1,2 @@
+--! record_pair: (pair record(first: int4, second?: text, tags: text[]))
+SELECT ROW(1, NULL::text, ARRAY['a', 'b']) AS pair;
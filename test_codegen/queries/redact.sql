@@ -0,0 +1,8 @@
+--: UserSecret()
+
+--! insert_user_secret
+INSERT INTO user_secret (id, login, password_hash, credential)
+VALUES (:id, :login, :password_hash, :credential);
+
+--! user_secrets : UserSecret
+SELECT * FROM user_secret ORDER BY id;
@@ -0,0 +1,9 @@
+--! create_pending_book
+-- :setup
+CREATE TEMPORARY TABLE pending_book (name TEXT NOT NULL);
+
+--! insert_pending_book
+INSERT INTO pending_book (name) VALUES (:name);
+
+--! pending_books
+SELECT name FROM pending_book ORDER BY name;
@@ -1,10 +1,12 @@
 --! select_everything: Everything()
+-- :allow_naive timestamp_ timestamp_without_time_zone_
 SELECT
     *
 FROM
     Everything;
 
 --! select_everything_null: EverythingNull(bool_?, boolean_?, char_?, smallint_?, int2_?, smallserial_?, serial2_?, int_?, int4_?, serial_?, serial4_?, bingint_?, int8_?, bigserial_?, serial8_?, float4_?, real_?, float8_?, double_precision_?, text_?, varchar_?, bytea_?, timestamp_?, timestamp_without_time_zone_?, timestamptz_?, timestamp_with_time_zone_?, date_?, time_?, json_?, jsonb_?, uuid_?, inet_?, macaddr_?, numeric_?)
+-- :allow_naive timestamp_ timestamp_without_time_zone_
 SELECT
     *
 FROM
@@ -15,12 +17,14 @@ INSERT INTO Everything (bool_, boolean_, char_, smallint_, int2_, smallserial_,
     VALUES (:bool_, :boolean_, :char_, :smallint_, :int2_, :smallserial_, :serial2_, :int_, :int4_, :serial_, :serial4_, :bingint_, :int8_, :bigserial_, :serial8_, :float4_, :real_, :float8_, :double_precision_, :text_, :varchar_, :bytea_, :timestamp_, :timestamp_without_time_zone_, :timestamptz_, :timestamp_with_time_zone_, :date_, :time_, :json_, :jsonb_, :uuid_, :inet_, :macaddr_, :numeric_);
 
 --! select_everything_array: EverythingArray()
+-- :allow_naive timestamp_ timestamp_without_time_zone_
 SELECT
     *
 FROM
     EverythingArray;
 
 --! select_everything_array_null: EverythingArrayNull(bool_?, boolean_?, char_?, smallint_?, int2_?, int_?, int4_?, bingint_?, int8_?, float4_?, real_?, float8_?, double_precision_?, text_?, varchar_?, bytea_?, timestamp_?, timestamp_without_time_zone_?, timestamptz_?, timestamp_with_time_zone_?, date_?, time_?, json_?, jsonb_?, uuid_?, inet_?, macaddr_?, numeric_?)
+-- :allow_naive timestamp_ timestamp_without_time_zone_
 SELECT
     *
 FROM
@@ -0,0 +1,8 @@
+--! insert_meeting
+INSERT INTO meeting (title, starts_at) VALUES (:title, :starts_at);
+
+--! meetings : Meeting()
+SELECT title, starts_at FROM meeting ORDER BY starts_at;
+
+--! meetings_after
+SELECT title FROM meeting WHERE starts_at > :after ORDER BY starts_at;
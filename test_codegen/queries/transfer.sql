@@ -0,0 +1,18 @@
+-- Moves an amount from a wallet to another, both updates being committed together
+-- transaction
+
+--! debit_wallet
+UPDATE wallet SET balance = balance - :amount WHERE id = :from_id;
+
+--! credit_wallet
+UPDATE wallet SET balance = balance + :amount WHERE id = :to_id;
+
+--! debited_balance
+-- :arity one
+SELECT balance FROM wallet WHERE id = :id;
+
+--! rename_wallet
+UPDATE wallet SET owner = :owner WHERE id = :id;
+
+--! wallets : Wallet()
+SELECT id, owner, balance FROM wallet ORDER BY id;
@@ -0,0 +1,11 @@
+--! insert_book_unprepared (author?)
+-- :prepared false
+INSERT INTO book (author, name) VALUES (:author, :name);
+
+--! books_by_author_unprepared (author?) : (author?)
+-- :prepared false
+SELECT * FROM book WHERE author IS NOT DISTINCT FROM :author ORDER BY name;
+
+--! find_books_unprepared : (author?)
+-- :prepared false
+SELECT * FROM book WHERE name = ANY (:title);
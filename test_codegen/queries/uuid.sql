@@ -0,0 +1,11 @@
+--! insert_ticket
+INSERT INTO ticket (id, seat) VALUES (:id, :seat) RETURNING id;
+
+--! new_ticket
+INSERT INTO ticket (seat) VALUES (:seat) RETURNING id;
+
+--! ticket_by_id : Ticket()
+SELECT id, seat FROM ticket WHERE id = :id;
+
+--! tickets_by_ids
+SELECT id FROM ticket WHERE id = ANY(:ids) ORDER BY seat;
@@ -0,0 +1,15 @@
+--! upsert_account @pg>=15 (age?)
+MERGE INTO account a
+USING (SELECT :email::TEXT AS email, :age::INT AS age) s ON a.email = s.email
+WHEN MATCHED THEN UPDATE SET age = s.age
+WHEN NOT MATCHED THEN INSERT (email, age) VALUES (s.email, s.age);
+
+--! upsert_account @pg<15 (age?)
+INSERT INTO account (email, age) VALUES (:email, :age)
+ON CONFLICT (email) DO UPDATE SET age = EXCLUDED.age;
+
+--! upsert_statement @pg<15
+SELECT 'INSERT ... ON CONFLICT' AS statement;
+
+--! upsert_statement @pg>=15
+SELECT 'MERGE' AS statement;
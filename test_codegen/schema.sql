@@ -79,6 +79,16 @@ CREATE TABLE nullity (
     composite nullity_composite
 );
 
+CREATE TYPE nullity_item AS (
+    name TEXT,
+    description TEXT
+);
+
+CREATE TABLE nullity_items (
+    name TEXT NOT NULL,
+    description TEXT
+);
+
 -- Params
 
 CREATE TABLE Book (
@@ -200,3 +210,165 @@ CREATE TABLE Syntax (
     async syntax_composite,
     enum syntax_enum
 );
+
+-- Errors
+
+CREATE TABLE account (
+    email TEXT PRIMARY KEY,
+    age INT CHECK (age >= 0)
+);
+
+-- Join
+
+CREATE TYPE mood AS ENUM ('cheerful', 'grumpy');
+
+CREATE TABLE publisher (
+    id INT PRIMARY KEY,
+    name TEXT NOT NULL,
+    mood mood NOT NULL DEFAULT 'cheerful'
+);
+
+CREATE TABLE edition (
+    title TEXT NOT NULL,
+    publisher_id INT
+);
+
+-- Newtype
+
+CREATE DOMAIN rating AS SMALLINT CHECK (VALUE BETWEEN 0 AND 5);
+
+CREATE TABLE member (
+    id INT PRIMARY KEY,
+    name TEXT NOT NULL
+);
+
+CREATE TABLE article (
+    id INT PRIMARY KEY,
+    member_id INT NOT NULL,
+    rating rating NOT NULL
+);
+
+-- Redact
+
+CREATE TYPE credential AS (
+    provider TEXT,
+    secret_token TEXT
+);
+
+CREATE TABLE user_secret (
+    id INT PRIMARY KEY,
+    login TEXT NOT NULL,
+    password_hash TEXT NOT NULL,
+    credential credential NOT NULL
+);
+
+-- Attributes
+
+CREATE TABLE contact (
+    name TEXT NOT NULL,
+    email TEXT
+);
+
+-- Enum drift
+
+CREATE TYPE drift_status AS ENUM ('active', 'retired');
+
+-- Deprecation
+
+CREATE TABLE legacy_user (
+    id INT PRIMARY KEY,
+    nickname TEXT NOT NULL
+);
+COMMENT ON COLUMN legacy_user.nickname IS 'DEPRECATED: users are identified by their id';
+
+-- Ranges
+
+CREATE TABLE booking (
+    id INT PRIMARY KEY,
+    seats int4range NOT NULL,
+    quota int8range NOT NULL,
+    price numrange NOT NULL,
+    slot tsrange NOT NULL,
+    span tstzrange NOT NULL,
+    stay daterange,
+    shifts int4range[] NOT NULL
+);
+
+-- Uuid
+
+CREATE TABLE ticket (
+    id uuid PRIMARY KEY DEFAULT gen_random_uuid(),
+    seat INT NOT NULL
+);
+
+-- Schema annotation
+
+CREATE SCHEMA analytics;
+
+CREATE TYPE analytics.device AS ENUM ('desktop', 'mobile');
+
+CREATE TABLE analytics.visit (
+    page TEXT NOT NULL,
+    device analytics.device NOT NULL
+);
+
+-- Time zones
+
+CREATE TABLE meeting (
+    title TEXT NOT NULL,
+    starts_at TIMESTAMPTZ NOT NULL
+);
+
+-- Transaction
+
+CREATE TABLE wallet (
+    id INT PRIMARY KEY,
+    owner TEXT NOT NULL UNIQUE,
+    balance INT NOT NULL CHECK (balance >= 0)
+);
+
+-- Decimal
+
+CREATE TABLE invoice_line (
+    label TEXT NOT NULL,
+    amount NUMERIC(12, 2) NOT NULL
+);
+
+-- Serde
+
+CREATE TYPE issue_state AS ENUM ('Open', 'in-progress', 'done');
+
+CREATE TYPE issue_owner AS (
+    name TEXT,
+    mood mood
+);
+
+CREATE TYPE issue AS (
+    title TEXT,
+    state issue_state,
+    owner issue_owner
+);
+
+CREATE TABLE issue_log (
+    issue issue NOT NULL
+);
+
+-- Json
+
+CREATE TABLE document (
+    id INT PRIMARY KEY,
+    body JSONB NOT NULL,
+    revisions JSONB[] NOT NULL
+);
+
+CREATE TABLE profile (
+    id INT PRIMARY KEY,
+    settings JSONB NOT NULL,
+    previous JSON
+);
+
+CREATE TABLE blob (
+    id INT PRIMARY KEY,
+    name TEXT NOT NULL,
+    data BYTEA NOT NULL
+);
@@ -1,6 +1,8 @@
 mod cornucopia;
 
-use ::cornucopia_sync::IterSql;
+use ::cornucopia_sync::{
+    BatchResult, BuilderError, EnumDrift, IterSql, LabelDrift, Range, RangeBound,
+};
 
 use eui48::MacAddress;
 use postgres::{Client, Config, NoTls};
@@ -8,33 +10,112 @@ use rust_decimal::Decimal;
 use serde_json::Value;
 use std::{
     borrow::Cow,
-    collections::HashMap,
+    collections::{HashMap, HashSet},
     net::{IpAddr, Ipv4Addr},
 };
 use time::{OffsetDateTime, PrimitiveDateTime};
 use uuid::Uuid;
 
 use crate::cornucopia::{
+    cache::QueryCache,
     queries::{
+        analytics::{
+            self,
+            sync::{insert_visit, pages_by_device, sorted_visits, visits},
+            Visit,
+        },
+        arity::sync::{book_by_name, count_books, insert_publisher_once},
+        attributes::{
+            sync::{contacts, insert_contact},
+            Contact, ContactParams,
+        },
+        batch::sync::purge_accounts,
+        blob::{
+            self,
+            sync::{attachment_by_id, blob_by_id, insert_blob},
+            BlobByIdBorrowed,
+        },
+        cache::{
+            sync::{
+                cached_books_by_author_cached, cached_count_books_cached, cached_find_books_cached,
+            },
+            BooksByAuthorCached,
+        },
+        conversion::{
+            sync::{named_details, named_summaries, update_named},
+            NamedDetail, NamedSummary, NamedUpdate,
+        },
         copy::sync::{insert_clone, insert_copy, select_copy},
+        cursor::{sync::numbers, Number},
+        decimal::sync::{insert_invoice_line, invoice_amounts, invoice_total, nan_amount},
+        deprecation::sync::{insert_legacy_user, legacy_users},
+        document::{
+            sync::{document_authors, document_by_id, insert_document},
+            DocumentById,
+        },
         domain::{
             sync::{
-                insert_nightmare_domain, select_nightmare_domain, select_nightmare_domain_null,
+                copy_in_insert_nightmare_domain, insert_nightmare_domain, select_nightmare_domain,
+                select_nightmare_domain_null,
             },
             InsertNightmareDomainParams, SelectNightmareDomain, SelectNightmareDomainNull,
         },
+        drift::sync::drift_statuses,
+        export::{
+            sync::{copy_in_import_books, copy_out_export_books},
+            BookImport,
+        },
+        issue::sync::{insert_issue, issues},
+        join::{
+            sync::{
+                editions_and_publishers, editions_with_mood, editions_with_publisher,
+                publishers_with_edition,
+            },
+            EditionsAndPublishers, EditionsWithMood, EditionsWithPublisher, PublishersWithEdition,
+        },
+        lock::{
+            sync::{lock_job_sync, try_lock_job_sync, try_xact_lock_job_sync, unlock_job_sync},
+            JOB_SYNC_LOCK_KEY,
+        },
+        merge::sync::{merge_account, merge_account_from_minors},
         named::sync::{
             named, named_by_id, named_complex, new_named_complex, new_named_hidden,
             new_named_visible,
         },
-        named::{Named, NamedComplex, NamedComplexParams, NamedParams},
-        nullity::sync::{new_nullity, nullity},
+        named::{Id, Named, NamedComplex, NamedComplexParams, NamedParams},
+        newtype::{
+            sync::{articles_by_member, insert_article, insert_member, rated_articles},
+            ArticlesByMember,
+        },
+        nullity::sync::{new_nullity, new_nullity_item, nullable_ints, nullity, nullity_items},
         nullity::{Nullity, NullityParams},
+        on_error::{
+            sync::{insert_account, insert_account_returning, InsertAccountStmt},
+            InsertAccountError, InsertAccountReturningError,
+        },
         params::sync::insert_book,
         params::{
-            sync::{find_books, params_use_twice, select_book},
-            SelectBook,
+            sync::{books_page, find_books, params_use_twice, select_book},
+            BooksPage, SelectBook,
         },
+        profile::{
+            sync::{insert_profile, profile_by_id, profile_settings},
+            ProfileById,
+        },
+        r#record::{sync::record_pair, RecordPairPair},
+        range::{
+            sync::{bookings, bookings_overlapping, insert_booking},
+            Bookings,
+        },
+        read_only::sync::{
+            AccountsOlderThanStmt, AdultAccountsStmt, DeleteAccountReturningStmt, LockAccountStmt,
+            UpdateAccountAgeStmt,
+        },
+        redact::{
+            sync::{insert_user_secret, user_secrets},
+            InsertUserSecretParams,
+        },
+        setup::sync::{create_pending_book, insert_pending_book, pending_books},
         stress::{
             sync::{
                 insert_everything, insert_everything_array, insert_nightmare, select_everything,
@@ -46,26 +127,75 @@ use crate::cornucopia::{
             sync::{r#typeof, tricky_sql10},
             TrickySql10Params,
         },
+        timezone::{
+            sync::{insert_meeting, meetings, meetings_after},
+            Meeting,
+        },
+        transfer::{
+            sync::run_in_transaction, CreditWalletParams, DebitWalletParams, RenameWalletParams,
+            TransactionParams, Wallet,
+        },
+        unprepared::{
+            sync::{books_by_author_unprepared, find_books_unprepared, insert_book_unprepared},
+            BooksByAuthorUnprepared, FindBooksUnprepared,
+        },
+        uuid::{
+            sync::{insert_ticket, new_ticket, ticket_by_id, tickets_by_ids},
+            Ticket,
+        },
+        variant::sync::{upsert_account, upsert_statement},
     },
+    two_phase::{
+        is_valid_gid,
+        sync::{commit_prepared, prepare_transaction, rollback_prepared, TwoPhaseError},
+    },
+    types::analytics::Device,
     types::public::{
-        CloneCompositeBorrowed, CopyComposite, CustomComposite, CustomCompositeBorrowed,
-        DomainComposite, DomainCompositeParams, EnumWithDot, NamedComposite,
-        NamedCompositeBorrowed, NamedCompositeWithDot, NightmareComposite,
-        NightmareCompositeParams, NullityComposite, NullityCompositeParams, SpongebobCharacter,
-        SyntaxComposite, SyntaxEnum,
+        CloneCompositeBorrowed, CopyComposite, CredentialBorrowed, CustomComposite,
+        CustomCompositeBorrowed, DomainComposite, DomainCompositeParams, DriftStatus, EnumWithDot,
+        Issue, IssueBorrowed, IssueOwner, IssueState, Mood, NamedComposite, NamedCompositeBorrowed,
+        NamedCompositeWithDot, NightmareComposite, NightmareCompositeParams, NullityComposite,
+        NullityCompositeParams, NullityItem, SpongebobCharacter, SyntaxComposite, SyntaxEnum,
     },
+    types::{MemberId, Rating},
 };
 use cornucopia_sync::Params;
 
-pub fn main() {
-    let client = &mut Config::new()
+/// Settings of a profile stored as `jsonb`, provided to the generated code by its path
+#[derive(Debug, Clone, PartialEq, serde::Serialize, serde::Deserialize)]
+pub struct Settings {
+    pub theme: String,
+    pub notifications: bool,
+    pub shortcuts: Vec<String>,
+}
+
+/// Newtype of `article.id`, provided to the generated code by its path
+#[derive(
+    Debug,
+    Clone,
+    Copy,
+    PartialEq,
+    serde::Serialize,
+    serde::Deserialize,
+    postgres_types::FromSql,
+    postgres_types::ToSql,
+)]
+#[postgres(transparent)]
+pub struct ArticleId(pub i32);
+
+fn connect() -> Client {
+    Config::new()
         .user("postgres")
         .password("postgres")
         .host("127.0.0.1")
         .port(5435)
         .dbname("postgres")
         .connect(NoTls)
-        .unwrap();
+        .unwrap()
+}
+
+pub fn main() {
+    let client = &mut connect();
     test_copy(client);
     test_params(client);
     test_named(client);
@@ -74,6 +204,42 @@ pub fn main() {
     test_domain(client);
     test_trait_sql(client);
     test_keyword_escaping(client);
+    test_arity(client);
+    test_copy_out(client);
+    test_copy_in(client);
+    test_setup(client);
+    test_on_error(client);
+    test_read_only();
+    test_budget();
+    test_batch(client);
+    test_join(client);
+    test_merge(client);
+    test_variant(client);
+    test_two_phase(client);
+    test_enum_drift(client);
+    test_record(client);
+    test_lock(client);
+    test_unprepared(client);
+    test_newtype(client);
+    test_redact(client);
+    test_serde(client);
+    test_cache(client);
+    test_row_conversions(client);
+    test_param_conversions(client);
+    test_attributes(client);
+    test_deprecation(client);
+    test_range(client);
+    test_params_builder(client);
+    test_cursor(client);
+    test_uuid(client);
+    test_schema(client);
+    test_timezone(client);
+    test_transaction(client);
+    test_decimal(client);
+    test_json(client);
+    test_typed_json(client);
+    test_bytea(client);
+    test_display();
 }
 
 pub fn test_params(client: &mut Client) {
@@ -102,6 +268,15 @@ pub fn test_params(client: &mut Client) {
             }
         ]
     );
+    // Row counts are bound as `i64`, even when first used in another context
+    let page_size: i64 = 1;
+    assert_eq!(
+        books_page().bind(client, &page_size, &1).all().unwrap(),
+        &[BooksPage {
+            page_size: 1,
+            name: "Necronomicon".into()
+        }]
+    );
     params_use_twice().bind(client, &"name").unwrap();
 }
 
@@ -170,6 +345,32 @@ pub fn test_nullity(client: &mut Client) {
             texts: vec![Some("Hello".to_string()), Some("world".to_string()), None],
         }
     );
+    assert_eq!(
+        nullable_ints()
+            .bind(client, &vec![Some(1), None])
+            .one()
+            .unwrap(),
+        vec![Some(1), None]
+    );
+    new_nullity_item()
+        .bind(client, &"plain", &None::<&str>)
+        .unwrap();
+    new_nullity_item()
+        .bind(client, &"described", &Some("A description"))
+        .unwrap();
+    assert_eq!(
+        nullity_items().bind(client).one().unwrap(),
+        vec![
+            NullityItem {
+                name: "described".to_string(),
+                description: Some("A description".to_string()),
+            },
+            NullityItem {
+                name: "plain".to_string(),
+                description: None,
+            },
+        ]
+    );
 }
 
 pub fn test_named(client: &mut Client) {
@@ -350,6 +551,13 @@ pub fn test_domain(client: &mut Client) {
     );
     let actual = select_nightmare_domain().bind(client).one().unwrap();
     assert_eq!(expected, actual);
+    // Erased domains expose the methods of their inner type
+    let actual = select_nightmare_domain()
+        .bind(client)
+        .map(|row| (row.txt.to_uppercase(), row.nb.checked_add(1)))
+        .one()
+        .unwrap();
+    assert_eq!(("HELLO WORLD".to_string(), Some(43)), actual);
     let expected = SelectNightmareDomainNull {
         arr: Some(vec![Some(json.clone())]),
         json: Some(json.clone()),
@@ -364,14 +572,20 @@ pub fn test_domain(client: &mut Client) {
     };
     let actual = select_nightmare_domain_null().bind(client).one().unwrap();
     assert_eq!(expected, actual);
+    // Domains are erased when copied in too
+    let mut transaction = client.transaction().unwrap();
+    assert_eq!(
+        copy_in_insert_nightmare_domain(&mut transaction, [&params, &params]).unwrap(),
+        2
+    );
 }
 
 // Test hard cases
 pub fn test_stress(client: &mut Client) {
-    let primitive_datetime_format =
-        time::format_description::parse("[year]-[month]-[day] [hour]:[minute]:[second]").unwrap();
-    let primitive_datetime =
-        PrimitiveDateTime::parse("2020-01-02 03:04:05", &primitive_datetime_format).unwrap();
+    let primitive_datetime = PrimitiveDateTime::new(
+        time::Date::from_calendar_date(2020, time::Month::January, 2).unwrap(),
+        time::Time::from_hms(3, 4, 5).unwrap(),
+    );
     let offset_datetime = OffsetDateTime::parse(
         "1985-04-12T23:20:50.52Z",
         &time::format_description::well_known::Rfc3339,
@@ -570,3 +784,1426 @@ pub fn test_keyword_escaping(client: &mut Client) {
     tricky_sql10().params(client, &params).unwrap();
     r#typeof().bind(client).all().unwrap();
 }
+
+pub fn test_arity(client: &mut Client) {
+    let mut transaction = client.transaction().unwrap();
+    let client = &mut transaction;
+    for name in ["Dune", "Dune", "Emma"] {
+        insert_book().bind(client, &None::<&str>, &name).unwrap();
+    }
+    let count = count_books().bind(client).one().unwrap();
+
+    // One row
+    assert_eq!(
+        book_by_name().bind(client, &"Emma").one().unwrap().name,
+        "Emma"
+    );
+    assert_eq!(
+        book_by_name()
+            .bind(client, &"Emma")
+            .opt()
+            .unwrap()
+            .unwrap()
+            .name,
+        "Emma"
+    );
+    // No row
+    assert!(book_by_name().bind(client, &"Ulysses").one().is_err());
+    assert_eq!(book_by_name().bind(client, &"Ulysses").opt().unwrap(), None);
+    // More than one row
+    assert!(book_by_name().bind(client, &"Dune").one().is_err());
+    assert!(book_by_name().bind(client, &"Dune").opt().is_err());
+    // Failed fetches must not have consumed or altered any row
+    assert_eq!(count_books().bind(client).one().unwrap(), count);
+
+    // Inserts skipping conflicts return no row on conflict
+    let insert = |client: &mut postgres::Transaction, name: &str| {
+        insert_publisher_once()
+            .bind(client, &100, &name)
+            .opt()
+            .unwrap()
+    };
+    assert_eq!(insert(client, "Ace"), Some("Ace".to_string()));
+    assert_eq!(insert(client, "Tor"), None);
+}
+
+pub fn test_copy_out(client: &mut Client) {
+    let mut transaction = client.transaction().unwrap();
+    let client = &mut transaction;
+    client.batch_execute("DELETE FROM book").unwrap();
+    insert_book()
+        .bind(client, &Some("Jane Austen"), &"Emma")
+        .unwrap();
+    insert_book()
+        .bind(client, &None::<&str>, &"Beowulf, retold")
+        .unwrap();
+
+    let mut buf = Vec::new();
+    let written = copy_out_export_books(client, &mut buf).unwrap();
+    assert_eq!(written, buf.len() as u64);
+    assert_eq!(
+        String::from_utf8(buf).unwrap(),
+        "name,author\n\"Beowulf, retold\",\nEmma,Jane Austen\n"
+    );
+}
+
+pub fn test_copy_in(client: &mut Client) {
+    let mut transaction = client.transaction().unwrap();
+    let client = &mut transaction;
+    client.batch_execute("DELETE FROM book").unwrap();
+    let books = [
+        BookImport {
+            name: "Emma",
+            author: Some("Jane Austen"),
+        },
+        BookImport {
+            name: "Beowulf, retold",
+            author: None,
+        },
+    ];
+    assert_eq!(copy_in_import_books(client, &books).unwrap(), 2);
+    let none: [BookImport<&str, &str>; 0] = [];
+    assert_eq!(copy_in_import_books(client, &none).unwrap(), 0);
+    assert_eq!(
+        select_book().bind(client).all().unwrap(),
+        &[
+            SelectBook {
+                author: Some("Jane Austen".into()),
+                name: "Emma".into()
+            },
+            SelectBook {
+                author: None,
+                name: "Beowulf, retold".into()
+            }
+        ]
+    );
+}
+
+pub fn test_setup(client: &mut Client) {
+    let mut transaction = client.transaction().unwrap();
+    let client = &mut transaction;
+    create_pending_book().bind(client).unwrap();
+    insert_pending_book().bind(client, &"Middlemarch").unwrap();
+    insert_pending_book().bind(client, &"Dracula").unwrap();
+    assert_eq!(
+        pending_books().bind(client).all().unwrap(),
+        vec!["Dracula".to_string(), "Middlemarch".to_string()]
+    );
+}
+
+pub fn test_on_error(client: &mut Client) {
+    assert_eq!(
+        insert_account()
+            .bind(client, &"ada@example.com", &36)
+            .unwrap(),
+        1
+    );
+    // Handled error codes
+    let err = insert_account()
+        .bind(client, &"ada@example.com", &37)
+        .unwrap_err();
+    assert!(matches!(err, InsertAccountError::UniqueViolation(_)));
+    assert!(err.to_string().contains("duplicate key"));
+    assert!(matches!(
+        insert_account().bind(client, &"bob@example.com", &-1),
+        Err(InsertAccountError::CheckViolation(_))
+    ));
+    assert!(matches!(
+        insert_account_returning()
+            .bind(client, &"ada@example.com", &36)
+            .one(),
+        Err(InsertAccountReturningError::UniqueViolation(_))
+    ));
+    // Unhandled error codes
+    let err = insert_account_returning()
+        .bind(client, &"bob@example.com", &-1)
+        .opt()
+        .unwrap_err();
+    assert!(matches!(err, InsertAccountReturningError::Other(_)));
+    let err: postgres::Error = err.into();
+    assert_eq!(
+        err.code(),
+        Some(&postgres::error::SqlState::CHECK_VIOLATION)
+    );
+    // Successful query
+    assert_eq!(
+        insert_account_returning()
+            .bind(client, &"bob@example.com", &41)
+            .one()
+            .unwrap(),
+        "bob@example.com"
+    );
+}
+
+pub fn test_read_only() {
+    let read_only = [
+        AdultAccountsStmt::IS_READ_ONLY,
+        AccountsOlderThanStmt::IS_READ_ONLY,
+    ];
+    assert_eq!(read_only, [true, true]);
+    let writing = [
+        InsertAccountStmt::IS_READ_ONLY,
+        UpdateAccountAgeStmt::IS_READ_ONLY,
+        DeleteAccountReturningStmt::IS_READ_ONLY,
+        LockAccountStmt::IS_READ_ONLY,
+    ];
+    assert_eq!(writing, [false; 4]);
+}
+
+pub fn test_budget() {
+    let budgets = [
+        AdultAccountsStmt::BUDGET_MS,
+        AccountsOlderThanStmt::BUDGET_MS,
+        InsertAccountStmt::BUDGET_MS,
+    ];
+    assert_eq!(budgets, [Some(50), Some(2000), None]);
+}
+
+pub fn test_batch(client: &mut Client) {
+    let mut transaction = client.transaction().unwrap();
+    let client = &mut transaction;
+    client.batch_execute("DELETE FROM account").unwrap();
+    client
+        .batch_execute(
+            "INSERT INTO account (email, age) VALUES
+                ('minor@example.com', 12),
+                ('unknown@example.com', NULL),
+                ('adult@example.com', 42)",
+        )
+        .unwrap();
+    assert_eq!(
+        purge_accounts().bind(client).unwrap(),
+        [
+            BatchResult {
+                command: "UPDATE".to_string(),
+                rows: 1
+            },
+            BatchResult {
+                command: "DELETE".to_string(),
+                rows: 2
+            }
+        ]
+    );
+}
+
+pub fn test_join(client: &mut Client) {
+    let mut transaction = client.transaction().unwrap();
+    let client = &mut transaction;
+    client
+        .batch_execute(
+            "INSERT INTO publisher (id, name, mood) VALUES (1, 'Gallimard', 'grumpy'), (2, 'Minuit', 'cheerful');
+            INSERT INTO edition (title, publisher_id) VALUES ('Folio', 1), ('Samizdat', NULL);",
+        )
+        .unwrap();
+    assert_eq!(
+        editions_with_publisher().bind(client).all().unwrap(),
+        [
+            EditionsWithPublisher {
+                title: "Folio".to_string(),
+                publisher: Some("Gallimard".to_string()),
+            },
+            EditionsWithPublisher {
+                title: "Samizdat".to_string(),
+                publisher: None,
+            },
+        ]
+    );
+    assert_eq!(
+        publishers_with_edition().bind(client).all().unwrap(),
+        [
+            PublishersWithEdition {
+                name: "Gallimard".to_string(),
+                title: Some("Folio".to_string()),
+            },
+            PublishersWithEdition {
+                name: "Minuit".to_string(),
+                title: None,
+            },
+        ]
+    );
+    assert_eq!(
+        editions_and_publishers().bind(client).all().unwrap(),
+        [
+            EditionsAndPublishers {
+                title: Some("Folio".to_string()),
+                name: Some("Gallimard".to_string()),
+            },
+            EditionsAndPublishers {
+                title: Some("Samizdat".to_string()),
+                name: None,
+            },
+            EditionsAndPublishers {
+                title: None,
+                name: Some("Minuit".to_string()),
+            },
+        ]
+    );
+    assert_eq!(
+        editions_with_mood().bind(client).all().unwrap(),
+        [
+            EditionsWithMood {
+                title: "Folio".to_string(),
+                mood: Some(Mood::grumpy),
+            },
+            EditionsWithMood {
+                title: "Samizdat".to_string(),
+                mood: None,
+            },
+        ]
+    );
+}
+
+pub fn test_merge(client: &mut Client) {
+    let mut transaction = client.transaction().unwrap();
+    let client = &mut transaction;
+    client.batch_execute("DELETE FROM account").unwrap();
+    // Insert then update through the same upsert
+    assert_eq!(
+        1,
+        merge_account()
+            .bind(client, &"minor@example.com", &Some(12))
+            .unwrap()
+    );
+    assert_eq!(
+        1,
+        merge_account()
+            .bind(client, &"unknown@example.com", &None)
+            .unwrap()
+    );
+    assert_eq!(
+        1,
+        merge_account()
+            .bind(client, &"unknown@example.com", &Some(16))
+            .unwrap()
+    );
+    assert_eq!(2, merge_account_from_minors().bind(client).unwrap());
+    let remaining: i64 = client
+        .query_one("SELECT count(*) FROM account", &[])
+        .unwrap()
+        .get(0);
+    assert_eq!(0, remaining);
+}
+
+pub fn test_variant(client: &mut Client) {
+    let mut transaction = client.transaction().unwrap();
+    let client = &mut transaction;
+    client.batch_execute("DELETE FROM account").unwrap();
+    // The variant matching the server is selected at runtime
+    let version: i32 = client
+        .query_one("SELECT current_setting('server_version_num')::int", &[])
+        .unwrap()
+        .get(0);
+    let expected = if version >= 150000 {
+        "MERGE"
+    } else {
+        "INSERT ... ON CONFLICT"
+    };
+    assert_eq!(expected, upsert_statement().bind(client).one().unwrap());
+    assert_eq!(
+        1,
+        upsert_account()
+            .bind(client, &"variant@example.com", &None)
+            .unwrap()
+    );
+    assert_eq!(
+        1,
+        upsert_account()
+            .bind(client, &"variant@example.com", &Some(30))
+            .unwrap()
+    );
+    let age: Option<i32> = client
+        .query_one(
+            "SELECT age FROM account WHERE email = 'variant@example.com'",
+            &[],
+        )
+        .unwrap()
+        .get(0);
+    assert_eq!(Some(30), age);
+}
+
+pub fn test_two_phase(client: &mut Client) {
+    let prepared = |client: &mut Client, gid: &str| -> i64 {
+        client
+            .query_one(
+                "SELECT count(*) FROM pg_prepared_xacts WHERE gid = $1",
+                &[&gid],
+            )
+            .unwrap()
+            .get(0)
+    };
+    let age = |client: &mut Client, email: &str| -> Option<Option<i32>> {
+        client
+            .query_opt("SELECT age FROM account WHERE email = $1", &[&email])
+            .unwrap()
+            .map(|row| row.get(0))
+    };
+    client.batch_execute("DELETE FROM account").unwrap();
+
+    // Identifiers are embedded in the statements
+    assert!(is_valid_gid("cornucopia-2pc:1"));
+    assert!(!is_valid_gid("it's"));
+    assert!(!is_valid_gid(""));
+    assert!(matches!(
+        commit_prepared(client, "it's"),
+        Err(TwoPhaseError::InvalidGid(_))
+    ));
+
+    // Prepared then committed
+    let inserted = prepare_transaction(client, "cornucopia-2pc:1", |client| {
+        merge_account().bind(client, &"prepared@example.com", &Some(42))
+    })
+    .unwrap();
+    assert_eq!(1, inserted);
+    assert_eq!(1, prepared(client, "cornucopia-2pc:1"));
+    assert_eq!(None, age(client, "prepared@example.com"));
+    commit_prepared(client, "cornucopia-2pc:1").unwrap();
+    assert_eq!(0, prepared(client, "cornucopia-2pc:1"));
+    assert_eq!(Some(Some(42)), age(client, "prepared@example.com"));
+
+    // Prepared then rolled back
+    prepare_transaction(client, "cornucopia-2pc:2", |client| {
+        merge_account().bind(client, &"rolled@example.com", &None)
+    })
+    .unwrap();
+    assert_eq!(1, prepared(client, "cornucopia-2pc:2"));
+    rollback_prepared(client, "cornucopia-2pc:2").unwrap();
+    assert_eq!(0, prepared(client, "cornucopia-2pc:2"));
+    assert_eq!(None, age(client, "rolled@example.com"));
+
+    // Not prepared when the queries fail
+    let failed = prepare_transaction(client, "cornucopia-2pc:3", |client| {
+        merge_account().bind(client, &"invalid@example.com", &Some(-1))
+    });
+    assert!(matches!(failed, Err(TwoPhaseError::Failed(_))));
+    assert_eq!(0, prepared(client, "cornucopia-2pc:3"));
+    assert_eq!(None, age(client, "invalid@example.com"));
+
+    client.batch_execute("DELETE FROM account").unwrap();
+}
+
+pub fn test_record(client: &mut Client) {
+    assert_eq!(
+        RecordPairPair {
+            first: 1,
+            second: None,
+            tags: vec!["a".to_string(), "b".to_string()],
+        },
+        record_pair().bind(client).one().unwrap()
+    );
+
+    // Nested records don't match the declared fields
+    let row = client
+        .query_one("SELECT ROW(1, ROW(2, 'x'), ARRAY['a'])", &[])
+        .unwrap();
+    let err = row.try_get::<_, RecordPairPair>(0).unwrap_err();
+    assert_eq!(
+        "the field `second` of the record of the column `pair` is of type `record`, while `text` is declared",
+        std::error::Error::source(&err).unwrap().to_string()
+    );
+    // As do records with other fields
+    let row = client.query_one("SELECT ROW(1, 'x')", &[]).unwrap();
+    let err = row.try_get::<_, RecordPairPair>(0).unwrap_err();
+    assert_eq!(
+        "the record of the column `pair` has 2 fields, while 3 are declared",
+        std::error::Error::source(&err).unwrap().to_string()
+    );
+}
+
+pub fn test_lock(client: &mut Client) {
+    // FNV-1a 64-bit hash of `job_sync`, which must never change
+    assert_eq!(5830332076885472630, JOB_SYNC_LOCK_KEY);
+    let other = &mut connect();
+
+    // Held by the session until released
+    lock_job_sync(client).unwrap();
+    assert!(!try_lock_job_sync(other).unwrap());
+    assert!(unlock_job_sync(client).unwrap());
+    assert!(!unlock_job_sync(client).unwrap());
+    assert!(try_lock_job_sync(other).unwrap());
+    assert!(!try_lock_job_sync(client).unwrap());
+    assert!(unlock_job_sync(other).unwrap());
+
+    // Held until the end of the transaction
+    let mut transaction = client.transaction().unwrap();
+    assert!(try_xact_lock_job_sync(&mut transaction).unwrap());
+    assert!(!try_lock_job_sync(other).unwrap());
+    transaction.commit().unwrap();
+    assert!(try_lock_job_sync(other).unwrap());
+    assert!(unlock_job_sync(other).unwrap());
+}
+
+pub fn test_enum_drift(client: &mut Client) {
+    assert_eq!(2, DriftStatus::LABEL_COUNT);
+    DriftStatus::verify(client).unwrap();
+    assert_eq!(
+        vec![DriftStatus::active, DriftStatus::retired],
+        drift_statuses().bind(client).all().unwrap()
+    );
+
+    // A label added after code generation
+    client
+        .batch_execute("ALTER TYPE drift_status ADD VALUE 'archived'")
+        .unwrap();
+    let Err(EnumDrift::Labels(drift)) = DriftStatus::verify(client) else {
+        panic!("the added label is not reported")
+    };
+    assert_eq!(
+        LabelDrift {
+            enum_name: "public.drift_status".to_string(),
+            missing: vec![],
+            extra: vec!["archived".to_string()],
+        },
+        drift
+    );
+    // Decoding the added label fails with the enum and the label
+    let row = client
+        .query_one("SELECT 'archived'::drift_status", &[])
+        .unwrap();
+    let err = row.try_get::<_, DriftStatus>(0).unwrap_err();
+    assert!(std::error::Error::source(&err)
+        .unwrap()
+        .to_string()
+        .starts_with("unknown label `archived` of the enum `public.drift_status`"));
+
+    // A label removed after code generation, by recreating the enum without it
+    client
+        .batch_execute("DROP TYPE drift_status; CREATE TYPE drift_status AS ENUM ('active')")
+        .unwrap();
+    let Err(EnumDrift::Labels(drift)) = DriftStatus::verify(client) else {
+        panic!("the removed label is not reported")
+    };
+    assert_eq!(
+        LabelDrift {
+            enum_name: "public.drift_status".to_string(),
+            missing: vec!["retired".to_string()],
+            extra: vec![],
+        },
+        drift
+    );
+
+    client
+        .batch_execute(
+            "DROP TYPE drift_status; CREATE TYPE drift_status AS ENUM ('active', 'retired')",
+        )
+        .unwrap();
+}
+
+pub fn test_unprepared(client: &mut Client) {
+    let mut transaction = client.transaction().unwrap();
+    let client = &mut transaction;
+    client.batch_execute("DELETE FROM book").unwrap();
+    let prepared_count = |client: &mut postgres::Transaction| -> i64 {
+        client
+            .query_one("SELECT count(*) FROM pg_prepared_statements", &[])
+            .unwrap()
+            .get(0)
+    };
+    let before = prepared_count(client);
+
+    let mut insert = insert_book_unprepared();
+    insert.bind(client, &Some("Orwell"), &"1984").unwrap();
+    insert.bind(client, &None::<&str>, &"Beowulf").unwrap();
+    insert
+        .bind(client, &Some("Orwell"), &"Animal Farm")
+        .unwrap();
+    let mut by_author = books_by_author_unprepared();
+    assert_eq!(
+        by_author.bind(client, &Some("Orwell")).all().unwrap(),
+        [
+            BooksByAuthorUnprepared {
+                name: "1984".to_string(),
+                author: Some("Orwell".to_string()),
+            },
+            BooksByAuthorUnprepared {
+                name: "Animal Farm".to_string(),
+                author: Some("Orwell".to_string()),
+            },
+        ]
+    );
+    assert_eq!(
+        by_author.bind(client, &None::<&str>).one().unwrap(),
+        BooksByAuthorUnprepared {
+            name: "Beowulf".to_string(),
+            author: None,
+        }
+    );
+    assert_eq!(by_author.bind(client, &Some("Homer")).opt().unwrap(), None);
+    let mut find = find_books_unprepared();
+    assert_eq!(
+        find.bind(client, &["Beowulf", "Odyssey"].as_slice())
+            .all()
+            .unwrap(),
+        [FindBooksUnprepared {
+            name: "Beowulf".to_string(),
+            author: None,
+        }]
+    );
+
+    // No statement is prepared nor cached by unprepared queries
+    assert_eq!(before, prepared_count(client));
+}
+
+// Newtypes of different columns are distinct types: this trait could not be
+// implemented for both if `MemberId` and `ArticleId` were the same type
+trait DistinctId {
+    fn raw(self) -> i32;
+}
+impl DistinctId for MemberId {
+    fn raw(self) -> i32 {
+        self.0
+    }
+}
+impl DistinctId for ArticleId {
+    fn raw(self) -> i32 {
+        self.0
+    }
+}
+
+pub fn test_newtype(client: &mut Client) {
+    let mut transaction = client.transaction().unwrap();
+    let client = &mut transaction;
+    let member = MemberId(1);
+    insert_member().bind(client, &member, &"Ada").unwrap();
+    insert_article()
+        .bind(client, &ArticleId(10), &member, &Rating(4))
+        .unwrap();
+    insert_article()
+        .bind(client, &ArticleId(11), &member, &Rating(2))
+        .unwrap();
+    assert_eq!(
+        articles_by_member().bind(client, &member).all().unwrap(),
+        [
+            ArticlesByMember {
+                id: ArticleId(10),
+                member_id: member,
+                member,
+                rating: Rating(4),
+                name: "Ada".to_string(),
+            },
+            ArticlesByMember {
+                id: ArticleId(11),
+                member_id: member,
+                member,
+                rating: Rating(2),
+                name: "Ada".to_string(),
+            },
+        ]
+    );
+    // Parameters compared to a domain have its base type, and opt into its newtype
+    let rated: Vec<ArticleId> = rated_articles().bind(client, &Rating(3)).all().unwrap();
+    assert_eq!(rated, [ArticleId(10)]);
+    assert_eq!((member.raw(), rated[0].raw()), (1, 10));
+    // Values of the domain are still checked by the database
+    assert!(insert_article()
+        .bind(client, &ArticleId(12), &member, &Rating(6))
+        .is_err());
+}
+
+pub fn test_redact(client: &mut Client) {
+    let mut transaction = client.transaction().unwrap();
+    let client = &mut transaction;
+    let params = InsertUserSecretParams {
+        id: 1,
+        login: "ada",
+        password_hash: "$argon2id$hash",
+        credential: CredentialBorrowed {
+            provider: "github",
+            secret_token: "gho_token",
+        },
+    };
+    // Redacted fields of params, rows and composites are hidden from their debug output
+    assert_eq!(
+        format!("{params:?}"),
+        "InsertUserSecretParams { id: 1, login: \"ada\", password_hash: \"***\", \
+         credential: CredentialBorrowed { provider: \"github\", secret_token: \"***\" } }"
+    );
+    insert_user_secret().params(client, &params).unwrap();
+    let secrets = user_secrets().bind(client).all().unwrap();
+    assert_eq!(
+        format!("{:?}", secrets[0]),
+        "UserSecret { id: 1, login: \"ada\", password_hash: \"***\", \
+         credential: Credential { provider: \"github\", secret_token: \"***\" } }"
+    );
+    // And from their display output
+    assert_eq!(
+        secrets[0].to_string(),
+        "id=1, login=ada, password_hash=***, \
+         credential=Credential { provider: \"github\", secret_token: \"***\" }"
+    );
+    // Values are left untouched
+    assert_eq!(secrets[0].password_hash, "$argon2id$hash");
+    assert_eq!(secrets[0].credential.secret_token, "gho_token");
+}
+
+pub fn test_serde(client: &mut Client) {
+    // Params arrive as JSON request bodies
+    let params: NamedParams<String> =
+        serde_json::from_str(r#"{"name": "from json", "price": 12.5}"#).unwrap();
+    let id = new_named_visible()
+        .params(client, &params)
+        .one()
+        .unwrap()
+        .id;
+    let row = named_by_id().bind(client, &id).one().unwrap();
+    assert_eq!(row.name, "from json");
+    // Rows are sent back as JSON
+    let json = serde_json::to_string(&row).unwrap();
+    assert_eq!(serde_json::from_str::<Named>(&json).unwrap(), row);
+
+    // Composites are borrowed from the request body
+    let body = r#"{"named": {"wow": "from json", "such_cool": 7}, "named_with_dot": null}"#;
+    let params: NamedComplexParams = serde_json::from_str(body).unwrap();
+    assert_eq!(params.named.wow, Some("from json"));
+    new_named_complex().params(client, &params).unwrap();
+    assert!(named_complex()
+        .bind(client)
+        .all()
+        .unwrap()
+        .contains(&NamedComplex {
+            named: NamedComposite {
+                wow: Some("from json".into()),
+                such_cool: Some(7),
+            },
+            named_with_dot: None,
+        }));
+
+    // Enums are (de)serialized as their postgres labels, in nested composites as well
+    let mut transaction = client.transaction().unwrap();
+    let body =
+        r#"{"title": "login", "state": "in-progress", "owner": {"name": "ann", "mood": "grumpy"}}"#;
+    let params: IssueBorrowed = serde_json::from_str(body).unwrap();
+    assert_eq!(params.state, IssueState::in_progress);
+    insert_issue().bind(&mut transaction, &params).unwrap();
+    let issue = issues().bind(&mut transaction).one().unwrap();
+    assert_eq!(
+        issue,
+        Issue {
+            title: "login".to_string(),
+            state: IssueState::in_progress,
+            owner: IssueOwner {
+                name: "ann".to_string(),
+                mood: Mood::grumpy,
+            },
+        }
+    );
+    assert_eq!(
+        serde_json::to_string(&issue).unwrap(),
+        r#"{"title":"login","state":"in-progress","owner":{"name":"ann","mood":"grumpy"}}"#
+    );
+    assert_eq!(
+        serde_json::to_string(&IssueState::Open).unwrap(),
+        r#""Open""#
+    );
+    assert_eq!(
+        serde_json::from_str::<IssueState>(r#""done""#).unwrap(),
+        IssueState::done
+    );
+    transaction.rollback().unwrap();
+}
+
+pub fn test_cache(client: &mut Client) {
+    let mut transaction = client.transaction().unwrap();
+    let client = &mut transaction;
+    client.batch_execute("DELETE FROM book").unwrap();
+    insert_book()
+        .bind(client, &Some("Orwell"), &"1984")
+        .unwrap();
+    insert_book()
+        .bind(client, &None::<&str>, &"Beowulf")
+        .unwrap();
+    let cache = QueryCache::new();
+
+    let orwell = cached_books_by_author_cached(&cache, client, &Some("Orwell")).unwrap();
+    assert_eq!(
+        orwell,
+        [BooksByAuthorCached {
+            name: "1984".to_string(),
+            author: Some("Orwell".to_string()),
+        }]
+    );
+    assert_eq!(cached_count_books_cached(&cache, client).unwrap(), 2);
+    assert_eq!(
+        cached_find_books_cached(&cache, client, &["Beowulf", "1984"].as_slice())
+            .unwrap()
+            .len(),
+        2
+    );
+
+    // Results are served from the cache until they expire
+    insert_book()
+        .bind(client, &Some("Orwell"), &"Animal Farm")
+        .unwrap();
+    assert_eq!(
+        cached_books_by_author_cached(&cache, client, &Some("Orwell")).unwrap(),
+        orwell
+    );
+    assert_eq!(cached_count_books_cached(&cache, client).unwrap(), 2);
+    // Equal parameters share their result, whatever their type
+    assert_eq!(
+        cached_books_by_author_cached(&cache, client, &Some("Orwell".to_string())).unwrap(),
+        orwell
+    );
+    // Other parameters are cached separately
+    assert_eq!(
+        cached_books_by_author_cached(&cache, client, &None::<&str>)
+            .unwrap()
+            .len(),
+        1
+    );
+    assert_eq!(
+        cached_find_books_cached(&cache, client, &vec!["Animal Farm"])
+            .unwrap()
+            .len(),
+        1
+    );
+
+    // Cleared results are fetched again
+    cache.clear();
+    assert_eq!(
+        cached_books_by_author_cached(&cache, client, &Some("Orwell"))
+            .unwrap()
+            .len(),
+        2
+    );
+    assert_eq!(cached_count_books_cached(&cache, client).unwrap(), 3);
+}
+
+pub fn test_row_conversions(client: &mut Client) {
+    let mut transaction = client.transaction().unwrap();
+    let client = &mut transaction;
+    for (name, price) in [("first", Some(1.5)), ("second", None)] {
+        new_named_visible()
+            .params(client, &NamedParams { name, price })
+            .one()
+            .unwrap();
+    }
+
+    // Rows are converted to the rows having a subset of their fields
+    let details = named_details().bind(client).all().unwrap();
+    let summaries = named_summaries().bind(client).all().unwrap();
+    assert_eq!(
+        details.iter().map(NamedSummary::from).collect::<Vec<_>>(),
+        summaries
+    );
+    assert_eq!(
+        details
+            .into_iter()
+            .map(NamedSummary::from)
+            .collect::<Vec<_>>(),
+        summaries
+    );
+    let detail = NamedDetail {
+        id: 1,
+        name: "third".to_string(),
+        price: None,
+        show: true,
+    };
+    assert_eq!(
+        NamedSummary::from(detail),
+        NamedSummary {
+            id: 1,
+            name: "third".to_string(),
+        }
+    );
+    let named = named().bind(client).all().unwrap();
+    assert_eq!(
+        named.iter().map(Id::from).collect::<Vec<_>>(),
+        named
+            .into_iter()
+            .map(|it| Id { id: it.id })
+            .collect::<Vec<_>>()
+    );
+}
+
+pub fn test_param_conversions(client: &mut Client) {
+    let mut transaction = client.transaction().unwrap();
+    let client = &mut transaction;
+    let Id { id } = new_named_visible()
+        .params(
+            client,
+            &NamedParams {
+                name: "first",
+                price: Some(1.5),
+            },
+        )
+        .one()
+        .unwrap();
+    let load = |client: &mut postgres::Transaction| {
+        let details = named_details().bind(client).all().unwrap();
+        details.into_iter().find(|it| it.id == id).unwrap()
+    };
+
+    // A loaded row is modified and saved back using the params borrowing its fields
+    let mut detail = load(client);
+    detail.name.push_str(" edition");
+    detail.price = None;
+    detail.show = false;
+    assert_eq!(
+        update_named()
+            .params(client, &NamedUpdate::from(&detail))
+            .unwrap(),
+        1
+    );
+    assert_eq!(load(client), detail);
+}
+
+pub fn test_attributes(client: &mut Client) {
+    let mut transaction = client.transaction().unwrap();
+    let client = &mut transaction;
+    // Params attributes rename the fields of the request body
+    let params: ContactParams<&str, &str> =
+        serde_json::from_str(r#"{"full_name": "Ada", "email": null}"#).unwrap();
+    insert_contact().params(client, &params).unwrap();
+    insert_contact()
+        .bind(client, &"Bob", &Some("bob@example.com"))
+        .unwrap();
+
+    // Row attributes rename the fields and skip the missing ones
+    let contacts = contacts().bind(client).all().unwrap();
+    assert_eq!(
+        contacts,
+        &[
+            Contact {
+                name: "Ada".to_string(),
+                email: None,
+            },
+            Contact {
+                name: "Bob".to_string(),
+                email: Some("bob@example.com".to_string()),
+            },
+        ]
+    );
+    assert_eq!(
+        serde_json::to_string(&contacts).unwrap(),
+        r#"[{"name":"Ada"},{"name":"Bob","mail":"bob@example.com"}]"#
+    );
+}
+
+pub fn test_deprecation(client: &mut Client) {
+    let mut transaction = client.transaction().unwrap();
+    let client = &mut transaction;
+    insert_legacy_user().bind(client, &1, &"ada").unwrap();
+    let ids = legacy_users().bind(client).map(|it| it.id).all().unwrap();
+    assert_eq!(ids, [1]);
+    // Reading the field of the deprecated column is warned about
+    #[allow(deprecated)]
+    let nickname = legacy_users()
+        .bind(client)
+        .map(|it| it.nickname.to_string())
+        .one()
+        .unwrap();
+    assert_eq!(nickname, "ada");
+}
+
+pub fn test_range(client: &mut Client) {
+    let mut transaction = client.transaction().unwrap();
+    let client = &mut transaction;
+    let day = |d| time::Date::from_calendar_date(2024, time::Month::March, d).unwrap();
+    let slot = Range::new(
+        RangeBound::Inclusive(PrimitiveDateTime::new(day(1), time::Time::MIDNIGHT)),
+        RangeBound::Unbounded,
+    );
+    let span = Range::new(
+        RangeBound::Inclusive(OffsetDateTime::UNIX_EPOCH),
+        RangeBound::Exclusive(OffsetDateTime::UNIX_EPOCH + time::Duration::HOUR),
+    );
+    let shifts = [
+        Range::new(RangeBound::Inclusive(8), RangeBound::Exclusive(12)),
+        Range::Empty,
+    ];
+    insert_booking()
+        .bind(
+            client,
+            &1,
+            &Range::new(RangeBound::Inclusive(1), RangeBound::Inclusive(4)),
+            &Range::new(RangeBound::Unbounded, RangeBound::Exclusive(100)),
+            &Range::new(
+                RangeBound::Exclusive(Decimal::new(995, 2)),
+                RangeBound::Inclusive(Decimal::new(20, 0)),
+            ),
+            &slot,
+            &span,
+            &Some(Range::new(
+                RangeBound::Inclusive(day(1)),
+                RangeBound::Exclusive(day(8)),
+            )),
+            &shifts.as_slice(),
+        )
+        .unwrap();
+    insert_booking()
+        .bind(
+            client,
+            &2,
+            &Range::Empty,
+            &Range::new(RangeBound::Unbounded, RangeBound::Unbounded),
+            &Range::Empty,
+            &slot,
+            &span,
+            &None,
+            &[].as_slice(),
+        )
+        .unwrap();
+    // Discrete ranges are canonicalized by the database
+    assert_eq!(
+        bookings().bind(client).all().unwrap(),
+        [
+            Bookings {
+                id: 1,
+                seats: Range::new(RangeBound::Inclusive(1), RangeBound::Exclusive(5)),
+                quota: Range::new(RangeBound::Unbounded, RangeBound::Exclusive(100)),
+                price: Range::new(
+                    RangeBound::Exclusive(Decimal::new(995, 2)),
+                    RangeBound::Inclusive(Decimal::new(20, 0)),
+                ),
+                slot,
+                span,
+                stay: Some(Range::new(
+                    RangeBound::Inclusive(day(1)),
+                    RangeBound::Exclusive(day(8)),
+                )),
+                shifts: shifts.to_vec(),
+            },
+            Bookings {
+                id: 2,
+                seats: Range::Empty,
+                quota: Range::new(RangeBound::Unbounded, RangeBound::Unbounded),
+                price: Range::Empty,
+                slot,
+                span,
+                stay: None,
+                shifts: vec![],
+            }
+        ]
+    );
+    let overlapping = bookings_overlapping()
+        .bind(
+            client,
+            &Range::new(RangeBound::Inclusive(4), RangeBound::Unbounded),
+        )
+        .all()
+        .unwrap();
+    assert_eq!(overlapping, [1]);
+}
+
+pub fn test_params_builder(client: &mut Client) {
+    let mut transaction = client.transaction().unwrap();
+    let client = &mut transaction;
+    // The nullable composite defaults to `None`
+    let params = NullityParams::builder()
+        .name("Jane Doe")
+        .texts([Some("Hi")].as_slice())
+        .build()
+        .unwrap();
+    new_nullity().params(client, &params).unwrap();
+    let rows = nullity().bind(client).all().unwrap();
+    let row = rows.iter().find(|it| it.name == "Jane Doe").unwrap();
+    assert_eq!(row.composite, None);
+    // The non-nullable fields must be set
+    let err = NullityParams::<&str, &[Option<&str>], &str>::builder()
+        .name("Jane Doe")
+        .build()
+        .unwrap_err();
+    assert_eq!(
+        err,
+        BuilderError {
+            params: "NullityParams",
+            field: "texts",
+        }
+    );
+    assert_eq!(
+        err.to_string(),
+        "the field `texts` of `NullityParams` was not set"
+    );
+}
+
+pub fn test_cursor(client: &mut Client) {
+    let mut transaction = client.transaction().unwrap();
+    let mut stmt = numbers();
+    let mut cursor = stmt.bind(&mut transaction, &10).cursor().unwrap();
+    let chunks: Vec<_> = std::iter::from_fn(|| Some(cursor.next_chunk(4).unwrap()))
+        .take_while(|it| !it.is_empty())
+        .collect();
+    assert_eq!(chunks.iter().map(Vec::len).collect::<Vec<_>>(), [4, 4, 2]);
+    assert_eq!(
+        chunks[2][1],
+        Number {
+            n: 10,
+            label: "number 10".to_string(),
+        }
+    );
+    cursor.close().unwrap();
+
+    // Cursors can be mapped like any query
+    let mut cursor = stmt
+        .bind(&mut transaction, &3)
+        .map(|it| it.n)
+        .cursor()
+        .unwrap();
+    assert_eq!(cursor.next_chunk(10).unwrap(), [1, 2, 3]);
+    drop(cursor);
+    transaction.commit().unwrap();
+}
+
+pub fn test_uuid(client: &mut Client) {
+    let mut transaction = client.transaction().unwrap();
+    let id = Uuid::parse_str("67e55044-10b1-426f-9247-bb680e5fe0c8").unwrap();
+    assert_eq!(
+        insert_ticket()
+            .bind(&mut transaction, &id, &1)
+            .one()
+            .unwrap(),
+        id
+    );
+    assert_eq!(
+        ticket_by_id().bind(&mut transaction, &id).one().unwrap(),
+        Ticket { id, seat: 1 }
+    );
+
+    // Keys generated by the database are read back as uuids
+    let generated = new_ticket().bind(&mut transaction, &2).one().unwrap();
+    assert_ne!(generated, id);
+    assert_eq!(
+        tickets_by_ids()
+            .bind(&mut transaction, &[generated, id].as_slice())
+            .all()
+            .unwrap(),
+        [id, generated]
+    );
+
+    // Rows free of floating point numbers and JSON values are hashable
+    let tickets: HashSet<Ticket> = [id, generated, id]
+        .iter()
+        .map(|it| ticket_by_id().bind(&mut transaction, it).one().unwrap())
+        .collect();
+    assert_eq!(tickets.len(), 2);
+    assert!(tickets.contains(&Ticket { id, seat: 1 }));
+    transaction.rollback().unwrap();
+}
+
+pub fn test_schema(client: &mut Client) {
+    let mut transaction = client.transaction().unwrap();
+    // The queries of the module are executed with its schema first in the search path
+    transaction
+        .batch_execute(&format!(
+            "SET LOCAL search_path TO {}, public",
+            analytics::SCHEMA
+        ))
+        .unwrap();
+    for (page, device) in [
+        ("/pricing", Device::mobile),
+        ("/home", Device::mobile),
+        ("/docs", Device::desktop),
+        ("/home", Device::desktop),
+    ] {
+        insert_visit()
+            .bind(&mut transaction, &page, &device)
+            .unwrap();
+    }
+    assert_eq!(
+        pages_by_device()
+            .bind(&mut transaction, &Device::mobile)
+            .all()
+            .unwrap(),
+        ["/home", "/pricing"]
+    );
+
+    // Rows are ordered like postgres orders them, enums in their declaration order
+    let mut rows = visits().bind(&mut transaction).all().unwrap();
+    rows.sort();
+    assert_eq!(rows, sorted_visits().bind(&mut transaction).all().unwrap());
+    assert_eq!(
+        rows[1],
+        Visit {
+            page: "/home".to_string(),
+            device: Device::desktop,
+        }
+    );
+    assert!(Device::desktop < Device::mobile);
+    transaction.rollback().unwrap();
+}
+
+pub fn test_timezone(client: &mut Client) {
+    let mut transaction = client.transaction().unwrap();
+    let rfc3339 = |it: &str| {
+        OffsetDateTime::parse(it, &time::format_description::well_known::Rfc3339).unwrap()
+    };
+    // A meeting starting at 19:30 in Paris
+    let starts_at = rfc3339("2024-05-17T19:30:00+02:00");
+    insert_meeting()
+        .bind(&mut transaction, &"standup", &starts_at)
+        .unwrap();
+
+    // The time zone of the session doesn't change the instant read back
+    transaction
+        .batch_execute("SET LOCAL TIME ZONE 'Asia/Tokyo'")
+        .unwrap();
+    let meeting = meetings().bind(&mut transaction).one().unwrap();
+    assert_eq!(
+        meeting,
+        Meeting {
+            title: "standup".to_string(),
+            starts_at
+        }
+    );
+    assert_eq!(meeting.starts_at, rfc3339("2024-05-18T02:30:00+09:00"));
+    assert_eq!(
+        meetings_after()
+            .bind(&mut transaction, &rfc3339("2024-05-18T02:00:00+09:00"))
+            .all()
+            .unwrap(),
+        ["standup"]
+    );
+    assert!(meetings_after()
+        .bind(&mut transaction, &rfc3339("2024-05-17T17:30:00Z"))
+        .all()
+        .unwrap()
+        .is_empty());
+    transaction.rollback().unwrap();
+}
+
+pub fn test_transaction(client: &mut Client) {
+    client
+        .batch_execute(
+            "INSERT INTO wallet (id, owner, balance) VALUES (1, 'alice', 50), (2, 'bob', 0)",
+        )
+        .unwrap();
+    let transfer = |amount: i32, owner: &'static str| TransactionParams {
+        debit_wallet: DebitWalletParams { amount, from_id: 1 },
+        credit_wallet: CreditWalletParams { amount, to_id: 2 },
+        debited_balance: 1,
+        rename_wallet: RenameWalletParams { owner, id: 2 },
+    };
+
+    // Every query is run in declaration order, then committed
+    let results = run_in_transaction(client, &transfer(30, "robert")).unwrap();
+    assert_eq!(results.debit_wallet, 1);
+    assert_eq!(results.credit_wallet, 1);
+    assert_eq!(results.debited_balance, 20);
+    assert_eq!(results.rename_wallet, 1);
+    let committed = vec![
+        Wallet {
+            id: 1,
+            owner: "alice".to_string(),
+            balance: 20,
+        },
+        Wallet {
+            id: 2,
+            owner: "robert".to_string(),
+            balance: 30,
+        },
+    ];
+    assert_eq!(results.wallets, committed);
+
+    // The first failing query rolls back the previous ones
+    let err = run_in_transaction(client, &transfer(30, "bobby")).unwrap_err();
+    assert_eq!(
+        err.code(),
+        Some(&postgres::error::SqlState::CHECK_VIOLATION)
+    );
+    let err = run_in_transaction(client, &transfer(10, "alice")).unwrap_err();
+    assert_eq!(
+        err.code(),
+        Some(&postgres::error::SqlState::UNIQUE_VIOLATION)
+    );
+    let results = run_in_transaction(client, &transfer(0, "robert")).unwrap();
+    assert_eq!(results.wallets, committed);
+    client.batch_execute("DELETE FROM wallet").unwrap();
+}
+
+pub fn test_decimal(client: &mut Client) {
+    let mut transaction = client.transaction().unwrap();
+    // Amounts that floats cannot represent exactly are summed exactly
+    for (label, amount) in [("a", "0.10"), ("b", "0.20"), ("c", "1234567890.05")] {
+        let amount: Decimal = amount.parse().unwrap();
+        insert_invoice_line()
+            .bind(&mut transaction, &label, &amount)
+            .unwrap();
+    }
+    assert_eq!(
+        invoice_total().bind(&mut transaction).one().unwrap(),
+        "1234567890.35".parse::<Decimal>().unwrap()
+    );
+    assert_eq!(
+        invoice_amounts().bind(&mut transaction).one().unwrap(),
+        [
+            Decimal::new(10, 2),
+            Decimal::new(20, 2),
+            Decimal::new(123456789005, 2)
+        ]
+    );
+
+    // A NaN cannot be read as a decimal, which is reported instead of panicking
+    let err = nan_amount().bind(&mut transaction).opt().unwrap_err();
+    assert!(err.to_string().contains("error deserializing column 0"));
+    transaction.rollback().unwrap();
+}
+
+pub fn test_json(client: &mut Client) {
+    let mut transaction = client.transaction().unwrap();
+    let body = serde_json::json!({
+        "title": "Release notes",
+        "author": {"name": "Ann", "roles": ["editor", "reviewer"]},
+        "sections": [{"heading": "Fixes", "items": 3}],
+    });
+    let revisions = [
+        serde_json::json!({"version": 1, "draft": true}),
+        serde_json::json!({"version": 2, "draft": false}),
+    ];
+    insert_document()
+        .bind(&mut transaction, &1, &body, &revisions.as_slice())
+        .unwrap();
+
+    // Nested documents read back as values, arrays of them as vectors
+    let document = document_by_id().bind(&mut transaction, &1).one().unwrap();
+    assert_eq!(
+        document,
+        DocumentById {
+            body: body.clone(),
+            revisions: revisions.to_vec(),
+        }
+    );
+    assert_eq!(document.body["author"]["roles"][1], "reviewer");
+    assert_eq!(document.body["sections"][0]["items"], 3);
+    assert_eq!(document.revisions[1]["draft"], false);
+    assert_eq!(
+        document_authors().bind(&mut transaction).all().unwrap(),
+        ["Ann"]
+    );
+
+    // Borrowed rows hold the raw documents, parsed only when mapped
+    let title = document_by_id()
+        .bind(&mut transaction, &1)
+        .map(|it| serde_json::from_str::<Value>(it.body.0.get()).unwrap()["title"].take())
+        .one()
+        .unwrap();
+    assert_eq!(title, "Release notes");
+
+    // Rows containing documents are serialized as nested JSON
+    let json = serde_json::to_value(&document).unwrap();
+    assert_eq!(json["body"]["author"]["name"], "Ann");
+    assert_eq!(json["revisions"][0]["version"], 1);
+    transaction.rollback().unwrap();
+}
+
+pub fn test_typed_json(client: &mut Client) {
+    let mut transaction = client.transaction().unwrap();
+    let dark = Settings {
+        theme: "dark".to_string(),
+        notifications: true,
+        shortcuts: vec!["ctrl+k".to_string(), "ctrl+p".to_string()],
+    };
+    let light = Settings {
+        theme: "light".to_string(),
+        notifications: false,
+        shortcuts: vec![],
+    };
+    insert_profile()
+        .bind(&mut transaction, &1, &&dark, &Some(&light))
+        .unwrap();
+    insert_profile()
+        .bind(&mut transaction, &2, &&light, &None)
+        .unwrap();
+
+    // Declared types are deserialized from the documents, absent ones being `NULL`
+    assert_eq!(
+        profile_by_id().bind(&mut transaction, &1).one().unwrap(),
+        ProfileById {
+            settings: dark.clone(),
+            previous: Some(light.clone()),
+        }
+    );
+    assert_eq!(
+        profile_by_id().bind(&mut transaction, &2).one().unwrap(),
+        ProfileById {
+            settings: light.clone(),
+            previous: None,
+        }
+    );
+    let previous_is_null: bool = transaction
+        .query_one("SELECT previous IS NULL FROM profile WHERE id = 2", &[])
+        .unwrap()
+        .get(0);
+    assert!(previous_is_null);
+    assert_eq!(
+        profile_settings()
+            .bind(&mut transaction, &"dark")
+            .all()
+            .unwrap(),
+        [dark]
+    );
+
+    // A document not matching the declared type is reported as an error
+    transaction
+        .batch_execute("INSERT INTO profile (id, settings) VALUES (3, '{\"theme\": 42}')")
+        .unwrap();
+    assert!(profile_by_id().bind(&mut transaction, &3).one().is_err());
+    transaction.rollback().unwrap();
+}
+
+/// Attachment of the domain, converted from and into the rows of `attachment_by_id`
+#[derive(Debug, PartialEq)]
+pub struct Attachment {
+    pub id: i32,
+    pub name: String,
+}
+
+pub fn test_bytea(client: &mut Client) {
+    let mut transaction = client.transaction().unwrap();
+    let data: Vec<u8> = (0..4 * 1024 * 1024)
+        .map(|it: u32| (it % 251) as u8)
+        .collect();
+    insert_blob()
+        .bind(&mut transaction, &1, &"payload", &data.as_slice())
+        .unwrap();
+    fn checksum(bytes: &[u8]) -> u64 {
+        bytes.iter().fold(0u64, |acc, it| {
+            acc.wrapping_mul(31).wrapping_add(u64::from(*it))
+        })
+    }
+
+    // Borrowed rows point into the buffer of the row, the payload is hashed without a copy
+    let (name, len, sum) = blob_by_id()
+        .bind(&mut transaction, &1)
+        .map(|it: BlobByIdBorrowed| (it.name.to_owned(), it.data.len(), checksum(it.data)))
+        .one()
+        .unwrap();
+    assert_eq!((name.as_str(), len), ("payload", data.len()));
+    assert_eq!(sum, checksum(&data));
+
+    // Only the owned conversion allocates a vector
+    let blob = blob_by_id().bind(&mut transaction, &1).one().unwrap();
+    assert_eq!(blob.data, data);
+
+    // Rows declaring a user struct are converted from and into it
+    let attachment: Attachment = attachment_by_id()
+        .bind(&mut transaction, &1)
+        .one()
+        .unwrap()
+        .into();
+    assert_eq!(
+        attachment,
+        Attachment {
+            id: 1,
+            name: "payload".to_string(),
+        }
+    );
+    let row = blob::Attachment::from(attachment);
+    assert_eq!((row.id, row.name.as_str()), (1, "payload"));
+    transaction.rollback().unwrap();
+}
+
+pub fn test_display() {
+    // Fields are displayed in order, nullable ones using their debug output
+    let mut row = Named {
+        id: 1,
+        name: "Ann".to_string(),
+        price: Some(12.5),
+        show: true,
+    };
+    assert_eq!(
+        row.to_string(),
+        "id=1, name=Ann, price=Some(12.5), show=true"
+    );
+    row.price = None;
+    assert_eq!(row.to_string(), "id=1, name=Ann, price=None, show=true");
+}
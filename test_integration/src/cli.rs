@@ -0,0 +1,130 @@
+use std::ffi::OsString;
+
+use owo_colors::OwoColorize;
+
+use crate::{
+    fixtures::{CliTest, TestSuite},
+    utils::{reset_db, Summary},
+};
+
+/// Environment variables read by the CLI, unset before each test
+const CLI_VARS: [&str; 4] = [
+    "DATABASE_URL",
+    "CORNUCOPIA_CONFIG",
+    "CORNUCOPIA_DESTINATION",
+    "CORNUCOPIA_QUERIES_PATH",
+];
+
+/// Run CLI test, stopping at the first failure if `fail_fast`
+pub(crate) fn run_cli_test(
+    client: &mut postgres::Client,
+    apply: bool,
+    fail_fast: bool,
+) -> Result<Summary, Box<dyn std::error::Error>> {
+    let mut summary = Summary::default();
+    let original_pwd = std::env::current_dir().unwrap();
+    let test_suites = TestSuite::<CliTest>::read("fixtures/cli");
+
+    for mut suite in test_suites {
+        println!("{} {}", "[cli]".magenta(), suite.name.magenta());
+        for test in suite.tests.iter_mut() {
+            // Reset db
+            reset_db(client)?;
+            client.batch_execute(&test.schema)?;
+
+            // Generate file tree
+            let temp_dir = tempfile::tempdir()?;
+            for (path, content) in &test.files {
+                let path = temp_dir.path().join(path);
+                std::fs::create_dir_all(path.parent().unwrap())?;
+                std::fs::write(path, content)?;
+            }
+            let cwd = temp_dir
+                .path()
+                .join(test.cwd.as_deref().unwrap_or_default());
+            std::fs::create_dir_all(&cwd)?;
+            std::env::set_current_dir(&cwd)?;
+
+            // The variables loaded by the CLI are process wide, restore them after the test
+            let env: Vec<(OsString, OsString)> = std::env::vars_os().collect();
+            for var in CLI_VARS {
+                std::env::remove_var(var);
+            }
+            for (var, value) in &test.env {
+                std::env::set_var(var, value);
+            }
+
+            // Run the CLI
+            let args = std::iter::once("cornucopia").chain(test.args.iter().map(String::as_str));
+            let got = match cornucopia::run_from(args) {
+                Ok(()) => match &test.generated {
+                    Some(path) => check_generated(path, &test.contains, &test.excludes),
+                    None => String::new(),
+                },
+                Err(err) => err.report(),
+            };
+            restore_env(&env);
+            std::env::set_current_dir(&original_pwd)?;
+
+            let got = got.trim();
+            let successful = got == test.error.trim();
+            summary.record(successful);
+            if successful {
+                println!("{} {}", test.name, "OK".green());
+            } else {
+                println!(
+                    "{} {}\n{}\n{}\n{}\n{}\n",
+                    test.name,
+                    "ERR".red(),
+                    "Expected:".bright_black(),
+                    test.error,
+                    "Got:".bright_black(),
+                    got,
+                );
+            }
+            if apply {
+                test.error = got.into();
+            }
+            if fail_fast && !successful {
+                break;
+            }
+        }
+
+        // Update error message if needed
+        if apply {
+            suite.write()?;
+        }
+        if fail_fast && !summary.is_successful() {
+            break;
+        }
+    }
+
+    Ok(summary)
+}
+
+/// Checks that the file at `path` was generated with the given snippets and without the
+/// excluded ones, returning the failure otherwise
+fn check_generated(path: &str, contains: &[String], excludes: &[String]) -> String {
+    let Ok(content) = std::fs::read_to_string(path) else {
+        return format!("`{path}` was not generated");
+    };
+    if let Some(snippet) = contains.iter().find(|it| !content.contains(it.as_str())) {
+        return format!("`{path}` does not contain `{snippet}`");
+    }
+    match excludes.iter().find(|it| content.contains(it.as_str())) {
+        Some(snippet) => format!("`{path}` contains `{snippet}`"),
+        None => String::new(),
+    }
+}
+
+/// Restores the environment variables to the given ones
+fn restore_env(env: &[(OsString, OsString)]) {
+    for (var, _) in std::env::vars_os() {
+        if !env.iter().any(|(it, _)| *it == var) {
+            std::env::remove_var(var);
+        }
+    }
+    for (var, value) in env {
+        std::env::set_var(var, value);
+    }
+}
@@ -1,96 +1,339 @@
 use crate::{
+    external::{ExternalCli, DATABASE_URL},
     fixtures::{CodegenTest, TestSuite},
-    utils::{reset_db, rustfmt_file, rustfmt_string},
+    utils::{reset_db, rustfmt_file, rustfmt_string, Summary},
 };
 
 use cornucopia::{CodegenSettings, Error};
 use owo_colors::OwoColorize;
-use std::{env::set_current_dir, process::Command};
+use std::{
+    env::set_current_dir,
+    path::{Path, PathBuf},
+    process::Command,
+    time::Instant,
+};
 
-// Run codegen test, return true if all test are successful
+// Run codegen test through the library or the `external` binary, stopping at the first
+// failure if `fail_fast`
 pub(crate) fn run_codegen_test(
     client: &mut postgres::Client,
+    external: Option<&ExternalCli>,
     apply: bool,
-) -> Result<bool, Box<dyn std::error::Error>> {
-    let mut successful = true;
+    fail_fast: bool,
+) -> Result<Summary, Box<dyn std::error::Error>> {
+    let mut summary = Summary::default();
     let original_pwd = std::env::current_dir()?;
     let fixture_path = "fixtures/codegen";
 
     let test_suites = TestSuite::<CodegenTest>::read(fixture_path);
-    for suite in test_suites {
+    'suites: for suite in test_suites {
         println!("{}", format!("[codegen] {}", suite.name).magenta());
         for test in suite.tests {
-            // Reset DB
+            let result = run_codegen_fixture(client, external, &test, apply, &original_pwd);
+            // Move back to original directory
+            std::env::set_current_dir(&original_pwd)?;
+            summary.record(result.is_ok());
+            if let Err(err) = result {
+                println!("{} {}\n{}", test.name, "ERR".red(), err);
+                if fail_fast {
+                    break 'suites;
+                }
+            }
+        }
+    }
+
+    Ok(summary)
+}
+
+/// Generate or check the code of a codegen test case, then run it
+fn run_codegen_fixture(
+    client: &mut postgres::Client,
+    external: Option<&ExternalCli>,
+    test: &CodegenTest,
+    apply: bool,
+    original_pwd: &Path,
+) -> Result<(), Box<dyn std::error::Error>> {
+    // Reset DB
+    reset_db(client)?;
+
+    // Set current dir to test base path
+    set_current_dir(format!("../{}", test.base_path))?;
+
+    // Load schema, or the migrations embedded in the generated code
+    match &test.migrations {
+        Some(dir) => {
+            let mut files = std::fs::read_dir(dir)?
+                .map(|entry| entry.map(|it| it.path()))
+                .collect::<Result<Vec<_>, _>>()?;
+            files.sort();
+            cornucopia::load_schema_with_observer(client, &files, &mut ())?;
+        }
+        None => cornucopia::load_schema_with_observer(client, &["schema.sql"], &mut ())?,
+    }
+
+    // Queries expanded by the macros when building the project have no generated code
+    if !test.macros {
+        check_codegen(client, external, test, apply)?;
+    }
+
+    if test.run {
+        // The run applies the embedded migrations to an empty database
+        if test.migrations.is_some() {
             reset_db(client)?;
+        }
+        // Change current directory
+        std::env::set_current_dir(original_pwd)?;
+        std::env::set_current_dir(format!("../{}", test.base_path))?;
+        let cargo = |subcommand: &str| {
+            let mut cmd = Command::new("cargo");
+            cmd.arg(subcommand);
+            if test.macros {
+                cmd.env("DATABASE_URL", DATABASE_URL);
+            }
+            if !test.features.is_empty() {
+                cmd.args(["--features", &test.features.join(",")]);
+            }
+            cmd.output()
+        };
+        // Build first, so that only the execution is timed
+        let result = cargo("build")?;
+        if !result.status.success() {
+            Err(format!(
+                "(build) {}\n{}",
+                test.name,
+                String::from_utf8_lossy(&result.stderr)
+                    .as_ref()
+                    .bright_black()
+            ))?;
+        }
+        // Run
+        let start = Instant::now();
+        let result = cargo("run")?;
+        let elapsed = start.elapsed().as_secs_f64();
+        if !result.status.success() {
+            Err(format!(
+                "(run) {}\n{}",
+                test.name,
+                String::from_utf8_lossy(&result.stderr)
+                    .as_ref()
+                    .bright_black()
+            ))?;
+        }
+        if let Some(max) = test.max_run_seconds.filter(|max| elapsed > *max) {
+            Err(format!(
+                "(run) {} took {elapsed:.2}s, more than the {max}s allowed",
+                test.name
+            ))?;
+        }
+        println!("(run) {} {} ({elapsed:.2}s)", test.name, "OK".green());
+    }
+    Ok(())
+}
 
-            // Set current dir to test base path
-            set_current_dir(format!("../{}", test.base_path))?;
+/// Generates the code of a codegen test case, or checks it when not applying it
+fn check_codegen(
+    client: &mut postgres::Client,
+    external: Option<&ExternalCli>,
+    test: &CodegenTest,
+    apply: bool,
+) -> Result<(), Box<dyn std::error::Error>> {
+    // If `--apply`, then the code will be regenerated.
+    // Otherwise, it is only checked.
+    if apply {
+        // Generate
+        generate(
+            client,
+            external,
+            test,
+            &test.destination,
+            test.benches.as_deref(),
+        )?;
+        // Format the generated files
+        rustfmt_file(&test.destination);
+        if let Some(benches) = &test.benches {
+            for path in bench_files(benches)? {
+                rustfmt_file(&path);
+            }
+        }
+    } else {
+        // Get currently checked-in generate file
+        let old_codegen = std::fs::read_to_string(&test.destination).unwrap();
+        // Generate new file, in a directory that does not exist yet
+        let out_dir = tempfile::tempdir()?;
+        let out_path = out_dir.path().join("generated/nested/cornucopia.rs");
+        let new_codegen = generate(client, external, test, &out_path, None)?;
+        if std::fs::read_to_string(&out_path)? != new_codegen {
+            Err(format!("\"{}\" was not written", out_path.display()))?;
+        }
+        // Format the generated code string by piping to rustfmt
+        let new_codegen_formatted = rustfmt_string(&new_codegen);
 
-            // Load schema
-            cornucopia::load_schema(client, &["schema.sql"])?;
+        // If the newly generated file differs from
+        // the currently checked in one, return an error.
+        if old_codegen != new_codegen_formatted {
+            Err(format!(
+                "\"{}\" is outdated",
+                test.destination.to_str().unwrap()
+            ))?;
+        }
+    }
+    // Benches are generated next to the generated file, in a temporary copy of the
+    // test layout, then checked the same way
+    if let (Some(benches), false) = (&test.benches, apply) {
+        let out_dir = tempfile::tempdir()?;
+        let out_benches = out_dir.path().join(benches);
+        std::fs::create_dir_all(&out_benches)?;
+        std::fs::copy(
+            benches.join("benches.toml"),
+            out_benches.join("benches.toml"),
+        )?;
+        generate(
+            client,
+            external,
+            test,
+            &out_dir.path().join(&test.destination),
+            Some(&out_benches),
+        )?;
+        let old_files = bench_files(benches)?;
+        let new_files = bench_files(&out_benches)?;
+        if old_files
+            .iter()
+            .map(|it| it.file_name())
+            .ne(new_files.iter().map(|it| it.file_name()))
+        {
+            Err(format!("\"{}\" benches are outdated", benches.display()))?;
+        }
+        for (old, new) in old_files.iter().zip(&new_files) {
+            let new_bench = std::fs::read_to_string(new)?;
+            if std::fs::read_to_string(old)? != rustfmt_string(&new_bench) {
+                Err(format!("\"{}\" is outdated", old.display()))?;
+            }
+        }
+    }
+    // Shared types are generated in their own file, checked the same way
+    if let Some(types_destination) = &test.types_destination {
+        if apply {
+            generate_types(client, external, test, types_destination)?;
+            rustfmt_file(types_destination);
+        } else {
+            let old_types = std::fs::read_to_string(types_destination)?;
+            let out_dir = tempfile::tempdir()?;
+            let new_types =
+                generate_types(client, external, test, &out_dir.path().join("types.rs"))?;
+            if old_types != rustfmt_string(&new_types) {
+                Err(format!("\"{}\" is outdated", types_destination.display()))?;
+            }
+        }
+    }
+    println!("(generate) {} {}", test.name, "OK".green());
 
-            // If `--apply`, then the code will be regenerated.
-            // Otherwise, it is only checked.
-            if apply {
-                // Generate
-                cornucopia::generate_live(
+    if test.offline {
+        // Generating from a snapshot must give the same code as generating live
+        let snapshot_dir = tempfile::tempdir()?;
+        let snapshot_path = snapshot_dir.path().join("snapshot.json");
+        let (live, offline) = match external {
+            Some(external) => {
+                let settings = test.cli_settings();
+                let queries = &test.queries_path;
+                let live_path = snapshot_dir.path().join("live.rs");
+                let live = external.live(&settings, queries, &live_path)?;
+                external.snapshot(&settings, queries, &snapshot_path)?;
+                let offline_path = snapshot_dir.path().join("offline.rs");
+                let offline =
+                    external.offline(&settings, queries, &snapshot_path, &offline_path)?;
+                (live, offline)
+            }
+            None => {
+                let settings = CodegenSettings::from(test);
+                let queries = &test.queries_path;
+                let live = cornucopia::generate_live_with_observer(
                     client,
-                    &test.queries_path,
-                    Some(&test.destination),
-                    CodegenSettings::from(&test),
+                    queries,
+                    None,
+                    settings.clone(),
+                    &mut (),
                 )
                 .map_err(Error::report)?;
-                // Format the generated file
-                rustfmt_file(&test.destination);
-            } else {
-                // Get currently checked-in generate file
-                let old_codegen = std::fs::read_to_string(&test.destination).unwrap();
-                // Generate new file
-                let new_codegen = cornucopia::generate_live(
-                    client,
-                    &test.queries_path,
+                cornucopia::snapshot_live_with_observer(client, queries, &snapshot_path, &mut ())
+                    .map_err(Error::report)?;
+                let offline = cornucopia::generate_offline_with_observer(
+                    queries,
+                    &snapshot_path,
                     None,
-                    CodegenSettings::from(&test),
+                    settings,
+                    &mut (),
                 )
                 .map_err(Error::report)?;
-                // Format the generated code string by piping to rustfmt
-                let new_codegen_formatted = rustfmt_string(&new_codegen);
-
-                // If the newly generated file differs from
-                // the currently checked in one, return an error.
-                if old_codegen != new_codegen_formatted {
-                    Err(format!(
-                        "\"{}\" is outdated",
-                        test.destination.to_str().unwrap()
-                    ))?;
-                }
-            }
-            println!("(generate) {} {}", test.name, "OK".green());
-
-            if test.run {
-                // Change current directory
-                std::env::set_current_dir(&original_pwd)?;
-                std::env::set_current_dir(&format!("../{}", test.base_path))?;
-                // Run
-                let result = Command::new("cargo").arg("run").output()?;
-                if result.status.success() {
-                    println!("(run) {} {}", test.name, "OK".green());
-                } else {
-                    successful = false;
-                    println!(
-                        " {}\n{}",
-                        "ERR".red(),
-                        String::from_utf8_lossy(&result.stderr)
-                            .as_ref()
-                            .bright_black()
-                    );
-                }
+                (live, offline)
             }
+        };
+        if live != offline {
+            Err(format!("offline codegen of \"{}\" differs", test.name))?;
+        }
+        println!("(offline) {} {}", test.name, "OK".green());
+    }
+    Ok(())
+}
 
-            // Move back to original directory
-            std::env::set_current_dir(&original_pwd)?;
+/// Generates the code of the test to `destination`, along with its benches in `benches`,
+/// through the library or the `external` binary
+fn generate(
+    client: &mut postgres::Client,
+    external: Option<&ExternalCli>,
+    test: &CodegenTest,
+    destination: &Path,
+    benches: Option<&Path>,
+) -> Result<String, Box<dyn std::error::Error>> {
+    if let Some(external) = external {
+        let mut settings = test.cli_settings();
+        if let Some(benches) = benches {
+            settings
+                .args
+                .push(format!("--emit-benches={}", benches.display()));
         }
+        return Ok(external.live(&settings, &test.queries_path, destination)?);
     }
+    let settings = match benches {
+        Some(benches) => test.settings_builder().emit_benches(benches).build(),
+        None => CodegenSettings::from(test),
+    };
+    Ok(cornucopia::generate_live_with_observer(
+        client,
+        test.queries_path.as_path(),
+        Some(destination),
+        settings,
+        &mut (),
+    )
+    .map_err(Error::report)?)
+}
+
+/// Generates the shared custom types of the test to `destination`, through the library
+/// or the `external` binary
+fn generate_types(
+    client: &mut postgres::Client,
+    external: Option<&ExternalCli>,
+    test: &CodegenTest,
+    destination: &Path,
+) -> Result<String, Box<dyn std::error::Error>> {
+    if let Some(external) = external {
+        return Ok(external.types(&test.cli_settings(), destination)?);
+    }
+    let settings = CodegenSettings::from(test);
+    Ok(
+        cornucopia::generate_types_live_with_observer(client, Some(destination), settings, &mut ())
+            .map_err(Error::report)?,
+    )
+}
 
-    Ok(successful)
+/// Generated bench files of a directory, sorted by name
+fn bench_files(dir: &Path) -> Result<Vec<PathBuf>, std::io::Error> {
+    let mut files = std::fs::read_dir(dir)?
+        .map(|entry| entry.map(|it| it.path()))
+        .filter(|it| {
+            it.as_ref()
+                .map_or(true, |it| it.extension().is_some_and(|ext| ext == "rs"))
+        })
+        .collect::<Result<Vec<_>, _>>()?;
+    files.sort();
+    Ok(files)
 }
@@ -0,0 +1,93 @@
+use cornucopia::CoverageSettings;
+use owo_colors::OwoColorize;
+
+use crate::{
+    fixtures::{CoverageTest, TestSuite},
+    utils::{reset_db, Summary},
+};
+
+/// Run coverage test, stopping at the first failure if `fail_fast`
+pub(crate) fn run_coverage_test(
+    client: &mut postgres::Client,
+    fail_fast: bool,
+) -> Result<Summary, Box<dyn std::error::Error>> {
+    let mut summary = Summary::default();
+    let original_pwd = std::env::current_dir().unwrap();
+    let test_suites = TestSuite::<CoverageTest>::read("fixtures/coverage");
+
+    'suites: for suite in test_suites {
+        println!("{} {}", "[coverage]".magenta(), suite.name.magenta());
+        for test in suite.tests {
+            // Reset db, including the schemas of other fixtures which would be reported
+            reset_db(client)?;
+            client.batch_execute(&test.schema)?;
+
+            // Generate file tree
+            let temp_dir = tempfile::tempdir()?;
+            std::env::set_current_dir(&temp_dir)?;
+            std::fs::create_dir("queries")?;
+            for (name, module) in &test.modules {
+                std::fs::write(format!("queries/{name}.sql"), module)?;
+            }
+
+            // Check coverage
+            let result = cornucopia::coverage_live_with_observer(
+                client,
+                "queries",
+                &CoverageSettings {
+                    include_views: test.include_views,
+                    include_system_schemas: false,
+                    only_tags: test.only_tags.clone(),
+                    require_budgets: test.require_budgets,
+                },
+                &mut (),
+            );
+            std::env::set_current_dir(&original_pwd)?;
+
+            let (expected, got) = match result {
+                Ok(coverage) => {
+                    let tables = coverage
+                        .tables
+                        .iter()
+                        .map(|it| {
+                            let columns = it.unreferenced_columns.join(", ");
+                            if it.unqueried {
+                                format!("{}.{}: no query ({columns})", it.schema, it.name)
+                            } else {
+                                format!("{}.{}: {columns}", it.schema, it.name)
+                            }
+                        })
+                        .collect::<Vec<_>>()
+                        .join("\n");
+                    match &test.report {
+                        Some(report) => (
+                            format!("{}\n{}", test.tables.join("\n"), report.trim()),
+                            format!("{tables}\n{}", coverage.to_string().trim()),
+                        ),
+                        None => (test.tables.join("\n"), tables),
+                    }
+                }
+                Err(err) => (test.tables.join("\n"), err.report()),
+            };
+            summary.record(expected == got);
+            if expected == got {
+                println!("{} {}", test.name, "OK".green());
+            } else {
+                println!(
+                    "{} {}\n{}\n{}\n{}\n{}\n",
+                    test.name,
+                    "ERR".red(),
+                    "Expected:".bright_black(),
+                    expected,
+                    "Got:".bright_black(),
+                    got,
+                );
+                if fail_fast {
+                    break 'suites;
+                }
+            }
+        }
+    }
+
+    Ok(summary)
+}
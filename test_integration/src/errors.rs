@@ -1,17 +1,23 @@
+use std::path::Path;
+
 use cornucopia::{CodegenSettings, Error};
 use owo_colors::OwoColorize;
 
 use crate::{
+    external::ExternalCli,
     fixtures::{ErrorTest, TestSuite},
-    utils::reset_db,
+    utils::{reset_db, Summary},
 };
 
-/// Run errors test, return true if all test are successful
+/// Run errors test through the library or the `external` binary, stopping at the first
+/// failure if `fail_fast`
 pub(crate) fn run_errors_test(
     client: &mut postgres::Client,
+    external: Option<&ExternalCli>,
     apply: bool,
-) -> Result<bool, Box<dyn std::error::Error>> {
-    let mut successful = true;
+    fail_fast: bool,
+) -> Result<Summary, Box<dyn std::error::Error>> {
+    let mut summary = Summary::default();
     let original_pwd = std::env::current_dir().unwrap();
     let test_suites = TestSuite::<ErrorTest>::read("fixtures/errors");
 
@@ -43,22 +49,32 @@ pub(crate) fn run_errors_test(
                 "queries/test.sql",
                 test.query.as_deref().unwrap_or_default(),
             )?;
+            if let Some(embedded) = &test.embedded {
+                std::fs::write("queries/embedded.rs", embedded)?;
+            }
+            for (name, module) in &test.modules {
+                std::fs::write(format!("queries/{name}.sql"), module)?;
+            }
+            if let Some(recorded) = &test.snapshot {
+                std::fs::create_dir("recorded")?;
+                std::fs::write("recorded/test.sql", recorded)?;
+            }
 
             // Run codegen
-            let result = cornucopia::load_schema(client, &["schema.sql"])
-                .map_err(Error::from)
-                .and_then(|_| {
-                    cornucopia::generate_live(
-                        client,
-                        "queries",
-                        None,
-                        CodegenSettings::from(&*test),
-                    )
-                });
-
-            let err = result.unwrap_err().report();
+            let result = cornucopia::load_schema_with_observer(client, &["schema.sql"], &mut ())
+                .map_err(Error::from);
+            let err = match (result, external) {
+                (Err(err), _) => err.report(),
+                (Ok(()), Some(external)) => run_external(external, test),
+                (Ok(()), None) => run_library(client, test).unwrap_err().report(),
+            };
             let err_trimmed = err.trim();
-            if err_trimmed == test.error.trim() {
+            let successful = match external {
+                Some(_) => test.matches_wrapped(&err),
+                None => test.matches(&err),
+            };
+            summary.record(successful);
+            if successful {
                 println!("{} {}", test.name, "OK".green());
             } else {
                 let got_msg = if apply {
@@ -71,28 +87,76 @@ pub(crate) fn run_errors_test(
                 } else {
                     "Expected:".bright_black()
                 };
-                successful = false;
                 println!(
                     "{} {}\n{}\n{}\n{}\n{}\n",
                     test.name,
                     "ERR".red(),
                     expected_msg,
-                    test.error,
+                    test.expected(),
                     got_msg,
                     err,
                 );
             }
-            if apply {
+            // Errors reported by the binary are wrapped, and never recorded
+            if apply && external.is_none() && test.is_exact() {
                 test.error = err_trimmed.into();
             }
             std::env::set_current_dir(&original_pwd)?;
+            if fail_fast && !successful {
+                break;
+            }
         }
 
         // Update error message if needed
         if apply {
             suite.write()?;
         }
+        if fail_fast && !summary.is_successful() {
+            break;
+        }
+    }
+
+    Ok(summary)
+}
+
+/// Generates the queries of the test, recording a snapshot first if it has one
+fn run_library(client: &mut postgres::Client, test: &ErrorTest) -> Result<String, Error> {
+    if test.snapshot.is_some() {
+        // Record the snapshot, then generate the queries offline
+        cornucopia::snapshot_live_with_observer(client, "recorded", "snapshot.json", &mut ())?;
+        cornucopia::generate_offline_with_observer(
+            "queries",
+            "snapshot.json",
+            test.destination.as_deref(),
+            CodegenSettings::from(test),
+            &mut (),
+        )
+    } else {
+        cornucopia::generate_live_with_observer(
+            client,
+            "queries",
+            test.destination.as_deref(),
+            CodegenSettings::from(test),
+            &mut (),
+        )
     }
+}
 
-    Ok(successful)
+/// Generates the queries of the test using the external binary, returning the error it
+/// reports, empty if it succeeds
+fn run_external(external: &ExternalCli, test: &ErrorTest) -> String {
+    let settings = test.cli_settings();
+    // The CLI always writes the generated code
+    let destination = Path::new(test.destination.as_deref().unwrap_or("cornucopia.rs"));
+    let result = if test.snapshot.is_some() {
+        external
+            .snapshot(&settings, Path::new("recorded"), Path::new("snapshot.json"))
+            .and_then(|()| {
+                let snapshot = Path::new("snapshot.json");
+                external.offline(&settings, Path::new("queries"), snapshot, destination)
+            })
+    } else {
+        external.live(&settings, Path::new("queries"), destination)
+    };
+    result.err().unwrap_or_default()
 }
@@ -1,11 +1,17 @@
 use std::{
+    collections::BTreeMap,
     error::Error,
     path::{Path, PathBuf},
 };
 
-use cornucopia::CodegenSettings;
+use clap::ValueEnum;
+use cornucopia::{
+    CodegenSettings, CodegenSettingsBuilder, DateTimeCrate, NaiveTimestampPolicy, NumericCrate,
+};
 use serde::{de::DeserializeOwned, Deserialize, Serialize};
 
+use crate::external::{CliConfig, CliSettings};
+
 #[derive(Serialize, Deserialize)]
 struct TestSuiteSerde<T> {
     #[serde(rename = "test")]
@@ -57,10 +63,68 @@ pub(crate) struct CodegenTest {
     pub(crate) sync: bool,
     #[serde(default)]
     pub(crate) r#async: bool,
+    /// Structs deriving `Serialize`, as a comma separated list of targets
+    #[serde(default)]
+    pub(crate) serialize: String,
+    /// Structs deriving `Deserialize`, as a comma separated list of targets
+    #[serde(default)]
+    pub(crate) deserialize: String,
+    #[serde(default)]
+    pub(crate) read_only_flag: bool,
+    #[serde(default)]
+    pub(crate) offline: bool,
+    #[serde(default)]
+    pub(crate) normalize_sql: bool,
+    #[serde(default)]
+    pub(crate) row_conversions: bool,
+    #[serde(default)]
+    pub(crate) params_builders: bool,
+    #[serde(default)]
+    pub(crate) param_conversions: bool,
+    /// Whether every variant of the queries is generated, selected at runtime
+    #[serde(default)]
+    pub(crate) all_variants: bool,
+    #[serde(default)]
+    pub(crate) two_phase_commit: bool,
     #[serde(default)]
-    pub(crate) derive_ser: bool,
+    pub(crate) enum_guards: bool,
+    #[serde(default)]
+    pub(crate) display: bool,
+    /// Whether queries are prepared, `true` if unset
+    pub(crate) prepared_statements: Option<bool>,
+    /// Path of the shared custom types referenced by the generated queries
+    pub(crate) types_path: Option<String>,
+    /// File the shared custom types are generated in
+    pub(crate) types_destination: Option<PathBuf>,
+    /// Path of the error type returned by the generated queries
+    pub(crate) error_type: Option<String>,
+    /// Directory of the generated benches, containing their `benches.toml` fixtures
+    pub(crate) benches: Option<PathBuf>,
+    /// Directory of the migrations embedded in the generated code, loaded instead of
+    /// `schema.sql`. The database is emptied before running the test, which applies them.
+    pub(crate) migrations: Option<PathBuf>,
+    pub(crate) naive_timestamp_policy: Option<String>,
+    /// Crate of the date and time types, `time` if unset
+    pub(crate) date_time_crate: Option<String>,
+    /// Crate of the `numeric` type, `rust_decimal` if unset
+    pub(crate) numeric_crate: Option<String>,
+    /// Newtypes by `table.column` or domain name
+    #[serde(default)]
+    pub(crate) newtypes: BTreeMap<String, String>,
+    /// Patterns of the fields hidden from `Debug` output
+    #[serde(default)]
+    pub(crate) redact: Vec<String>,
     #[serde(default)]
     pub(crate) run: bool,
+    /// Whether the queries are expanded by the `cornucopia_macros` attribute when building
+    /// the project against the database of the fixtures, instead of being generated
+    #[serde(default)]
+    pub(crate) macros: bool,
+    /// Cargo features enabled when running the test
+    #[serde(default)]
+    pub(crate) features: Vec<String>,
+    /// Ceiling of the wall-clock duration of the run, excluding its build, unlimited if unset
+    pub(crate) max_run_seconds: Option<f64>,
 }
 
 fn default_queries_path() -> PathBuf {
@@ -73,10 +137,118 @@ fn default_destination_path() -> PathBuf {
 
 impl From<&CodegenTest> for CodegenSettings {
     fn from(codegen_test: &CodegenTest) -> Self {
-        Self {
-            gen_async: codegen_test.r#async || !codegen_test.sync,
-            gen_sync: codegen_test.sync,
-            derive_ser: codegen_test.derive_ser,
+        codegen_test.settings_builder().build()
+    }
+}
+
+impl CodegenTest {
+    /// Settings of the test, to which the benches directory can still be added
+    pub(crate) fn settings_builder(&self) -> CodegenSettingsBuilder {
+        let builder = CodegenSettings::builder()
+            .async_(self.r#async || !self.sync)
+            .sync(self.sync)
+            .serialize(self.serialize.parse().unwrap())
+            .deserialize(self.deserialize.parse().unwrap())
+            .generate_read_only_flag(self.read_only_flag)
+            .normalize_sql(self.normalize_sql)
+            .row_conversions(self.row_conversions)
+            .params_builders(self.params_builders)
+            .param_conversions(self.param_conversions)
+            .all_variants(self.all_variants)
+            .two_phase_commit(self.two_phase_commit)
+            .enum_guards(self.enum_guards)
+            .display(self.display)
+            .prepared_statements(self.prepared_statements.unwrap_or(true))
+            .naive_timestamp_policy(naive_timestamp_policy(&self.naive_timestamp_policy))
+            .date_time_crate(
+                self.date_time_crate
+                    .as_deref()
+                    .map_or(DateTimeCrate::default(), |it| {
+                        DateTimeCrate::from_str(it, false).unwrap()
+                    }),
+            )
+            .numeric_crate(
+                self.numeric_crate
+                    .as_deref()
+                    .map_or(NumericCrate::default(), |it| {
+                        NumericCrate::from_str(it, false).unwrap()
+                    }),
+            );
+        let builder = self
+            .redact
+            .iter()
+            .fold(builder, |builder, pattern| builder.redact(pattern));
+        let builder = match &self.types_path {
+            Some(types_path) => builder.types_path(types_path),
+            None => builder,
+        };
+        let builder = match &self.error_type {
+            Some(error_type) => builder.error_type(error_type),
+            None => builder,
+        };
+        let builder = match &self.migrations {
+            Some(dir) => builder.embed_migrations(dir),
+            None => builder,
+        };
+        newtypes(builder, &self.newtypes)
+    }
+
+    /// Settings of the test as CLI args, to which the benches directory can still be added
+    pub(crate) fn cli_settings(&self) -> CliSettings {
+        let mut args = Vec::new();
+        let flags = [
+            ("--async", self.r#async || !self.sync),
+            ("--sync", self.sync),
+            ("--read-only-flag", self.read_only_flag),
+            ("--normalize-sql", self.normalize_sql),
+            ("--row-conversions", self.row_conversions),
+            ("--params-builders", self.params_builders),
+            ("--param-conversions", self.param_conversions),
+            ("--all-variants", self.all_variants),
+            ("--two-phase-commit", self.two_phase_commit),
+            ("--enum-guards", self.enum_guards),
+            ("--display", self.display),
+            (
+                "--no-prepared-statements",
+                self.prepared_statements == Some(false),
+            ),
+        ];
+        args.extend(
+            flags
+                .iter()
+                .filter(|(_, it)| *it)
+                .map(|(flag, _)| flag.to_string()),
+        );
+        if !self.serialize.is_empty() {
+            args.push(format!("--serialize={}", self.serialize));
+        }
+        if !self.deserialize.is_empty() {
+            args.push(format!("--deserialize={}", self.deserialize));
+        }
+        if let Some(policy) = &self.naive_timestamp_policy {
+            args.push(format!("--naive-timestamp-policy={policy}"));
+        }
+        if let Some(date_time_crate) = &self.date_time_crate {
+            args.push(format!("--date-time-crate={date_time_crate}"));
+        }
+        if let Some(numeric_crate) = &self.numeric_crate {
+            args.push(format!("--numeric-crate={numeric_crate}"));
+        }
+        if let Some(types_path) = &self.types_path {
+            args.push(format!("--types-path={types_path}"));
+        }
+        if let Some(error_type) = &self.error_type {
+            args.push(format!("--error-type={error_type}"));
+        }
+        if let Some(dir) = &self.migrations {
+            args.push(format!("--embed-migrations={}", dir.display()));
+        }
+        CliSettings {
+            args,
+            config: CliConfig {
+                newtypes: self.newtypes.clone(),
+                redact: self.redact.clone(),
+            },
         }
     }
 }
@@ -86,16 +258,278 @@ impl From<&CodegenTest> for CodegenSettings {
 pub(crate) struct ErrorTest {
     pub(crate) name: String,
     pub(crate) query: Option<String>,
+    /// Rust file embedding queries, written next to `query`
+    pub(crate) embedded: Option<String>,
     pub(crate) schema: Option<String>,
+    /// Queries recorded in a snapshot, `query` then being generated offline
+    pub(crate) snapshot: Option<String>,
+    pub(crate) naive_timestamp_policy: Option<String>,
+    pub(crate) prepared_statements: Option<bool>,
+    /// File the generated code is written to, relative to the test directory
+    pub(crate) destination: Option<String>,
+    pub(crate) create_destination_dirs: Option<bool>,
+    pub(crate) normalize_sql: Option<bool>,
+    pub(crate) strict_nullability: Option<bool>,
+    pub(crate) params_builders: Option<bool>,
+    pub(crate) keep_going: Option<bool>,
+    /// Roles the queries are prepared as
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub(crate) roles: Vec<String>,
+    /// Expected error, compared to the reported one once both are trimmed
+    #[serde(default, skip_serializing_if = "String::is_empty")]
     pub(crate) error: String,
+    /// Snippet the reported error must contain, ignoring case, instead of `error`
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub(crate) error_contains: Option<String>,
+    /// Pattern the reported error must match, instead of `error` and `error_contains`
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub(crate) error_regex: Option<String>,
+    /// Newtypes by `table.column` or domain name
+    #[serde(default, skip_serializing_if = "BTreeMap::is_empty")]
+    pub(crate) newtypes: BTreeMap<String, String>,
+    /// Other query modules by name, written next to `query`
+    #[serde(default, skip_serializing_if = "BTreeMap::is_empty")]
+    pub(crate) modules: BTreeMap<String, String>,
 }
 
-impl From<&ErrorTest> for CodegenSettings {
-    fn from(_error_test: &ErrorTest) -> Self {
-        Self {
-            derive_ser: false,
-            gen_async: false,
-            gen_sync: true,
+impl ErrorTest {
+    /// Whether the reported error matches the first matcher present among `error_regex`,
+    /// `error_contains` and `error`, the others being ignored
+    pub(crate) fn matches(&self, err: &str) -> bool {
+        if let Some(pattern) = &self.error_regex {
+            let regex = regex::Regex::new(pattern)
+                .unwrap_or_else(|e| panic!("invalid `error_regex` of {}: {e}", self.name));
+            regex.is_match(err)
+        } else if let Some(snippet) = &self.error_contains {
+            err.to_lowercase().contains(&snippet.trim().to_lowercase())
+        } else {
+            err.trim() == self.error.trim()
+        }
+    }
+
+    /// Like [`Self::matches`], for an error reported by a binary wrapping its lines to the
+    /// width of the terminal: both errors are compared without their whitespace and the
+    /// gutters of their wrapped lines
+    pub(crate) fn matches_wrapped(&self, err: &str) -> bool {
+        let unwrapped = |it: &str| -> String {
+            it.chars()
+                .filter(|c| !c.is_whitespace() && *c != '│')
+                .collect()
+        };
+        if self.error_regex.is_some() {
+            self.matches(err)
+        } else if let Some(snippet) = &self.error_contains {
+            unwrapped(&err.to_lowercase()).contains(&unwrapped(&snippet.to_lowercase()))
+        } else {
+            unwrapped(err) == unwrapped(&self.error)
         }
     }
+
+    /// Description of the expected error, using its first matcher
+    pub(crate) fn expected(&self) -> String {
+        if let Some(pattern) = &self.error_regex {
+            format!("matching /{pattern}/")
+        } else if let Some(snippet) = &self.error_contains {
+            format!("containing `{}`", snippet.trim())
+        } else {
+            self.error.clone()
+        }
+    }
+
+    /// Settings of the test as CLI args
+    pub(crate) fn cli_settings(&self) -> CliSettings {
+        let mut args = vec!["--sync".to_string()];
+        let flags = [
+            (
+                "--no-prepared-statements",
+                self.prepared_statements == Some(false),
+            ),
+            (
+                "--no-create-dirs",
+                self.create_destination_dirs == Some(false),
+            ),
+            ("--normalize-sql", self.normalize_sql == Some(true)),
+            (
+                "--strict-nullability",
+                self.strict_nullability == Some(true),
+            ),
+            ("--params-builders", self.params_builders == Some(true)),
+            ("--keep-going", self.keep_going == Some(true)),
+        ];
+        args.extend(
+            flags
+                .iter()
+                .filter(|(_, it)| *it)
+                .map(|(flag, _)| flag.to_string()),
+        );
+        if let Some(policy) = &self.naive_timestamp_policy {
+            args.push(format!("--naive-timestamp-policy={policy}"));
+        }
+        if !self.roles.is_empty() {
+            args.push(format!("--roles={}", self.roles.join(",")));
+        }
+        CliSettings {
+            args,
+            config: CliConfig {
+                newtypes: self.newtypes.clone(),
+                redact: Vec::new(),
+            },
+        }
+    }
+
+    /// Whether `--apply-errors` can record the reported error, errors expected using
+    /// another matcher being left untouched
+    pub(crate) fn is_exact(&self) -> bool {
+        self.error_regex.is_none() && self.error_contains.is_none()
+    }
+}
+
+/// CLI test case, run from a directory holding its files
+#[derive(Debug, Deserialize, Serialize)]
+pub(crate) struct CliTest {
+    pub(crate) name: String,
+    /// Args of the CLI, following the name of the binary
+    pub(crate) args: Vec<String>,
+    /// Schema loaded in the database before running the CLI
+    #[serde(default, skip_serializing_if = "String::is_empty")]
+    pub(crate) schema: String,
+    /// Contents by path relative to the test directory, like `.env` or `queries/test.sql`
+    #[serde(default)]
+    pub(crate) files: BTreeMap<String, String>,
+    /// Environment variables set when running the CLI
+    #[serde(default, skip_serializing_if = "BTreeMap::is_empty")]
+    pub(crate) env: BTreeMap<String, String>,
+    /// Directory the CLI is run from, relative to the test directory
+    pub(crate) cwd: Option<String>,
+    /// File the CLI must generate, relative to `cwd`
+    pub(crate) generated: Option<String>,
+    /// Snippets the generated file must contain
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub(crate) contains: Vec<String>,
+    /// Snippets the generated file must not contain
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub(crate) excludes: Vec<String>,
+    /// Expected error, empty when the CLI must succeed
+    #[serde(default)]
+    pub(crate) error: String,
+}
+
+/// Progress events test case, whose query modules are generated live
+#[derive(Debug, Deserialize)]
+pub(crate) struct ProgressTest {
+    pub(crate) name: String,
+    pub(crate) schema: Option<String>,
+    /// Query modules by name
+    pub(crate) modules: BTreeMap<String, String>,
+    pub(crate) naive_timestamp_policy: Option<String>,
+    #[serde(default)]
+    pub(crate) merge_nullability: bool,
+    /// Content of the nullability file of the test, if any
+    pub(crate) nullability: Option<String>,
+    /// Query modules rewritten before generating the code a second time, incrementally,
+    /// the events of both generations being expected
+    pub(crate) rerun: Option<BTreeMap<String, String>>,
+    /// Expected events, in order
+    pub(crate) events: Vec<String>,
+}
+
+impl From<&ProgressTest> for CodegenSettings {
+    fn from(progress_test: &ProgressTest) -> Self {
+        let builder = CodegenSettings::builder()
+            .naive_timestamp_policy(naive_timestamp_policy(
+                &progress_test.naive_timestamp_policy,
+            ))
+            .merge_nullability(progress_test.merge_nullability)
+            .incremental(progress_test.rerun.is_some());
+        match progress_test.nullability {
+            Some(_) => builder.nullability_file(NULLABILITY_FILE),
+            None => builder,
+        }
+        .build()
+    }
+}
+
+/// File the nullability of a progress test is written to, in its directory
+pub(crate) const NULLABILITY_FILE: &str = "nullability.toml";
+
+/// Column coverage test case, whose query modules are checked live
+#[derive(Debug, Deserialize)]
+pub(crate) struct CoverageTest {
+    pub(crate) name: String,
+    pub(crate) schema: String,
+    /// Query modules by name
+    pub(crate) modules: BTreeMap<String, String>,
+    #[serde(default)]
+    pub(crate) include_views: bool,
+    /// `key=value` tags of the checked queries
+    #[serde(default)]
+    pub(crate) only_tags: Vec<String>,
+    #[serde(default)]
+    pub(crate) require_budgets: bool,
+    /// Expected tables, as `schema.table: columns` or `schema.table: no query (columns)`
+    pub(crate) tables: Vec<String>,
+    /// Expected human-readable report, if checked
+    pub(crate) report: Option<String>,
+}
+
+/// Standalone query validation test case
+#[derive(Debug, Deserialize)]
+pub(crate) struct ValidateTest {
+    pub(crate) name: String,
+    pub(crate) sql: String,
+    pub(crate) schema: Option<String>,
+    #[serde(default)]
+    pub(crate) nullable: Vec<String>,
+    #[serde(default)]
+    pub(crate) params: Vec<String>,
+    #[serde(default)]
+    pub(crate) columns: Vec<(String, String, bool)>,
+    pub(crate) error: Option<String>,
+}
+
+/// Query file formatting test case
+#[derive(Debug, Deserialize)]
+pub(crate) struct FormatTest {
+    pub(crate) name: String,
+    /// Query file to format
+    pub(crate) input: String,
+    /// Expected formatted file, which must be left unchanged when formatted again
+    pub(crate) expected: String,
+    /// Whether the SQL is also reflowed
+    #[serde(default)]
+    pub(crate) sql: bool,
+}
+
+impl From<&ErrorTest> for CodegenSettings {
+    fn from(error_test: &ErrorTest) -> Self {
+        let builder = CodegenSettings::builder()
+            .async_(false)
+            .sync(true)
+            .naive_timestamp_policy(naive_timestamp_policy(&error_test.naive_timestamp_policy))
+            .prepared_statements(error_test.prepared_statements.unwrap_or(true))
+            .create_destination_dirs(error_test.create_destination_dirs.unwrap_or(true))
+            .normalize_sql(error_test.normalize_sql.unwrap_or(false))
+            .strict_nullability(error_test.strict_nullability.unwrap_or(false))
+            .params_builders(error_test.params_builders.unwrap_or(false))
+            .keep_going(error_test.keep_going.unwrap_or(false))
+            .roles(error_test.roles.iter().cloned());
+        newtypes(builder, &error_test.newtypes).build()
+    }
+}
+
+fn newtypes(
+    builder: CodegenSettingsBuilder,
+    newtypes: &BTreeMap<String, String>,
+) -> CodegenSettingsBuilder {
+    newtypes.iter().fold(builder, |builder, (target, path)| {
+        builder.newtype(target, path)
+    })
+}
+
+fn naive_timestamp_policy(policy: &Option<String>) -> NaiveTimestampPolicy {
+    policy
+        .as_deref()
+        .map_or(NaiveTimestampPolicy::default(), |it| {
+            NaiveTimestampPolicy::from_str(it, false).unwrap()
+        })
 }
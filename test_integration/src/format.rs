@@ -0,0 +1,63 @@
+use cornucopia::FormatSettings;
+use owo_colors::OwoColorize;
+
+use crate::{
+    fixtures::{FormatTest, TestSuite},
+    utils::Summary,
+};
+
+/// Run format test, stopping at the first failure if `fail_fast`
+pub(crate) fn run_format_test(fail_fast: bool) -> Result<Summary, Box<dyn std::error::Error>> {
+    let mut summary = Summary::default();
+    let test_suites = TestSuite::<FormatTest>::read("fixtures/format");
+
+    'suites: for suite in test_suites {
+        println!("{} {}", "[format]".magenta(), suite.name.magenta());
+        for test in suite.tests {
+            let temp_dir = tempfile::tempdir()?;
+            let path = temp_dir.path().join("test.sql");
+            std::fs::write(&path, &test.input)?;
+
+            let format = |check| {
+                cornucopia::format_queries(
+                    temp_dir.path(),
+                    &FormatSettings {
+                        check,
+                        sql: test.sql,
+                    },
+                )
+            };
+            let got = match format(false) {
+                Ok(_) => std::fs::read_to_string(&path)?,
+                Err(err) => err.report(),
+            };
+            // Formatting is idempotent
+            let unstable = format(true).is_ok_and(|files| !files.is_empty());
+            let successful = got == test.expected && !unstable;
+            summary.record(successful);
+            if successful {
+                println!("{} {}", test.name, "OK".green());
+            } else {
+                println!(
+                    "{} {}\n{}\n{}\n{}\n{}\n{}\n",
+                    test.name,
+                    "ERR".red(),
+                    "Expected:".bright_black(),
+                    test.expected,
+                    "Got:".bright_black(),
+                    got,
+                    if unstable {
+                        "Formatting again changed the file"
+                    } else {
+                        ""
+                    },
+                );
+                if fail_fast {
+                    break 'suites;
+                }
+            }
+        }
+    }
+
+    Ok(summary)
+}
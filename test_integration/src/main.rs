@@ -1,13 +1,24 @@
-use std::{fmt::Display, process::ExitCode};
+use std::{fmt::Display, path::PathBuf, process::ExitCode};
 
-use crate::{codegen::run_codegen_test, errors::run_errors_test};
+use crate::{
+    cli::run_cli_test, codegen::run_codegen_test, coverage::run_coverage_test,
+    errors::run_errors_test, external::ExternalCli, format::run_format_test,
+    progress::run_progress_test, validate::run_validate_test,
+};
 use clap::Parser;
 use cornucopia::container;
+use owo_colors::OwoColorize;
 
+mod cli;
 mod codegen;
+mod coverage;
 mod errors;
+mod external;
 mod fixtures;
+mod format;
+mod progress;
 mod utils;
+mod validate;
 
 /// Integration test CLI arguments
 #[derive(Parser, Debug)]
@@ -22,6 +33,13 @@ struct Args {
     /// Use `podman` instead of `docker`
     #[clap(short, long)]
     podman: bool,
+    /// Stop at the first failing fixture
+    #[clap(long)]
+    fail_fast: bool,
+    /// Run the codegen and error fixtures through this cornucopia binary instead of the
+    /// library, e.g. to bisect a regression using the builds of several commits
+    #[clap(long, value_name = "PATH")]
+    cornucopia_bin: Option<PathBuf>,
 }
 
 /// Print error to stderr
@@ -38,18 +56,63 @@ fn test(
         apply_errors,
         apply_codegen,
         podman,
+        fail_fast,
+        cornucopia_bin,
     }: Args,
 ) -> bool {
+    let external = cornucopia_bin.map(ExternalCli::new);
     // Start by removing previous container if it was left open
     container::cleanup(podman).ok();
     container::setup(podman).unwrap();
-    let successful = std::panic::catch_unwind(|| {
+    let summary = std::panic::catch_unwind(|| {
         let mut client = cornucopia::conn::cornucopia_conn().unwrap();
-        display(run_errors_test(&mut client, apply_errors)).unwrap()
-            && display(run_codegen_test(&mut client, apply_codegen)).unwrap()
+        let external = external.as_ref();
+        let mut summary = display(run_errors_test(
+            &mut client,
+            external,
+            apply_errors,
+            fail_fast,
+        ))
+        .unwrap();
+        if !fail_fast || summary.is_successful() {
+            summary += display(run_validate_test(&mut client, fail_fast)).unwrap();
+        }
+        if !fail_fast || summary.is_successful() {
+            summary += display(run_format_test(fail_fast)).unwrap();
+        }
+        if !fail_fast || summary.is_successful() {
+            summary += display(run_cli_test(&mut client, apply_errors, fail_fast)).unwrap();
+        }
+        if !fail_fast || summary.is_successful() {
+            summary += display(run_progress_test(&mut client, fail_fast)).unwrap();
+        }
+        if !fail_fast || summary.is_successful() {
+            summary += display(run_coverage_test(&mut client, fail_fast)).unwrap();
+        }
+        if !fail_fast || summary.is_successful() {
+            summary += display(run_codegen_test(
+                &mut client,
+                external,
+                apply_codegen,
+                fail_fast,
+            ))
+            .unwrap();
+        }
+        summary
     });
     container::cleanup(podman).unwrap();
-    successful.unwrap()
+    let summary = summary.unwrap();
+    let failed = format!("{} failed", summary.failed);
+    println!(
+        "fixtures: {}, {} passed",
+        if summary.is_successful() {
+            failed.green().to_string()
+        } else {
+            failed.red().to_string()
+        },
+        summary.passed
+    );
+    summary.is_successful()
 }
 
 /// Main entry point
@@ -71,7 +134,24 @@ mod test {
         assert!(test(crate::Args {
             apply_errors: false,
             apply_codegen: false,
-            podman: false
+            podman: false,
+            fail_fast: false,
+            cornucopia_bin: None,
+        }))
+    }
+
+    /// Runs the fixtures through the binary given in `CORNUCOPIA_BIN`, which must generate
+    /// the same code and report the same errors as the library
+    #[test]
+    #[ignore = "needs a cornucopia binary in `CORNUCOPIA_BIN`"]
+    fn run_external() {
+        let bin = std::env::var_os("CORNUCOPIA_BIN").expect("`CORNUCOPIA_BIN` is not set");
+        assert!(test(crate::Args {
+            apply_errors: false,
+            apply_codegen: false,
+            podman: false,
+            fail_fast: false,
+            cornucopia_bin: Some(bin.into()),
         }))
     }
 }
@@ -0,0 +1,144 @@
+use cornucopia::{CodegenSettings, ProgressEvent, ProgressObserver};
+use owo_colors::OwoColorize;
+
+use crate::{
+    fixtures::{ProgressTest, TestSuite, NULLABILITY_FILE},
+    utils::{reset_db, Summary},
+};
+
+/// Records the events of code generation as lines comparable to the fixtures
+#[derive(Default)]
+struct RecordingObserver {
+    events: Vec<String>,
+}
+
+impl ProgressObserver for RecordingObserver {
+    fn on_event(&mut self, event: ProgressEvent) {
+        let line = match event {
+            ProgressEvent::ContainerStarting => "container starting".to_string(),
+            ProgressEvent::ContainerSlow { .. } => "container slow".to_string(),
+            ProgressEvent::ContainerRetry { .. } => "container retry".to_string(),
+            ProgressEvent::SchemaLoaded(path) => format!("schema loaded `{}`", path.display()),
+            ProgressEvent::ModulePrepared { name, queries } => {
+                format!("module `{name}` prepared ({queries} queries)")
+            }
+            ProgressEvent::ModuleCached { name, queries } => {
+                format!("module `{name}` cached ({queries} queries)")
+            }
+            // The size of the generated code changes with codegen, only its path is checked
+            ProgressEvent::CodegenWritten { path, .. } => {
+                format!("codegen written `{}`", path.display())
+            }
+            ProgressEvent::WarningEmitted(warning) => format!("warning: {warning}"),
+            _ => unreachable!("unknown progress event"),
+        };
+        self.events.push(line);
+    }
+}
+
+/// Run progress test, stopping at the first failure if `fail_fast`
+pub(crate) fn run_progress_test(
+    client: &mut postgres::Client,
+    fail_fast: bool,
+) -> Result<Summary, Box<dyn std::error::Error>> {
+    let mut summary = Summary::default();
+    let original_pwd = std::env::current_dir().unwrap();
+    let test_suites = TestSuite::<ProgressTest>::read("fixtures/progress");
+
+    'suites: for suite in test_suites {
+        println!("{} {}", "[progress]".magenta(), suite.name.magenta());
+        for test in suite.tests {
+            // Reset db
+            reset_db(client)?;
+
+            // Generate file tree, paths being reported relatively to it
+            let temp_dir = tempfile::tempdir()?;
+            std::env::set_current_dir(&temp_dir)?;
+            std::fs::write(
+                "schema.sql",
+                [
+                    "CREATE TABLE author (id SERIAL, name TEXT);\n",
+                    test.schema.as_deref().unwrap_or_default(),
+                ]
+                .concat(),
+            )?;
+            std::fs::create_dir("queries")?;
+            for (name, module) in &test.modules {
+                std::fs::write(format!("queries/{name}.sql"), module)?;
+            }
+            if let Some(nullability) = &test.nullability {
+                std::fs::write(NULLABILITY_FILE, nullability)?;
+            }
+
+            // Run codegen
+            let mut observer = RecordingObserver::default();
+            let result =
+                cornucopia::load_schema_with_observer(client, &["schema.sql"], &mut observer)
+                    .map_err(cornucopia::Error::from)
+                    .and_then(|_| {
+                        cornucopia::generate_live_with_observer(
+                            client,
+                            "queries",
+                            Some("cornucopia.rs"),
+                            CodegenSettings::from(&test),
+                            &mut observer,
+                        )
+                    })
+                    .and_then(|code| {
+                        // Incremental tests generate the code again once their modules are rewritten
+                        let Some(modules) = &test.rerun else {
+                            return Ok(code);
+                        };
+                        for (name, module) in modules {
+                            std::fs::write(format!("queries/{name}.sql"), module).unwrap();
+                        }
+                        let code = cornucopia::generate_live_with_observer(
+                            client,
+                            "queries",
+                            Some("cornucopia.rs"),
+                            CodegenSettings::from(&test),
+                            &mut observer,
+                        )?;
+                        let full = cornucopia::generate_live_with_observer(
+                            client,
+                            "queries",
+                            None,
+                            CodegenSettings::from(&test),
+                            &mut (),
+                        )?;
+                        if code != full {
+                            observer
+                                .events
+                                .push("incremental codegen differs from a full one".to_string());
+                        }
+                        Ok(code)
+                    });
+            std::env::set_current_dir(&original_pwd)?;
+
+            let expected = test.events.join("\n");
+            let got = match result {
+                Ok(_) => observer.events.join("\n"),
+                Err(err) => err.report(),
+            };
+            summary.record(expected == got);
+            if expected == got {
+                println!("{} {}", test.name, "OK".green());
+            } else {
+                println!(
+                    "{} {}\n{}\n{}\n{}\n{}\n",
+                    test.name,
+                    "ERR".red(),
+                    "Expected:".bright_black(),
+                    expected,
+                    "Got:".bright_black(),
+                    got,
+                );
+                if fail_fast {
+                    break 'suites;
+                }
+            }
+        }
+    }
+
+    Ok(summary)
+}
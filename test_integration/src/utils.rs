@@ -1,14 +1,57 @@
 use std::{
     io::Write,
+    ops::AddAssign,
     path::Path,
     process::{Command, Stdio},
 };
 
-/// Reset the current database
+/// Number of passed and failed fixtures
+#[derive(Debug, Default, Clone, Copy)]
+pub(crate) struct Summary {
+    pub(crate) passed: usize,
+    pub(crate) failed: usize,
+}
+
+impl Summary {
+    pub(crate) fn record(&mut self, successful: bool) {
+        if successful {
+            self.passed += 1;
+        } else {
+            self.failed += 1;
+        }
+    }
+
+    pub(crate) fn is_successful(&self) -> bool {
+        self.failed == 0
+    }
+}
+
+impl AddAssign for Summary {
+    fn add_assign(&mut self, rhs: Self) {
+        self.passed += rhs.passed;
+        self.failed += rhs.failed;
+    }
+}
+
+/// Reset the current database, dropping the schemas created by the previous tests
 pub(crate) fn reset_db(client: &mut postgres::Client) -> Result<(), postgres::Error> {
+    drop_schemas(client)?;
     client.batch_execute("DROP SCHEMA public CASCADE;CREATE SCHEMA public;")
 }
 
+/// Drops the schemas other than `public` and those of the system
+fn drop_schemas(client: &mut postgres::Client) -> Result<(), postgres::Error> {
+    let schemas = client.query(
+        "SELECT quote_ident(nspname) FROM pg_catalog.pg_namespace
+        WHERE nspname NOT IN ('public', 'information_schema') AND nspname NOT LIKE 'pg\\_%'",
+        &[],
+    )?;
+    for row in schemas {
+        client.batch_execute(&format!("DROP SCHEMA {} CASCADE", row.get::<_, String>(0)))?;
+    }
+    Ok(())
+}
+
 pub(crate) fn rustfmt_file(path: &Path) {
     Command::new("rustfmt")
         .args(["--edition", "2021"])
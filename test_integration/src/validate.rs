@@ -0,0 +1,77 @@
+use cornucopia::{ValidatedQuery, ValidationSettings};
+use owo_colors::OwoColorize;
+
+use crate::{
+    fixtures::{TestSuite, ValidateTest},
+    utils::{reset_db, Summary},
+};
+
+/// Run validate test, stopping at the first failure if `fail_fast`
+pub(crate) fn run_validate_test(
+    client: &mut postgres::Client,
+    fail_fast: bool,
+) -> Result<Summary, Box<dyn std::error::Error>> {
+    let mut summary = Summary::default();
+    let test_suites = TestSuite::<ValidateTest>::read("fixtures/validate");
+
+    'suites: for suite in test_suites {
+        println!("{} {}", "[validate]".magenta(), suite.name.magenta());
+        for test in suite.tests {
+            // Reset db
+            reset_db(client)?;
+            client.batch_execute(
+                &[
+                    "CREATE TABLE author (id SERIAL, name TEXT);\n",
+                    test.schema.as_deref().unwrap_or_default(),
+                ]
+                .concat(),
+            )?;
+
+            let result = cornucopia::validate_query_against_schema(
+                client,
+                &test.sql,
+                &ValidationSettings {
+                    nullable_columns: test.nullable.clone(),
+                },
+            );
+            let (expected, got) = match (&test.error, result) {
+                (Some(error), Err(err)) => (error.trim().to_string(), err.report().trim().into()),
+                (Some(error), Ok(validated)) => {
+                    (error.trim().to_string(), format!("{validated:?}"))
+                }
+                (None, result) => (
+                    format!(
+                        "{:?}",
+                        ValidatedQuery {
+                            param_types: test.params,
+                            column_types: test.columns,
+                        }
+                    ),
+                    match result {
+                        Ok(validated) => format!("{validated:?}"),
+                        Err(err) => err.report(),
+                    },
+                ),
+            };
+            summary.record(expected == got);
+            if expected == got {
+                println!("{} {}", test.name, "OK".green());
+            } else {
+                println!(
+                    "{} {}\n{}\n{}\n{}\n{}\n",
+                    test.name,
+                    "ERR".red(),
+                    "Expected:".bright_black(),
+                    expected,
+                    "Got:".bright_black(),
+                    got,
+                );
+                if fail_fast {
+                    break 'suites;
+                }
+            }
+        }
+    }
+
+    Ok(summary)
+}